@@ -1,4 +1,8 @@
-use crate::command::dump::DumpCommand;
+use crate::command::{
+    dump::DumpCommand,
+    import_state::ImportStateCommand,
+    pivot_hint::{PivotHintGenerateCommand, PivotHintVerifyCommand},
+};
 use clap::{Args, Parser, Subcommand, ValueEnum};
 
 /// Conflux client
@@ -260,15 +264,39 @@ pub enum Commands {
     /// Manage accounts
     #[command(subcommand_required = true, arg_required_else_help = true)]
     Account(AccountSubcommands),
-    /// Dump eSpace account state at a given block number
+    /// Dump eSpace and Core Space account state at a given block number
     #[command(subcommand_required = false, arg_required_else_help = false)]
     Dump(DumpCommand),
+    /// Write the state of a dump as the genesis state of a fresh data
+    /// directory
+    #[command(subcommand_required = false, arg_required_else_help = true)]
+    ImportState(ImportStateCommand),
+    /// Generate or verify a pivot hint file with the local pivot chain
+    #[command(subcommand_required = true, arg_required_else_help = true)]
+    PivotHint(PivotHintSubcommands),
     /// RPC based subcommands to query blockchain information and send
     /// transactions
     #[command(subcommand_required = true, arg_required_else_help = true)]
     Rpc(Box<RpcCommand>),
 }
 
+/// Pivot Hint Subcommands
+#[derive(Args, Debug)]
+pub struct PivotHintSubcommands {
+    #[command(subcommand)]
+    pub command: PivotHintCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum PivotHintCommand {
+    /// Write a pivot hint file from the local pivot chain and print its
+    /// checksum.
+    Generate(PivotHintGenerateCommand),
+    /// Verify a pivot hint file against the local pivot chain and report the
+    /// first mismatched page.
+    Verify(PivotHintVerifyCommand),
+}
+
 /// Account Subcommands
 #[derive(Args, Debug)]
 pub struct AccountSubcommands {
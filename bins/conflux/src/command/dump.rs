@@ -1,21 +1,36 @@
-use cfx_types::parse_hex_string;
+use cfx_types::{parse_hex_string, Address, Space};
 use clap::{ArgMatches, Args};
 use client::{
     configuration::Configuration,
-    state_dump::{dump_whole_state, iterate_dump_whole_state, StateDumpConfig},
+    state_dump::{
+        dump_whole_state, iterate_dump_whole_state, stream_dump_whole_state,
+        DumpedAccount, StateDumpConfig, STREAM_FILE_NAME,
+    },
 };
 use parking_lot::{Condvar, Mutex};
+use rustc_hex::FromHex;
 use serde_json;
 use std::{collections::HashMap, fs, path::Path, sync::Arc};
 
 #[derive(Args, Debug)]
 pub struct DumpCommand {
-    /// Include accounts for which we don't have the address (missing preimage)
-    // #[arg(id = "incompletes", long = "incompletes")]
-    // incompletes: bool,
+    /// Include the addresses which have storage entries but no account
+    #[arg(id = "incompletes", long = "incompletes")]
+    incompletes: bool,
     /// Print streaming JSON iteratively, delimited by newlines
-    // #[arg(id = "iterative", long = "iterative", default_value = "true")]
-    // iterative: bool,
+    #[arg(id = "iterative", long = "iterative", conflicts_with = "multifile")]
+    iterative: bool,
+    /// Resume an interrupted iterative dump from its last checkpoint
+    #[arg(id = "resume", long = "resume", requires = "iterative")]
+    resume: bool,
+    /// Number of accounts between checkpoints in iterative mode
+    #[arg(
+        id = "checkpoint-interval",
+        long = "checkpoint-interval",
+        value_name = "NUM",
+        default_value = "10000"
+    )]
+    checkpoint_interval: u64,
     /// Max number of elements (0 = no limit)
     #[arg(
         id = "limit",
@@ -27,6 +42,14 @@ pub struct DumpCommand {
     /// Target block number, if not specified, the latest block will be used
     #[arg(id = "block", long = "block", value_name = "NUM")]
     block: Option<u64>,
+    /// The space to dump: espace, core or all
+    #[arg(
+        id = "space",
+        long = "space",
+        value_name = "SPACE",
+        default_value = "espace"
+    )]
+    space: String,
     /// Exclude contract code (save db lookups)
     #[arg(id = "nocode", long = "nocode")]
     no_code: bool,
@@ -41,6 +64,15 @@ pub struct DumpCommand {
         default_value = "0x0000000000000000000000000000000000000000"
     )]
     start: String,
+    /// Only dump the listed accounts, separated by commas
+    #[arg(id = "addresses", long = "addresses", value_name = "ADDRESSES")]
+    addresses: Option<String>,
+    /// Only dump the accounts listed in the file, one address per line
+    #[arg(id = "address-file", long = "address-file", value_name = "PATH")]
+    address_file: Option<String>,
+    /// Only dump the storage entries whose key starts with the hex prefix
+    #[arg(id = "storage-prefix", long = "storage-prefix", value_name = "HEX")]
+    storage_prefix: Option<String>,
     /// Path to the output folder (default: ./dump)
     #[arg(id = "output", long = "output", value_name = "PATH")]
     output: Option<String>,
@@ -54,14 +86,28 @@ impl DumpCommand {
         let output = matches.get_one::<String>("output").cloned();
         Ok(Self {
             block: matches.get_one::<u64>("block").cloned(),
-            // incompletes: matches.get_flag("incompletes"),
-            // iterative: matches.get_flag("iterative"),
+            incompletes: matches.get_flag("incompletes"),
+            iterative: matches.get_flag("iterative"),
+            resume: matches.get_flag("resume"),
+            checkpoint_interval: matches
+                .get_one::<u64>("checkpoint-interval")
+                .cloned()
+                .unwrap_or(10000),
             limit: matches.get_one::<u64>("limit").cloned().unwrap_or(0),
+            space: matches
+                .get_one::<String>("space")
+                .cloned()
+                .unwrap_or("espace".to_string()),
             no_code: matches.get_flag("nocode"),
             no_storage: matches.get_flag("nostorage"),
             start: matches.get_one::<String>("start").cloned().unwrap_or(
                 "0x0000000000000000000000000000000000000000".to_string(),
             ),
+            addresses: matches.get_one::<String>("addresses").cloned(),
+            address_file: matches.get_one::<String>("address-file").cloned(),
+            storage_prefix: matches
+                .get_one::<String>("storage-prefix")
+                .cloned(),
             output,
             multi_file: matches.get_flag("multifile"),
         })
@@ -72,6 +118,22 @@ impl DumpCommand {
     ) -> Result<StateDumpConfig, String> {
         let start_address = parse_hex_string(&self.start)
             .map_err(|e| format!("Invalid address: {}", e))?;
+        let spaces = match self.space.as_str() {
+            "espace" => vec![Space::Ethereum],
+            "core" => vec![Space::Native],
+            "all" => vec![Space::Native, Space::Ethereum],
+            space => return Err(format!("Invalid space: {}", space)),
+        };
+        let storage_key_prefix = self
+            .storage_prefix
+            .as_ref()
+            .map(|prefix| {
+                prefix
+                    .trim_start_matches("0x")
+                    .from_hex::<Vec<u8>>()
+                    .map_err(|e| format!("Invalid storage prefix: {}", e))
+            })
+            .transpose()?;
         Ok(StateDumpConfig {
             start_address,
             limit: self.limit,
@@ -79,9 +141,40 @@ impl DumpCommand {
             no_code: self.no_code,
             no_storage: self.no_storage,
             out_put_path: output_path.to_string(),
+            spaces,
+            addresses: self.get_addresses()?,
+            storage_key_prefix,
+            incompletes: self.incompletes,
         })
     }
 
+    fn get_addresses(&self) -> Result<Option<Vec<Address>>, String> {
+        if self.addresses.is_none() && self.address_file.is_none() {
+            return Ok(None);
+        }
+
+        let mut items: Vec<String> = vec![];
+        if let Some(addresses) = &self.addresses {
+            items.extend(addresses.split(',').map(str::to_string));
+        }
+        if let Some(path) = &self.address_file {
+            let content = fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+            items.extend(content.lines().map(str::to_string));
+        }
+
+        items
+            .iter()
+            .map(|item| item.trim())
+            .filter(|item| !item.is_empty())
+            .map(|item| {
+                parse_hex_string(item)
+                    .map_err(|e| format!("Invalid address {}: {}", item, e))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(Some)
+    }
+
     pub fn execute(&self, conf: &mut Configuration) -> Result<String, String> {
         // Determine output directory
         let output_path = match self.output {
@@ -101,41 +194,74 @@ impl DumpCommand {
         let exit = Arc::new((Mutex::new(false), Condvar::new()));
         let config = self.get_state_dump_config(output_path)?;
 
-        let _total_accounts = if self.multi_file {
-            // Write to multiple files
-            let state_root = iterate_dump_whole_state(
+        if self.iterative {
+            let checkpoint = stream_dump_whole_state(
                 conf,
                 exit,
                 &config,
-                |account_state| {
-                    let address =
-                        account_state.address.expect("address is not set");
-                    let filename = format!("{:?}.json", address);
-                    let file_path = Path::new(output_path).join(&filename);
+                self.resume,
+                self.checkpoint_interval,
+            )?;
+            let status = if checkpoint.completed {
+                "completed"
+            } else {
+                "stopped by the limit, run with --resume to continue"
+            };
+            return Ok(format!(
+                "Dumped {} accounts to {} ({})",
+                checkpoint.accounts,
+                Path::new(output_path).join(STREAM_FILE_NAME).display(),
+                status
+            ));
+        }
+
+        let _total_accounts = if self.multi_file {
+            // Core Space accounts are written to a sub folder, as they may
+            // share the addresses with eSpace accounts.
+            if config.spaces.contains(&Space::Native) {
+                let core_path = Path::new(output_path).join("core");
+                fs::create_dir_all(&core_path).map_err(|e| {
+                    format!("Failed to create output directory: {}", e)
+                })?;
+            }
+
+            // Write to multiple files
+            let state_root =
+                iterate_dump_whole_state(conf, exit, &config, |account| {
+                    let address = account.address();
+                    let file_path = match account {
+                        DumpedAccount::Native(_) => Path::new(output_path)
+                            .join("core")
+                            .join(format!("{:?}.json", address)),
+                        DumpedAccount::Ethereum(_) => Path::new(output_path)
+                            .join(format!("{:?}.json", address)),
+                    };
 
                     // Serialize account_state to JSON
-                    let json_content =
-                        serde_json::to_string_pretty(&account_state)
-                            .map_err(|e| {
-                                format!(
-                            "Failed to serialize account state for {}: {}",
+                    let json_content = match &account {
+                        DumpedAccount::Native(account_state) => {
+                            serde_json::to_string_pretty(account_state)
+                        }
+                        DumpedAccount::Ethereum(account_state) => {
+                            serde_json::to_string_pretty(account_state)
+                        }
+                    }
+                    .map_err(|e| {
+                        format!(
+                            "Failed to serialize account state for {:?}: {}",
                             address, e
                         )
-                            })
-                            .expect("Failed to serialize account state");
+                    })?;
 
                     // Write to file
-                    fs::write(&file_path, json_content)
-                        .map_err(|e| {
-                            format!(
-                                "Failed to write file {}: {}",
-                                file_path.display(),
-                                e
-                            )
-                        })
-                        .expect("Failed to write file");
-                },
-            )?;
+                    fs::write(&file_path, json_content).map_err(|e| {
+                        format!(
+                            "Failed to write file {}: {}",
+                            file_path.display(),
+                            e
+                        )
+                    })
+                })?;
 
             // Write meta info
             let mut meta_info = HashMap::new();
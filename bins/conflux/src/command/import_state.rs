@@ -0,0 +1,89 @@
+use clap::{ArgMatches, Args};
+use client::{
+    configuration::Configuration,
+    state_import::{import_genesis_state, verify_state_import},
+};
+
+/// Number of mismatched accounts printed by a dry run
+const MAX_REPORTED_MISMATCHES: usize = 10;
+
+#[derive(Args, Debug)]
+pub struct ImportStateCommand {
+    /// Path to the dump: the state.json file, the folder of a multi file
+    /// dump, or the state.jsonl file (or its folder) of an iterative dump
+    #[arg(id = "input", long = "input", value_name = "PATH", required = true)]
+    input: String,
+    /// Import into a temporary folder and verify the round trip of the
+    /// accounts and the state root, without touching the data directory
+    #[arg(id = "dry-run", long = "dry-run")]
+    dry_run: bool,
+}
+
+impl ImportStateCommand {
+    pub fn parse(matches: &ArgMatches) -> Result<Self, String> {
+        Ok(Self {
+            input: matches
+                .get_one::<String>("input")
+                .cloned()
+                .ok_or("input is required")?,
+            dry_run: matches.get_flag("dry-run"),
+        })
+    }
+
+    pub fn execute(&self, conf: &mut Configuration) -> Result<String, String> {
+        if !self.dry_run {
+            let (genesis_hash, state_root, summary) =
+                import_genesis_state(conf, &self.input)?;
+            return Ok(format!(
+                "Imported {} accounts and {} storage entries\nstate root: {:?}\ngenesis hash: {:?}\nStart the node in dev or test mode with\n  genesis_state = \"{}\"",
+                summary.accounts,
+                summary.storage_entries,
+                state_root,
+                genesis_hash,
+                self.input
+            ));
+        }
+
+        let verification = verify_state_import(conf, &self.input)?;
+        let summary = &verification.summary;
+        let mut output = format!(
+            "Imported {} accounts and {} storage entries\nstate root: {:?}",
+            summary.accounts, summary.storage_entries, verification.state_root
+        );
+
+        if !verification.mismatches.is_empty() {
+            for (space, address) in
+                verification.mismatches.iter().take(MAX_REPORTED_MISMATCHES)
+            {
+                output +=
+                    &format!("\nmismatched {:?} account {:?}", space, address);
+            }
+            return Err(format!(
+                "{}\n{} accounts do not survive the round trip",
+                output,
+                verification.mismatches.len()
+            ));
+        }
+        output += "\nAll accounts survive the round trip";
+
+        match summary.recorded_root {
+            Some(root) if root == verification.state_root => {
+                output += "\nThe state root matches the dump";
+            }
+            Some(root) => {
+                // The root of a chain also depends on how the state is split
+                // into the snapshot, the intermediate and the delta tries, so
+                // only a dump taken at the genesis of a chain bootstrapped by
+                // this command is expected to match.
+                return Err(format!(
+                    "{}\nThe state root differs from {:?} recorded in the dump",
+                    output, root
+                ));
+            }
+            None => {
+                output += "\nNo state root is recorded in the dump";
+            }
+        }
+        Ok(output)
+    }
+}
@@ -5,4 +5,6 @@
 pub mod account;
 pub mod dump;
 pub mod helpers;
+pub mod import_state;
+pub mod pivot_hint;
 pub mod rpc;
@@ -0,0 +1,171 @@
+use cfx_types::H256;
+use cfxcore::consensus::pivot_hint::{PivotHintMismatch, PivotHintParams};
+use clap::{ArgMatches, Args};
+use client::{
+    configuration::Configuration,
+    pivot_hint::{
+        generate_pivot_hint_file, verify_pivot_hint_file,
+        PivotHintGenerateConfig,
+    },
+};
+use parking_lot::{Condvar, Mutex};
+use std::{str::FromStr, sync::Arc};
+
+#[derive(Args, Debug)]
+pub struct PivotHintGenerateCommand {
+    /// Path of the pivot hint file to write
+    #[arg(
+        id = "output",
+        long = "output",
+        value_name = "PATH",
+        required = true
+    )]
+    output: String,
+    /// The last height to record, rounded down to the page boundary. The
+    /// latest confirmed epoch is used if not specified
+    #[arg(id = "height", long = "height", value_name = "NUM")]
+    height: Option<u64>,
+    /// Number of consecutive blocks in each page
+    #[arg(
+        id = "page-interval",
+        long = "page-interval",
+        value_name = "NUM",
+        default_value = "100000"
+    )]
+    page_interval: u32,
+    /// Full hashes are recorded every `major-interval` heights
+    #[arg(
+        id = "major-interval",
+        long = "major-interval",
+        value_name = "NUM",
+        default_value = "1000"
+    )]
+    major_interval: u32,
+    /// Hash prefixes are recorded every `minor-interval` heights
+    #[arg(
+        id = "minor-interval",
+        long = "minor-interval",
+        value_name = "NUM",
+        default_value = "10"
+    )]
+    minor_interval: u32,
+    /// Length of the recorded hash prefixes
+    #[arg(
+        id = "minor-hash-length",
+        long = "minor-hash-length",
+        value_name = "NUM",
+        default_value = "4"
+    )]
+    minor_hash_length: u32,
+}
+
+impl PivotHintGenerateCommand {
+    pub fn parse(matches: &ArgMatches) -> Result<Self, String> {
+        let get_u32 = |id: &str| -> Result<u32, String> {
+            matches
+                .get_one::<u32>(id)
+                .cloned()
+                .ok_or(format!("{} is required", id))
+        };
+        Ok(Self {
+            output: matches
+                .get_one::<String>("output")
+                .cloned()
+                .ok_or("output is required")?,
+            height: matches.get_one::<u64>("height").cloned(),
+            page_interval: get_u32("page-interval")?,
+            major_interval: get_u32("major-interval")?,
+            minor_interval: get_u32("minor-interval")?,
+            minor_hash_length: get_u32("minor-hash-length")?,
+        })
+    }
+
+    pub fn execute(&self, conf: &mut Configuration) -> Result<String, String> {
+        let exit = Arc::new((Mutex::new(false), Condvar::new()));
+        let config = PivotHintGenerateConfig {
+            file_path: self.output.clone(),
+            height: self.height,
+            params: PivotHintParams {
+                page_interval: self.page_interval as u64,
+                major_interval: self.major_interval as u64,
+                minor_interval: self.minor_interval as u64,
+                minor_hash_length: self.minor_hash_length as usize,
+            },
+        };
+
+        let (checksum, range_max) =
+            generate_pivot_hint_file(conf, exit, &config)?;
+
+        Ok(format!(
+            "Wrote pivot hint of heights below {} to {}\npivot_hint_checksum: {:x}",
+            range_max, self.output, checksum
+        ))
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct PivotHintVerifyCommand {
+    /// Path of the pivot hint file to verify
+    #[arg(id = "file", long = "file", value_name = "PATH", required = true)]
+    file: String,
+    /// The expected checksum of the file, as pinned in
+    /// `pivot_hint_checksum`
+    #[arg(id = "checksum", long = "checksum", value_name = "HEX")]
+    checksum: Option<String>,
+}
+
+impl PivotHintVerifyCommand {
+    pub fn parse(matches: &ArgMatches) -> Result<Self, String> {
+        Ok(Self {
+            file: matches
+                .get_one::<String>("file")
+                .cloned()
+                .ok_or("file is required")?,
+            checksum: matches.get_one::<String>("checksum").cloned(),
+        })
+    }
+
+    pub fn execute(&self, conf: &mut Configuration) -> Result<String, String> {
+        let expected_checksum = self
+            .checksum
+            .as_ref()
+            .map(|checksum| {
+                H256::from_str(checksum.trim_start_matches("0x"))
+                    .map_err(|e| format!("Invalid checksum: {:?}", e))
+            })
+            .transpose()?;
+
+        let exit = Arc::new((Mutex::new(false), Condvar::new()));
+        let verification = verify_pivot_hint_file(conf, exit, &self.file)?;
+
+        if let Some(expected) = expected_checksum {
+            if expected != verification.checksum {
+                return Err(format!(
+                    "Checksum mismatch: expected {:x}, got {:x}",
+                    expected, verification.checksum
+                ));
+            }
+        }
+
+        match verification.first_mismatch {
+            None => Ok(format!(
+                "Pivot hint of heights below {} matches the local pivot chain\npivot_hint_checksum: {:x}",
+                verification.range_max, verification.checksum
+            )),
+            Some(PivotHintMismatch {
+                page_number,
+                height: Some(height),
+            }) => Err(format!(
+                "Page {} mismatches the local pivot chain at height {}",
+                page_number, height
+            )),
+            Some(PivotHintMismatch {
+                page_number,
+                height: None,
+            }) => Err(format!(
+                "Page {} does not match its page digest",
+                page_number
+            )),
+        }
+    }
+}
@@ -33,6 +33,8 @@ use client::{
 use command::{
     account::{AccountCmd, ImportAccounts, ListAccounts, NewAccount},
     dump::DumpCommand,
+    import_state::ImportStateCommand,
+    pivot_hint::{PivotHintGenerateCommand, PivotHintVerifyCommand},
 };
 use log::{info, LevelFilter};
 use log4rs::{
@@ -178,6 +180,31 @@ fn handle_sub_command(matches: &ArgMatches) -> Result<Option<String>, String> {
         return Ok(Some(execute_output));
     }
 
+    // import state sub-commands
+    if let Some(("import-state", import_matches)) = matches.subcommand() {
+        let import_cmd = ImportStateCommand::parse(import_matches)?;
+        let mut conf = Configuration::parse(matches)?;
+        let execute_output = import_cmd.execute(&mut conf)?;
+        return Ok(Some(execute_output));
+    }
+
+    // pivot hint sub-commands
+    if let Some(("pivot-hint", pivot_hint_matches)) = matches.subcommand() {
+        let mut conf = Configuration::parse(matches)?;
+        let execute_output = match pivot_hint_matches.subcommand() {
+            Some(("generate", generate_matches)) => {
+                PivotHintGenerateCommand::parse(generate_matches)?
+                    .execute(&mut conf)?
+            }
+            Some(("verify", verify_matches)) => {
+                PivotHintVerifyCommand::parse(verify_matches)?
+                    .execute(&mut conf)?
+            }
+            _ => unreachable!(),
+        };
+        return Ok(Some(execute_output));
+    }
+
     // general RPC commands
     let mut subcmd_matches = matches;
     while let Some(m) = subcmd_matches.subcommand() {
@@ -18,6 +18,8 @@ use std::fmt;
 
 use cfxstore::Error as SSError;
 
+use crate::TypedDataError;
+
 /// Signing error
 #[derive(Debug)]
 pub enum SignError {
@@ -27,6 +29,8 @@ pub enum SignError {
     NotFound,
     /// Low-level error from store
     SStore(SSError),
+    /// Invalid typed structured data
+    InvalidTypedData(TypedDataError),
 }
 
 impl fmt::Display for SignError {
@@ -35,6 +39,7 @@ impl fmt::Display for SignError {
             SignError::NotUnlocked => write!(f, "Account is locked"),
             SignError::NotFound => write!(f, "Account does not exist"),
             SignError::SStore(ref e) => write!(f, "{}", e),
+            SignError::InvalidTypedData(ref e) => write!(f, "{}", e),
         }
     }
 }
@@ -42,3 +47,7 @@ impl fmt::Display for SignError {
 impl From<SSError> for SignError {
     fn from(e: SSError) -> Self { SignError::SStore(e) }
 }
+
+impl From<TypedDataError> for SignError {
+    fn from(e: TypedDataError) -> Self { SignError::InvalidTypedData(e) }
+}
@@ -21,6 +21,7 @@
 mod account_data;
 mod error;
 mod stores;
+mod typed_data;
 
 use self::{
     account_data::{AccountData, Unlock},
@@ -44,7 +45,14 @@ use parking_lot::RwLock;
 pub use cfxkey::Signature;
 pub use cfxstore::{Derivation, Error, IndexDerivation, KeyFile};
 
-pub use self::{account_data::AccountMeta, error::SignError};
+pub use self::{
+    account_data::AccountMeta,
+    error::SignError,
+    typed_data::{
+        FieldType, TypedData, TypedDataError, TypedDataKind, CIP23_DOMAIN,
+        EIP712_DOMAIN,
+    },
+};
 
 type AccountToken = Password;
 
@@ -493,6 +501,16 @@ impl AccountProvider {
         }
     }
 
+    /// Signs EIP-712 or CIP-23 typed structured data. If password is not
+    /// provided the account must be unlocked.
+    pub fn sign_typed_data(
+        &self, address: Address, password: Option<Password>,
+        typed_data: &TypedData,
+    ) -> Result<Signature, SignError> {
+        let message = typed_data.signing_hash()?;
+        self.sign(address, password, message)
+    }
+
     /// Signs message using the derived secret. If password is not provided the
     /// account must be unlocked.
     pub fn sign_derived(
@@ -0,0 +1,744 @@
+// Copyright 2019 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+//! Hashing of typed structured data as specified by
+//! [EIP-712](https://eips.ethereum.org/EIPS/eip-712) and its Conflux
+//! counterpart [CIP-23](https://github.com/Conflux-Chain/CIPs/blob/master/CIPs/cip-23.md).
+//!
+//! The two standards only differ in the domain: CIP-23 names the domain type
+//! `CIP23Domain`, requires a `chainId` and expects the `verifyingContract` to
+//! be a base32 (CIP-37) address on the network of that chain.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    fmt,
+};
+
+use cfx_addr::{cfx_addr_decode, Network};
+use cfx_types::{Address, H256, U256};
+use keccak_hash::keccak;
+use rustc_hex::FromHex;
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Name of the domain type of EIP-712 typed data.
+pub const EIP712_DOMAIN: &str = "EIP712Domain";
+/// Name of the domain type of CIP-23 typed data.
+pub const CIP23_DOMAIN: &str = "CIP23Domain";
+
+/// Fields allowed in the domain type, with their expected types.
+const DOMAIN_FIELDS: [(&str, &str); 5] = [
+    ("name", "string"),
+    ("version", "string"),
+    ("chainId", "uint256"),
+    ("verifyingContract", "address"),
+    ("salt", "bytes32"),
+];
+
+/// A member of a struct type.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FieldType {
+    /// Name of the member.
+    pub name: String,
+    /// Solidity type of the member.
+    #[serde(rename = "type")]
+    pub type_: String,
+}
+
+/// Typed structured data, as accepted by `eth_signTypedData_v4`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypedData {
+    /// Struct type definitions, including the domain type.
+    pub types: BTreeMap<String, Vec<FieldType>>,
+    /// Type of `message`.
+    pub primary_type: String,
+    /// Values of the domain type.
+    pub domain: Value,
+    /// Values of the primary type.
+    pub message: Value,
+}
+
+/// The standard a piece of typed data follows, determined by its domain type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TypedDataKind {
+    /// Domain type is `EIP712Domain`.
+    Eip712,
+    /// Domain type is `CIP23Domain`.
+    Cip23,
+}
+
+impl TypedDataKind {
+    /// Name of the domain type.
+    pub fn domain_type(&self) -> &'static str {
+        match self {
+            TypedDataKind::Eip712 => EIP712_DOMAIN,
+            TypedDataKind::Cip23 => CIP23_DOMAIN,
+        }
+    }
+}
+
+/// Typed data validation error.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TypedDataError {
+    /// Exactly one of `EIP712Domain` and `CIP23Domain` must be defined.
+    InvalidDomainType,
+    /// A struct type definition is malformed.
+    InvalidTypeDefinition(String),
+    /// A referenced type is neither a known atomic type nor a defined struct.
+    UnknownType(String),
+    /// The domain type or domain values are invalid.
+    InvalidDomain(String),
+    /// A value does not match its declared type.
+    InvalidValue {
+        /// Location of the value, e.g. `message.from.wallet`.
+        path: String,
+        /// What is wrong with the value.
+        reason: String,
+    },
+}
+
+impl fmt::Display for TypedDataError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            TypedDataError::InvalidDomainType => write!(
+                f,
+                "Exactly one of {} and {} must be defined",
+                EIP712_DOMAIN, CIP23_DOMAIN
+            ),
+            TypedDataError::InvalidTypeDefinition(reason) => {
+                write!(f, "Invalid type definition: {}", reason)
+            }
+            TypedDataError::UnknownType(ty) => {
+                write!(f, "Unknown type {}", ty)
+            }
+            TypedDataError::InvalidDomain(reason) => {
+                write!(f, "Invalid domain: {}", reason)
+            }
+            TypedDataError::InvalidValue { path, reason } => {
+                write!(f, "Invalid value of {}: {}", path, reason)
+            }
+        }
+    }
+}
+
+/// A parsed member type.
+enum ParsedType<'a> {
+    Bool,
+    Address,
+    Uint(usize),
+    Int(usize),
+    FixedBytes(usize),
+    Bytes,
+    String,
+    Array(&'a str, Option<usize>),
+    Struct(&'a str),
+}
+
+fn invalid_value(path: &str, reason: impl Into<String>) -> TypedDataError {
+    TypedDataError::InvalidValue {
+        path: path.into(),
+        reason: reason.into(),
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+/// Parse the size suffix of `uintN`, `intN` and `bytesN`. Leading zeros are
+/// not allowed.
+fn parse_size(size: &str) -> Option<usize> {
+    if size.starts_with('0') {
+        return None;
+    }
+    size.parse().ok()
+}
+
+fn parse_hex(value: &Value, path: &str) -> Result<Vec<u8>, TypedDataError> {
+    let s = value
+        .as_str()
+        .ok_or_else(|| invalid_value(path, "expect a hex string"))?;
+    let hex = s
+        .strip_prefix("0x")
+        .ok_or_else(|| invalid_value(path, "missing 0x prefix"))?;
+    hex.from_hex()
+        .map_err(|e| invalid_value(path, format!("invalid hex: {}", e)))
+}
+
+/// Parse a JSON number, or a decimal or 0x prefixed hex string, into its sign
+/// and absolute value.
+fn parse_integer(
+    value: &Value, path: &str,
+) -> Result<(bool, U256), TypedDataError> {
+    match value {
+        Value::Number(n) => {
+            if let Some(v) = n.as_u64() {
+                Ok((false, v.into()))
+            } else if let Some(v) = n.as_i64() {
+                Ok((true, v.unsigned_abs().into()))
+            } else {
+                Err(invalid_value(path, "expect an integer"))
+            }
+        }
+        Value::String(s) => {
+            let (negative, abs) = match s.strip_prefix('-') {
+                Some(abs) => (true, abs),
+                None => (false, s.as_str()),
+            };
+            let parsed = match abs.strip_prefix("0x") {
+                Some("") => None,
+                Some(hex) => U256::from_str_radix(hex, 16).ok(),
+                None => U256::from_dec_str(abs).ok(),
+            };
+            parsed
+                .map(|v| (negative, v))
+                .ok_or_else(|| invalid_value(path, "expect an integer"))
+        }
+        _ => Err(invalid_value(path, "expect an integer")),
+    }
+}
+
+/// Parse a hex or base32 address.
+fn parse_address(
+    value: &Value, path: &str,
+) -> Result<(Address, Option<Network>), TypedDataError> {
+    let s = value
+        .as_str()
+        .ok_or_else(|| invalid_value(path, "expect an address string"))?;
+    if s.starts_with("0x") {
+        let bytes = parse_hex(value, path)?;
+        if bytes.len() != Address::len_bytes() {
+            return Err(invalid_value(path, "expect a 20 bytes address"));
+        }
+        return Ok((Address::from_slice(&bytes), None));
+    }
+    let decoded = cfx_addr_decode(s)
+        .map_err(|e| invalid_value(path, format!("{}", e)))?;
+    let address = decoded
+        .hex_address
+        .ok_or_else(|| invalid_value(path, "expect a 20 bytes address"))?;
+    Ok((address, Some(decoded.network)))
+}
+
+fn network_id(network: Network) -> u64 {
+    match network {
+        Network::Main => 1029,
+        Network::Test => 1,
+        Network::Id(id) => id,
+    }
+}
+
+impl TypedData {
+    /// The standard followed by this typed data.
+    pub fn kind(&self) -> Result<TypedDataKind, TypedDataError> {
+        match (
+            self.types.contains_key(EIP712_DOMAIN),
+            self.types.contains_key(CIP23_DOMAIN),
+        ) {
+            (true, false) => Ok(TypedDataKind::Eip712),
+            (false, true) => Ok(TypedDataKind::Cip23),
+            _ => Err(TypedDataError::InvalidDomainType),
+        }
+    }
+
+    /// The `chainId` of the domain, if present.
+    pub fn chain_id(&self) -> Result<Option<U256>, TypedDataError> {
+        match self.domain.get("chainId") {
+            None => Ok(None),
+            Some(value) => match parse_integer(value, "domain.chainId")? {
+                (false, chain_id) => Ok(Some(chain_id)),
+                (true, _) => {
+                    Err(invalid_value("domain.chainId", "negative chain id"))
+                }
+            },
+        }
+    }
+
+    fn parse_type<'a>(
+        &self, ty: &'a str,
+    ) -> Result<ParsedType<'a>, TypedDataError> {
+        if let Some(prefix) = ty.strip_suffix(']') {
+            let open = prefix
+                .rfind('[')
+                .ok_or_else(|| TypedDataError::UnknownType(ty.into()))?;
+            let len =
+                match &prefix[open + 1..] {
+                    "" => None,
+                    len => Some(parse_size(len).ok_or_else(|| {
+                        TypedDataError::UnknownType(ty.into())
+                    })?),
+                };
+            return Ok(ParsedType::Array(&prefix[..open], len));
+        }
+
+        let parsed = match ty {
+            "bool" => ParsedType::Bool,
+            "address" => ParsedType::Address,
+            "bytes" => ParsedType::Bytes,
+            "string" => ParsedType::String,
+            _ if self.types.contains_key(ty) => ParsedType::Struct(ty),
+            _ => {
+                let sized = if let Some(size) = ty.strip_prefix("bytes") {
+                    parse_size(size)
+                        .filter(|n| (1..=32).contains(n))
+                        .map(ParsedType::FixedBytes)
+                } else if let Some(size) = ty.strip_prefix("uint") {
+                    parse_size(size)
+                        .filter(|n| n % 8 == 0 && (8..=256).contains(n))
+                        .map(ParsedType::Uint)
+                } else if let Some(size) = ty.strip_prefix("int") {
+                    parse_size(size)
+                        .filter(|n| n % 8 == 0 && (8..=256).contains(n))
+                        .map(ParsedType::Int)
+                } else {
+                    None
+                };
+                sized.ok_or_else(|| TypedDataError::UnknownType(ty.into()))?
+            }
+        };
+        Ok(parsed)
+    }
+
+    fn check_type(&self, ty: &str) -> Result<(), TypedDataError> {
+        match self.parse_type(ty)? {
+            ParsedType::Array(inner, _) => self.check_type(inner),
+            _ => Ok(()),
+        }
+    }
+
+    /// Check the type definitions and the domain. Values are checked against
+    /// their types while hashing.
+    pub fn validate(&self) -> Result<(), TypedDataError> {
+        let kind = self.kind()?;
+
+        for (name, fields) in &self.types {
+            if !is_identifier(name) {
+                return Err(TypedDataError::InvalidTypeDefinition(format!(
+                    "invalid type name {:?}",
+                    name
+                )));
+            }
+            let mut names = HashSet::new();
+            for field in fields {
+                if !is_identifier(&field.name) {
+                    return Err(TypedDataError::InvalidTypeDefinition(
+                        format!(
+                            "invalid member name {:?} of {}",
+                            field.name, name
+                        ),
+                    ));
+                }
+                if !names.insert(field.name.as_str()) {
+                    return Err(TypedDataError::InvalidTypeDefinition(
+                        format!("duplicate member {} of {}", field.name, name),
+                    ));
+                }
+                self.check_type(&field.type_)?;
+            }
+        }
+
+        if !self.types.contains_key(&self.primary_type) {
+            return Err(TypedDataError::UnknownType(self.primary_type.clone()));
+        }
+        if self.primary_type == kind.domain_type() {
+            return Err(TypedDataError::InvalidTypeDefinition(
+                "primary type must not be the domain type".into(),
+            ));
+        }
+
+        self.validate_domain(kind)
+    }
+
+    fn validate_domain(
+        &self, kind: TypedDataKind,
+    ) -> Result<(), TypedDataError> {
+        let domain_type = kind.domain_type();
+        for field in &self.types[domain_type] {
+            match DOMAIN_FIELDS.iter().find(|(name, _)| *name == field.name) {
+                Some((_, ty)) if *ty == field.type_ => {}
+                Some((_, ty)) => {
+                    return Err(TypedDataError::InvalidDomain(format!(
+                        "{} must be of type {}",
+                        field.name, ty
+                    )))
+                }
+                None => {
+                    return Err(TypedDataError::InvalidDomain(format!(
+                        "unknown field {}",
+                        field.name
+                    )))
+                }
+            }
+        }
+
+        if kind == TypedDataKind::Cip23 {
+            let chain_id = self.chain_id()?.ok_or_else(|| {
+                TypedDataError::InvalidDomain("chainId is required".into())
+            })?;
+            if let Some(contract) = self.domain.get("verifyingContract") {
+                let path = "domain.verifyingContract";
+                match parse_address(contract, path)? {
+                    (_, Some(network))
+                        if U256::from(network_id(network)) == chain_id => {}
+                    (_, Some(_)) => {
+                        return Err(invalid_value(
+                            path,
+                            "address network does not match chainId",
+                        ))
+                    }
+                    (_, None) => {
+                        return Err(invalid_value(
+                            path,
+                            "expect a base32 address",
+                        ))
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Collect the struct types referenced by `ty`, directly or indirectly.
+    fn collect_dependencies<'a>(
+        &'a self, ty: &'a str, deps: &mut BTreeSet<&'a str>,
+    ) {
+        let base = ty.split('[').next().unwrap_or(ty);
+        if let Some((name, fields)) = self.types.get_key_value(base) {
+            if deps.insert(name.as_str()) {
+                for field in fields {
+                    self.collect_dependencies(&field.type_, deps);
+                }
+            }
+        }
+    }
+
+    /// `encodeType` of a struct type: its own signature followed by the
+    /// signatures of the referenced struct types in alphabetical order.
+    pub fn encode_type(&self, ty: &str) -> Result<String, TypedDataError> {
+        if !self.types.contains_key(ty) {
+            return Err(TypedDataError::UnknownType(ty.into()));
+        }
+        let mut deps = BTreeSet::new();
+        self.collect_dependencies(ty, &mut deps);
+        deps.remove(ty);
+
+        let mut encoded = String::new();
+        for name in std::iter::once(ty).chain(deps) {
+            let members = self.types[name]
+                .iter()
+                .map(|field| format!("{} {}", field.type_, field.name))
+                .collect::<Vec<_>>()
+                .join(",");
+            encoded.push_str(&format!("{}({})", name, members));
+        }
+        Ok(encoded)
+    }
+
+    /// `typeHash` of a struct type.
+    pub fn type_hash(&self, ty: &str) -> Result<H256, TypedDataError> {
+        Ok(keccak(self.encode_type(ty)?))
+    }
+
+    fn hash_struct(
+        &self, ty: &str, value: &Value, path: &str,
+    ) -> Result<H256, TypedDataError> {
+        let object = value
+            .as_object()
+            .ok_or_else(|| invalid_value(path, "expect an object"))?;
+        let fields = &self.types[ty];
+        if let Some(key) = object
+            .keys()
+            .find(|key| !fields.iter().any(|field| &field.name == *key))
+        {
+            return Err(invalid_value(
+                path,
+                format!("{} is not a member of {}", key, ty),
+            ));
+        }
+
+        let mut encoded = self.type_hash(ty)?.as_bytes().to_vec();
+        for field in fields {
+            let field_path = format!("{}.{}", path, field.name);
+            let field_value = object
+                .get(&field.name)
+                .ok_or_else(|| invalid_value(&field_path, "missing value"))?;
+            encoded.extend_from_slice(
+                self.encode_value(&field.type_, field_value, &field_path)?
+                    .as_bytes(),
+            );
+        }
+        Ok(keccak(encoded))
+    }
+
+    fn encode_value(
+        &self, ty: &str, value: &Value, path: &str,
+    ) -> Result<H256, TypedDataError> {
+        let encoded = match self.parse_type(ty)? {
+            ParsedType::Bool => {
+                let v = value
+                    .as_bool()
+                    .ok_or_else(|| invalid_value(path, "expect a boolean"))?;
+                H256::from_low_u64_be(v as u64)
+            }
+            ParsedType::Address => {
+                let (address, _) = parse_address(value, path)?;
+                H256::from(address)
+            }
+            ParsedType::Uint(bits) => match parse_integer(value, path)? {
+                (false, v) if v.bits() <= bits => H256::from(v.to_big_endian()),
+                _ => {
+                    return Err(invalid_value(
+                        path,
+                        format!("out of range of uint{}", bits),
+                    ))
+                }
+            },
+            ParsedType::Int(bits) => {
+                let bound = U256::one() << (bits - 1);
+                match parse_integer(value, path)? {
+                    (false, v) if v < bound => H256::from(v.to_big_endian()),
+                    (true, v) if v <= bound => {
+                        let v = (!v).overflowing_add(U256::one()).0;
+                        H256::from(v.to_big_endian())
+                    }
+                    _ => {
+                        return Err(invalid_value(
+                            path,
+                            format!("out of range of int{}", bits),
+                        ))
+                    }
+                }
+            }
+            ParsedType::FixedBytes(len) => {
+                let bytes = parse_hex(value, path)?;
+                if bytes.len() != len {
+                    return Err(invalid_value(
+                        path,
+                        format!("expect {} bytes", len),
+                    ));
+                }
+                let mut encoded = H256::zero();
+                encoded.as_bytes_mut()[..len].copy_from_slice(&bytes);
+                encoded
+            }
+            ParsedType::Bytes => keccak(parse_hex(value, path)?),
+            ParsedType::String => keccak(
+                value
+                    .as_str()
+                    .ok_or_else(|| invalid_value(path, "expect a string"))?,
+            ),
+            ParsedType::Array(inner, len) => {
+                let items = value
+                    .as_array()
+                    .ok_or_else(|| invalid_value(path, "expect an array"))?;
+                if let Some(len) = len {
+                    if items.len() != len {
+                        return Err(invalid_value(
+                            path,
+                            format!("expect {} items", len),
+                        ));
+                    }
+                }
+                let mut encoded = Vec::with_capacity(items.len() * 32);
+                for (i, item) in items.iter().enumerate() {
+                    let item_path = format!("{}[{}]", path, i);
+                    encoded.extend_from_slice(
+                        self.encode_value(inner, item, &item_path)?.as_bytes(),
+                    );
+                }
+                keccak(encoded)
+            }
+            ParsedType::Struct(name) => self.hash_struct(name, value, path)?,
+        };
+        Ok(encoded)
+    }
+
+    /// `hashStruct` of the domain.
+    pub fn domain_separator(&self) -> Result<H256, TypedDataError> {
+        let kind = self.kind()?;
+        self.hash_struct(kind.domain_type(), &self.domain, "domain")
+    }
+
+    /// The hash to be signed:
+    /// `keccak256("\x19\x01" ‖ domainSeparator ‖ hashStruct(message))`.
+    pub fn signing_hash(&self) -> Result<H256, TypedDataError> {
+        self.validate()?;
+        let mut encoded = vec![0x19, 0x01];
+        encoded.extend_from_slice(self.domain_separator()?.as_bytes());
+        encoded.extend_from_slice(
+            self.hash_struct(&self.primary_type, &self.message, "message")?
+                .as_bytes(),
+        );
+        Ok(keccak(encoded))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::str::FromStr;
+
+    fn mail(domain_type: &str, domain: Value) -> TypedData {
+        serde_json::from_value(json!({
+            "types": {
+                domain_type: [
+                    { "name": "name", "type": "string" },
+                    { "name": "version", "type": "string" },
+                    { "name": "chainId", "type": "uint256" },
+                    { "name": "verifyingContract", "type": "address" }
+                ],
+                "Person": [
+                    { "name": "name", "type": "string" },
+                    { "name": "wallet", "type": "address" }
+                ],
+                "Mail": [
+                    { "name": "from", "type": "Person" },
+                    { "name": "to", "type": "Person" },
+                    { "name": "contents", "type": "string" }
+                ]
+            },
+            "primaryType": "Mail",
+            "domain": domain,
+            "message": {
+                "from": {
+                    "name": "Cow",
+                    "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"
+                },
+                "to": {
+                    "name": "Bob",
+                    "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"
+                },
+                "contents": "Hello, Bob!"
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_eip712_mail_example() {
+        let data = mail(
+            EIP712_DOMAIN,
+            json!({
+                "name": "Ether Mail",
+                "version": "1",
+                "chainId": 1,
+                "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+            }),
+        );
+
+        assert_eq!(data.kind().unwrap(), TypedDataKind::Eip712);
+        assert_eq!(
+            data.encode_type("Mail").unwrap(),
+            "Mail(Person from,Person to,string contents)Person(string name,address wallet)"
+        );
+        assert_eq!(
+            data.domain_separator().unwrap(),
+            H256::from_str(
+                "f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f"
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            data.signing_hash().unwrap(),
+            H256::from_str(
+                "be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_cip23_domain() {
+        let contract = cfx_addr::cfx_addr_encode(
+            &[0x8c; 20],
+            Network::Main,
+            cfx_addr::EncodingOptions::Simple,
+        )
+        .unwrap();
+        let data = mail(
+            CIP23_DOMAIN,
+            json!({
+                "name": "Conflux Mail",
+                "version": "1",
+                "chainId": 1029,
+                "verifyingContract": contract,
+            }),
+        );
+        assert_eq!(data.kind().unwrap(), TypedDataKind::Cip23);
+        assert!(data.signing_hash().is_ok());
+
+        // The verifying contract must be on the network of the chain.
+        let mut wrong_chain = data.clone();
+        wrong_chain.domain["chainId"] = json!(1);
+        assert!(wrong_chain.signing_hash().is_err());
+
+        // Hex verifying contracts are not accepted by CIP-23.
+        let mut hex_contract = data.clone();
+        hex_contract.domain["verifyingContract"] =
+            json!("0x8c8c8c8c8c8c8c8c8c8c8c8c8c8c8c8c8c8c8c8c");
+        assert!(hex_contract.signing_hash().is_err());
+    }
+
+    #[test]
+    fn test_reject_invalid_payload() {
+        let domain = json!({ "name": "Ether Mail", "chainId": 1 });
+        let valid = mail(EIP712_DOMAIN, domain);
+
+        let mut both_domains = valid.clone();
+        both_domains
+            .types
+            .insert(CIP23_DOMAIN.into(), valid.types[EIP712_DOMAIN].clone());
+        assert_eq!(
+            both_domains.validate(),
+            Err(TypedDataError::InvalidDomainType)
+        );
+
+        let mut unknown_type = valid.clone();
+        unknown_type.types.get_mut("Person").unwrap()[1].type_ = "uint".into();
+        assert_eq!(
+            unknown_type.validate(),
+            Err(TypedDataError::UnknownType("uint".into()))
+        );
+
+        let mut duplicate = valid.clone();
+        let field = duplicate.types["Mail"][2].clone();
+        duplicate.types.get_mut("Mail").unwrap().push(field);
+        assert!(duplicate.validate().is_err());
+
+        let mut extra_value = valid.clone();
+        extra_value.message["from"]["age"] = json!(3);
+        assert!(extra_value.signing_hash().is_err());
+
+        let mut missing_value = valid.clone();
+        missing_value
+            .message
+            .as_object_mut()
+            .unwrap()
+            .remove("contents");
+        assert!(missing_value.signing_hash().is_err());
+    }
+
+    #[test]
+    fn test_encode_integers() {
+        let data = mail(EIP712_DOMAIN, json!({}));
+        let encode =
+            |ty: &str, value: Value| data.encode_value(ty, &value, "v");
+
+        assert_eq!(encode("int8", json!(-1)).unwrap(), H256::repeat_byte(0xff));
+        assert_eq!(
+            encode("uint256", json!("0x10")).unwrap(),
+            H256::from_low_u64_be(16)
+        );
+        assert!(encode("int8", json!(-129)).is_err());
+        assert!(encode("int8", json!(128)).is_err());
+        assert!(encode("uint8", json!("256")).is_err());
+        assert!(encode("uint8", json!(-1)).is_err());
+        assert!(encode("uint8", json!(1.5)).is_err());
+    }
+}
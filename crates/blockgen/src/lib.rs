@@ -4,9 +4,13 @@
 mod assembler;
 mod mine_session;
 mod miner;
+mod remote_work;
 mod test_api;
 
-pub use crate::test_api::BlockGeneratorTestApi;
+pub use crate::{
+    remote_work::{RemoteWork, WorkError},
+    test_api::BlockGeneratorTestApi,
+};
 
 use crate::{
     assembler::BlockAssembler, mine_session::MiningSession, miner::MineWorker,
@@ -38,6 +42,7 @@ pub struct BlockGenerator {
     sync: SharedSynchronizationService,
     status: RwLock<MiningStatus>,
     assembler: BlockAssembler,
+    remote_work: Arc<RemoteWork>,
 }
 
 impl BlockGenerator {
@@ -57,6 +62,10 @@ impl BlockGenerator {
             mining_author,
             pos_verifier,
         );
+        let remote_work = Arc::new(RemoteWork::new(
+            pow.clone(),
+            pow_config.pow_problem_window_size,
+        ));
         BlockGenerator {
             pow_config,
             pow,
@@ -64,6 +73,7 @@ impl BlockGenerator {
             sync,
             assembler,
             status: RwLock::new(MiningStatus::Start),
+            remote_work,
         }
     }
 
@@ -86,15 +96,18 @@ impl BlockGenerator {
         self.sync.on_mined_block(block).ok();
     }
 
+    /// The work of the mining session for the miners using JSON-RPC.
+    pub fn remote_work(&self) -> Arc<RemoteWork> { self.remote_work.clone() }
+
     pub fn test_api(self: &Arc<Self>) -> BlockGeneratorTestApi {
         BlockGeneratorTestApi::new(self.clone())
     }
 
     pub fn mine(self: &Arc<Self>) {
-        let miner_type = if self.pow_config.use_stratum() {
-            miner::MinerType::Stratum
-        } else {
-            miner::MinerType::Cpu(1)
+        let miner_type = match self.pow_config.mining_type {
+            MiningType::Stratum => miner::MinerType::Stratum,
+            MiningType::Remote => miner::MinerType::Remote,
+            _ => miner::MinerType::Cpu(1),
         };
 
         let (miner, solution_rx) = miner::spawn(self.clone(), miner_type);
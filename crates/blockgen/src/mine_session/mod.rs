@@ -1,7 +1,7 @@
 mod state;
 
 use crate::{BlockGenerator, MineWorker, SolutionReceiver};
-use cfxcore::pow::ProofOfWorkProblem;
+use cfxcore::pow::{ProofOfWorkProblem, ProofOfWorkSolution};
 use log::{debug, trace, warn};
 use std::{
     ops::Deref,
@@ -54,6 +54,7 @@ impl<'a> MiningSession<'a> {
             // Pull the mining solution
             self.process_pending_solutions();
         }
+        self.remote_work.clear();
     }
 
     fn should_mine(&self) -> bool {
@@ -91,13 +92,14 @@ impl<'a> MiningSession<'a> {
         );
 
         self.state.update_job(next_mining_block, problem);
+        self.remote_work.notify_new_problem(problem);
         self.send_problem(problem);
     }
 
     /// Pull mining solution from the workers, returns whether a valid solution
     /// is found
     fn process_pending_solutions(&mut self) {
-        while let Ok(solution) = self.solution_rx.try_recv() {
+        while let Some(solution) = self.next_solution() {
             debug!("new solution: {:?}", solution);
 
             if let Some(mut mined_block) =
@@ -125,6 +127,13 @@ impl<'a> MiningSession<'a> {
         thread::sleep(BLOCKGEN_LOOP_SLEEP_DURATION);
     }
 
+    fn next_solution(&self) -> Option<ProofOfWorkSolution> {
+        self.solution_rx
+            .try_recv()
+            .ok()
+            .or_else(|| self.remote_work.pop_solution())
+    }
+
     /// We will send out heartbeat because newcomers or
     /// disconnected people may lose the previous message
     fn ensure_miner_has_current_work(&mut self) {
@@ -6,7 +6,7 @@ pub mod cpu;
 pub mod stratum;
 
 use cfxcore::pow::ProofOfWorkProblem;
-use std::sync::Arc;
+use std::sync::{mpsc, Arc};
 use stratum::Stratum;
 
 use crate::{BlockGenerator, SolutionReceiver};
@@ -18,6 +18,15 @@ pub trait MineWorker {
 pub enum MinerType {
     Stratum,
     Cpu(usize), // Number of CPU workers
+    Remote,     // Only the miners using JSON-RPC
+}
+
+/// The work is only fetched by the remote miners, see
+/// [`RemoteWork`](crate::RemoteWork).
+struct NoopMiner;
+
+impl MineWorker for NoopMiner {
+    fn receive_problem(&self, _problem: ProofOfWorkProblem) {}
 }
 
 pub fn spawn(
@@ -34,6 +43,11 @@ pub fn spawn(
                 cpu::CpuMinerCoordinator::spawn(bg, num_workers);
             (Box::new(worker_manager), receiver)
         }
+        MinerType::Remote => {
+            // The remote solutions are pulled from `RemoteWork`.
+            let (_, receiver) = mpsc::channel();
+            (Box::new(NoopMiner), receiver)
+        }
     };
     (worker, solution_receiver)
 }
@@ -0,0 +1,239 @@
+// Copyright 2019 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+//! Work packages for the miners which fetch the work and submit the solutions
+//! over JSON-RPC (`eth_getWork`/`eth_submitWork`) instead of stratum.
+
+use cfx_types::{H256, U256};
+use cfxcore::pow::{PowComputer, ProofOfWorkProblem, ProofOfWorkSolution};
+use log::info;
+use parking_lot::Mutex;
+use std::{
+    collections::{HashSet, VecDeque},
+    fmt,
+    sync::Arc,
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum WorkError {
+    /// The mining session has not assembled a block yet, or mining is not
+    /// enabled.
+    NoWork,
+    /// The solution is for a block template which has been replaced and is
+    /// no longer mined on.
+    StaleWork(H256),
+    /// The template has already been solved with the same nonce.
+    AlreadySolved(H256),
+    /// The nonce does not meet the boundary of the template.
+    InvalidSolution(H256),
+}
+
+impl fmt::Display for WorkError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WorkError::NoWork => write!(
+                f,
+                "No mining work available, mining is disabled or the first block is not assembled yet"
+            ),
+            WorkError::StaleWork(pow_hash) => write!(
+                f,
+                "Stale work: the block template {:?} has been replaced, fetch new work",
+                pow_hash
+            ),
+            WorkError::AlreadySolved(pow_hash) => write!(
+                f,
+                "The block template {:?} is already solved with this nonce",
+                pow_hash
+            ),
+            WorkError::InvalidSolution(pow_hash) => write!(
+                f,
+                "Invalid nonce for the block template {:?}",
+                pow_hash
+            ),
+        }
+    }
+}
+
+#[derive(Default)]
+struct RemoteWorkInner {
+    // The recent problems with their solved nonces, the last one is the
+    // current work.
+    recent_problems: VecDeque<(ProofOfWorkProblem, HashSet<U256>)>,
+    // The valid solutions to be claimed by the mining session.
+    solutions: VecDeque<ProofOfWorkSolution>,
+}
+
+/// The work of the mining session exposed to the remote miners. The same
+/// window of recent problems as the stratum job dispatcher is kept, so a
+/// solution found for a template which has just been replaced is still
+/// accepted.
+pub struct RemoteWork {
+    pow: Arc<PowComputer>,
+    window_size: usize,
+    inner: Mutex<RemoteWorkInner>,
+}
+
+impl RemoteWork {
+    pub(crate) fn new(pow: Arc<PowComputer>, window_size: usize) -> Self {
+        RemoteWork {
+            pow,
+            window_size,
+            inner: Default::default(),
+        }
+    }
+
+    /// Returns the problem of the block template currently mined on.
+    pub fn get_work(&self) -> Result<ProofOfWorkProblem, WorkError> {
+        self.inner
+            .lock()
+            .recent_problems
+            .back()
+            .map(|(problem, _)| *problem)
+            .ok_or(WorkError::NoWork)
+    }
+
+    /// Validates a solution of a recent problem and hands it over to the
+    /// mining session.
+    pub fn submit_work(
+        &self, pow_hash: H256, nonce: U256,
+    ) -> Result<(), WorkError> {
+        let mut inner = self.inner.lock();
+        if inner.recent_problems.is_empty() {
+            return Err(WorkError::NoWork);
+        }
+
+        let solution = ProofOfWorkSolution { nonce };
+        let (problem, solved_nonces) = inner
+            .recent_problems
+            .iter_mut()
+            .find(|(problem, _)| problem.block_hash == pow_hash)
+            .ok_or(WorkError::StaleWork(pow_hash))?;
+        if solved_nonces.contains(&nonce) {
+            return Err(WorkError::AlreadySolved(pow_hash));
+        }
+        if !self.pow.validate(problem, &solution) {
+            return Err(WorkError::InvalidSolution(pow_hash));
+        }
+        solved_nonces.insert(nonce);
+        inner.solutions.push_back(solution);
+
+        info!("Remote worker mined a block! pow_hash = {:?}", pow_hash);
+        Ok(())
+    }
+
+    pub(crate) fn notify_new_problem(&self, problem: ProofOfWorkProblem) {
+        let mut inner = self.inner.lock();
+        if inner.recent_problems.len() == self.window_size {
+            inner.recent_problems.pop_front();
+        }
+        inner.recent_problems.push_back((problem, HashSet::new()));
+    }
+
+    pub(crate) fn pop_solution(&self) -> Option<ProofOfWorkSolution> {
+        self.inner.lock().solutions.pop_front()
+    }
+
+    /// Drops the work when the mining session stops.
+    pub(crate) fn clear(&self) { *self.inner.lock() = Default::default(); }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RemoteWork, WorkError};
+    use cfx_types::{H256, U256};
+    use cfxcore::pow::{PowComputer, ProofOfWorkProblem};
+    use std::sync::Arc;
+
+    fn remote_work(window_size: usize) -> RemoteWork {
+        RemoteWork::new(Arc::new(PowComputer::new(false)), window_size)
+    }
+
+    fn problem(block_height: u64) -> ProofOfWorkProblem {
+        // Any nonce solves a problem of difficulty 1.
+        ProofOfWorkProblem::new(
+            block_height,
+            H256::from_low_u64_be(block_height),
+            U256::one(),
+        )
+    }
+
+    #[test]
+    fn test_no_work() {
+        let work = remote_work(2);
+        assert_eq!(work.get_work(), Err(WorkError::NoWork));
+        assert_eq!(
+            work.submit_work(H256::zero(), U256::zero()),
+            Err(WorkError::NoWork)
+        );
+    }
+
+    #[test]
+    fn test_stale_work() {
+        let work = remote_work(2);
+        work.notify_new_problem(problem(1));
+        assert_eq!(
+            work.submit_work(H256::from_low_u64_be(2), U256::zero()),
+            Err(WorkError::StaleWork(H256::from_low_u64_be(2)))
+        );
+        assert!(work.pop_solution().is_none());
+
+        // The work is dropped with the mining session.
+        work.clear();
+        assert_eq!(
+            work.submit_work(problem(1).block_hash, U256::zero()),
+            Err(WorkError::NoWork)
+        );
+    }
+
+    #[test]
+    fn test_duplicate_work() {
+        let work = remote_work(2);
+        let problem = problem(1);
+        work.notify_new_problem(problem);
+        work.submit_work(problem.block_hash, U256::one()).unwrap();
+        assert_eq!(
+            work.submit_work(problem.block_hash, U256::one()),
+            Err(WorkError::AlreadySolved(problem.block_hash))
+        );
+        // Another nonce for the same template is still accepted.
+        work.submit_work(problem.block_hash, U256::from(2)).unwrap();
+
+        assert_eq!(work.pop_solution().unwrap().nonce, U256::one());
+        assert_eq!(work.pop_solution().unwrap().nonce, U256::from(2));
+        assert!(work.pop_solution().is_none());
+    }
+
+    #[test]
+    fn test_work_window() {
+        let work = remote_work(2);
+        for block_height in 1..=3 {
+            work.notify_new_problem(problem(block_height));
+        }
+        assert_eq!(work.get_work().unwrap().block_hash, problem(3).block_hash);
+
+        // The previous template is still in the window.
+        work.submit_work(problem(2).block_hash, U256::zero())
+            .unwrap();
+        // The oldest one has been moved out of it.
+        assert_eq!(
+            work.submit_work(problem(1).block_hash, U256::zero()),
+            Err(WorkError::StaleWork(problem(1).block_hash))
+        );
+        assert_eq!(work.pop_solution().unwrap().nonce, U256::zero());
+        assert!(work.pop_solution().is_none());
+    }
+
+    #[test]
+    fn test_invalid_solution() {
+        let work = remote_work(2);
+        let problem =
+            ProofOfWorkProblem::new(1, H256::from_low_u64_be(1), U256::MAX);
+        work.notify_new_problem(problem);
+        assert_eq!(
+            work.submit_work(problem.block_hash, U256::zero()),
+            Err(WorkError::InvalidSolution(problem.block_hash))
+        );
+        assert!(work.pop_solution().is_none());
+    }
+}
@@ -8,7 +8,7 @@ use crate::{
     db::{
         COL_BLAMED_HEADER_VERIFIED_ROOTS, COL_BLOCKS, COL_BLOCK_TRACES,
         COL_EPOCH_NUMBER, COL_HASH_BY_BLOCK_NUMBER, COL_MISC,
-        COL_REWARD_BY_POS_EPOCH, COL_TX_INDEX,
+        COL_REWARD_BY_POS_EPOCH, COL_TX_HASH_BY_SENDER_NONCE, COL_TX_INDEX,
     },
     pow::PowComputer,
     verification::VerificationConfig,
@@ -20,7 +20,7 @@ use cfx_internal_common::{
 use cfx_storage::{
     storage_db::KeyValueDbTrait, KvdbRocksdb, KvdbSqlite, KvdbSqliteStatements,
 };
-use cfx_types::H256;
+use cfx_types::{Address, H256, U256};
 use db::SystemDB;
 use malloc_size_of::{MallocSizeOf, MallocSizeOfOps};
 use primitives::{Block, BlockHeader, SignedTransaction, TransactionIndex};
@@ -39,6 +39,8 @@ const EPOCH_SKIPPED_BLOCK_SET_SUFFIX_BYTE: u8 = 7;
 const BLOCK_REWARD_RESULT_SUFFIX_BYTE: u8 = 8;
 const BLOCK_TERMINAL_KEY: &[u8] = b"block_terminals";
 const GC_PROGRESS_KEY: &[u8] = b"gc_progress";
+const TX_SENDER_NONCE_INDEX_BACKFILL_KEY: &[u8] =
+    b"tx_sender_nonce_index_backfill";
 
 #[derive(Clone, Copy, Hash, Ord, PartialOrd, Eq, PartialEq, EnumIter)]
 enum DBTable {
@@ -50,6 +52,7 @@ enum DBTable {
     BlockTraces,
     HashByBlockNumber,
     RewardByPosEpoch,
+    TxHashBySenderNonce,
 }
 
 fn rocks_db_col(table: DBTable) -> u32 {
@@ -62,6 +65,7 @@ fn rocks_db_col(table: DBTable) -> u32 {
         DBTable::BlockTraces => COL_BLOCK_TRACES,
         DBTable::HashByBlockNumber => COL_HASH_BY_BLOCK_NUMBER,
         DBTable::RewardByPosEpoch => COL_REWARD_BY_POS_EPOCH,
+        DBTable::TxHashBySenderNonce => COL_TX_HASH_BY_SENDER_NONCE,
     }
 }
 
@@ -75,6 +79,7 @@ fn sqlite_db_table(table: DBTable) -> String {
         DBTable::BlockTraces => "block_traces",
         DBTable::HashByBlockNumber => "hash_by_block_number",
         DBTable::RewardByPosEpoch => "reward_by_pos_epoch",
+        DBTable::TxHashBySenderNonce => "tx_hash_by_sender_nonce",
     }
     .into()
 }
@@ -196,6 +201,25 @@ impl DBManager {
         self.load_decodable_val(DBTable::Transactions, hash.as_bytes())
     }
 
+    pub fn insert_tx_hash_by_sender_nonce_to_db(
+        &self, sender: &Address, nonce: &U256, hash: &H256,
+    ) {
+        self.insert_encodable_val(
+            DBTable::TxHashBySenderNonce,
+            &sender_nonce_key(sender, nonce),
+            hash,
+        )
+    }
+
+    pub fn tx_hash_by_sender_nonce_from_db(
+        &self, sender: &Address, nonce: &U256,
+    ) -> Option<H256> {
+        self.load_decodable_val(
+            DBTable::TxHashBySenderNonce,
+            &sender_nonce_key(sender, nonce),
+        )
+    }
+
     pub fn insert_hash_by_block_number_to_db(
         &self, block_number: u64, hash: &H256,
     ) {
@@ -486,6 +510,27 @@ impl DBManager {
         self.load_decodable_val(DBTable::Misc, GC_PROGRESS_KEY)
     }
 
+    /// The next epoch to be processed by the backfill of the sender and nonce
+    /// index.
+    pub fn insert_tx_sender_nonce_index_backfill_progress_to_db(
+        &self, next_to_process: u64,
+    ) {
+        self.insert_encodable_val(
+            DBTable::Misc,
+            TX_SENDER_NONCE_INDEX_BACKFILL_KEY,
+            &next_to_process,
+        );
+    }
+
+    pub fn tx_sender_nonce_index_backfill_progress_from_db(
+        &self,
+    ) -> Option<u64> {
+        self.load_decodable_val(
+            DBTable::Misc,
+            TX_SENDER_NONCE_INDEX_BACKFILL_KEY,
+        )
+    }
+
     pub fn insert_pos_reward(
         &self, pos_epoch: u64, pos_reward: &PosRewardInfo,
     ) {
@@ -575,6 +620,15 @@ fn append_suffix(h: &H256, suffix: u8) -> Vec<u8> {
     key
 }
 
+/// The sender followed by the big-endian nonce, so the transactions of a
+/// sender are stored next to each other in nonce order.
+fn sender_nonce_key(sender: &Address, nonce: &U256) -> [u8; 52] {
+    let mut key = [0; 52];
+    key[0..20].copy_from_slice(sender.as_bytes());
+    key[20..52].copy_from_slice(&nonce.to_big_endian());
+    key
+}
+
 fn local_block_info_key(block_hash: &H256) -> Vec<u8> {
     append_suffix(block_hash, LOCAL_BLOCK_INFO_SUFFIX_BYTE)
 }
@@ -629,3 +683,31 @@ impl MallocSizeOf for DBManager {
             .size_of(ops)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::sender_nonce_key;
+    use cfx_types::{Address, U256};
+
+    #[test]
+    fn test_sender_nonce_key_order() {
+        let sender = Address::repeat_byte(0x01);
+        let next_sender = Address::repeat_byte(0x02);
+
+        let key = sender_nonce_key(&sender, &U256::from(1));
+        assert_eq!(&key[0..20], sender.as_bytes());
+        assert_eq!(U256::from_big_endian(&key[20..52]), U256::from(1));
+
+        // The keys of a sender are ordered by the nonce, before the keys of
+        // the next sender.
+        let keys = [
+            sender_nonce_key(&sender, &U256::zero()),
+            sender_nonce_key(&sender, &U256::from(1)),
+            sender_nonce_key(&sender, &U256::from(255)),
+            sender_nonce_key(&sender, &U256::from(256)),
+            sender_nonce_key(&sender, &U256::MAX),
+            sender_nonce_key(&next_sender, &U256::zero()),
+        ];
+        assert!(keys.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+}
@@ -13,7 +13,7 @@ use cfx_storage::{
     state_manager::StateIndex, utils::guarded_value::*, StorageManager,
     StorageManagerTrait,
 };
-use cfx_types::{Bloom, Space, H256};
+use cfx_types::{Address, Bloom, Space, H256, U256};
 pub use cfxcore_types::block_data_manager::block_data_types;
 use db::SystemDB;
 use malloc_size_of::{new_malloc_size_ops, MallocSizeOf, MallocSizeOfOps};
@@ -33,6 +33,8 @@ use std::{
 use threadpool::ThreadPool;
 pub mod db_gc_manager;
 pub mod db_manager;
+#[cfg(test)]
+mod tests;
 pub mod tx_data_manager;
 use crate::{
     block_data_manager::{
@@ -59,6 +61,9 @@ lazy_static! {
 }
 
 pub const NULLU64: u64 = !0;
+/// The number of epochs between two persisted progresses of the backfill of
+/// the sender and nonce index.
+const TX_SENDER_NONCE_INDEX_BACKFILL_BATCH: u64 = 1000;
 
 #[derive(DeriveMallocSizeOf)]
 pub struct InvalidBlockSet {
@@ -804,6 +809,123 @@ impl BlockDataManager {
         }
     }
 
+    /// Whether the eSpace transactions are indexed by their sender and nonce.
+    pub fn tx_sender_nonce_index_enabled(&self) -> bool {
+        self.config.persist_tx_sender_nonce_index
+    }
+
+    pub fn tx_hash_by_sender_nonce(
+        &self, sender: &Address, nonce: &U256,
+    ) -> Option<H256> {
+        if !self.config.persist_tx_sender_nonce_index {
+            return None;
+        }
+        self.db_manager
+            .tx_hash_by_sender_nonce_from_db(sender, nonce)
+    }
+
+    /// Index an executed eSpace transaction by its sender and nonce. An
+    /// executed transaction takes the nonce of its sender, so the index is
+    /// only overwritten if the transaction is executed again in another
+    /// epoch after a pivot chain reorg. A skipped transaction does not take
+    /// the nonce and is not indexed.
+    pub fn insert_tx_hash_by_sender_nonce(
+        &self, tx: &SignedTransaction, outcome_status: TransactionStatus,
+    ) {
+        if !self.config.persist_tx_sender_nonce_index
+            || tx.space() != Space::Ethereum
+            || outcome_status == TransactionStatus::Skipped
+        {
+            return;
+        }
+        self.db_manager.insert_tx_hash_by_sender_nonce_to_db(
+            &tx.sender,
+            tx.nonce(),
+            &tx.hash(),
+        );
+    }
+
+    /// Build the sender and nonce index for the epochs executed before the
+    /// index is enabled, up to `to_epoch`. The progress is persisted, so an
+    /// interrupted backfill resumes from where it stops. The epochs whose
+    /// block bodies or execution results have been garbage collected are
+    /// skipped.
+    pub fn backfill_tx_sender_nonce_index(&self, to_epoch: u64) {
+        if !self.config.persist_tx_sender_nonce_index {
+            return;
+        }
+        let start = self
+            .db_manager
+            .tx_sender_nonce_index_backfill_progress_from_db()
+            .unwrap_or(0)
+            .max(self.earliest_epoch_with_block_body())
+            .max(self.earliest_epoch_with_execution_result());
+        if start > to_epoch {
+            return;
+        }
+        info!(
+            "Backfill tx sender nonce index, range=[{}, {}]",
+            start, to_epoch
+        );
+
+        for epoch in start..=to_epoch {
+            self.index_epoch_tx_sender_nonce(epoch);
+            if (epoch + 1) % TX_SENDER_NONCE_INDEX_BACKFILL_BATCH == 0 {
+                self.db_manager
+                    .insert_tx_sender_nonce_index_backfill_progress_to_db(
+                        epoch + 1,
+                    );
+                debug!("Tx sender nonce index backfilled to epoch {}", epoch);
+            }
+        }
+        self.db_manager
+            .insert_tx_sender_nonce_index_backfill_progress_to_db(to_epoch + 1);
+        info!(
+            "Tx sender nonce index backfill finished at epoch {}",
+            to_epoch
+        );
+    }
+
+    fn index_epoch_tx_sender_nonce(&self, epoch: u64) {
+        let epoch_blocks = match self.executed_epoch_set_hashes_from_db(epoch) {
+            Some(blocks) => blocks,
+            None => {
+                debug!("Epoch set of epoch {} is missing", epoch);
+                return;
+            }
+        };
+        let pivot = match epoch_blocks.last() {
+            Some(pivot) => *pivot,
+            None => return,
+        };
+        for block_hash in &epoch_blocks {
+            let (block, result) = match (
+                self.block_by_hash(block_hash, false /* update_cache */),
+                self.block_execution_result_by_hash_with_epoch(
+                    block_hash, &pivot,
+                    false, /* update_pivot_assumption */
+                    false, /* update_cache */
+                ),
+            ) {
+                (Some(block), Some(result)) => (block, result),
+                _ => {
+                    debug!(
+                        "Block {:?} or its execution result is missing",
+                        block_hash
+                    );
+                    continue;
+                }
+            };
+            for (tx, receipt) in block
+                .transactions
+                .iter()
+                .zip(&result.block_receipts.receipts)
+            {
+                self.insert_tx_hash_by_sender_nonce(tx, receipt.outcome_status);
+            }
+        }
+    }
+
     pub fn hash_by_block_number(
         &self, block_number: u64, update_cache: bool,
     ) -> Option<H256> {
@@ -1308,6 +1430,10 @@ impl BlockDataManager {
                                 rpc_index: Some(rpc_index),
                             },
                         );
+                        self.insert_tx_hash_by_sender_nonce(
+                            tx,
+                            *outcome_status,
+                        );
 
                         for ptx in phantom_txs {
                             self.insert_transaction_index(
@@ -1830,6 +1956,7 @@ pub enum DbType {
 pub struct DataManagerConfiguration {
     pub persist_tx_index: bool,
     pub persist_block_number_index: bool,
+    pub persist_tx_sender_nonce_index: bool,
     pub tx_cache_index_maintain_timeout: Duration,
     pub db_type: DbType,
     pub additional_maintained_block_body_epoch_count: Option<usize>,
@@ -1853,6 +1980,7 @@ impl DataManagerConfiguration {
         Self {
             persist_tx_index,
             persist_block_number_index,
+            persist_tx_sender_nonce_index: false,
             tx_cache_index_maintain_timeout,
             db_type,
             additional_maintained_block_body_epoch_count: None,
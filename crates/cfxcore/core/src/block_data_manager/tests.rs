@@ -0,0 +1,225 @@
+// Copyright 2019 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use super::{BlockDataManager, DataManagerConfiguration, DbType};
+use crate::{
+    keylib::{Generator, KeyPair, Random},
+    pow::PowComputer,
+    sync::utils::initialize_data_manager_with_config,
+};
+use cfx_executor::machine::VmFactory;
+use cfx_types::{Address, H256, U256};
+use primitives::{
+    receipt::{BlockReceipts, TransactionStatus},
+    transaction::{native_transaction::NativeTransaction, Eip155Transaction},
+    Action, Block, BlockHeaderBuilder, Receipt, SignedTransaction, Transaction,
+};
+use std::{path::Path, sync::Arc, time::Duration};
+
+fn new_data_manager(
+    db_dir: &Path, persist_tx_sender_nonce_index: bool,
+) -> Arc<BlockDataManager> {
+    let mut config = DataManagerConfiguration::new(
+        true,  /* persist_tx_index */
+        false, /* persist_block_number_index */
+        Duration::from_millis(300_000),
+        DbType::Rocksdb,
+    );
+    config.persist_tx_sender_nonce_index = persist_tx_sender_nonce_index;
+    initialize_data_manager_with_config(
+        db_dir.to_str().unwrap(),
+        config,
+        Arc::new(PowComputer::new(true)),
+        VmFactory::new(1024 * 32),
+    )
+    .0
+}
+
+fn new_eth_tx(sender: &KeyPair, nonce: u64) -> Arc<SignedTransaction> {
+    let tx: Transaction = Eip155Transaction {
+        nonce: nonce.into(),
+        gas_price: U256::one(),
+        gas: 21_000.into(),
+        action: Action::Call(Address::random()),
+        value: U256::zero(),
+        chain_id: Some(1),
+        data: Vec::new(),
+    }
+    .into();
+    Arc::new(tx.sign(sender.secret()))
+}
+
+fn new_native_tx(sender: &KeyPair, nonce: u64) -> Arc<SignedTransaction> {
+    let tx: Transaction = NativeTransaction {
+        nonce: nonce.into(),
+        gas_price: U256::one(),
+        gas: 21_000.into(),
+        action: Action::Call(Address::random()),
+        value: U256::zero(),
+        storage_limit: 0,
+        epoch_height: 0,
+        chain_id: 1,
+        data: Vec::new(),
+    }
+    .into();
+    Arc::new(tx.sign(sender.secret()))
+}
+
+fn indexed_hash(
+    data_man: &BlockDataManager, tx: &SignedTransaction,
+) -> Option<H256> {
+    data_man.tx_hash_by_sender_nonce(&tx.sender, tx.nonce())
+}
+
+/// Insert an executed epoch with a single block which contains `txs`.
+fn insert_epoch(
+    data_man: &BlockDataManager, epoch: u64,
+    txs: Vec<(Arc<SignedTransaction>, TransactionStatus)>,
+) {
+    let mut header = BlockHeaderBuilder::new().with_height(epoch).build();
+    header.compute_hash();
+    let (transactions, statuses): (Vec<_>, Vec<_>) = txs.into_iter().unzip();
+    let block = Arc::new(Block::new(header, transactions));
+    let hash = block.hash();
+    let receipts = BlockReceipts {
+        receipts: statuses
+            .into_iter()
+            .map(|outcome_status| Receipt {
+                outcome_status,
+                ..Default::default()
+            })
+            .collect(),
+        block_number: epoch,
+        secondary_reward: U256::zero(),
+        tx_execution_error_messages: vec![],
+    };
+    data_man.insert_block(block, true /* persistent */);
+    data_man.insert_block_execution_result(
+        hash,
+        hash,
+        Arc::new(receipts),
+        true, /* persistent */
+    );
+    data_man.insert_executed_epoch_set_hashes_to_db(epoch, &vec![hash]);
+}
+
+#[test]
+fn test_index_executed_tx_by_sender_nonce() {
+    let db_dir = tempfile::tempdir().unwrap();
+    let data_man = new_data_manager(db_dir.path(), true);
+    let sender = Random.generate().unwrap();
+
+    let success = new_eth_tx(&sender, 0);
+    data_man
+        .insert_tx_hash_by_sender_nonce(&success, TransactionStatus::Success);
+    assert_eq!(indexed_hash(&data_man, &success), Some(success.hash()));
+
+    // A failed transaction still takes the nonce.
+    let failure = new_eth_tx(&sender, 1);
+    data_man
+        .insert_tx_hash_by_sender_nonce(&failure, TransactionStatus::Failure);
+    assert_eq!(indexed_hash(&data_man, &failure), Some(failure.hash()));
+
+    let skipped = new_eth_tx(&sender, 2);
+    data_man
+        .insert_tx_hash_by_sender_nonce(&skipped, TransactionStatus::Skipped);
+    assert_eq!(indexed_hash(&data_man, &skipped), None);
+
+    let native = new_native_tx(&sender, 3);
+    data_man
+        .insert_tx_hash_by_sender_nonce(&native, TransactionStatus::Success);
+    assert_eq!(indexed_hash(&data_man, &native), None);
+
+    // The same nonce executed again after a reorg overwrites the index.
+    let replacement = new_eth_tx(&sender, 1);
+    data_man.insert_tx_hash_by_sender_nonce(
+        &replacement,
+        TransactionStatus::Success,
+    );
+    assert_eq!(indexed_hash(&data_man, &failure), Some(replacement.hash()));
+}
+
+#[test]
+fn test_tx_sender_nonce_index_disabled() {
+    let db_dir = tempfile::tempdir().unwrap();
+    let data_man = new_data_manager(db_dir.path(), false);
+    assert!(!data_man.tx_sender_nonce_index_enabled());
+
+    let tx = new_eth_tx(&Random.generate().unwrap(), 0);
+    data_man.insert_tx_hash_by_sender_nonce(&tx, TransactionStatus::Success);
+    insert_epoch(&data_man, 1, vec![(tx.clone(), TransactionStatus::Success)]);
+    data_man.backfill_tx_sender_nonce_index(1);
+
+    assert_eq!(indexed_hash(&data_man, &tx), None);
+    assert_eq!(
+        data_man
+            .db_manager
+            .tx_hash_by_sender_nonce_from_db(&tx.sender, tx.nonce()),
+        None
+    );
+    assert_eq!(
+        data_man
+            .db_manager
+            .tx_sender_nonce_index_backfill_progress_from_db(),
+        None
+    );
+}
+
+#[test]
+fn test_backfill_tx_sender_nonce_index() {
+    let db_dir = tempfile::tempdir().unwrap();
+    let data_man = new_data_manager(db_dir.path(), true);
+    let sender = Random.generate().unwrap();
+
+    let txs: Vec<_> = (0..4).map(|nonce| new_eth_tx(&sender, nonce)).collect();
+    let skipped = new_eth_tx(&sender, 4);
+    for (epoch, tx) in (1..).zip(&txs[..3]) {
+        insert_epoch(
+            &data_man,
+            epoch,
+            vec![(tx.clone(), TransactionStatus::Success)],
+        );
+    }
+    insert_epoch(
+        &data_man,
+        4,
+        vec![
+            (txs[3].clone(), TransactionStatus::Success),
+            (skipped.clone(), TransactionStatus::Skipped),
+        ],
+    );
+
+    // An interrupted backfill persisted that epoch 3 is the next to process.
+    data_man
+        .db_manager
+        .insert_tx_sender_nonce_index_backfill_progress_to_db(3);
+    data_man.backfill_tx_sender_nonce_index(3);
+
+    assert_eq!(indexed_hash(&data_man, &txs[0]), None);
+    assert_eq!(indexed_hash(&data_man, &txs[1]), None);
+    assert_eq!(indexed_hash(&data_man, &txs[2]), Some(txs[2].hash()));
+    assert_eq!(indexed_hash(&data_man, &txs[3]), None);
+    assert_eq!(
+        data_man
+            .db_manager
+            .tx_sender_nonce_index_backfill_progress_from_db(),
+        Some(4)
+    );
+
+    // The next run resumes from epoch 4 and does not redo the earlier ones.
+    data_man.backfill_tx_sender_nonce_index(4);
+    assert_eq!(indexed_hash(&data_man, &txs[0]), None);
+    assert_eq!(indexed_hash(&data_man, &txs[3]), Some(txs[3].hash()));
+    assert_eq!(indexed_hash(&data_man, &skipped), None);
+    assert_eq!(
+        data_man
+            .db_manager
+            .tx_sender_nonce_index_backfill_progress_from_db(),
+        Some(5)
+    );
+
+    // Nothing is left to backfill.
+    data_man.backfill_tx_sender_nonce_index(4);
+    assert_eq!(indexed_hash(&data_man, &txs[1]), None);
+}
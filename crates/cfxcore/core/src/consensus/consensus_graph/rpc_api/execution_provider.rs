@@ -2,17 +2,25 @@ use super::super::ConsensusGraph;
 
 use crate::{
     block_data_manager::BlockExecutionResultWithEpoch,
+    consensus::consensus_inner::consensus_executor::{
+        PendingBlockExecution, SimulateBlock, SimulatedBlockOutcome,
+    },
     errors::Result as CoreResult,
 };
 use cfx_execute_helper::estimation::{EstimateExt, EstimateRequest};
-use cfx_executor::executive::ExecutionOutcome;
+use cfx_executor::{
+    executive::ExecutionOutcome,
+    state::{SavedState, State},
+};
 use cfx_parameters::rpc::{
     GAS_PRICE_BLOCK_SAMPLE_SIZE, GAS_PRICE_DEFAULT_VALUE,
     GAS_PRICE_TRANSACTION_SAMPLE_SIZE,
 };
-use cfx_rpc_eth_types::EvmOverrides;
+use cfx_rpc_eth_types::{BlockOverrides, EvmOverrides};
 use cfx_types::{Space, H256, U256};
+use cfx_vm_types::Env;
 use primitives::{EpochNumber, SignedTransaction};
+use std::sync::Arc;
 
 impl ConsensusGraph {
     /// Get the average gas price of the last GAS_PRICE_TRANSACTION_SAMPLE_SIZE
@@ -147,4 +155,85 @@ impl ConsensusGraph {
             evm_overrides,
         )
     }
+
+    pub fn simulate_virtual(
+        &self, blocks: Vec<SimulateBlock>, epoch: EpochNumber, validation: bool,
+    ) -> CoreResult<Vec<SimulatedBlockOutcome>> {
+        // only allow to simulate against stated epoch
+        self.validate_stated_epoch(&epoch)?;
+        let (epoch_id, epoch_size) = if let Ok(v) =
+            self.get_block_hashes_by_epoch(epoch)
+        {
+            (v.last().expect("pivot block always exist").clone(), v.len())
+        } else {
+            bail!("cannot get block hashes in the specified epoch, maybe it does not exist?");
+        };
+        self.executor
+            .simulate_virtual(&epoch_id, epoch_size, blocks, validation)
+    }
+
+    /// Executes the pending transactions as the block following `epoch`, see
+    /// `ConsensusExecutionHandler::execute_pending_block`.
+    pub fn execute_pending_block(
+        &self, epoch: EpochNumber, transactions: Vec<Arc<SignedTransaction>>,
+        block_overrides: BlockOverrides,
+    ) -> CoreResult<PendingBlockExecution> {
+        self.validate_stated_epoch(&epoch)?;
+        let (epoch_id, epoch_size) = if let Ok(v) =
+            self.get_block_hashes_by_epoch(epoch)
+        {
+            (v.last().expect("pivot block always exist").clone(), v.len())
+        } else {
+            bail!("cannot get block hashes in the specified epoch, maybe it does not exist?");
+        };
+        self.executor.execute_pending_block(
+            &epoch_id,
+            epoch_size,
+            transactions,
+            block_overrides,
+        )
+    }
+
+    /// Opens the state of the epoch `epoch_id` with the changes of a pending
+    /// block built on it.
+    pub fn pending_block_state(
+        &self, epoch_id: &H256, saved_state: &SavedState,
+    ) -> CoreResult<State> {
+        self.executor.pending_block_state(epoch_id, saved_state)
+    }
+
+    pub fn call_virtual_on_state(
+        &self, state: &mut State, env: &Env, tx: &SignedTransaction,
+        request: EstimateRequest, evm_overrides: EvmOverrides,
+    ) -> CoreResult<(ExecutionOutcome, EstimateExt)> {
+        self.executor.call_virtual_on_state(
+            state,
+            env,
+            tx,
+            request,
+            evm_overrides,
+        )
+    }
+
+    /// Executes a virtual call on top of `state`, which holds the state of
+    /// the pivot block `epoch_id`, see
+    /// `ConsensusExecutionHandler::call_virtual_on_epoch_state`.
+    pub fn call_virtual_on_epoch_state(
+        &self, state: &mut State, epoch_id: &H256, tx: &SignedTransaction,
+        request: EstimateRequest, evm_overrides: EvmOverrides,
+    ) -> CoreResult<(ExecutionOutcome, EstimateExt)> {
+        // the transaction is executed as the first one of the next epoch
+        let block_number = match self.get_block_number(epoch_id)? {
+            Some(number) => number + 1,
+            None => bail!("cannot obtain the block number of {:?}", epoch_id),
+        };
+        self.executor.call_virtual_on_epoch_state(
+            state,
+            epoch_id,
+            block_number,
+            tx,
+            request,
+            evm_overrides,
+        )
+    }
 }
@@ -92,7 +92,8 @@ impl ConsensusGraph {
         self.txpool.set_ready_for_mining();
         self.txpool
             .notify_new_best_info(self.best_info.read_recursive().clone())
-            .expect("No DB error")
+            .expect("No DB error");
+        self.txpool.restore_journal();
     }
 
     pub fn set_initial_sequence_number(&self, initial_sn: u64) {
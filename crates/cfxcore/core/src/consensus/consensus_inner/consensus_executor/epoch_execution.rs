@@ -283,7 +283,7 @@ impl ConsensusExecutionHandler {
         let spec = machine.spec(env.number, env.epoch_height);
 
         let options = TransactOptions {
-            observer: self.make_observer(transaction, block_context),
+            observer: self.make_observer(transaction, rpc_index, block_context),
             settings: TransactSettings::all_checks(),
         };
 
@@ -313,6 +313,7 @@ impl ConsensusExecutionHandler {
         }
 
         let tx_skipped = r.receipt.tx_skipped();
+        let outcome_status = r.receipt.outcome_status;
         let phantom_txs = r.phantom_txs.clone();
 
         recorder.receive_tx_outcome(r, transaction, block_context);
@@ -333,6 +334,8 @@ impl ConsensusExecutionHandler {
                 rpc_index: Some(rpc_index),
             },
         );
+        self.data_man
+            .insert_tx_hash_by_sender_nonce(transaction, outcome_status);
 
         // persist tx index for phantom transactions.
         // note: in some cases, pivot chain reorgs will result in
@@ -361,11 +364,12 @@ impl ConsensusExecutionHandler {
     }
 
     fn make_observer(
-        &self, transaction: &Arc<SignedTransaction>,
+        &self, transaction: &Arc<SignedTransaction>, rpc_index: usize,
         block_context: &BlockProcessContext,
     ) -> Observer {
         use alloy_rpc_types_trace::geth::{
-            GethDebugBuiltInTracerType::*, GethDebugTracerType::BuiltInTracer,
+            GethDebugBuiltInTracerType::*,
+            GethDebugTracerType::{BuiltInTracer, JsTracer},
         };
 
         let mut observer = if self.config.executive_trace {
@@ -382,21 +386,31 @@ impl ConsensusExecutionHandler {
             let support_tracer = matches!(
                 task.opts.tracer,
                 Some(BuiltInTracer(
-                    FourByteTracer | CallTracer | PreStateTracer | NoopTracer
-                )) | None
+                    FourByteTracer
+                        | CallTracer
+                        | PreStateTracer
+                        | NoopTracer
+                        | MuxTracer
+                        | FlatCallTracer
+                        | Erc7562Tracer
+                )) | Some(JsTracer(_))
+                    | None
             );
             let tx_gas_limit = transaction.gas_limit().as_u64();
 
             if need_trace && support_tracer {
+                let pivot_block = block_context.epoch_context.pivot_block;
                 observer.geth_tracer = Some(GethTracer::new(
                     TxExecContext {
                         tx_gas_limit,
-                        block_height: block_context
-                            .epoch_context
-                            .pivot_block
-                            .block_header
-                            .height(),
+                        block_height: pivot_block.block_header.height(),
                         block_number: block_context.block_number,
+                        block_hash: pivot_block.hash(),
+                        tx_hash: transaction.hash(),
+                        tx_index: rpc_index as u64,
+                        gas_price: u64::try_from(*transaction.gas_price())
+                            .unwrap_or(u64::MAX),
+                        block_timestamp: pivot_block.block_header.timestamp(),
                     },
                     Arc::clone(&self.machine),
                     task.opts.clone(),
@@ -3,6 +3,7 @@
 // See http://www.gnu.org/licenses/
 
 mod epoch_execution;
+mod simulate;
 
 use core::convert::TryFrom;
 use std::{
@@ -65,16 +66,20 @@ use cfx_executor::{
     executive::{ExecutionOutcome, ExecutiveContext},
     machine::Machine,
     state::{
-        distribute_pos_interest, update_pos_status, State, StateCommitResult,
+        distribute_pos_interest, update_pos_status, SavedState, State,
+        StateCommitResult,
     },
 };
 use cfx_vm_types::{Env, Spec};
 use geth_tracer::GethTraceWithHash;
 
 use alloy_rpc_types_trace::geth::GethDebugTracingOptions;
-use cfx_rpc_eth_types::EvmOverrides;
+use cfx_rpc_eth_types::{BlockOverrides, EvmOverrides};
 
 use self::epoch_execution::{GethTask, VirtualCall};
+pub use self::simulate::{
+    PendingBlockExecution, SimulateBlock, SimulatedBlockOutcome,
+};
 
 lazy_static! {
     static ref CONSENSIS_EXECUTION_TIMER: Arc<dyn Meter> =
@@ -653,6 +658,61 @@ impl ConsensusExecutor {
         )
     }
 
+    pub fn simulate_virtual(
+        &self, epoch_id: &H256, epoch_size: usize, blocks: Vec<SimulateBlock>,
+        validation: bool,
+    ) -> CoreResult<Vec<SimulatedBlockOutcome>> {
+        self.handler
+            .simulate_virtual(epoch_id, epoch_size, blocks, validation)
+    }
+
+    pub fn execute_pending_block(
+        &self, epoch_id: &H256, epoch_size: usize,
+        transactions: Vec<Arc<SignedTransaction>>,
+        block_overrides: BlockOverrides,
+    ) -> CoreResult<PendingBlockExecution> {
+        self.handler.execute_pending_block(
+            epoch_id,
+            epoch_size,
+            transactions,
+            block_overrides,
+        )
+    }
+
+    pub fn pending_block_state(
+        &self, epoch_id: &H256, saved_state: &SavedState,
+    ) -> CoreResult<State> {
+        self.handler.pending_block_state(epoch_id, saved_state)
+    }
+
+    pub fn call_virtual_on_state(
+        &self, state: &mut State, env: &Env, tx: &SignedTransaction,
+        request: EstimateRequest, evm_overrides: EvmOverrides,
+    ) -> CoreResult<(ExecutionOutcome, EstimateExt)> {
+        self.handler.call_virtual_on_state(
+            state,
+            env,
+            tx,
+            request,
+            evm_overrides,
+        )
+    }
+
+    pub fn call_virtual_on_epoch_state(
+        &self, state: &mut State, epoch_id: &H256, block_number: u64,
+        tx: &SignedTransaction, request: EstimateRequest,
+        evm_overrides: EvmOverrides,
+    ) -> CoreResult<(ExecutionOutcome, EstimateExt)> {
+        self.handler.call_virtual_on_epoch_state(
+            state,
+            epoch_id,
+            block_number,
+            tx,
+            request,
+            evm_overrides,
+        )
+    }
+
     pub fn collect_blocks_geth_trace(
         &self, epoch_id: H256, epoch_num: u64, blocks: &Vec<Arc<Block>>,
         opts: GethDebugTracingOptions, tx_hash: Option<H256>,
@@ -0,0 +1,401 @@
+use super::ConsensusExecutionHandler;
+
+use cfx_execute_helper::estimation::{
+    EstimateExt, EstimateRequest, EstimationContext,
+};
+use cfx_executor::{
+    executive::{ExecutionOutcome, ExecutiveContext},
+    state::{SavedState, State},
+};
+use cfx_rpc_eth_types::{BlockOverrides, EvmOverrides};
+use cfx_types::{AllChainID, Space, H256, U256};
+use cfx_vm_types::Env;
+use primitives::{Receipt, SignedTransaction};
+use std::sync::Arc;
+
+use crate::{
+    errors::{invalid_params_check, Error as CoreError, Result as CoreResult},
+    verification::{VerifyTxLocalMode, VerifyTxMode},
+};
+
+/// A block of eSpace transactions to be executed by
+/// [`ConsensusExecutionHandler::simulate_virtual`].
+pub struct SimulateBlock {
+    pub transactions: Vec<(SignedTransaction, EstimateRequest)>,
+    /// The state overrides are applied on top of the state left by the
+    /// previous blocks, the block overrides replace the default environment.
+    pub evm_overrides: EvmOverrides,
+}
+
+/// The outcome of a [`SimulateBlock`].
+pub struct SimulatedBlockOutcome {
+    /// The environment the transactions are executed in.
+    pub env: Env,
+    /// The transactions with the sender and the nonce actually used, and
+    /// their outcomes.
+    pub transactions: Vec<(SignedTransaction, ExecutionOutcome)>,
+}
+
+/// The pending block executed by
+/// [`ConsensusExecutionHandler::execute_pending_block`].
+pub struct PendingBlockExecution {
+    pub env: Env,
+    /// The transactions executed in the pending block. The transactions which
+    /// can not be executed on the state are left out.
+    pub transactions: Vec<Arc<SignedTransaction>>,
+    pub receipts: Vec<Receipt>,
+    pub errors: Vec<String>,
+    /// The changes made by the transactions to the state of the epoch, see
+    /// [`ConsensusExecutionHandler::pending_block_state`].
+    pub saved_state: SavedState,
+}
+
+impl ConsensusExecutionHandler {
+    /// Executes the blocks one after another on top of the state of
+    /// `epoch_id`. Unlike `call_virtual`, the state is shared, so each
+    /// transaction sees the effects of all the transactions before it. Each
+    /// block follows the previous one, with the block number and the
+    /// timestamp increased by one, unless they are overridden.
+    pub fn simulate_virtual(
+        &self, epoch_id: &H256, epoch_size: usize, blocks: Vec<SimulateBlock>,
+        validation: bool,
+    ) -> CoreResult<Vec<SimulatedBlockOutcome>> {
+        let (outcomes, _state) =
+            self.simulate_on_state(epoch_id, epoch_size, blocks, validation)?;
+        Ok(outcomes)
+    }
+
+    /// Executes the pending transactions as the block following `epoch_id`,
+    /// with the nonce, the balance and the base price checked as if they were
+    /// packed. The changes made by the transactions are kept, so the queries
+    /// against the pending block can be answered from them.
+    pub fn execute_pending_block(
+        &self, epoch_id: &H256, epoch_size: usize,
+        transactions: Vec<Arc<SignedTransaction>>,
+        block_overrides: BlockOverrides,
+    ) -> CoreResult<PendingBlockExecution> {
+        let request = EstimateRequest {
+            has_sender: true,
+            has_gas_limit: true,
+            has_gas_price: true,
+            has_nonce: true,
+            has_storage_limit: true,
+            collect_access_list: false,
+        };
+        let block = SimulateBlock {
+            transactions: transactions
+                .iter()
+                .map(|tx| ((**tx).clone(), request))
+                .collect(),
+            evm_overrides: EvmOverrides::new(
+                None,
+                Some(Box::new(block_overrides)),
+            ),
+        };
+        let (mut outcomes, mut state) =
+            self.simulate_on_state(epoch_id, epoch_size, vec![block], true)?;
+        let outcome = outcomes.pop().expect("one block is simulated");
+
+        let spec = self
+            .machine
+            .spec(outcome.env.number, outcome.env.epoch_height);
+        let mut accumulated_gas_used = U256::zero();
+        let mut executed = Vec::with_capacity(transactions.len());
+        let mut receipts = Vec::with_capacity(transactions.len());
+        let mut errors = Vec::with_capacity(transactions.len());
+        for (tx, (_, tx_outcome)) in
+            transactions.into_iter().zip(outcome.transactions)
+        {
+            if tx_outcome.try_as_executed().is_none() {
+                debug!(
+                    "Skip pending transaction {:?}: {:?}",
+                    tx.hash(),
+                    tx_outcome
+                );
+                continue;
+            }
+            errors.push(tx_outcome.error_message());
+            receipts.push(
+                tx_outcome.make_receipt(&mut accumulated_gas_used, &spec),
+            );
+            executed.push(tx);
+        }
+
+        Ok(PendingBlockExecution {
+            env: outcome.env,
+            transactions: executed,
+            receipts,
+            errors,
+            saved_state: state.save(),
+        })
+    }
+
+    /// Opens the state of `epoch_id` with the changes of a pending block
+    /// built on it. Each caller gets its own copy, so the queries against the
+    /// pending block do not wait for each other.
+    pub fn pending_block_state(
+        &self, epoch_id: &H256, saved_state: &SavedState,
+    ) -> CoreResult<State> {
+        let best_block_header =
+            match self.data_man.block_header_by_hash(epoch_id) {
+                Some(header) => header,
+                None => bail!("invalid epoch id"),
+            };
+        let statedb = self.get_statedb_by_epoch_id_and_space(
+            epoch_id,
+            best_block_header.height(),
+            Some(Space::Ethereum),
+        )?;
+        let mut state = State::new(statedb)?;
+        state.restore(saved_state.clone());
+        Ok(state)
+    }
+
+    /// Executes a virtual call on top of `state`, e.g. the state of a pending
+    /// block, in the environment `env`. The state is left unchanged.
+    pub fn call_virtual_on_state(
+        &self, state: &mut State, env: &Env, tx: &SignedTransaction,
+        request: EstimateRequest, evm_overrides: EvmOverrides,
+    ) -> CoreResult<(ExecutionOutcome, EstimateExt)> {
+        let mut env = Env {
+            accumulated_gas_used: U256::zero(),
+            gas_limit: *tx.gas(),
+            transaction_hash: tx.hash(),
+            ..env.clone()
+        };
+        if let Some(block_overrides) = evm_overrides.block {
+            ExecutiveContext::apply_env_overrides(&mut env, block_overrides);
+        }
+        let spec = self.machine.spec(env.number, env.epoch_height);
+        invalid_params_check(
+            "tx",
+            self.verification_config.verify_transaction_common(
+                tx,
+                AllChainID::fake_for_virtual(tx.chain_id().unwrap_or(1)),
+                env.epoch_height,
+                &self.machine.params().transition_heights,
+                VerifyTxMode::Local(VerifyTxLocalMode::Full, &spec),
+            ),
+        )?;
+
+        let saved = state.save();
+        let r = match &evm_overrides.state {
+            Some(state_overrides) => {
+                state.apply_override_post_execution(state_overrides, tx.space())
+            }
+            None => Ok(()),
+        }
+        .and_then(|()| {
+            EstimationContext::new(state, &env, self.machine.as_ref(), &spec)
+                .transact_virtual(tx.clone(), request)
+        });
+        state.restore(saved);
+        trace!("Execution result {:?}", r);
+        Ok(r?)
+    }
+
+    /// Executes a virtual call on top of `state`, which holds the state of
+    /// the epoch `epoch_id`, as if the transaction were packed in the block
+    /// numbered `block_number` of the next epoch. Unlike `call_virtual`, the
+    /// epoch execution context is not needed, so the state can be served by
+    /// other sources, e.g. retrieved on demand by light nodes.
+    pub fn call_virtual_on_epoch_state(
+        &self, state: &mut State, epoch_id: &H256, block_number: u64,
+        tx: &SignedTransaction, request: EstimateRequest,
+        evm_overrides: EvmOverrides,
+    ) -> CoreResult<(ExecutionOutcome, EstimateExt)> {
+        let best_block_header =
+            match self.data_man.block_header_by_hash(epoch_id) {
+                Some(header) => header,
+                None => bail!("invalid epoch id"),
+            };
+        let block_height = best_block_header.height() + 1;
+
+        let pos_id = best_block_header.pos_reference().as_ref();
+        let pos_view_number =
+            pos_id.and_then(|id| self.pos_verifier.get_pos_view(id));
+        let pivot_decision_epoch = pos_id
+            .and_then(|id| self.pos_verifier.get_pivot_decision(id))
+            .and_then(|hash| self.data_man.block_header_by_hash(&hash))
+            .map(|header| header.height());
+
+        let base_gas_price = best_block_header.base_price().unwrap_or_default();
+        let burnt_gas_price =
+            base_gas_price.map_all(|x| state.burnt_gas_price(x));
+
+        let env = Env {
+            chain_id: self.machine.params().chain_id_map(block_height),
+            number: block_number,
+            author: *best_block_header.author(),
+            timestamp: best_block_header.timestamp(),
+            difficulty: Default::default(),
+            accumulated_gas_used: U256::zero(),
+            last_hash: *epoch_id,
+            gas_limit: *tx.gas(),
+            epoch_height: block_height,
+            pos_view: pos_view_number,
+            finalized_epoch: pivot_decision_epoch,
+            transaction_epoch_bound: self
+                .verification_config
+                .transaction_epoch_bound,
+            base_gas_price,
+            burnt_gas_price,
+            ..Default::default()
+        };
+        self.call_virtual_on_state(state, &env, tx, request, evm_overrides)
+    }
+
+    fn simulate_on_state(
+        &self, epoch_id: &H256, epoch_size: usize, blocks: Vec<SimulateBlock>,
+        validation: bool,
+    ) -> CoreResult<(Vec<SimulatedBlockOutcome>, State)> {
+        let best_block_header =
+            match self.data_man.block_header_by_hash(epoch_id) {
+                Some(header) => header,
+                None => bail!("invalid epoch id"),
+            };
+        let block_height = best_block_header.height() + 1;
+
+        let pos_id = best_block_header.pos_reference().as_ref();
+        let pos_view_number =
+            pos_id.and_then(|id| self.pos_verifier.get_pos_view(id));
+        let pivot_decision_epoch = pos_id
+            .and_then(|id| self.pos_verifier.get_pivot_decision(id))
+            .and_then(|hash| self.data_man.block_header_by_hash(&hash))
+            .map(|header| header.height());
+
+        let start_block_number = match self.data_man.get_epoch_execution_context(epoch_id) {
+            Some(v) => v.start_block_number + epoch_size as u64,
+            None => bail!("cannot obtain the execution context. Database is potentially corrupted!"),
+        };
+        let transitions = &self.machine.params().transition_heights;
+
+        let statedb = self.get_statedb_by_epoch_id_and_space(
+            epoch_id,
+            best_block_header.height(),
+            Some(Space::Ethereum),
+        )?;
+        let mut state = State::new(statedb)?;
+
+        let first_env = Env {
+            chain_id: self.machine.params().chain_id_map(block_height),
+            number: start_block_number,
+            author: *best_block_header.author(),
+            timestamp: best_block_header.timestamp(),
+            difficulty: Default::default(),
+            accumulated_gas_used: U256::zero(),
+            last_hash: *epoch_id,
+            gas_limit: *best_block_header.gas_limit(),
+            epoch_height: block_height,
+            pos_view: pos_view_number,
+            finalized_epoch: pivot_decision_epoch,
+            transaction_epoch_bound: self
+                .verification_config
+                .transaction_epoch_bound,
+            base_gas_price: best_block_header.base_price().unwrap_or_default(),
+            ..Default::default()
+        };
+
+        let mut outcomes: Vec<SimulatedBlockOutcome> =
+            Vec::with_capacity(blocks.len());
+        for block in blocks {
+            let mut env = match outcomes.last() {
+                Some(SimulatedBlockOutcome { env: parent, .. }) => Env {
+                    number: parent.number + 1,
+                    timestamp: parent.timestamp + 1,
+                    epoch_height: parent.epoch_height + 1,
+                    accumulated_gas_used: U256::zero(),
+                    ..parent.clone()
+                },
+                None => first_env.clone(),
+            };
+            if let Some(block_overrides) = block.evm_overrides.block {
+                // The eSpace blocks are numbered by the epoch height.
+                if let Some(number) = block_overrides.number {
+                    env.epoch_height = number.as_u64();
+                }
+                ExecutiveContext::apply_env_overrides(
+                    &mut env,
+                    block_overrides,
+                );
+            }
+            // The blocks follow each other, the first one follows the epoch.
+            // Only the first block may share the timestamp of its parent, as
+            // it does by default.
+            let (parent_height, parent_timestamp, timestamp_ordered) =
+                match outcomes.last() {
+                    Some(SimulatedBlockOutcome { env: parent, .. }) => (
+                        parent.epoch_height,
+                        parent.timestamp,
+                        env.timestamp > parent.timestamp,
+                    ),
+                    None => (
+                        best_block_header.height(),
+                        best_block_header.timestamp(),
+                        env.timestamp >= best_block_header.timestamp(),
+                    ),
+                };
+            if env.epoch_height <= parent_height {
+                bail!(CoreError::InvalidParam(
+                    "blockOverrides".into(),
+                    format!(
+                        "block number {} is not greater than the number {} of its parent",
+                        env.epoch_height, parent_height
+                    ),
+                ));
+            }
+            if !timestamp_ordered {
+                bail!(CoreError::InvalidParam(
+                    "blockOverrides".into(),
+                    format!(
+                        "the timestamp {} of block {} does not follow the timestamp {} of its parent",
+                        env.timestamp, env.epoch_height, parent_timestamp
+                    ),
+                ));
+            }
+            env.burnt_gas_price =
+                env.base_gas_price.map_all(|x| state.burnt_gas_price(x));
+
+            if let Some(state_overrides) = &block.evm_overrides.state {
+                state.apply_override_post_execution(
+                    state_overrides,
+                    Space::Ethereum,
+                )?;
+            }
+
+            let spec = self.machine.spec(env.number, env.epoch_height);
+            let mut transactions = Vec::with_capacity(block.transactions.len());
+            for (mut tx, request) in block.transactions {
+                invalid_params_check(
+                    "tx",
+                    self.verification_config.verify_transaction_common(
+                        &tx,
+                        AllChainID::fake_for_virtual(
+                            tx.chain_id().unwrap_or(1),
+                        ),
+                        env.epoch_height,
+                        transitions,
+                        VerifyTxMode::Local(VerifyTxLocalMode::Full, &spec),
+                    ),
+                )?;
+
+                env.transaction_hash = tx.hash();
+                let outcome = EstimationContext::new(
+                    &mut state,
+                    &env,
+                    self.machine.as_ref(),
+                    &spec,
+                )
+                .transact_simulated(&mut tx, request, validation)?;
+                trace!("Simulation result {:?}", outcome);
+
+                env.accumulated_gas_used += outcome.gas_used();
+                transactions.push((tx, outcome));
+            }
+
+            outcomes.push(SimulatedBlockOutcome { env, transactions });
+        }
+
+        Ok((outcomes, state))
+    }
+}
@@ -13,6 +13,9 @@ pub mod pos_handler;
 mod statistics;
 
 pub use crate::consensus::consensus_inner::{
+    consensus_executor::{
+        PendingBlockExecution, SimulateBlock, SimulatedBlockOutcome,
+    },
     ConsensusGraphInner, ConsensusInnerConfig,
 };
 
@@ -0,0 +1,179 @@
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write},
+};
+
+use crate::hash::{keccak, H256};
+
+use super::{PivotHintHeader, PivotHintPage, HEADER_LENGTH};
+
+/// Parameters of a pivot hint file to generate.
+#[derive(Clone, Copy, Debug)]
+pub struct PivotHintParams {
+    /// Number of consecutive blocks in each page
+    pub page_interval: u64,
+    /// Full hashes are recorded every `major_interval` heights
+    pub major_interval: u64,
+    /// Hash prefixes are recorded every `minor_interval` heights
+    pub minor_interval: u64,
+    /// Length of the recorded hash prefixes
+    pub minor_hash_length: usize,
+}
+
+/// Result of verifying a pivot hint file against a pivot chain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PivotHintVerification {
+    /// Keccak hash of the Page Digests Part of the file
+    pub checksum: H256,
+    /// Upper bound (exclusive) of block heights recorded in the file
+    pub range_max: u64,
+    /// The first page which does not match the pivot chain
+    pub first_mismatch: Option<PivotHintMismatch>,
+}
+
+/// A page of a pivot hint file which does not match the pivot chain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PivotHintMismatch {
+    pub page_number: u64,
+    /// The first height with a different hash, or `None` if the page content
+    /// does not match its digest.
+    pub height: Option<u64>,
+}
+
+/// Writes a pivot hint file which records the pivot block hashes below
+/// `range_max`, and returns its checksum (the keccak hash of the Page Digests
+/// Part) to be pinned in the config.
+///
+/// # Arguments
+/// * `pivot_hash_at` - Callback to retrieve the pivot block hash at the
+///   specified height
+pub fn generate_pivot_hint(
+    file_path: &str, params: PivotHintParams, range_max: u64,
+    mut pivot_hash_at: impl FnMut(u64) -> Result<H256, String>,
+) -> Result<H256, String> {
+    let header = PivotHintHeader {
+        minor_interval: params.minor_interval,
+        major_interval: params.major_interval,
+        page_interval: params.page_interval,
+        range_max,
+        minor_hash_length: params.minor_hash_length,
+    };
+    header.check_params()?;
+
+    let file = File::create(file_path)
+        .map_err(|e| format!("Cannot create file: {:?}", e))?;
+    let mut writer = BufWriter::new(file);
+    let write_err = |e: std::io::Error| format!("Cannot write file: {:?}", e);
+
+    // The page digests are only known after all pages are written, so the
+    // space is reserved and filled later.
+    let page_number = header.page_number();
+    writer.write_all(&header.to_raw()).map_err(write_err)?;
+    writer
+        .write_all(&vec![0u8; page_number * 32])
+        .map_err(write_err)?;
+
+    let mut raw_page_digests = Vec::with_capacity(page_number * 32);
+    for page in 0..page_number as u64 {
+        let page_content = make_page(&header, page, &mut pivot_hash_at)?;
+        raw_page_digests.extend_from_slice(&keccak(&page_content).0);
+        writer.write_all(&page_content).map_err(write_err)?;
+    }
+
+    writer
+        .seek(SeekFrom::Start(HEADER_LENGTH as u64))
+        .map_err(write_err)?;
+    writer.write_all(&raw_page_digests).map_err(write_err)?;
+    writer.flush().map_err(write_err)?;
+
+    Ok(keccak(&raw_page_digests))
+}
+
+/// Verifies every page of a pivot hint file against the pivot chain, and
+/// reports the first mismatched page.
+///
+/// # Arguments
+/// * `pivot_hash_at` - Callback to retrieve the pivot block hash at the
+///   specified height
+pub fn verify_pivot_hint(
+    file_path: &str, mut pivot_hash_at: impl FnMut(u64) -> Result<H256, String>,
+) -> Result<PivotHintVerification, String> {
+    let file = File::open(file_path)
+        .map_err(|e| format!("Cannot open file: {:?}", e))?;
+    let mut reader = BufReader::new(file);
+
+    let mut raw_header = [0u8; HEADER_LENGTH];
+    reader
+        .read_exact(&mut raw_header)
+        .map_err(|e| format!("Cannot load header: {:?}", e))?;
+    let header = PivotHintHeader::from_raw(raw_header)
+        .map_err(|e| format!("Cannot parse and check header: {}", e))?;
+
+    let mut raw_page_digests = vec![0u8; header.page_number() * 32];
+    reader
+        .read_exact(&mut raw_page_digests)
+        .map_err(|e| format!("Cannot load page digests: {:?}", e))?;
+
+    let mut first_mismatch = None;
+    for (page_number, page_digest) in
+        raw_page_digests.chunks_exact(32).enumerate()
+    {
+        let page_number = page_number as u64;
+        let mut page_content = vec![0u8; header.page_bytes()];
+        reader
+            .read_exact(&mut page_content)
+            .map_err(|e| format!("Cannot load the page: {:?}", e))?;
+
+        if keccak(&page_content).0 != page_digest {
+            first_mismatch = Some(PivotHintMismatch {
+                page_number,
+                height: None,
+            });
+            break;
+        }
+
+        let page = PivotHintPage::new(page_content, header);
+        let start_height = page_number * header.page_interval;
+        for page_offset in
+            (0..header.page_interval).step_by(header.minor_interval as usize)
+        {
+            let height = start_height + page_offset;
+            if !page.check_hash_at_height(page_offset, pivot_hash_at(height)?) {
+                first_mismatch = Some(PivotHintMismatch {
+                    page_number,
+                    height: Some(height),
+                });
+                break;
+            }
+        }
+        if first_mismatch.is_some() {
+            break;
+        }
+    }
+
+    Ok(PivotHintVerification {
+        checksum: keccak(&raw_page_digests),
+        range_max: header.range_max,
+        first_mismatch,
+    })
+}
+
+fn make_page(
+    header: &PivotHintHeader, page_number: u64,
+    pivot_hash_at: &mut impl FnMut(u64) -> Result<H256, String>,
+) -> Result<Vec<u8>, String> {
+    let start_height = page_number * header.page_interval;
+    let mut major_section = Vec::with_capacity(header.major_section_bytes());
+    let mut minor_section = Vec::with_capacity(header.minor_section_bytes());
+    for page_offset in
+        (0..header.page_interval).step_by(header.minor_interval as usize)
+    {
+        let hash = pivot_hash_at(start_height + page_offset)?;
+        if page_offset % header.major_interval == 0 {
+            major_section.extend_from_slice(&hash.0);
+        }
+        minor_section.extend_from_slice(&hash[..header.minor_hash_length]);
+    }
+    major_section.append(&mut minor_section);
+    Ok(major_section)
+}
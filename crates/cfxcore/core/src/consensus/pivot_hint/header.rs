@@ -1,5 +1,5 @@
 use rangetools::Rangetools;
-use std::io::Read;
+use std::io::{Read, Write};
 
 pub const HEADER_LENGTH: usize = 28;
 
@@ -38,18 +38,6 @@ impl PivotHintHeader {
         let range_max = read_u64(&mut reader).unwrap();
         let minor_hash_length = read_u32(&mut reader).unwrap() as usize;
 
-        if major_interval % minor_interval != 0 {
-            return Err("Inconsistent header params: major_interval".into());
-        }
-
-        if page_interval % major_interval != 0 {
-            return Err("Inconsistent header params: page_interval".into());
-        }
-
-        if range_max % page_interval != 0 {
-            return Err("Inconsistent header params: range_max".into());
-        }
-
         let header = PivotHintHeader {
             major_interval,
             minor_interval,
@@ -57,6 +45,7 @@ impl PivotHintHeader {
             range_max,
             page_interval,
         };
+        header.check_params()?;
 
         let page_bytes = read_u32(&mut reader).unwrap() as usize;
         if header.page_bytes() != page_bytes {
@@ -67,6 +56,58 @@ impl PivotHintHeader {
         Ok(header)
     }
 
+    pub fn to_raw(&self) -> [u8; HEADER_LENGTH] {
+        let mut raw_header = [0u8; HEADER_LENGTH];
+        let mut writer = &mut raw_header[..];
+        writer
+            .write_all(&(self.minor_interval as u32).to_le_bytes())
+            .unwrap();
+        writer
+            .write_all(&(self.major_interval as u32).to_le_bytes())
+            .unwrap();
+        writer
+            .write_all(&(self.page_interval as u32).to_le_bytes())
+            .unwrap();
+        writer.write_all(&self.range_max.to_le_bytes()).unwrap();
+        writer
+            .write_all(&(self.minor_hash_length as u32).to_le_bytes())
+            .unwrap();
+        writer
+            .write_all(&(self.page_bytes() as u32).to_le_bytes())
+            .unwrap();
+        assert!(writer.is_empty());
+        raw_header
+    }
+
+    /// Checks the integer multiple relationships of the parameters.
+    pub fn check_params(&self) -> Result<(), String> {
+        if self.minor_interval == 0 {
+            return Err("Inconsistent header params: minor_interval".into());
+        }
+
+        if self.minor_hash_length == 0 || self.minor_hash_length > 32 {
+            return Err("Inconsistent header params: minor_hash_length".into());
+        }
+
+        if self.major_interval == 0
+            || self.major_interval % self.minor_interval != 0
+        {
+            return Err("Inconsistent header params: major_interval".into());
+        }
+
+        if self.page_interval == 0
+            || self.page_interval % self.major_interval != 0
+        {
+            return Err("Inconsistent header params: page_interval".into());
+        }
+
+        if self.range_max % self.page_interval != 0 {
+            return Err("Inconsistent header params: range_max".into());
+        }
+
+        Ok(())
+    }
+
     pub fn major_section_bytes(&self) -> usize {
         (32 * (self.page_interval / self.major_interval)) as usize
     }
@@ -53,14 +53,23 @@
 //!    Checksum
 //! 3. Keep Page Digests in memory
 //! 4. Verify each page against Page Digests when loading to prevent corruption
+//!
+//! # Generation
+//! [`generate_pivot_hint`] writes a pivot hint file from the pivot chain of a
+//! synced node, and [`verify_pivot_hint`] checks an existing file against it.
 
 mod config;
+mod generate;
 mod header;
 mod page;
 #[cfg(test)]
 mod tests;
 
 pub use config::PivotHintConfig;
+pub use generate::{
+    generate_pivot_hint, verify_pivot_hint, PivotHintMismatch, PivotHintParams,
+    PivotHintVerification,
+};
 use header::{PivotHintHeader, HEADER_LENGTH};
 use page::PivotHintPage;
 
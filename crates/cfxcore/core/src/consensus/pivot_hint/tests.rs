@@ -8,7 +8,10 @@ use std::{
 
 use crate::hash::H256;
 
-use super::{PivotHint, PivotHintConfig};
+use super::{
+    generate_pivot_hint, verify_pivot_hint, PivotHint, PivotHintConfig,
+    PivotHintMismatch, PivotHintParams,
+};
 
 pub struct TestHashFile {
     file: File,
@@ -36,18 +39,118 @@ impl TestHashFile {
     }
 }
 
-fn make_test_pivot_hint() -> PivotHint {
-    let file_path = Path::new(env!("CARGO_MANIFEST_DIR"))
+fn sample_pivot_hint_path() -> String {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
         .join("test_data")
-        .join("sample_pivot_hint.bin");
-    let checksum = H256::from_str(
+        .join("sample_pivot_hint.bin")
+        .to_str()
+        .unwrap()
+        .to_string()
+}
+
+fn sample_checksum() -> H256 {
+    H256::from_str(
         "28dcd783ff03d7f9718e95e52c9d56174d83faaa25aaeb9c6cc1dd7239d3069e",
     )
-    .unwrap();
-    let config = PivotHintConfig::new(file_path.to_str().unwrap(), checksum);
+    .unwrap()
+}
+
+fn make_test_pivot_hint() -> PivotHint {
+    let config =
+        PivotHintConfig::new(&sample_pivot_hint_path(), sample_checksum());
     PivotHint::new(&config).unwrap()
 }
 
+/// Recovers the recorded pivot hashes of the sample pivot hint file. Heights
+/// which only have a hash prefix recorded are padded with zeros.
+fn sample_recorded_hashes() -> Vec<H256> {
+    const MINOR_INTERVAL: usize = 5;
+    const MAJOR_INTERVAL: usize = 100;
+    const PAGE_INTERVAL: usize = 20_000;
+    const PAGES: usize = 50;
+    const PAGE_BYTES: usize = 22_400;
+
+    let mut content = vec![];
+    File::open(sample_pivot_hint_path())
+        .unwrap()
+        .read_to_end(&mut content)
+        .unwrap();
+    let pages = &content[28 + PAGES * 32..];
+
+    let mut hashes = vec![];
+    for page in pages.chunks_exact(PAGE_BYTES) {
+        let (major, minor) = page.split_at(PAGE_INTERVAL / MAJOR_INTERVAL * 32);
+        for offset in (0..PAGE_INTERVAL).step_by(MINOR_INTERVAL) {
+            let mut hash = H256::zero();
+            if offset % MAJOR_INTERVAL == 0 {
+                let index = offset / MAJOR_INTERVAL;
+                hash.0.copy_from_slice(&major[index * 32..(index + 1) * 32]);
+            } else {
+                let index = offset / MINOR_INTERVAL;
+                hash.0[..4].copy_from_slice(&minor[index * 4..(index + 1) * 4]);
+            }
+            hashes.push(hash);
+        }
+    }
+    hashes
+}
+
+#[test]
+fn test_generate_and_verify_pivot_hint() {
+    let hashes = sample_recorded_hashes();
+    let hash_at = |height: u64| {
+        assert!(height % 5 == 0);
+        Ok(hashes[height as usize / 5])
+    };
+
+    let dir = tempfile::tempdir().unwrap();
+    let file_path = dir.path().join("pivot_hint.bin");
+    let file_path = file_path.to_str().unwrap();
+    let params = PivotHintParams {
+        page_interval: 20_000,
+        major_interval: 100,
+        minor_interval: 5,
+        minor_hash_length: 4,
+    };
+    let checksum =
+        generate_pivot_hint(file_path, params, 1_000_000, hash_at).unwrap();
+    assert_eq!(checksum, sample_checksum());
+    assert_eq!(
+        std::fs::read(file_path).unwrap(),
+        std::fs::read(sample_pivot_hint_path()).unwrap()
+    );
+
+    let verification = verify_pivot_hint(file_path, hash_at).unwrap();
+    assert_eq!(verification.checksum, sample_checksum());
+    assert_eq!(verification.range_max, 1_000_000);
+    assert_eq!(verification.first_mismatch, None);
+
+    let verification = verify_pivot_hint(file_path, |height| {
+        let mut hash = hash_at(height)?;
+        if height >= 450_100 {
+            hash.0[0] ^= 0x80;
+        }
+        Ok(hash)
+    })
+    .unwrap();
+    assert_eq!(
+        verification.first_mismatch,
+        Some(PivotHintMismatch {
+            page_number: 22,
+            height: Some(450_100),
+        })
+    );
+
+    let invalid_params = PivotHintParams {
+        major_interval: 7,
+        ..params
+    };
+    assert!(
+        generate_pivot_hint(file_path, invalid_params, 1_000_000, hash_at)
+            .is_err()
+    );
+}
+
 #[test]
 fn test_pivot_hint() {
     let pivot_hint = make_test_pivot_hint();
@@ -53,6 +53,7 @@ use diem_types::{
 use network::NetworkService;
 use parking_lot::Mutex;
 use pos_ledger_db::PosLedgerDB;
+use safety_rules::TSafetyRules;
 use std::{fs, io::Read, path::PathBuf};
 
 pub type PosVerifier = PosHandler;
@@ -197,13 +198,26 @@ impl PosHandler {
         let (test_command_sender, test_command_receiver) =
             futures_mpsc::channel(1024);
 
+        let keys = self.conf.pos_keys.as_ref().map(|(bls_key, vrf_key)| {
+            (bls_key.private_key(), vrf_key.private_key())
+        });
+        let consensus_private_key =
+            keys.as_ref().map(|(bls_key, _)| bls_key.clone());
+        let mut safety_rules = safety_rules::create_safety_rules_client(
+            &pos_config.consensus.safety_rules,
+            keys,
+            /* export_consensus_key */ true,
+        )
+        .map_err(|e| format!("Failed to create SafetyRules: e={:?}", e))?;
+        let (public_key, vrf_public_key) = safety_rules
+            .public_keys()
+            .map_err(|e| format!("Failed to get the PoS keys: e={:?}", e))?;
         let node_keys = PosNodeKeys {
-            author: from_consensus_public_key(
-                &self.conf.bls_key.public_key(),
-                &self.conf.vrf_key.public_key(),
-            ),
-            consensus_private_key: self.conf.bls_key.private_key(),
-            vrf_private_key: self.conf.vrf_key.private_key(),
+            author: from_consensus_public_key(&public_key, &vrf_public_key),
+            public_key,
+            vrf_public_key,
+            safety_rules,
+            consensus_private_key,
         };
         let chain_params = PosChainParams {
             chain_id: ChainId::new(network.network_id()),
@@ -632,8 +646,12 @@ impl PosInterface for PosConnection {
 }
 
 pub struct PosConfiguration {
-    pub bls_key: ConfigKey<ConsensusPrivateKey>,
-    pub vrf_key: ConfigKey<ConsensusVRFPrivateKey>,
+    /// The PoS keys loaded by the node, which are `None` if they are kept by
+    /// the SafetyRules process.
+    pub pos_keys: Option<(
+        ConfigKey<ConsensusPrivateKey>,
+        ConfigKey<ConsensusVRFPrivateKey>,
+    )>,
     pub diem_conf_path: Option<String>,
     pub protocol_conf: ProtocolConfiguration,
     pub pos_initial_nodes_path: String,
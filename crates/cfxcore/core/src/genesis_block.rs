@@ -24,7 +24,7 @@ use cfx_parameters::{
     genesis::*,
     staking::POS_VOTE_PRICE,
 };
-use cfx_statedb::StateDb;
+use cfx_statedb::{Error as DbError, StateDb, StateDbExt};
 use cfx_storage::{StorageManager, StorageManagerTrait};
 use cfx_types::{
     address_util::AddressUtil, cal_contract_address_with_space, Address,
@@ -418,6 +418,103 @@ pub fn genesis_block(
     genesis
 }
 
+/// The tokens held in a state written into the genesis state, which set the
+/// global statistics if the written state carries none.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GenesisSupply {
+    pub total_issued: U256,
+    pub total_evm_tokens: U256,
+}
+
+/// Build a genesis block whose state is written by `write_state` into the
+/// empty state, e.g. a state imported from a dump. No genesis transaction is
+/// executed, and the internal contracts, the global statistics and the
+/// `genesis_accounts` are only initialized as in [`genesis_block`] if the
+/// written state does not have them.
+pub fn genesis_block_with_state<F>(
+    storage_manager: &Arc<StorageManager>,
+    genesis_accounts: HashMap<AddressWithSpace, U256>,
+    test_net_version: Address, initial_difficulty: U256, machine: Arc<Machine>,
+    write_state: F,
+) -> Result<Block, String>
+where
+    F: FnOnce(&mut StateDb) -> Result<GenesisSupply, String>,
+{
+    let db_error =
+        |e: DbError| format!("Failed to initialize genesis state: {}", e);
+
+    let mut state_db =
+        StateDb::new(storage_manager.get_state_for_genesis_write());
+    let supply = write_state(&mut state_db)?;
+    let global_stat_written = state_db.is_initialized().map_err(db_error)?;
+    let mut state = State::new(state_db).map_err(db_error)?;
+
+    let mut genesis_block_author = test_net_version;
+    genesis_block_author.set_user_account_type_bits();
+
+    let mut internal_contracts = vec![];
+    for address in machine.internal_contracts().initialized_at_genesis() {
+        if !state
+            .exists(&address.with_native_space())
+            .map_err(db_error)?
+        {
+            internal_contracts.push(*address);
+        }
+    }
+    initialize_internal_contract_accounts(&mut state, &internal_contracts)
+        .map_err(db_error)?;
+    if !global_stat_written {
+        state.add_total_issued(supply.total_issued);
+        state.add_total_evm_tokens(supply.total_evm_tokens);
+    }
+    trace!("genesis_accounts: {:?}", genesis_accounts);
+    for (addr, balance) in genesis_accounts {
+        if state.exists(&addr).map_err(db_error)? {
+            continue;
+        }
+        state.add_balance(&addr, &balance).map_err(db_error)?;
+        state.add_total_issued(balance);
+        if addr.space == Space::Ethereum {
+            state.add_total_evm_tokens(balance);
+        }
+    }
+    state.commit_cache(false);
+
+    let state_root = state
+        .compute_state_root_for_genesis(/* debug_record = */ None)
+        .map_err(|e| format!("Failed to compute genesis state root: {}", e))?;
+    let receipt_root = compute_receipts_root(&vec![Arc::new(BlockReceipts {
+        receipts: vec![],
+        block_number: 0,
+        secondary_reward: U256::zero(),
+        tx_execution_error_messages: vec![],
+    })]);
+
+    let mut genesis = Block::new(
+        BlockHeaderBuilder::new()
+            .with_deferred_state_root(state_root.aux_info.state_root_hash)
+            .with_deferred_receipts_root(receipt_root)
+            .with_gas_limit(GENESIS_GAS_LIMIT.into())
+            .with_author(genesis_block_author)
+            .with_difficulty(initial_difficulty)
+            .with_transactions_root(compute_transaction_root(&vec![]))
+            .build(),
+        vec![],
+    );
+    genesis.block_header.compute_hash();
+    debug!(
+        "Initialize genesis_block={:?} hash={:?} from state",
+        genesis,
+        genesis.hash()
+    );
+
+    state
+        .commit(genesis.block_header.hash(), /* debug_record = */ None)
+        .map_err(|e| format!("Failed to commit genesis state: {}", e))?;
+    genesis.block_header.pow_hash = Some(Default::default());
+    Ok(genesis)
+}
+
 pub fn register_transaction(
     bls_priv_key: BLSPrivateKey, vrf_pub_key: EcVrfPublicKey, power: u64,
     genesis_chain_id: u32, legacy: bool,
@@ -31,7 +31,7 @@ pub mod verification;
 pub use cfxcore_pow as pow;
 pub use cfxcore_types::{
     cache_config, cache_manager, channel, core_error, node_type, state_exposer,
-    unique_id,
+    txpool_event, unique_id,
 };
 
 pub use crate::{
@@ -9,7 +9,9 @@ use crate::{
 };
 use cfx_internal_common::ChainIdParamsOneChainInner;
 use cfx_types::{H160, H256};
-use network::{node_table::NodeId, NetworkContext, UpdateNodeOperation};
+use network::{
+    node_table::NodeId, Misbehavior, NetworkContext, UpdateNodeOperation,
+};
 use parking_lot::Mutex;
 use primitives::{account::AccountError, filter::FilterError, StateRoot};
 use rlp::DecoderError;
@@ -162,6 +164,7 @@ pub fn handle(
     let mut disconnect = true;
     let reason = format!("{}", e);
     let mut op = None;
+    let mut misbehavior = None;
 
     // NOTE: do not use wildcard; this way, the compiler
     // will help covering all the cases.
@@ -203,11 +206,16 @@ pub fn handle(
         Error::GenesisMismatch{..}
         | Error::InvalidHeader
         | Error::ChainIdMismatch{..}
-        | Error::UnexpectedMessage{..}
         | Error::UnexpectedPeerType{..} => op = Some(UpdateNodeOperation::Failure),
 
+        Error::UnexpectedMessage{..} => {
+            op = Some(UpdateNodeOperation::Failure);
+            misbehavior = Some(Misbehavior::UnexpectedMessage);
+        }
+
         Error::UnexpectedResponse{..} => {
-            op = Some(UpdateNodeOperation::Demotion)
+            op = Some(UpdateNodeOperation::Demotion);
+            misbehavior = Some(Misbehavior::UnexpectedMessage);
         }
 
         Error::InvalidBloom{..}
@@ -224,7 +232,10 @@ pub fn handle(
         | Error::InvalidWitnessRoot{..}
         | Error::AlreadyThrottled(_)
         | Error::Decoder(_)
-        | Error::AccountError(_) => op = Some(UpdateNodeOperation::Remove),
+        | Error::AccountError(_) => {
+            op = Some(UpdateNodeOperation::Remove);
+            misbehavior = Some(Misbehavior::InvalidMessage);
+        }
 
         Error::Throttled(_, resp) => {
             disconnect = false;
@@ -277,6 +288,9 @@ pub fn handle(
         // }
     };
 
+    if let Some(misbehavior) = misbehavior {
+        io.note_misbehavior(peer, misbehavior);
+    }
     if disconnect {
         io.disconnect_peer(peer, op, reason.as_str());
     }
@@ -92,24 +92,45 @@ impl StateEntries {
     pub fn request_now(
         &self, io: &dyn NetworkContext, epoch: u64, key: Vec<u8>,
     ) -> impl Future<Output = Result<StateEntry>> {
-        let mut verified = self.verified.write();
-        let key = StateKey { epoch, key };
-
-        if !verified.contains_key(&key) {
-            let missing = std::iter::once(MissingStateEntry::new(key.clone()));
+        self.request_all_now(io, epoch, vec![key])
+            .pop()
+            .expect("one future for one key")
+    }
 
-            self.sync_manager.request_now(missing, |peer, keys| {
-                self.send_request(io, peer, keys)
-            });
+    /// Requests the entries of `keys` at once, in batches instead of one
+    /// request per entry. The futures are returned in the order of `keys`.
+    pub fn request_all_now(
+        &self, io: &dyn NetworkContext, epoch: u64, keys: Vec<Vec<u8>>,
+    ) -> Vec<impl Future<Output = Result<StateEntry>>> {
+        let mut verified = self.verified.write();
+        let keys: Vec<StateKey> = keys
+            .into_iter()
+            .map(|key| StateKey { epoch, key })
+            .collect();
+
+        let missing: Vec<MissingStateEntry> = keys
+            .iter()
+            .filter(|key| !verified.contains_key(*key))
+            .map(|key| MissingStateEntry::new(key.clone()))
+            .collect();
+        for batch in missing.chunks(STATE_ENTRY_REQUEST_BATCH_SIZE) {
+            self.sync_manager
+                .request_now(batch.iter().cloned(), |peer, keys| {
+                    self.send_request(io, peer, keys)
+                });
         }
 
-        verified
-            .entry(key.clone())
-            .or_insert(PendingItem::pending())
-            .clear_error();
-
-        FutureItem::new(key, self.verified.clone())
-            .map(|res| res.map_err(|e| e.into()))
+        keys.into_iter()
+            .map(|key| {
+                verified
+                    .entry(key.clone())
+                    .or_insert(PendingItem::pending())
+                    .clear_error();
+
+                FutureItem::new(key, self.verified.clone())
+                    .map(|res| res.map_err(|e| e.into()))
+            })
+            .collect()
     }
 
     #[inline]
@@ -7,6 +7,7 @@ mod config;
 mod error;
 mod handler;
 mod message;
+mod on_demand_state;
 mod provider;
 pub mod query_service;
 
@@ -0,0 +1,464 @@
+// Copyright 2026 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+//! A read-only state backend for light nodes. The state entries are retrieved
+//! from the full nodes on demand, each of them verified against the trusted
+//! state root of the epoch, so the executor can run on top of the state
+//! without having it locally.
+
+use super::{Error as LightError, QueryService};
+use cfx_internal_common::StateRootWithAuxInfo;
+use cfx_storage::{
+    state::StateTrait, Error as StorageError, MptKeyValue,
+    Result as StorageResult,
+};
+use cfx_types::{Address, AddressSpaceUtil, Space, H160};
+use parking_lot::RwLock;
+use primitives::{
+    Action, EpochId, MptValue, SignedTransaction, StorageKey,
+    StorageKeyWithSpace, StorageRoot,
+};
+use std::{collections::HashMap, future::Future, sync::Arc};
+use tokio::runtime::Handle;
+
+/// Retrieves the state entries of an epoch, each of them verified against the
+/// state root of the epoch.
+#[async_trait::async_trait]
+pub trait StateRetriever: Send + Sync {
+    async fn state_entries(
+        &self, epoch: u64, keys: Vec<Vec<u8>>,
+    ) -> Result<Vec<Option<Vec<u8>>>, LightError>;
+
+    /// Retrieves the storage root of a Core Space account.
+    async fn storage_root(
+        &self, epoch: u64, address: H160,
+    ) -> Result<StorageRoot, LightError>;
+}
+
+#[async_trait::async_trait]
+impl StateRetriever for QueryService {
+    async fn state_entries(
+        &self, epoch: u64, keys: Vec<Vec<u8>>,
+    ) -> Result<Vec<Option<Vec<u8>>>, LightError> {
+        self.retrieve_state_entries_raw(epoch, keys).await
+    }
+
+    async fn storage_root(
+        &self, epoch: u64, address: H160,
+    ) -> Result<StorageRoot, LightError> {
+        self.retrieve_storage_root(epoch, address).await
+    }
+}
+
+pub struct OnDemandStorage {
+    light: Arc<dyn StateRetriever>,
+    epoch: u64,
+    /// The executor is synchronous, so the retrievals are driven by the
+    /// runtime the light protocol runs on.
+    runtime: Handle,
+    /// The verified entries retrieved so far, so each of them is retrieved
+    /// only once during a call.
+    entries: RwLock<HashMap<Vec<u8>, Option<Box<[u8]>>>>,
+}
+
+impl OnDemandStorage {
+    /// Must be called from the light protocol runtime. The storage itself
+    /// must be used outside of it, e.g. in a blocking task, as it blocks on
+    /// the retrievals.
+    pub fn new(light: Arc<dyn StateRetriever>, epoch: u64) -> Self {
+        OnDemandStorage {
+            light,
+            epoch,
+            runtime: Handle::current(),
+            entries: Default::default(),
+        }
+    }
+
+    fn block_on<T>(
+        &self, fut: impl Future<Output = Result<T, LightError>>,
+    ) -> StorageResult<T> {
+        self.runtime
+            .block_on(fut)
+            .map_err(|e| StorageError::Msg(format!("{}", e)))
+    }
+
+    fn read_only() -> StorageError {
+        StorageError::Msg("The state retrieved on demand is read-only".into())
+    }
+
+    /// Retrieves the entries not retrieved yet at once, so that reading them
+    /// later takes no round trip.
+    pub fn prefetch(&self, keys: Vec<Vec<u8>>) -> StorageResult<()> {
+        let mut keys: Vec<Vec<u8>> = {
+            let entries = self.entries.read();
+            keys.into_iter()
+                .filter(|key| !entries.contains_key(key))
+                .collect()
+        };
+        keys.sort();
+        keys.dedup();
+        if keys.is_empty() {
+            return Ok(());
+        }
+
+        let values =
+            self.block_on(self.light.state_entries(self.epoch, keys.clone()))?;
+        let mut entries = self.entries.write();
+        for (key, value) in keys.into_iter().zip(values) {
+            entries.insert(key, value.map(Vec::into_boxed_slice));
+        }
+        Ok(())
+    }
+}
+
+/// Returns the keys of the state entries that executing `tx` surely reads:
+/// the accounts of the sender and the receiver, and the accounts and the
+/// storage entries in the access list.
+pub fn prefetch_keys(tx: &SignedTransaction) -> Vec<Vec<u8>> {
+    let space = tx.space();
+    let account_key = |address: &Address| {
+        StorageKey::new_account_key(address)
+            .with_space(space)
+            .to_key_bytes()
+    };
+
+    let mut keys = vec![account_key(&tx.sender().address)];
+    if let Action::Call(ref address) = tx.action() {
+        keys.push(account_key(address));
+    }
+    for item in tx.access_list().into_iter().flatten() {
+        keys.push(account_key(&item.address));
+        for storage_key in &item.storage_keys {
+            keys.push(
+                StorageKey::new_storage_key(
+                    &item.address,
+                    storage_key.as_bytes(),
+                )
+                .with_space(space)
+                .to_key_bytes(),
+            );
+        }
+    }
+    keys
+}
+
+/// Returns `true` if the storage root proves that the account has no storage
+/// entry.
+fn is_empty_storage(root: &StorageRoot) -> bool {
+    !matches!(root.delta, MptValue::Some(_))
+        && !matches!(root.intermediate, MptValue::Some(_))
+        && root.snapshot.is_none()
+}
+
+impl StateTrait for OnDemandStorage {
+    fn get(
+        &self, access_key: StorageKeyWithSpace,
+    ) -> StorageResult<Option<Box<[u8]>>> {
+        let key = access_key.to_key_bytes();
+        if let Some(entry) = self.entries.read().get(&key) {
+            return Ok(entry.clone());
+        }
+
+        self.prefetch(vec![key.clone()])?;
+        Ok(self.entries.read().get(&key).cloned().flatten())
+    }
+
+    fn set(
+        &mut self, _access_key: StorageKeyWithSpace, _value: Box<[u8]>,
+    ) -> StorageResult<()> {
+        Err(Self::read_only())
+    }
+
+    fn delete(
+        &mut self, _access_key: StorageKeyWithSpace,
+    ) -> StorageResult<()> {
+        Err(Self::read_only())
+    }
+
+    fn delete_test_only(
+        &mut self, _access_key: StorageKeyWithSpace,
+    ) -> StorageResult<Option<Box<[u8]>>> {
+        Err(Self::read_only())
+    }
+
+    fn delete_all(
+        &mut self, _access_key_prefix: StorageKeyWithSpace,
+    ) -> StorageResult<Option<Vec<MptKeyValue>>> {
+        Err(Self::read_only())
+    }
+
+    fn read_all(
+        &mut self, access_key_prefix: StorageKeyWithSpace,
+    ) -> StorageResult<Option<Vec<MptKeyValue>>> {
+        // The entries under a prefix can not be proven complete one by one,
+        // only the absence of the whole storage of an account is proven, which
+        // is the common case, e.g. when killing a contract without storage.
+        let address = match access_key_prefix.key {
+            StorageKey::StorageRootKey(address)
+            | StorageKey::StorageKey {
+                address_bytes: address,
+                ..
+            } => H160::from_slice(address),
+            _ => {
+                return Err(StorageError::Msg(format!(
+                    "Iterating over {:?} is not supported by the state \
+                     retrieved on demand",
+                    access_key_prefix
+                )))
+            }
+        };
+
+        let empty = match access_key_prefix.space {
+            // The storage root of a Core Space account proves it.
+            Space::Native => is_empty_storage(
+                &self.block_on(self.light.storage_root(self.epoch, address))?,
+            ),
+            // The storage roots of the eSpace accounts can not be retrieved,
+            // but the storage is deleted along with the account, so the
+            // absence of the account proves it.
+            Space::Ethereum => self
+                .get(
+                    StorageKey::new_account_key(&address)
+                        .with_space(Space::Ethereum),
+                )?
+                .is_none(),
+        };
+        if empty {
+            return Ok(None);
+        }
+        Err(StorageError::Msg(format!(
+            "Iterating over the storage of {:?} is not supported by the \
+             state retrieved on demand",
+            address.with_space(access_key_prefix.space)
+        )))
+    }
+
+    fn compute_state_root(&mut self) -> StorageResult<StateRootWithAuxInfo> {
+        Err(Self::read_only())
+    }
+
+    fn get_state_root(&self) -> StorageResult<StateRootWithAuxInfo> {
+        Err(Self::read_only())
+    }
+
+    fn commit(
+        &mut self, _epoch: EpochId,
+    ) -> StorageResult<StateRootWithAuxInfo> {
+        Err(Self::read_only())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cfx_types::H256;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::runtime::{Builder, Runtime};
+
+    #[derive(Default)]
+    struct MockRetriever {
+        entries: HashMap<Vec<u8>, Vec<u8>>,
+        storage_roots: HashMap<H160, StorageRoot>,
+        requests: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl StateRetriever for MockRetriever {
+        async fn state_entries(
+            &self, _epoch: u64, keys: Vec<Vec<u8>>,
+        ) -> Result<Vec<Option<Vec<u8>>>, LightError> {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+            Ok(keys
+                .iter()
+                .map(|key| self.entries.get(key).cloned())
+                .collect())
+        }
+
+        async fn storage_root(
+            &self, _epoch: u64, address: H160,
+        ) -> Result<StorageRoot, LightError> {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+            self.storage_roots
+                .get(&address)
+                .cloned()
+                .ok_or_else(|| "storage root not found".into())
+        }
+    }
+
+    fn new_storage(
+        retriever: Arc<MockRetriever>,
+    ) -> (Runtime, OnDemandStorage) {
+        let runtime = Builder::new_current_thread().build().unwrap();
+        let storage = {
+            let _guard = runtime.enter();
+            OnDemandStorage::new(retriever, 1)
+        };
+        (runtime, storage)
+    }
+
+    fn storage_root(
+        delta: MptValue<H256>, intermediate: MptValue<H256>,
+        snapshot: Option<H256>,
+    ) -> StorageRoot {
+        StorageRoot {
+            delta,
+            intermediate,
+            snapshot,
+        }
+    }
+
+    #[test]
+    fn test_is_empty_storage() {
+        let root = H256::repeat_byte(1);
+        assert!(is_empty_storage(&storage_root(
+            MptValue::None,
+            MptValue::None,
+            None
+        )));
+        assert!(is_empty_storage(&storage_root(
+            MptValue::TombStone,
+            MptValue::TombStone,
+            None
+        )));
+        assert!(!is_empty_storage(&storage_root(
+            MptValue::Some(root),
+            MptValue::None,
+            None
+        )));
+        assert!(!is_empty_storage(&storage_root(
+            MptValue::None,
+            MptValue::Some(root),
+            None
+        )));
+        assert!(!is_empty_storage(&storage_root(
+            MptValue::TombStone,
+            MptValue::None,
+            Some(root)
+        )));
+    }
+
+    #[test]
+    fn test_read_all() {
+        let native_empty = H160::repeat_byte(1);
+        let native_stored = H160::repeat_byte(2);
+        let espace_absent = H160::repeat_byte(3);
+        let espace_present = H160::repeat_byte(4);
+
+        let mut retriever = MockRetriever::default();
+        retriever.storage_roots.insert(
+            native_empty,
+            storage_root(MptValue::None, MptValue::TombStone, None),
+        );
+        retriever.storage_roots.insert(
+            native_stored,
+            storage_root(
+                MptValue::Some(H256::repeat_byte(1)),
+                MptValue::None,
+                None,
+            ),
+        );
+        retriever.entries.insert(
+            StorageKey::new_account_key(&espace_present)
+                .with_evm_space()
+                .to_key_bytes(),
+            vec![1],
+        );
+        let (_runtime, mut storage) = new_storage(Arc::new(retriever));
+
+        // the whole storage or a part of it
+        assert_eq!(
+            storage
+                .read_all(
+                    StorageKey::new_storage_root_key(&native_empty)
+                        .with_native_space()
+                )
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            storage
+                .read_all(
+                    StorageKey::new_storage_key(&native_empty, &[0])
+                        .with_native_space()
+                )
+                .unwrap(),
+            None
+        );
+        assert!(storage
+            .read_all(
+                StorageKey::new_storage_root_key(&native_stored)
+                    .with_native_space()
+            )
+            .is_err());
+
+        assert_eq!(
+            storage
+                .read_all(
+                    StorageKey::new_storage_root_key(&espace_absent)
+                        .with_evm_space()
+                )
+                .unwrap(),
+            None
+        );
+        assert!(storage
+            .read_all(
+                StorageKey::new_storage_root_key(&espace_present)
+                    .with_evm_space()
+            )
+            .is_err());
+
+        // only the storage prefixes are supported
+        assert!(storage
+            .read_all(
+                StorageKey::new_account_key(&native_empty).with_native_space()
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_prefetch() {
+        let address = H160::repeat_byte(1);
+        let account_key = StorageKey::new_account_key(&address);
+        let storage_key = StorageKey::new_storage_key(&address, &[0]);
+        let absent_key = StorageKey::new_storage_key(&address, &[1]);
+
+        let mut retriever = MockRetriever::default();
+        retriever
+            .entries
+            .insert(account_key.with_evm_space().to_key_bytes(), vec![1]);
+        retriever
+            .entries
+            .insert(storage_key.with_evm_space().to_key_bytes(), vec![2]);
+        let retriever = Arc::new(retriever);
+        let (_runtime, storage) = new_storage(retriever.clone());
+
+        // the entries are retrieved in one request
+        storage
+            .prefetch(vec![
+                account_key.with_evm_space().to_key_bytes(),
+                storage_key.with_evm_space().to_key_bytes(),
+                account_key.with_evm_space().to_key_bytes(),
+            ])
+            .unwrap();
+        assert_eq!(retriever.requests.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            storage.get(account_key.with_evm_space()).unwrap(),
+            Some(vec![1].into_boxed_slice())
+        );
+        assert_eq!(
+            storage.get(storage_key.with_evm_space()).unwrap(),
+            Some(vec![2].into_boxed_slice())
+        );
+        assert_eq!(retriever.requests.load(Ordering::SeqCst), 1);
+
+        // the absence of an entry is also kept
+        assert_eq!(storage.get(absent_key.with_evm_space()).unwrap(), None);
+        assert_eq!(storage.get(absent_key.with_evm_space()).unwrap(), None);
+        assert_eq!(retriever.requests.load(Ordering::SeqCst), 2);
+
+        // the keys in another space are different entries
+        assert_eq!(storage.get(account_key.with_native_space()).unwrap(), None);
+        assert_eq!(retriever.requests.load(Ordering::SeqCst), 3);
+    }
+}
@@ -9,6 +9,7 @@ use crate::{
         common::{FullPeerFilter, LedgerInfo},
         handler::sync::TxInfoValidated,
         message::msgid,
+        on_demand_state::{prefetch_keys, OnDemandStorage},
         Error as LightError, Handler as LightHandler, LightNodeConfiguration,
         LIGHT_PROTOCOL_ID, LIGHT_PROTOCOL_VERSION,
     },
@@ -16,7 +17,14 @@ use crate::{
     Notifications,
 };
 use cfx_addr::Network;
-use cfx_executor::state::COMMISSION_PRIVILEGE_SPECIAL_KEY;
+use cfx_execute_helper::{
+    estimation::{EstimateExt, EstimateRequest},
+    phantom_tx::build_bloom_and_recover_phantom,
+};
+use cfx_executor::{
+    executive::ExecutionOutcome,
+    state::{State, COMMISSION_PRIVILEGE_SPECIAL_KEY},
+};
 use cfx_parameters::{
     consensus::DEFERRED_STATE_EPOCH_COUNT,
     internal_contract_addresses::SPONSOR_WHITELIST_CONTROL_CONTRACT_ADDRESS,
@@ -27,10 +35,15 @@ use cfx_parameters::{
         TRANSACTION_COUNT_PER_BLOCK_WATER_LINE_MEDIUM,
     },
 };
-use cfx_statedb::global_params::{self, GlobalParamKey};
+use cfx_rpc_cfx_types::PhantomBlock;
+use cfx_rpc_eth_types::EvmOverrides;
+use cfx_statedb::{
+    global_params::{self, GlobalParamKey},
+    StateDb,
+};
 use cfx_types::{
-    address_util::AddressUtil, AllChainID, BigEndianHash, Bloom, H160, H256,
-    KECCAK_EMPTY_BLOOM, U256,
+    address_util::AddressUtil, AddressSpaceUtil, AddressWithSpace, AllChainID,
+    BigEndianHash, Bloom, Space, H160, H256, KECCAK_EMPTY_BLOOM, U256,
 };
 use futures::{
     future::{self, Either},
@@ -42,7 +55,7 @@ use primitives::{
     log_entry::{LocalizedLogEntry, LogEntry},
     Account, Block, BlockReceipts, CodeInfo, DepositList, EpochNumber, Receipt,
     SignedTransaction, StorageKey, StorageRoot, StorageValue, TransactionIndex,
-    VoteStakeList,
+    TransactionStatus, VoteStakeList,
 };
 use rlp::Rlp;
 use std::{collections::BTreeSet, future::Future, sync::Arc, time::Duration};
@@ -144,6 +157,31 @@ impl QueryService {
         .await
     }
 
+    /// Same as `retrieve_state_entry_raw`, but the entries are requested at
+    /// once, which takes one round trip instead of one per entry.
+    pub(super) async fn retrieve_state_entries_raw(
+        &self, epoch: u64, keys: Vec<Vec<u8>>,
+    ) -> Result<Vec<Option<Vec<u8>>>, LightError> {
+        trace!(
+            "retrieve_state_entries_raw epoch = {}, keys = {:?}",
+            epoch,
+            keys
+        );
+
+        with_timeout(
+            *MAX_POLL_TIME,
+            format!(
+                "Timeout while retrieving {} state entries for epoch {:?}",
+                keys.len(),
+                epoch
+            ),
+            future::try_join_all(self.with_io(|io| {
+                self.handler.state_entries.request_all_now(io, epoch, keys)
+            })),
+        )
+        .await
+    }
+
     async fn retrieve_state_entry<T: rlp::Decodable>(
         &self, epoch: u64, key: Vec<u8>,
     ) -> Result<Option<T>, LightError> {
@@ -157,7 +195,7 @@ impl QueryService {
         }
     }
 
-    async fn retrieve_storage_root(
+    pub(super) async fn retrieve_storage_root(
         &self, epoch: u64, address: H160,
     ) -> Result<StorageRoot, LightError> {
         trace!(
@@ -356,21 +394,21 @@ impl QueryService {
         }
     }
 
-    fn account_key(address: &H160) -> Vec<u8> {
-        StorageKey::new_account_key(&address)
-            .with_native_space()
+    fn account_key(address: &AddressWithSpace) -> Vec<u8> {
+        StorageKey::new_account_key(&address.address)
+            .with_space(address.space)
             .to_key_bytes()
     }
 
-    fn code_key(address: &H160, code_hash: &H256) -> Vec<u8> {
-        StorageKey::new_code_key(&address, &code_hash)
-            .with_native_space()
+    fn code_key(address: &AddressWithSpace, code_hash: &H256) -> Vec<u8> {
+        StorageKey::new_code_key(&address.address, &code_hash)
+            .with_space(address.space)
             .to_key_bytes()
     }
 
-    fn storage_key(address: &H160, position: &[u8]) -> Vec<u8> {
-        StorageKey::new_storage_key(&address, &position)
-            .with_native_space()
+    fn storage_key(address: &AddressWithSpace, position: &[u8]) -> Vec<u8> {
+        StorageKey::new_storage_key(&address.address, &position)
+            .with_space(address.space)
             .to_key_bytes()
     }
 
@@ -388,6 +426,13 @@ impl QueryService {
 
     pub async fn get_account(
         &self, epoch: EpochNumber, address: H160,
+    ) -> Result<Option<Account>, LightError> {
+        self.get_account_with_space(epoch, address.with_native_space())
+            .await
+    }
+
+    pub async fn get_account_with_space(
+        &self, epoch: EpochNumber, address: AddressWithSpace,
     ) -> Result<Option<Account>, LightError> {
         debug!("get_account epoch={:?} address={:?}", epoch, address);
 
@@ -396,9 +441,10 @@ impl QueryService {
 
         match self.retrieve_state_entry_raw(epoch, key).await? {
             None => Ok(None),
-            Some(rlp) => {
-                Ok(Some(Account::new_from_rlp(address, &Rlp::new(&rlp))?))
-            }
+            Some(rlp) => Ok(Some(Account::new_from_rlp(
+                address.address,
+                &Rlp::new(&rlp),
+            )?)),
         }
     }
 
@@ -420,11 +466,22 @@ impl QueryService {
 
     pub async fn get_code(
         &self, epoch: EpochNumber, address: H160,
+    ) -> Result<Option<Vec<u8>>, Error> {
+        self.get_code_with_space(epoch, address.with_native_space())
+            .await
+    }
+
+    pub async fn get_code_with_space(
+        &self, epoch: EpochNumber, address: AddressWithSpace,
     ) -> Result<Option<Vec<u8>>, Error> {
         debug!("get_code epoch={:?} address={:?}", epoch, address);
 
         // do not query peers for non-contract addresses
-        if !address.is_contract_address() && !address.is_builtin_address() {
+        // note: eSpace addresses do not encode the account type
+        if address.space == Space::Native
+            && !address.address.is_contract_address()
+            && !address.address.is_builtin_address()
+        {
             return Ok(None);
         }
 
@@ -437,7 +494,7 @@ impl QueryService {
             Ok(Some(rlp)) => {
                 account_result_to_rpc_result(
                     "address",
-                    Account::new_from_rlp(address, &Rlp::new(&rlp)),
+                    Account::new_from_rlp(address.address, &Rlp::new(&rlp)),
                 )?
                 .code_hash
             }
@@ -461,6 +518,17 @@ impl QueryService {
 
     pub async fn get_storage(
         &self, epoch: EpochNumber, address: H160, position: H256,
+    ) -> Result<Option<H256>, LightError> {
+        self.get_storage_with_space(
+            epoch,
+            address.with_native_space(),
+            position,
+        )
+        .await
+    }
+
+    pub async fn get_storage_with_space(
+        &self, epoch: EpochNumber, address: AddressWithSpace, position: H256,
     ) -> Result<Option<H256>, LightError> {
         debug!(
             "get_storage epoch={:?} address={:?} position={:?}",
@@ -494,7 +562,7 @@ impl QueryService {
             pos.extend_from_slice(COMMISSION_PRIVILEGE_SPECIAL_KEY.as_bytes());
 
             let key = Self::storage_key(
-                &SPONSOR_WHITELIST_CONTROL_CONTRACT_ADDRESS,
+                &SPONSOR_WHITELIST_CONTROL_CONTRACT_ADDRESS.with_native_space(),
                 &pos,
             );
 
@@ -508,7 +576,7 @@ impl QueryService {
             pos.extend_from_slice(user.as_bytes());
 
             let key = Self::storage_key(
-                &SPONSOR_WHITELIST_CONTROL_CONTRACT_ADDRESS,
+                &SPONSOR_WHITELIST_CONTROL_CONTRACT_ADDRESS.with_native_space(),
                 &pos,
             );
 
@@ -593,6 +661,40 @@ impl QueryService {
         ])
     }
 
+    /// Executes a virtual call on top of the state of `epoch`. The state
+    /// entries touched by the execution are retrieved on demand, each of them
+    /// verified against the state root of the epoch.
+    pub async fn call_virtual(
+        self: &Arc<Self>, tx: SignedTransaction, epoch: EpochNumber,
+        request: EstimateRequest, evm_overrides: EvmOverrides,
+    ) -> Result<(ExecutionOutcome, EstimateExt), Error> {
+        debug!("call_virtual tx={:?} epoch={:?}", tx.hash(), epoch);
+
+        let epoch = self.get_height_from_epoch_number(epoch)?;
+        let pivot_hash = self.ledger.pivot_header_of(epoch)?.hash();
+        let storage = OnDemandStorage::new(self.clone(), epoch);
+        let consensus = self.consensus.clone();
+
+        // the executor blocks on the retrievals, keep it off the runtime
+        tokio::task::spawn_blocking(move || {
+            // Retrieving the entries known to be read in one round trip is
+            // only an optimization; they are retrieved again on failure.
+            if let Err(e) = storage.prefetch(prefetch_keys(&tx)) {
+                debug!("Failed to prefetch the state entries: {}", e);
+            }
+            let mut state = State::new(StateDb::new(Box::new(storage)))?;
+            consensus.call_virtual_on_epoch_state(
+                &mut state,
+                &pivot_hash,
+                &tx,
+                request,
+                evm_overrides,
+            )
+        })
+        .await
+        .map_err(|e| format!("Virtual call aborted: {}", e))?
+    }
+
     pub async fn get_tx_info(&self, hash: H256) -> Result<TxInfo, LightError> {
         debug!("get_tx_info hash={:?}", hash);
 
@@ -1035,7 +1137,358 @@ impl QueryService {
         Ok(matching)
     }
 
+    /// Reconstruct the eSpace phantom block of `epoch` from the verified
+    /// transactions and receipts of its blocks.
+    /// NOTE: transaction execution errors are not covered by the receipts
+    /// root, so they are left empty.
+    pub async fn get_phantom_block(
+        &self, epoch: u64,
+    ) -> Result<PhantomBlock, LightError> {
+        debug!("get_phantom_block epoch={:?}", epoch);
+
+        let hashes = self.ledger.block_hashes_in(epoch)?;
+
+        let pivot = match hashes.last() {
+            Some(hash) => self.ledger.header(*hash)?,
+            None => bail!(LightError::InternalError(
+                "Inconsistent state: empty epoch".into()
+            )),
+        };
+
+        let mut phantom_block = PhantomBlock {
+            pivot_header: pivot,
+            transactions: vec![],
+            receipts: vec![],
+            errors: vec![],
+            bloom: Bloom::zero(),
+            traces: vec![],
+            total_gas_limit: U256::zero(),
+        };
+
+        // the genesis block has no executed transactions
+        if epoch == 0 {
+            return Ok(phantom_block);
+        }
+
+        let ((_, receipts), block_txs) = try_join!(
+            self.retrieve_receipts(epoch),
+            future::try_join_all(
+                hashes.iter().map(|h| self.retrieve_block_txs(*h))
+            )
+        )?;
+
+        // sanity check: one receipt list for each block
+        if receipts.len() != hashes.len() {
+            bail!(LightError::InternalError(
+                "Inconsistent state: blocks and receipts length mismatch"
+                    .into()
+            ));
+        }
+
+        let evm_chain_id = self
+            .consensus
+            .config()
+            .chain_id
+            .read()
+            .get_chain_id(epoch)
+            .in_evm_space();
+
+        let mut accumulated_gas_used = U256::zero();
+
+        for ((hash, txs), block_receipts) in
+            hashes.iter().zip(block_txs).zip(receipts)
+        {
+            let header = self.ledger.header(*hash)?;
+
+            // note: we only include gas limit for blocks that will pack eSpace
+            // tx(multiples of 5)
+            phantom_block.total_gas_limit += header.espace_gas_limit(
+                self.consensus
+                    .params
+                    .can_pack_evm_transaction(header.height()),
+            );
+
+            // sanity check: transaction and receipt length
+            if txs.len() != block_receipts.receipts.len() {
+                bail!(LightError::InternalError(
+                    "Inconsistent state: transactions and receipts length mismatch".into()
+                ));
+            }
+
+            let block_number =
+                self.consensus.get_block_number(hash)?.ok_or_else(|| {
+                    format!("Block number of {:?} not found", hash)
+                })?;
+            let spec = self.consensus.params.spec(block_number, epoch);
+
+            append_to_phantom_block(
+                &mut phantom_block,
+                txs,
+                block_receipts.receipts,
+                &mut accumulated_gas_used,
+                spec.cip90,
+                evm_chain_id,
+            );
+        }
+
+        Ok(phantom_block)
+    }
+
+    /// Retrieve the eSpace phantom blocks that might contain logs matching
+    /// `filter`, in ascending order. Epochs are matched against their
+    /// verified blooms first, so that only the candidates are retrieved.
+    /// Retrieval stops as soon as the blocks contain more than `max_logs`
+    /// matching logs.
+    pub async fn get_phantom_blocks_for_logs(
+        &self, filter: LogFilter, max_logs: Option<usize>,
+    ) -> Result<Vec<PhantomBlock>, LightError> {
+        debug!("get_phantom_blocks_for_logs filter = {:?}", filter);
+
+        // block hash filters refer to pivot blocks in eSpace,
+        // so we only need the epochs here
+        let (epochs, _) = self
+            .get_filter_epochs(&filter)
+            .map_err(|e| format!("{}", e))?;
+
+        let blooms = filter.bloom_possibilities();
+
+        let mut matching: Vec<u64> = stream::iter(epochs)
+            .map(|epoch| self.retrieve_bloom(epoch))
+            .buffered(LOG_FILTERING_LOOKAHEAD)
+            .try_filter_map(|(epoch, bloom)| {
+                let matches = blooms.iter().any(|b| bloom.contains_bloom(b));
+                future::ready(Ok(matches.then_some(epoch)))
+            })
+            .try_collect()
+            .await?;
+
+        matching.reverse();
+
+        let mut stream = stream::iter(matching)
+            .map(|epoch| self.get_phantom_block(epoch))
+            .buffered(LOG_FILTERING_LOOKAHEAD);
+
+        let mut phantom_blocks = vec![];
+        let mut num_logs = 0;
+
+        while let Some(pb) = stream.try_next().await? {
+            num_logs += pb
+                .receipts
+                .iter()
+                .flat_map(|r| &r.logs)
+                .filter(|log| filter.matches(log))
+                .count();
+
+            phantom_blocks.push(pb);
+
+            // the caller rejects the query anyway, no need to retrieve the
+            // remaining blocks
+            if max_logs.map_or(false, |max| num_logs > max) {
+                break;
+            }
+        }
+
+        Ok(phantom_blocks)
+    }
+
     pub fn get_network_type(&self) -> &Network {
         self.network.get_network_type()
     }
 }
+
+/// Appends the eSpace transactions of a block and the phantom transactions
+/// recovered from its native transactions to `phantom_block`, along with
+/// their receipts. `accumulated_gas_used` is the gas used by the previous
+/// blocks of the epoch and is updated to include this block.
+fn append_to_phantom_block(
+    phantom_block: &mut PhantomBlock, txs: Vec<SignedTransaction>,
+    receipts: Vec<Receipt>, accumulated_gas_used: &mut U256, cip90: bool,
+    evm_chain_id: u32,
+) {
+    let gas_used_offset = *accumulated_gas_used;
+
+    for (tx, receipt) in txs.into_iter().zip(receipts) {
+        match tx.space() {
+            Space::Ethereum => {
+                // we do not return non-executed transaction
+                if receipt.outcome_status == TransactionStatus::Skipped {
+                    continue;
+                }
+
+                *accumulated_gas_used =
+                    gas_used_offset + receipt.accumulated_gas_used;
+
+                phantom_block.bloom.accrue_bloom(&receipt.log_bloom);
+                phantom_block.receipts.push(Receipt {
+                    accumulated_gas_used: *accumulated_gas_used,
+                    ..receipt
+                });
+                phantom_block.transactions.push(Arc::new(tx));
+                phantom_block.errors.push("".into());
+            }
+            Space::Native => {
+                // note: failing transactions and transactions before
+                // cip90 will not produce any phantom txs
+                if receipt.outcome_status != TransactionStatus::Success
+                    || !cip90
+                {
+                    continue;
+                }
+
+                let (phantom_txs, _) = build_bloom_and_recover_phantom(
+                    &receipt.logs[..],
+                    tx.hash(),
+                );
+
+                for p in phantom_txs {
+                    phantom_block
+                        .transactions
+                        .push(Arc::new(p.clone().into_eip155(evm_chain_id)));
+
+                    // note: phantom txs consume no gas
+                    let phantom_receipt = p.into_receipt(*accumulated_gas_used);
+
+                    phantom_block
+                        .bloom
+                        .accrue_bloom(&phantom_receipt.log_bloom);
+                    phantom_block.receipts.push(phantom_receipt);
+
+                    // note: phantom txs never fail
+                    phantom_block.errors.push("".into());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cfx_executor::internal_contract::{
+        cross_space_events::WithdrawEvent, SolidityEventTrait,
+    };
+    use cfx_parameters::internal_contract_addresses::CROSS_SPACE_CONTRACT_ADDRESS;
+    use primitives::{
+        transaction::{Eip155Transaction, NativeTransaction},
+        BlockHeaderBuilder,
+    };
+    use solidity_abi::{ABIEncodable, EventIndexEncodable};
+
+    fn empty_phantom_block() -> PhantomBlock {
+        PhantomBlock {
+            pivot_header: BlockHeaderBuilder::new().build(),
+            transactions: vec![],
+            receipts: vec![],
+            errors: vec![],
+            bloom: Bloom::zero(),
+            traces: vec![],
+            total_gas_limit: U256::zero(),
+        }
+    }
+
+    fn receipt(
+        outcome_status: TransactionStatus, accumulated_gas_used: u64,
+        logs: Vec<LogEntry>,
+    ) -> Receipt {
+        Receipt {
+            accumulated_gas_used: accumulated_gas_used.into(),
+            outcome_status,
+            logs,
+            ..Default::default()
+        }
+    }
+
+    fn withdraw_log(from: H160, nonce: U256, value: U256) -> LogEntry {
+        let mut topics = vec![WithdrawEvent::EVENT_SIG];
+        topics.extend((from.0, H160::zero()).indexed_event_encode());
+
+        LogEntry {
+            address: CROSS_SPACE_CONTRACT_ADDRESS,
+            topics,
+            data: (value, nonce).abi_encode(),
+            space: Space::Native,
+        }
+    }
+
+    #[test]
+    fn test_append_to_phantom_block() {
+        let sender = H160::from_low_u64_be(1);
+        let espace_tx = |nonce: u64| {
+            Eip155Transaction {
+                nonce: nonce.into(),
+                ..Default::default()
+            }
+            .fake_sign_rpc(sender.with_evm_space())
+        };
+        let native_tx = |nonce: u64| {
+            NativeTransaction {
+                nonce: nonce.into(),
+                ..Default::default()
+            }
+            .fake_sign(sender.with_native_space())
+        };
+        let withdraw = withdraw_log(sender, 7.into(), 100.into());
+
+        let txs = vec![
+            espace_tx(0),
+            espace_tx(1),
+            native_tx(0),
+            native_tx(1),
+            espace_tx(2),
+        ];
+        let receipts = vec![
+            receipt(TransactionStatus::Success, 21000, vec![]),
+            // not executed, excluded from the phantom block
+            receipt(TransactionStatus::Skipped, 21000, vec![]),
+            receipt(TransactionStatus::Success, 0, vec![withdraw.clone()]),
+            // failed, produces no phantom transaction
+            receipt(TransactionStatus::Failure, 0, vec![withdraw]),
+            receipt(TransactionStatus::Failure, 42000, vec![]),
+        ];
+
+        let mut pb = empty_phantom_block();
+        let mut accumulated_gas_used = U256::from(50000);
+        append_to_phantom_block(
+            &mut pb,
+            txs.clone(),
+            receipts.clone(),
+            &mut accumulated_gas_used,
+            true,
+            1030,
+        );
+
+        assert_eq!(pb.transactions.len(), 3);
+        assert_eq!(pb.receipts.len(), 3);
+        assert_eq!(pb.errors.len(), 3);
+
+        assert_eq!(pb.transactions[0].hash(), txs[0].hash());
+        assert_eq!(pb.transactions[2].hash(), txs[4].hash());
+
+        // the phantom transaction of the withdrawal
+        let phantom = &pb.transactions[1];
+        assert_eq!(phantom.sender(), sender.with_evm_space());
+        assert_eq!(*phantom.nonce(), 7.into());
+        assert_eq!(phantom.chain_id(), Some(1030));
+        assert_eq!(pb.receipts[1].outcome_status, TransactionStatus::Success);
+
+        // gas used is offset by the previous blocks, phantom txs use none
+        let gas_used: Vec<_> =
+            pb.receipts.iter().map(|r| r.accumulated_gas_used).collect();
+        assert_eq!(gas_used, vec![71000.into(), 71000.into(), 92000.into()]);
+        assert_eq!(accumulated_gas_used, 92000.into());
+
+        // before cip90, native transactions produce no phantom transactions
+        let mut pb = empty_phantom_block();
+        let mut accumulated_gas_used = U256::zero();
+        append_to_phantom_block(
+            &mut pb,
+            txs,
+            receipts,
+            &mut accumulated_gas_used,
+            false,
+            1030,
+        );
+        assert_eq!(pb.transactions.len(), 2);
+        assert_eq!(accumulated_gas_used, 42000.into());
+    }
+}
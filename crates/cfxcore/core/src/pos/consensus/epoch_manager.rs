@@ -52,7 +52,9 @@ use diem_types::{
     epoch_change::EpochChangeProof,
     epoch_state::EpochState,
     transaction::{SignedTransaction, TransactionPayload},
-    validator_config::{ConsensusPrivateKey, ConsensusVRFPrivateKey},
+    validator_config::{
+        ConsensusPrivateKey, ConsensusPublicKey, ConsensusVRFPublicKey,
+    },
 };
 use futures::{
     channel::{mpsc, oneshot},
@@ -60,7 +62,7 @@ use futures::{
 };
 use parking_lot::RwLock;
 use pow_types::PowInterface;
-use safety_rules::SafetyRules;
+use safety_rules::TSafetyRules;
 use std::{
     cmp::Ordering,
     sync::{
@@ -75,8 +77,10 @@ use std::{
 pub struct EpochManager {
     author: Author,
     config: ConsensusConfig,
-    consensus_private_key: ConfigKey<ConsensusPrivateKey>,
-    vrf_private_key: ConfigKey<ConsensusVRFPrivateKey>,
+    public_key: ConsensusPublicKey,
+    vrf_public_key: ConsensusVRFPublicKey,
+    // Only loaded with the local SafetyRules, for the test commands.
+    consensus_private_key: Option<ConfigKey<ConsensusPrivateKey>>,
     vrf_proposal_threshold: U256,
     chain_id: ChainId,
     time_service: Arc<dyn TimeService>,
@@ -88,7 +92,7 @@ pub struct EpochManager {
     txn_manager: Arc<dyn TxnManager>,
     state_computer: Arc<dyn StateComputer>,
     storage: Arc<dyn PersistentLivenessStorage>,
-    safety_rules: Arc<RwLock<SafetyRules>>,
+    safety_rules: Arc<RwLock<Box<dyn TSafetyRules + Send + Sync>>>,
     processor: Option<RoundManager>,
     // Conflux PoW handler
     pow_handler: Arc<dyn PowInterface>,
@@ -122,31 +126,24 @@ impl EpochManager {
         started_as_voter: bool,
     ) -> Self {
         let config = node_config.consensus.clone();
-        let sr_config = &node_config.consensus.safety_rules;
         let PosNodeKeys {
             author,
+            public_key,
+            vrf_public_key,
+            safety_rules,
             consensus_private_key,
-            vrf_private_key,
         } = node_keys;
         let PosChainParams {
             chain_id,
             vrf_proposal_threshold,
         } = chain_params;
-        let consensus_private_key = ConfigKey::new(consensus_private_key);
-        let vrf_private_key = ConfigKey::new(vrf_private_key);
-        let safety_rules = safety_rules::create_safety_rules(
-            sr_config,
-            author,
-            consensus_private_key.private_key(),
-            Some(vrf_private_key.private_key()),
-            /* export_consensus_key */ true,
-        );
         diem_debug!("EpochManager.author={:?}", author);
         Self {
             author,
             config,
-            consensus_private_key,
-            vrf_private_key,
+            public_key,
+            vrf_public_key,
+            consensus_private_key: consensus_private_key.map(ConfigKey::new),
             vrf_proposal_threshold,
             chain_id,
             time_service,
@@ -232,7 +229,7 @@ impl EpochManager {
             }
             ConsensusProposerType::VrfProposer => Box::new(VrfProposer::new(
                 self.author,
-                self.vrf_private_key.private_key(),
+                self.safety_rules.clone(),
                 self.vrf_proposal_threshold,
                 epoch_state.clone(),
             )),
@@ -396,7 +393,7 @@ impl EpochManager {
                     self.time_service.clone(),
                     self.config.max_block_size,
                     self.pow_handler.clone(),
-                    self.consensus_private_key.private_key(),
+                    self.safety_rules.clone(),
                 )),
                 None => None,
             };
@@ -433,8 +430,8 @@ impl EpochManager {
             self.chain_id,
             self.is_voting,
             self.election_control.clone(),
-            Some(self.consensus_private_key.clone()),
-            Some(self.vrf_private_key.clone()),
+            self.public_key.clone(),
+            self.vrf_public_key.clone(),
         );
         processor.start(last_vote).await;
         self.processor = Some(processor);
@@ -725,7 +722,7 @@ impl EpochManager {
         &mut self, block_id: HashValue,
     ) -> anyhow::Result<()> {
         diem_debug!("force_vote_proposal: {:?}", block_id);
-        let bls_key = self.consensus_private_key.private_key();
+        let bls_key = self.force_signing_key()?;
         let author = self.author;
         self.processor_mut()
             .force_vote_proposal(block_id, author, &bls_key)
@@ -736,12 +733,24 @@ impl EpochManager {
         &mut self, round: Round, parent_block_id: HashValue,
         payload: Vec<TransactionPayload>,
     ) -> anyhow::Result<()> {
-        let bls_key = self.consensus_private_key.private_key();
+        let bls_key = self.force_signing_key()?;
         self.processor_mut()
             .force_propose(round, parent_block_id, payload, &bls_key)
             .await
     }
 
+    /// The key to sign the messages breaking the safety rules, which is only
+    /// available with the local SafetyRules.
+    fn force_signing_key(&self) -> anyhow::Result<ConsensusPrivateKey> {
+        self.consensus_private_key
+            .as_ref()
+            .map(|key| key.private_key())
+            .ok_or(anyhow!(
+                "The PoS key is kept by the SafetyRules process, which only \
+                 signs the messages following the safety rules"
+            ))
+    }
+
     async fn force_sign_pivot_decision(
         &mut self, pivot_decision: PivotBlockDecision,
     ) -> anyhow::Result<()> {
@@ -7,7 +7,7 @@
 
 use std::sync::Arc;
 
-use anyhow::{bail, ensure, format_err, Context};
+use anyhow::{anyhow, bail, ensure, format_err, Context};
 
 use consensus_types::{
     block::Block,
@@ -18,11 +18,11 @@ use consensus_types::{
 use diem_logger::{debug as diem_debug, error as diem_error};
 use diem_types::{
     transaction::{RawTransaction, SignedTransaction, TransactionPayload},
-    validator_config::ConsensusPrivateKey,
     validator_verifier::ValidatorVerifier,
 };
-use parking_lot::Mutex;
+use parking_lot::{Mutex, RwLock};
 use pow_types::PowInterface;
+use safety_rules::TSafetyRules;
 
 use crate::pos::consensus::{
     block_storage::BlockReader, state_replication::TxnManager,
@@ -55,8 +55,8 @@ pub struct ProposalGenerator {
     last_round_generated: Mutex<Round>,
     // Handle the interaction with PoW consensus.
     pow_handler: Arc<dyn PowInterface>,
-    // TODO(lpl): Where to put them?
-    pub private_key: ConsensusPrivateKey,
+    // Signs the transactions of this validator.
+    safety_rules: Arc<RwLock<Box<dyn TSafetyRules + Send + Sync>>>,
 }
 
 impl ProposalGenerator {
@@ -64,7 +64,7 @@ impl ProposalGenerator {
         author: Author, block_store: Arc<dyn BlockReader + Send + Sync>,
         txn_manager: Arc<dyn TxnManager>, time_service: Arc<dyn TimeService>,
         max_block_size: u64, pow_handler: Arc<dyn PowInterface>,
-        private_key: ConsensusPrivateKey,
+        safety_rules: Arc<RwLock<Box<dyn TSafetyRules + Send + Sync>>>,
     ) -> Self {
         Self {
             author,
@@ -74,12 +74,22 @@ impl ProposalGenerator {
             max_block_size,
             last_round_generated: Mutex::new(0),
             pow_handler,
-            private_key,
+            safety_rules,
         }
     }
 
     pub fn author(&self) -> Author { self.author }
 
+    /// Signs a transaction of this validator with its SafetyRules.
+    pub fn sign_transaction(
+        &self, raw_tx: RawTransaction,
+    ) -> anyhow::Result<SignedTransaction> {
+        self.safety_rules
+            .write()
+            .sign_transaction(raw_tx)
+            .map_err(|e| anyhow!("sign_transaction: err={:?}", e))
+    }
+
     /// Creates a NIL block proposal extending the highest certified block from
     /// the block store.
     pub fn generate_nil_block(&self, round: Round) -> anyhow::Result<Block> {
@@ -226,9 +236,8 @@ impl ProposalGenerator {
                             self.author,
                         ) {
                             Ok(raw_tx) => {
-                                let signed_tx = raw_tx
-                                    .sign(&self.private_key)?
-                                    .into_inner();
+                                let signed_tx =
+                                    self.sign_transaction(raw_tx)?;
                                 payload.push(signed_tx);
                             }
                             // TODO(lpl): This is not supposed to happen, so
@@ -292,7 +301,7 @@ impl ProposalGenerator {
                     u64::MAX,
                     Default::default(),
                 );
-                raw_tx.sign(&self.private_key).map(|tx| tx.into_inner())
+                self.sign_transaction(raw_tx)
             })
             .collect::<anyhow::Result<Vec<SignedTransaction>>>()?;
 
@@ -10,18 +10,20 @@ use consensus_types::common::{Author, Round};
 
 use cfx_types::U256;
 use consensus_types::{block::Block, block_data::BlockData};
-use diem_crypto::{vrf_number_with_nonce, HashValue, VRFPrivateKey, VRFProof};
-use diem_logger::debug as diem_debug;
+use diem_crypto::{vrf_number_with_nonce, HashValue, VRFProof};
+use diem_logger::{debug as diem_debug, warn as diem_warn};
 use diem_types::{
-    epoch_state::EpochState,
-    validator_config::{ConsensusVRFPrivateKey, ConsensusVRFProof},
+    epoch_state::EpochState, validator_config::ConsensusVRFProof,
 };
-use parking_lot::Mutex;
+use parking_lot::{Mutex, RwLock};
+use safety_rules::TSafetyRules;
+use std::sync::Arc;
 
 /// The round proposer maps a round to author
 pub struct VrfProposer {
     author: Author,
-    vrf_private_key: ConsensusVRFPrivateKey,
+    // Computes the VRF proofs with the VRF key of `author`.
+    safety_rules: Arc<RwLock<Box<dyn TSafetyRules + Send + Sync>>>,
 
     proposal_threshold: HashValue,
 
@@ -35,13 +37,14 @@ pub struct VrfProposer {
 
 impl VrfProposer {
     pub fn new(
-        author: Author, vrf_private_key: ConsensusVRFPrivateKey,
+        author: Author,
+        safety_rules: Arc<RwLock<Box<dyn TSafetyRules + Send + Sync>>>,
         proposal_threshold_u256: U256, epoch_state: EpochState,
     ) -> Self {
         let proposal_threshold = proposal_threshold_u256.to_big_endian();
         Self {
             author,
-            vrf_private_key,
+            safety_rules,
             proposal_threshold: HashValue::new(proposal_threshold),
             // current_round and current_seed will not be used before
             // `next_round` is called.
@@ -52,6 +55,16 @@ impl VrfProposer {
         }
     }
 
+    fn compute_vrf_proof(&self, seed: &[u8]) -> Option<ConsensusVRFProof> {
+        match self.safety_rules.write().compute_vrf_proof(seed) {
+            Ok(proof) => Some(proof),
+            Err(e) => {
+                diem_warn!("compute_vrf_proof: err={:?}", e);
+                None
+            }
+        }
+    }
+
     pub fn get_vrf_number(&self, block: &Block) -> Option<HashValue> {
         Some(vrf_number_with_nonce(
             &block.vrf_proof()?.to_hash().ok()?,
@@ -87,12 +100,10 @@ impl ProposerElection for VrfProposer {
         let mut round_seed = self.current_seed.lock().clone();
         let leader_round = (round + 1) / 3;
         round_seed.extend_from_slice(&leader_round.to_be_bytes());
-        let vrf_output = self
-            .vrf_private_key
-            .compute(round_seed.as_slice())
-            .expect("vrf compute fail")
-            .to_hash()
-            .expect("to hash error");
+        let vrf_output = match self.compute_vrf_proof(round_seed.as_slice()) {
+            Some(vrf_proof) => vrf_proof.to_hash().expect("to hash error"),
+            None => return false,
+        };
         for nonce in 0..=voting_power {
             let vrf_number = vrf_number_with_nonce(&vrf_output, nonce);
             if vrf_number <= self.proposal_threshold {
@@ -207,14 +218,11 @@ impl ProposerElection for VrfProposer {
             .verifier()
             .get_voting_power(&block_data.author()?)?;
 
-        let vrf_proof = self
-            .vrf_private_key
-            .compute(
-                block_data
-                    .vrf_round_seed(self.current_seed.lock().as_slice())
-                    .as_slice(),
-            )
-            .ok()?;
+        let vrf_proof = self.compute_vrf_proof(
+            block_data
+                .vrf_round_seed(self.current_seed.lock().as_slice())
+                .as_slice(),
+        )?;
         let vrf_output = vrf_proof.to_hash().ok()?;
         for nonce in 0..=voting_power {
             let vrf_number = vrf_number_with_nonce(&vrf_output, nonce);
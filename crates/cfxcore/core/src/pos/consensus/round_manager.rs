@@ -34,8 +34,7 @@ use consensus_types::{
     vote::Vote,
     vote_msg::VoteMsg,
 };
-use diem_config::keys::ConfigKey;
-use diem_crypto::{hash::CryptoHash, HashValue, SigningKey, VRFPrivateKey};
+use diem_crypto::{hash::CryptoHash, HashValue, SigningKey};
 use diem_logger::prelude::*;
 use diem_types::{
     account_address::{from_consensus_public_key, AccountAddress},
@@ -47,10 +46,12 @@ use diem_types::{
         ConflictSignature, DisputePayload, ElectionPayload, RawTransaction,
         SignedTransaction, TransactionPayload,
     },
-    validator_config::{ConsensusPrivateKey, ConsensusVRFPrivateKey},
+    validator_config::{
+        ConsensusPrivateKey, ConsensusPublicKey, ConsensusVRFPublicKey,
+    },
     validator_verifier::ValidatorVerifier,
 };
-use safety_rules::SafetyRules;
+use safety_rules::{SafetyRules, TSafetyRules};
 
 use crate::pos::{
     mempool::SubmissionStatus,
@@ -138,7 +139,7 @@ pub struct RoundManager {
     proposer_election: Box<dyn ProposerElection + Send + Sync>,
     // None if this is not a validator.
     proposal_generator: Option<ProposalGenerator>,
-    safety_rules: Arc<RwLock<SafetyRules>>,
+    safety_rules: Arc<RwLock<Box<dyn TSafetyRules + Send + Sync>>>,
     network: ConsensusNetworkSender,
     storage: Arc<dyn PersistentLivenessStorage>,
     sync_only: bool,
@@ -150,8 +151,8 @@ pub struct RoundManager {
 
     is_voting: bool,
     election_control: Arc<AtomicBool>,
-    consensus_private_key: Option<ConfigKey<ConsensusPrivateKey>>,
-    vrf_private_key: Option<ConfigKey<ConsensusVRFPrivateKey>>,
+    public_key: ConsensusPublicKey,
+    vrf_public_key: ConsensusVRFPublicKey,
 }
 
 impl RoundManager {
@@ -160,7 +161,7 @@ impl RoundManager {
         round_state: RoundState,
         proposer_election: Box<dyn ProposerElection + Send + Sync>,
         proposal_generator: Option<ProposalGenerator>,
-        safety_rules: Arc<RwLock<SafetyRules>>,
+        safety_rules: Arc<RwLock<Box<dyn TSafetyRules + Send + Sync>>>,
         network: ConsensusNetworkSender,
         storage: Arc<dyn PersistentLivenessStorage>, sync_only: bool,
         tx_sender: mpsc::Sender<(
@@ -168,8 +169,7 @@ impl RoundManager {
             oneshot::Sender<anyhow::Result<SubmissionStatus>>,
         )>,
         chain_id: ChainId, is_voting: bool, election_control: Arc<AtomicBool>,
-        consensus_private_key: Option<ConfigKey<ConsensusPrivateKey>>,
-        vrf_private_key: Option<ConfigKey<ConsensusVRFPrivateKey>>,
+        public_key: ConsensusPublicKey, vrf_public_key: ConsensusVRFPublicKey,
     ) -> Self {
         Self {
             epoch_state,
@@ -185,8 +185,8 @@ impl RoundManager {
             tx_sender,
             chain_id,
             election_control,
-            consensus_private_key,
-            vrf_private_key,
+            public_key,
+            vrf_public_key,
         }
     }
 
@@ -299,8 +299,7 @@ impl RoundManager {
             },
             self.chain_id,
         );
-        let signed_tx =
-            raw_tx.sign(&proposal_generator.private_key)?.into_inner();
+        let signed_tx = proposal_generator.sign_transaction(raw_tx)?;
         let (tx, rx) = oneshot::channel();
         self.tx_sender.send((signed_tx, tx)).await?;
         // TODO(lpl): Check if we want to wait here.
@@ -324,38 +323,33 @@ impl RoundManager {
             // elected but cannot vote.
             return Ok(());
         }
-        if self.vrf_private_key.is_none()
-            || self.consensus_private_key.is_none()
-        {
-            diem_warn!("broadcast_election without keys");
-            return Ok(());
-        }
-        let private_key = self.consensus_private_key.as_ref().unwrap();
-        let vrf_private_key = self.vrf_private_key.as_ref().unwrap();
-        let author = from_consensus_public_key(
-            &private_key.public_key(),
-            &vrf_private_key.public_key(),
-        );
+        let author =
+            from_consensus_public_key(&self.public_key, &self.vrf_public_key);
         diem_debug!("broadcast_election starts");
         let pos_state = self.storage.pos_ledger_db().get_latest_pos_state();
         if let Some(target_term) = pos_state.next_elect_term(&author) {
             let epoch_vrf_seed = pos_state.target_term_seed(target_term);
+            let vrf_proof = self
+                .safety_rules
+                .write()
+                .compute_vrf_proof(epoch_vrf_seed.as_slice())
+                .map_err(|e| anyhow!("compute_vrf_proof: err={:?}", e))?;
             let election_payload = ElectionPayload {
-                public_key: private_key.public_key(),
-                vrf_public_key: vrf_private_key.public_key(),
+                public_key: self.public_key.clone(),
+                vrf_public_key: self.vrf_public_key.clone(),
                 target_term,
-                vrf_proof: vrf_private_key
-                    .private_key()
-                    .compute(epoch_vrf_seed.as_slice())
-                    .unwrap(),
+                vrf_proof,
             };
             let raw_tx = RawTransaction::new_election(
                 author,
                 election_payload,
                 self.chain_id,
             );
-            let signed_tx =
-                raw_tx.sign(&private_key.private_key())?.into_inner();
+            let signed_tx = self
+                .safety_rules
+                .write()
+                .sign_transaction(raw_tx)
+                .map_err(|e| anyhow!("sign_transaction: err={:?}", e))?;
             let (tx, rx) = oneshot::channel();
             self.tx_sender.send((signed_tx, tx)).await?;
             // TODO(lpl): Check if we want to wait here.
@@ -395,7 +389,7 @@ impl RoundManager {
             signed_proposal.set_vrf_nonce_and_proof(
                 self.proposer_election
                     .gen_vrf_nonce_and_proof(signed_proposal.block_data())
-                    .expect("threshold checked in is_valid_proposer"),
+                    .ok_or(anyhow!("Failed to generate the VRF proof"))?,
             )
         }
         diem_debug!(self.new_log(LogEvent::Propose), "{}", signed_proposal);
@@ -1029,9 +1023,8 @@ impl RoundManager {
                             proposal_generator.author(),
                             dispute_payload,
                         );
-                        let signed_tx = raw_tx
-                            .sign(&proposal_generator.private_key)?
-                            .into_inner();
+                        let signed_tx =
+                            proposal_generator.sign_transaction(raw_tx)?;
                         // TODO(lpl): Track disputed nodes to avoid sending
                         // multiple dispute, and retry if needed?
                         let (tx, rx) = oneshot::channel();
@@ -1266,8 +1259,7 @@ impl RoundManager {
             pivot_decision,
             self.chain_id,
         );
-        let signed_tx =
-            raw_tx.sign(&proposal_generator.private_key)?.into_inner();
+        let signed_tx = proposal_generator.sign_transaction(raw_tx)?;
         let (tx, rx) = oneshot::channel();
         self.tx_sender.send((signed_tx, tx)).await?;
         // TODO(lpl): Check if we want to wait here.
@@ -37,7 +37,9 @@ use diem_types::{
     chain_id::ChainId,
     term_state::NodeID,
     transaction::SignedTransaction,
-    validator_config::{ConsensusPrivateKey, ConsensusVRFPrivateKey},
+    validator_config::{
+        ConsensusPrivateKey, ConsensusPublicKey, ConsensusVRFPublicKey,
+    },
 };
 use executor::db_bootstrapper::maybe_bootstrap;
 use futures::channel::{
@@ -46,6 +48,7 @@ use futures::channel::{
 };
 use network::NetworkService;
 use pos_ledger_db::PosLedgerDB;
+use safety_rules::TSafetyRules;
 use std::{
     boxed::Box,
     fs,
@@ -61,15 +64,19 @@ use tokio::runtime::Runtime;
 const AC_SMP_CHANNEL_BUFFER_SIZE: usize = 1_024;
 const INTRA_NODE_CHANNEL_BUFFER_SIZE: usize = 1;
 
-/// This validator's identity and signing secrets.
+/// This validator's identity and the SafetyRules signing with its keys.
 ///
-/// These used to live inside `SafetyRulesConfig.test` / `.vrf_private_key`,
-/// but they are supplied by the Conflux-side TOML and have nothing to do
-/// with per-chain parameters.
+/// The keys are supplied by the Conflux-side TOML, or kept by the SafetyRules
+/// process, and have nothing to do with per-chain parameters.
 pub struct PosNodeKeys {
     pub author: AccountAddress,
-    pub consensus_private_key: ConsensusPrivateKey,
-    pub vrf_private_key: ConsensusVRFPrivateKey,
+    pub public_key: ConsensusPublicKey,
+    pub vrf_public_key: ConsensusVRFPublicKey,
+    /// Signs all the consensus messages and transactions of this validator.
+    pub safety_rules: Box<dyn TSafetyRules + Send + Sync>,
+    /// The consensus key loaded by the node with the local SafetyRules. It is
+    /// only used by the test commands which sign conflicting messages.
+    pub consensus_private_key: Option<ConsensusPrivateKey>,
 }
 
 /// Chain-wide PoS consensus parameters that every validator must agree on.
@@ -15,17 +15,23 @@ use crate::{
         request_manager::{AsAny, Request},
         Error, ProtocolConfiguration, SYNC_PROTO_V1, SYNC_PROTO_V3,
     },
+    transaction_pool::TransactionPoolError,
 };
 use cfx_types::H256;
 use malloc_size_of_derive::MallocSizeOf as DeriveMallocSizeOf;
 use metrics::MeterTimer;
-use network::service::ProtocolVersion;
+use network::{service::ProtocolVersion, Misbehavior};
 use primitives::{transaction::TxPropagateId, TransactionWithSignature};
 use priority_send_queue::SendQueuePriority;
 use rlp::{Decodable, DecoderError, Encodable, Rlp, RlpStream};
 use rlp_derive::{RlpDecodable, RlpEncodable};
 use siphasher::sip::SipHasher24;
-use std::{any::Any, collections::HashSet, hash::Hasher, time::Duration};
+use std::{
+    any::Any,
+    collections::{HashMap, HashSet},
+    hash::Hasher,
+    time::Duration,
+};
 
 #[derive(Debug, PartialEq)]
 pub struct Transactions {
@@ -89,19 +95,7 @@ impl Handleable for Transactions {
                 .consensus
                 .tx_pool()
                 .insert_new_transactions(transactions);
-            if failure.is_empty() {
-                debug!(
-                    "Transactions successfully inserted to transaction pool"
-                );
-            } else {
-                debug!(
-                    "{} transactions are rejected by the transaction pool",
-                    failure.len()
-                );
-                for (tx, e) in failure {
-                    trace!("Transaction {} is rejected by the transaction pool: error = {}", tx, e);
-                }
-            }
+            note_rejected_transactions(ctx, failure);
 
             ctx.manager
                 .request_manager
@@ -114,6 +108,34 @@ impl Handleable for Transactions {
     }
 }
 
+/// Logs the transactions rejected by the transaction pool, and lowers the
+/// reputation of the peer if any of them is malformed.
+fn note_rejected_transactions(
+    ctx: &Context, failure: HashMap<H256, TransactionPoolError>,
+) {
+    if failure.is_empty() {
+        debug!("Transactions successfully inserted to transaction pool");
+        return;
+    }
+    debug!(
+        "{} transactions are rejected by the transaction pool",
+        failure.len()
+    );
+    let mut malformed = false;
+    for (tx, e) in failure {
+        trace!(
+            "Transaction {} is rejected by the transaction pool: error = {}",
+            tx,
+            e
+        );
+        malformed |= e.is_malformed();
+    }
+    if malformed {
+        ctx.io
+            .note_misbehavior(&ctx.node_id, Misbehavior::SpamTransactions);
+    }
+}
+
 /////////////////////////////////////////////////////////////////////
 #[derive(Debug, PartialEq)]
 pub struct TransactionDigests {
@@ -596,19 +618,7 @@ impl Handleable for GetTransactionsResponse {
                 .consensus
                 .tx_pool()
                 .insert_new_transactions(self.transactions);
-            if failure.is_empty() {
-                debug!(
-                    "Transactions successfully inserted to transaction pool"
-                );
-            } else {
-                debug!(
-                    "{} transactions are rejected by the transaction pool",
-                    failure.len()
-                );
-                for (tx, e) in failure {
-                    trace!("Transaction {} is rejected by the transaction pool: error = {}", tx, e);
-                }
-            }
+            note_rejected_transactions(ctx, failure);
             ctx.manager
                 .request_manager
                 .transactions_received_from_digests(ctx.io, &req, signed_trans);
@@ -675,19 +685,7 @@ impl Handleable for GetTransactionsFromTxHashesResponse {
                 .consensus
                 .tx_pool()
                 .insert_new_transactions(self.transactions);
-            if failure.is_empty() {
-                debug!(
-                    "Transactions successfully inserted to transaction pool"
-                );
-            } else {
-                debug!(
-                    "{} transactions are rejected by the transaction pool",
-                    failure.len()
-                );
-                for (tx, e) in failure {
-                    trace!("Transaction {} is rejected by the transaction pool: error = {}", tx, e);
-                }
-            }
+            note_rejected_transactions(ctx, failure);
             ctx.manager
                 .request_manager
                 .transactions_received_from_tx_hashes(&req, signed_trans);
@@ -16,7 +16,7 @@ use cfx_parameters::sync::FAILED_REQUEST_RESEND_WAIT;
 use malloc_size_of::MallocSizeOf;
 use malloc_size_of_derive::MallocSizeOf as DeriveMallocSizeOf;
 use network::{
-    node_table::NodeId, Error as NetworkError, NetworkContext,
+    node_table::NodeId, Error as NetworkError, Misbehavior, NetworkContext,
     UpdateNodeOperation,
 };
 use parking_lot::Mutex;
@@ -161,6 +161,7 @@ impl RequestHandler {
     ) -> Vec<RequestMessage> {
         // Check if in-flight requests timeout
         let mut timeout_requests = Vec::new();
+        let mut peers_timed_out = Vec::new();
         let mut peers_to_disconnect = HashSet::new();
         let mut peers_to_send_pending_requests = HashSet::new();
         for sync_req in self.get_timeout_sync_requests() {
@@ -178,6 +179,7 @@ impl RequestHandler {
                     } else {
                         peers_to_send_pending_requests.insert(peer_id);
                     }
+                    peers_timed_out.push(peer_id);
                 }
                 req.request.notify_timeout();
                 timeout_requests.push(req);
@@ -190,9 +192,13 @@ impl RequestHandler {
         } else {
             Some(UpdateNodeOperation::Failure)
         };
+        // Note `self.peers` will be used in `note_misbehavior` and
+        // `disconnect_peer` if the peer is disconnected, so we must call them
+        // without locking `self.peers`.
+        for peer_id in peers_timed_out {
+            io.note_misbehavior(&peer_id, Misbehavior::RequestTimeout);
+        }
         for peer_id in peers_to_disconnect {
-            // Note `self.peers` will be used in `disconnect_peer`, so we must
-            // call it without locking `self.peers`.
             io.disconnect_peer(
                 &peer_id,
                 op,
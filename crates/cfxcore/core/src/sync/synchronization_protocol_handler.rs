@@ -36,7 +36,7 @@ use metrics::{register_meter_with_group, Meter, MeterTimer};
 use network::{
     node_table::NodeId, service::ProtocolVersion,
     throttling::THROTTLING_SERVICE, Error as NetworkError, HandlerWorkType,
-    NetworkContext, NetworkProtocolHandler, UpdateNodeOperation,
+    Misbehavior, NetworkContext, NetworkProtocolHandler, UpdateNodeOperation,
 };
 use parking_lot::{Mutex, RwLock};
 use primitives::{Block, BlockHeader, EpochId, SignedTransaction};
@@ -603,11 +603,15 @@ impl SynchronizationProtocolHandler {
         let reason = format!("{}", e);
         let error_reason = format!("{:?}", e);
         let mut op = None;
+        let mut misbehavior = None;
 
         // NOTE, DO NOT USE WILDCARD IN THE FOLLOWING MATCH STATEMENT!
         // COMPILER WILL HELP TO FIND UNHANDLED ERROR CASES.
         match e {
-            Error::InvalidBlock => op = Some(UpdateNodeOperation::Failure),
+            Error::InvalidBlock => {
+                op = Some(UpdateNodeOperation::Failure);
+                misbehavior = Some(Misbehavior::InvalidBlock);
+            }
             Error::InvalidGetBlockTxn(_) => {
                 op = Some(UpdateNodeOperation::Demotion)
             }
@@ -634,7 +638,9 @@ impl SynchronizationProtocolHandler {
                 disconnect = false;
                 warn = false;
             }
-            Error::TooManyTrans => {}
+            Error::TooManyTrans => {
+                misbehavior = Some(Misbehavior::SpamTransactions)
+            }
             Error::InvalidTimestamp => op = Some(UpdateNodeOperation::Demotion),
             Error::InvalidSnapshotManifest(_) => {
                 op = Some(UpdateNodeOperation::Demotion)
@@ -719,6 +725,9 @@ impl SynchronizationProtocolHandler {
             );
         }
 
+        if let Some(misbehavior) = misbehavior {
+            io.note_misbehavior(peer, misbehavior);
+        }
         if disconnect {
             io.disconnect_peer(peer, op, reason.as_str());
         }
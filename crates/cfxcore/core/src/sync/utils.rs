@@ -109,6 +109,24 @@ pub fn create_simple_block(
 
 pub fn initialize_data_manager(
     db_dir: &str, dbtype: DbType, pow: Arc<PowComputer>, vm: VmFactory,
+) -> (Arc<BlockDataManager>, Arc<Block>) {
+    initialize_data_manager_with_config(
+        db_dir,
+        DataManagerConfiguration::new(
+            false,                          /* do not persist transaction
+                                             * address */
+            false, /* do not persist block number index */
+            Duration::from_millis(300_000), /* max cached tx count */
+            dbtype,
+        ),
+        pow,
+        vm,
+    )
+}
+
+pub fn initialize_data_manager_with_config(
+    db_dir: &str, config: DataManagerConfiguration, pow: Arc<PowComputer>,
+    vm: VmFactory,
 ) -> (Arc<BlockDataManager>, Arc<Block>) {
     let ledger_db = db::open_database(
         db_dir,
@@ -168,13 +186,7 @@ pub fn initialize_data_manager(
         ledger_db.clone(),
         storage_manager,
         worker_thread_pool,
-        DataManagerConfiguration::new(
-            false,                          /* do not persist transaction
-                                             * address */
-            false, /* do not persist block number index */
-            Duration::from_millis(300_000), /* max cached tx count */
-            dbtype,
-        ),
+        config,
         pow,
     ));
     (data_man, genesis_block)
@@ -192,8 +204,10 @@ pub fn initialize_synchronization_graph_with_data_manager(
         None,
         // These configurations will not be used.
         PosConfiguration {
-            bls_key: ConfigKey::new(ConsensusPrivateKey::generate(&mut rng)),
-            vrf_key: ConfigKey::new(ConsensusVRFPrivateKey::generate(&mut rng)),
+            pos_keys: Some((
+                ConfigKey::new(ConsensusPrivateKey::generate(&mut rng)),
+                ConfigKey::new(ConsensusVRFPrivateKey::generate(&mut rng)),
+            )),
             diem_conf_path: Default::default(),
             protocol_conf: Default::default(),
             pos_initial_nodes_path: "".to_string(),
@@ -13,7 +13,7 @@ use primitives::{block_header::compute_next_price, SignedTransaction};
 use rand::SeedableRng;
 use rand_xorshift::XorShiftRng;
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, HashMap, HashSet},
     sync::Arc,
 };
 
@@ -97,7 +97,7 @@ impl DeferredPool {
     >(
         &'a mut self, space: Space, block_gas_limit: U256,
         block_size_limit: usize, tx_num_limit: usize, tx_min_price: U256,
-        validity: F,
+        priority_senders: &HashSet<AddressWithSpace>, validity: F,
     ) -> (Vec<Arc<SignedTransaction>>, U256, usize) {
         if block_gas_limit.is_zero()
             || block_size_limit == 0
@@ -138,11 +138,18 @@ impl DeferredPool {
         let mut rest_size_limit = block_size_limit;
         let mut rest_gas_limit = block_gas_limit;
 
-        'all: for (sender, sender_txs, _) in self
-            .packing_pool
-            .in_space_mut(space)
+        // The ready transactions of the priority senders are considered before
+        // the randomly sampled ones.
+        let packing_pool = self.packing_pool.in_space(space);
+        let priority_txs = priority_senders.iter().filter_map(|sender| {
+            Some((*sender, packing_pool.get_transactions(sender)?))
+        });
+        let sampled_txs = packing_pool
             .tx_sampler(&mut rng, block_gas_limit.into())
-        {
+            .filter(|(sender, _, _)| !priority_senders.contains(sender))
+            .map(|(sender, sender_txs, _)| (sender, sender_txs));
+
+        'all: for (sender, sender_txs) in priority_txs.chain(sampled_txs) {
             'sender: for tx in sender_txs.iter() {
                 if tx.gas_price() < &tx_min_price {
                     trace!(
@@ -15,7 +15,7 @@ use primitives::{
     Action, SignedTransaction, Transaction,
 };
 use rand::RngCore;
-use std::{str::FromStr, sync::Arc};
+use std::{collections::HashSet, str::FromStr, sync::Arc};
 
 const PRIVATE_KEY: &str =
     "74806d258099decd5f5bd500f5b318aaaa0a8a289f8dcb10a9609966d8a0e442";
@@ -368,6 +368,7 @@ fn test_packing_sampler_valid_transaction() {
         40000,
         10,
         U256::from(20),
+        &HashSet::new(),
         validity,
     );
 
@@ -430,6 +431,7 @@ fn test_packing_sampler_empty_limits() {
         0,
         0,
         U256::from(50),
+        &HashSet::new(),
         validity,
     );
 
@@ -1097,3 +1099,36 @@ fn test_eth_content_from() {
     );
     assert!(result4.len() == 0);
 }
+
+#[test]
+fn test_packing_sampler_priority_senders() {
+    let mut dpool = DeferredPool::new_for_test();
+    let senders: Vec<_> = (0..10).map(|_| Random.generate().unwrap()).collect();
+    for sender in &senders {
+        let addr = sender.address().with_native_space();
+        let tx = new_test_tx(sender, 0, 100, 21000, 0, Space::Native);
+        dpool.insert(TxWithReadyInfo::new(tx, false, U256::zero(), 0), false);
+        dpool.recalculate_readiness_with_local_info(
+            &addr,
+            U256::from(0),
+            U256::from(1_000_000_000_000_000u64),
+        );
+    }
+
+    // Only one transaction fits, and it is always from the priority sender.
+    let priority = senders[7].address().with_native_space();
+    let validity = |_: &SignedTransaction| PackingCheckResult::Pack;
+    for _ in 0..10 {
+        let (txs, _, _) = dpool.packing_sampler(
+            Space::Native,
+            U256::from(15000000),
+            40000,
+            1,
+            U256::from(20),
+            &HashSet::from([priority]),
+            validity,
+        );
+        assert_eq!(txs.len(), 1);
+        assert_eq!(txs[0].sender(), priority);
+    }
+}
@@ -38,6 +38,23 @@ pub enum TransactionPoolError {
     StateDbError(String),
 }
 
+impl TransactionPoolError {
+    /// Whether the transaction is invalid regardless of the state, so that
+    /// the peer relaying it is faulty.
+    pub fn is_malformed(&self) -> bool {
+        matches!(
+            self,
+            TransactionPoolError::RlpDecodeError(_)
+                | TransactionPoolError::TransactionError(
+                    TransactionError::InvalidSignature(_)
+                        | TransactionError::InvalidRlp(_)
+                        | TransactionError::InvalidGasLimit(_)
+                        | TransactionError::TooBig
+                )
+        )
+    }
+}
+
 impl From<cfx_statedb::Error> for TransactionPoolError {
     fn from(value: cfx_statedb::Error) -> Self {
         TransactionPoolError::StateDbError(format!(
@@ -0,0 +1,333 @@
+// Copyright 2026 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+//! An on-disk journal of the transactions in the pool, so the pending
+//! transactions survive node restarts.
+//!
+//! The journal is a sequence of RLP records `[local, transaction]` appended
+//! as the transactions are inserted. It is rewritten from the pool content on
+//! rotation, which drops the transactions that have left the pool. The
+//! rotation happens once the journal has doubled since the last one, so its
+//! cost is amortized over the insertions even if the pool is large.
+
+use cfx_types::H256;
+use parking_lot::{Mutex, RwLock};
+use primitives::{SignedTransaction, TransactionWithSignature};
+use rlp::{Rlp, RlpStream};
+use std::{
+    cmp::max,
+    collections::HashSet,
+    fs::{self, File, OpenOptions},
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+#[derive(Clone, Debug)]
+pub struct TxJournalConfig {
+    pub path: PathBuf,
+    /// Whether the transactions received from the peers are journaled as
+    /// well as the locally submitted ones.
+    pub journal_all: bool,
+    /// The journal is not rotated until it grows beyond this size in bytes.
+    pub max_size: u64,
+}
+
+struct JournalWriter {
+    file: File,
+    size: u64,
+    /// The size of the journal after the last rotation, i.e. of the
+    /// transactions still in the pool at that time.
+    live_size: u64,
+}
+
+impl JournalWriter {
+    /// Whether the journal has grown enough to be rotated.
+    fn needs_rotation(&self, max_size: u64) -> bool {
+        self.size > max(max_size, self.live_size.saturating_mul(2))
+    }
+}
+
+pub struct TransactionJournal {
+    config: TxJournalConfig,
+    /// `None` if the journal file can not be opened, in which case the
+    /// insertions are not journaled until the next successful rotation.
+    writer: Mutex<Option<JournalWriter>>,
+    /// The hashes of the locally submitted transactions in the pool.
+    local_transactions: RwLock<HashSet<H256>>,
+}
+
+impl TransactionJournal {
+    pub fn new(config: TxJournalConfig) -> Self {
+        let writer = match Self::open_writer(&config.path) {
+            Ok(writer) => Some(writer),
+            Err(e) => {
+                warn!(
+                    "Failed to open transaction journal {:?}: {:?}",
+                    config.path, e
+                );
+                None
+            }
+        };
+        TransactionJournal {
+            config,
+            writer: Mutex::new(writer),
+            local_transactions: Default::default(),
+        }
+    }
+
+    pub fn is_local(&self, hash: &H256) -> bool {
+        self.local_transactions.read().contains(hash)
+    }
+
+    /// Records the transactions inserted into the pool. Returns `true` if the
+    /// journal needs to be rotated.
+    pub fn insert(
+        &self, transactions: &[Arc<SignedTransaction>], local: bool,
+    ) -> bool {
+        if local {
+            self.local_transactions
+                .write()
+                .extend(transactions.iter().map(|tx| tx.hash()));
+        } else if !self.config.journal_all {
+            return false;
+        }
+        if transactions.is_empty() {
+            return false;
+        }
+
+        let mut buf = vec![];
+        for tx in transactions {
+            buf.extend_from_slice(&encode_record(&tx.transaction, local));
+        }
+
+        let mut writer = self.writer.lock();
+        let writer = match writer.as_mut() {
+            Some(writer) => writer,
+            None => return false,
+        };
+        if let Err(e) = writer.file.write_all(&buf) {
+            warn!("Failed to write transaction journal: {:?}", e);
+            return false;
+        }
+        writer.size += buf.len() as u64;
+        writer.needs_rotation(self.config.max_size)
+    }
+
+    /// Marks the transactions restored from the journal as local ones,
+    /// without writing them again.
+    pub fn mark_local(&self, hashes: impl IntoIterator<Item = H256>) {
+        self.local_transactions.write().extend(hashes);
+    }
+
+    /// Loads the transactions in the journal and whether they are local. A
+    /// truncated or corrupted tail, e.g. left by a crash, is skipped.
+    pub fn load(&self) -> io::Result<Vec<(TransactionWithSignature, bool)>> {
+        let mut buf = vec![];
+        match File::open(&self.config.path) {
+            Ok(mut file) => file.read_to_end(&mut buf)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(e),
+        };
+
+        let mut transactions = vec![];
+        let mut offset = 0;
+        while offset < buf.len() {
+            match decode_record(&buf[offset..]) {
+                Ok((tx, local, len)) => {
+                    transactions.push((tx, local));
+                    offset += len;
+                }
+                Err(e) => {
+                    warn!(
+                        "Skip corrupted transaction journal from offset {}: {:?}",
+                        offset, e
+                    );
+                    break;
+                }
+            }
+        }
+        Ok(transactions)
+    }
+
+    /// Rewrites the journal with the transactions currently in the pool and
+    /// returns the number of transactions written.
+    pub fn rotate(
+        &self, pool_transactions: Vec<Arc<SignedTransaction>>,
+    ) -> io::Result<usize> {
+        // Hold the writer during the rotation, so no insertion is lost.
+        let mut writer = self.writer.lock();
+
+        let mut local_transactions = self.local_transactions.write();
+        let pool_hashes: HashSet<H256> =
+            pool_transactions.iter().map(|tx| tx.hash()).collect();
+        local_transactions.retain(|hash| pool_hashes.contains(hash));
+
+        let (local, remote): (Vec<_>, Vec<_>) = pool_transactions
+            .into_iter()
+            .partition(|tx| local_transactions.contains(&tx.hash()));
+        let remote = if self.config.journal_all {
+            remote
+        } else {
+            vec![]
+        };
+
+        let mut buf = vec![];
+        for (tx, is_local) in local
+            .iter()
+            .map(|tx| (tx, true))
+            .chain(remote.iter().map(|tx| (tx, false)))
+        {
+            buf.extend_from_slice(&encode_record(&tx.transaction, is_local));
+        }
+        let count = local.len() + remote.len();
+
+        let tmp_path = self.config.path.with_extension("new");
+        {
+            let mut file = File::create(&tmp_path)?;
+            file.write_all(&buf)?;
+            file.sync_all()?;
+        }
+        fs::rename(&tmp_path, &self.config.path)?;
+        *writer = Some(Self::open_writer(&self.config.path)?);
+
+        info!(
+            "Transaction journal rotated, transactions={} size={}",
+            count,
+            buf.len()
+        );
+        Ok(count)
+    }
+
+    fn open_writer(path: &Path) -> io::Result<JournalWriter> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let size = file.metadata()?.len();
+        Ok(JournalWriter {
+            file,
+            size,
+            live_size: size,
+        })
+    }
+}
+
+fn encode_record(tx: &TransactionWithSignature, local: bool) -> Vec<u8> {
+    let mut stream = RlpStream::new_list(2);
+    stream.append(&local);
+    stream.append(tx);
+    stream.out().to_vec()
+}
+
+/// Decodes the first record in `buf` and returns its length.
+fn decode_record(
+    buf: &[u8],
+) -> Result<(TransactionWithSignature, bool, usize), rlp::DecoderError> {
+    let rlp = Rlp::new(buf);
+    let len = rlp.payload_info()?.total();
+    if len > buf.len() {
+        return Err(rlp::DecoderError::RlpIsTooShort);
+    }
+    let record = Rlp::new(&buf[..len]);
+    Ok((record.val_at(1)?, record.val_at(0)?, len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keylib::{Generator, Random};
+    use cfx_types::{Address, U256};
+    use primitives::{
+        transaction::native_transaction::NativeTransaction, Action, Transaction,
+    };
+
+    fn new_test_tx(nonce: u64) -> Arc<SignedTransaction> {
+        let sender = Random.generate().unwrap();
+        Arc::new(
+            Transaction::from(NativeTransaction {
+                nonce: U256::from(nonce),
+                gas_price: U256::from(1),
+                gas: U256::from(21000),
+                action: Action::Call(Address::random()),
+                value: U256::zero(),
+                storage_limit: 0,
+                epoch_height: 0,
+                chain_id: 1,
+                data: Vec::new(),
+            })
+            .sign(sender.secret()),
+        )
+    }
+
+    fn journal(
+        dir: &tempfile::TempDir, journal_all: bool,
+    ) -> TransactionJournal {
+        journal_with_max_size(dir, journal_all, 1 << 20)
+    }
+
+    fn journal_with_max_size(
+        dir: &tempfile::TempDir, journal_all: bool, max_size: u64,
+    ) -> TransactionJournal {
+        TransactionJournal::new(TxJournalConfig {
+            path: dir.path().join("transactions.rlp"),
+            journal_all,
+            max_size,
+        })
+    }
+
+    #[test]
+    fn test_journal_insert_load_rotate() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = journal(&dir, false);
+        let (local, remote) = (new_test_tx(0), new_test_tx(1));
+
+        assert!(!journal.insert(&[local.clone()], true));
+        // The remote transactions are only journaled with `journal_all`.
+        assert!(!journal.insert(&[remote.clone()], false));
+        let loaded = journal.load().unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].0.hash(), local.hash());
+        assert!(loaded[0].1);
+
+        // The transactions which have left the pool are dropped.
+        assert_eq!(journal.rotate(vec![remote.clone()]).unwrap(), 0);
+        assert!(journal.load().unwrap().is_empty());
+        assert!(!journal.is_local(&local.hash()));
+    }
+
+    #[test]
+    fn test_journal_truncated_tail() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = journal(&dir, true);
+        journal.insert(&[new_test_tx(0), new_test_tx(1)], false);
+
+        let path = dir.path().join("transactions.rlp");
+        let len = fs::metadata(&path).unwrap().len();
+        OpenOptions::new()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_len(len - 1)
+            .unwrap();
+        assert_eq!(journal.load().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_journal_rotation_threshold() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = journal_with_max_size(&dir, false, 1);
+        let txs: Vec<_> = (0..5).map(new_test_tx).collect();
+
+        assert!(journal.insert(&txs[..2], true));
+        assert_eq!(journal.rotate(txs[..2].to_vec()).unwrap(), 2);
+        // Not rotated again until the journal doubles.
+        assert!(!journal.insert(&txs[2..3], true));
+        assert!(journal.insert(&txs[3..], true));
+
+        // The transactions in the pool are kept beyond the size limit.
+        assert_eq!(journal.rotate(txs.clone()).unwrap(), 5);
+        assert_eq!(journal.load().unwrap().len(), 5);
+    }
+}
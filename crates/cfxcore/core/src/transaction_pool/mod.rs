@@ -5,17 +5,24 @@
 mod deferred_pool;
 mod error;
 mod garbage_collector;
+mod journal;
 mod nonce_pool;
 mod pool_metrics;
 mod state_provider;
 mod transaction_pool_inner;
 
+pub use crate::txpool_event::{TxDropReason, TxPoolEvent};
 pub use error::TransactionPoolError;
+pub use journal::TxJournalConfig;
 
 use crate::{
     block_data_manager::BlockDataManager,
+    channel::Channel,
     consensus::BestInformation,
-    transaction_pool::{nonce_pool::TxWithReadyInfo, pool_metrics::*},
+    transaction_pool::{
+        journal::TransactionJournal, nonce_pool::TxWithReadyInfo,
+        pool_metrics::*,
+    },
     verification::{VerificationConfig, VerifyTxLocalMode, VerifyTxMode},
 };
 use cfx_executor::{
@@ -33,7 +40,8 @@ use cfx_rpc_cfx_types::{PendingReason, TransactionStatus};
 use cfx_statedb::{Result as StateDbResult, StateDb};
 use cfx_storage::{StateIndex, StorageManagerTrait};
 use cfx_types::{
-    AddressWithSpace as Address, AllChainID, Space, SpaceMap, H256, U256,
+    AddressSpaceUtil, AddressWithSpace as Address, AllChainID, Space, SpaceMap,
+    H160, H256, U256,
 };
 use cfx_vm_types::Spec;
 
@@ -48,11 +56,11 @@ use primitives::{
 use state_provider::StateProvider;
 use std::{
     cmp::{max, min},
-    collections::{hash_map::HashMap, BTreeMap, BTreeSet},
+    collections::{hash_map::HashMap, BTreeMap, BTreeSet, HashSet},
     mem,
     ops::DerefMut,
     sync::{
-        atomic::{AtomicBool, Ordering},
+        atomic::{AtomicBool, AtomicU64, Ordering},
         Arc,
     },
 };
@@ -68,6 +76,10 @@ pub struct TxPoolConfig {
     pub max_packing_batch_gas_limit: u64,
     pub max_packing_batch_size: usize,
     pub packing_pool_degree: u8,
+    pub journal: Option<TxJournalConfig>,
+    /// Whether the ready transactions of the local accounts are packed
+    /// before the others.
+    pub local_packing_priority: bool,
 }
 
 impl MallocSizeOf for TxPoolConfig {
@@ -88,6 +100,8 @@ impl Default for TxPoolConfig {
             max_packing_batch_gas_limit: DEFAULT_TARGET_BLOCK_GAS_LIMIT / 10,
             packing_pool_degree: 4,
             target_block_gas_limit: DEFAULT_TARGET_BLOCK_GAS_LIMIT,
+            journal: None,
+            local_packing_priority: false,
         }
     }
 }
@@ -179,10 +193,15 @@ pub struct TransactionPool {
     set_tx_requests: Mutex<Vec<Arc<SignedTransaction>>>,
     recycle_tx_requests: Mutex<Vec<Arc<SignedTransaction>>>,
     machine: Arc<Machine>,
+    journal: Option<TransactionJournal>,
 
     /// If it's `false`, operations on the tx pool will be ignored to save
     /// memory/CPU cost.
     ready_for_mining: AtomicBool,
+
+    /// Increased whenever the transactions in the pool or their readiness
+    /// may change.
+    version: AtomicU64,
 }
 
 impl MallocSizeOf for TransactionPool {
@@ -214,12 +233,13 @@ impl TransactionPool {
         data_man: Arc<BlockDataManager>, machine: Arc<Machine>,
     ) -> Self {
         let genesis_hash = data_man.true_genesis.hash();
-        let inner = TransactionPoolInner::new(
+        let mut inner = TransactionPoolInner::new(
             config.capacity,
             config.max_packing_batch_gas_limit as usize,
             config.max_packing_batch_size,
             config.packing_pool_degree,
         );
+        inner.set_local_packing_priority(config.local_packing_priority);
         let best_executed_state = Mutex::new(
             Self::get_best_executed_state_by_epoch(
                 &data_man,
@@ -230,6 +250,7 @@ impl TransactionPool {
             )
             .expect("The genesis state is guaranteed to exist."),
         );
+        let journal = config.journal.clone().map(TransactionJournal::new);
         TransactionPool {
             config,
             verification_config,
@@ -241,7 +262,9 @@ impl TransactionPool {
             set_tx_requests: Mutex::new(Default::default()),
             recycle_tx_requests: Mutex::new(Default::default()),
             machine,
+            journal,
             ready_for_mining: AtomicBool::new(false),
+            version: AtomicU64::new(0),
         }
     }
 
@@ -387,7 +410,28 @@ impl TransactionPool {
     /// cannot be inserted to the tx pool, it will be included in the returned
     /// `failure` and will not be propagated.
     pub fn insert_new_transactions(
-        &self, mut transactions: Vec<TransactionWithSignature>,
+        &self, transactions: Vec<TransactionWithSignature>,
+    ) -> (
+        Vec<Arc<SignedTransaction>>,
+        HashMap<H256, TransactionPoolError>,
+    ) {
+        self.insert_new_transactions_with_origin(transactions, false)
+    }
+
+    /// Same as `insert_new_transactions`, but for the transactions submitted
+    /// to this node directly, e.g. through RPC. These transactions are
+    /// always journaled if the journal is enabled.
+    pub fn insert_local_transactions(
+        &self, transactions: Vec<TransactionWithSignature>,
+    ) -> (
+        Vec<Arc<SignedTransaction>>,
+        HashMap<H256, TransactionPoolError>,
+    ) {
+        self.insert_new_transactions_with_origin(transactions, true)
+    }
+
+    fn insert_new_transactions_with_origin(
+        &self, mut transactions: Vec<TransactionWithSignature>, local: bool,
     ) -> (
         Vec<Arc<SignedTransaction>>,
         HashMap<H256, TransactionPoolError>,
@@ -414,6 +458,8 @@ impl TransactionPool {
         let transitions = &self.machine.params().transition_heights;
 
         // filter out invalid transactions.
+        let has_local_accounts = self.inner.read().has_local_accounts();
+        let mut underpriced = HashMap::new();
         let mut index = 0;
         while let Some(tx) = transactions.get(index) {
             match self.verify_transaction_tx_pool(
@@ -425,6 +471,14 @@ impl TransactionPool {
                 &vm_spec,
             ) {
                 Ok(_) => index += 1,
+                // The local accounts are exempt from the minimum gas price,
+                // which can only be checked once the sender is recovered.
+                Err(
+                    e @ TransactionPoolError::GasPriceLessThanMinimum { .. },
+                ) if has_local_accounts => {
+                    underpriced.insert(tx.hash, e);
+                    index += 1;
+                }
                 Err(e) => {
                     let removed = transactions.swap_remove(index);
                     debug!("failed to insert tx into pool (validation failed), hash = {:?}, error = {:?}", removed.hash, e);
@@ -448,12 +502,20 @@ impl TransactionPool {
                 let state = self.get_best_state_provider();
                 let mut inner =
                     self.inner.write_with_metric(&INSERT_TXS_ENQUEUE_LOCK);
+                self.bump_version();
                 let mut to_prop = self.to_propagate_trans.write();
 
                 for tx in signed_trans {
                     if inner.get(&tx.hash).is_some() {
                         continue;
                     }
+                    if let Some(e) = underpriced.remove(&tx.hash) {
+                        if !inner.is_local_account(&tx.sender()) {
+                            debug!("failed to insert tx into pool (validation failed), hash = {:?}, error = {:?}", tx.hash, e);
+                            failure.insert(tx.hash(), e);
+                            continue;
+                        }
+                    }
 
                     if let Err(e) = self.add_transaction_with_readiness_check(
                         &mut *inner,
@@ -496,6 +558,8 @@ impl TransactionPool {
         INSERT_TXS_SUCCESS_TPS.mark(passed_transactions.len());
         INSERT_TXS_FAILURE_TPS.mark(failure.len());
 
+        self.journal_transactions(&passed_transactions, local);
+
         (passed_transactions, failure)
     }
 
@@ -506,6 +570,18 @@ impl TransactionPool {
     /// cannot be inserted to the tx pool, it will be included in the returned
     /// `failure` and will not be propagated.
     pub fn insert_new_signed_transactions(
+        &self, signed_transactions: Vec<Arc<SignedTransaction>>,
+    ) -> (
+        Vec<Arc<SignedTransaction>>,
+        HashMap<H256, TransactionPoolError>,
+    ) {
+        let (passed_transactions, failure) =
+            self.add_new_signed_transactions(signed_transactions);
+        self.journal_transactions(&passed_transactions, false);
+        (passed_transactions, failure)
+    }
+
+    fn add_new_signed_transactions(
         &self, mut signed_transactions: Vec<Arc<SignedTransaction>>,
     ) -> (
         Vec<Arc<SignedTransaction>>,
@@ -544,6 +620,9 @@ impl TransactionPool {
                 &vm_spec,
             ) {
                 Ok(_) => index += 1,
+                Err(TransactionPoolError::GasPriceLessThanMinimum {
+                    ..
+                }) if self.is_local_account(&tx.sender()) => index += 1,
                 Err(e) => {
                     let removed = signed_transactions.swap_remove(index);
                     debug!("failed to insert tx into pool (validation failed), hash = {:?}, error = {:?}", removed.hash, e);
@@ -578,6 +657,7 @@ impl TransactionPool {
             let state = self.get_best_state_provider();
             let mut inner =
                 self.inner.write_with_metric(&INSERT_TXS_ENQUEUE_LOCK);
+            self.bump_version();
             let mut to_prop = self.to_propagate_trans.write();
 
             for tx in signed_transactions {
@@ -613,6 +693,99 @@ impl TransactionPool {
         (passed_transactions, failure)
     }
 
+    fn journal_transactions(
+        &self, transactions: &[Arc<SignedTransaction>], local: bool,
+    ) {
+        if let Some(journal) = &self.journal {
+            if journal.insert(transactions, local) {
+                if let Err(e) = self.rotate_journal() {
+                    warn!("{}", e);
+                }
+            }
+        }
+    }
+
+    /// Rewrites the journal with the transactions currently in the pool, and
+    /// returns the number of journaled transactions.
+    pub fn rotate_journal(&self) -> Result<usize, String> {
+        let journal = self
+            .journal
+            .as_ref()
+            .ok_or("transaction journal is disabled")?;
+        let (_, all_transactions) = self.content(None);
+        journal
+            .rotate(all_transactions)
+            .map_err(|e| format!("failed to rotate transaction journal: {}", e))
+    }
+
+    /// Replays the journaled transactions into the pool, which should be done
+    /// once the best state is known. The transactions whose nonces are below
+    /// the nonces of their senders in the best state are pruned, and the
+    /// journal is rotated afterwards to drop them from the disk.
+    pub fn restore_journal(&self) {
+        let journal = match &self.journal {
+            Some(journal) => journal,
+            None => return,
+        };
+        let journaled = match journal.load() {
+            Ok(journaled) => journaled,
+            Err(e) => {
+                warn!("failed to load transaction journal: {}", e);
+                return;
+            }
+        };
+
+        if !journaled.is_empty() {
+            let total = journaled.len();
+            let local_hashes: HashSet<H256> = journaled
+                .iter()
+                .filter(|(_, local)| *local)
+                .map(|(tx, _)| tx.hash())
+                .collect();
+            let transactions =
+                journaled.into_iter().map(|(tx, _)| tx).collect();
+            let signed_transactions = match self
+                .data_man
+                .recover_unsigned_tx(&transactions)
+            {
+                Ok(signed_transactions) => signed_transactions,
+                Err(e) => {
+                    warn!("failed to recover journaled transactions: {:?}", e);
+                    vec![]
+                }
+            };
+
+            let state = self.get_best_state_provider();
+            let signed_transactions: Vec<_> = signed_transactions
+                .into_iter()
+                .filter(|tx| match state.get_nonce(&tx.sender()) {
+                    Ok(nonce) => *tx.nonce() >= nonce,
+                    Err(_) => true,
+                })
+                .collect();
+            let stale = total - signed_transactions.len();
+
+            let (passed, failure) =
+                self.add_new_signed_transactions(signed_transactions);
+            journal.mark_local(
+                passed
+                    .iter()
+                    .map(|tx| tx.hash())
+                    .filter(|hash| local_hashes.contains(hash)),
+            );
+            info!(
+                "Transaction journal restored, restored={} stale={} failed={}",
+                passed.len(),
+                stale,
+                failure.len()
+            );
+        }
+
+        if let Err(e) = self.rotate_journal() {
+            warn!("{}", e);
+        }
+    }
+
     /// verify transactions based on the rules that have nothing to do with
     /// readiness
     fn verify_transaction_tx_pool(
@@ -842,11 +1015,13 @@ impl TransactionPool {
         &self, accounts_from_execution: Vec<Account>,
     ) {
         let mut inner = self.inner.write_with_metric(&NOTIFY_MODIFIED_LOCK);
+        self.bump_version();
         inner.notify_modified_accounts(accounts_from_execution)
     }
 
     pub fn clear_tx_pool(&self) {
         let mut inner = self.inner.write();
+        self.bump_version();
         inner.clear()
     }
 
@@ -870,6 +1045,36 @@ impl TransactionPool {
         inner.total_unpacked(None)
     }
 
+    /// Publishes the transaction events of the pool to `events`.
+    pub fn set_event_channel(&self, events: Arc<Channel<TxPoolEvent>>) {
+        self.inner.write().set_event_channel(events);
+    }
+
+    /// Registers the accounts whose transactions are exempt from the minimum
+    /// gas price and from the garbage collection when the pool is full, in
+    /// both spaces.
+    pub fn add_local_accounts(&self, accounts: Vec<H160>) {
+        if !accounts.is_empty() {
+            info!("Add {} local accounts to transaction pool", accounts.len());
+        }
+        self.inner
+            .write()
+            .add_local_accounts(accounts.into_iter().flat_map(|address| {
+                [address.with_native_space(), address.with_evm_space()]
+            }));
+    }
+
+    pub fn is_local_account(&self, address: &Address) -> bool {
+        self.inner.read().is_local_account(address)
+    }
+
+    /// The pending and queued transactions from the local accounts.
+    pub fn total_local_pending_and_queued(
+        &self, space: Option<Space>,
+    ) -> (u64, u64) {
+        self.inner.read().total_local_pending_and_queued(space)
+    }
+
     // The total pending transactions in the pool
     // Pending transactions are transactions that are ready to be packed
     pub fn total_pending(&self, space: Option<Space>) -> u64 {
@@ -940,6 +1145,7 @@ impl TransactionPool {
 
         let state = self.get_best_state_provider();
         let mut inner = self.inner.write_with_metric(&NOTIFY_BEST_INFO_LOCK);
+        self.bump_version();
         let inner = inner.deref_mut();
 
         while let Some(tx) = set_tx_buffer.pop() {
@@ -1207,4 +1413,12 @@ impl TransactionPool {
     pub fn set_ready_for_mining(&self) {
         self.ready_for_mining.store(true, Ordering::SeqCst);
     }
+
+    /// Returns the version of the pool content. The transactions packed from
+    /// the pool may differ only if the version has changed.
+    pub fn version(&self) -> u64 { self.version.load(Ordering::SeqCst) }
+
+    // Called with the write lock of `inner` held, so the version is increased
+    // before the pool is changed.
+    fn bump_version(&self) { self.version.fetch_add(1, Ordering::SeqCst); }
 }
@@ -7,7 +7,11 @@ use super::{
     TransactionPoolError,
 };
 
-use crate::verification::{PackingCheckResult, VerificationConfig};
+use crate::{
+    channel::Channel,
+    txpool_event::{TxDropReason, TxPoolEvent},
+    verification::{PackingCheckResult, VerificationConfig},
+};
 use cfx_executor::machine::Machine;
 use cfx_packing_pool::PackingPoolConfig;
 use cfx_parameters::{
@@ -30,7 +34,7 @@ use primitives::{
 };
 use rlp::*;
 use std::{
-    collections::{BTreeMap, BTreeSet, HashMap},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     sync::Arc,
     time::{SystemTime, UNIX_EPOCH},
 };
@@ -102,6 +106,15 @@ pub struct TransactionPoolInner {
     /// Keeps all transactions in the transaction pool.
     /// It should contain the same transaction set as `deferred_pool`.
     txs: TransactionSet,
+    /// The senders whose unexecuted transactions are not garbage collected
+    /// when the pool is full, e.g. the accounts of this node.
+    local_accounts: HashSet<AddressWithSpace>,
+    /// Whether the ready transactions of `local_accounts` are packed before
+    /// the sampled ones.
+    local_packing_priority: bool,
+    /// The channel to publish the transaction events to, if any.
+    #[ignore_malloc_size_of = "channels are not handled in MallocSizeOf"]
+    events: Option<Arc<Channel<TxPoolEvent>>>,
 }
 
 impl TransactionPoolInner {
@@ -122,6 +135,9 @@ impl TransactionPoolInner {
             ready_nonces_and_balances: HashMap::new(),
             garbage_collector: SpaceMap::default(),
             txs: TransactionSet::default(),
+            local_accounts: HashSet::new(),
+            local_packing_priority: false,
+            events: None,
         }
     }
 
@@ -129,6 +145,14 @@ impl TransactionPoolInner {
     pub fn new_for_test() -> Self { Self::new(50_000, 3_000_000, 50, 4) }
 
     pub fn clear(&mut self) {
+        if self.has_event_subscribers() {
+            for tx in self.txs.values() {
+                self.notify_event(TxPoolEvent::Dropped {
+                    transaction: tx.clone(),
+                    reason: TxDropReason::Cleared,
+                });
+            }
+        }
         self.deferred_pool.clear();
         self.ready_nonces_and_balances.clear();
         self.garbage_collector.apply_all(|x| x.clear());
@@ -137,6 +161,38 @@ impl TransactionPoolInner {
         self.unpacked_transaction_count.apply_all(|x| *x = 0);
     }
 
+    pub fn add_local_accounts(
+        &mut self, accounts: impl IntoIterator<Item = AddressWithSpace>,
+    ) {
+        self.local_accounts.extend(accounts);
+    }
+
+    pub fn has_local_accounts(&self) -> bool { !self.local_accounts.is_empty() }
+
+    pub fn is_local_account(&self, address: &AddressWithSpace) -> bool {
+        self.local_accounts.contains(address)
+    }
+
+    pub fn set_local_packing_priority(&mut self, enabled: bool) {
+        self.local_packing_priority = enabled;
+    }
+
+    pub fn set_event_channel(&mut self, events: Arc<Channel<TxPoolEvent>>) {
+        self.events = Some(events);
+    }
+
+    fn has_event_subscribers(&self) -> bool {
+        self.events
+            .as_ref()
+            .map_or(false, |events| events.num_subscriptions() != 0)
+    }
+
+    fn notify_event(&self, event: TxPoolEvent) {
+        if let Some(events) = &self.events {
+            events.send(event);
+        }
+    }
+
     pub fn total_deferred(&self, space: Option<Space>) -> usize {
         match space {
             Some(space) => *self.txs.count.in_space(space),
@@ -185,6 +241,41 @@ impl TransactionPoolInner {
         self.total_unpacked(space) as u64 - self.total_pending(space)
     }
 
+    /// The number of the pending and queued transactions from
+    /// `local_accounts`.
+    pub fn total_local_pending_and_queued(
+        &self, space: Option<Space>,
+    ) -> (u64, u64) {
+        let mut pending = 0;
+        let mut queued = 0;
+        for addr in &self.local_accounts {
+            if space.map_or(false, |space| addr.space != space) {
+                continue;
+            }
+            let bucket = match self.deferred_pool.get_bucket(addr) {
+                Some(bucket) => bucket,
+                None => continue,
+            };
+            let (nonce, balance) = self
+                .ready_nonces_and_balances
+                .get(addr)
+                .cloned()
+                .unwrap_or_default();
+            let addr_pending = match bucket
+                .recalculate_readiness_with_local_info(nonce, balance)
+            {
+                Some((tx, next_nonce)) => {
+                    (next_nonce - tx.nonce() + 1).as_u64()
+                }
+                None => 0,
+            };
+            pending += addr_pending;
+            queued +=
+                (bucket.count_from(&nonce) as u64).saturating_sub(addr_pending);
+        }
+        (pending, queued)
+    }
+
     pub fn get(&self, tx_hash: &H256) -> Option<Arc<SignedTransaction>> {
         self.txs.get(tx_hash).map(|x| x.clone())
     }
@@ -219,7 +310,7 @@ impl TransactionPoolInner {
     pub fn collect_garbage(&mut self, new_tx: &SignedTransaction) {
         let space = new_tx.space();
         let count_before_gc = self.total_deferred(Some(space));
-        let mut skipped_self_node = None;
+        let mut skipped_nodes = Vec::new();
         while self.is_full(space)
             && !self.garbage_collector.in_space(space).is_empty()
         {
@@ -237,15 +328,18 @@ impl TransactionPoolInner {
             // no unconditional garbage collection to conduct and we need to
             // check if we should replace one unexecuted tx.
             if victim.count == 0 {
-                if *victim_address == new_tx.sender() {
+                if *victim_address == new_tx.sender()
+                    || self.local_accounts.contains(victim_address)
+                {
                     // We do not GC a not-executed transaction from the same
-                    // sender, so save it and try another account.
+                    // sender or from a local account, so save it and try
+                    // another account.
                     let (victim_address, victim) = self
                         .garbage_collector
                         .in_space_mut(space)
                         .pop()
                         .unwrap();
-                    skipped_self_node = Some((victim_address, victim));
+                    skipped_nodes.push((victim_address, victim));
                     continue;
                 } else if victim.has_ready_tx
                     && victim.first_tx_gas_price >= *new_tx.gas_price()
@@ -285,11 +379,14 @@ impl TransactionPoolInner {
 
             // We have to garbage collect an unexecuted transaction.
             // TODO: Implement more heuristic strategies
-            if *to_remove_tx.nonce() >= ready_nonce {
+            let drop_reason = if *to_remove_tx.nonce() >= ready_nonce {
                 assert_eq!(victim.count, 0);
                 GC_UNEXECUTED_COUNTER.inc(1);
                 warn!("an unexecuted tx is garbage-collected.");
-            }
+                TxDropReason::Evicted
+            } else {
+                TxDropReason::Executed
+            };
 
             if !tx_with_ready_info.is_already_packed() {
                 let tx_space = tx_with_ready_info.space();
@@ -332,11 +429,15 @@ impl TransactionPoolInner {
 
             // maintain txs
             self.txs.remove(&to_remove_tx.hash());
+            self.notify_event(TxPoolEvent::Dropped {
+                transaction: to_remove_tx,
+                reason: drop_reason,
+            });
         }
 
         // Insert back skipped nodes to keep `garbage_collector`
         // unchanged.
-        if let Some((addr, node)) = skipped_self_node {
+        for (addr, node) in skipped_nodes {
             self.garbage_collector.in_space_mut(space).insert(
                 &addr,
                 node.count,
@@ -421,6 +522,13 @@ impl TransactionPoolInner {
                     *self.unpacked_transaction_count.in_space_mut(tx_space) +=
                         1;
                 }
+                self.notify_event(
+                    if packed {
+                        TxPoolEvent::Packed(transaction.clone())
+                    } else {
+                        TxPoolEvent::Added(transaction.clone())
+                    },
+                );
             }
             InsertResult::Failed(_) => {}
             InsertResult::Updated(replaced_tx) => {
@@ -442,6 +550,16 @@ impl TransactionPoolInner {
                     *self.unpacked_transaction_count.in_space_mut(tx_space) +=
                         1;
                 }
+                // The same transaction is inserted again when it is packed.
+                if replaced_tx.hash() != transaction.hash() {
+                    self.notify_event(TxPoolEvent::Replaced {
+                        old: replaced_tx.get_arc_tx().clone(),
+                        new: transaction.clone(),
+                    });
+                }
+                if packed {
+                    self.notify_event(TxPoolEvent::Packed(transaction.clone()));
+                }
             }
         }
 
@@ -613,9 +731,29 @@ impl TransactionPoolInner {
         &mut self, addr: &AddressWithSpace, nonce: U256, balance: U256,
     ) {
         let space = addr.space;
+        let ready_before: Option<HashSet<H256>> =
+            self.has_event_subscribers().then(|| {
+                self.deferred_pool
+                    .ready_transactions_by_address(*addr)
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|tx| tx.hash())
+                    .collect()
+            });
         let ret = self
             .deferred_pool
             .recalculate_readiness_with_local_info(addr, nonce, balance);
+        if let Some(ready_before) = ready_before {
+            for tx in self
+                .deferred_pool
+                .ready_transactions_by_address(*addr)
+                .unwrap_or_default()
+            {
+                if !ready_before.contains(&tx.hash()) {
+                    self.notify_event(TxPoolEvent::Ready(tx.clone()));
+                }
+            }
+        }
         match &ret {
             Some(tx) => trace!(
                 "txpool::recalculate_readiness addr={:?} state_nonce={:?} ready_nonce={:?} ready_hash={:?} balance={:?}",
@@ -709,6 +847,7 @@ impl TransactionPoolInner {
                 block_size_limit,
                 num_txs,
                 U256::zero(),
+                &HashSet::new(),
                 validity,
             );
         debug!(
@@ -725,6 +864,7 @@ impl TransactionPoolInner {
             block_size_limit - used_size,
             num_txs - sampled_tx.len(),
             U256::zero(),
+            &HashSet::new(),
             validity,
         );
         debug!(
@@ -767,6 +907,13 @@ impl TransactionPoolInner {
 
         let mut block_base_price = parent_base_price.clone();
 
+        let no_priority = HashSet::new();
+        let priority_senders = if self.local_packing_priority {
+            &self.local_accounts
+        } else {
+            &no_priority
+        };
+
         let can_pack_evm =
             machine.params().can_pack_evm_transaction(best_epoch_height);
 
@@ -794,6 +941,7 @@ impl TransactionPoolInner {
                     block_size_limit,
                     num_txs,
                     tx_min_price,
+                    priority_senders,
                     &validity,
                 );
 
@@ -860,6 +1008,7 @@ impl TransactionPoolInner {
                 block_size_limit - evm_used_size,
                 num_txs - evm_packed_tx_num,
                 tx_min_price,
+                priority_senders,
                 &validity,
             );
 
@@ -1160,7 +1309,11 @@ impl TransactionPoolInner {
 
 #[cfg(test)]
 mod tests {
-    use crate::verification::PackingCheckResult;
+    use crate::{
+        channel::Channel,
+        txpool_event::{TxDropReason, TxPoolEvent},
+        verification::PackingCheckResult,
+    };
 
     use super::TransactionPoolInner;
     use crate::keylib::{Generator, KeyPair, Random};
@@ -1171,7 +1324,7 @@ mod tests {
     use cfx_parameters::block::{
         cspace_block_gas_limit_after_cip1559, espace_block_gas_limit,
     };
-    use cfx_types::{Address, Space, SpaceMap, U256};
+    use cfx_types::{Address, AddressSpaceUtil, Space, SpaceMap, U256};
     use itertools::Itertools;
     use primitives::{
         block_header::compute_next_price_tuple,
@@ -1332,4 +1485,67 @@ mod tests {
             pool.clear();
         }
     }
+
+    #[test]
+    fn test_local_accounts_not_garbage_collected() {
+        let mut pool = TransactionPoolInner::new(2, 3_000_000, 50, 4);
+        let (local, remote, new) = (
+            Random.generate().unwrap(),
+            Random.generate().unwrap(),
+            Random.generate().unwrap(),
+        );
+        pool.add_local_accounts([local.address().with_native_space()]);
+
+        // The local transaction has the lowest gas price, so it would be the
+        // victim without the exemption.
+        let local_tx = new_test_tx(&local, 0, 10, 21000, 0, Space::Native);
+        let remote_tx = new_test_tx(&remote, 0, 20, 21000, 0, Space::Native);
+        pool.insert_transaction_for_test(local_tx.clone(), U256::zero());
+        pool.insert_transaction_for_test(remote_tx.clone(), U256::zero());
+
+        let new_tx = new_test_tx(&new, 0, 100, 21000, 0, Space::Native);
+        pool.insert_transaction_for_test(new_tx.clone(), U256::zero());
+        assert!(pool.get(&local_tx.hash()).is_some());
+        assert!(pool.get(&remote_tx.hash()).is_none());
+        assert!(pool.get(&new_tx.hash()).is_some());
+    }
+
+    #[test]
+    fn test_txpool_events() {
+        let mut pool = TransactionPoolInner::new(1, 3_000_000, 50, 4);
+        let events = Arc::new(Channel::new("test-txpool-events"));
+        pool.set_event_channel(events.clone());
+        let mut receiver = events.subscribe();
+        let (alice, bob) =
+            (Random.generate().unwrap(), Random.generate().unwrap());
+
+        let tx = new_test_tx(&alice, 0, 10, 21000, 0, Space::Native);
+        pool.insert_transaction_for_test(tx.clone(), U256::zero());
+        assert!(matches!(receiver.try_recv(),
+            Ok(TxPoolEvent::Added(t)) if t.hash() == tx.hash()));
+        assert!(matches!(receiver.try_recv(),
+            Ok(TxPoolEvent::Ready(t)) if t.hash() == tx.hash()));
+
+        let replacement = new_test_tx(&alice, 0, 20, 21000, 0, Space::Native);
+        pool.insert_transaction_for_test(replacement.clone(), U256::zero());
+        assert!(matches!(receiver.try_recv(),
+            Ok(TxPoolEvent::Replaced { old, new })
+                if old.hash() == tx.hash() && new.hash() == replacement.hash()));
+        assert!(matches!(receiver.try_recv(),
+            Ok(TxPoolEvent::Ready(t)) if t.hash() == replacement.hash()));
+
+        // The pool is full, so the transaction of alice is evicted.
+        let other = new_test_tx(&bob, 0, 100, 21000, 0, Space::Native);
+        pool.insert_transaction_for_test(other.clone(), U256::zero());
+        assert!(matches!(receiver.try_recv(),
+            Ok(TxPoolEvent::Dropped {
+                transaction,
+                reason: TxDropReason::Evicted,
+            }) if transaction.hash() == replacement.hash()));
+        assert!(matches!(receiver.try_recv(),
+            Ok(TxPoolEvent::Added(t)) if t.hash() == other.hash()));
+        assert!(matches!(receiver.try_recv(),
+            Ok(TxPoolEvent::Ready(t)) if t.hash() == other.hash()));
+        assert!(receiver.try_recv().is_err());
+    }
 }
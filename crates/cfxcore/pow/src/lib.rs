@@ -79,6 +79,8 @@ pub struct ProofOfWorkSolution {
 pub enum MiningType {
     Stratum,
     CPU,
+    /// Only the miners fetching the work over JSON-RPC
+    Remote,
     Disable,
 }
 
@@ -89,6 +91,7 @@ impl FromStr for MiningType {
         let mining_type = match s {
             "stratum" => Self::Stratum,
             "cpu" => Self::CPU,
+            "remote" => Self::Remote,
             "disable" => Self::Disable,
             _ => return Err("invalid mining type".into()),
         };
@@ -246,6 +249,14 @@ pub fn pow_quality_to_hash(pow_quality: &U256, nonce: &U256) -> H256 {
     BigEndianHash::from_uint(&hash_u256)
 }
 
+/// Returns the seed hash of the octopus dataset used at `block_height`.
+pub fn seed_hash(block_height: u64) -> H256 {
+    H256(
+        seed_compute::SeedHashCompute::default()
+            .hash_block_height(block_height),
+    )
+}
+
 /// Convert boundary to its original difficulty. Basically just `f(x) = 2^256 /
 /// x`.
 pub fn boundary_to_difficulty(boundary: &U256) -> U256 {
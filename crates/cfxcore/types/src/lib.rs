@@ -5,6 +5,7 @@ pub mod channel;
 pub mod core_error;
 pub mod node_type;
 pub mod state_exposer;
+pub mod txpool_event;
 pub mod unique_id;
 
 pub use unique_id::UniqueId;
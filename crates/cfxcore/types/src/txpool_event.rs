@@ -0,0 +1,72 @@
+// Copyright 2026 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use primitives::SignedTransaction;
+use std::{fmt, sync::Arc};
+
+/// A change of a transaction in the transaction pool.
+#[derive(Clone, Debug)]
+pub enum TxPoolEvent {
+    /// A new transaction is inserted.
+    Added(Arc<SignedTransaction>),
+    /// A transaction is replaced by another one with the same sender and
+    /// nonce.
+    Replaced {
+        old: Arc<SignedTransaction>,
+        new: Arc<SignedTransaction>,
+    },
+    /// A transaction is removed from the pool.
+    Dropped {
+        transaction: Arc<SignedTransaction>,
+        reason: TxDropReason,
+    },
+    /// A transaction is packed in a block.
+    Packed(Arc<SignedTransaction>),
+    /// A transaction becomes ready to be packed.
+    Ready(Arc<SignedTransaction>),
+}
+
+impl TxPoolEvent {
+    /// The transaction the event is about. For a replacement, it is the
+    /// replaced transaction.
+    pub fn transaction(&self) -> &Arc<SignedTransaction> {
+        match self {
+            TxPoolEvent::Added(tx)
+            | TxPoolEvent::Packed(tx)
+            | TxPoolEvent::Ready(tx) => tx,
+            TxPoolEvent::Replaced { old, .. } => old,
+            TxPoolEvent::Dropped { transaction, .. } => transaction,
+        }
+    }
+
+    /// The transaction newly pending in the pool, if any.
+    pub fn new_pending_transaction(&self) -> Option<&Arc<SignedTransaction>> {
+        match self {
+            TxPoolEvent::Added(tx) => Some(tx),
+            TxPoolEvent::Replaced { new, .. } => Some(new),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TxDropReason {
+    /// Garbage collected to make room for new transactions.
+    Evicted,
+    /// Garbage collected after it has been executed.
+    Executed,
+    /// The transaction pool is cleared.
+    Cleared,
+}
+
+impl fmt::Display for TxDropReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            TxDropReason::Evicted => "evicted",
+            TxDropReason::Executed => "executed",
+            TxDropReason::Cleared => "cleared",
+        };
+        f.write_str(reason)
+    }
+}
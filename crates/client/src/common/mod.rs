@@ -26,7 +26,9 @@ use cfx_parameters::genesis::{
 use cfx_rpc_cfx_types::apis::ApiSet;
 use cfx_storage::StorageManager;
 use cfx_tasks::TaskManager;
-use cfx_types::{address_util::AddressUtil, Address, Space, U256};
+use cfx_types::{
+    address_util::AddressUtil, Address, AddressWithSpace, Space, U256,
+};
 pub use cfxcore::pos::pos::PosDropHandle;
 use cfxcore::{
     block_data_manager::BlockDataManager,
@@ -44,7 +46,10 @@ use cfxcore::{
 };
 use cfxcore_accounts::AccountProvider;
 use cfxkey::public_to_address;
-use diem_config::keys::ConfigKey;
+use diem_config::{
+    config::{NodeConfig, SafetyRulesService},
+    keys::ConfigKey,
+};
 use diem_crypto::{
     key_file::{load_pri_key, save_pri_key},
     PrivateKey, Uniform,
@@ -64,6 +69,7 @@ use crate::{
     accounts::{account_provider, keys_path},
     keylib::KeyPair,
     rpc_starter::{launch_async_rpc_servers, launch_cfx_async_rpc_servers},
+    state_import::import_state,
 };
 #[cfg(all(unix, feature = "jemalloc-prof"))]
 use cfx_mallocator_utils::start_pprf_server;
@@ -150,74 +156,108 @@ pub fn initialize_common_modules(
 > {
     info!("Working directory: {:?}", std::env::current_dir());
 
+    // The sender and nonce index only stores the transaction hashes, which
+    // are resolved through the transaction index.
+    let data_man_config = conf.data_mananger_config();
+    if data_man_config.persist_tx_sender_nonce_index
+        && !data_man_config.persist_tx_index
+    {
+        bail!(
+            "persist_tx_sender_nonce_index requires persist_tx_index, which \
+             is enabled by default on archive nodes"
+        );
+    }
+
+    // With the SafetyRules process the PoS keys are only kept by that
+    // process, so the node neither loads nor generates them.
+    let pos_keys_in_safety_rules = match &conf.raw_conf.pos_config_path {
+        Some(path) if Path::new(path).exists() => matches!(
+            NodeConfig::load(path)
+                .map_err(|e| format!("Failed to load pos config: {:?}", e))?
+                .consensus
+                .safety_rules
+                .service,
+            SafetyRulesService::Process(_)
+        ),
+        _ => false,
+    };
     // TODO(lpl): Keep it properly and allow not running pos.
-    let (self_pos_private_key, self_vrf_private_key) = {
-        let key_path = Path::new(&conf.raw_conf.pos_private_key_path);
-
-        let read_pos_password = |prompt: &str| -> Result<Vec<u8>, String> {
-            match rpassword::prompt_password(prompt) {
-                Ok(password) => Ok(password.into_bytes()),
-                Err(e) => {
-                    let mut msg = format!("{:?}", e);
-                    // On macOS, attempting to open `/dev/tty` without a
-                    // controlling TTY can return ENXIO
-                    // ("Device not configured"). This commonly happens when
-                    // running under a debugger/IDE that doesn't allocate a real
-                    // terminal.
-                    if e.raw_os_error() == Some(6) {
-                        msg.push_str(" Hint: maybe no controlling TTY detected (macOS ENXIO: \"Device not configured\"). If you are running under VS Code debugger or with redirected stdio, set `CFX_POS_KEY_ENCRYPTION_PASSWORD` env var to avoid interactive prompting.");
+    let pos_keys = if pos_keys_in_safety_rules {
+        None
+    } else {
+        Some({
+            let key_path = Path::new(&conf.raw_conf.pos_private_key_path);
+
+            let read_pos_password = |prompt: &str| -> Result<Vec<u8>, String> {
+                match rpassword::prompt_password(prompt) {
+                    Ok(password) => Ok(password.into_bytes()),
+                    Err(e) => {
+                        let mut msg = format!("{:?}", e);
+                        // On macOS, attempting to open `/dev/tty` without a
+                        // controlling TTY can return ENXIO
+                        // ("Device not configured"). This commonly happens when
+                        // running under a debugger/IDE that doesn't allocate a
+                        // real terminal.
+                        if e.raw_os_error() == Some(6) {
+                            msg.push_str(" Hint: maybe no controlling TTY detected (macOS ENXIO: \"Device not configured\"). If you are running under VS Code debugger or with redirected stdio, set `CFX_POS_KEY_ENCRYPTION_PASSWORD` env var to avoid interactive prompting.");
+                        }
+                        Err(msg)
                     }
-                    Err(msg)
                 }
-            }
-        };
+            };
 
-        let default_passwd = if conf.is_test_or_dev_mode() {
-            Some(vec![])
-        } else {
-            conf.raw_conf
-                .dev_pos_private_key_encryption_password
-                .clone()
-                // If the password is not set in the config file, read it from
-                // the environment variable.
-                .or(std::env::var("CFX_POS_KEY_ENCRYPTION_PASSWORD").ok())
-                .map(|s| s.into_bytes())
-        };
-        if key_path.exists() {
-            let passwd = match default_passwd {
-                Some(p) => p,
-                None => read_pos_password(
-                    "PoS key detected, please input your encryption password.\nPassword:",
-                )?,
+            let default_passwd = if conf.is_test_or_dev_mode() {
+                Some(vec![])
+            } else {
+                conf.raw_conf
+                    .dev_pos_private_key_encryption_password
+                    .clone()
+                    // If the password is not set in the config file, read it
+                    // from the environment variable.
+                    .or(std::env::var("CFX_POS_KEY_ENCRYPTION_PASSWORD").ok())
+                    .map(|s| s.into_bytes())
             };
-            match load_pri_key(key_path, &passwd) {
-                Ok((sk, vrf_sk)) => {
-                    (ConfigKey::new(sk), ConfigKey::new(vrf_sk))
-                }
-                Err(e) => {
-                    bail!("Load pos_key failed: {}", e);
-                }
-            }
-        } else {
-            create_dir_all(key_path.parent().unwrap()).unwrap();
-            let passwd = match default_passwd {
-                Some(p) => p,
-                None => {
-                    let p = read_pos_password("PoS key is not detected and will be generated instead, please input your encryption password. This password is needed when you restart the node\nPassword:")?;
-                    let p2 = read_pos_password("Repeat Password:")?;
-                    if p != p2 {
-                        bail!("Passwords do not match!");
+            if key_path.exists() {
+                let passwd = match default_passwd {
+                    Some(p) => p,
+                    None => read_pos_password(
+                        "PoS key detected, please input your encryption password.\nPassword:",
+                    )?,
+                };
+                match load_pri_key(key_path, &passwd) {
+                    Ok((sk, vrf_sk)) => {
+                        (ConfigKey::new(sk), ConfigKey::new(vrf_sk))
+                    }
+                    Err(e) => {
+                        bail!("Load pos_key failed: {}", e);
                     }
-                    p
                 }
-            };
-            let mut rng = StdRng::from_rng(OsRng).unwrap();
-            let private_key = ConsensusPrivateKey::generate(&mut rng);
-            let vrf_private_key = ConsensusVRFPrivateKey::generate(&mut rng);
-            save_pri_key(key_path, &passwd, &(&private_key, &vrf_private_key))
+            } else {
+                create_dir_all(key_path.parent().unwrap()).unwrap();
+                let passwd = match default_passwd {
+                    Some(p) => p,
+                    None => {
+                        let p = read_pos_password("PoS key is not detected and will be generated instead, please input your encryption password. This password is needed when you restart the node\nPassword:")?;
+                        let p2 = read_pos_password("Repeat Password:")?;
+                        if p != p2 {
+                            bail!("Passwords do not match!");
+                        }
+                        p
+                    }
+                };
+                let mut rng = StdRng::from_rng(OsRng).unwrap();
+                let private_key = ConsensusPrivateKey::generate(&mut rng);
+                let vrf_private_key =
+                    ConsensusVRFPrivateKey::generate(&mut rng);
+                save_pri_key(
+                    key_path,
+                    &passwd,
+                    &(&private_key, &vrf_private_key),
+                )
                 .expect("error saving private key");
-            (ConfigKey::new(private_key), ConfigKey::new(vrf_private_key))
-        }
+                (ConfigKey::new(private_key), ConfigKey::new(vrf_private_key))
+            }
+        })
     };
 
     let worker_thread_pool = Arc::new(Mutex::new(ThreadPool::with_name(
@@ -259,39 +299,7 @@ pub fn initialize_common_modules(
     }
 
     let genesis_accounts = if conf.is_test_or_dev_mode() {
-        match (
-            &conf.raw_conf.genesis_secrets,
-            &conf.raw_conf.genesis_evm_secrets,
-        ) {
-            (Some(file), evm_file) => {
-                // Load core space accounts
-                let mut accounts = genesis::load_secrets_file(
-                    file,
-                    secret_store.as_ref(),
-                    Space::Native,
-                )?;
-
-                // Load EVM space accounts if specified
-                if let Some(evm_file) = evm_file {
-                    let evm_accounts = genesis::load_secrets_file(
-                        evm_file,
-                        secret_store.as_ref(),
-                        Space::Ethereum,
-                    )?;
-                    accounts.extend(evm_accounts);
-                }
-                accounts
-            }
-            (None, Some(evm_file)) => {
-                // Only load EVM space accounts
-                genesis::load_secrets_file(
-                    evm_file,
-                    secret_store.as_ref(),
-                    Space::Ethereum,
-                )?
-            }
-            (None, None) => genesis::default(conf.is_test_or_dev_mode()),
-        }
+        dev_genesis_accounts(conf, secret_store.as_ref())?
     } else {
         match conf.raw_conf.genesis_accounts {
             Some(ref file) => genesis::load_file(file, |addr_str| {
@@ -320,16 +328,34 @@ pub fn initialize_common_modules(
     let vm = VmFactory::new(1024 * 32);
     let machine = Arc::new(Machine::new_with_builtin(conf.common_params(), vm));
 
-    let genesis_block = genesis_block(
-        &storage_manager,
-        genesis_accounts.clone(),
-        GENESIS_ACCOUNT_ADDRESS,
-        U256::zero(),
-        machine.clone(),
-        conf.raw_conf.execute_genesis, /* need_to_execute */
-        conf.raw_conf.chain_id,
-        &initial_nodes,
-    );
+    let genesis_block = match &conf.raw_conf.genesis_state {
+        Some(path) => {
+            if !conf.is_test_or_dev_mode() {
+                bail!("genesis_state is only supported in dev or test mode");
+            }
+            info!("Initialize genesis state from {}", path);
+            genesis::genesis_block_with_state(
+                &storage_manager,
+                genesis_accounts.clone(),
+                GENESIS_ACCOUNT_ADDRESS,
+                U256::zero(),
+                machine.clone(),
+                |state_db| {
+                    import_state(state_db, path).map(|summary| summary.supply)
+                },
+            )?
+        }
+        None => genesis_block(
+            &storage_manager,
+            genesis_accounts.clone(),
+            GENESIS_ACCOUNT_ADDRESS,
+            U256::zero(),
+            machine.clone(),
+            conf.raw_conf.execute_genesis, /* need_to_execute */
+            conf.raw_conf.chain_id,
+            &initial_nodes,
+        ),
+    };
     storage_manager.notify_genesis_hash(genesis_block.hash());
     let mut genesis_accounts = genesis_accounts;
     let genesis_accounts = genesis_accounts
@@ -372,8 +398,7 @@ pub fn initialize_common_modules(
     let pos_verifier = Arc::new(PosVerifier::new(
         Some(network.clone()),
         PosConfiguration {
-            bls_key: self_pos_private_key,
-            vrf_key: self_vrf_private_key,
+            pos_keys,
             diem_conf_path: conf.raw_conf.pos_config_path.clone(),
             protocol_conf: conf.protocol_config(),
             pos_initial_nodes_path: conf
@@ -395,6 +420,7 @@ pub fn initialize_common_modules(
 
     let statistics = Arc::new(Statistics::new());
     let notifications = Notifications::init();
+    txpool.set_event_channel(notifications.txpool_events.clone());
     let pivot_hint = if let Some(conf) = &consensus_conf.pivot_hint_conf {
         Some(Arc::new(PivotHint::new(conf)?))
     } else {
@@ -455,6 +481,19 @@ pub fn initialize_common_modules(
         .expect("failed to initialize account provider"),
     );
 
+    let mut local_accounts = accounts
+        .accounts()
+        .map_err(|e| format!("failed to list local accounts: {:?}", e))?;
+    if let Some(addresses) = &conf.raw_conf.tx_pool_local_accounts {
+        for address in addresses.split(',').filter(|s| !s.is_empty()) {
+            local_accounts.push(parse_config_address_string(
+                address.trim(),
+                network_config.get_network_type(),
+            )?);
+        }
+    }
+    txpool.add_local_accounts(local_accounts);
+
     let tokio_runtime =
         Arc::new(TokioRuntime::new().map_err(|e| e.to_string())?);
 
@@ -475,6 +514,40 @@ pub fn initialize_common_modules(
     ))
 }
 
+/// The genesis accounts of the dev or test mode, loaded from the configured
+/// secret files into `secret_store`.
+pub(crate) fn dev_genesis_accounts(
+    conf: &Configuration, secret_store: &SecretStore,
+) -> Result<HashMap<AddressWithSpace, U256>, String> {
+    let accounts = match (
+        &conf.raw_conf.genesis_secrets,
+        &conf.raw_conf.genesis_evm_secrets,
+    ) {
+        (Some(file), evm_file) => {
+            // Load core space accounts
+            let mut accounts =
+                genesis::load_secrets_file(file, secret_store, Space::Native)?;
+
+            // Load EVM space accounts if specified
+            if let Some(evm_file) = evm_file {
+                let evm_accounts = genesis::load_secrets_file(
+                    evm_file,
+                    secret_store,
+                    Space::Ethereum,
+                )?;
+                accounts.extend(evm_accounts);
+            }
+            accounts
+        }
+        (None, Some(evm_file)) => {
+            // Only load EVM space accounts
+            genesis::load_secrets_file(evm_file, secret_store, Space::Ethereum)?
+        }
+        (None, None) => genesis::default(conf.is_test_or_dev_mode()),
+    };
+    Ok(accounts)
+}
+
 pub fn initialize_not_light_node_modules(
     conf: &mut Configuration, exit: Arc<(Mutex<bool>, Condvar)>,
     node_type: NodeType,
@@ -533,6 +606,27 @@ pub fn initialize_not_light_node_modules(
     ));
     sync.register().unwrap();
 
+    if conf.raw_conf.persist_tx_sender_nonce_index
+        && conf.raw_conf.backfill_tx_sender_nonce_index
+    {
+        let data_man = data_man.clone();
+        let consensus = consensus.clone();
+        let sync = sync.clone();
+        thread::Builder::new()
+            .name("tx_sender_nonce_index_backfill".into())
+            .spawn(move || {
+                // The epochs executed after catching up are indexed during
+                // execution, so only the epochs before them are backfilled.
+                while sync.catch_up_mode() {
+                    thread::sleep(Duration::from_secs(10));
+                }
+                data_man.backfill_tx_sender_nonce_index(
+                    consensus.best_executed_state_epoch_number(),
+                );
+            })
+            .expect("Tx sender nonce index backfill thread start fails");
+    }
+
     if let Some(print_memory_usage_period_s) =
         conf.raw_conf.print_memory_usage_period_s
     {
@@ -642,6 +736,8 @@ pub fn initialize_not_light_node_modules(
             txpool.clone(),
             notifications.clone(),
             task_executor.clone(),
+            accounts.clone(),
+            Some(blockgen.remote_work()),
             conf,
         ))?;
 
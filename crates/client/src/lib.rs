@@ -13,5 +13,7 @@ pub mod common;
 mod node_types;
 pub use cfx_config as configuration;
 pub use node_types::{archive, full, light};
+pub mod pivot_hint;
 pub mod rpc_starter;
 pub mod state_dump;
+pub mod state_import;
@@ -14,7 +14,9 @@ use cfx_rpc_builder::RpcServerHandle;
 use crate::{
     common::{initialize_common_modules, ClientComponents},
     configuration::Configuration,
-    rpc_starter::launch_cfx_light_async_rpc_servers,
+    rpc_starter::{
+        launch_cfx_light_async_rpc_servers, launch_eth_light_async_rpc_servers,
+    },
 };
 use blockgen::BlockGenerator;
 use cfx_tasks::TaskManager;
@@ -28,6 +30,7 @@ pub struct LightClientExtraComponents {
     pub consensus: Arc<ConsensusGraph>,
     pub cfx_rpc_server_handle: Option<RpcServerHandle>,
     pub debug_cfx_rpc_server_handle: Option<RpcServerHandle>,
+    pub eth_rpc_server_handle: Option<RpcServerHandle>,
     pub light: Arc<LightQueryService>,
     pub secret_store: Arc<SecretStore>,
     pub txpool: Arc<TransactionPool>,
@@ -130,6 +133,15 @@ impl LightClient {
                 true,
             ))?;
 
+        // Start the eSpace RPC servers, which serve the verifiable subset
+        // of the eth_* methods.
+        let eth_rpc_server_handle =
+            tokio_runtime.block_on(launch_eth_light_async_rpc_servers(
+                consensus.clone(),
+                light.clone(),
+                &conf,
+            ))?;
+
         network.start();
 
         Ok(Box::new(ClientComponents {
@@ -140,6 +152,7 @@ impl LightClient {
                 consensus,
                 cfx_rpc_server_handle,
                 debug_cfx_rpc_server_handle,
+                eth_rpc_server_handle,
                 light,
                 secret_store,
                 txpool,
@@ -0,0 +1,115 @@
+use crate::common::initialize_not_light_node_modules;
+use cfx_config::Configuration;
+use cfx_types::H256;
+use cfxcore::{
+    consensus::pivot_hint::{
+        generate_pivot_hint, verify_pivot_hint, PivotHintParams,
+        PivotHintVerification,
+    },
+    ConsensusGraph, NodeType,
+};
+use chrono::Utc;
+use parking_lot::{Condvar, Mutex};
+use primitives::EpochNumber;
+use std::{sync::Arc, thread, time::Duration};
+
+pub struct PivotHintGenerateConfig {
+    /// Path of the pivot hint file to write
+    pub file_path: String,
+    /// The last height to record, the latest confirmed epoch is used if not
+    /// specified. It is rounded down to the page boundary.
+    pub height: Option<u64>,
+    pub params: PivotHintParams,
+}
+
+/// Writes a pivot hint file from the local pivot chain, and returns the
+/// checksum of the file and the upper bound (exclusive) of the recorded
+/// heights.
+pub fn generate_pivot_hint_file(
+    conf: &mut Configuration, exit_cond_var: Arc<(Mutex<bool>, Condvar)>,
+    config: &PivotHintGenerateConfig,
+) -> Result<(H256, u64), String> {
+    let consensus = prepare_consensus(conf, exit_cond_var)?;
+
+    let confirmed_height = consensus.latest_confirmed_epoch_number();
+    let height = config.height.unwrap_or(confirmed_height);
+    if height > confirmed_height {
+        return Err(format!(
+            "Height {} is above the latest confirmed epoch {}",
+            height, confirmed_height
+        ));
+    }
+
+    let page_interval = config.params.page_interval;
+    if page_interval == 0 {
+        return Err("page_interval must be positive".into());
+    }
+    let range_max = (height + 1) / page_interval * page_interval;
+    if range_max == 0 {
+        return Err(format!(
+            "Height {} is below the first page boundary {}",
+            height, page_interval
+        ));
+    }
+
+    println(&format!(
+        "Generating pivot hint below height {}...",
+        range_max
+    ));
+    let checksum = generate_pivot_hint(
+        &config.file_path,
+        config.params,
+        range_max,
+        pivot_hash_at(&consensus),
+    )?;
+
+    Ok((checksum, range_max))
+}
+
+/// Verifies a pivot hint file against the local pivot chain.
+pub fn verify_pivot_hint_file(
+    conf: &mut Configuration, exit_cond_var: Arc<(Mutex<bool>, Condvar)>,
+    file_path: &str,
+) -> Result<PivotHintVerification, String> {
+    let consensus = prepare_consensus(conf, exit_cond_var)?;
+
+    println(&format!("Verifying pivot hint {}...", file_path));
+    verify_pivot_hint(file_path, pivot_hash_at(&consensus))
+}
+
+fn prepare_consensus(
+    conf: &mut Configuration, exit_cond_var: Arc<(Mutex<bool>, Condvar)>,
+) -> Result<Arc<ConsensusGraph>, String> {
+    let node_type = match conf.node_type() {
+        NodeType::Light | NodeType::Unknown => {
+            return Err("Pivot hint requires a full or archive node".into());
+        }
+        node_type => node_type,
+    };
+
+    println("Preparing consensus graph...");
+    let (_, _, _, consensus, sync_service, _, _, _, _, _, _, _) =
+        initialize_not_light_node_modules(conf, exit_cond_var, node_type)?;
+
+    while sync_service.catch_up_mode() {
+        thread::sleep(Duration::from_secs(1));
+    }
+
+    Ok(consensus)
+}
+
+fn pivot_hash_at(
+    consensus: &ConsensusGraph,
+) -> impl FnMut(u64) -> Result<H256, String> + '_ {
+    |height| {
+        consensus
+            .get_hash_from_epoch_number(EpochNumber::Number(height))
+            .map_err(|e| {
+                format!("Cannot get pivot hash at height {}: {}", height, e)
+            })
+    }
+}
+
+fn println(message: &str) {
+    println!("[{}] {}", Utc::now().format("%Y-%m-%d %H:%M:%S"), message);
+}
@@ -2,19 +2,21 @@
 // Conflux is free software and distributed under GNU General Public License.
 // See http://www.gnu.org/licenses/
 
-use blockgen::BlockGeneratorTestApi;
+use blockgen::{BlockGeneratorTestApi, RemoteWork};
 use cfx_config::Configuration;
 use cfx_rpc_builder::{
     CfxRpcModule, CfxRpcModuleBuilder, CfxRpcModuleSelection,
     CfxRpcServerConfig, CfxTransportRpcModuleConfig, CfxTransportRpcModules,
-    RpcModuleBuilder, RpcServerConfig, RpcServerHandle,
-    TransportRpcModuleConfig,
+    EthRpcModule, RpcModuleBuilder, RpcServerConfig, RpcServerHandle,
+    TransportRpcModuleConfig, TransportRpcModules,
 };
 use cfx_rpc_cfx_api::{
-    CfxDebugRpcServer, CfxRpcServer, DebugRpcServer, PubSubApiServer,
-    TestRpcServer,
+    CfxDebugRpcServer, CfxRpcServer, DebugRpcServer, NetRpcServer,
+    PubSubApiServer, TestRpcServer,
 };
 use cfx_rpc_cfx_types::apis::ApiSet;
+use cfx_rpc_eth_api::{EthApiServer, NetApiServer, Web3ApiServer};
+use cfx_rpc_middlewares::RpcAuth;
 use cfx_tasks::TaskExecutor;
 use cfxcore::{
     block_data_manager::BlockDataManager, consensus::pos_handler::PosVerifier,
@@ -32,7 +34,8 @@ use txgen::{DirectTransactionGenerator, TransactionGenerator};
 pub async fn launch_async_rpc_servers(
     consensus: SharedConsensusGraph, sync: SharedSynchronizationService,
     tx_pool: SharedTransactionPool, notifications: Arc<Notifications>,
-    executor: TaskExecutor, conf: &Configuration,
+    executor: TaskExecutor, accounts: Arc<cfxcore_accounts::AccountProvider>,
+    remote_work: Option<Arc<RemoteWork>>, conf: &Configuration,
 ) -> Result<Option<RpcServerHandle>, String> {
     let http_config = conf.eth_http_config();
     let ws_config = conf.eth_ws_config();
@@ -72,9 +75,28 @@ pub async fn launch_async_rpc_servers(
                         .with_ws_cors(ws_config.cors.clone());
                 (transport_rpc_module_config, server_config)
             }
-            _ => return Ok(None),
+            (false, false) => (
+                TransportRpcModuleConfig::default(),
+                RpcServerConfig::default(),
+            ),
         };
 
+    let ipc_config = conf.eth_ipc_server_config();
+    let (transport_rpc_module_config, server_config) = match ipc_config {
+        Some(ipc_config) => {
+            let ipc_apis = conf.raw_conf.ipc_evm_rpc_apis.clone();
+            info!("Enabled evm ipc rpc modules: {:?}", ipc_apis.to_selection());
+            (
+                transport_rpc_module_config.with_ipc(ipc_apis),
+                server_config.with_ipc(ipc_config),
+            )
+        }
+        None => (transport_rpc_module_config, server_config),
+    };
+    if transport_rpc_module_config.is_empty() {
+        return Ok(None);
+    }
+
     info!("Enabled evm async rpc modules: {:?}", apis.into_selection());
     let rpc_conf = conf.rpc_impl_config();
     let enable_metrics = rpc_conf.enable_metrics;
@@ -86,6 +108,8 @@ pub async fn launch_async_rpc_servers(
         tx_pool,
         executor,
         notifications,
+        accounts,
+        remote_work,
     );
 
     let transport_rpc_modules =
@@ -93,6 +117,7 @@ pub async fn launch_async_rpc_servers(
 
     let throttling_conf_file = conf.raw_conf.throttling_conf.clone();
     let server_handle = server_config
+        .with_auth(load_rpc_auth(conf)?)
         .start(&transport_rpc_modules, throttling_conf_file, enable_metrics)
         .await
         .map_err(|e| e.to_string())?;
@@ -153,9 +178,36 @@ pub async fn launch_cfx_async_rpc_servers(
                         .with_ws_cors(ws_config.cors.clone());
                 (transport_rpc_module_config, server_config)
             }
-            _ => return Ok(None),
+            (false, false) => (
+                CfxTransportRpcModuleConfig::default(),
+                CfxRpcServerConfig::default(),
+            ),
         };
 
+    // The ipc server is started along with the local rpc services.
+    let ipc_config = if is_debug {
+        conf.ipc_server_config()
+    } else {
+        None
+    };
+    let (transport_rpc_module_config, server_config) = match ipc_config {
+        Some(ipc_config) => {
+            let ipc_apis = conf.raw_conf.ipc_rpc_apis.clone();
+            info!(
+                "Enabled cfx ipc rpc modules: {:?}",
+                CfxRpcModuleSelection::from(ipc_apis.clone()).into_selection()
+            );
+            (
+                transport_rpc_module_config.with_ipc(ipc_apis),
+                server_config.with_ipc(ipc_config),
+            )
+        }
+        None => (transport_rpc_module_config, server_config),
+    };
+    if transport_rpc_module_config.is_empty() {
+        return Ok(None);
+    }
+
     info!(
         "Enabled cfx async rpc modules: {:?}",
         CfxRpcModuleSelection::from(apis).into_selection()
@@ -185,8 +237,11 @@ pub async fn launch_cfx_async_rpc_servers(
 
     let throttling_conf_file = conf.raw_conf.throttling_conf.clone();
     let throttling_section = if is_debug { "rpc_local" } else { "rpc" };
+    // The local rpc services are not authenticated.
+    let auth = if is_debug { None } else { load_rpc_auth(conf)? };
 
     let server_handle = server_config
+        .with_auth(auth)
         .start(
             &transport_rpc_modules,
             throttling_conf_file,
@@ -215,7 +270,7 @@ pub async fn launch_cfx_light_async_rpc_servers(
             LightCfxHandler, LightDebugHandler, LightTestHandler,
             RpcImpl as LightRpcImpl,
         },
-        PubSubHandler,
+        NetHandler, PubSubHandler,
     };
 
     let (http_config, ws_config) = if is_debug {
@@ -257,6 +312,7 @@ pub async fn launch_cfx_light_async_rpc_servers(
     ));
 
     let light_rpc_impl = Arc::new(LightRpcImpl::new(
+        conf.rpc_impl_config(),
         light,
         accounts,
         consensus.clone(),
@@ -290,6 +346,12 @@ pub async fn launch_cfx_light_async_rpc_servers(
                 module
                     .merge(DebugRpcServer::into_rpc(debug_handler))
                     .expect("No conflicts for Debug module");
+
+                module
+                    .merge(NetRpcServer::into_rpc(NetHandler::new(
+                        network.clone(),
+                    )))
+                    .expect("No conflicts for Net module");
             }
             CfxRpcModule::Test => {
                 let handler = LightTestHandler::new(common_rpc_impl.clone());
@@ -352,8 +414,11 @@ pub async fn launch_cfx_light_async_rpc_servers(
     let enable_metrics = rpc_conf.enable_metrics;
     let throttling_conf_file = conf.raw_conf.throttling_conf.clone();
     let throttling_section = if is_debug { "rpc_local" } else { "rpc" };
+    // The local rpc services are not authenticated.
+    let auth = if is_debug { None } else { load_rpc_auth(conf)? };
 
     let server_handle = server_config
+        .with_auth(auth)
         .start(
             &transport_modules,
             throttling_conf_file,
@@ -365,3 +430,107 @@ pub async fn launch_cfx_light_async_rpc_servers(
 
     Ok(Some(server_handle))
 }
+
+// start espace light rpc server (async, jsonrpsee)
+pub async fn launch_eth_light_async_rpc_servers(
+    consensus: SharedConsensusGraph, light: Arc<LightQueryService>,
+    conf: &Configuration,
+) -> Result<Option<RpcServerHandle>, String> {
+    use cfx_rpc_eth_impl::{helpers::ChainInfo, LightEthApi, NetApi, Web3Api};
+
+    let http_config = conf.eth_http_config();
+    let ws_config = conf.eth_ws_config();
+    let apis = conf.raw_conf.public_evm_rpc_apis.clone();
+
+    let server_config = match (http_config.enabled, ws_config.enabled) {
+        (true, true) => RpcServerConfig::http(conf.jsonrpsee_server_builder())
+            .with_ws(conf.jsonrpsee_server_builder())
+            .with_cors(http_config.cors.clone())
+            .with_ws_cors(ws_config.cors.clone())
+            .with_http_address(http_config.address)
+            .with_ws_address(ws_config.address),
+        (true, false) => RpcServerConfig::http(conf.jsonrpsee_server_builder())
+            .with_http_address(http_config.address)
+            .with_cors(http_config.cors.clone()),
+        (false, true) => RpcServerConfig::ws(conf.jsonrpsee_server_builder())
+            .with_ws_address(ws_config.address)
+            .with_ws_cors(ws_config.cors.clone()),
+        _ => return Ok(None),
+    };
+
+    let rpc_conf = conf.rpc_impl_config();
+    let enable_metrics = rpc_conf.enable_metrics;
+
+    let mut module = RpcModule::new(());
+
+    for api in apis.iter_selection() {
+        match api {
+            EthRpcModule::Eth => {
+                let handler = LightEthApi::new(
+                    rpc_conf.clone(),
+                    consensus.clone(),
+                    light.clone(),
+                );
+                module
+                    .merge(EthApiServer::into_rpc(handler))
+                    .expect("No conflicts for Eth module");
+            }
+            EthRpcModule::Net => {
+                let handler =
+                    NetApi::new(Box::new(ChainInfo::new(consensus.clone())));
+                module
+                    .merge(NetApiServer::into_rpc(handler))
+                    .expect("No conflicts for Net module");
+            }
+            EthRpcModule::Web3 => {
+                module
+                    .merge(Web3ApiServer::into_rpc(Web3Api))
+                    .expect("No conflicts for Web3 module");
+            }
+            api => {
+                warn!("Light nodes do not support {:?} eSpace RPC", api);
+            }
+        }
+    }
+
+    info!(
+        "Enabled evm light async rpc modules: {:?}",
+        apis.to_selection()
+    );
+
+    let mut transport_modules = TransportRpcModules::default();
+
+    match (http_config.enabled, ws_config.enabled) {
+        (true, true) => {
+            transport_modules.config =
+                TransportRpcModuleConfig::set_http(apis.clone()).with_ws(apis);
+            transport_modules.http = Some(module.clone());
+            transport_modules.ws = Some(module);
+        }
+        (true, false) => {
+            transport_modules.config = TransportRpcModuleConfig::set_http(apis);
+            transport_modules.http = Some(module);
+        }
+        (false, true) => {
+            transport_modules.config = TransportRpcModuleConfig::set_ws(apis);
+            transport_modules.ws = Some(module);
+        }
+        _ => unreachable!(),
+    }
+
+    let throttling_conf_file = conf.raw_conf.throttling_conf.clone();
+    let server_handle = server_config
+        .with_auth(load_rpc_auth(conf)?)
+        .start(&transport_modules, throttling_conf_file, enable_metrics)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(Some(server_handle))
+}
+
+fn load_rpc_auth(conf: &Configuration) -> Result<Option<Arc<RpcAuth>>, String> {
+    match &conf.raw_conf.rpc_auth_conf {
+        Some(file) => Ok(Some(Arc::new(RpcAuth::load(file)?))),
+        None => Ok(None),
+    }
+}
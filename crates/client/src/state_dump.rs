@@ -4,18 +4,24 @@ use cfx_rpc_eth_types::{AccountState, StateDump, EOA_STORAGE_ROOT_H256};
 use cfx_rpc_primitives::Bytes;
 use cfx_statedb::{StateDbExt, StateDbGeneric};
 use cfx_storage::state_manager::StateManagerTrait;
-use cfx_types::{Address, Space, H256, U256};
+use cfx_types::{
+    address_util::{AddressUtil, TYPE_BITS_BUILTIN},
+    Address, AddressSpaceUtil, Space, H256, U256,
+};
 use cfxcore::NodeType;
 use chrono::Utc;
 use keccak_hash::{keccak, KECCAK_EMPTY};
 use parking_lot::{Condvar, Mutex};
 use primitives::{
-    Account, SkipInputCheck, StorageKey, StorageKeyWithSpace, StorageValue,
+    account::StoragePoints, Account, DepositInfo, SkipInputCheck, SponsorInfo,
+    StorageKey, StorageKeyWithSpace, StorageValue, VoteStakeInfo,
 };
 use rlp::Rlp;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::{
-    collections::{BTreeMap, HashMap},
-    fs,
+    collections::BTreeMap,
+    fs::{self, File, OpenOptions},
+    io::{BufWriter, Seek, SeekFrom, Write},
     ops::Deref,
     path::Path,
     sync::Arc,
@@ -23,6 +29,12 @@ use std::{
     time::Duration,
 };
 
+/// File name of the newline-delimited account stream in iterative mode
+pub const STREAM_FILE_NAME: &str = "state.jsonl";
+/// File name of the checkpoint of the account stream
+pub const CHECKPOINT_FILE_NAME: &str = "checkpoint.json";
+
+#[derive(Clone)]
 pub struct StateDumpConfig {
     pub start_address: Address,
     pub limit: u64,
@@ -30,22 +42,266 @@ pub struct StateDumpConfig {
     pub no_code: bool,
     pub no_storage: bool,
     pub out_put_path: String,
+    /// Spaces to dump. Core Space accounts are always dumped before eSpace
+    /// accounts.
+    pub spaces: Vec<Space>,
+    /// Only dump the listed accounts if specified
+    pub addresses: Option<Vec<Address>>,
+    /// Only dump the storage entries whose key starts with this prefix
+    pub storage_key_prefix: Option<Vec<u8>>,
+    /// Also dump the addresses which have storage entries but no account
+    pub incompletes: bool,
+}
+
+/// The state of a Core Space account, including the Core Space only fields
+/// which have no counterpart in eSpace.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoreAccountState {
+    pub address: Address,
+    /// Absent for builtin addresses which only hold storage entries, and for
+    /// the incomplete accounts
+    #[serde(flatten)]
+    pub account: Option<CoreAccount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<Bytes>,
+    /// The address which pays the collateral for the code
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code_owner: Option<Address>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage: Option<BTreeMap<CoreStorageKey, CoreStorageValue>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deposit_list: Option<Vec<DepositInfo>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vote_list: Option<Vec<VoteStakeInfo>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoreAccount {
+    pub balance: U256,
+    pub nonce: U256,
+    pub code_hash: H256,
+    pub staking_balance: U256,
+    pub collateral_for_storage: U256,
+    pub accumulated_interest_return: U256,
+    pub admin: Address,
+    pub sponsor_info: CoreSponsorInfo,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoreSponsorInfo {
+    pub sponsor_for_gas: Address,
+    pub sponsor_for_collateral: Address,
+    pub sponsor_gas_bound: U256,
+    pub sponsor_balance_for_gas: U256,
+    pub sponsor_balance_for_collateral: U256,
+    /// The storage points introduced in CIP-107, absent if never initialized
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unused_storage_points: Option<U256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub used_storage_points: Option<U256>,
+}
+
+/// The key of a Core Space storage entry. It is a 32 bytes slot for the
+/// contracts, but the builtin addresses also use shorter or longer keys.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CoreStorageKey(pub Vec<u8>);
+
+impl From<H256> for CoreStorageKey {
+    fn from(key: H256) -> Self { Self(key.as_bytes().to_vec()) }
+}
+
+impl Serialize for CoreStorageKey {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer {
+        Bytes(self.0.clone()).serialize(serializer)
+    }
+}
+
+impl<'a> Deserialize<'a> for CoreStorageKey {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where D: Deserializer<'a> {
+        Bytes::deserialize(deserializer).map(|bytes| Self(bytes.0))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoreStorageValue {
+    pub value: U256,
+    /// The address which pays the collateral for the entry
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<Address>,
+}
+
+/// An account of either space, as written to each line of the account stream.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "space", rename_all = "lowercase")]
+pub enum DumpedAccount {
+    Native(CoreAccountState),
+    #[serde(rename = "evm")]
+    Ethereum(AccountState),
+}
+
+impl DumpedAccount {
+    pub fn space(&self) -> Space {
+        match self {
+            DumpedAccount::Native(_) => Space::Native,
+            DumpedAccount::Ethereum(_) => Space::Ethereum,
+        }
+    }
+
+    pub fn address(&self) -> Address {
+        match self {
+            DumpedAccount::Native(account) => account.address,
+            DumpedAccount::Ethereum(account) => {
+                account.address.expect("address is always set")
+            }
+        }
+    }
+}
+
+/// The position of the account stream, a resumed dump continues after the
+/// last written account.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateDumpCheckpoint {
+    pub root: H256,
+    pub epoch: u64,
+    /// The space and address of the last written account
+    pub last_account: Option<(Space, Address)>,
+    /// Number of accounts written to the stream
+    pub accounts: u64,
+    /// Length of the stream file at the checkpoint. Anything after it is
+    /// discarded when resuming.
+    pub offset: u64,
+    pub completed: bool,
+    /// The options of the dump, which a resumed dump must keep
+    pub options: StateDumpOptions,
 }
 
-// This method will read all data (k, v) from the Conflux state tree (including
-// core space and espace accounts, code, storage, deposit, vote_list) into
-// memory at once, then parse and assemble them and assemble all account states
-// into a StateDump struct and return it
+/// The options which select the dumped accounts and their content.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateDumpOptions {
+    pub start_address: Address,
+    pub spaces: Vec<Space>,
+    pub addresses: Option<Vec<Address>>,
+    pub storage_key_prefix: Option<Bytes>,
+    pub no_code: bool,
+    pub no_storage: bool,
+    pub incompletes: bool,
+}
+
+impl From<&StateDumpConfig> for StateDumpOptions {
+    fn from(config: &StateDumpConfig) -> Self {
+        let mut spaces = config.spaces.clone();
+        spaces.sort();
+        spaces.dedup();
+        let addresses = config.addresses.clone().map(|mut addresses| {
+            addresses.sort();
+            addresses.dedup();
+            addresses
+        });
+        Self {
+            start_address: config.start_address,
+            spaces,
+            addresses,
+            storage_key_prefix: config.storage_key_prefix.clone().map(Bytes),
+            no_code: config.no_code,
+            no_storage: config.no_storage,
+            incompletes: config.incompletes,
+        }
+    }
+}
+
+impl From<SponsorInfo> for CoreSponsorInfo {
+    fn from(info: SponsorInfo) -> Self {
+        Self {
+            sponsor_for_gas: info.sponsor_for_gas,
+            sponsor_for_collateral: info.sponsor_for_collateral,
+            sponsor_gas_bound: info.sponsor_gas_bound,
+            sponsor_balance_for_gas: info.sponsor_balance_for_gas,
+            sponsor_balance_for_collateral: info.sponsor_balance_for_collateral,
+            unused_storage_points: info
+                .storage_points
+                .as_ref()
+                .map(|p| p.unused),
+            used_storage_points: info.storage_points.as_ref().map(|p| p.used),
+        }
+    }
+}
+
+impl From<CoreSponsorInfo> for SponsorInfo {
+    fn from(info: CoreSponsorInfo) -> Self {
+        let storage_points =
+            match (info.unused_storage_points, info.used_storage_points) {
+                (None, None) => None,
+                (unused, used) => Some(StoragePoints {
+                    unused: unused.unwrap_or_default(),
+                    used: used.unwrap_or_default(),
+                }),
+            };
+        Self {
+            sponsor_for_gas: info.sponsor_for_gas,
+            sponsor_for_collateral: info.sponsor_for_collateral,
+            sponsor_gas_bound: info.sponsor_gas_bound,
+            sponsor_balance_for_gas: info.sponsor_balance_for_gas,
+            sponsor_balance_for_collateral: info.sponsor_balance_for_collateral,
+            storage_points,
+        }
+    }
+}
+
+impl From<&Account> for CoreAccount {
+    fn from(account: &Account) -> Self {
+        Self {
+            balance: account.balance,
+            nonce: account.nonce,
+            code_hash: account.code_hash,
+            staking_balance: account.staking_balance,
+            collateral_for_storage: account.collateral_for_storage,
+            accumulated_interest_return: account.accumulated_interest_return,
+            admin: account.admin,
+            sponsor_info: account.sponsor_info.clone().into(),
+        }
+    }
+}
+
+// This method collects the eSpace accounts into a StateDump struct in memory
+// and returns it. It is only suitable for the eSpace or a restricted set of
+// accounts, the iterative mode should be used for a full dump.
 pub fn dump_whole_state(
     conf: &mut Configuration, exit_cond_var: Arc<(Mutex<bool>, Condvar)>,
     config: &StateDumpConfig,
 ) -> Result<StateDump, String> {
-    let (mut state_db, state_root) =
+    if config.spaces != [Space::Ethereum] {
+        return Err(
+            "Core Space accounts can only be dumped in the multi-file or iterative mode"
+                .into(),
+        );
+    }
+
+    let (mut state_db, state_root, _) =
         prepare_state_db(conf, exit_cond_var, config)?;
 
-    let accounts =
-        export_space_accounts(&mut state_db, Space::Ethereum, config)
-            .map_err(|e| e.to_string())?;
+    let mut accounts = BTreeMap::new();
+    export_accounts_with_callback(
+        &mut state_db,
+        config,
+        None,
+        &mut |account| {
+            if let DumpedAccount::Ethereum(account_state) = account {
+                accounts.insert(
+                    account_state.address.expect("address is always set"),
+                    account_state,
+                );
+            }
+            Ok(())
+        },
+    )?;
 
     let state_dump = StateDump {
         root: state_root,
@@ -56,33 +312,211 @@ pub fn dump_whole_state(
     Ok(state_dump)
 }
 
-// This method will iterate through the entire state tree, storing each found
-// account in a temporary map After iterating through all accounts, it will
-// retrieve the code and storage for each account, then call the callback method
-// Pass the AccountState as a parameter to the callback method, which will
-// handle the AccountState
-pub fn iterate_dump_whole_state<F: Fn(AccountState)>(
+// This method will iterate through the state tree in the order of space and
+// address. For each found account it retrieves the code, storage and other
+// data of the account, then pass it to the callback method, which will handle
+// the DumpedAccount
+pub fn iterate_dump_whole_state<
+    F: FnMut(DumpedAccount) -> Result<(), String>,
+>(
     conf: &mut Configuration, exit_cond_var: Arc<(Mutex<bool>, Condvar)>,
-    config: &StateDumpConfig, callback: F,
+    config: &StateDumpConfig, mut callback: F,
 ) -> Result<H256, String> {
-    let (mut state_db, state_root) =
+    let (mut state_db, state_root, _) =
         prepare_state_db(conf, exit_cond_var, config)?;
 
-    export_space_accounts_with_callback(
+    export_accounts_with_callback(&mut state_db, config, None, &mut callback)?;
+
+    Ok(state_root)
+}
+
+// This method writes the accounts as newline-delimited JSON into
+// `STREAM_FILE_NAME` under the output path, and saves a checkpoint every
+// `checkpoint_interval` accounts. If `resume` is set, the dump continues from
+// the last checkpoint, at the epoch of the checkpoint.
+pub fn stream_dump_whole_state(
+    conf: &mut Configuration, exit_cond_var: Arc<(Mutex<bool>, Condvar)>,
+    config: &StateDumpConfig, resume: bool, checkpoint_interval: u64,
+) -> Result<StateDumpCheckpoint, String> {
+    let mut config = config.clone();
+    let previous = if resume {
+        let checkpoint = load_resumed_checkpoint(&config)?;
+        if checkpoint.completed {
+            println("The dump has already been completed");
+            return Ok(checkpoint);
+        }
+        config.block = Some(checkpoint.epoch);
+        Some(checkpoint)
+    } else {
+        None
+    };
+
+    let (mut state_db, state_root, epoch) =
+        prepare_state_db(conf, exit_cond_var, &config)?;
+
+    let checkpoint = match previous {
+        Some(checkpoint) => {
+            if checkpoint.root != state_root {
+                return Err(format!(
+                    "State root mismatch at epoch {}: checkpoint {:?}, local {:?}",
+                    epoch, checkpoint.root, state_root
+                ));
+            }
+            println(&format!(
+                "Resume the dump after {} accounts",
+                checkpoint.accounts
+            ));
+            checkpoint
+        }
+        None => StateDumpCheckpoint {
+            root: state_root,
+            epoch,
+            last_account: None,
+            accounts: 0,
+            offset: 0,
+            completed: false,
+            options: (&config).into(),
+        },
+    };
+
+    write_stream(
         &mut state_db,
-        Space::Ethereum,
-        config,
-        callback,
+        &config,
+        checkpoint,
+        resume,
+        checkpoint_interval,
     )
-    .map_err(|e| e.to_string())?;
+}
 
-    Ok(state_root)
+// Loads the checkpoint of the dump to resume, which must have been started
+// with the same options.
+fn load_resumed_checkpoint(
+    config: &StateDumpConfig,
+) -> Result<StateDumpCheckpoint, String> {
+    let checkpoint = load_checkpoint(
+        &Path::new(&config.out_put_path).join(CHECKPOINT_FILE_NAME),
+    )?;
+    let options = StateDumpOptions::from(config);
+    if checkpoint.options != options {
+        return Err(format!(
+            "The options {:?} differ from {:?} of the dump to resume",
+            options, checkpoint.options
+        ));
+    }
+    Ok(checkpoint)
+}
+
+// Writes the accounts after the checkpoint into the stream file. A resumed
+// stream must exist, and is truncated to the checkpoint.
+fn write_stream(
+    state_db: &mut StateDbGeneric, config: &StateDumpConfig,
+    mut checkpoint: StateDumpCheckpoint, resume: bool,
+    checkpoint_interval: u64,
+) -> Result<StateDumpCheckpoint, String> {
+    let output_path = Path::new(&config.out_put_path);
+    let stream_path = output_path.join(STREAM_FILE_NAME);
+    let checkpoint_path = output_path.join(CHECKPOINT_FILE_NAME);
+
+    let file = OpenOptions::new()
+        .create(!resume)
+        .write(true)
+        .truncate(!resume)
+        .open(&stream_path)
+        .map_err(|e| {
+            format!("Failed to open {}: {}", stream_path.display(), e)
+        })?;
+    let len = file
+        .metadata()
+        .map_err(|e| format!("Failed to read stream file: {}", e))?
+        .len();
+    if len < checkpoint.offset {
+        return Err(format!(
+            "The stream file {} is shorter than the checkpoint",
+            stream_path.display()
+        ));
+    }
+    // Drop the accounts written after the last checkpoint
+    file.set_len(checkpoint.offset)
+        .map_err(|e| format!("Failed to truncate stream file: {}", e))?;
+    let mut writer = BufWriter::new(file);
+    writer
+        .seek(SeekFrom::End(0))
+        .map_err(|e| format!("Failed to seek stream file: {}", e))?;
+
+    let start = checkpoint.last_account;
+    let initial_accounts = checkpoint.accounts;
+    let mut since_checkpoint = 0;
+    export_accounts_with_callback(state_db, config, start, &mut |account| {
+        let line = serde_json::to_string(&account).map_err(|e| {
+            format!(
+                "Failed to serialize account state for {:?}: {}",
+                account.address(),
+                e
+            )
+        })?;
+        writeln!(writer, "{}", line)
+            .map_err(|e| format!("Failed to write stream file: {}", e))?;
+
+        checkpoint.last_account = Some((account.space(), account.address()));
+        checkpoint.accounts += 1;
+        since_checkpoint += 1;
+        if since_checkpoint >= checkpoint_interval {
+            since_checkpoint = 0;
+            save_checkpoint(&mut writer, &mut checkpoint, &checkpoint_path)?;
+        }
+        Ok(())
+    })?;
+
+    // A dump stopped by the limit can be resumed later.
+    checkpoint.completed = config.limit == 0
+        || checkpoint.accounts - initial_accounts < config.limit;
+    save_checkpoint(&mut writer, &mut checkpoint, &checkpoint_path)?;
+
+    Ok(checkpoint)
+}
+
+pub(crate) fn load_checkpoint(
+    path: &Path,
+) -> Result<StateDumpCheckpoint, String> {
+    let content = fs::read_to_string(path).map_err(|e| {
+        format!("Failed to read checkpoint {}: {}", path.display(), e)
+    })?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse checkpoint: {}", e))
+}
+
+fn save_checkpoint(
+    writer: &mut BufWriter<File>, checkpoint: &mut StateDumpCheckpoint,
+    path: &Path,
+) -> Result<(), String> {
+    writer
+        .flush()
+        .and_then(|_| writer.get_ref().sync_data())
+        .map_err(|e| format!("Failed to flush stream file: {}", e))?;
+    checkpoint.offset = writer
+        .stream_position()
+        .map_err(|e| format!("Failed to get stream position: {}", e))?;
+
+    // Write to a temporary file first so that an interruption never leaves a
+    // broken checkpoint.
+    let content = serde_json::to_string_pretty(checkpoint)
+        .map_err(|e| format!("Failed to serialize checkpoint: {}", e))?;
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, content)
+        .and_then(|_| fs::rename(&tmp_path, path))
+        .map_err(|e| format!("Failed to write checkpoint: {}", e))?;
+
+    println(&format!(
+        "Checkpoint: {} accounts, last {:?}",
+        checkpoint.accounts, checkpoint.last_account
+    ));
+    Ok(())
 }
 
 fn prepare_state_db(
     conf: &mut Configuration, exit_cond_var: Arc<(Mutex<bool>, Condvar)>,
     config: &StateDumpConfig,
-) -> Result<(StateDbGeneric, H256), String> {
+) -> Result<(StateDbGeneric, H256, u64), String> {
     println("Preparing state...");
     let (data_man, _, _, consensus, sync_service, _, _, _, _, _, _, _) =
         initialize_not_light_node_modules(
@@ -120,218 +554,301 @@ fn prepare_state_db(
         .get_state_readonly_index(&epoch_hash)
         .ok_or("Failed to get state index")?;
 
+    let space = match config.spaces[..] {
+        [space] => Some(space),
+        _ => None,
+    };
     let state = state_manager
-        .get_state_no_commit(state_index, true, Some(Space::Ethereum))
+        .get_state_no_commit(state_index, true, space)
         .map_err(|e| e.to_string())?
         .ok_or("Failed to get state")?;
 
     let state_db = StateDbGeneric::new(state);
 
-    Ok((state_db, *state_root))
+    Ok((state_db, *state_root, target_height))
 }
 
-fn export_space_accounts(
-    state: &mut StateDbGeneric, space: Space, config: &StateDumpConfig,
-) -> Result<BTreeMap<Address, AccountState>, Box<dyn std::error::Error>> {
+// Visits the accounts of the configured spaces in the order of (space,
+// address). The iteration starts from `config.start_address` of each space, or
+// right after the `after` account in its space.
+pub(crate) fn export_accounts_with_callback(
+    state: &mut StateDbGeneric, config: &StateDumpConfig,
+    after: Option<(Space, Address)>,
+    callback: &mut dyn FnMut(DumpedAccount) -> Result<(), String>,
+) -> Result<(), String> {
     println("Start to iterate state...");
-    let empty_key = StorageKey::EmptyKey.with_space(space);
-    let kv_pairs = state.read_all(empty_key, None)?;
-
-    let mut accounts_map = BTreeMap::new();
-    let mut codes_map = HashMap::new();
-    let mut storage_map = HashMap::new();
+    let mut spaces = config.spaces.clone();
+    spaces.sort();
+    spaces.dedup();
 
-    for (key, value) in kv_pairs {
-        let storage_key_with_space =
-            StorageKeyWithSpace::from_key_bytes::<SkipInputCheck>(&key);
-        if storage_key_with_space.space != space {
-            continue;
+    let mut found_accounts = 0;
+    let mut visit = |state: &mut StateDbGeneric,
+                     space: Space,
+                     address: Address,
+                     account: Option<Account>|
+     -> Result<bool, String> {
+        // Only the builtin addresses are complete without an account
+        if account.is_none()
+            && !config.incompletes
+            && !(space == Space::Native && address.is_builtin_address())
+        {
+            return Ok(false);
         }
-        match storage_key_with_space.key {
-            StorageKey::AccountKey(address_bytes) => {
-                let address = Address::from_slice(address_bytes);
-                println(&format!("Find account: {:?}", address));
-                let account =
-                    Account::new_from_rlp(address, &Rlp::new(&value))?;
-                accounts_map.insert(address, account);
+        let dumped = match space {
+            Space::Native => {
+                read_core_account_state(state, address, account, config)
+                    .map_err(|e| e.to_string())?
+                    .map(DumpedAccount::Native)
             }
-            StorageKey::CodeKey {
-                address_bytes,
-                code_hash_bytes: _,
-            } => {
-                if config.no_code {
-                    continue;
-                }
-
-                let address = Address::from_slice(address_bytes);
-                let code = Bytes(value.to_vec());
-                codes_map.insert(address, code);
-            }
-            StorageKey::StorageKey {
-                address_bytes,
-                storage_key,
-            } => {
-                if config.no_storage {
-                    continue;
-                }
-
-                let address = Address::from_slice(address_bytes);
-                let h256_storage_key = H256::from_slice(storage_key);
-                let storage_value_with_owner: StorageValue =
-                    rlp::decode(&value)?;
-                let account_storage_map =
-                    storage_map.entry(address).or_insert(BTreeMap::new());
-                account_storage_map
-                    .insert(h256_storage_key, storage_value_with_owner.value);
-            }
-            _ => {
-                continue;
+            Space::Ethereum => {
+                get_account_state(state, address, account, config, space)
+                    .map_err(|e| e.to_string())?
+                    .map(DumpedAccount::Ethereum)
             }
+        };
+        if let Some(dumped) = dumped {
+            callback(dumped)?;
+            found_accounts += 1;
         }
-    }
-
-    let mut accounts = BTreeMap::new();
+        // Stop the iteration once the limit is reached
+        Ok(config.limit > 0 && found_accounts >= config.limit)
+    };
 
-    for (address, account) in accounts_map {
-        let is_contract = account.code_hash != KECCAK_EMPTY;
-        // conflux state tree don't have storage root, so we use a fixed value
-        let root = EOA_STORAGE_ROOT_H256;
-        let address_hash = keccak(address);
-
-        let code = if is_contract {
-            codes_map.get(&address).cloned()
-        } else {
-            if let Some(code) = codes_map.get(&address) {
-                println(&format!("no-contract account have code: {:?}", code));
+    for space in spaces {
+        let is_in_range = |address: &Address| match after {
+            Some((after_space, after_address)) if after_space == space => {
+                *address > after_address
             }
-            None
+            _ => *address >= config.start_address,
         };
+        if matches!(after, Some((after_space, _)) if space < after_space) {
+            continue;
+        }
 
-        let storage = if is_contract {
-            storage_map.get(&address).cloned()
-        } else {
-            if let Some(_storage) = storage_map.get(&address) {
-                println("no-contract account have storage");
+        if let Some(addresses) = &config.addresses {
+            let mut addresses: Vec<_> =
+                addresses.iter().filter(|a| is_in_range(a)).collect();
+            addresses.sort();
+            addresses.dedup();
+            for address in addresses {
+                let account = state
+                    .get_account(&address.with_space(space))
+                    .map_err(|e| e.to_string())?;
+                if visit(state, space, *address, account)? {
+                    return Ok(());
+                }
             }
-            None
-        };
+            continue;
+        }
 
-        let account_state = AccountState {
-            balance: account.balance,
-            nonce: account.nonce.as_u64(),
-            root,
-            code_hash: account.code_hash,
-            code,
-            storage,
-            address: Some(address),
-            address_hash: Some(address_hash),
+        let first_prefix = match after {
+            Some((after_space, after_address)) if after_space == space => {
+                after_address[0]
+            }
+            _ => config.start_address[0],
         };
-
-        accounts.insert(address, account_state);
-
-        if config.limit > 0 && accounts.len() >= config.limit as usize {
-            break;
+        for i in first_prefix..=255 {
+            let accounts =
+                read_prefix_accounts(state, space, i, config.incompletes)?;
+            if !accounts.is_empty() {
+                println("Start to read account code and storage data...");
+            }
+            for (address, account) in accounts {
+                if !is_in_range(&address) {
+                    continue;
+                }
+                if visit(state, space, address, account)? {
+                    return Ok(());
+                }
+            }
         }
     }
 
-    Ok(accounts)
+    Ok(())
 }
 
-pub fn export_space_accounts_with_callback<F: Fn(AccountState)>(
-    state: &mut StateDbGeneric, space: Space, config: &StateDumpConfig,
-    callback: F,
-) -> Result<(), Box<dyn std::error::Error>> {
-    println("Start to iterate state...");
-    let mut found_accounts = 0;
-    let mut core_space_key_count: u64 = 0;
-    let mut total_key_count: u64 = 0;
+// Reads the accounts whose address starts with the prefix byte. In Core Space
+// the builtin addresses may hold storage entries without an account, so all
+// keys under the builtin prefixes are visited to find them. With
+// `incompletes`, all keys are visited to find the addresses which have
+// entries but no account.
+fn read_prefix_accounts(
+    state: &mut StateDbGeneric, space: Space, prefix: u8, incompletes: bool,
+) -> Result<BTreeMap<Address, Option<Account>>, String> {
+    let prefix = [prefix];
+    let start_key = StorageKey::AddressPrefixKey(&prefix).with_space(space);
+    let only_account_key = !(incompletes
+        || space == Space::Native
+            && Address::repeat_byte(prefix[0]).address_type_bits()
+                == TYPE_BITS_BUILTIN);
 
-    for i in 0..=255 {
-        let prefix = [i];
-        let start_key = StorageKey::AddressPrefixKey(&prefix).with_space(space);
-
-        let mut account_states = BTreeMap::new();
+    let mut accounts = BTreeMap::new();
+    let mut error = None;
+    let mut inner_callback = |(key, value): (Vec<u8>, Box<[u8]>)| {
+        if error.is_some() {
+            return;
+        }
 
-        let mut inner_callback = |(key, value): (Vec<u8>, Box<[u8]>)| {
-            total_key_count += 1;
+        let storage_key_with_space =
+            StorageKeyWithSpace::from_key_bytes::<SkipInputCheck>(&key);
+        if storage_key_with_space.space != space {
+            return;
+        }
 
-            if total_key_count.is_multiple_of(10000) {
-                println(&format!(
-                    "total_key_count: {}, core_space_key_count: {}",
-                    total_key_count, core_space_key_count
-                ));
+        match storage_key_with_space.key {
+            StorageKey::AccountKey(address_bytes) => {
+                let address = Address::from_slice(address_bytes);
+                println(&format!("Find account: {:?}", address));
+                match Account::new_from_rlp(address, &Rlp::new(&value)) {
+                    Ok(account) => {
+                        accounts.insert(address, Some(account));
+                    }
+                    Err(e) => {
+                        error = Some(format!(
+                            "Failed to decode account {:?}: {:?}",
+                            address, e
+                        ))
+                    }
+                }
             }
-
-            let storage_key_with_space =
-                StorageKeyWithSpace::from_key_bytes::<SkipInputCheck>(&key);
-            if storage_key_with_space.space != space {
-                core_space_key_count += 1;
-                return;
+            StorageKey::StorageRootKey(address_bytes)
+            | StorageKey::StorageKey { address_bytes, .. }
+            | StorageKey::CodeRootKey(address_bytes)
+            | StorageKey::CodeKey { address_bytes, .. }
+            | StorageKey::DepositListKey(address_bytes)
+            | StorageKey::VoteListKey(address_bytes) => {
+                accounts
+                    .entry(Address::from_slice(address_bytes))
+                    .or_insert(None);
             }
+            _ => {}
+        }
+    };
 
-            if let StorageKey::AccountKey(address_bytes) =
-                storage_key_with_space.key
-            {
-                let address = Address::from_slice(address_bytes);
-                println(&format!("Find account: {:?}", address));
-                let account = Account::new_from_rlp(address, &Rlp::new(&value))
-                    .expect("Failed to decode account");
+    state
+        .read_all_with_callback(
+            start_key,
+            &mut inner_callback,
+            only_account_key,
+        )
+        .map_err(|e| e.to_string())?;
 
-                account_states.insert(address, account);
-            }
-        };
+    match error {
+        Some(e) => Err(e),
+        None => Ok(accounts),
+    }
+}
 
-        state.read_all_with_callback(start_key, &mut inner_callback, true)?;
+fn read_core_account_state(
+    state: &mut StateDbGeneric, address: Address, account: Option<Account>,
+    config: &StateDumpConfig,
+) -> Result<Option<CoreAccountState>, Box<dyn std::error::Error>> {
+    let address_with_space = address.with_native_space();
+    let code_hash = account.as_ref().map_or(KECCAK_EMPTY, |a| a.code_hash);
 
-        if !account_states.is_empty() {
-            println("Start to read account code and storage data...");
-        }
+    let code_info = if code_hash != KECCAK_EMPTY && !config.no_code {
+        state.get_code(&address_with_space, &code_hash)?
+    } else {
+        None
+    };
 
-        for (_address, account) in account_states {
-            let account_state =
-                get_account_state(state, &account, config, space)?;
-            callback(account_state);
-            found_accounts += 1;
-            if config.limit > 0 && found_accounts >= config.limit as usize {
-                break;
-            }
-        }
+    // Builtin addresses keep their data in storage without having code, and
+    // incomplete accounts only have storage entries.
+    let storage = if (code_hash != KECCAK_EMPTY
+        || address.is_builtin_address()
+        || account.is_none())
+        && !config.no_storage
+    {
+        let storage =
+            get_contract_storage(state, &address, Space::Native, config)?;
+        (!storage.is_empty()).then(|| {
+            storage
+                .into_iter()
+                .map(|(key, value)| {
+                    let value = CoreStorageValue {
+                        value: value.value,
+                        owner: value.owner,
+                    };
+                    (CoreStorageKey(key), value)
+                })
+                .collect()
+        })
+    } else {
+        None
+    };
+
+    let deposit_list = state
+        .get_deposit_list(&address_with_space)?
+        .map(|list| list.0)
+        .filter(|list| !list.is_empty());
+    let vote_list = state
+        .get_vote_list(&address_with_space)?
+        .map(|list| list.0)
+        .filter(|list| !list.is_empty());
+
+    if account.is_none()
+        && storage.is_none()
+        && deposit_list.is_none()
+        && vote_list.is_none()
+    {
+        return Ok(None);
     }
 
-    Ok(())
+    Ok(Some(CoreAccountState {
+        address,
+        account: account.as_ref().map(CoreAccount::from),
+        code: code_info
+            .as_ref()
+            .map(|code_info| Bytes(code_info.code.deref().to_vec())),
+        code_owner: code_info.map(|code_info| code_info.owner),
+        storage,
+        deposit_list,
+        vote_list,
+    }))
 }
 
-#[allow(unused)]
+// Reads an eSpace account. An incomplete account, which has no account entry,
+// is only dumped if it has storage entries.
 fn get_account_state(
-    state: &mut StateDbGeneric, account: &Account, config: &StateDumpConfig,
-    space: Space,
-) -> Result<AccountState, Box<dyn std::error::Error>> {
-    let address = account.address();
+    state: &mut StateDbGeneric, address: Address, account: Option<Account>,
+    config: &StateDumpConfig, space: Space,
+) -> Result<Option<AccountState>, Box<dyn std::error::Error>> {
+    let address = address.with_space(space);
+    let incomplete = account.is_none();
+    let account = account.unwrap_or_else(|| Account::new_empty(&address));
 
     let is_contract = account.code_hash != KECCAK_EMPTY;
     // get code
     let code = if is_contract && !config.no_code {
         state
-            .get_code(address, &account.code_hash)?
+            .get_code(&address, &account.code_hash)?
             .map(|code_info| Bytes(code_info.code.deref().to_vec()))
     } else {
         None
     };
 
-    let storage = if is_contract && !config.no_storage {
+    let storage = if (is_contract || incomplete) && !config.no_storage {
         let storage =
             get_contract_storage(state, &address.address, space, config)?;
-        Some(storage)
+        Some(
+            storage
+                .into_iter()
+                .map(|(key, value)| (H256::from_slice(&key), value.value))
+                .collect::<BTreeMap<_, _>>(),
+        )
     } else {
         None
     };
+    if incomplete && storage.as_ref().map_or(true, |s| s.is_empty()) {
+        return Ok(None);
+    }
 
     // conflux state tree don't have storage root, so we use a fixed value
     let root = EOA_STORAGE_ROOT_H256;
 
     let address_hash = keccak(address.address);
 
-    Ok(AccountState {
+    Ok(Some(AccountState {
         balance: account.balance,
         nonce: account.nonce.as_u64(),
         root,
@@ -340,14 +857,14 @@ fn get_account_state(
         storage,
         address: Some(address.address),
         address_hash: Some(address_hash),
-    })
+    }))
 }
 
 fn get_contract_storage(
     state: &mut StateDbGeneric, address: &Address, space: Space,
     config: &StateDumpConfig,
-) -> Result<BTreeMap<H256, U256>, Box<dyn std::error::Error>> {
-    let mut storage: BTreeMap<H256, U256> = Default::default();
+) -> Result<BTreeMap<Vec<u8>, StorageValue>, Box<dyn std::error::Error>> {
+    let mut storage: BTreeMap<Vec<u8>, StorageValue> = Default::default();
     let mut chunk_count = 0;
 
     let mut inner_callback = |(key, value): (Vec<u8>, Box<[u8]>)| {
@@ -362,17 +879,44 @@ fn get_contract_storage(
             storage_key,
         } = storage_key_with_space.key
         {
-            let h256_storage_key = H256::from_slice(storage_key);
+            if let Some(prefix) = &config.storage_key_prefix {
+                if !storage_key.starts_with(prefix) {
+                    return;
+                }
+            }
+
             let storage_value_with_owner: StorageValue =
                 rlp::decode(&value).expect("Failed to decode storage value");
-            storage.insert(h256_storage_key, storage_value_with_owner.value);
+            storage.insert(storage_key.to_vec(), storage_value_with_owner);
 
             if storage.len() == 5_000_000 {
                 chunk_count += 1;
-                let name = format!("{:?}-chunk{}.json", address, chunk_count);
+                let name = storage_chunk_file_name(address, space, chunk_count);
                 let file_path = Path::new(&config.out_put_path).join(&name);
-                let json_content = serde_json::to_string_pretty(&storage)
-                    .expect("Failed to serialize storage");
+                // Core Space chunks keep the collateral owners of the entries
+                let json_content = match space {
+                    Space::Native => {
+                        let chunk: BTreeMap<_, _> = storage
+                            .iter()
+                            .map(|(k, v)| {
+                                let value = CoreStorageValue {
+                                    value: v.value,
+                                    owner: v.owner,
+                                };
+                                (CoreStorageKey(k.clone()), value)
+                            })
+                            .collect();
+                        serde_json::to_string_pretty(&chunk)
+                    }
+                    Space::Ethereum => {
+                        let chunk: BTreeMap<_, _> = storage
+                            .iter()
+                            .map(|(k, v)| (H256::from_slice(k), v.value))
+                            .collect();
+                        serde_json::to_string_pretty(&chunk)
+                    }
+                }
+                .expect("Failed to serialize storage");
                 fs::write(&file_path, json_content)
                     .expect("Failed to write storage file");
                 storage.clear();
@@ -386,6 +930,408 @@ fn get_contract_storage(
     Ok(storage)
 }
 
+/// The name of the file holding the `index`-th chunk (starting from 1) of a
+/// contract storage which is too big to be kept in one file.
+pub(crate) fn storage_chunk_file_name(
+    address: &Address, space: Space, index: usize,
+) -> String {
+    match space {
+        Space::Native => format!("{:?}-core-chunk{}.json", address, index),
+        Space::Ethereum => format!("{:?}-chunk{}.json", address, index),
+    }
+}
+
 fn println(message: &str) {
     println!("[{}] {}", Utc::now().format("%Y-%m-%d %H:%M:%S"), message);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state_import::write_dumped_account;
+    use cfx_parameters::consensus::{
+        ERA_DEFAULT_EPOCH_COUNT, SNAPSHOT_EPOCHS_CAPACITY,
+    };
+    use cfx_storage::{StateIndex, StorageConfiguration, StorageManager};
+    use tempfile::TempDir;
+
+    fn core_account(address: Address) -> CoreAccountState {
+        CoreAccountState {
+            address,
+            account: Some(CoreAccount {
+                balance: 100.into(),
+                nonce: 1.into(),
+                code_hash: KECCAK_EMPTY,
+                staking_balance: 10.into(),
+                collateral_for_storage: U256::zero(),
+                accumulated_interest_return: U256::zero(),
+                admin: Address::zero(),
+                sponsor_info: SponsorInfo::default().into(),
+            }),
+            code: None,
+            code_owner: None,
+            storage: None,
+            deposit_list: None,
+            vote_list: None,
+        }
+    }
+
+    fn evm_account(address: Address, code: Option<Vec<u8>>) -> DumpedAccount {
+        let code = code.map(Bytes);
+        DumpedAccount::Ethereum(AccountState {
+            balance: 5.into(),
+            nonce: 1,
+            root: EOA_STORAGE_ROOT_H256,
+            code_hash: code
+                .as_ref()
+                .map_or(KECCAK_EMPTY, |code| keccak(&code.0)),
+            storage: code.as_ref().map(|_| {
+                BTreeMap::from([(H256::from_low_u64_be(1), 7.into())])
+            }),
+            code,
+            address: Some(address),
+            address_hash: Some(keccak(address)),
+        })
+    }
+
+    // Builds a state with a Core Space account, an eSpace account and an
+    // eSpace contract, and an eSpace storage entry of `orphan` which has no
+    // account.
+    fn test_state(dir: &TempDir, orphan: Address) -> StateDbGeneric {
+        let storage_manager = Arc::new(
+            StorageManager::new(StorageConfiguration::new_default(
+                dir.path().to_str().unwrap(),
+                SNAPSHOT_EPOCHS_CAPACITY,
+                ERA_DEFAULT_EPOCH_COUNT,
+            ))
+            .unwrap(),
+        );
+        let mut state_db =
+            StateDbGeneric::new(storage_manager.get_state_for_genesis_write());
+        for account in [
+            DumpedAccount::Native(core_account(Address::repeat_byte(0x11))),
+            evm_account(Address::repeat_byte(0x22), None),
+            evm_account(Address::repeat_byte(0x33), Some(vec![0x60, 0x00])),
+        ] {
+            write_dumped_account(&mut state_db, &account).unwrap();
+        }
+        state_db
+            .set::<StorageValue>(
+                StorageKey::new_storage_key(
+                    &orphan,
+                    H256::from_low_u64_be(1).as_bytes(),
+                )
+                .with_evm_space(),
+                &StorageValue {
+                    value: 1.into(),
+                    owner: None,
+                },
+                None,
+            )
+            .unwrap();
+
+        let epoch_id = H256::zero();
+        let state_root = state_db.commit(epoch_id, None).unwrap();
+        let state = storage_manager
+            .get_state_no_commit(
+                StateIndex::new_for_readonly(&epoch_id, &state_root),
+                /* try_open = */ true,
+                None,
+            )
+            .unwrap()
+            .unwrap();
+        StateDbGeneric::new(state)
+    }
+
+    fn test_config(dir: &TempDir) -> StateDumpConfig {
+        StateDumpConfig {
+            start_address: Address::zero(),
+            limit: 0,
+            block: None,
+            no_code: false,
+            no_storage: false,
+            out_put_path: dir.path().to_str().unwrap().to_string(),
+            spaces: vec![Space::Native, Space::Ethereum],
+            addresses: None,
+            storage_key_prefix: None,
+            incompletes: false,
+        }
+    }
+
+    fn new_checkpoint(config: &StateDumpConfig) -> StateDumpCheckpoint {
+        StateDumpCheckpoint {
+            root: H256::repeat_byte(1),
+            epoch: 100,
+            last_account: None,
+            accounts: 0,
+            offset: 0,
+            completed: false,
+            options: config.into(),
+        }
+    }
+
+    fn export(
+        state_db: &mut StateDbGeneric, config: &StateDumpConfig,
+    ) -> Vec<(Space, Address)> {
+        let mut accounts = vec![];
+        export_accounts_with_callback(state_db, config, None, &mut |account| {
+            accounts.push((account.space(), account.address()));
+            Ok(())
+        })
+        .unwrap();
+        accounts
+    }
+
+    #[test]
+    fn test_stream_dump_resume() {
+        let state_dir = TempDir::new().unwrap();
+        let mut state_db = test_state(&state_dir, Address::repeat_byte(0x44));
+
+        // Dump in one go
+        let full_dir = TempDir::new().unwrap();
+        let config = test_config(&full_dir);
+        let checkpoint = write_stream(
+            &mut state_db,
+            &config,
+            new_checkpoint(&config),
+            /* resume = */ false,
+            /* checkpoint_interval = */ 1,
+        )
+        .unwrap();
+        assert!(checkpoint.completed);
+        assert_eq!(checkpoint.accounts, 3);
+
+        // Dump stopped by the limit, then resumed
+        let dir = TempDir::new().unwrap();
+        let mut config = test_config(&dir);
+        config.limit = 1;
+        let checkpoint = write_stream(
+            &mut state_db,
+            &config,
+            new_checkpoint(&config),
+            /* resume = */ false,
+            /* checkpoint_interval = */ 10,
+        )
+        .unwrap();
+        assert!(!checkpoint.completed);
+        assert_eq!(checkpoint.accounts, 1);
+        assert_eq!(
+            checkpoint.last_account,
+            Some((Space::Native, Address::repeat_byte(0x11)))
+        );
+        // A line written after the checkpoint is dropped.
+        let stream_path = dir.path().join(STREAM_FILE_NAME);
+        let mut file =
+            OpenOptions::new().append(true).open(&stream_path).unwrap();
+        write!(file, r#"{{"space":"evm","#).unwrap();
+
+        // The limit can change when resuming.
+        let config = test_config(&dir);
+        let checkpoint = load_resumed_checkpoint(&config).unwrap();
+        let checkpoint = write_stream(
+            &mut state_db,
+            &config,
+            checkpoint,
+            /* resume = */ true,
+            /* checkpoint_interval = */ 10,
+        )
+        .unwrap();
+        assert!(checkpoint.completed);
+        assert_eq!(checkpoint.accounts, 3);
+        assert_eq!(
+            load_checkpoint(&dir.path().join(CHECKPOINT_FILE_NAME)).unwrap(),
+            checkpoint
+        );
+        assert_eq!(
+            fs::read_to_string(&stream_path).unwrap(),
+            fs::read_to_string(full_dir.path().join(STREAM_FILE_NAME)).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_resume_checks() {
+        let state_dir = TempDir::new().unwrap();
+        let mut state_db = test_state(&state_dir, Address::repeat_byte(0x44));
+        let dir = TempDir::new().unwrap();
+        let mut config = test_config(&dir);
+        config.limit = 1;
+        write_stream(
+            &mut state_db,
+            &config,
+            new_checkpoint(&config),
+            /* resume = */ false,
+            /* checkpoint_interval = */ 10,
+        )
+        .unwrap();
+
+        // The options which select the accounts and their content are kept.
+        let changes: [fn(&mut StateDumpConfig); 7] = [
+            |config| config.start_address = Address::repeat_byte(0x22),
+            |config| config.spaces = vec![Space::Ethereum],
+            |config| config.addresses = Some(vec![Address::repeat_byte(0x22)]),
+            |config| config.storage_key_prefix = Some(vec![0]),
+            |config| config.no_code = true,
+            |config| config.no_storage = true,
+            |config| config.incompletes = true,
+        ];
+        for change in changes {
+            let mut config = test_config(&dir);
+            change(&mut config);
+            assert!(load_resumed_checkpoint(&config).is_err());
+        }
+        let mut config = test_config(&dir);
+        config.spaces = vec![Space::Ethereum, Space::Native, Space::Ethereum];
+        assert!(load_resumed_checkpoint(&config).is_ok());
+
+        // A missing stream is not recreated.
+        let stream_path = dir.path().join(STREAM_FILE_NAME);
+        fs::remove_file(&stream_path).unwrap();
+        let config = test_config(&dir);
+        let checkpoint = load_resumed_checkpoint(&config).unwrap();
+        assert!(write_stream(
+            &mut state_db,
+            &config,
+            checkpoint.clone(),
+            /* resume = */ true,
+            /* checkpoint_interval = */ 10,
+        )
+        .is_err());
+        assert!(!stream_path.exists());
+
+        // Nor is a truncated one extended.
+        fs::write(&stream_path, "").unwrap();
+        assert!(write_stream(
+            &mut state_db,
+            &config,
+            checkpoint,
+            /* resume = */ true,
+            /* checkpoint_interval = */ 10,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_incompletes() {
+        let state_dir = TempDir::new().unwrap();
+        let orphan = Address::repeat_byte(0x44);
+        let mut state_db = test_state(&state_dir, orphan);
+        let dir = TempDir::new().unwrap();
+
+        let mut config = test_config(&dir);
+        let complete = vec![
+            (Space::Native, Address::repeat_byte(0x11)),
+            (Space::Ethereum, Address::repeat_byte(0x22)),
+            (Space::Ethereum, Address::repeat_byte(0x33)),
+        ];
+        assert_eq!(export(&mut state_db, &config), complete);
+        config.addresses = Some(vec![orphan]);
+        assert!(export(&mut state_db, &config).is_empty());
+
+        config.incompletes = true;
+        let mut dumped = vec![];
+        export_accounts_with_callback(
+            &mut state_db,
+            &config,
+            None,
+            &mut |account| {
+                dumped.push(account);
+                Ok(())
+            },
+        )
+        .unwrap();
+        match &dumped[..] {
+            [DumpedAccount::Ethereum(state)] => {
+                assert_eq!(state.address, Some(orphan));
+                assert!(state.balance.is_zero());
+                assert_eq!(state.code_hash, KECCAK_EMPTY);
+                assert_eq!(
+                    state.storage,
+                    Some(BTreeMap::from([(
+                        H256::from_low_u64_be(1),
+                        1.into()
+                    )]))
+                );
+            }
+            dumped => panic!("unexpected accounts {:?}", dumped),
+        }
+
+        config.addresses = None;
+        let mut expected = complete;
+        expected.push((Space::Ethereum, orphan));
+        assert_eq!(export(&mut state_db, &config), expected);
+    }
+
+    #[test]
+    fn test_core_account_serde() {
+        let mut state = core_account(Address::repeat_byte(0x11));
+        state.storage = Some(BTreeMap::from([
+            (
+                H256::from_low_u64_be(1).into(),
+                CoreStorageValue {
+                    value: 7.into(),
+                    owner: Some(Address::repeat_byte(0x11)),
+                },
+            ),
+            // Keys of the builtin addresses are not always 32 bytes long.
+            (
+                CoreStorageKey(b"interest_rate".to_vec()),
+                CoreStorageValue {
+                    value: 8.into(),
+                    owner: None,
+                },
+            ),
+        ]));
+        let account = DumpedAccount::Native(state);
+        let json = serde_json::to_value(&account).unwrap();
+        assert_eq!(json["space"], "native");
+        assert_eq!(json["balance"], "0x64");
+        assert_eq!(json["stakingBalance"], "0xa");
+        assert_eq!(json["sponsorInfo"]["sponsorBalanceForGas"], "0x0");
+        assert!(json["sponsorInfo"].get("unusedStoragePoints").is_none());
+        assert!(json.get("depositList").is_none());
+        let storage = &json["storage"];
+        assert_eq!(
+            storage[format!("{:?}", H256::from_low_u64_be(1))]["owner"],
+            format!("{:?}", Address::repeat_byte(0x11))
+        );
+        assert_eq!(storage["0x696e7465726573745f72617465"]["value"], "0x8");
+        assert!(storage["0x696e7465726573745f72617465"]
+            .get("owner")
+            .is_none());
+        assert_eq!(
+            serde_json::from_value::<DumpedAccount>(json).unwrap(),
+            account
+        );
+
+        // A builtin address may only have storage entries.
+        let mut state = core_account(Address::zero());
+        state.account = None;
+        state.storage = Some(BTreeMap::from([(
+            CoreStorageKey(vec![1, 2]),
+            CoreStorageValue {
+                value: 1.into(),
+                owner: None,
+            },
+        )]));
+        let account = DumpedAccount::Native(state);
+        let json = serde_json::to_value(&account).unwrap();
+        assert!(json.get("balance").is_none());
+        assert_eq!(
+            serde_json::from_value::<DumpedAccount>(json).unwrap(),
+            account
+        );
+
+        let sponsor_info = SponsorInfo {
+            sponsor_for_gas: Address::repeat_byte(1),
+            sponsor_balance_for_gas: 3.into(),
+            storage_points: Some(StoragePoints {
+                unused: 4.into(),
+                used: 5.into(),
+            }),
+            ..Default::default()
+        };
+        let core_sponsor_info = CoreSponsorInfo::from(sponsor_info.clone());
+        assert_eq!(core_sponsor_info.unused_storage_points, Some(4.into()));
+        assert_eq!(SponsorInfo::from(core_sponsor_info), sponsor_info);
+    }
+}
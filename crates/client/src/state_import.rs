@@ -0,0 +1,1086 @@
+use crate::{
+    common::dev_genesis_accounts,
+    state_dump::{
+        export_accounts_with_callback, load_checkpoint,
+        storage_chunk_file_name, CoreAccount, CoreStorageKey, CoreStorageValue,
+        DumpedAccount, StateDumpConfig, CHECKPOINT_FILE_NAME, STREAM_FILE_NAME,
+    },
+};
+use cfx_config::Configuration;
+use cfx_executor::machine::{Machine, VmFactory};
+use cfx_parameters::genesis::GENESIS_ACCOUNT_ADDRESS;
+use cfx_rpc_eth_types::{AccountState, StateDump, EOA_STORAGE_ROOT_H256};
+use cfx_rpc_primitives::Bytes;
+use cfx_statedb::{StateDbExt, StateDbGeneric};
+use cfx_storage::{
+    state_manager::StateManagerTrait, StateIndex, StorageManager,
+};
+use cfx_types::{
+    Address, AddressSpaceUtil, AddressWithSpace, Space, H256, U256,
+};
+use cfxcore::{
+    genesis_block::{genesis_block_with_state, GenesisSupply},
+    NodeType,
+};
+use chrono::Utc;
+use keccak_hash::{keccak, KECCAK_EMPTY};
+use primitives::{
+    is_default::IsDefault, storage::STORAGE_LAYOUT_REGULAR_V0, Account,
+    CodeInfo, DepositList, StorageKey, StorageKeyWithSpace, StorageValue,
+    VoteStakeList,
+};
+use secret_store::SecretStore;
+use std::{
+    collections::{BTreeMap, HashMap},
+    env, fs,
+    fs::File,
+    io::{BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+    process,
+    str::FromStr,
+    sync::Arc,
+};
+
+const META_FILE_NAME: &str = "meta.json";
+const SINGLE_FILE_NAME: &str = "state.json";
+
+pub struct StateImportSummary {
+    pub accounts: u64,
+    pub storage_entries: u64,
+    /// The tokens held by the accounts of the dump
+    pub supply: GenesisSupply,
+    /// The state root recorded in the dump, if any
+    pub recorded_root: Option<H256>,
+}
+
+pub struct StateImportVerification {
+    pub summary: StateImportSummary,
+    /// The state root of the imported state
+    pub state_root: H256,
+    /// The accounts which are missing or different after the round trip
+    pub mismatches: Vec<(Space, Address)>,
+}
+
+/// Writes the accounts of a state dump into the state, and returns the
+/// number of written accounts and storage entries. The dump can be the
+/// `state.json` file, the folder of a multi file dump, or the `state.jsonl`
+/// file (or its folder) of an iterative dump.
+pub fn import_state(
+    state_db: &mut StateDbGeneric, path: &str,
+) -> Result<StateImportSummary, String> {
+    let mut accounts = 0;
+    let mut storage_entries = 0;
+    let mut supply = GenesisSupply::default();
+    let recorded_root = for_each_dumped_account(path, &mut |account| {
+        storage_entries += write_dumped_account(state_db, &account)?;
+        let tokens = held_tokens(&account);
+        supply.total_issued += tokens;
+        if account.space() == Space::Ethereum {
+            supply.total_evm_tokens += tokens;
+        }
+        accounts += 1;
+        if accounts % 10000 == 0 {
+            println(&format!("Imported {} accounts", accounts));
+        }
+        Ok(())
+    })?;
+
+    Ok(StateImportSummary {
+        accounts,
+        storage_entries,
+        supply,
+        recorded_root,
+    })
+}
+
+/// Writes the dumped state as the genesis state of a fresh data directory,
+/// the same way as a node started with `genesis_state`. Returns the genesis
+/// block hash and the state root.
+pub fn import_genesis_state(
+    conf: &Configuration, path: &str,
+) -> Result<(H256, H256, StateImportSummary), String> {
+    if !conf.is_test_or_dev_mode() {
+        return Err(
+            "genesis_state is only supported in dev or test mode".into()
+        );
+    }
+    let storage_config = conf.storage_config(&NodeType::Archive);
+    let block_db_dir = match &conf.raw_conf.block_db_dir {
+        Some(dir) => PathBuf::from(dir),
+        None => {
+            Path::new(&conf.raw_conf.conflux_data_dir).join("blockchain_db")
+        }
+    };
+    for dir in [&storage_config.path_storage_dir, &block_db_dir] {
+        if !is_empty_dir(dir)? {
+            return Err(format!(
+                "The data directory is not fresh, {} is not empty",
+                dir.display()
+            ));
+        }
+    }
+
+    let storage_manager = Arc::new(
+        StorageManager::new(storage_config)
+            .map_err(|e| format!("Failed to initialize storage: {}", e))?,
+    );
+    let genesis_accounts = dev_genesis_accounts(conf, &SecretStore::new())?;
+    let machine = Arc::new(Machine::new_with_builtin(
+        conf.common_params(),
+        VmFactory::new(1024 * 32),
+    ));
+    let mut summary = None;
+    let genesis = genesis_block_with_state(
+        &storage_manager,
+        genesis_accounts,
+        GENESIS_ACCOUNT_ADDRESS,
+        U256::zero(),
+        machine,
+        |state_db| {
+            let imported = import_state(state_db, path)?;
+            let supply = imported.supply;
+            summary = Some(imported);
+            Ok(supply)
+        },
+    )?;
+    storage_manager.notify_genesis_hash(genesis.hash());
+
+    Ok((
+        genesis.hash(),
+        *genesis.block_header.deferred_state_root(),
+        summary.expect("set by the import"),
+    ))
+}
+
+/// Imports the dump into a temporary data directory, exports the imported
+/// state again and compares it with the dump.
+pub fn verify_state_import(
+    conf: &mut Configuration, path: &str,
+) -> Result<StateImportVerification, String> {
+    let tmp_dir =
+        env::temp_dir().join(format!("conflux-state-import-{}", process::id()));
+    conf.raw_conf.conflux_data_dir = tmp_dir
+        .to_str()
+        .ok_or("Invalid temporary directory")?
+        .to_string();
+
+    let result = verify_state_import_in(conf, path, &tmp_dir);
+    if let Err(e) = fs::remove_dir_all(&tmp_dir) {
+        println(&format!("Failed to remove {}: {}", tmp_dir.display(), e));
+    }
+    result
+}
+
+fn verify_state_import_in(
+    conf: &Configuration, path: &str, tmp_dir: &Path,
+) -> Result<StateImportVerification, String> {
+    let storage_manager = Arc::new(
+        StorageManager::new(conf.storage_config(&NodeType::Archive))
+            .map_err(|e| format!("Failed to initialize storage: {}", e))?,
+    );
+    verify_imported_state(&storage_manager, path, &tmp_dir.join("export"))
+}
+
+fn verify_imported_state(
+    storage_manager: &Arc<StorageManager>, path: &str, export_dir: &Path,
+) -> Result<StateImportVerification, String> {
+    println("Importing state...");
+    let mut state_db =
+        StateDbGeneric::new(storage_manager.get_state_for_genesis_write());
+    let summary = import_state(&mut state_db, path)?;
+    let epoch_id = H256::zero();
+    let state_root = state_db
+        .commit(epoch_id, /* debug_record = */ None)
+        .map_err(|e| format!("Failed to commit state: {}", e))?;
+
+    let state = storage_manager
+        .get_state_no_commit(
+            StateIndex::new_for_readonly(&epoch_id, &state_root),
+            /* try_open = */ true,
+            None,
+        )
+        .map_err(|e| e.to_string())?
+        .ok_or("Failed to open the imported state")?;
+    let mut state_db = StateDbGeneric::new(state);
+
+    fs::create_dir_all(export_dir)
+        .map_err(|e| format!("Failed to create export directory: {}", e))?;
+    let config = StateDumpConfig {
+        start_address: Address::zero(),
+        limit: 0,
+        block: None,
+        no_code: false,
+        no_storage: false,
+        out_put_path: export_dir
+            .to_str()
+            .ok_or("Invalid export directory")?
+            .to_string(),
+        spaces: vec![Space::Native, Space::Ethereum],
+        addresses: None,
+        storage_key_prefix: None,
+        incompletes: false,
+    };
+    let mut exported = BTreeMap::new();
+    export_accounts_with_callback(
+        &mut state_db,
+        &config,
+        None,
+        &mut |mut account| {
+            merge_storage_chunks(export_dir, &mut account)?;
+            if let Some(account) = normalize(account) {
+                exported.insert((account.space(), account.address()), account);
+            }
+            Ok(())
+        },
+    )?;
+
+    println("Comparing the imported state with the dump...");
+    let mut mismatches = vec![];
+    for_each_dumped_account(path, &mut |account| {
+        let account = match normalize(account) {
+            Some(account) => account,
+            // Nothing is written for an empty account
+            None => return Ok(()),
+        };
+        let key = (account.space(), account.address());
+        if exported.remove(&key).as_ref() != Some(&account) {
+            mismatches.push(key);
+        }
+        Ok(())
+    })?;
+    mismatches.extend(exported.into_keys());
+    mismatches.sort();
+
+    Ok(StateImportVerification {
+        summary,
+        state_root: state_root.aux_info.state_root_hash,
+        mismatches,
+    })
+}
+
+/// Visits the accounts of a state dump, and returns the state root recorded
+/// in the dump.
+pub fn for_each_dumped_account(
+    path: &str, callback: &mut dyn FnMut(DumpedAccount) -> Result<(), String>,
+) -> Result<Option<H256>, String> {
+    let path = Path::new(path);
+    if path.is_dir() {
+        if path.join(STREAM_FILE_NAME).exists() {
+            read_stream_file(&path.join(STREAM_FILE_NAME), callback)
+        } else if path.join(META_FILE_NAME).exists() {
+            read_multi_file(path, callback)
+        } else if path.join(SINGLE_FILE_NAME).exists() {
+            read_single_file(&path.join(SINGLE_FILE_NAME), callback)
+        } else {
+            Err(format!("No state dump found in {}", path.display()))
+        }
+    } else if path.extension().map_or(false, |ext| ext == "jsonl") {
+        read_stream_file(path, callback)
+    } else {
+        read_single_file(path, callback)
+    }
+}
+
+/// Writes a dumped account into the state, and returns the number of written
+/// storage entries.
+pub fn write_dumped_account(
+    state_db: &mut StateDbGeneric, account: &DumpedAccount,
+) -> Result<u64, String> {
+    match account {
+        DumpedAccount::Native(state) => {
+            let address = state.address.with_native_space();
+            let code_hash = state
+                .account
+                .as_ref()
+                .map_or(KECCAK_EMPTY, |account| account.code_hash);
+            if let Some(account) = &state.account {
+                set(
+                    state_db,
+                    StorageKey::new_account_key(&address.address)
+                        .with_native_space(),
+                    &to_core_account(&address, account),
+                )?;
+            }
+
+            let storage = state
+                .storage
+                .iter()
+                .flatten()
+                .map(|(key, value)| {
+                    let value = StorageValue {
+                        value: value.value,
+                        owner: value.owner,
+                    };
+                    (key.0.clone(), value)
+                })
+                .collect();
+            let storage_entries = write_code_and_storage(
+                state_db,
+                &address,
+                code_hash,
+                state.code.as_ref(),
+                state.code_owner.unwrap_or_default(),
+                storage,
+            )?;
+
+            if let Some(deposit_list) = &state.deposit_list {
+                set(
+                    state_db,
+                    StorageKey::new_deposit_list_key(&address.address)
+                        .with_native_space(),
+                    &DepositList(deposit_list.clone()),
+                )?;
+            }
+            if let Some(vote_list) = &state.vote_list {
+                set(
+                    state_db,
+                    StorageKey::new_vote_list_key(&address.address)
+                        .with_native_space(),
+                    &VoteStakeList(vote_list.clone()),
+                )?;
+            }
+            Ok(storage_entries)
+        }
+        DumpedAccount::Ethereum(state) => {
+            let address = account.address().with_evm_space();
+            let mut account = Account::new_empty(&address);
+            account.balance = state.balance;
+            account.nonce = state.nonce.into();
+            account.code_hash = state.code_hash;
+            set(
+                state_db,
+                StorageKey::new_account_key(&address.address).with_evm_space(),
+                &account,
+            )?;
+
+            let storage = state
+                .storage
+                .iter()
+                .flatten()
+                .map(|(key, value)| {
+                    let value = StorageValue {
+                        value: *value,
+                        owner: None,
+                    };
+                    (key.as_bytes().to_vec(), value)
+                })
+                .collect();
+            write_code_and_storage(
+                state_db,
+                &address,
+                state.code_hash,
+                state.code.as_ref(),
+                Address::zero(),
+                storage,
+            )
+        }
+    }
+}
+
+fn write_code_and_storage(
+    state_db: &mut StateDbGeneric, address: &AddressWithSpace, code_hash: H256,
+    code: Option<&Bytes>, code_owner: Address,
+    storage: BTreeMap<Vec<u8>, StorageValue>,
+) -> Result<u64, String> {
+    // The storage layout must be present before any code or storage entry is
+    // written, as it is for every contract created on chain.
+    if code_hash != KECCAK_EMPTY || !storage.is_empty() {
+        state_db
+            .set_storage_layout(address, STORAGE_LAYOUT_REGULAR_V0, None)
+            .map_err(|e| e.to_string())?;
+    }
+
+    if let Some(code) = code {
+        if keccak(&code.0) != code_hash {
+            return Err(format!(
+                "Code hash mismatch for account {:?}",
+                address.address
+            ));
+        }
+        let code_info = CodeInfo {
+            code: Arc::new(code.0.clone()),
+            owner: code_owner,
+        };
+        set(
+            state_db,
+            StorageKey::new_code_key(&address.address, &code_hash)
+                .with_space(address.space),
+            &code_info,
+        )?;
+    }
+
+    let mut storage_entries = 0;
+    for (key, value) in storage {
+        // Zero values are never kept in the state
+        if value.is_default() {
+            continue;
+        }
+        set(
+            state_db,
+            StorageKey::new_storage_key(&address.address, &key)
+                .with_space(address.space),
+            &value,
+        )?;
+        storage_entries += 1;
+    }
+    Ok(storage_entries)
+}
+
+fn set<T>(
+    state_db: &mut StateDbGeneric, key: StorageKeyWithSpace, value: &T,
+) -> Result<(), String>
+where T: rlp::Encodable + IsDefault {
+    state_db
+        .set::<T>(key, value, /* debug_record = */ None)
+        .map_err(|e| e.to_string())
+}
+
+// The tokens held by a dumped account, including those staked, locked as
+// collateral or given by the sponsors.
+fn held_tokens(account: &DumpedAccount) -> U256 {
+    match account {
+        DumpedAccount::Native(state) => {
+            state.account.as_ref().map_or(U256::zero(), |account| {
+                account.balance
+                    + account.staking_balance
+                    + account.collateral_for_storage
+                    + account.sponsor_info.sponsor_balance_for_gas
+                    + account.sponsor_info.sponsor_balance_for_collateral
+            })
+        }
+        DumpedAccount::Ethereum(state) => state.balance,
+    }
+}
+
+fn to_core_account(
+    address: &AddressWithSpace, account: &CoreAccount,
+) -> Account {
+    let mut core_account = Account::new_empty(address);
+    core_account.balance = account.balance;
+    core_account.nonce = account.nonce;
+    core_account.code_hash = account.code_hash;
+    core_account.staking_balance = account.staking_balance;
+    core_account.collateral_for_storage = account.collateral_for_storage;
+    core_account.accumulated_interest_return =
+        account.accumulated_interest_return;
+    core_account.admin = account.admin;
+    core_account.sponsor_info = account.sponsor_info.clone().into();
+    core_account
+}
+
+// Brings a dumped account to the form in which it is exported from the state,
+// or None if nothing of the account is kept in the state.
+fn normalize(account: DumpedAccount) -> Option<DumpedAccount> {
+    match account {
+        DumpedAccount::Native(mut state) => {
+            let address = state.address.with_native_space();
+            state.account = state.account.filter(|account| {
+                !to_core_account(&address, account).is_default()
+            });
+            state.storage = state
+                .storage
+                .map(|storage| {
+                    storage
+                        .into_iter()
+                        .filter(|(_, value)| {
+                            !StorageValue {
+                                value: value.value,
+                                owner: value.owner,
+                            }
+                            .is_default()
+                        })
+                        .collect::<BTreeMap<_, _>>()
+                })
+                .filter(|storage| !storage.is_empty());
+            state.deposit_list =
+                state.deposit_list.filter(|list| !list.is_empty());
+            state.vote_list = state.vote_list.filter(|list| !list.is_empty());
+            if state.account.is_none()
+                && state.storage.is_none()
+                && state.deposit_list.is_none()
+                && state.vote_list.is_none()
+            {
+                return None;
+            }
+            Some(DumpedAccount::Native(state))
+        }
+        DumpedAccount::Ethereum(mut state) => {
+            let address = state.address?;
+            state.storage = state
+                .storage
+                .map(|storage| {
+                    storage
+                        .into_iter()
+                        .filter(|(_, value)| !value.is_zero())
+                        .collect::<BTreeMap<_, _>>()
+                })
+                .filter(|storage| !storage.is_empty());
+            state.root = EOA_STORAGE_ROOT_H256;
+            state.address_hash = Some(keccak(address));
+            if state.balance.is_zero()
+                && state.nonce == 0
+                && state.code_hash == KECCAK_EMPTY
+            {
+                return None;
+            }
+            Some(DumpedAccount::Ethereum(state))
+        }
+    }
+}
+
+// Reads an iterative dump. If the checkpoint is found next to the stream, the
+// dump must be completed, and only the part covered by the checkpoint is read.
+fn read_stream_file(
+    path: &Path, callback: &mut dyn FnMut(DumpedAccount) -> Result<(), String>,
+) -> Result<Option<H256>, String> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let checkpoint_path = dir.join(CHECKPOINT_FILE_NAME);
+    let file = File::open(path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let (reader, recorded_root): (Box<dyn Read>, _) =
+        if checkpoint_path.exists() {
+            let checkpoint = load_checkpoint(&checkpoint_path)?;
+            if !checkpoint.completed {
+                return Err(format!(
+                "The dump in {} is not completed, resume it before importing",
+                dir.display()
+            ));
+            }
+            (
+                Box::new(file.take(checkpoint.offset)),
+                Some(checkpoint.root),
+            )
+        } else {
+            (Box::new(file), None)
+        };
+
+    for (index, line) in BufReader::new(reader).lines().enumerate() {
+        let line = line
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let mut account: DumpedAccount =
+            serde_json::from_str(&line).map_err(|e| {
+                format!(
+                    "Failed to parse line {} of {}: {}",
+                    index + 1,
+                    path.display(),
+                    e
+                )
+            })?;
+        merge_storage_chunks(dir, &mut account)?;
+        callback(account)?;
+    }
+
+    Ok(recorded_root)
+}
+
+// Reads a multi file dump, where each account is written to a file named by
+// its address, and the Core Space accounts are in the `core` sub folder.
+fn read_multi_file(
+    dir: &Path, callback: &mut dyn FnMut(DumpedAccount) -> Result<(), String>,
+) -> Result<Option<H256>, String> {
+    let meta: HashMap<String, H256> =
+        serde_json::from_str(&read_file(&dir.join(META_FILE_NAME))?)
+            .map_err(|e| format!("Failed to parse meta file: {}", e))?;
+
+    let core_dir = dir.join("core");
+    if core_dir.is_dir() {
+        for (address, path) in list_account_files(&core_dir)? {
+            let mut account = DumpedAccount::Native(
+                serde_json::from_str(&read_file(&path)?).map_err(|e| {
+                    format!("Failed to parse {}: {}", path.display(), e)
+                })?,
+            );
+            if account.address() != address {
+                return Err(format!("Address mismatch in {}", path.display()));
+            }
+            merge_storage_chunks(dir, &mut account)?;
+            callback(account)?;
+        }
+    }
+
+    for (address, path) in list_account_files(dir)? {
+        let mut state: AccountState = serde_json::from_str(&read_file(&path)?)
+            .map_err(|e| {
+                format!("Failed to parse {}: {}", path.display(), e)
+            })?;
+        state.address.get_or_insert(address);
+        let mut account = DumpedAccount::Ethereum(state);
+        merge_storage_chunks(dir, &mut account)?;
+        callback(account)?;
+    }
+
+    Ok(meta.get("root").cloned())
+}
+
+fn read_single_file(
+    path: &Path, callback: &mut dyn FnMut(DumpedAccount) -> Result<(), String>,
+) -> Result<Option<H256>, String> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let dump: StateDump = serde_json::from_str(&read_file(path)?)
+        .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+    for (address, mut state) in dump.accounts {
+        state.address.get_or_insert(address);
+        let mut account = DumpedAccount::Ethereum(state);
+        merge_storage_chunks(dir, &mut account)?;
+        callback(account)?;
+    }
+    Ok(Some(dump.root))
+}
+
+// Lists the `<address>.json` files of the folder, sorted by address.
+fn list_account_files(dir: &Path) -> Result<Vec<(Address, PathBuf)>, String> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+    let mut files = vec![];
+    for entry in entries {
+        let path = entry
+            .map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?
+            .path();
+        if path.extension().map_or(true, |ext| ext != "json") {
+            continue;
+        }
+        let address =
+            path.file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| {
+                    Address::from_str(stem.trim_start_matches("0x")).ok()
+                });
+        // Skip the meta and storage chunk files
+        if let Some(address) = address {
+            files.push((address, path));
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+// Adds the storage entries written to the chunk files of the account.
+fn merge_storage_chunks(
+    dir: &Path, account: &mut DumpedAccount,
+) -> Result<(), String> {
+    let (address, space) = (account.address(), account.space());
+    for index in 1.. {
+        let path = dir.join(storage_chunk_file_name(&address, space, index));
+        if !path.exists() {
+            break;
+        }
+        let content = read_file(&path)?;
+        let parse_error = |e: serde_json::Error| {
+            format!("Failed to parse {}: {}", path.display(), e)
+        };
+        match account {
+            DumpedAccount::Native(state) => {
+                let chunk: BTreeMap<CoreStorageKey, CoreStorageValue> =
+                    serde_json::from_str(&content).map_err(parse_error)?;
+                state
+                    .storage
+                    .get_or_insert_with(Default::default)
+                    .extend(chunk);
+            }
+            DumpedAccount::Ethereum(state) => {
+                let chunk: BTreeMap<H256, U256> =
+                    serde_json::from_str(&content).map_err(parse_error)?;
+                state
+                    .storage
+                    .get_or_insert_with(Default::default)
+                    .extend(chunk);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn read_file(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+}
+
+fn is_empty_dir(dir: &Path) -> Result<bool, String> {
+    if !dir.exists() {
+        return Ok(true);
+    }
+    let mut entries = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+    Ok(entries.next().is_none())
+}
+
+fn println(message: &str) {
+    println!("[{}] {}", Utc::now().format("%Y-%m-%d %H:%M:%S"), message);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state_dump::{
+        CoreAccountState, CoreSponsorInfo, StateDumpCheckpoint,
+        StateDumpOptions,
+    };
+    use cfx_executor::state::State;
+    use cfx_internal_common::StateRootWithAuxInfo;
+    use cfx_parameters::consensus::{
+        ERA_DEFAULT_EPOCH_COUNT, SNAPSHOT_EPOCHS_CAPACITY,
+    };
+    use cfx_statedb::global_params::{InterestRate, TotalIssued};
+    use cfx_storage::StorageConfiguration;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    fn core_account(address: Address, balance: u64) -> DumpedAccount {
+        DumpedAccount::Native(CoreAccountState {
+            address,
+            account: Some(CoreAccount {
+                balance: balance.into(),
+                nonce: 1.into(),
+                code_hash: KECCAK_EMPTY,
+                staking_balance: 10.into(),
+                collateral_for_storage: U256::zero(),
+                accumulated_interest_return: U256::zero(),
+                admin: Address::zero(),
+                sponsor_info: CoreSponsorInfo {
+                    sponsor_for_gas: Address::zero(),
+                    sponsor_for_collateral: Address::zero(),
+                    sponsor_gas_bound: U256::zero(),
+                    sponsor_balance_for_gas: U256::zero(),
+                    sponsor_balance_for_collateral: U256::zero(),
+                    unused_storage_points: None,
+                    used_storage_points: None,
+                },
+            }),
+            code: None,
+            code_owner: None,
+            storage: None,
+            deposit_list: None,
+            vote_list: None,
+        })
+    }
+
+    fn evm_contract(address: Address, balance: u64) -> DumpedAccount {
+        let code = Bytes::new(vec![0x60, 0x00, 0x60, 0x00, 0xf3]);
+        DumpedAccount::Ethereum(AccountState {
+            balance: balance.into(),
+            nonce: 1,
+            root: EOA_STORAGE_ROOT_H256,
+            code_hash: keccak(&code.0),
+            code: Some(code),
+            storage: Some(BTreeMap::from([
+                (H256::from_low_u64_be(1), U256::from(7)),
+                // Zero values are not written
+                (H256::from_low_u64_be(2), U256::zero()),
+            ])),
+            address: Some(address),
+            address_hash: None,
+        })
+    }
+
+    // Writes the accounts as an iterative dump, and returns the length of the
+    // stream.
+    fn write_stream(dir: &Path, accounts: &[DumpedAccount]) -> u64 {
+        let mut file = File::create(dir.join(STREAM_FILE_NAME)).unwrap();
+        for account in accounts {
+            writeln!(file, "{}", serde_json::to_string(account).unwrap())
+                .unwrap();
+        }
+        file.metadata().unwrap().len()
+    }
+
+    fn write_checkpoint(dir: &Path, offset: u64, completed: bool) {
+        let checkpoint = StateDumpCheckpoint {
+            root: H256::repeat_byte(1),
+            epoch: 100,
+            last_account: None,
+            accounts: 2,
+            offset,
+            completed,
+            options: StateDumpOptions {
+                start_address: Address::zero(),
+                spaces: vec![Space::Native, Space::Ethereum],
+                addresses: None,
+                storage_key_prefix: None,
+                no_code: false,
+                no_storage: false,
+                incompletes: false,
+            },
+        };
+        fs::write(
+            dir.join(CHECKPOINT_FILE_NAME),
+            serde_json::to_string(&checkpoint).unwrap(),
+        )
+        .unwrap();
+    }
+
+    fn read_dump(
+        path: &Path,
+    ) -> Result<(Vec<DumpedAccount>, Option<H256>), String> {
+        let mut accounts = vec![];
+        let root =
+            for_each_dumped_account(path.to_str().unwrap(), &mut |account| {
+                accounts.push(account);
+                Ok(())
+            })?;
+        Ok((accounts, root))
+    }
+
+    fn storage_manager(dir: &TempDir) -> Arc<StorageManager> {
+        Arc::new(
+            StorageManager::new(StorageConfiguration::new_default(
+                dir.path().to_str().unwrap(),
+                SNAPSHOT_EPOCHS_CAPACITY,
+                ERA_DEFAULT_EPOCH_COUNT,
+            ))
+            .unwrap(),
+        )
+    }
+
+    fn genesis_state(
+        storage_manager: &Arc<StorageManager>, genesis_hash: &H256,
+    ) -> State {
+        let state = storage_manager
+            .get_state_no_commit(
+                StateIndex::new_for_readonly(
+                    genesis_hash,
+                    &StateRootWithAuxInfo::genesis(genesis_hash),
+                ),
+                /* try_open = */ false,
+                None,
+            )
+            .unwrap()
+            .unwrap();
+        State::new(StateDbGeneric::new(state)).unwrap()
+    }
+
+    #[test]
+    fn test_read_stream_dump() {
+        let dir = TempDir::new().unwrap();
+        let core = Address::repeat_byte(0x11);
+        let contract = Address::repeat_byte(0x22);
+        let offset = write_stream(
+            dir.path(),
+            &[core_account(core, 100), evm_contract(contract, 5)],
+        );
+        fs::write(
+            dir.path()
+                .join(storage_chunk_file_name(&contract, Space::Ethereum, 1)),
+            r#"{"0x0000000000000000000000000000000000000000000000000000000000000003":"0x9"}"#,
+        )
+        .unwrap();
+        // A line written after the checkpoint is not read.
+        let mut file = fs::OpenOptions::new()
+            .append(true)
+            .open(dir.path().join(STREAM_FILE_NAME))
+            .unwrap();
+        write!(file, r#"{{"space":"evm","#).unwrap();
+
+        write_checkpoint(dir.path(), offset, /* completed = */ true);
+        let (accounts, root) = read_dump(dir.path()).unwrap();
+        assert_eq!(root, Some(H256::repeat_byte(1)));
+        assert_eq!(accounts.len(), 2);
+        assert_eq!(accounts[0], core_account(core, 100));
+        match &accounts[1] {
+            DumpedAccount::Ethereum(state) => {
+                let storage = state.storage.as_ref().unwrap();
+                assert_eq!(storage.len(), 3);
+                assert_eq!(storage[&H256::from_low_u64_be(3)], 9.into());
+            }
+            account => panic!("unexpected account {:?}", account),
+        }
+        // The stream file can also be given directly.
+        let (accounts, _) =
+            read_dump(&dir.path().join(STREAM_FILE_NAME)).unwrap();
+        assert_eq!(accounts.len(), 2);
+
+        write_checkpoint(dir.path(), offset, /* completed = */ false);
+        assert!(read_dump(dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_read_file_dumps() {
+        let core = Address::repeat_byte(0x11);
+        let contract = Address::repeat_byte(0x22);
+        let root = H256::repeat_byte(2);
+        let DumpedAccount::Ethereum(mut contract_state) =
+            evm_contract(contract, 5)
+        else {
+            unreachable!()
+        };
+        contract_state.address = None;
+
+        // A multi file dump keeps each account in its own file.
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("core")).unwrap();
+        fs::write(
+            dir.path().join(META_FILE_NAME),
+            serde_json::to_string(&HashMap::from([("root", root)])).unwrap(),
+        )
+        .unwrap();
+        let DumpedAccount::Native(core_state) = core_account(core, 100) else {
+            unreachable!()
+        };
+        fs::write(
+            dir.path().join("core").join(format!("{:?}.json", core)),
+            serde_json::to_string(&core_state).unwrap(),
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join(format!("{:?}.json", contract)),
+            serde_json::to_string(&contract_state).unwrap(),
+        )
+        .unwrap();
+        let (accounts, recorded_root) = read_dump(dir.path()).unwrap();
+        assert_eq!(recorded_root, Some(root));
+        assert_eq!(
+            accounts,
+            vec![core_account(core, 100), evm_contract(contract, 5)]
+        );
+
+        // A single file dump only has eSpace accounts.
+        let dir = TempDir::new().unwrap();
+        let dump = StateDump {
+            root,
+            accounts: BTreeMap::from([(contract, contract_state)]),
+            next: None,
+        };
+        fs::write(
+            dir.path().join(SINGLE_FILE_NAME),
+            serde_json::to_string(&dump).unwrap(),
+        )
+        .unwrap();
+        let (accounts, recorded_root) = read_dump(dir.path()).unwrap();
+        assert_eq!(recorded_root, Some(root));
+        assert_eq!(accounts, vec![evm_contract(contract, 5)]);
+
+        assert!(read_dump(&dir.path().join("missing")).is_err());
+    }
+
+    #[test]
+    fn test_import_round_trip() {
+        let dump_dir = TempDir::new().unwrap();
+        let empty = DumpedAccount::Ethereum(AccountState {
+            balance: U256::zero(),
+            nonce: 0,
+            root: EOA_STORAGE_ROOT_H256,
+            code_hash: KECCAK_EMPTY,
+            code: None,
+            storage: None,
+            address: Some(Address::repeat_byte(0x33)),
+            address_hash: None,
+        });
+        write_stream(
+            dump_dir.path(),
+            &[
+                core_account(Address::repeat_byte(0x11), 100),
+                evm_contract(Address::repeat_byte(0x22), 5),
+                empty,
+            ],
+        );
+        let path = dump_dir.path().to_str().unwrap();
+
+        let data_dir = TempDir::new().unwrap();
+        let verification = verify_imported_state(
+            &storage_manager(&data_dir),
+            path,
+            &data_dir.path().join("export"),
+        )
+        .unwrap();
+        assert!(verification.mismatches.is_empty());
+        let summary = &verification.summary;
+        assert_eq!(summary.accounts, 3);
+        assert_eq!(summary.storage_entries, 1);
+        assert_eq!(summary.recorded_root, None);
+        assert_eq!(
+            summary.supply,
+            GenesisSupply {
+                total_issued: 115.into(),
+                total_evm_tokens: 5.into(),
+            }
+        );
+
+        // The code must match its hash.
+        let DumpedAccount::Ethereum(mut state) =
+            evm_contract(Address::repeat_byte(0x22), 5)
+        else {
+            unreachable!()
+        };
+        state.code_hash = H256::repeat_byte(3);
+        write_stream(dump_dir.path(), &[DumpedAccount::Ethereum(state)]);
+        let data_dir = TempDir::new().unwrap();
+        assert!(verify_imported_state(
+            &storage_manager(&data_dir),
+            path,
+            &data_dir.path().join("export"),
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_genesis_block_with_state() {
+        let dump_dir = TempDir::new().unwrap();
+        let core = Address::repeat_byte(0x11);
+        let contract = Address::repeat_byte(0x22);
+        write_stream(
+            dump_dir.path(),
+            &[core_account(core, 100), evm_contract(contract, 5)],
+        );
+        let path = dump_dir.path().to_str().unwrap();
+        let machine = Arc::new(Machine::new_with_builtin(
+            Default::default(),
+            VmFactory::new(1024 * 32),
+        ));
+        let new_account = Address::repeat_byte(0x44).with_evm_space();
+        // The account in the dump is kept.
+        let genesis_accounts = HashMap::from([
+            (core.with_native_space(), U256::from(1000)),
+            (new_account, U256::from(50)),
+        ]);
+
+        let data_dir = TempDir::new().unwrap();
+        let storage_manager = storage_manager(&data_dir);
+        let genesis = genesis_block_with_state(
+            &storage_manager,
+            genesis_accounts.clone(),
+            GENESIS_ACCOUNT_ADDRESS,
+            U256::zero(),
+            machine.clone(),
+            |state_db| import_state(state_db, path).map(|s| s.supply),
+        )
+        .unwrap();
+        let state = genesis_state(&storage_manager, &genesis.hash());
+        for address in machine.internal_contracts().initialized_at_genesis() {
+            assert!(state.exists(&address.with_native_space()).unwrap());
+        }
+        assert_eq!(
+            state.balance(&core.with_native_space()).unwrap(),
+            100.into()
+        );
+        assert_eq!(state.balance(&new_account).unwrap(), 50.into());
+        assert_eq!(state.total_issued_tokens(), 165.into());
+        assert_eq!(state.total_espace_tokens(), 55.into());
+
+        // The global statistics of the dump are kept.
+        let data_dir = TempDir::new().unwrap();
+        let storage_manager = storage_manager(&data_dir);
+        let genesis = genesis_block_with_state(
+            &storage_manager,
+            genesis_accounts,
+            GENESIS_ACCOUNT_ADDRESS,
+            U256::zero(),
+            machine,
+            |state_db| {
+                let summary = import_state(state_db, path)?;
+                state_db
+                    .set_global_param::<InterestRate>(&1.into(), None)
+                    .unwrap();
+                state_db
+                    .set_global_param::<TotalIssued>(&1000.into(), None)
+                    .unwrap();
+                Ok(summary.supply)
+            },
+        )
+        .unwrap();
+        let state = genesis_state(&storage_manager, &genesis.hash());
+        assert_eq!(state.total_issued_tokens(), 1050.into());
+        assert_eq!(state.total_espace_tokens(), 50.into());
+    }
+}
@@ -4,7 +4,9 @@
 
 use std::{collections::BTreeMap, convert::TryInto, path::PathBuf, sync::Arc};
 
-use cfx_rpc_builder::RpcModuleSelection;
+use cfx_rpc_builder::{
+    IpcServerConfig, RpcModuleSelection, DEFAULT_IPC_PERMISSIONS,
+};
 use lazy_static::*;
 use log::{error, warn};
 use parking_lot::RwLock;
@@ -45,7 +47,7 @@ use cfxcore::{
     light_protocol::LightNodeConfiguration,
     sync::{ProtocolConfiguration, StateSyncConfiguration, SyncGraphConfig},
     sync_parameters::*,
-    transaction_pool::TxPoolConfig,
+    transaction_pool::{TxJournalConfig, TxPoolConfig},
     NodeType,
 };
 use diem_types::term_state::{
@@ -54,7 +56,7 @@ use diem_types::term_state::{
 };
 use jsonrpsee::server::ServerConfigBuilder;
 use metrics::MetricsConfiguration;
-use network::DiscoveryConfiguration;
+use network::{DiscoveryConfiguration, ReputationConfig, SessionFramingConfig};
 use primitives::block_header::CIP112_TRANSITION_HEIGHT;
 use txgen::TransactionGeneratorConfig;
 
@@ -65,6 +67,7 @@ lazy_static! {
 }
 const BLOCK_DB_DIR_NAME: &str = "blockchain_db";
 const NET_CONFIG_DB_DIR_NAME: &str = "net_config";
+const TX_POOL_JOURNAL_FILE_NAME: &str = "transactions.rlp";
 
 // usage:
 // ```
@@ -135,6 +138,9 @@ build_config! {
         (metrics_output_file, (Option<String>), None)
         (metrics_report_interval_ms, (u64), 3_000)
         (metrics_prometheus_listen_addr, (Option<String>), None)
+        (metrics_prometheus_push_gateway, (Option<String>), None)
+        (metrics_prometheus_push_job, (String), "conflux".into())
+        (metrics_prometheus_push_instance, (Option<String>), None)
         (profiling_listen_addr, (Option<String>), None)
         (rocksdb_disable_wal, (bool), false)
         (txgen_account_count, (usize), 10)
@@ -154,6 +160,7 @@ build_config! {
         (genesis_accounts, (Option<String>), None)
         (genesis_evm_secrets, (Option<String>), None)
         (genesis_secrets, (Option<String>), None)
+        (genesis_state, (Option<String>), None)
         (pivot_hint_path, (Option<String>), None)
         (pivot_hint_checksum, (Option<String>), None)
         (initial_difficulty, (Option<u64>), None)
@@ -234,12 +241,15 @@ build_config! {
         (jsonrpc_max_connections, (u32), 100)
         (jsonrpc_max_subscriptions_per_connection, (u32), 1024)
         (jsonrpc_message_buffer_capacity, (u32), 1024)
+        (jsonrpc_ipc_path, (Option<String>), None)
+        (jsonrpc_ipc_eth_path, (Option<String>), None)
         // The network_id, if unset, defaults to the chain_id.
         // Only override the network_id for local experiments,
         // when user would like to keep the existing blockchain data
         // but disconnect from the public network.
         (network_id, (Option<u64>), None)
         (rpc_enable_metrics, (bool), false)
+        (rpc_auth_conf, (Option<String>), None)
         (tcp_port, (u16), 32323)
         (public_tcp_port, (Option<u16>), None)
         (public_address, (Option<String>), None)
@@ -305,6 +315,12 @@ build_config! {
         (net_key, (Option<String>), None)
         (node_table_timeout_s, (u64), 300)
         (node_table_promotion_timeout_s, (u64), 3 * 24 * 3600)
+        (peer_ban_duration_s, (u64), 3600)
+        (peer_score_half_life_s, (u64), 1800)
+        (session_compression, (bool), false)
+        (session_compression_threshold, (usize), 1024)
+        (session_encryption, (bool), false)
+        (session_require_encryption, (bool), false)
         (session_ip_limits, (String), "1,8,4,2".into())
         (subnet_quota, (usize), 128)
 
@@ -315,6 +331,12 @@ build_config! {
         (tx_pool_min_eth_tx_gas_price, (Option<u64>), None)
         (tx_pool_nonce_bits, (usize), TXPOOL_DEFAULT_NONCE_BITS)
         (tx_pool_allow_gas_over_half_block, (bool), false)
+        (tx_pool_journal, (bool), false)
+        (tx_pool_journal_all, (bool), false)
+        (tx_pool_journal_path, (Option<String>), None)
+        (tx_pool_journal_max_size_mb, (u64), 64)
+        (tx_pool_local_accounts, (Option<String>), None)
+        (tx_pool_local_packing_priority, (bool), false)
         (max_packing_batch_gas_limit, (u64), 3_000_000)
         (max_packing_batch_size, (usize), 50)
         (packing_pool_degree, (u8), 4)
@@ -360,12 +382,15 @@ build_config! {
         (enable_optimistic_execution, (bool), true)
         (future_block_buffer_capacity, (usize), 32768)
         (get_logs_filter_max_limit, (Option<usize>), None)
+        (get_multiple_accounts_max_limit, (usize), 100)
         (get_logs_filter_max_epoch_range, (Option<u64>), None)
         (get_logs_filter_max_block_number_range, (Option<u64>), None)
         (get_logs_epoch_batch_size, (usize), 32)
         (max_trans_count_received_in_catch_up, (u64), 60_000)
         (persist_tx_index, (bool), false)
         (persist_block_number_index, (bool), true)
+        (persist_tx_sender_nonce_index, (bool), false)
+        (backfill_tx_sender_nonce_index, (bool), false)
         (print_memory_usage_period_s, (Option<u64>), None)
         (target_block_gas_limit, (u64), DEFAULT_TARGET_BLOCK_GAS_LIMIT)
         (executive_trace, (bool), false)
@@ -454,6 +479,9 @@ build_config! {
         (node_type, (Option<NodeType>), None, NodeType::from_str)
         (public_rpc_apis, (ApiSet), ApiSet::Safe, ApiSet::from_str)
         (public_evm_rpc_apis, (RpcModuleSelection), RpcModuleSelection::Evm, RpcModuleSelection::from_str)
+        (ipc_rpc_apis, (ApiSet), ApiSet::All, ApiSet::from_str)
+        (ipc_evm_rpc_apis, (RpcModuleSelection), RpcModuleSelection::All, RpcModuleSelection::from_str)
+        (jsonrpc_ipc_permissions, (u32), DEFAULT_IPC_PERMISSIONS, parse_file_mode)
         (single_mpt_space, (Option<Space>), None, Space::from_str)
     }
 }
@@ -560,6 +588,21 @@ impl Configuration {
             self.raw_conf.session_ip_limits.clone().try_into().map_err(
                 |e| format!("failed to parse session ip limit config: {}", e),
             )?;
+        network_config.session_framing = SessionFramingConfig {
+            compression: self.raw_conf.session_compression,
+            compression_threshold: self.raw_conf.session_compression_threshold,
+            encryption: self.raw_conf.session_encryption,
+            require_encryption: self.raw_conf.session_require_encryption,
+        };
+        network_config.reputation = ReputationConfig {
+            half_life: Duration::from_secs(
+                self.raw_conf.peer_score_half_life_s,
+            ),
+            ban_duration: Duration::from_secs(
+                self.raw_conf.peer_ban_duration_s,
+            ),
+            ..Default::default()
+        };
         network_config.fast_discovery_refresh_timeout = Duration::from_millis(
             self.raw_conf.discovery_fast_refresh_timeout_ms,
         );
@@ -983,6 +1026,9 @@ impl Configuration {
             persist_block_number_index: self
                 .raw_conf
                 .persist_block_number_index,
+            persist_tx_sender_nonce_index: self
+                .raw_conf
+                .persist_tx_sender_nonce_index,
             tx_cache_index_maintain_timeout: Duration::from_millis(
                 self.raw_conf.tx_cache_index_maintain_timeout_ms,
             ),
@@ -1079,6 +1125,18 @@ impl Configuration {
                 .raw_conf
                 .metrics_prometheus_listen_addr
                 .clone(),
+            prometheus_push_gateway: self
+                .raw_conf
+                .metrics_prometheus_push_gateway
+                .clone(),
+            prometheus_push_job: self
+                .raw_conf
+                .metrics_prometheus_push_job
+                .clone(),
+            prometheus_push_instance: self
+                .raw_conf
+                .metrics_prometheus_push_instance
+                .clone(),
         }
     }
 
@@ -1111,12 +1169,29 @@ impl Configuration {
                 .max_packing_batch_gas_limit,
             max_packing_batch_size: self.raw_conf.max_packing_batch_size,
             packing_pool_degree: self.raw_conf.packing_pool_degree,
+            journal: self.raw_conf.tx_pool_journal.then(|| TxJournalConfig {
+                path: match &self.raw_conf.tx_pool_journal_path {
+                    Some(path) => path.into(),
+                    None => Path::new(&self.raw_conf.conflux_data_dir)
+                        .join(TX_POOL_JOURNAL_FILE_NAME),
+                },
+                journal_all: self.raw_conf.tx_pool_journal_all,
+                max_size: self.raw_conf.tx_pool_journal_max_size_mb
+                    * 1024
+                    * 1024,
+            }),
+            local_packing_priority: self
+                .raw_conf
+                .tx_pool_local_packing_priority,
         }
     }
 
     pub fn rpc_impl_config(&self) -> RpcImplConfiguration {
         RpcImplConfiguration {
             get_logs_filter_max_limit: self.raw_conf.get_logs_filter_max_limit,
+            get_multiple_accounts_max_limit: self
+                .raw_conf
+                .get_multiple_accounts_max_limit,
             dev_pack_tx_immediately: self
                 .raw_conf
                 .dev_pack_tx_immediately
@@ -1206,6 +1281,35 @@ impl Configuration {
             )
     }
 
+    pub fn ipc_server_config(&self) -> Option<IpcServerConfig> {
+        self.make_ipc_server_config(self.raw_conf.jsonrpc_ipc_path.as_ref())
+    }
+
+    pub fn eth_ipc_server_config(&self) -> Option<IpcServerConfig> {
+        self.make_ipc_server_config(self.raw_conf.jsonrpc_ipc_eth_path.as_ref())
+    }
+
+    fn make_ipc_server_config(
+        &self, path: Option<&String>,
+    ) -> Option<IpcServerConfig> {
+        path.map(|path| {
+            IpcServerConfig::new(path)
+                .with_permissions(self.raw_conf.jsonrpc_ipc_permissions)
+                .with_max_request_body_size(
+                    self.raw_conf.jsonrpc_max_request_body_size,
+                )
+                .with_max_response_body_size(
+                    self.raw_conf.jsonrpc_max_response_body_size,
+                )
+                .with_max_subscriptions_per_connection(
+                    self.raw_conf.jsonrpc_max_subscriptions_per_connection,
+                )
+                .with_message_buffer_capacity(
+                    self.raw_conf.jsonrpc_message_buffer_capacity,
+                )
+        })
+    }
+
     pub fn execution_config(&self) -> ConsensusExecutionConfiguration {
         ConsensusExecutionConfiguration {
             executive_trace: self.raw_conf.executive_trace,
@@ -1569,6 +1673,15 @@ pub fn to_bootnodes(bootnodes: &Option<String>) -> Result<Vec<String>, String> {
     }
 }
 
+/// Parses an octal file mode like `600` or `0o660`.
+pub fn parse_file_mode(mode: &str) -> Result<u32, String> {
+    let digits = mode.trim().trim_start_matches("0o");
+    match u32::from_str_radix(digits, 8) {
+        Ok(mode) if mode <= 0o777 => Ok(mode),
+        _ => Err(format!("invalid file mode {:?}", mode)),
+    }
+}
+
 pub fn parse_config_address_string(
     addr: &str, network: &Network,
 ) -> Result<Address, String> {
@@ -1604,7 +1717,7 @@ pub fn parse_config_address_string(
 mod tests {
     use cfx_addr::Network;
 
-    use crate::configuration::parse_config_address_string;
+    use crate::configuration::{parse_config_address_string, parse_file_mode};
 
     #[test]
     fn test_config_address_string() {
@@ -1641,4 +1754,12 @@ mod tests {
             .unwrap()
         );
     }
+
+    #[test]
+    fn test_file_mode() {
+        assert_eq!(parse_file_mode("600"), Ok(0o600));
+        assert_eq!(parse_file_mode("0o660"), Ok(0o660));
+        assert!(parse_file_mode("800").is_err());
+        assert!(parse_file_mode("1777").is_err());
+    }
 }
@@ -44,10 +44,10 @@ pub struct Block {
 #[derive(Debug, PartialEq, Eq, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Withdrawal {
-    index: U256,
-    validator_index: U256,
-    address: Address,
-    amount: U256,
+    pub index: U256,
+    pub validator_index: U256,
+    pub address: Address,
+    pub amount: U256,
 }
 
 #[derive(Debug, PartialEq, Eq, Deserialize)]
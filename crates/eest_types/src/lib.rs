@@ -5,6 +5,7 @@ mod config;
 mod spec_id;
 mod spec_name;
 mod state;
+mod t8n;
 mod test_authorization;
 mod transaction_type;
 mod utils;
@@ -16,6 +17,7 @@ pub use config::*;
 pub use spec_id::*;
 pub use spec_name::*;
 pub use state::*;
+pub use t8n::*;
 pub use test_authorization::*;
 pub use transaction_type::*;
 pub use utils::deserializer;
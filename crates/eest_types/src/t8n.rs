@@ -0,0 +1,148 @@
+//! Input types of the `t8n` (state transition) tool, which is driven by the
+//! execution spec tests to fill the fixtures.
+//!
+//! See https://github.com/ethereum/go-ethereum/tree/master/cmd/evm for the
+//! format.
+
+use crate::{TestAuthorization, Withdrawal};
+use cfx_rpc_primitives::Bytes;
+use cfx_types::{Address, H256, U256, U64};
+use primitives::transaction::AccessList;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Account in the pre-state allocation. All the fields are optional, unlike
+/// the accounts in the fixtures.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct T8nAccount {
+    #[serde(default)]
+    pub balance: U256,
+    #[serde(default)]
+    pub code: Bytes,
+    #[serde(default)]
+    pub nonce: U256,
+    #[serde(default)]
+    pub storage: HashMap<U256, U256>,
+}
+
+/// Environment of the block to build.
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct T8nEnv {
+    pub current_coinbase: Address,
+    pub current_difficulty: Option<U256>,
+    pub current_random: Option<H256>,
+    pub current_gas_limit: U256,
+    pub current_number: U256,
+    pub current_timestamp: U256,
+    pub current_base_fee: Option<U256>,
+    pub current_excess_blob_gas: Option<U256>,
+
+    pub parent_difficulty: Option<U256>,
+    pub parent_timestamp: Option<U256>,
+    pub parent_base_fee: Option<U256>,
+    pub parent_gas_used: Option<U256>,
+    pub parent_gas_limit: Option<U256>,
+    pub parent_excess_blob_gas: Option<U256>,
+    pub parent_blob_gas_used: Option<U256>,
+    pub parent_beacon_block_root: Option<H256>,
+
+    /// Hashes of the ancestors by the block number, in hex or decimal.
+    #[serde(default)]
+    pub block_hashes: BTreeMap<String, H256>,
+    pub withdrawals: Option<Vec<Withdrawal>>,
+}
+
+/// Transaction to apply, which is either signed with `v`, `r` and `s`, or
+/// signed by the tool with `secret_key`.
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct T8nTransaction {
+    #[serde(default, rename = "type")]
+    pub tx_type: U64,
+    pub chain_id: Option<U256>,
+    pub nonce: U256,
+    pub gas_price: Option<U256>,
+    pub max_priority_fee_per_gas: Option<U256>,
+    pub max_fee_per_gas: Option<U256>,
+    #[serde(alias = "gasLimit")]
+    pub gas: U256,
+    #[serde(default)]
+    pub to: Option<Address>,
+    pub value: U256,
+    #[serde(alias = "data")]
+    pub input: Bytes,
+    pub access_list: Option<AccessList>,
+    pub authorization_list: Option<Vec<TestAuthorization>>,
+    pub max_fee_per_blob_gas: Option<U256>,
+    pub blob_versioned_hashes: Option<Vec<H256>>,
+    pub v: Option<U64>,
+    pub r: Option<U256>,
+    pub s: Option<U256>,
+    pub secret_key: Option<H256>,
+    /// Whether a legacy transaction signed by the tool is protected by
+    /// EIP-155.
+    pub protected: Option<bool>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_t8n_input() {
+        let alloc = r#"{
+            "0xa94f5374fce5edbc8e2a8697c15331677e6ebf0b": {
+                "balance": "0x5ffd4878be161d74",
+                "nonce": "0xac"
+            },
+            "0x8a8eafb1cf62bfbeb1741769dae1a9dd47996192": {
+                "balance": "0xfeedbead",
+                "code": "0x600160005500",
+                "storage": {
+                    "0x00": "0x01"
+                }
+            }
+        }"#;
+        let alloc: HashMap<Address, T8nAccount> =
+            serde_json::from_str(alloc).unwrap();
+        assert_eq!(alloc.len(), 2);
+
+        let env = r#"{
+            "currentCoinbase": "0xc94f5374fce5edbc8e2a8697c15331677e6ebf0b",
+            "currentGasLimit": "0x750a163df65e8a",
+            "currentNumber": "0x01",
+            "currentTimestamp": "0x03e8",
+            "currentRandom": "0x0000000000000000000000000000000000000000000000000000000000000000",
+            "currentBaseFee": "0x07",
+            "blockHashes": {
+                "0x00": "0xe4ae30d2ed1d8eb9dbeeb8b3e8a9bda4e1d1e3d8e2b5d0ae3c68f8b1c5d1ae9b"
+            },
+            "withdrawals": []
+        }"#;
+        let env: T8nEnv = serde_json::from_str(env).unwrap();
+        assert_eq!(env.current_number, U256::from(1));
+        assert_eq!(env.block_hashes.len(), 1);
+
+        let txs = r#"[{
+            "type": "0x2",
+            "chainId": "0x1",
+            "nonce": "0xac",
+            "maxPriorityFeePerGas": "0x0",
+            "maxFeePerGas": "0x7",
+            "gas": "0x5208",
+            "to": "0x8a8eafb1cf62bfbeb1741769dae1a9dd47996192",
+            "value": "0x1",
+            "input": "0x",
+            "accessList": [],
+            "v": "0x0",
+            "r": "0x0",
+            "s": "0x0",
+            "secretKey": "0x45a915e4d060149eb4365960e6a7a45f334393093061116b197e3240065ff2d8"
+        }]"#;
+        let txs: Vec<T8nTransaction> = serde_json::from_str(txs).unwrap();
+        assert_eq!(txs[0].tx_type, U64::from(2));
+        assert!(txs[0].secret_key.is_some());
+    }
+}
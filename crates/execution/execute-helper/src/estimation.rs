@@ -181,6 +181,41 @@ impl<'a> EstimationContext<'a> {
         )
     }
 
+    /// Executes a transaction of a simulated block and keeps its effects in
+    /// the state, so that the following transactions see them. Without
+    /// `validation` the transaction is treated like a virtual call; with it
+    /// the nonce, the balance and the base price are checked as if it was
+    /// packed. The sender and the nonce filled in are written back to `tx`.
+    pub fn transact_simulated(
+        &mut self, tx: &mut SignedTransaction, request: EstimateRequest,
+        validation: bool,
+    ) -> DbResult<ExecutionOutcome> {
+        let settings = if validation {
+            if !request.has_nonce {
+                *tx.nonce_mut() = self.state.nonce(&tx.sender())?;
+            }
+            TransactSettings {
+                check_epoch_bound: false,
+                ..TransactSettings::all_checks()
+            }
+        } else {
+            self.process_estimate_request(tx, &request)?;
+            request.transact_settings(ChargeCollateral::EstimateSender)
+        };
+        // The executive requires an empty cache.
+        self.state.commit_cache(false);
+
+        let outcome = self.as_executive().transact(
+            tx,
+            TransactOptions {
+                observer: Observer::virtual_call(),
+                settings,
+            },
+        )?;
+        self.state.update_state_post_tx_execution(false);
+        Ok(outcome)
+    }
+
     pub fn prepare_access_list_inspector(
         &mut self, tx: &SignedTransaction, request: &EstimateRequest,
     ) -> Option<AccessListInspector> {
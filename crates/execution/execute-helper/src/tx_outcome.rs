@@ -21,7 +21,7 @@ pub struct ProcessTxOutcome {
     pub tx_staking_events: Vec<StakingEvent>,
     pub tx_exec_error_msg: String,
     pub consider_repacked: bool,
-    pub geth_trace: Option<GethTrace>,
+    pub geth_trace: Option<Result<GethTrace, String>>,
 }
 
 pub fn parity_traces(outcome: &ExecutionOutcome) -> Vec<ExecTrace> {
@@ -31,7 +31,9 @@ pub fn parity_traces(outcome: &ExecutionOutcome) -> Vec<ExecTrace> {
         .unwrap_or_default()
 }
 
-pub fn geth_traces(outcome: &ExecutionOutcome) -> Option<GethTrace> {
+pub fn geth_traces(
+    outcome: &ExecutionOutcome,
+) -> Option<Result<GethTrace, String>> {
     outcome
         .try_as_executed()
         .and_then(|executed| executed.ext_result.get::<GethTraceKey>().cloned())
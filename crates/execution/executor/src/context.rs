@@ -532,7 +532,13 @@ impl<'a> ContextTrait for Context<'a> {
     fn depth(&self) -> usize { self.depth }
 
     fn trace_step(&mut self, interpreter: &dyn vm::InterpreterInfo) {
-        self.tracer.step(interpreter);
+        self.tracer.step_with_state(interpreter, self.state);
+    }
+
+    fn trace_step_cost(
+        &mut self, interpreter: &dyn vm::InterpreterInfo, cost: U256,
+    ) {
+        self.tracer.step_cost(interpreter, cost, self.state);
     }
 
     fn trace_step_end(&mut self, interpreter: &dyn vm::InterpreterInfo) {
@@ -1,3 +1,4 @@
+use crate::state::State;
 use cfx_types::{Address, Space, H256, U256};
 use cfx_vm_types::InterpreterInfo;
 
@@ -19,6 +20,25 @@ pub trait OpcodeTracer {
     /// more is available on `interp` (see [Interpreter]).
     fn step(&mut self, interp: &dyn InterpreterInfo) { let _ = interp; }
 
+    /// Called on each step of the interpreter, with read access to the state
+    /// before the instruction is executed. Defaults to [`Self::step`] for
+    /// tracers that don't inspect the state.
+    fn step_with_state(&mut self, interp: &dyn InterpreterInfo, state: &State) {
+        let _ = state;
+        self.step(interp);
+    }
+
+    /// Called after `step` once the gas cost of the instruction is known,
+    /// before it is charged and executed. Not called if the instruction fails
+    /// before its cost is computed.
+    fn step_cost(
+        &mut self, interp: &dyn InterpreterInfo, cost: U256, state: &State,
+    ) {
+        let _ = interp;
+        let _ = cost;
+        let _ = state;
+    }
+
     /// Called after `step` when the instruction has been executed.
     fn step_end(&mut self, interp: &dyn InterpreterInfo) { let _ = interp; }
 
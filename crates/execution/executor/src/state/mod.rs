@@ -23,7 +23,7 @@ mod state_object;
 pub use state_object::{
     distribute_pos_interest, initialize_cip107, initialize_cip137,
     initialize_or_update_dao_voted_params, settle_collateral_for_all,
-    update_pos_status, State, StateCommitResult,
+    update_pos_status, SavedState, State, StateCommitResult,
     COMMISSION_PRIVILEGE_SPECIAL_KEY,
 };
 #[cfg(test)]
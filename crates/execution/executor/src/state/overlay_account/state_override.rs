@@ -61,6 +61,49 @@ impl OverlayAccount {
         acc
     }
 
+    /// Applies the override to an account which may have been modified by
+    /// the executed transactions. The overridden storage entries are written
+    /// to the committed cache so they take precedence over the values set by
+    /// the earlier transactions.
+    pub fn apply_override(&mut self, acc_overrides: &AccountOverride) {
+        assert!(self.storage_write_checkpoint.is_none());
+
+        if let Some(balance) = acc_overrides.balance {
+            let curr_balance = *self.balance();
+            if curr_balance > U256::zero() {
+                self.sub_balance(&curr_balance);
+            }
+            self.add_balance(&balance);
+        }
+
+        if let Some(nonce) = acc_overrides.nonce {
+            self.set_nonce(&U256::from(nonce.as_u64()));
+        }
+
+        if let Some(code) = acc_overrides.code.as_ref() {
+            self.override_code(code.clone(), self.address.address);
+        }
+
+        let account_storage = match &acc_overrides.state {
+            AccountStateOverrideMode::State(state_override) => {
+                self.storage_overrided = true;
+                self.storage_read_cache.write().clear();
+                self.storage_committed_cache.write().clear();
+                state_override
+            }
+            AccountStateOverrideMode::Diff(diff) => diff,
+            AccountStateOverrideMode::None => return,
+        };
+
+        let mut commit_cache = self.storage_committed_cache.write();
+        for (key, value) in account_storage {
+            commit_cache.insert(
+                key.as_bytes().to_vec(),
+                override_storage_value(&self.address, value),
+            );
+        }
+    }
+
     fn override_storage_read_cache(
         &mut self, account_storage: &HashMap<H256, H256>,
         complete_override: bool,
@@ -69,19 +112,27 @@ impl OverlayAccount {
 
         self.storage_overrided = complete_override;
 
+        let address = self.address;
         let read_cache = Arc::get_mut(&mut self.storage_read_cache)
             .expect("override should happen when no checkpoint")
             .get_mut();
         for (key, value) in account_storage {
-            let key = key.as_bytes().to_vec();
-            let value = U256::from_big_endian(value.as_bytes());
-            let owner = if self.address.space == Space::Native {
-                Some(self.address.address)
-            } else {
-                None
-            };
-            let storage_value = StorageValue { owner, value };
-            read_cache.insert(key, storage_value);
+            read_cache.insert(
+                key.as_bytes().to_vec(),
+                override_storage_value(&address, value),
+            );
         }
     }
 }
+
+fn override_storage_value(
+    address: &AddressWithSpace, value: &H256,
+) -> StorageValue {
+    let value = U256::from_big_endian(value.as_bytes());
+    let owner = if address.space == Space::Native {
+        Some(address.address)
+    } else {
+        None
+    };
+    StorageValue { owner, value }
+}
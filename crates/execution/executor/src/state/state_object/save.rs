@@ -10,6 +10,20 @@ pub struct SavedState {
     global_stat: GlobalStat,
 }
 
+impl Clone for SavedState {
+    fn clone(&self) -> Self {
+        let committed_cache = self
+            .committed_cache
+            .iter()
+            .map(|(k, v)| (*k, v.clone_account()))
+            .collect();
+        SavedState {
+            committed_cache,
+            global_stat: self.global_stat.clone(),
+        }
+    }
+}
+
 impl State {
     pub fn save(&mut self) -> SavedState {
         self.commit_cache(false);
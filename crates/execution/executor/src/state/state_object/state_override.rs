@@ -15,6 +15,26 @@ impl State {
         Ok(state)
     }
 
+    /// Applies the state override on top of the executed transactions, used
+    /// by the rpc calls which execute a sequence of blocks, eg
+    /// eth_simulateV1.
+    pub fn apply_override_post_execution(
+        &mut self, state_override: &StateOverride, space: Space,
+    ) -> DbResult<()> {
+        assert!(self.no_checkpoint());
+
+        for (address, account) in state_override.iter() {
+            let addr_with_space = AddressWithSpace {
+                address: address.to_owned(),
+                space,
+            };
+            self.write_account_or_new_lock(&addr_with_space)?
+                .apply_override(account);
+        }
+        self.commit_cache(false);
+        Ok(())
+    }
+
     fn apply_override(
         &mut self, state_override: &StateOverride, space: Space,
     ) -> DbResult<()> {
@@ -991,6 +991,59 @@ fn create_contract_fail_previous_storage() {
     // TODO(69): checking ownership
 }
 
+#[test]
+fn restore_saved_state() {
+    let mut state = get_state_for_genesis_write();
+    let mut address = Address::zero();
+    address.set_contract_type_bits();
+    let address_with_space = address.with_native_space();
+    let key = u256_to_vec(&U256::from(0));
+    state
+        .new_contract_with_code(&address_with_space, U256::zero())
+        .unwrap();
+    state
+        .add_balance(&address_with_space, &U256::from(1069u64))
+        .unwrap();
+    state
+        .set_storage(
+            &address_with_space,
+            key.clone(),
+            U256::one(),
+            address,
+            &mut Substate::new(),
+        )
+        .unwrap();
+    let saved = state.save();
+
+    // The changes are restored on another state of the same epoch, and the
+    // changes made there do not affect the saved copy.
+    for _ in 0..2 {
+        let mut other = get_state_for_genesis_write();
+        other.restore(saved.clone());
+        assert_eq!(
+            other.balance(&address_with_space).unwrap(),
+            U256::from(1069u64)
+        );
+        assert_eq!(
+            other.storage_at(&address_with_space, &key).unwrap(),
+            U256::one()
+        );
+        other
+            .set_storage(
+                &address_with_space,
+                key.clone(),
+                U256::from(2),
+                address,
+                &mut Substate::new(),
+            )
+            .unwrap();
+        other
+            .sub_balance(&address_with_space, &U256::from(69u64))
+            .unwrap();
+        other.commit_cache(false);
+    }
+}
+
 #[test]
 fn test_automatic_collateral_normal_account() {
     let mut state = get_state_for_genesis_write();
@@ -27,7 +27,10 @@
 // IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
 // DEALINGS IN THE SOFTWARE.
 use alloy_rpc_types_trace::{
-    geth::{CallConfig, GethDefaultTracingOptions, PreStateConfig},
+    geth::{
+        erc7562::Erc7562Config, CallConfig, FlatCallConfig,
+        GethDefaultTracingOptions, PreStateConfig,
+    },
     parity::TraceType,
 };
 use std::collections::HashSet;
@@ -165,6 +168,30 @@ impl TracingInspectorConfig {
             .set_record_logs(config.with_log.unwrap_or_default())
     }
 
+    /// Returns a config for geth's
+    /// [FlatCallTracer](alloy_rpc_trace_types::geth::FlatCallFrame).
+    ///
+    /// This returns [Self::default_parity] and only records calls to
+    /// precompiles if configured in the given [FlatCallConfig]
+    #[inline]
+    pub fn from_flat_call_config(config: &FlatCallConfig) -> Self {
+        Self::default_parity().set_exclude_precompile_calls(
+            !config.include_precompiles.unwrap_or_default(),
+        )
+    }
+
+    /// Returns a config for geth's
+    /// [Erc7562Tracer](alloy_rpc_trace_types::geth::erc7562::Erc7562Frame).
+    ///
+    /// This returns [Self::none] and enables
+    /// [TracingInspectorConfig::record_logs] if configured in
+    /// the given [Erc7562Config]. Opcodes are inspected by the tracer itself
+    /// and don't need to be recorded as steps.
+    #[inline]
+    pub fn from_erc7562_config(config: &Erc7562Config) -> Self {
+        Self::none().set_record_logs(config.with_log.unwrap_or_default())
+    }
+
     /// Returns a config for geth's
     /// [PrestateTracer](alloy_rpc_trace_types::geth::PreStateFrame).
     ///
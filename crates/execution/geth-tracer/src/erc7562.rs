@@ -0,0 +1,429 @@
+use crate::utils::to_alloy_u256;
+use alloy_primitives::{Address, Bytes, B256};
+use alloy_rpc_types_trace::geth::erc7562::{
+    AccessedSlots, ContractSize, Erc7562Config, Erc7562Frame,
+};
+use cfx_types::U256;
+use cfx_vm_types::InterpreterInfo;
+use revm_bytecode::opcode;
+use std::collections::{HashMap, HashSet};
+
+/// Opcode level data of a single call frame, as required by
+/// [ERC-7562](https://eips.ethereum.org/EIPS/eip-7562) validation rules.
+#[derive(Clone, Debug, Default)]
+pub struct Erc7562FrameInfo {
+    pub accessed_slots: AccessedSlots,
+    pub ext_code_access_info: Vec<String>,
+    pub used_opcodes: HashMap<u8, u64>,
+    pub contract_size: HashMap<Address, ContractSize>,
+    pub out_of_gas: bool,
+    pub keccak: Vec<Bytes>,
+}
+
+impl Erc7562FrameInfo {
+    /// Moves the collected data into the given frame.
+    pub fn fill_frame(self, frame: &mut Erc7562Frame) {
+        frame.accessed_slots = self.accessed_slots;
+        frame.ext_code_access_info = self.ext_code_access_info;
+        frame.used_opcodes = self.used_opcodes.into_iter().collect();
+        frame.contract_size = self.contract_size.into_iter().collect();
+        frame.out_of_gas = self.out_of_gas;
+        frame.keccak = self.keccak;
+    }
+}
+
+/// A step whose result is only known after the opcode was executed.
+#[derive(Clone, Copy, Debug)]
+enum PendingStep {
+    Sload {
+        trace_idx: usize,
+        slot: B256,
+    },
+    ExtCodeSize {
+        trace_idx: usize,
+        address: Address,
+    },
+    Keccak {
+        trace_idx: usize,
+        offset: U256,
+        size: U256,
+    },
+}
+
+/// ERC-7562 tracing inspector that records the used opcodes, storage accesses
+/// and code accesses of every call frame.
+///
+/// Frames are identified by their index in the call arena of the
+/// [TracingInspector](crate::tracing_inspector::TracingInspector).
+#[derive(Clone, Debug, Default)]
+pub struct Erc7562Inspector {
+    /// Opcodes that are not reported in `usedOpcodes`
+    ignored_opcodes: HashSet<u8>,
+    /// Collected data by trace index
+    frames: HashMap<usize, Erc7562FrameInfo>,
+    /// The opcode of the last executed step
+    last_opcode: u8,
+    /// The step waiting for its result in `step_end`
+    pending_step: Option<PendingStep>,
+    /// An `EXTCODE*` access, which is fine if the next opcode is `ISZERO`
+    pending_ext_code_access: Option<(usize, Address)>,
+}
+
+impl Erc7562Inspector {
+    pub fn new(config: &Erc7562Config) -> Self {
+        let ignored_opcodes = if config.ignored_opcodes.is_empty() {
+            default_ignored_opcodes()
+        } else {
+            config.ignored_opcodes.iter().copied().collect()
+        };
+        Self {
+            ignored_opcodes,
+            ..Default::default()
+        }
+    }
+
+    fn frame(&mut self, trace_idx: usize) -> &mut Erc7562FrameInfo {
+        self.frames.entry(trace_idx).or_default()
+    }
+
+    /// Takes the collected data of the given frame.
+    pub fn take_frame(&mut self, trace_idx: usize) -> Erc7562FrameInfo {
+        self.frames.remove(&trace_idx).unwrap_or_default()
+    }
+
+    /// Records the code size of a callee in the calling frame. `code_size` is
+    /// `None` for calls to precompiles, which are not reported.
+    pub fn record_call(
+        &mut self, parent_idx: usize, address: Address,
+        code_size: Option<usize>,
+    ) {
+        if let Some(code_size) = code_size {
+            let opcode = self.last_opcode;
+            self.frame(parent_idx).contract_size.insert(
+                address,
+                ContractSize {
+                    contract_size: code_size as u64,
+                    opcode,
+                },
+            );
+        }
+    }
+
+    pub fn set_out_of_gas(&mut self, trace_idx: usize) {
+        self.frame(trace_idx).out_of_gas = true;
+        self.pending_step = None;
+        self.pending_ext_code_access = None;
+    }
+
+    pub fn step(&mut self, trace_idx: usize, interp: &dyn InterpreterInfo) {
+        let op = interp.current_opcode();
+        let stack = interp.stack();
+        let peek = |n: usize| stack.len().checked_sub(n + 1).map(|i| stack[i]);
+
+        if let Some((idx, address)) = self.pending_ext_code_access.take() {
+            // `EXTCODESIZE` followed by `ISZERO` is only an existence check
+            if op != opcode::ISZERO {
+                self.frame(idx)
+                    .ext_code_access_info
+                    .push(address.to_checksum(None));
+            }
+        }
+
+        if !self.ignored_opcodes.contains(&op) {
+            *self.frame(trace_idx).used_opcodes.entry(op).or_default() += 1;
+        }
+
+        match op {
+            opcode::SLOAD => {
+                self.pending_step = peek(0).map(|slot| PendingStep::Sload {
+                    trace_idx,
+                    slot: to_b256(slot),
+                });
+            }
+            opcode::SSTORE => {
+                if let Some(slot) = peek(0) {
+                    let slots = &mut self.frame(trace_idx).accessed_slots;
+                    *slots.writes.entry(to_b256(slot)).or_default() += 1;
+                }
+            }
+            opcode::TLOAD => {
+                if let Some(slot) = peek(0) {
+                    let slots = &mut self.frame(trace_idx).accessed_slots;
+                    *slots.transient_reads.entry(to_b256(slot)).or_default() +=
+                        1;
+                }
+            }
+            opcode::TSTORE => {
+                if let Some(slot) = peek(0) {
+                    let slots = &mut self.frame(trace_idx).accessed_slots;
+                    *slots
+                        .transient_writes
+                        .entry(to_b256(slot))
+                        .or_default() += 1;
+                }
+            }
+            opcode::KECCAK256 => {
+                if let (Some(offset), Some(size)) = (peek(0), peek(1)) {
+                    self.pending_step = Some(PendingStep::Keccak {
+                        trace_idx,
+                        offset,
+                        size,
+                    });
+                }
+            }
+            opcode::EXTCODESIZE | opcode::EXTCODEHASH | opcode::EXTCODECOPY => {
+                if let Some(word) = peek(0) {
+                    let address = Address::from_word(to_b256(word));
+                    self.pending_ext_code_access = Some((trace_idx, address));
+                    if op == opcode::EXTCODESIZE {
+                        self.pending_step = Some(PendingStep::ExtCodeSize {
+                            trace_idx,
+                            address,
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        self.last_opcode = op;
+    }
+
+    pub fn step_end(&mut self, interp: &dyn InterpreterInfo) {
+        let Some(pending) = self.pending_step.take() else {
+            return;
+        };
+
+        match pending {
+            PendingStep::Sload { trace_idx, slot } => {
+                let Some(value) = interp.stack().last() else {
+                    return;
+                };
+                let slots = &mut self.frame(trace_idx).accessed_slots;
+                // only reads of values not written by this frame matter
+                if !slots.writes.contains_key(&slot) {
+                    slots.reads.entry(slot).or_default().push(to_b256(*value));
+                }
+            }
+            PendingStep::ExtCodeSize { trace_idx, address } => {
+                let Some(size) = interp.stack().last() else {
+                    return;
+                };
+                self.frame(trace_idx).contract_size.insert(
+                    address,
+                    ContractSize {
+                        contract_size: size.low_u64(),
+                        opcode: opcode::EXTCODESIZE,
+                    },
+                );
+            }
+            PendingStep::Keccak {
+                trace_idx,
+                offset,
+                size,
+            } => {
+                // the memory has been expanded if the opcode succeeded
+                let mem = interp.mem();
+                if offset.bits() > 64 || size.bits() > 64 {
+                    return;
+                }
+                let (offset, size) =
+                    (offset.low_u64() as usize, size.low_u64() as usize);
+                if let Some(data) = offset
+                    .checked_add(size)
+                    .and_then(|end| mem.get(offset..end))
+                {
+                    self.frame(trace_idx).keccak.push(data.to_vec().into());
+                }
+            }
+        }
+    }
+}
+
+/// Opcodes which are not reported if no `ignoredOpcodes` is configured, the
+/// same as geth's defaults.
+fn default_ignored_opcodes() -> HashSet<u8> {
+    let mut ignored: HashSet<u8> = (opcode::PUSH0..=opcode::SWAP16).collect();
+    ignored.extend([
+        opcode::POP,
+        opcode::ADD,
+        opcode::SUB,
+        opcode::MUL,
+        opcode::DIV,
+        opcode::EQ,
+        opcode::LT,
+        opcode::GT,
+        opcode::SLT,
+        opcode::SGT,
+        opcode::SHL,
+        opcode::SHR,
+        opcode::AND,
+        opcode::OR,
+        opcode::NOT,
+        opcode::ISZERO,
+    ]);
+    ignored
+}
+
+fn to_b256(value: U256) -> B256 { B256::from(to_alloy_u256(value)) }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cfx_types::Address as CfxAddress;
+
+    #[derive(Default)]
+    struct TestInterp {
+        opcode: u8,
+        stack: Vec<U256>,
+        mem: Vec<u8>,
+        return_stack: Vec<usize>,
+    }
+
+    impl TestInterp {
+        fn new(opcode: u8, stack: Vec<U256>) -> Self {
+            Self {
+                opcode,
+                stack,
+                ..Default::default()
+            }
+        }
+    }
+
+    impl InterpreterInfo for TestInterp {
+        fn gas_remainning(&self) -> U256 { U256::zero() }
+
+        fn program_counter(&self) -> u64 { 0 }
+
+        fn current_opcode(&self) -> u8 { self.opcode }
+
+        fn opcode(&self, _pc: u64) -> Option<u8> { Some(self.opcode) }
+
+        fn mem(&self) -> &Vec<u8> { &self.mem }
+
+        fn stack(&self) -> &Vec<U256> { &self.stack }
+
+        fn return_stack(&self) -> &Vec<usize> { &self.return_stack }
+
+        fn contract_address(&self) -> CfxAddress { CfxAddress::zero() }
+    }
+
+    fn slot(n: u64) -> B256 { to_b256(U256::from(n)) }
+
+    #[test]
+    fn test_storage_access() {
+        let mut inspector = Erc7562Inspector::new(&Default::default());
+
+        // a read of slot 1, then a write and a read of slot 2
+        inspector.step(0, &TestInterp::new(opcode::SLOAD, vec![1.into()]));
+        inspector.step_end(&TestInterp::new(opcode::SLOAD, vec![7.into()]));
+        inspector.step(
+            0,
+            &TestInterp::new(opcode::SSTORE, vec![9.into(), 2.into()]),
+        );
+        inspector.step_end(&TestInterp::new(opcode::SSTORE, vec![]));
+        inspector.step(0, &TestInterp::new(opcode::SLOAD, vec![2.into()]));
+        inspector.step_end(&TestInterp::new(opcode::SLOAD, vec![9.into()]));
+        inspector.step(0, &TestInterp::new(opcode::TLOAD, vec![3.into()]));
+        inspector.step_end(&TestInterp::new(opcode::TLOAD, vec![0.into()]));
+
+        let frame = inspector.take_frame(0);
+        let slots = &frame.accessed_slots;
+        assert_eq!(slots.reads.len(), 1);
+        assert_eq!(slots.reads[&slot(1)], vec![slot(7)]);
+        assert_eq!(slots.writes[&slot(2)], 1);
+        assert_eq!(slots.transient_reads[&slot(3)], 1);
+        assert_eq!(frame.used_opcodes[&opcode::SLOAD], 2);
+        assert_eq!(frame.used_opcodes[&opcode::SSTORE], 1);
+
+        // the data is only taken once
+        assert!(inspector.take_frame(0).used_opcodes.is_empty());
+    }
+
+    #[test]
+    fn test_ignored_opcodes() {
+        let mut inspector = Erc7562Inspector::new(&Default::default());
+        inspector.step(0, &TestInterp::new(opcode::PUSH1, vec![]));
+        inspector.step(0, &TestInterp::new(opcode::ADD, vec![]));
+        inspector.step(0, &TestInterp::new(opcode::GAS, vec![]));
+        let frame = inspector.take_frame(0);
+        assert_eq!(frame.used_opcodes.len(), 1);
+        assert_eq!(frame.used_opcodes[&opcode::GAS], 1);
+
+        // a configured list replaces the default one
+        let config = Erc7562Config {
+            ignored_opcodes: vec![opcode::GAS],
+            ..Default::default()
+        };
+        let mut inspector = Erc7562Inspector::new(&config);
+        inspector.step(0, &TestInterp::new(opcode::ADD, vec![]));
+        inspector.step(0, &TestInterp::new(opcode::GAS, vec![]));
+        let frame = inspector.take_frame(0);
+        assert_eq!(frame.used_opcodes.len(), 1);
+        assert_eq!(frame.used_opcodes[&opcode::ADD], 1);
+    }
+
+    #[test]
+    fn test_ext_code_access() {
+        let mut inspector = Erc7562Inspector::new(&Default::default());
+        let checked = Address::repeat_byte(1);
+        let accessed = Address::repeat_byte(2);
+        let word = |address: Address| {
+            U256::from_big_endian(address.into_word().as_slice())
+        };
+
+        // `EXTCODESIZE` followed by `ISZERO` is an existence check
+        inspector.step(
+            0,
+            &TestInterp::new(opcode::EXTCODESIZE, vec![word(checked)]),
+        );
+        inspector
+            .step_end(&TestInterp::new(opcode::EXTCODESIZE, vec![0.into()]));
+        inspector.step(0, &TestInterp::new(opcode::ISZERO, vec![0.into()]));
+        inspector.step(
+            0,
+            &TestInterp::new(opcode::EXTCODEHASH, vec![word(accessed)]),
+        );
+        inspector
+            .step_end(&TestInterp::new(opcode::EXTCODEHASH, vec![0.into()]));
+        inspector.step(0, &TestInterp::new(opcode::POP, vec![0.into()]));
+
+        let frame = inspector.take_frame(0);
+        assert_eq!(
+            frame.ext_code_access_info,
+            vec![accessed.to_checksum(None)]
+        );
+        assert_eq!(frame.contract_size[&checked].contract_size, 0);
+        assert_eq!(frame.contract_size[&checked].opcode, opcode::EXTCODESIZE);
+    }
+
+    #[test]
+    fn test_keccak_and_calls() {
+        let mut inspector = Erc7562Inspector::new(&Default::default());
+        inspector.step(
+            1,
+            &TestInterp::new(opcode::KECCAK256, vec![4.into(), 2.into()]),
+        );
+        let mut interp = TestInterp::new(opcode::KECCAK256, vec![]);
+        interp.mem = vec![0, 0, 0xaa, 0xbb, 0xcc, 0xdd];
+        inspector.step_end(&interp);
+
+        // the code size of the callee is recorded with the calling opcode
+        inspector.step(1, &TestInterp::new(opcode::STATICCALL, vec![]));
+        inspector.record_call(1, Address::repeat_byte(3), Some(10));
+        inspector.record_call(1, Address::with_last_byte(1), None);
+        inspector.set_out_of_gas(2);
+
+        let frame = inspector.take_frame(1);
+        assert_eq!(
+            frame.keccak,
+            vec![Bytes::from(vec![0xaa, 0xbb, 0xcc, 0xdd])]
+        );
+        assert_eq!(frame.contract_size.len(), 1);
+        let size = &frame.contract_size[&Address::repeat_byte(3)];
+        assert_eq!(size.contract_size, 10);
+        assert_eq!(size.opcode, opcode::STATICCALL);
+        assert!(!frame.out_of_gas);
+        assert!(inspector.take_frame(2).out_of_gas);
+    }
+}
@@ -27,14 +27,21 @@
 // IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
 // DEALINGS IN THE SOFTWARE.
 use crate::{
-    types::{CallTraceNode, CallTraceStepStackItem},
+    config::TraceStyle,
+    erc7562::Erc7562Inspector,
+    types::{CallTraceNode, CallTraceStepStackItem, TxExecContext},
+    utils::to_alloy_h256,
     TracingInspectorConfig,
 };
 use alloy_primitives::{Address, Bytes, B256, U256};
-use alloy_rpc_types_trace::geth::{
-    AccountChangeKind, AccountState, CallConfig, CallFrame, DefaultFrame,
-    DiffMode, GethDefaultTracingOptions, PreStateConfig, PreStateFrame,
-    PreStateMode, StructLog,
+use alloy_rpc_types_trace::{
+    geth::{
+        erc7562::{Erc7562Config, Erc7562Frame},
+        AccountChangeKind, AccountState, CallConfig, CallFrame, DefaultFrame,
+        DiffMode, FlatCallConfig, FlatCallFrame, GethDefaultTracingOptions,
+        PreStateConfig, PreStateFrame, PreStateMode, StructLog,
+    },
+    parity::{LocalizedTransactionTrace, TraceOutput, TransactionTrace},
 };
 use revm::{
     database::DatabaseRef,
@@ -240,6 +247,118 @@ impl GethTraceBuilder {
         }
     }
 
+    /// Generate parity-style flat traces for geth's flat call tracer.
+    ///
+    /// Traces are emitted in call order and are localized with the block and
+    /// transaction info of the given [TxExecContext]. Calls to precompiles
+    /// are only included if they were recorded as children, see
+    /// [TracingInspectorConfig::from_flat_call_config].
+    pub fn geth_flat_call_traces(
+        &self, opts: FlatCallConfig, gas_used: u64, ctx: &TxExecContext,
+    ) -> FlatCallFrame {
+        if self.nodes.is_empty() {
+            return Default::default();
+        }
+
+        let style = if opts.convert_parity_errors.unwrap_or_default() {
+            TraceStyle::Parity
+        } else {
+            TraceStyle::Geth
+        };
+
+        let mut traces = Vec::with_capacity(self.nodes.len());
+        self.fill_flat_call_traces(&self.nodes[0], vec![], style, &mut traces);
+
+        // the root call should report the gas used of the transaction
+        match traces[0].result.as_mut() {
+            Some(TraceOutput::Call(output)) => output.gas_used = gas_used,
+            Some(TraceOutput::Create(output)) => output.gas_used = gas_used,
+            None => {}
+        }
+
+        traces
+            .into_iter()
+            .map(|trace| LocalizedTransactionTrace {
+                trace,
+                block_hash: Some(to_alloy_h256(ctx.block_hash)),
+                block_number: Some(ctx.block_height),
+                transaction_hash: Some(to_alloy_h256(ctx.tx_hash)),
+                transaction_position: Some(ctx.tx_index),
+            })
+            .collect()
+    }
+
+    /// Appends the traces of the given node and all its children in call
+    /// order.
+    fn fill_flat_call_traces(
+        &self, node: &CallTraceNode, trace_address: Vec<usize>,
+        style: TraceStyle, traces: &mut Vec<TransactionTrace>,
+    ) {
+        traces
+            .push(node.parity_transaction_trace(trace_address.clone(), style));
+
+        for (i, child) in node.children.iter().enumerate() {
+            let mut child_address = trace_address.clone();
+            child_address.push(i);
+            self.fill_flat_call_traces(
+                &self.nodes[*child],
+                child_address,
+                style,
+                traces,
+            );
+        }
+
+        // selfdestructs are not recorded as individual call traces but are
+        // derived from the call trace and are added as the last subtrace
+        if let Some(selfdestruct) =
+            node.parity_selfdestruct_trace(trace_address)
+        {
+            traces.push(selfdestruct);
+        }
+    }
+
+    /// Generate geth-style traces for the ERC-7562 tracer.
+    ///
+    /// The call frames are built like [Self::geth_call_traces], the opcode
+    /// level data of every frame is taken from the given
+    /// [Erc7562Inspector].
+    pub fn geth_erc7562_traces(
+        &self, opts: Erc7562Config, gas_used: u64,
+        mut inspector: Erc7562Inspector,
+    ) -> Erc7562Frame {
+        if self.nodes.is_empty() {
+            return Default::default();
+        }
+
+        let include_logs = opts.with_log.unwrap_or_default();
+        let mut frames = Vec::with_capacity(self.nodes.len());
+        for (idx, trace) in self.nodes.iter().enumerate() {
+            // include logs only if call and all its parents were successful
+            let include_logs =
+                include_logs && !self.call_or_parent_failed(trace);
+            let mut frame = trace.geth_empty_erc7562_frame(include_logs);
+            inspector.take_frame(idx).fill_frame(&mut frame);
+            frames.push((idx, frame));
+        }
+        frames[0].1.gas_used = gas_used;
+
+        // roll up the child frames to their parent, see
+        // `Self::geth_call_traces`
+        loop {
+            let (idx, frame) = frames.pop().expect("frames not empty");
+            let node = &self.nodes[idx];
+            if let Some(parent) = node.parent {
+                frames[parent].1.calls.insert(0, frame);
+            } else {
+                debug_assert!(
+                    frames.is_empty(),
+                    "only one root node has no parent"
+                );
+                return frame;
+            }
+        }
+    }
+
     /// Returns true if the given trace or any of its parents failed.
     fn call_or_parent_failed(&self, node: &CallTraceNode) -> bool {
         if node.trace.is_error() {
@@ -421,3 +540,112 @@ pub(crate) fn load_account_code<DB: DatabaseRef>(
         })
         .map(Into::into)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{CallKind, CallTrace};
+    use alloy_rpc_types_trace::parity::Action;
+    use cfx_types::H256;
+
+    fn node(
+        idx: usize, parent: Option<usize>, children: Vec<usize>, kind: CallKind,
+    ) -> CallTraceNode {
+        CallTraceNode {
+            parent,
+            children,
+            idx,
+            trace: CallTrace {
+                kind,
+                success: true,
+                gas_limit: 100_000,
+                gas_used: 21_000,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_geth_flat_call_traces() {
+        let mut root = node(0, None, vec![1, 2], CallKind::Call);
+        root.trace.selfdestruct_refund_target = Some(Address::repeat_byte(1));
+        let create = node(1, Some(0), vec![], CallKind::Create);
+        let mut failed = node(2, Some(0), vec![], CallKind::StaticCall);
+        failed.trace.success = false;
+        failed.trace.status = Some(revm_interpreter::InstructionResult::Revert);
+
+        let builder = GethTraceBuilder::new(
+            vec![root, create, failed],
+            TracingInspectorConfig::default_parity(),
+        );
+        let ctx = TxExecContext {
+            tx_gas_limit: 100_000,
+            block_number: 10,
+            block_height: 5,
+            block_hash: H256::repeat_byte(2),
+            tx_hash: H256::repeat_byte(3),
+            tx_index: 1,
+            gas_price: 1,
+            block_timestamp: 0,
+        };
+        let traces = builder.geth_flat_call_traces(
+            FlatCallConfig {
+                convert_parity_errors: Some(true),
+                ..Default::default()
+            },
+            30_000,
+            &ctx,
+        );
+
+        let trace_addresses: Vec<_> = traces
+            .iter()
+            .map(|t| t.trace.trace_address.clone())
+            .collect();
+        assert_eq!(trace_addresses, vec![vec![], vec![0], vec![1], vec![2]]);
+        assert_eq!(traces[0].trace.subtraces, 3);
+        assert_eq!(traces[0].block_number, Some(5));
+        assert_eq!(traces[0].transaction_position, Some(1));
+        assert_eq!(
+            traces[0]
+                .trace
+                .result
+                .as_ref()
+                .and_then(|r| r.as_call())
+                .map(|output| output.gas_used),
+            Some(30_000)
+        );
+        assert!(matches!(traces[1].trace.action, Action::Create(_)));
+        assert!(traces[2].trace.result.is_none());
+        assert_eq!(traces[2].trace.error.as_deref(), Some("Reverted"));
+        assert!(matches!(traces[3].trace.action, Action::Selfdestruct(_)));
+    }
+
+    #[test]
+    fn test_geth_erc7562_traces() {
+        let root = node(0, None, vec![1], CallKind::Call);
+        let mut child = node(1, Some(0), vec![], CallKind::StaticCall);
+        child.trace.gas_used = 5_000;
+        let builder = GethTraceBuilder::new(
+            vec![root, child],
+            TracingInspectorConfig::default_geth(),
+        );
+
+        let mut inspector = Erc7562Inspector::new(&Default::default());
+        inspector.record_call(0, Address::repeat_byte(1), Some(3));
+        inspector.set_out_of_gas(1);
+
+        let frame =
+            builder.geth_erc7562_traces(Default::default(), 30_000, inspector);
+        assert_eq!(frame.gas_used, 30_000);
+        assert_eq!(
+            frame.contract_size[&Address::repeat_byte(1)].contract_size,
+            3
+        );
+        assert!(!frame.out_of_gas);
+        assert_eq!(frame.calls.len(), 1);
+        assert_eq!(frame.calls[0].gas_used, 5_000);
+        assert!(frame.calls[0].out_of_gas);
+        assert!(frame.calls[0].calls.is_empty());
+    }
+}
@@ -1,15 +1,21 @@
 use crate::{
     config::TracingInspectorConfig,
+    erc7562::Erc7562Inspector,
     fourbyte::FourByteInspector,
+    js::{
+        parse_timeout, JsInspector, JsTxContext, TransactionContext,
+        DEFAULT_TIMEOUT,
+    },
     tracing_inspector::TracingInspector,
     types::{LogCallOrder, TxExecContext},
     utils::{to_alloy_address, to_alloy_h256, to_alloy_u256},
 };
 use alloy_primitives::{Address, Bytes, LogData};
 use alloy_rpc_types_trace::geth::{
-    CallConfig, GethDebugBuiltInTracerType, GethDebugBuiltInTracerType::*,
-    GethDebugTracerType, GethDebugTracingOptions, GethTrace, NoopFrame,
-    PreStateConfig,
+    erc7562::Erc7562Config, mux::MuxFrame, CallConfig, FlatCallConfig,
+    GethDebugBuiltInTracerType, GethDebugBuiltInTracerType::*,
+    GethDebugTracerConfig, GethDebugTracerType, GethDebugTracingOptions,
+    GethTrace, NoopFrame, PreStateConfig,
 };
 use cfx_executor::{
     machine::Machine,
@@ -18,19 +24,28 @@ use cfx_executor::{
         OpcodeTracer, SetAuthTracer, StorageTracer,
     },
     stack::{FrameResult, FrameReturn},
+    state::State as CfxState,
 };
 use cfx_types::{Space, H160};
 use cfx_vm_types::{ActionParams, CallType, Error, InterpreterInfo};
 use revm::{database::InMemoryDB, state::EvmState as State};
 use revm_interpreter::{Gas, InstructionResult, InterpreterResult};
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 pub struct GethTracer {
     inner: TracingInspector,
     //
     fourbyte_inspector: FourByteInspector,
     //
+    erc7562_inspector: Erc7562Inspector,
+    // child tracers of the mux tracer
+    mux_tracers: Vec<(GethDebugTracerType, GethTracer)>,
+    // javascript tracer, or the error of its initialization
+    js_inspector: Option<Result<JsInspector, String>>,
+    //
+    tx_exec_context: TxExecContext,
+    //
     tx_gas_limit: u64, // tx level gas limit
     //
     gas_left: u64, // update in call_result/create_result
@@ -48,10 +63,44 @@ impl GethTracer {
         opts: GethDebugTracingOptions,
     ) -> Self {
         let TxExecContext { tx_gas_limit, .. } = tx_exec_context;
+        let mut erc7562_config = Erc7562Config::default();
+        let mut mux_tracers = Vec::new();
+        let mut js_inspector = None;
         let config = match opts.tracer {
             Some(GethDebugTracerType::BuiltInTracer(builtin_tracer)) => {
                 match builtin_tracer {
-                    FourByteTracer | NoopTracer | MuxTracer => {
+                    FourByteTracer | NoopTracer => {
+                        TracingInspectorConfig::none()
+                    }
+                    MuxTracer => {
+                        let c = opts
+                            .tracer_config
+                            .clone()
+                            .into_mux_config()
+                            .expect("should success");
+                        for (tracer_type, tracer_config) in c.0 {
+                            // nested mux tracers are rejected by the rpc
+                            if tracer_type
+                                == GethDebugTracerType::BuiltInTracer(MuxTracer)
+                            {
+                                continue;
+                            }
+                            let child_opts = GethDebugTracingOptions {
+                                config: opts.config.clone(),
+                                tracer: Some(tracer_type.clone()),
+                                tracer_config: tracer_config
+                                    .unwrap_or_default(),
+                                timeout: opts.timeout.clone(),
+                            };
+                            mux_tracers.push((
+                                tracer_type,
+                                GethTracer::new(
+                                    tx_exec_context.clone(),
+                                    machine.clone(),
+                                    child_opts,
+                                ),
+                            ));
+                        }
                         TracingInspectorConfig::none()
                     }
                     CallTracer => {
@@ -62,7 +111,14 @@ impl GethTracer {
                             .expect("should success");
                         TracingInspectorConfig::from_geth_call_config(&c)
                     }
-                    FlatCallTracer => TracingInspectorConfig::none(),
+                    FlatCallTracer => {
+                        let c = opts
+                            .tracer_config
+                            .clone()
+                            .into_flat_call_config()
+                            .expect("should success");
+                        TracingInspectorConfig::from_flat_call_config(&c)
+                    }
                     PreStateTracer => {
                         let c = opts
                             .tracer_config
@@ -71,18 +127,39 @@ impl GethTracer {
                             .expect("should success");
                         TracingInspectorConfig::from_geth_prestate_config(&c)
                     }
-                    Erc7562Tracer => TracingInspectorConfig::none(),
+                    Erc7562Tracer => {
+                        erc7562_config =
+                            erc7562_config_from(&opts).expect("should success");
+                        TracingInspectorConfig::from_erc7562_config(
+                            &erc7562_config,
+                        )
+                    }
                 }
             }
-            Some(GethDebugTracerType::JsTracer(_)) => {
+            Some(GethDebugTracerType::JsTracer(ref code)) => {
+                let transaction_context = TransactionContext {
+                    block_hash: Some(to_alloy_h256(tx_exec_context.block_hash)),
+                    tx_index: Some(tx_exec_context.tx_index as usize),
+                    tx_hash: Some(to_alloy_h256(tx_exec_context.tx_hash)),
+                };
+                js_inspector =
+                    Some(new_js_inspector(code, &opts, transaction_context));
                 TracingInspectorConfig::none()
             }
             None => TracingInspectorConfig::from_geth_config(&opts.config),
         };
 
         Self {
-            inner: TracingInspector::new(config, machine, tx_exec_context),
+            inner: TracingInspector::new(
+                config,
+                machine,
+                tx_exec_context.clone(),
+            ),
             fourbyte_inspector: FourByteInspector::new(),
+            erc7562_inspector: Erc7562Inspector::new(&erc7562_config),
+            mux_tracers,
+            js_inspector,
+            tx_exec_context,
             tx_gas_limit,
             depth: 0,
             gas_left: tx_gas_limit,
@@ -97,10 +174,8 @@ impl GethTracer {
                 GethDebugTracerType::BuiltInTracer(builtin_tracer) => {
                     Some(builtin_tracer)
                 }
-                GethDebugTracerType::JsTracer(_) => {
-                    // not supported
-                    Some(NoopTracer)
-                }
+                // handled by the js inspector
+                GethDebugTracerType::JsTracer(_) => None,
             },
             None => None,
         }
@@ -114,13 +189,58 @@ impl GethTracer {
         self.opts.tracer_config.clone().into_pre_state_config().ok()
     }
 
+    fn flat_call_config(&self) -> Option<FlatCallConfig> {
+        self.opts.tracer_config.clone().into_flat_call_config().ok()
+    }
+
+    fn erc7562_config(&self) -> Option<Erc7562Config> {
+        erc7562_config_from(&self.opts)
+    }
+
     pub fn is_fourbyte_tracer(&self) -> bool {
         self.tracer_type() == Some(FourByteTracer)
     }
 
+    pub fn is_mux_tracer(&self) -> bool {
+        self.tracer_type() == Some(MuxTracer)
+    }
+
+    pub fn is_erc7562_tracer(&self) -> bool {
+        self.tracer_type() == Some(Erc7562Tracer)
+    }
+
     pub fn gas_used(&self) -> u64 { self.tx_gas_limit - self.gas_left }
 
-    pub fn drain(self) -> GethTrace {
+    /// Records a step of this tracer, the child tracers of the mux tracer
+    /// are not included.
+    fn record_step(&mut self, interp: &dyn InterpreterInfo) {
+        self.inner
+            .gas_inspector
+            .set_gas_remainning(interp.gas_remainning().as_u64());
+
+        if self.inner.config.record_steps {
+            self.inner.start_step(interp, self.depth as u64);
+        }
+
+        if self.is_erc7562_tracer() {
+            self.erc7562_inspector
+                .step(self.inner.last_trace_idx(), interp);
+        }
+    }
+
+    /// Returns the trace, or the error of a javascript tracer.
+    pub fn drain(self) -> Result<GethTrace, String> {
+        if let Some(js_inspector) = self.js_inspector {
+            let tx = JsTxContext {
+                gas_limit: self.tx_gas_limit,
+                gas_used: self.tx_gas_limit - self.gas_left,
+                gas_price: self.tx_exec_context.gas_price,
+                block_number: self.tx_exec_context.block_number,
+                timestamp: self.tx_exec_context.block_timestamp,
+            };
+            return js_inspector?.json_result(tx).map(GethTrace::JS);
+        }
+
         let trace = match self.tracer_type() {
             Some(t) => match t {
                 FourByteTracer => self.fourbyte_inspector.drain(),
@@ -146,9 +266,41 @@ impl GethTracer {
                         .unwrap();
                     GethTrace::PreStateTracer(frame)
                 }
-                NoopTracer | MuxTracer | FlatCallTracer | Erc7562Tracer => {
-                    GethTrace::NoopTracer(NoopFrame::default())
+                FlatCallTracer => {
+                    let gas_used = self.gas_used();
+                    let opts =
+                        self.flat_call_config().expect("should have config");
+                    let frame =
+                        self.inner.into_geth_builder().geth_flat_call_traces(
+                            opts,
+                            gas_used,
+                            &self.tx_exec_context,
+                        );
+                    GethTrace::FlatCallTracer(frame)
+                }
+                Erc7562Tracer => {
+                    let gas_used = self.gas_used();
+                    let opts =
+                        self.erc7562_config().expect("should have config");
+                    let frame =
+                        self.inner.into_geth_builder().geth_erc7562_traces(
+                            opts,
+                            gas_used,
+                            self.erc7562_inspector,
+                        );
+                    GethTrace::Erc7562Tracer(frame)
+                }
+                MuxTracer => {
+                    let frame = self
+                        .mux_tracers
+                        .into_iter()
+                        .map(|(tracer_type, tracer)| {
+                            Ok((tracer_type, tracer.drain()?))
+                        })
+                        .collect::<Result<_, String>>()?;
+                    GethTrace::MuxTracer(MuxFrame(frame))
                 }
+                NoopTracer => GethTrace::NoopTracer(NoopFrame::default()),
             },
             None => {
                 let gas_used = self.gas_used();
@@ -167,7 +319,7 @@ impl GethTracer {
             }
         };
 
-        trace
+        Ok(trace)
     }
 }
 
@@ -180,7 +332,7 @@ impl DrainTrace for GethTracer {
 pub struct GethTraceKey;
 
 impl typemap::Key for GethTraceKey {
-    type Value = GethTrace;
+    type Value = Result<GethTrace, String>;
 }
 
 impl CheckpointTracer for GethTracer {}
@@ -193,11 +345,25 @@ impl SetAuthTracer for GethTracer {}
 
 impl CallTracer for GethTracer {
     fn record_call(&mut self, params: &ActionParams) {
+        if self.is_mux_tracer() {
+            for (_, tracer) in self.mux_tracers.iter_mut() {
+                tracer.record_call(params);
+            }
+            return;
+        }
+
         if self.is_fourbyte_tracer() {
             self.fourbyte_inspector.record_call(params);
             return;
         }
 
+        if let Some(Ok(js_inspector)) = &mut self.js_inspector {
+            let inner = &self.inner;
+            js_inspector
+                .register_precompiles(|| inner.precompiles(params.space));
+            js_inspector.call(params);
+        }
+
         let gas_limit = params.gas.as_u64();
         self.gas_stack.push(gas_limit);
 
@@ -209,6 +375,22 @@ impl CallTracer for GethTracer {
             _ => (params.sender, params.address),
         };
 
+        if self.is_erc7562_tracer() {
+            if let Some(parent) = self.inner.active_trace().map(|t| t.idx) {
+                let code_size = (!self.inner.is_precompile_call(
+                    &params.code_address,
+                    Default::default(),
+                    params.space,
+                ))
+                .then(|| params.code.as_ref().map_or(0, |code| code.len()));
+                self.erc7562_inspector.record_call(
+                    parent,
+                    to_alloy_address(params.code_address),
+                    code_size,
+                );
+            }
+        }
+
         let value = if matches!(params.call_type, CallType::DelegateCall)
             && self.inner.active_trace().is_some()
         {
@@ -244,10 +426,21 @@ impl CallTracer for GethTracer {
     }
 
     fn record_call_result(&mut self, result: &FrameResult) {
+        if self.is_mux_tracer() {
+            for (_, tracer) in self.mux_tracers.iter_mut() {
+                tracer.record_call_result(result);
+            }
+            return;
+        }
+
         if self.is_fourbyte_tracer() {
             return;
         }
 
+        if let Some(Ok(js_inspector)) = &mut self.js_inspector {
+            js_inspector.call_end(result);
+        }
+
         self.depth -= 1;
         let mut gas_spent = self.gas_stack.pop().expect("should have value");
 
@@ -262,6 +455,13 @@ impl CallTracer for GethTracer {
             self.inner.gas_inspector.set_gas_remainning(0);
         }
 
+        if self.is_erc7562_tracer()
+            && instruction_result == InstructionResult::OutOfGas
+        {
+            self.erc7562_inspector
+                .set_out_of_gas(self.inner.last_trace_idx());
+        }
+
         let output = result
             .as_ref()
             .map(|f| Bytes::from(f.return_data.to_vec()))
@@ -277,10 +477,24 @@ impl CallTracer for GethTracer {
     }
 
     fn record_create(&mut self, params: &ActionParams) {
+        if self.is_mux_tracer() {
+            for (_, tracer) in self.mux_tracers.iter_mut() {
+                tracer.record_create(params);
+            }
+            return;
+        }
+
         if self.is_fourbyte_tracer() {
             return;
         }
 
+        if let Some(Ok(js_inspector)) = &mut self.js_inspector {
+            let inner = &self.inner;
+            js_inspector
+                .register_precompiles(|| inner.precompiles(params.space));
+            js_inspector.create(params);
+        }
+
         let gas_limit = params.gas.as_u64();
         self.gas_stack.push(gas_limit);
 
@@ -311,10 +525,21 @@ impl CallTracer for GethTracer {
     }
 
     fn record_create_result(&mut self, result: &FrameResult) {
+        if self.is_mux_tracer() {
+            for (_, tracer) in self.mux_tracers.iter_mut() {
+                tracer.record_create_result(result);
+            }
+            return;
+        }
+
         if self.is_fourbyte_tracer() {
             return;
         }
 
+        if let Some(Ok(js_inspector)) = &mut self.js_inspector {
+            js_inspector.call_end(result);
+        }
+
         self.depth -= 1;
         let mut gas_spent = self.gas_stack.pop().expect("should have value");
 
@@ -329,6 +554,13 @@ impl CallTracer for GethTracer {
             self.inner.gas_inspector.set_gas_remainning(0);
         }
 
+        if self.is_erc7562_tracer()
+            && instruction_result == InstructionResult::OutOfGas
+        {
+            self.erc7562_inspector
+                .set_out_of_gas(self.inner.last_trace_idx());
+        }
+
         let output = result
             .as_ref()
             .map(|f| Bytes::from(f.return_data.to_vec()))
@@ -354,28 +586,70 @@ impl CallTracer for GethTracer {
 
 impl OpcodeTracer for GethTracer {
     fn do_trace_opcode(&self, enabled: &mut bool) {
-        if self.inner.config.record_steps {
+        if self.inner.config.record_steps
+            || self.is_erc7562_tracer()
+            || self.js_inspector.is_some()
+        {
             *enabled |= true;
         }
+
+        for (_, tracer) in self.mux_tracers.iter() {
+            tracer.do_trace_opcode(enabled);
+        }
     }
 
     fn initialize_interp(&mut self, gas_limit: cfx_types::U256) {
+        for (_, tracer) in self.mux_tracers.iter_mut() {
+            tracer.initialize_interp(gas_limit);
+        }
+
         self.inner
             .gas_inspector
             .set_gas_remainning(gas_limit.as_u64());
     }
 
     fn step(&mut self, interp: &dyn InterpreterInfo) {
-        self.inner
-            .gas_inspector
-            .set_gas_remainning(interp.gas_remainning().as_u64());
+        for (_, tracer) in self.mux_tracers.iter_mut() {
+            tracer.step(interp);
+        }
 
-        if self.inner.config.record_steps {
-            self.inner.start_step(interp, self.depth as u64);
+        self.record_step(interp);
+    }
+
+    fn step_with_state(
+        &mut self, interp: &dyn InterpreterInfo, state: &CfxState,
+    ) {
+        // the javascript tracers of the mux tracer read the state as well
+        for (_, tracer) in self.mux_tracers.iter_mut() {
+            tracer.step_with_state(interp, state);
+        }
+
+        self.record_step(interp);
+
+        if let Some(Ok(js_inspector)) = &mut self.js_inspector {
+            js_inspector.step(interp, self.depth as u64);
+        }
+    }
+
+    fn step_cost(
+        &mut self, interp: &dyn InterpreterInfo, cost: cfx_types::U256,
+        state: &CfxState,
+    ) {
+        for (_, tracer) in self.mux_tracers.iter_mut() {
+            tracer.step_cost(interp, cost, state);
+        }
+
+        if let Some(Ok(js_inspector)) = &mut self.js_inspector {
+            let cost = u64::try_from(cost).unwrap_or(u64::MAX);
+            js_inspector.step_cost(interp, cost, state);
         }
     }
 
     fn step_end(&mut self, interp: &dyn InterpreterInfo) {
+        for (_, tracer) in self.mux_tracers.iter_mut() {
+            tracer.step_end(interp);
+        }
+
         let remainning = interp.gas_remainning().as_u64();
         let last_gas_cost = self
             .inner
@@ -389,12 +663,20 @@ impl OpcodeTracer for GethTracer {
         if self.inner.config.record_steps {
             self.inner.fill_step_on_step_end(interp);
         }
+
+        if self.is_erc7562_tracer() {
+            self.erc7562_inspector.step_end(interp);
+        }
     }
 
     fn log(
-        &mut self, _address: &cfx_types::Address,
-        topics: &Vec<cfx_types::H256>, data: &[u8],
+        &mut self, address: &cfx_types::Address, topics: &Vec<cfx_types::H256>,
+        data: &[u8],
     ) {
+        for (_, tracer) in self.mux_tracers.iter_mut() {
+            tracer.log(address, topics, data);
+        }
+
         if self.inner.config.record_logs {
             let trace_idx = self.inner.last_trace_idx();
             let trace = &mut self.inner.traces.arena[trace_idx];
@@ -407,13 +689,24 @@ impl OpcodeTracer for GethTracer {
     }
 
     fn selfdestruct(
-        &mut self, space: Space, _contract: &cfx_types::Address,
-        target: &cfx_types::Address, _value: cfx_types::U256,
+        &mut self, space: Space, contract: &cfx_types::Address,
+        target: &cfx_types::Address, value: cfx_types::U256,
     ) {
+        if self.is_mux_tracer() {
+            for (_, tracer) in self.mux_tracers.iter_mut() {
+                tracer.selfdestruct(space, contract, target, value);
+            }
+            return;
+        }
+
         if self.is_fourbyte_tracer() {
             return;
         }
 
+        if let Some(Ok(js_inspector)) = &mut self.js_inspector {
+            js_inspector.selfdestruct(contract, target, value);
+        }
+
         let trace_idx = self.inner.last_trace_idx();
         let trace = &mut self.inner.traces.arena[trace_idx].trace;
         trace.selfdestruct_refund_target =
@@ -421,6 +714,120 @@ impl OpcodeTracer for GethTracer {
     }
 }
 
+/// Parses the [Erc7562Config] of the given options, a missing config is
+/// treated as the default one.
+pub fn erc7562_config_from(
+    opts: &GethDebugTracingOptions,
+) -> Option<Erc7562Config> {
+    if opts.tracer_config.is_null() {
+        return Some(Erc7562Config::default());
+    }
+    opts.tracer_config.clone().from_value().ok()
+}
+
+/// Checks the tracer of the options and its config before tracing.
+pub fn check_tracing_options(
+    opts: &GethDebugTracingOptions,
+) -> Result<(), String> {
+    match &opts.tracer {
+        Some(GethDebugTracerType::BuiltInTracer(tracer)) => {
+            check_tracer_config(*tracer, opts.tracer_config.clone())
+        }
+        Some(GethDebugTracerType::JsTracer(code)) => {
+            check_js_tracer(code, opts)
+        }
+        None => Ok(()),
+    }
+}
+
+/// Checks the config of a built-in tracer.
+fn check_tracer_config(
+    tracer: GethDebugBuiltInTracerType, config: GethDebugTracerConfig,
+) -> Result<(), String> {
+    match tracer {
+        FourByteTracer | NoopTracer => {}
+        CallTracer => {
+            config.into_call_config().map_err(|err| err.to_string())?;
+        }
+        PreStateTracer => {
+            config
+                .into_pre_state_config()
+                .map_err(|err| err.to_string())?;
+        }
+        FlatCallTracer => {
+            config
+                .into_flat_call_config()
+                .map_err(|err| err.to_string())?;
+        }
+        Erc7562Tracer => {
+            if !config.is_null() {
+                config
+                    .from_value::<Erc7562Config>()
+                    .map_err(|err| err.to_string())?;
+            }
+        }
+        MuxTracer => {
+            let mux_config =
+                config.into_mux_config().map_err(|err| err.to_string())?;
+            for (tracer, config) in mux_config.0 {
+                let opts = GethDebugTracingOptions {
+                    tracer_config: config.unwrap_or_default(),
+                    ..Default::default()
+                };
+                match tracer {
+                    GethDebugTracerType::BuiltInTracer(MuxTracer) => {
+                        return Err(
+                            "nested muxTracer is not supported".to_string()
+                        );
+                    }
+                    GethDebugTracerType::BuiltInTracer(tracer) => {
+                        check_tracer_config(tracer, opts.tracer_config)?;
+                    }
+                    GethDebugTracerType::JsTracer(code) => {
+                        check_js_tracer(&code, &opts)?;
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Compiles the javascript tracer, so that invalid code, config or timeout
+/// can be rejected before tracing. Its `setup` function is only called by the
+/// tracing.
+pub fn check_js_tracer(
+    code: &str, opts: &GethDebugTracingOptions,
+) -> Result<(), String> {
+    JsInspector::check(
+        code.to_string(),
+        opts.tracer_config.clone().into_json(),
+        js_timeout(opts)?,
+    )
+    .map_err(|err| err.to_string())
+}
+
+fn js_timeout(opts: &GethDebugTracingOptions) -> Result<Duration, String> {
+    match opts.timeout {
+        Some(ref timeout) => parse_timeout(timeout),
+        None => Ok(DEFAULT_TIMEOUT),
+    }
+}
+
+fn new_js_inspector(
+    code: &str, opts: &GethDebugTracingOptions,
+    transaction_context: TransactionContext,
+) -> Result<JsInspector, String> {
+    let timeout = js_timeout(opts)?;
+    JsInspector::new(
+        code.to_string(),
+        opts.tracer_config.clone().into_json(),
+        transaction_context,
+        timeout,
+    )
+    .map_err(|err| err.to_string())
+}
+
 pub fn to_instruction_result(frame_result: &FrameResult) -> InstructionResult {
     let result = match frame_result {
         Ok(r) => match r.apply_state {
@@ -465,3 +872,115 @@ pub fn to_instruction_result(frame_result: &FrameResult) -> InstructionResult {
     };
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cfx_executor::machine::VmFactory;
+    use cfx_types::{Address as CfxAddress, H256, U256};
+    use cfx_vm_types::ReturnData;
+
+    fn tracer(opts: GethDebugTracingOptions) -> GethTracer {
+        let machine = Machine::new_with_builtin(
+            Default::default(),
+            VmFactory::new(1024 * 32),
+        );
+        let ctx = TxExecContext {
+            tx_gas_limit: 100_000,
+            block_number: 10,
+            block_height: 5,
+            block_hash: H256::repeat_byte(1),
+            tx_hash: H256::repeat_byte(2),
+            tx_index: 0,
+            gas_price: 1,
+            block_timestamp: 0,
+        };
+        GethTracer::new(ctx, Arc::new(machine), opts)
+    }
+
+    fn mux_opts(config: serde_json::Value) -> GethDebugTracingOptions {
+        GethDebugTracingOptions {
+            tracer: Some(GethDebugTracerType::BuiltInTracer(MuxTracer)),
+            tracer_config: config.into(),
+            ..Default::default()
+        }
+    }
+
+    fn call(tracer: &mut GethTracer, data: Vec<u8>) {
+        let params = ActionParams {
+            sender: CfxAddress::repeat_byte(1),
+            address: CfxAddress::repeat_byte(2),
+            code_address: CfxAddress::repeat_byte(2),
+            gas: U256::from(50_000),
+            data: Some(data),
+            space: Space::Ethereum,
+            ..Default::default()
+        };
+        tracer.record_call(&params);
+        tracer.record_call_result(&Ok(FrameReturn {
+            space: Space::Ethereum,
+            gas_left: U256::from(20_000),
+            apply_state: true,
+            return_data: ReturnData::empty(),
+            create_address: None,
+            substate: None,
+        }));
+    }
+
+    #[test]
+    fn test_mux_tracer() {
+        let mut tracer = tracer(mux_opts(serde_json::json!({
+            "callTracer": { "onlyTopCall": true },
+            "4byteTracer": null,
+            "noopTracer": null,
+            // nested mux tracers are ignored
+            "muxTracer": {},
+        })));
+        assert!(tracer.is_mux_tracer());
+        assert_eq!(tracer.mux_tracers.len(), 3);
+
+        call(&mut tracer, vec![0xaa, 0xbb, 0xcc, 0xdd, 0x01, 0x02]);
+        let trace = tracer.drain().unwrap();
+        let GethTrace::MuxTracer(MuxFrame(frames)) = trace else {
+            panic!("unexpected trace {:?}", trace);
+        };
+        assert_eq!(frames.len(), 3);
+
+        let call_frame =
+            &frames[&GethDebugTracerType::BuiltInTracer(CallTracer)];
+        let GethTrace::CallTracer(call_frame) = call_frame else {
+            panic!("unexpected trace {:?}", call_frame);
+        };
+        assert_eq!(call_frame.from, Address::repeat_byte(1));
+        assert_eq!(call_frame.to, Some(Address::repeat_byte(2)));
+        // the gas used by the transaction, not the gas spent by the call
+        assert_eq!(call_frame.gas_used, alloy_primitives::U256::from(80_000));
+
+        let fourbyte_frame =
+            &frames[&GethDebugTracerType::BuiltInTracer(FourByteTracer)];
+        let GethTrace::FourByteTracer(fourbyte_frame) = fourbyte_frame else {
+            panic!("unexpected trace {:?}", fourbyte_frame);
+        };
+        assert_eq!(fourbyte_frame.0.get("0xaabbccdd-2"), Some(&1));
+
+        assert!(matches!(
+            frames[&GethDebugTracerType::BuiltInTracer(NoopTracer)],
+            GethTrace::NoopTracer(_)
+        ));
+    }
+
+    #[test]
+    fn test_mux_tracer_js_error() {
+        // the error of a child tracer is the error of the mux tracer
+        let code =
+            "{result: function() { throw 'failed' }, fault: function() {}}";
+        let mut config = serde_json::Map::new();
+        config.insert(code.to_string(), serde_json::Value::Null);
+        config.insert("callTracer".to_string(), serde_json::Value::Null);
+        let mut tracer = tracer(mux_opts(config.into()));
+        assert_eq!(tracer.mux_tracers.len(), 2);
+
+        call(&mut tracer, vec![]);
+        assert!(tracer.drain().is_err());
+    }
+}
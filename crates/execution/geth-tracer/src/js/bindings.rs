@@ -0,0 +1,993 @@
+// Copyright 2023-2024 Paradigm.xyz
+// This file is part of reth.
+// Reth is a modular, contributor-friendly and blazing-fast implementation of
+// the Ethereum protocol
+
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Type bindings for js tracing inspector
+
+use super::{
+    builtins::{
+        address_to_byte_array, address_to_byte_array_value, bytes_to_address,
+        bytes_to_hash, from_buf_value, to_bigint, to_byte_array,
+        to_byte_array_value,
+    },
+    TransactionContext,
+};
+use crate::utils::{from_alloy_address, to_alloy_u256};
+use alloy_primitives::{Address, Bytes, B256, U256};
+use boa_engine::{
+    js_string,
+    native_function::NativeFunction,
+    object::{builtins::JsUint8Array, FunctionObjectBuilder},
+    Context, JsArgs, JsError, JsNativeError, JsObject, JsResult, JsValue,
+};
+use boa_gc::{empty_trace, Finalize, Trace};
+use cfx_executor::state::State;
+use cfx_statedb::Result as DbResult;
+use cfx_types::{AddressSpaceUtil, Space, U256 as CfxU256};
+use revm_bytecode::{
+    opcode::{PUSH0, PUSH32},
+    OpCode,
+};
+use std::{cell::RefCell, rc::Rc};
+
+/// A macro that creates a native function that returns via [JsValue::from]
+macro_rules! js_value_getter {
+    ($value:ident, $ctx:ident) => {
+        FunctionObjectBuilder::new(
+            $ctx.realm(),
+            NativeFunction::from_copy_closure(move |_this, _args, _ctx| {
+                Ok(JsValue::from($value))
+            }),
+        )
+        .length(0)
+        .build()
+    };
+}
+
+/// A macro that creates a native function that returns a captured JsValue
+macro_rules! js_value_capture_getter {
+    ($value:ident, $ctx:ident) => {
+        FunctionObjectBuilder::new(
+            $ctx.realm(),
+            NativeFunction::from_copy_closure_with_captures(
+                move |_this, _args, input, _ctx| {
+                    Ok(JsValue::from(input.clone()))
+                },
+                $value,
+            ),
+        )
+        .length(0)
+        .build()
+    };
+}
+
+/// A wrapper for a value that can be garbage collected, but will not give
+/// access to the value if it has been dropped via its guard.
+///
+/// This is used to allow the JS tracer functions to access values at a certain
+/// point during inspection by ref without having to clone them and capture
+/// them in the js object.
+///
+/// JS tracer functions get access to evm internals via objects or function
+/// arguments, for example `function step(log,evm)` where log has an object
+/// `stack` that has a function `peek(number)` that returns a value from the
+/// stack.
+///
+/// These functions could get garbage collected, however the data accessed by
+/// the function is supposed to be ephemeral and only valid for the duration of
+/// the function call.
+///
+/// This type supports garbage collection of (rust) references and prevents
+/// access to the value if it has been dropped.
+#[derive(Debug)]
+struct GuardedNullableGc<Val: 'static> {
+    /// The lifetime is a lie to make it possible to use a reference in boa
+    /// which requires 'static
+    inner: Rc<RefCell<Option<Guarded<'static, Val>>>>,
+}
+
+impl<Val: 'static> GuardedNullableGc<Val> {
+    /// Creates a garbage collectible value to the given reference.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the guard is dropped before the value is
+    /// dropped.
+    fn new_ref(val: &Val) -> (Self, GcGuard<'_, Val>) {
+        Self::new(Guarded::Ref(val))
+    }
+
+    /// Creates a garbage collectible value to the given owned value.
+    fn new_owned<'a>(val: Val) -> (Self, GcGuard<'a, Val>) {
+        Self::new(Guarded::Owned(val))
+    }
+
+    fn new(val: Guarded<'_, Val>) -> (Self, GcGuard<'_, Val>) {
+        let inner = Rc::new(RefCell::new(Some(val)));
+        let guard = GcGuard {
+            inner: Rc::clone(&inner),
+        };
+
+        // SAFETY: guard enforces that the value is removed from the refcell
+        // before it is dropped.
+        #[allow(clippy::missing_transmute_annotations)]
+        let this = Self {
+            inner: unsafe { std::mem::transmute(inner) },
+        };
+
+        (this, guard)
+    }
+
+    /// Executes the given closure with a reference to the inner value if it is
+    /// still present.
+    fn with_inner<F, R>(&self, f: F) -> Option<R>
+    where F: FnOnce(&Val) -> R {
+        self.inner.borrow().as_ref().map(|guard| f(guard.as_ref()))
+    }
+}
+
+impl<Val: 'static> Clone for GuardedNullableGc<Val> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Rc::clone(&self.inner),
+        }
+    }
+}
+
+impl<Val: 'static> Finalize for GuardedNullableGc<Val> {}
+
+unsafe impl<Val: 'static> Trace for GuardedNullableGc<Val> {
+    empty_trace!();
+}
+
+/// A value that is either a reference or an owned value.
+#[derive(Debug)]
+enum Guarded<'a, T> {
+    Ref(&'a T),
+    Owned(T),
+}
+
+impl<T> Guarded<'_, T> {
+    #[inline]
+    fn as_ref(&self) -> &T {
+        match self {
+            Guarded::Ref(val) => val,
+            Guarded::Owned(val) => val,
+        }
+    }
+}
+
+/// Guard the inner value, once this value is dropped the inner value is also
+/// removed.
+///
+/// This type guarantees that it never outlives the wrapped value.
+#[derive(Debug)]
+#[must_use]
+pub(crate) struct GcGuard<'a, Val> {
+    inner: Rc<RefCell<Option<Guarded<'a, Val>>>>,
+}
+
+impl<'a, Val> Drop for GcGuard<'a, Val> {
+    fn drop(&mut self) { self.inner.borrow_mut().take(); }
+}
+
+/// The Log object that is passed to the javascript inspector.
+#[derive(Debug)]
+pub(crate) struct StepLog {
+    /// Stack before step execution
+    pub(crate) stack: StackRef,
+    /// Opcode to be executed
+    pub(crate) op: OpObj,
+    /// All allocated memory in a step
+    pub(crate) memory: MemoryRef,
+    /// Program counter before step execution
+    pub(crate) pc: u64,
+    /// Remaining gas before step execution
+    pub(crate) gas_remaining: u64,
+    /// Gas spent by the current call before the step execution
+    pub(crate) cost: u64,
+    /// Call depth
+    pub(crate) depth: u64,
+    /// Gas refund counter before step execution
+    pub(crate) refund: u64,
+    /// returns information about the error if one occurred, otherwise returns
+    /// undefined
+    pub(crate) error: Option<String>,
+    /// The contract object available to the js inspector
+    pub(crate) contract: Contract,
+}
+
+impl StepLog {
+    /// Converts the contract object into a js object
+    ///
+    /// Caution: this expects a global property `bigint` to be present.
+    pub(crate) fn into_js_object(
+        self, ctx: &mut Context,
+    ) -> JsResult<JsObject> {
+        let Self {
+            stack,
+            op,
+            memory,
+            pc,
+            gas_remaining: gas,
+            cost,
+            depth,
+            refund,
+            error,
+            contract,
+        } = self;
+        let obj = JsObject::default();
+
+        // fields
+        let op = op.into_js_object(ctx)?;
+        let memory = memory.into_js_object(ctx)?;
+        let stack = stack.into_js_object(ctx)?;
+        let contract = contract.into_js_object(ctx)?;
+
+        obj.set(js_string!("op"), op, false, ctx)?;
+        obj.set(js_string!("memory"), memory, false, ctx)?;
+        obj.set(js_string!("stack"), stack, false, ctx)?;
+        obj.set(js_string!("contract"), contract, false, ctx)?;
+
+        // methods
+        let error = if let Some(error) = error {
+            JsValue::from(js_string!(error))
+        } else {
+            JsValue::undefined()
+        };
+        let get_error = js_value_capture_getter!(error, ctx);
+        let get_pc = js_value_getter!(pc, ctx);
+        let get_gas = js_value_getter!(gas, ctx);
+        let get_cost = js_value_getter!(cost, ctx);
+        let get_refund = js_value_getter!(refund, ctx);
+        let get_depth = js_value_getter!(depth, ctx);
+
+        obj.set(js_string!("getPC"), get_pc, false, ctx)?;
+        obj.set(js_string!("getError"), get_error, false, ctx)?;
+        obj.set(js_string!("getGas"), get_gas, false, ctx)?;
+        obj.set(js_string!("getCost"), get_cost, false, ctx)?;
+        obj.set(js_string!("getDepth"), get_depth, false, ctx)?;
+        obj.set(js_string!("getRefund"), get_refund, false, ctx)?;
+
+        Ok(obj)
+    }
+}
+
+/// Represents the memory object
+#[derive(Clone, Debug)]
+pub(crate) struct MemoryRef(GuardedNullableGc<Vec<u8>>);
+
+impl MemoryRef {
+    /// Creates a new memory reference
+    pub(crate) fn new(mem: &Vec<u8>) -> (Self, GcGuard<'_, Vec<u8>>) {
+        let (inner, guard) = GuardedNullableGc::new_ref(mem);
+        (Self(inner), guard)
+    }
+
+    /// Creates an empty memory, used when the memory of the step is no longer
+    /// available.
+    pub(crate) fn empty() -> Self {
+        let (inner, _guard) = GuardedNullableGc::new_owned(Vec::new());
+        Self(inner)
+    }
+
+    fn len(&self) -> usize {
+        self.0.with_inner(|mem| mem.len()).unwrap_or_default()
+    }
+
+    fn slice(&self, offset: usize, size: usize) -> Vec<u8> {
+        self.0
+            .with_inner(|mem| mem[offset..offset + size].to_vec())
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn into_js_object(
+        self, ctx: &mut Context,
+    ) -> JsResult<JsObject> {
+        let obj = JsObject::default();
+        let len = self.len();
+
+        let length = FunctionObjectBuilder::new(
+            ctx.realm(),
+            NativeFunction::from_copy_closure(move |_this, _args, _ctx| {
+                Ok(JsValue::from(len as u64))
+            }),
+        )
+        .length(0)
+        .build();
+
+        // slice returns the requested range of memory as a byte slice.
+        let slice = FunctionObjectBuilder::new(
+            ctx.realm(),
+            NativeFunction::from_copy_closure_with_captures(
+                move |_this, args, memory, ctx| {
+                    let start = args.get_or_undefined(0).to_number(ctx)?;
+                    let end = args.get_or_undefined(1).to_number(ctx)?;
+                    if end < start || start < 0. || (end as usize) > memory.len()
+                    {
+                        return Err(JsError::from_native(
+                            JsNativeError::typ().with_message(format!(
+                                "tracer accessed out of bound memory: offset {start}, end {end}"
+                            )),
+                        ));
+                    }
+                    let start = start as usize;
+                    let size = end as usize - start;
+                    to_byte_array_value(memory.slice(start, size), ctx)
+                },
+                self.clone(),
+            ),
+        )
+        .length(2)
+        .build();
+
+        let get_uint = FunctionObjectBuilder::new(
+            ctx.realm(),
+            NativeFunction::from_copy_closure_with_captures(
+                move |_this, args, memory, ctx| {
+                    let offset_f64 = args.get_or_undefined(0).to_number(ctx)?;
+                    let len = memory.len();
+                    let offset = offset_f64 as usize;
+                    if offset_f64 < 0. || len < offset.saturating_add(32) {
+                        return Err(JsError::from_native(
+                            JsNativeError::typ().with_message(format!(
+                                "tracer accessed out of bound memory: available {len}, offset {offset}, size 32"
+                            )),
+                        ));
+                    }
+                    let slice = memory.slice(offset, 32);
+                    to_bigint(U256::from_be_slice(&slice), ctx)
+                },
+                self,
+            ),
+        )
+        .length(1)
+        .build();
+
+        obj.set(js_string!("slice"), slice, false, ctx)?;
+        obj.set(js_string!("getUint"), get_uint, false, ctx)?;
+        obj.set(js_string!("length"), length, false, ctx)?;
+        Ok(obj)
+    }
+}
+
+impl Finalize for MemoryRef {}
+
+unsafe impl Trace for MemoryRef {
+    empty_trace!();
+}
+
+/// Represents the opcode object
+#[derive(Debug)]
+pub(crate) struct OpObj(pub(crate) u8);
+
+impl OpObj {
+    pub(crate) fn into_js_object(
+        self, context: &mut Context,
+    ) -> JsResult<JsObject> {
+        let obj = JsObject::default();
+        let value = self.0;
+        let is_push = (PUSH0..=PUSH32).contains(&value);
+
+        let to_number = FunctionObjectBuilder::new(
+            context.realm(),
+            NativeFunction::from_copy_closure(move |_this, _args, _ctx| {
+                Ok(JsValue::from(value))
+            }),
+        )
+        .length(0)
+        .build();
+
+        let is_push = FunctionObjectBuilder::new(
+            context.realm(),
+            NativeFunction::from_copy_closure(move |_this, _args, _ctx| {
+                Ok(JsValue::from(is_push))
+            }),
+        )
+        .length(0)
+        .build();
+
+        let to_string = FunctionObjectBuilder::new(
+            context.realm(),
+            NativeFunction::from_copy_closure(move |_this, _args, _ctx| {
+                // same as geth for opcodes which are not defined
+                let s = match OpCode::new(value) {
+                    Some(op) => op.as_str().to_string(),
+                    None => format!("opcode {value:#x} not defined"),
+                };
+                Ok(JsValue::from(js_string!(s)))
+            }),
+        )
+        .length(0)
+        .build();
+
+        obj.set(js_string!("toNumber"), to_number, false, context)?;
+        obj.set(js_string!("toString"), to_string, false, context)?;
+        obj.set(js_string!("isPush"), is_push, false, context)?;
+        Ok(obj)
+    }
+}
+
+impl From<u8> for OpObj {
+    fn from(op: u8) -> Self { Self(op) }
+}
+
+/// Represents the stack object
+///
+/// The interpreter keeps the top of the stack at the end of the vector.
+#[derive(Debug)]
+pub(crate) struct StackRef(GuardedNullableGc<Vec<CfxU256>>);
+
+impl StackRef {
+    /// Creates a new stack reference
+    pub(crate) fn new(
+        stack: &Vec<CfxU256>,
+    ) -> (Self, GcGuard<'_, Vec<CfxU256>>) {
+        let (inner, guard) = GuardedNullableGc::new_ref(stack);
+        (Self(inner), guard)
+    }
+
+    /// Creates an empty stack, used when the stack of the step is no longer
+    /// available.
+    pub(crate) fn empty() -> Self {
+        let (inner, _guard) = GuardedNullableGc::new_owned(Vec::new());
+        Self(inner)
+    }
+
+    fn peek(&self, idx: usize, ctx: &mut Context) -> JsResult<JsValue> {
+        let value = self
+            .0
+            .with_inner(|stack| {
+                stack
+                    .len()
+                    .checked_sub(idx + 1)
+                    .map(|pos| to_alloy_u256(stack[pos]))
+                    .ok_or(stack.len())
+            })
+            .unwrap_or(Err(0));
+        match value {
+            Ok(value) => to_bigint(value, ctx),
+            Err(len) => Err(JsError::from_native(
+                JsNativeError::typ().with_message(format!(
+                    "tracer accessed out of bound stack: size {len}, index {idx}"
+                )),
+            )),
+        }
+    }
+
+    pub(crate) fn into_js_object(
+        self, context: &mut Context,
+    ) -> JsResult<JsObject> {
+        let obj = JsObject::default();
+        let len = self.0.with_inner(|stack| stack.len()).unwrap_or_default();
+        let length = FunctionObjectBuilder::new(
+            context.realm(),
+            NativeFunction::from_copy_closure(move |_this, _args, _ctx| {
+                Ok(JsValue::from(len))
+            }),
+        )
+        .length(0)
+        .build();
+
+        // peek returns the nth-from-the-top element of the stack.
+        let peek = FunctionObjectBuilder::new(
+            context.realm(),
+            NativeFunction::from_copy_closure_with_captures(
+                move |_this, args, stack, ctx| {
+                    let idx_f64 = args.get_or_undefined(0).to_number(ctx)?;
+                    let idx = idx_f64 as usize;
+                    if len <= idx || idx_f64 < 0. {
+                        return Err(JsError::from_native(
+                            JsNativeError::typ().with_message(format!(
+                                "tracer accessed out of bound stack: size {len}, index {idx_f64}"
+                            )),
+                        ));
+                    }
+                    stack.peek(idx, ctx)
+                },
+                self,
+            ),
+        )
+        .length(1)
+        .build();
+
+        obj.set(js_string!("length"), length, false, context)?;
+        obj.set(js_string!("peek"), peek, false, context)?;
+        Ok(obj)
+    }
+}
+
+impl Finalize for StackRef {}
+
+unsafe impl Trace for StackRef {
+    empty_trace!();
+}
+
+/// Represents the contract object
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Contract {
+    pub(crate) caller: Address,
+    pub(crate) contract: Address,
+    pub(crate) value: U256,
+    pub(crate) input: Bytes,
+}
+
+impl Contract {
+    /// Converts the contract object into a js object
+    ///
+    /// Caution: this expects a global property `bigint` to be present.
+    pub(crate) fn into_js_object(
+        self, ctx: &mut Context,
+    ) -> JsResult<JsObject> {
+        let Self {
+            caller,
+            contract,
+            value,
+            input,
+        } = self;
+        let obj = JsObject::default();
+
+        let get_caller = FunctionObjectBuilder::new(
+            ctx.realm(),
+            NativeFunction::from_copy_closure(move |_this, _args, ctx| {
+                address_to_byte_array_value(caller, ctx)
+            }),
+        )
+        .length(0)
+        .build();
+
+        let get_address = FunctionObjectBuilder::new(
+            ctx.realm(),
+            NativeFunction::from_copy_closure(move |_this, _args, ctx| {
+                address_to_byte_array_value(contract, ctx)
+            }),
+        )
+        .length(0)
+        .build();
+
+        let get_value = FunctionObjectBuilder::new(
+            ctx.realm(),
+            NativeFunction::from_copy_closure(move |_this, _args, ctx| {
+                to_bigint(value, ctx)
+            }),
+        )
+        .length(0)
+        .build();
+
+        let input = to_byte_array_value(input, ctx)?;
+        let get_input = FunctionObjectBuilder::new(
+            ctx.realm(),
+            NativeFunction::from_copy_closure_with_captures(
+                move |_this, _args, input, _ctx| Ok(input.clone()),
+                input,
+            ),
+        )
+        .length(0)
+        .build();
+
+        obj.set(js_string!("getCaller"), get_caller, false, ctx)?;
+        obj.set(js_string!("getAddress"), get_address, false, ctx)?;
+        obj.set(js_string!("getValue"), get_value, false, ctx)?;
+        obj.set(js_string!("getInput"), get_input, false, ctx)?;
+
+        Ok(obj)
+    }
+}
+
+/// Represents the call frame object for exit functions
+pub(crate) struct FrameResult {
+    pub(crate) gas_used: u64,
+    pub(crate) output: Bytes,
+    pub(crate) error: Option<String>,
+}
+
+impl FrameResult {
+    pub(crate) fn into_js_object(
+        self, ctx: &mut Context,
+    ) -> JsResult<JsObject> {
+        let Self {
+            gas_used,
+            output,
+            error,
+        } = self;
+        let obj = JsObject::default();
+
+        let output = to_byte_array_value(output, ctx)?;
+        let get_output = FunctionObjectBuilder::new(
+            ctx.realm(),
+            NativeFunction::from_copy_closure_with_captures(
+                move |_this, _args, output, _ctx| Ok(output.clone()),
+                output,
+            ),
+        )
+        .length(0)
+        .build();
+
+        let error = error
+            .map(|err| JsValue::from(js_string!(err)))
+            .unwrap_or_default();
+        let get_error = js_value_capture_getter!(error, ctx);
+        let get_gas_used = js_value_getter!(gas_used, ctx);
+
+        obj.set(js_string!("getGasUsed"), get_gas_used, false, ctx)?;
+        obj.set(js_string!("getOutput"), get_output, false, ctx)?;
+        obj.set(js_string!("getError"), get_error, false, ctx)?;
+
+        Ok(obj)
+    }
+}
+
+/// Represents the call frame object for enter functions
+pub(crate) struct CallFrame {
+    pub(crate) contract: Contract,
+    /// The geth name of the call type, e.g. `CALL` or `SELFDESTRUCT`
+    pub(crate) r#type: String,
+    pub(crate) gas: u64,
+}
+
+impl CallFrame {
+    pub(crate) fn into_js_object(
+        self, ctx: &mut Context,
+    ) -> JsResult<JsObject> {
+        let Self {
+            contract:
+                Contract {
+                    caller,
+                    contract,
+                    value,
+                    input,
+                },
+            r#type,
+            gas,
+        } = self;
+        let obj = JsObject::default();
+
+        let get_from = FunctionObjectBuilder::new(
+            ctx.realm(),
+            NativeFunction::from_copy_closure(move |_this, _args, ctx| {
+                address_to_byte_array_value(caller, ctx)
+            }),
+        )
+        .length(0)
+        .build();
+
+        let get_to = FunctionObjectBuilder::new(
+            ctx.realm(),
+            NativeFunction::from_copy_closure(move |_this, _args, ctx| {
+                address_to_byte_array_value(contract, ctx)
+            }),
+        )
+        .length(0)
+        .build();
+
+        let get_value = FunctionObjectBuilder::new(
+            ctx.realm(),
+            NativeFunction::from_copy_closure(move |_this, _args, ctx| {
+                to_bigint(value, ctx)
+            }),
+        )
+        .length(0)
+        .build();
+
+        let input = to_byte_array_value(input, ctx)?;
+        let get_input = FunctionObjectBuilder::new(
+            ctx.realm(),
+            NativeFunction::from_copy_closure_with_captures(
+                move |_this, _args, input, _ctx| Ok(input.clone()),
+                input,
+            ),
+        )
+        .length(0)
+        .build();
+
+        let get_gas = js_value_getter!(gas, ctx);
+        let ty = js_string!(r#type);
+        let get_type = js_value_capture_getter!(ty, ctx);
+
+        obj.set(js_string!("getFrom"), get_from, false, ctx)?;
+        obj.set(js_string!("getTo"), get_to, false, ctx)?;
+        obj.set(js_string!("getValue"), get_value, false, ctx)?;
+        obj.set(js_string!("getInput"), get_input, false, ctx)?;
+        obj.set(js_string!("getGas"), get_gas, false, ctx)?;
+        obj.set(js_string!("getType"), get_type, false, ctx)?;
+
+        Ok(obj)
+    }
+}
+
+/// The `ctx` object that represents the context in which the transaction is
+/// executed.
+pub(crate) struct JsEvmContext {
+    /// String, one of the two values CALL and CREATE
+    pub(crate) r#type: String,
+    /// Sender of the transaction
+    pub(crate) from: Address,
+    /// Target of the transaction
+    pub(crate) to: Option<Address>,
+    pub(crate) input: Bytes,
+    /// Gas limit
+    pub(crate) gas: u64,
+    /// Number, amount of gas used in executing the transaction (excludes
+    /// txdata costs)
+    pub(crate) gas_used: u64,
+    /// Number, gas price configured in the transaction being executed
+    pub(crate) gas_price: u64,
+    /// Number, intrinsic gas for the transaction being executed
+    pub(crate) intrinsic_gas: u64,
+    /// big.int Amount to be transferred in drip
+    pub(crate) value: U256,
+    /// Number, block number
+    pub(crate) block: u64,
+    pub(crate) output: Bytes,
+    /// String, the error of the top level call if it failed
+    pub(crate) error: Option<String>,
+    /// Number, block timestamp
+    pub(crate) time: String,
+    pub(crate) transaction_ctx: TransactionContext,
+}
+
+impl JsEvmContext {
+    pub(crate) fn into_js_object(
+        self, ctx: &mut Context,
+    ) -> JsResult<JsObject> {
+        let Self {
+            r#type,
+            from,
+            to,
+            input,
+            gas,
+            gas_used,
+            gas_price,
+            intrinsic_gas,
+            value,
+            block,
+            output,
+            error,
+            time,
+            transaction_ctx,
+        } = self;
+        let obj = JsObject::default();
+
+        // add properties
+
+        obj.set(js_string!("type"), js_string!(r#type), false, ctx)?;
+        obj.set(
+            js_string!("from"),
+            address_to_byte_array(from, ctx)?,
+            false,
+            ctx,
+        )?;
+        if let Some(to) = to {
+            obj.set(
+                js_string!("to"),
+                address_to_byte_array(to, ctx)?,
+                false,
+                ctx,
+            )?;
+        } else {
+            obj.set(js_string!("to"), JsValue::null(), false, ctx)?;
+        }
+
+        obj.set(js_string!("input"), to_byte_array(input, ctx)?, false, ctx)?;
+        obj.set(js_string!("gas"), gas, false, ctx)?;
+        obj.set(js_string!("gasUsed"), gas_used, false, ctx)?;
+        obj.set(js_string!("gasPrice"), gas_price, false, ctx)?;
+        obj.set(js_string!("intrinsicGas"), intrinsic_gas, false, ctx)?;
+        obj.set(js_string!("value"), to_bigint(value, ctx)?, false, ctx)?;
+        obj.set(js_string!("block"), block, false, ctx)?;
+        obj.set(
+            js_string!("output"),
+            to_byte_array(output, ctx)?,
+            false,
+            ctx,
+        )?;
+        if let Some(error) = error {
+            obj.set(js_string!("error"), js_string!(error), false, ctx)?;
+        }
+        obj.set(js_string!("time"), js_string!(time), false, ctx)?;
+        if let Some(block_hash) = transaction_ctx.block_hash {
+            obj.set(
+                js_string!("blockHash"),
+                to_byte_array(block_hash.0, ctx)?,
+                false,
+                ctx,
+            )?;
+        }
+        if let Some(tx_index) = transaction_ctx.tx_index {
+            obj.set(js_string!("txIndex"), tx_index as u64, false, ctx)?;
+        }
+        if let Some(tx_hash) = transaction_ctx.tx_hash {
+            obj.set(
+                js_string!("txHash"),
+                to_byte_array(tx_hash.0, ctx)?,
+                false,
+                ctx,
+            )?;
+        }
+
+        Ok(obj)
+    }
+}
+
+/// DB is the object that allows the js inspector to read the state.
+///
+/// Accounts are read from the space of the traced transaction. The state is
+/// only available during `step`, any access after that throws an error.
+#[derive(Clone)]
+pub(crate) struct EvmDbRef {
+    state: Option<GuardedNullableGc<State>>,
+    space: Space,
+}
+
+impl EvmDbRef {
+    /// Creates a new db JS object of the given state.
+    pub(crate) fn new(
+        state: &State, space: Space,
+    ) -> (Self, GcGuard<'_, State>) {
+        let (state, guard) = GuardedNullableGc::new_ref(state);
+        let this = Self {
+            state: Some(state),
+            space,
+        };
+        (this, guard)
+    }
+
+    /// Creates a db JS object without access to the state, used in hooks that
+    /// are invoked outside of the interpreter.
+    pub(crate) fn unavailable(space: Space) -> Self {
+        Self { state: None, space }
+    }
+
+    fn read<T>(
+        &self, address: JsValue, ctx: &mut Context,
+        f: impl FnOnce(&State, &cfx_types::AddressWithSpace) -> DbResult<T>,
+    ) -> JsResult<T> {
+        let buf = from_buf_value(address, ctx)?;
+        let address = bytes_to_address(buf);
+        let address_with_space =
+            from_alloy_address(address).with_space(self.space);
+
+        let res = self
+            .state
+            .as_ref()
+            .and_then(|state| {
+                state.with_inner(|state| f(state, &address_with_space))
+            })
+            .ok_or_else(|| {
+                JsError::from_native(
+                    JsNativeError::error()
+                        .with_message("db is only accessible during step"),
+                )
+            })?;
+        res.map_err(|err| {
+            JsError::from_native(JsNativeError::error().with_message(format!(
+                "Failed to read address {address:?} from database: {err}"
+            )))
+        })
+    }
+
+    pub(crate) fn into_js_object(
+        self, ctx: &mut Context,
+    ) -> JsResult<JsObject> {
+        let obj = JsObject::default();
+        let exists = FunctionObjectBuilder::new(
+            ctx.realm(),
+            NativeFunction::from_copy_closure_with_captures(
+                move |_this, args, db, ctx| {
+                    let val = args.get_or_undefined(0).clone();
+                    let exists =
+                        db.read(val, ctx, |state, addr| state.exists(addr))?;
+                    Ok(JsValue::from(exists))
+                },
+                self.clone(),
+            ),
+        )
+        .length(1)
+        .build();
+
+        let get_balance = FunctionObjectBuilder::new(
+            ctx.realm(),
+            NativeFunction::from_copy_closure_with_captures(
+                move |_this, args, db, ctx| {
+                    let val = args.get_or_undefined(0).clone();
+                    let balance =
+                        db.read(val, ctx, |state, addr| state.balance(addr))?;
+                    to_bigint(to_alloy_u256(balance), ctx)
+                },
+                self.clone(),
+            ),
+        )
+        .length(1)
+        .build();
+
+        let get_nonce = FunctionObjectBuilder::new(
+            ctx.realm(),
+            NativeFunction::from_copy_closure_with_captures(
+                move |_this, args, db, ctx| {
+                    let val = args.get_or_undefined(0).clone();
+                    let nonce =
+                        db.read(val, ctx, |state, addr| state.nonce(addr))?;
+                    Ok(JsValue::from(nonce.low_u64()))
+                },
+                self.clone(),
+            ),
+        )
+        .length(1)
+        .build();
+
+        let get_code = FunctionObjectBuilder::new(
+            ctx.realm(),
+            NativeFunction::from_copy_closure_with_captures(
+                move |_this, args, db, ctx| {
+                    let val = args.get_or_undefined(0).clone();
+                    let code =
+                        db.read(val, ctx, |state, addr| state.code(addr))?;
+                    let code =
+                        code.map(|code| code.to_vec()).unwrap_or_default();
+                    Ok(JsUint8Array::from_iter(code, ctx)?.into())
+                },
+                self.clone(),
+            ),
+        )
+        .length(1)
+        .build();
+
+        let get_state = FunctionObjectBuilder::new(
+            ctx.realm(),
+            NativeFunction::from_copy_closure_with_captures(
+                move |_this, args, db, ctx| {
+                    let addr = args.get_or_undefined(0).clone();
+                    let slot = args.get_or_undefined(1).clone();
+                    let buf = from_buf_value(slot, ctx)?;
+                    let slot = bytes_to_hash(buf);
+                    let value = db.read(addr, ctx, |state, addr| {
+                        state.storage_at(addr, slot.as_slice())
+                    })?;
+                    let value = B256::from(to_alloy_u256(value));
+                    Ok(to_byte_array(value.0, ctx)?.into())
+                },
+                self,
+            ),
+        )
+        .length(2)
+        .build();
+
+        obj.set(js_string!("getBalance"), get_balance, false, ctx)?;
+        obj.set(js_string!("getNonce"), get_nonce, false, ctx)?;
+        obj.set(js_string!("getCode"), get_code, false, ctx)?;
+        obj.set(js_string!("getState"), get_state, false, ctx)?;
+        obj.set(js_string!("exists"), exists, false, ctx)?;
+        Ok(obj)
+    }
+}
+
+impl Finalize for EvmDbRef {}
+
+unsafe impl Trace for EvmDbRef {
+    empty_trace!();
+}
@@ -0,0 +1,1067 @@
+// Copyright 2023-2024 Paradigm.xyz
+// This file is part of reth.
+// Reth is a modular, contributor-friendly and blazing-fast implementation of
+// the Ethereum protocol
+
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Javascript inspector
+//!
+//! Runs the tracer objects of geth's
+//! [custom javascript tracing](https://geth.ethereum.org/docs/developers/evm-tracing/custom-tracer#custom-javascript-tracing),
+//! i.e. an object with the `result` and `fault` functions and the optional
+//! `setup`, `step`, `enter` and `exit` functions.
+
+use crate::{
+    config::TraceStyle,
+    geth_tracer::to_instruction_result,
+    js::{
+        bindings::{
+            CallFrame, Contract, EvmDbRef, FrameResult, JsEvmContext,
+            MemoryRef, StackRef, StepLog,
+        },
+        builtins::{register_builtins, to_serde_value, PrecompileList},
+    },
+    types::CallKind,
+    utils::{fmt_error_msg, to_alloy_address, to_alloy_u256},
+};
+use alloy_primitives::{Address, Bytes, B256};
+use boa_engine::{
+    js_string,
+    object::builtins::JsArray,
+    property::{Attribute, PropertyDescriptor},
+    Context, JsError, JsObject, JsResult, JsSymbol, JsValue, Script, Source,
+};
+use cfx_executor::{stack::FrameResult as CfxFrameResult, state::State};
+use cfx_types::Space;
+use cfx_vm_types::{ActionParams, CreateType, InterpreterInfo};
+use malloc_size_of::allocation::AllocationScope;
+use std::{
+    collections::HashSet,
+    fmt,
+    future::Future,
+    pin::pin,
+    task::{self, Poll, Waker},
+    time::{Duration, Instant},
+};
+
+pub(crate) mod bindings;
+pub(crate) mod builtins;
+
+/// The maximum number of iterations in a loop.
+///
+/// Once exceeded, the loop will throw an error.
+// An empty loop with this limit takes around 50ms to fail.
+pub const LOOP_ITERATION_LIMIT: u64 = 200_000;
+
+/// The recursion limit for function calls.
+///
+/// Once exceeded, the function will throw an error.
+pub const RECURSION_LIMIT: usize = 10_000;
+
+/// The time a tracer may spend on a single transaction if the request does not
+/// set a `timeout`, same as geth.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The maximum size in bytes of the JSON encoded result of a tracer.
+pub const RESULT_SIZE_LIMIT: usize = 64 * 1024 * 1024;
+
+/// The maximum number of bytes a tracer may allocate, net of the
+/// deallocations.
+///
+/// Only enforced if the global allocator counts the allocations, as the one
+/// of `cfx-mallocator-utils` does.
+pub const HEAP_SIZE_LIMIT: usize = 256 * 1024 * 1024;
+
+/// The number of "clock cycles" of the javascript VM between two checks of
+/// the timeout and the heap size.
+const CHECK_INTERVAL: u32 = 10_000;
+
+/// The global object holding the function called by [`Runner::call`].
+const CALL_OBJECT: &str = "__tracer_call";
+
+/// A javascript inspector that will delegate inspector functions to javascript
+/// functions.
+///
+/// The first error thrown by the script, or exceeding the timeout, stops the
+/// tracing and is returned instead of the result.
+#[derive(Debug)]
+pub struct JsInspector {
+    ctx: Context,
+    /// The evaluated object that contains the inspector functions.
+    obj: JsObject,
+    /// The context of the transaction that is being inspected.
+    transaction_context: TransactionContext,
+
+    /// The javascript function that will be called when the result is
+    /// requested.
+    result_fn: JsSymbol,
+    fault_fn: JsSymbol,
+
+    /// Invoked when the EVM enters a new call that is _NOT_ the top level
+    /// call, including selfdestructs.
+    enter_fn: Option<JsSymbol>,
+    /// Invoked when the EVM exits a call that is _NOT_ the top level call.
+    exit_fn: Option<JsSymbol>,
+    /// Executed before each instruction is executed.
+    step_fn: Option<JsSymbol>,
+    /// Keeps track of the current call stack.
+    call_stack: Vec<CallStackItem>,
+    /// The top level call, kept for the `ctx` object of `result`.
+    root_call: Option<CallStackItem>,
+    /// The outcome of the top level call.
+    root_result: Option<(Bytes, Option<String>)>,
+    /// The last executed step, passed to `fault` if the step failed.
+    last_step: Option<StepInfo>,
+    /// Marker to track whether the precompiles have been registered.
+    precompiles_registered: bool,
+    /// Runs the javascript within the timeout and the heap size limit.
+    runner: Runner,
+    /// The error which aborted the tracing.
+    error: Option<String>,
+}
+
+impl JsInspector {
+    /// Creates a new inspector from a javascript code snippet that evaluates
+    /// to an object with the expected fields and a config object.
+    ///
+    /// The object must have the following fields:
+    ///  - `result`: a function that will be called when the result is
+    ///    requested.
+    ///  - `fault`: a function that will be called when the transaction fails.
+    ///
+    /// Optional functions are invoked during inspection:
+    /// - `setup`: a function that will be called before the inspection starts.
+    /// - `enter`: a function that will be called when the execution enters a
+    ///   new call.
+    /// - `exit`: a function that will be called when the execution exits a
+    ///   call.
+    /// - `step`: a function that will be called when the execution steps to the
+    ///   next instruction.
+    pub fn new(
+        code: String, config: serde_json::Value,
+        transaction_context: TransactionContext, timeout: Duration,
+    ) -> Result<Self, JsInspectorError> {
+        let (mut this, setup_fn) =
+            Self::compile(code, transaction_context, timeout)?;
+
+        let js_config_value = JsValue::from_json(&config, &mut this.ctx)
+            .map_err(JsInspectorError::InvalidJsonConfig)?;
+
+        if let Some(setup_fn) = setup_fn {
+            // call setup()
+            this.runner
+                .call(&this.obj, &setup_fn, &[js_config_value], &mut this.ctx)
+                .map_err(|err| {
+                    err.into_error(JsInspectorError::SetupCallFailed)
+                })?;
+        }
+
+        Ok(this)
+    }
+
+    /// Checks the code and the config of a tracer before tracing, without
+    /// calling `setup`.
+    pub fn check(
+        code: String, config: serde_json::Value, timeout: Duration,
+    ) -> Result<(), JsInspectorError> {
+        let (mut this, _) = Self::compile(code, Default::default(), timeout)?;
+        JsValue::from_json(&config, &mut this.ctx)
+            .map_err(JsInspectorError::InvalidJsonConfig)?;
+        Ok(())
+    }
+
+    /// Evaluates the code and returns the inspector and its `setup` function.
+    fn compile(
+        code: String, transaction_context: TransactionContext,
+        timeout: Duration,
+    ) -> Result<(Self, Option<JsSymbol>), JsInspectorError> {
+        // Instantiate the execution context
+        let mut ctx = Context::default();
+
+        // Apply the default runtime limits
+        // This is a safe guard to prevent infinite loops
+        ctx.runtime_limits_mut()
+            .set_loop_iteration_limit(LOOP_ITERATION_LIMIT);
+        ctx.runtime_limits_mut()
+            .set_recursion_limit(RECURSION_LIMIT);
+
+        register_builtins(&mut ctx)?;
+        let mut runner = Runner::new(&mut ctx, timeout)?;
+
+        // evaluate the code
+        let code = format!("({})", code);
+        let obj = runner
+            .eval(&code, &mut ctx)
+            .map_err(|err| err.into_error(JsInspectorError::EvalCode))?;
+
+        let obj = obj
+            .as_object()
+            .cloned()
+            .ok_or(JsInspectorError::ExpectedJsObject)?;
+
+        // ensure all the fields are callables, if present
+
+        let result_fn = Runner::method(&obj, "result", &mut ctx)?
+            .ok_or(JsInspectorError::ResultFunctionMissing)?;
+
+        let fault_fn = Runner::method(&obj, "fault", &mut ctx)?
+            .ok_or(JsInspectorError::FaultFunctionMissing)?;
+
+        let enter_fn = Runner::method(&obj, "enter", &mut ctx)?;
+        let exit_fn = Runner::method(&obj, "exit", &mut ctx)?;
+        let step_fn = Runner::method(&obj, "step", &mut ctx)?;
+
+        let setup = obj.get(js_string!("setup"), &mut ctx)?;
+        if setup.as_object().is_some_and(|o| !o.is_callable()) {
+            return Err(JsInspectorError::SetupFunctionNotCallable);
+        }
+        let setup_fn = Runner::method(&obj, "setup", &mut ctx)?;
+
+        let this = Self {
+            ctx,
+            obj,
+            transaction_context,
+            result_fn,
+            fault_fn,
+            enter_fn,
+            exit_fn,
+            step_fn,
+            call_stack: Default::default(),
+            root_call: None,
+            root_result: None,
+            last_step: None,
+            precompiles_registered: false,
+            runner,
+            error: None,
+        };
+        Ok((this, setup_fn))
+    }
+
+    /// Calls the result function and returns the result as
+    /// [serde_json::Value].
+    ///
+    /// Note: This is supposed to be called after the inspection has finished.
+    /// The state is no longer available, so the `db` object passed to
+    /// `result` throws on any access.
+    pub fn json_result(
+        mut self, tx: JsTxContext,
+    ) -> Result<serde_json::Value, String> {
+        self.check_deadline();
+        if let Some(err) = self.error.take() {
+            return Err(err);
+        }
+
+        let root = self.root_call.take().unwrap_or_default();
+        let (output, error) = self.root_result.take().unwrap_or_default();
+        let ctx = JsEvmContext {
+            r#type: if root.kind.is_any_create() {
+                "CREATE"
+            } else {
+                "CALL"
+            }
+            .to_string(),
+            from: root.contract.caller,
+            to: Some(root.contract.contract),
+            input: root.contract.input,
+            gas: tx.gas_limit,
+            gas_used: tx.gas_used,
+            gas_price: tx.gas_price,
+            intrinsic_gas: tx.gas_limit.saturating_sub(root.gas_limit),
+            value: root.contract.value,
+            block: tx.block_number,
+            output,
+            error,
+            time: tx.timestamp.to_string(),
+            transaction_ctx: self.transaction_context,
+        };
+
+        let result = self
+            .call_result(ctx, EvmDbRef::unavailable(root.space))
+            .map_err(|err| err.into_message("result"))?;
+        let size = result.to_string().len();
+        if size > RESULT_SIZE_LIMIT {
+            return Err(format!(
+                "tracer result exceeds the size limit of {RESULT_SIZE_LIMIT} bytes"
+            ));
+        }
+        Ok(result)
+    }
+
+    fn call_result(
+        &mut self, ctx: JsEvmContext, db: EvmDbRef,
+    ) -> Result<serde_json::Value, Interrupt> {
+        let ctx = ctx.into_js_object(&mut self.ctx)?;
+        let db = db.into_js_object(&mut self.ctx)?;
+        let result = self.runner.call(
+            &self.obj,
+            &self.result_fn,
+            &[ctx.into(), db.into()],
+            &mut self.ctx,
+        )?;
+        Ok(to_serde_value(result, &mut self.ctx)?)
+    }
+
+    fn try_fault(
+        &mut self, step: StepLog, db: EvmDbRef,
+    ) -> Result<(), Interrupt> {
+        let step = step.into_js_object(&mut self.ctx)?;
+        let db = db.into_js_object(&mut self.ctx)?;
+        self.runner.call(
+            &self.obj,
+            &self.fault_fn,
+            &[step.into(), db.into()],
+            &mut self.ctx,
+        )?;
+        Ok(())
+    }
+
+    fn try_step(
+        &mut self, step: StepLog, db: EvmDbRef,
+    ) -> Result<(), Interrupt> {
+        if let Some(step_fn) = &self.step_fn {
+            let step = step.into_js_object(&mut self.ctx)?;
+            let db = db.into_js_object(&mut self.ctx)?;
+            self.runner.call(
+                &self.obj,
+                step_fn,
+                &[step.into(), db.into()],
+                &mut self.ctx,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn try_enter(&mut self, frame: CallFrame) -> Result<(), Interrupt> {
+        if let Some(enter_fn) = &self.enter_fn {
+            let frame = frame.into_js_object(&mut self.ctx)?;
+            self.runner.call(
+                &self.obj,
+                enter_fn,
+                &[frame.into()],
+                &mut self.ctx,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn try_exit(&mut self, frame: FrameResult) -> Result<(), Interrupt> {
+        if let Some(exit_fn) = &self.exit_fn {
+            let frame = frame.into_js_object(&mut self.ctx)?;
+            self.runner.call(
+                &self.obj,
+                exit_fn,
+                &[frame.into()],
+                &mut self.ctx,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Returns false if the tracing was aborted, after checking the deadline.
+    fn check_deadline(&mut self) -> bool {
+        if self.error.is_none() && Instant::now() > self.runner.deadline {
+            self.error = Some(Interrupt::Timeout.to_string());
+        }
+        self.error.is_none()
+    }
+
+    /// Records the first error of the script, which aborts the tracing.
+    ///
+    /// An interrupted script leaves the context in an unusable state, so no
+    /// function is called after that.
+    fn on_js_error(&mut self, method: &str, err: Interrupt) {
+        if self.error.is_none() {
+            self.error = Some(err.into_message(method));
+        }
+    }
+
+    /// Returns true whether the active call is the root call.
+    #[inline]
+    fn is_root_call_active(&self) -> bool { self.call_stack.len() == 1 }
+
+    /// Registers the `isPrecompiled` builtin with the precompiles of the
+    /// space of the traced transaction.
+    pub fn register_precompiles(
+        &mut self, precompiles: impl FnOnce() -> HashSet<Address>,
+    ) {
+        if self.precompiles_registered {
+            return;
+        }
+        let precompiles = PrecompileList(precompiles());
+
+        let _ = precompiles.register_callable(&mut self.ctx);
+
+        self.precompiles_registered = true
+    }
+
+    /// Records the instruction of the step, `step` is called once its cost
+    /// is known.
+    pub fn step(&mut self, interp: &dyn InterpreterInfo, depth: u64) {
+        let Some(call) = self.call_stack.last() else {
+            return;
+        };
+        self.last_step = Some(StepInfo {
+            op: interp.current_opcode(),
+            pc: interp.program_counter(),
+            gas_remaining: interp.gas_remainning().as_u64(),
+            cost: 0,
+            depth,
+            contract: call.contract.clone(),
+        });
+    }
+
+    /// Calls `step` with the cost of the recorded instruction, before it is
+    /// executed.
+    pub fn step_cost(
+        &mut self, interp: &dyn InterpreterInfo, cost: u64, state: &State,
+    ) {
+        let (Some(call), Some(step)) =
+            (self.call_stack.last(), self.last_step.as_mut())
+        else {
+            return;
+        };
+        let space = call.space;
+        step.cost = cost;
+        let step = step.clone();
+
+        if self.step_fn.is_none() || !self.check_deadline() {
+            return;
+        }
+
+        let (db, _db_guard) = EvmDbRef::new(state, space);
+        let (stack, _stack_guard) = StackRef::new(interp.stack());
+        let (memory, _memory_guard) = MemoryRef::new(interp.mem());
+        if let Err(err) = self.try_step(step.into_log(stack, memory, None), db)
+        {
+            self.on_js_error("step", err);
+        }
+    }
+
+    pub fn call(&mut self, params: &ActionParams) {
+        // determine correct `from` and `to` based on the call scheme
+        let kind: CallKind = params.call_type.into();
+        let (from, to) = if kind.is_delegate() {
+            (params.address, params.code_address)
+        } else {
+            (params.sender, params.address)
+        };
+        self.push_call(params, kind, from, to);
+    }
+
+    pub fn create(&mut self, params: &ActionParams) {
+        let kind = match params.create_type {
+            CreateType::CREATE2 => CallKind::Create2,
+            _ => CallKind::Create,
+        };
+        self.push_call(params, kind, params.sender, params.address);
+    }
+
+    fn push_call(
+        &mut self, params: &ActionParams, kind: CallKind,
+        from: cfx_types::Address, to: cfx_types::Address,
+    ) {
+        let value = to_alloy_u256(params.value.value());
+        let call = CallStackItem {
+            contract: Contract {
+                caller: to_alloy_address(from),
+                contract: to_alloy_address(to),
+                value,
+                input: params.data.clone().unwrap_or_default().into(),
+            },
+            kind,
+            gas_limit: params.gas.as_u64(),
+            space: params.space,
+        };
+        if self.call_stack.is_empty() {
+            self.root_call = Some(call.clone());
+        }
+        self.call_stack.push(call);
+
+        if self.enter_fn.is_none()
+            || self.is_root_call_active()
+            || !self.check_deadline()
+        {
+            return;
+        }
+
+        let call = self.call_stack.last().expect("call is pushed");
+        let frame = CallFrame {
+            contract: call.contract.clone(),
+            r#type: call.kind.to_string(),
+            gas: call.gas_limit,
+        };
+        if let Err(err) = self.try_enter(frame) {
+            self.on_js_error("enter", err);
+        }
+    }
+
+    /// Handles the end of a call or create.
+    pub fn call_end(&mut self, result: &CfxFrameResult) {
+        let Some(call) = self.call_stack.last() else {
+            return;
+        };
+        let space = call.space;
+
+        let (gas_used, output) = match result {
+            Ok(r) => (
+                call.gas_limit.saturating_sub(r.gas_left.as_u64()),
+                Bytes::from(r.return_data.to_vec()),
+            ),
+            Err(_) => (call.gas_limit, Bytes::new()),
+        };
+        let error =
+            fmt_error_msg(to_instruction_result(result), TraceStyle::Geth);
+
+        // an opcode failed, revert is an expected outcome of the frame
+        if let (Err(_), Some(step)) = (result, self.last_step.take()) {
+            if self.check_deadline() {
+                let log = step.into_log(
+                    StackRef::empty(),
+                    MemoryRef::empty(),
+                    error.clone(),
+                );
+                if let Err(err) =
+                    self.try_fault(log, EvmDbRef::unavailable(space))
+                {
+                    self.on_js_error("fault", err);
+                }
+            }
+        }
+
+        if self.is_root_call_active() {
+            self.root_result = Some((output, error));
+        } else if self.exit_fn.is_some() && self.check_deadline() {
+            let frame_result = FrameResult {
+                gas_used,
+                output,
+                error,
+            };
+            if let Err(err) = self.try_exit(frame_result) {
+                self.on_js_error("exit", err);
+            }
+        }
+
+        self.call_stack.pop();
+    }
+
+    pub fn selfdestruct(
+        &mut self, contract: &cfx_types::Address, target: &cfx_types::Address,
+        value: cfx_types::U256,
+    ) {
+        if !self.check_deadline() {
+            return;
+        }
+
+        // This is exempt from the root call constraint, because selfdestruct
+        // is treated as a new scope that is entered and immediately exited.
+        if self.enter_fn.is_some() {
+            let frame = CallFrame {
+                contract: Contract {
+                    caller: to_alloy_address(*contract),
+                    contract: to_alloy_address(*target),
+                    value: to_alloy_u256(value),
+                    input: Bytes::new(),
+                },
+                r#type: "SELFDESTRUCT".to_string(),
+                gas: 0,
+            };
+            if let Err(err) = self.try_enter(frame) {
+                self.on_js_error("enter", err);
+                return;
+            }
+        }
+
+        // exit with empty frame result ref <https://github.com/ethereum/go-ethereum/blob/0004c6b229b787281760b14fb9460ffd9c2496f1/core/vm/instructions.go#L829-L829>
+        if self.exit_fn.is_some() {
+            let frame_result = FrameResult {
+                gas_used: 0,
+                output: Bytes::new(),
+                error: None,
+            };
+            if let Err(err) = self.try_exit(frame_result) {
+                self.on_js_error("exit", err);
+            }
+        }
+    }
+}
+
+/// Runs the javascript of a tracer, interrupting it once the timeout or the
+/// heap size limit is exceeded.
+///
+/// The VM of boa can only be suspended while it evaluates a script, and a
+/// function called by a builtin, e.g. `Function.prototype.call`, runs to
+/// completion. So the functions of the tracer are kept as hidden methods of the
+/// tracer object, and called by a script.
+#[derive(Debug)]
+struct Runner {
+    /// Calls the method set in [`CALL_OBJECT`].
+    call_script: Script,
+    call_object: JsObject,
+    deadline: Instant,
+    /// The bytes allocated by the javascript so far.
+    heap_size: isize,
+}
+
+impl Runner {
+    fn new(ctx: &mut Context, timeout: Duration) -> JsResult<Self> {
+        let call_object = JsObject::with_null_proto();
+        ctx.register_global_property(
+            js_string!(CALL_OBJECT),
+            call_object.clone(),
+            Attribute::empty(),
+        )?;
+
+        let call_script = Script::parse(
+            Source::from_bytes(
+                format!("{CALL_OBJECT}.this[{CALL_OBJECT}.key](...{CALL_OBJECT}.args)")
+                    .as_bytes(),
+            ),
+            None,
+            ctx,
+        )?;
+
+        Ok(Self {
+            call_script,
+            call_object,
+            deadline: Instant::now() + timeout,
+            heap_size: 0,
+        })
+    }
+
+    /// Keeps the function `name` of the object, if it is callable, as a hidden
+    /// method which can't be replaced by the tracer.
+    fn method(
+        obj: &JsObject, name: &str, ctx: &mut Context,
+    ) -> JsResult<Option<JsSymbol>> {
+        let Some(function) = obj
+            .get(js_string!(name), ctx)?
+            .as_object()
+            .cloned()
+            .filter(|o| o.is_callable())
+        else {
+            return Ok(None);
+        };
+        let key = JsSymbol::new(Some(js_string!(name))).ok_or_else(|| {
+            JsError::from_opaque(js_string!("too many symbols").into())
+        })?;
+        obj.define_property_or_throw(
+            key.clone(),
+            PropertyDescriptor::builder()
+                .value(function)
+                .writable(false)
+                .enumerable(false)
+                .configurable(false),
+            ctx,
+        )?;
+        Ok(Some(key))
+    }
+
+    /// Evaluates the given code.
+    fn eval(
+        &mut self, code: &str, ctx: &mut Context,
+    ) -> Result<JsValue, Interrupt> {
+        let script =
+            Script::parse(Source::from_bytes(code.as_bytes()), None, ctx)?;
+        self.run(&script, ctx)
+    }
+
+    /// Calls the method of the object with the given arguments.
+    fn call(
+        &mut self, this: &JsObject, method: &JsSymbol, args: &[JsValue],
+        ctx: &mut Context,
+    ) -> Result<JsValue, Interrupt> {
+        let args = JsArray::from_iter(args.iter().cloned(), ctx);
+        self.call_object
+            .set(js_string!("this"), this.clone(), true, ctx)?;
+        self.call_object
+            .set(js_string!("key"), method.clone(), true, ctx)?;
+        self.call_object.set(js_string!("args"), args, true, ctx)?;
+        let script = self.call_script.clone();
+        self.run(&script, ctx)
+    }
+
+    /// Evaluates the script, checking the timeout and the heap size at every
+    /// [`CHECK_INTERVAL`]. The context is left in an unusable state if the
+    /// evaluation is interrupted.
+    fn run(
+        &mut self, script: &Script, ctx: &mut Context,
+    ) -> Result<JsValue, Interrupt> {
+        if Instant::now() > self.deadline {
+            return Err(Interrupt::Timeout);
+        }
+
+        let allocations = AllocationScope::new();
+        let exceeds_heap_size_limit = |allocated: isize| {
+            self.heap_size.saturating_add(allocated) > HEAP_SIZE_LIMIT as isize
+        };
+        let result = {
+            let mut evaluation =
+                pin!(script.evaluate_async_with_budget(ctx, CHECK_INTERVAL));
+            let mut cx = task::Context::from_waker(Waker::noop());
+            loop {
+                if let Poll::Ready(result) = evaluation.as_mut().poll(&mut cx) {
+                    break result.map_err(Interrupt::Error);
+                }
+                if Instant::now() > self.deadline {
+                    break Err(Interrupt::Timeout);
+                }
+                if exceeds_heap_size_limit(allocations.allocated()) {
+                    break Err(Interrupt::HeapSizeLimit);
+                }
+            }
+        };
+        let allocated = allocations.allocated();
+        if exceeds_heap_size_limit(allocated) {
+            return Err(Interrupt::HeapSizeLimit);
+        }
+        self.heap_size = self.heap_size.saturating_add(allocated);
+        result
+    }
+}
+
+/// The reason the javascript stopped before returning.
+#[derive(Debug)]
+enum Interrupt {
+    /// The script threw an error.
+    Error(JsError),
+    Timeout,
+    HeapSizeLimit,
+}
+
+impl Interrupt {
+    /// Converts into an error of the inspector, wrapping the error thrown by
+    /// the script with `f`.
+    fn into_error(
+        self, f: impl FnOnce(JsError) -> JsInspectorError,
+    ) -> JsInspectorError {
+        match self {
+            Interrupt::Error(err) => f(err),
+            Interrupt::Timeout => JsInspectorError::Timeout,
+            Interrupt::HeapSizeLimit => JsInspectorError::HeapSizeLimit,
+        }
+    }
+
+    /// Returns the message of the error which aborted the tracing in the given
+    /// function of the tracer.
+    fn into_message(self, method: &str) -> String {
+        match self {
+            Interrupt::Error(err) => {
+                format!("{err} in server-side tracer function '{method}'")
+            }
+            interrupt => interrupt.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Interrupt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Interrupt::Error(err) => write!(f, "{err}"),
+            Interrupt::Timeout => write!(f, "{}", JsInspectorError::Timeout),
+            Interrupt::HeapSizeLimit => {
+                write!(f, "{}", JsInspectorError::HeapSizeLimit)
+            }
+        }
+    }
+}
+
+impl From<JsError> for Interrupt {
+    fn from(err: JsError) -> Self { Interrupt::Error(err) }
+}
+
+/// Contains some contextual infos for a transaction execution that is made
+/// available to the JS object.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TransactionContext {
+    /// Hash of the block the tx is contained within.
+    ///
+    /// `None` if this is a call.
+    pub block_hash: Option<B256>,
+    /// Index of the transaction within a block.
+    ///
+    /// `None` if this is a call.
+    pub tx_index: Option<usize>,
+    /// Hash of the transaction being traced.
+    ///
+    /// `None` if this is a call.
+    pub tx_hash: Option<B256>,
+}
+
+/// Transaction level infos of the `ctx` object which are only known after
+/// the execution.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct JsTxContext {
+    pub gas_limit: u64,
+    pub gas_used: u64,
+    pub gas_price: u64,
+    pub block_number: u64,
+    pub timestamp: u64,
+}
+
+/// Represents an active call
+#[derive(Clone, Debug, Default)]
+struct CallStackItem {
+    contract: Contract,
+    kind: CallKind,
+    gas_limit: u64,
+    space: Space,
+}
+
+/// The fields of a step which outlive the step itself.
+#[derive(Clone, Debug)]
+struct StepInfo {
+    op: u8,
+    pc: u64,
+    gas_remaining: u64,
+    cost: u64,
+    depth: u64,
+    contract: Contract,
+}
+
+impl StepInfo {
+    fn into_log(
+        self, stack: StackRef, memory: MemoryRef, error: Option<String>,
+    ) -> StepLog {
+        StepLog {
+            stack,
+            op: self.op.into(),
+            memory,
+            pc: self.pc,
+            gas_remaining: self.gas_remaining,
+            cost: self.cost,
+            depth: self.depth,
+            // the refund counter is kept in the substate, which is not
+            // available to the tracer
+            refund: 0,
+            error,
+            contract: self.contract,
+        }
+    }
+}
+
+/// Error variants that can occur during JavaScript inspection.
+#[derive(Debug, thiserror::Error)]
+pub enum JsInspectorError {
+    /// Error originating from a JavaScript operation.
+    #[error(transparent)]
+    JsError(#[from] JsError),
+
+    /// Failure during the evaluation of JavaScript code.
+    #[error("failed to evaluate JS code: {0}")]
+    EvalCode(JsError),
+
+    /// The evaluated code is not a JavaScript object.
+    #[error("the evaluated code is not a JS object")]
+    ExpectedJsObject,
+
+    /// The trace object must expose a function named `result()`.
+    #[error("trace object must expose a function result()")]
+    ResultFunctionMissing,
+
+    /// The trace object must expose a function named `fault()`.
+    #[error("trace object must expose a function fault()")]
+    FaultFunctionMissing,
+
+    /// The setup object must be a callable function.
+    #[error("setup object must be a function")]
+    SetupFunctionNotCallable,
+
+    /// Failure during the invocation of the `setup()` function.
+    #[error("failed to call setup(): {0}")]
+    SetupCallFailed(JsError),
+
+    /// Invalid JSON configuration encountered.
+    #[error("invalid JSON config: {0}")]
+    InvalidJsonConfig(JsError),
+
+    /// The timeout of the tracer is exceeded.
+    #[error("execution timeout")]
+    Timeout,
+
+    /// The tracer allocated more than [`HEAP_SIZE_LIMIT`].
+    #[error("tracer exceeds the heap size limit of {HEAP_SIZE_LIMIT} bytes")]
+    HeapSizeLimit,
+}
+
+/// Parses a tracer `timeout` in the format of Go's `time.ParseDuration`, e.g.
+/// `300ms` or `1m30s`.
+pub fn parse_timeout(s: &str) -> Result<Duration, String> {
+    let invalid = || format!("invalid timeout {s:?}");
+    if s == "0" {
+        return Ok(Duration::ZERO);
+    }
+
+    let mut rest = s;
+    let mut total = 0f64;
+    if rest.is_empty() {
+        return Err(invalid());
+    }
+    while !rest.is_empty() {
+        let num_len = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .ok_or_else(invalid)?;
+        let value: f64 = rest[..num_len].parse().map_err(|_| invalid())?;
+        rest = &rest[num_len..];
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(rest.len());
+        let nanos_per_unit = match &rest[..unit_len] {
+            "ns" => 1f64,
+            "us" | "µs" => 1e3,
+            "ms" => 1e6,
+            "s" => 1e9,
+            "m" => 60e9,
+            "h" => 3600e9,
+            _ => return Err(invalid()),
+        };
+        total += value * nanos_per_unit;
+        rest = &rest[unit_len..];
+    }
+    Ok(Duration::from_nanos(total as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use malloc_size_of::allocation::CountingAllocator;
+
+    #[global_allocator]
+    static ALLOC: CountingAllocator<std::alloc::System> =
+        CountingAllocator::new(std::alloc::System);
+
+    #[test]
+    fn test_loop_iteration_limit() {
+        let mut context = Context::default();
+        context
+            .runtime_limits_mut()
+            .set_loop_iteration_limit(LOOP_ITERATION_LIMIT);
+
+        let result = context.eval(Source::from_bytes(
+            r"
+            let i = 0;
+            while (true) {
+                i++;
+            }
+        ",
+        ));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_timeout() {
+        assert_eq!(parse_timeout("5s"), Ok(Duration::from_secs(5)));
+        assert_eq!(parse_timeout("300ms"), Ok(Duration::from_millis(300)));
+        assert_eq!(parse_timeout("1m30s"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_timeout("1.5h"), Ok(Duration::from_secs(5400)));
+        assert!(parse_timeout("").is_err());
+        assert!(parse_timeout("10").is_err());
+        assert!(parse_timeout("5 s").is_err());
+    }
+
+    #[test]
+    fn test_missing_functions() {
+        let new = |code: &str| {
+            JsInspector::new(
+                code.to_string(),
+                serde_json::Value::Null,
+                Default::default(),
+                DEFAULT_TIMEOUT,
+            )
+        };
+        assert!(matches!(
+            new("{fault: function() {}}"),
+            Err(JsInspectorError::ResultFunctionMissing)
+        ));
+        assert!(matches!(
+            new("{result: function() {}}"),
+            Err(JsInspectorError::FaultFunctionMissing)
+        ));
+        assert!(matches!(
+            new("{result: function() {}, fault: function() {"),
+            Err(JsInspectorError::EvalCode(_))
+        ));
+        assert!(
+            new("{result: function() { return 1 }, fault: function() {}}")
+                .is_ok()
+        );
+    }
+
+    fn new_with_setup(
+        setup: &str, timeout: Duration,
+    ) -> Result<JsInspector, JsInspectorError> {
+        let code = format!(
+            "{{result: function() {{}}, fault: function() {{}}, \
+             setup: function() {{ {setup} }}}}"
+        );
+        JsInspector::new(
+            code,
+            serde_json::Value::Null,
+            Default::default(),
+            timeout,
+        )
+    }
+
+    #[test]
+    fn test_timeout() {
+        // The loop limit doesn't apply to each loop of the nested calls.
+        let start = Instant::now();
+        let result = new_with_setup(
+            "(function() { while (true) {} })()",
+            Duration::from_millis(200),
+        );
+        assert!(matches!(result, Err(JsInspectorError::Timeout)));
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_heap_size_limit() {
+        let result = new_with_setup(
+            "this.a = []; \
+             for (let i = 0; ; i++) { this.a.push('x'.repeat(1 << 20) + i) }",
+            Duration::from_secs(60),
+        );
+        assert!(matches!(result, Err(JsInspectorError::HeapSizeLimit)));
+    }
+
+    #[test]
+    fn test_check_skips_setup() {
+        let setup = "throw new Error('setup')";
+        let code = format!(
+            "{{result: function() {{}}, fault: function() {{}}, \
+             setup: function() {{ {setup} }}}}"
+        );
+        assert!(JsInspector::check(
+            code,
+            serde_json::Value::Null,
+            DEFAULT_TIMEOUT
+        )
+        .is_ok());
+        assert!(matches!(
+            new_with_setup(setup, DEFAULT_TIMEOUT),
+            Err(JsInspectorError::SetupCallFailed(_))
+        ));
+    }
+}
@@ -1,10 +1,12 @@
 #![allow(unused)]
 mod arena;
 mod config;
+mod erc7562;
 mod fourbyte;
 mod gas;
 mod geth_builder;
 mod geth_tracer;
+mod js;
 mod tracing_inspector;
 mod types;
 mod utils;
@@ -13,7 +15,10 @@ use arena::CallTraceArena;
 use config::TracingInspectorConfig;
 use geth_builder::GethTraceBuilder;
 
-pub use geth_tracer::{GethTraceKey, GethTracer};
+pub use geth_tracer::{
+    check_js_tracer, check_tracing_options, erc7562_config_from, GethTraceKey,
+    GethTracer,
+};
 pub use types::{GethTraceWithHash, TxExecContext};
 pub use utils::{
     from_alloy_address, to_alloy_address, to_alloy_h256, to_alloy_u256,
@@ -48,7 +48,7 @@ use revm_interpreter::{InstructionResult, InterpreterResult};
 
 use cfx_vm_types::InterpreterInfo;
 
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 
 #[derive(Clone)]
 pub struct TracingInspector {
@@ -190,6 +190,18 @@ impl TracingInspector {
         false
     }
 
+    /// Returns the addresses of the precompiles of the given space.
+    pub fn precompiles(&self, space: Space) -> HashSet<Address> {
+        let builtins = match space {
+            Space::Native => self.machine.builtins(),
+            Space::Ethereum => self.machine.builtins_evm(),
+        };
+        builtins
+            .keys()
+            .map(|addr| to_alloy_address(*addr))
+            .collect()
+    }
+
     /// Returns the currently active call trace.
     ///
     /// This will be the last call trace pushed to the stack: the call we
@@ -30,8 +30,16 @@
 //! Types for representing call trace items.
 use crate::{config::TraceStyle, utils, utils::convert_memory};
 use alloy_primitives::{Address, Bytes, LogData, U256};
-use alloy_rpc_types_trace::geth::{
-    CallFrame, CallLogFrame, GethDefaultTracingOptions, GethTrace, StructLog,
+use alloy_rpc_types_trace::{
+    geth::{
+        erc7562::{CallFrameType, Erc7562Frame},
+        CallFrame, CallLogFrame, GethDefaultTracingOptions, GethTrace,
+        StructLog,
+    },
+    parity::{
+        Action, CallAction, CallOutput, CallType, CreateAction, CreateOutput,
+        CreationMethod, SelfdestructAction, TraceOutput, TransactionTrace,
+    },
 };
 use cfx_types::{Space, H256};
 use cfx_vm_types::CallType as CfxCallType;
@@ -274,6 +282,119 @@ impl CallTraceNode {
 
         call_frame
     }
+
+    /// Converts this call trace into an _empty_ [Erc7562Frame], the opcode
+    /// level data is filled in by the caller.
+    pub fn geth_empty_erc7562_frame(&self, include_logs: bool) -> Erc7562Frame {
+        let CallFrame {
+            from,
+            gas,
+            gas_used,
+            to,
+            input,
+            output,
+            error,
+            revert_reason,
+            logs,
+            value,
+            ..
+        } = self.geth_empty_call_frame(include_logs);
+
+        Erc7562Frame {
+            call_frame_type: self.trace.kind.into(),
+            from,
+            gas: gas.saturating_to(),
+            gas_used: gas_used.saturating_to(),
+            to,
+            input,
+            output,
+            error,
+            revert_reason,
+            logs,
+            value,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the parity [Action] of this call trace.
+    pub fn parity_action(&self) -> Action {
+        if self.kind().is_any_create() {
+            Action::Create(CreateAction {
+                from: self.trace.caller,
+                gas: self.trace.gas_limit,
+                init: self.trace.data.clone(),
+                value: self.trace.value,
+                creation_method: self.kind().into(),
+            })
+        } else {
+            Action::Call(CallAction {
+                from: self.trace.caller,
+                call_type: self.kind().into(),
+                gas: self.trace.gas_limit,
+                input: self.trace.data.clone(),
+                to: self.trace.address,
+                value: self.trace.value,
+            })
+        }
+    }
+
+    /// Returns the parity [TraceOutput] of this call trace, `None` if the
+    /// call failed.
+    pub fn parity_trace_output(&self) -> Option<TraceOutput> {
+        if !self.trace.success {
+            return None;
+        }
+        let output = if self.kind().is_any_create() {
+            TraceOutput::Create(CreateOutput {
+                address: self.trace.address,
+                code: self.trace.output.clone(),
+                gas_used: self.trace.gas_used,
+            })
+        } else {
+            TraceOutput::Call(CallOutput {
+                gas_used: self.trace.gas_used,
+                output: self.trace.output.clone(),
+            })
+        };
+        Some(output)
+    }
+
+    /// Converts this call trace into a parity [TransactionTrace] at the given
+    /// position of the call tree.
+    ///
+    /// Selfdestructs are accounted as an additional subtrace, see
+    /// [Self::parity_selfdestruct_trace].
+    pub(crate) fn parity_transaction_trace(
+        &self, trace_address: Vec<usize>, style: TraceStyle,
+    ) -> TransactionTrace {
+        TransactionTrace {
+            action: self.parity_action(),
+            error: self.trace.as_error_msg(style),
+            result: self.parity_trace_output(),
+            subtraces: self.children.len() + self.is_selfdestruct() as usize,
+            trace_address,
+        }
+    }
+
+    /// If the trace is a selfdestruct, returns the parity [TransactionTrace]
+    /// of it, which is the last subtrace of this call.
+    pub fn parity_selfdestruct_trace(
+        &self, mut trace_address: Vec<usize>,
+    ) -> Option<TransactionTrace> {
+        let refund_address = self.trace.selfdestruct_refund_target?;
+        trace_address.push(self.children.len());
+        Some(TransactionTrace {
+            action: Action::Selfdestruct(SelfdestructAction {
+                address: self.trace.address,
+                balance: self.trace.value,
+                refund_address,
+            }),
+            error: None,
+            result: None,
+            subtraces: 0,
+            trace_address,
+        })
+    }
 }
 
 /// A unified representation of a call.
@@ -362,6 +483,41 @@ impl From<CreateScheme> for CallKind {
     }
 }
 
+impl From<CallKind> for CallType {
+    fn from(kind: CallKind) -> Self {
+        match kind {
+            CallKind::Call => Self::Call,
+            CallKind::StaticCall => Self::StaticCall,
+            CallKind::CallCode => Self::CallCode,
+            CallKind::DelegateCall => Self::DelegateCall,
+            CallKind::Create | CallKind::Create2 => Self::None,
+        }
+    }
+}
+
+impl From<CallKind> for CreationMethod {
+    fn from(kind: CallKind) -> Self {
+        match kind {
+            CallKind::Create => Self::Create,
+            CallKind::Create2 => Self::Create2,
+            _ => Self::None,
+        }
+    }
+}
+
+impl From<CallKind> for CallFrameType {
+    fn from(kind: CallKind) -> Self {
+        match kind {
+            CallKind::Call => Self::Call,
+            CallKind::StaticCall => Self::StaticCall,
+            CallKind::CallCode => Self::CallCode,
+            CallKind::DelegateCall => Self::DelegateCall,
+            CallKind::Create => Self::Create,
+            CallKind::Create2 => Self::Create2,
+        }
+    }
+}
+
 impl From<CfxCallType> for CallKind {
     fn from(ct: CfxCallType) -> Self {
         match ct {
@@ -585,7 +741,8 @@ impl AsRef<[u8]> for RecordedMemory {
 }
 
 pub struct GethTraceWithHash {
-    pub trace: GethTrace,
+    /// The trace, or the error of a javascript tracer
+    pub trace: Result<GethTrace, String>,
     pub tx_hash: H256,
     pub space: Space,
 }
@@ -595,6 +752,15 @@ pub struct TxExecContext {
     pub tx_gas_limit: u64,
     pub block_number: BlockNumber,
     pub block_height: BlockHeight,
+    /// Hash of the (pivot) block the transaction is packed in
+    pub block_hash: H256,
+    pub tx_hash: H256,
+    /// Index of the transaction in the block of its space
+    pub tx_index: u64,
+    /// Gas price of the transaction
+    pub gas_price: u64,
+    /// Timestamp of the (pivot) block the transaction is packed in
+    pub block_timestamp: u64,
 }
 
 #[cfg(feature = "serde")]
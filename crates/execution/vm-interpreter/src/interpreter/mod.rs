@@ -592,6 +592,11 @@ impl<Cost: CostType, const CANCUN: bool> Interpreter<Cost, CANCUN> {
             Err(e) => return Err(InterpreterResult::Done(Err(e))),
         };
 
+        if self.do_trace {
+            context.trace_step_cost(self, requirements.gas_cost.as_u256());
+        }
+
+        let gasometer = self.gasometer.as_mut().expect(GASOMETER_PROOF);
         if let Err(e) = gasometer.verify_gas(&requirements.gas_cost) {
             return Err(InterpreterResult::Done(Err(e)));
         }
@@ -173,6 +173,15 @@ pub trait Context {
         let _ = interpreter;
     }
 
+    /// Called once the gas cost of the instruction is known, before it is
+    /// charged and executed.
+    fn trace_step_cost(
+        &mut self, interpreter: &dyn InterpreterInfo, cost: U256,
+    ) {
+        let _ = interpreter;
+        let _ = cost;
+    }
+
     fn trace_step_end(&mut self, interpreter: &dyn InterpreterInfo) {
         let _ = interpreter;
     }
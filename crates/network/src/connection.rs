@@ -85,7 +85,7 @@ pub enum WriteStatus {
     Complete,
 }
 
-const MAX_PAYLOAD_SIZE: usize = (1 << 24) - 1;
+pub(crate) const MAX_PAYLOAD_SIZE: usize = (1 << 24) - 1;
 
 pub trait GenericSocket: Read + Write {}
 
@@ -0,0 +1,577 @@
+// Copyright 2026 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+//! Optional per-session framing of the protocol packets.
+//!
+//! Peers advertise the framing capabilities they enable in an extension of
+//! the HELLO packet, and a session frames its protocol packets with the
+//! capabilities enabled on both sides. Peers that do not advertise any
+//! capability, e.g. of older versions, keep exchanging plain packets.
+//!
+//! A framed packet carries an extension with the flags of the transformations
+//! applied to its data. The data is compressed with snappy if it is large
+//! enough, and then encrypted with AES-256-GCM under the directional keys
+//! derived from the nonces exchanged during the handshake and the
+//! capabilities advertised by both peers, so that the capabilities can not be
+//! altered without failing the decryption. Stripping the encryption
+//! capability altogether is only detected with `require_encryption`.
+
+use crate::{connection::MAX_PAYLOAD_SIZE, Error, ProtocolId};
+use aes_gcm::{
+    aead::{Aead, KeyInit, Payload},
+    Aes256Gcm, Nonce,
+};
+use cfx_types::H256;
+use cfx_util_macros::bail;
+use keccak_hash::keccak;
+use log::debug;
+use priority_send_queue::SendQueuePriority;
+
+/// Compresses the protocol packets.
+pub const CAPABILITY_COMPRESSION: u8 = 0x01;
+/// Encrypts the protocol packets.
+pub const CAPABILITY_ENCRYPTION: u8 = 0x02;
+
+/// Extension of the HELLO packet with the capabilities of the sender.
+const EXTENSION_CAPABILITIES: u8 = 0x01;
+/// Extension of a protocol packet with the flags of its framing.
+const EXTENSION_FRAMING: u8 = 0x02;
+
+const FRAME_COMPRESSED: u8 = 0x01;
+const FRAME_ENCRYPTED: u8 = 0x02;
+
+/// The lane and the counter of the packet are appended to the encrypted data,
+/// and make up the AES-GCM nonce of the packet.
+const LANE_SIZE: usize = 1;
+const COUNTER_SIZE: usize = 8;
+const NONCE_SIZE: usize = 12;
+
+/// The packets are sealed when they are queued, and the queue of each
+/// priority is written in order, so the packets of each priority are counted
+/// in a lane of their own and received with increasing counters. The packets
+/// written immediately, bypassing the queues, have a lane of their own too.
+const LANES: usize = 4;
+const LANE_IMMEDIATE: u8 = 3;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SessionFramingConfig {
+    /// Compress the protocol packets with the peers supporting it.
+    pub compression: bool,
+    /// Only the packets larger than this size in bytes are compressed.
+    pub compression_threshold: usize,
+    /// Encrypt the protocol packets with the peers supporting it.
+    pub encryption: bool,
+    /// Disconnect the peers with which the protocol packets are not
+    /// encrypted. Implies `encryption`.
+    pub require_encryption: bool,
+}
+
+impl Default for SessionFramingConfig {
+    fn default() -> Self {
+        SessionFramingConfig {
+            compression: false,
+            compression_threshold: 1024,
+            encryption: false,
+            require_encryption: false,
+        }
+    }
+}
+
+impl SessionFramingConfig {
+    /// Returns the capabilities to advertise to a peer. Encryption is only
+    /// advertised if a session secret is established by the handshake.
+    pub fn capabilities(&self, has_session_secret: bool) -> u8 {
+        let mut capabilities = 0;
+        if self.compression {
+            capabilities |= CAPABILITY_COMPRESSION;
+        }
+        if (self.encryption || self.require_encryption) && has_session_secret {
+            capabilities |= CAPABILITY_ENCRYPTION;
+        }
+        capabilities
+    }
+}
+
+pub fn capabilities_extension(capabilities: u8) -> Vec<u8> {
+    vec![EXTENSION_CAPABILITIES, capabilities]
+}
+
+/// Returns the capabilities advertised in the extensions of a HELLO packet,
+/// which are none for the peers without the extension.
+pub fn find_capabilities(extensions: &[Vec<u8>]) -> u8 {
+    find_extension(extensions, EXTENSION_CAPABILITIES).unwrap_or(0)
+}
+
+/// Returns the framing flags in the extensions of a protocol packet, or
+/// `None` if the packet is not framed.
+pub fn find_framing(extensions: &[Vec<u8>]) -> Option<u8> {
+    find_extension(extensions, EXTENSION_FRAMING)
+}
+
+/// Extensions of unknown kinds are ignored, so that new kinds can be added
+/// without breaking the older peers.
+fn find_extension(extensions: &[Vec<u8>], kind: u8) -> Option<u8> {
+    extensions.iter().find_map(|extension| match extension[..] {
+        [k, value, ..] if k == kind => Some(value),
+        _ => None,
+    })
+}
+
+struct FrameCipher {
+    sealing: Aes256Gcm,
+    opening: Aes256Gcm,
+    /// Counters of the sealed packets in each lane, which make the nonce of
+    /// each packet unique under the sealing key.
+    sealing_counters: [u64; LANES],
+    /// The lowest counter accepted in each lane, so that the packets can not
+    /// be replayed.
+    opening_counters: [u64; LANES],
+}
+
+impl FrameCipher {
+    /// Each direction of the session has its own key, so the two peers never
+    /// seal with the same key and nonce. The keys are bound to the
+    /// capabilities advertised by the initiator and the recipient, so the
+    /// peers get different keys if the HELLO packets are tampered with.
+    fn new(
+        session_secret: &H256, originated: bool, initiator_capabilities: u8,
+        recipient_capabilities: u8,
+    ) -> Self {
+        let capabilities = [initiator_capabilities, recipient_capabilities];
+        let initiator_key =
+            Self::derive_key(session_secret, b"initiator", &capabilities);
+        let recipient_key =
+            Self::derive_key(session_secret, b"recipient", &capabilities);
+        let (sealing_key, opening_key) = if originated {
+            (initiator_key, recipient_key)
+        } else {
+            (recipient_key, initiator_key)
+        };
+        FrameCipher {
+            sealing: Aes256Gcm::new(sealing_key.as_bytes().into()),
+            opening: Aes256Gcm::new(opening_key.as_bytes().into()),
+            sealing_counters: [0; LANES],
+            opening_counters: [0; LANES],
+        }
+    }
+
+    fn derive_key(
+        session_secret: &H256, direction: &[u8], capabilities: &[u8],
+    ) -> H256 {
+        let mut input = session_secret.as_bytes().to_vec();
+        input.extend_from_slice(direction);
+        input.extend_from_slice(capabilities);
+        keccak(input)
+    }
+
+    fn nonce(lane: u8, counter: &[u8]) -> [u8; NONCE_SIZE] {
+        let mut nonce = [0u8; NONCE_SIZE];
+        nonce[NONCE_SIZE - COUNTER_SIZE - LANE_SIZE] = lane;
+        nonce[NONCE_SIZE - COUNTER_SIZE..].copy_from_slice(counter);
+        nonce
+    }
+
+    /// The protocol and the framing flags are authenticated along with the
+    /// data, so that they can not be altered either.
+    fn seal(
+        &mut self, lane: u8, aad: &[u8], data: &[u8],
+    ) -> Result<Vec<u8>, Error> {
+        let next = &mut self.sealing_counters[lane as usize];
+        let counter = next.to_be_bytes();
+        *next = match next.checked_add(1) {
+            Some(counter) => counter,
+            None => bail!("session packet counter exhausted"),
+        };
+
+        let mut sealed = self
+            .sealing
+            .encrypt(
+                Nonce::from_slice(&Self::nonce(lane, &counter)),
+                Payload { msg: data, aad },
+            )
+            .map_err(|_| Error::Msg("failed to encrypt packet".into()))?;
+        sealed.push(lane);
+        sealed.extend_from_slice(&counter);
+        Ok(sealed)
+    }
+
+    /// Packets of different lanes may be received in a different order than
+    /// they are sealed, but the counters of each lane must increase.
+    fn open(&mut self, aad: &[u8], data: &[u8]) -> Result<Vec<u8>, Error> {
+        if data.len() < LANE_SIZE + COUNTER_SIZE {
+            debug!("failed to open framed packet, counter missed");
+            bail!(Error::BadProtocol);
+        }
+        let (sealed, trailer) =
+            data.split_at(data.len() - LANE_SIZE - COUNTER_SIZE);
+        let (lane, counter) = (trailer[0], &trailer[LANE_SIZE..]);
+        if lane as usize >= LANES {
+            debug!("failed to open framed packet, unknown lane {}", lane);
+            bail!(Error::BadProtocol);
+        }
+        let value = u64::from_be_bytes(counter.try_into().expect("checked"));
+        let lowest = &mut self.opening_counters[lane as usize];
+        if value < *lowest {
+            debug!("failed to open framed packet, replayed counter {}", value);
+            bail!(Error::Auth);
+        }
+
+        let opened = self
+            .opening
+            .decrypt(
+                Nonce::from_slice(&Self::nonce(lane, counter)),
+                Payload { msg: sealed, aad },
+            )
+            .map_err(|_| {
+                debug!("failed to open framed packet, authentication failed");
+                Error::Auth
+            })?;
+        // The largest counter is never sealed.
+        *lowest = value.checked_add(1).ok_or(Error::Auth)?;
+        Ok(opened)
+    }
+}
+
+/// The framing negotiated for a session.
+pub struct SessionFraming {
+    /// `None` if compression is not negotiated.
+    compression_threshold: Option<usize>,
+    /// `None` if encryption is not negotiated.
+    cipher: Option<FrameCipher>,
+}
+
+impl SessionFraming {
+    /// Negotiates the framing with the capabilities advertised by this node
+    /// and the remote peer. Returns `None` if no capability is enabled on
+    /// both sides, in which case the packets are not framed.
+    pub fn negotiate(
+        config: &SessionFramingConfig, local_capabilities: u8,
+        remote_capabilities: u8, session_secret: Option<&H256>,
+        originated: bool,
+    ) -> Option<Self> {
+        let capabilities = local_capabilities & remote_capabilities;
+        let compression_threshold =
+            if capabilities & CAPABILITY_COMPRESSION != 0 {
+                Some(config.compression_threshold)
+            } else {
+                None
+            };
+        let cipher = match session_secret {
+            Some(secret) if capabilities & CAPABILITY_ENCRYPTION != 0 => {
+                let (initiator_capabilities, recipient_capabilities) =
+                    if originated {
+                        (local_capabilities, remote_capabilities)
+                    } else {
+                        (remote_capabilities, local_capabilities)
+                    };
+                Some(FrameCipher::new(
+                    secret,
+                    originated,
+                    initiator_capabilities,
+                    recipient_capabilities,
+                ))
+            }
+            _ => None,
+        };
+
+        if compression_threshold.is_none() && cipher.is_none() {
+            return None;
+        }
+        Some(SessionFraming {
+            compression_threshold,
+            cipher,
+        })
+    }
+
+    pub fn is_encrypted(&self) -> bool { self.cipher.is_some() }
+
+    /// Frames the data of a protocol packet, and returns the framed data
+    /// along with the extension to attach to the packet. `priority` is the
+    /// queue the packet is sent with, or `None` if it is written immediately.
+    pub fn frame(
+        &mut self, protocol: &ProtocolId, priority: Option<SendQueuePriority>,
+        mut data: Vec<u8>,
+    ) -> Result<(Vec<u8>, Vec<u8>), Error> {
+        let mut flags = 0;
+
+        if let Some(threshold) = self.compression_threshold {
+            if data.len() >= threshold {
+                let compressed = snap::raw::Encoder::new()
+                    .compress_vec(&data)
+                    .map_err(|e| Error::Msg(format!("{}", e)))?;
+                // Incompressible data, e.g. encrypted or already compressed
+                // payloads, is sent as it is.
+                if compressed.len() < data.len() {
+                    data = compressed;
+                    flags |= FRAME_COMPRESSED;
+                }
+            }
+        }
+
+        if let Some(cipher) = self.cipher.as_mut() {
+            flags |= FRAME_ENCRYPTED;
+            let lane = priority.map_or(LANE_IMMEDIATE, |p| p as u8);
+            data = cipher.seal(lane, &Self::aad(protocol, flags), &data)?;
+        }
+
+        Ok((data, vec![EXTENSION_FRAMING, flags]))
+    }
+
+    /// Restores the data of a protocol packet framed by the peer. Once the
+    /// framing is negotiated, all the protocol packets must be framed.
+    pub fn unframe(
+        framing: Option<&mut Self>, protocol: &ProtocolId, flags: Option<u8>,
+        data: &[u8],
+    ) -> Result<Vec<u8>, Error> {
+        let (framing, flags) = match (framing, flags) {
+            (None, None) => return Ok(data.to_vec()),
+            (Some(framing), Some(flags)) => (framing, flags),
+            _ => {
+                debug!("failed to unframe packet, unexpected framing");
+                bail!(Error::BadProtocol);
+            }
+        };
+
+        let mut data = if flags & FRAME_ENCRYPTED != 0 {
+            match framing.cipher.as_mut() {
+                Some(cipher) => {
+                    cipher.open(&Self::aad(protocol, flags), data)?
+                }
+                None => {
+                    debug!(
+                        "failed to unframe packet, encryption not negotiated"
+                    );
+                    bail!(Error::BadProtocol);
+                }
+            }
+        } else if framing.is_encrypted() {
+            debug!(
+                "failed to unframe packet, plain packet in encrypted session"
+            );
+            bail!(Error::BadProtocol);
+        } else {
+            data.to_vec()
+        };
+
+        if flags & FRAME_COMPRESSED != 0 {
+            if framing.compression_threshold.is_none() {
+                debug!("failed to unframe packet, compression not negotiated");
+                bail!(Error::BadProtocol);
+            }
+            let len = snap::raw::decompress_len(&data)
+                .map_err(|e| Error::Decoder(format!("{}", e)))?;
+            if len > MAX_PAYLOAD_SIZE {
+                debug!("failed to unframe packet, decompressed size {}", len);
+                bail!(Error::BadProtocol);
+            }
+            data = snap::raw::Decoder::new()
+                .decompress_vec(&data)
+                .map_err(|e| Error::Decoder(format!("{}", e)))?;
+        }
+
+        Ok(data)
+    }
+
+    fn aad(protocol: &ProtocolId, flags: u8) -> Vec<u8> {
+        let mut aad = protocol.to_vec();
+        aad.push(flags);
+        aad
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn negotiate_pair(
+        capabilities: u8,
+    ) -> (Option<SessionFraming>, Option<SessionFraming>) {
+        negotiate_with(capabilities, capabilities)
+    }
+
+    /// Negotiates the framing of both peers with the capabilities they
+    /// receive from each other.
+    fn negotiate_with(
+        initiator_received: u8, recipient_received: u8,
+    ) -> (Option<SessionFraming>, Option<SessionFraming>) {
+        let config = SessionFramingConfig {
+            compression: true,
+            compression_threshold: 16,
+            encryption: true,
+            require_encryption: false,
+        };
+        let capabilities = config.capabilities(true);
+        let secret = H256::random();
+        (
+            SessionFraming::negotiate(
+                &config,
+                capabilities,
+                initiator_received,
+                Some(&secret),
+                true,
+            ),
+            SessionFraming::negotiate(
+                &config,
+                capabilities,
+                recipient_received,
+                Some(&secret),
+                false,
+            ),
+        )
+    }
+
+    fn assert_roundtrip(
+        sender: &mut SessionFraming, receiver: &mut SessionFraming,
+    ) {
+        let protocol = [1, 2, 3];
+        let data = vec![7u8; 100];
+        let (framed, extension) = sender
+            .frame(&protocol, Some(SendQueuePriority::Normal), data.clone())
+            .unwrap();
+        let flags = find_framing(&[extension]);
+        assert_eq!(flags, Some(FRAME_COMPRESSED | FRAME_ENCRYPTED));
+
+        // Tampered packets and packets of other protocols are rejected.
+        let mut tampered = framed.clone();
+        tampered[0] ^= 1;
+        assert!(SessionFraming::unframe(
+            Some(receiver),
+            &protocol,
+            flags,
+            &tampered
+        )
+        .is_err());
+        assert!(SessionFraming::unframe(
+            Some(receiver),
+            &[3, 2, 1],
+            flags,
+            &framed
+        )
+        .is_err());
+
+        assert_eq!(
+            SessionFraming::unframe(Some(receiver), &protocol, flags, &framed)
+                .unwrap(),
+            data
+        );
+
+        // Plain packets are rejected once encryption is negotiated.
+        assert!(SessionFraming::unframe(
+            Some(receiver),
+            &protocol,
+            None,
+            &data
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_frame_roundtrip() {
+        let (initiator, recipient) =
+            negotiate_pair(CAPABILITY_COMPRESSION | CAPABILITY_ENCRYPTION);
+        let (mut initiator, mut recipient) =
+            (initiator.unwrap(), recipient.unwrap());
+        assert_roundtrip(&mut initiator, &mut recipient);
+        assert_roundtrip(&mut recipient, &mut initiator);
+    }
+
+    #[test]
+    fn test_frame_replay() {
+        let (sender, receiver) = negotiate_pair(CAPABILITY_ENCRYPTION);
+        let (mut sender, mut receiver) = (sender.unwrap(), receiver.unwrap());
+        let protocol = [1, 2, 3];
+        let mut frame = |priority, data: u8| {
+            let (framed, extension) =
+                sender.frame(&protocol, priority, vec![data]).unwrap();
+            (framed, find_framing(&[extension]))
+        };
+        let normal_1 = frame(Some(SendQueuePriority::Normal), 1);
+        let normal_2 = frame(Some(SendQueuePriority::Normal), 2);
+        let high = frame(Some(SendQueuePriority::High), 3);
+        let immediate = frame(None, 4);
+        let mut unframe = |(framed, flags): &(Vec<u8>, Option<u8>)| {
+            SessionFraming::unframe(
+                Some(&mut receiver),
+                &protocol,
+                *flags,
+                framed,
+            )
+        };
+
+        // The packets of different priorities may overtake each other.
+        assert_eq!(unframe(&immediate).unwrap(), vec![4]);
+        assert_eq!(unframe(&high).unwrap(), vec![3]);
+        assert_eq!(unframe(&normal_1).unwrap(), vec![1]);
+
+        // Replayed packets are rejected.
+        assert!(unframe(&high).is_err());
+        assert!(unframe(&normal_1).is_err());
+        assert_eq!(unframe(&normal_2).unwrap(), vec![2]);
+        assert!(unframe(&normal_1).is_err());
+
+        // So are the packets of unknown lanes.
+        let (mut framed, flags) = normal_2.clone();
+        let lane = framed.len() - LANE_SIZE - COUNTER_SIZE;
+        framed[lane] = LANES as u8;
+        assert!(unframe(&(framed, flags)).is_err());
+    }
+
+    #[test]
+    fn test_frame_capabilities_tampered() {
+        // The compression capability of the initiator is stripped from the
+        // HELLO packet received by the recipient, so the keys differ.
+        let (initiator, recipient) = negotiate_with(
+            CAPABILITY_COMPRESSION | CAPABILITY_ENCRYPTION,
+            CAPABILITY_ENCRYPTION,
+        );
+        let (mut initiator, mut recipient) =
+            (initiator.unwrap(), recipient.unwrap());
+        let (framed, extension) = initiator
+            .frame(&[1, 2, 3], Some(SendQueuePriority::Normal), vec![1])
+            .unwrap();
+        assert!(SessionFraming::unframe(
+            Some(&mut recipient),
+            &[1, 2, 3],
+            find_framing(&[extension]),
+            &framed
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_frame_negotiation() {
+        assert!(negotiate_pair(0).0.is_none());
+        assert!(SessionFraming::unframe(None, &[1, 2, 3], None, &[4])
+            .is_ok_and(|data| data == vec![4]));
+
+        // Small packets are not compressed.
+        let (sender, receiver) = negotiate_pair(CAPABILITY_COMPRESSION);
+        let (mut sender, mut receiver) = (sender.unwrap(), receiver.unwrap());
+        let (framed, extension) = sender
+            .frame(&[1, 2, 3], Some(SendQueuePriority::Normal), vec![4])
+            .unwrap();
+        assert_eq!(framed, vec![4]);
+        assert_eq!(find_framing(&[extension]), Some(0));
+        assert!(SessionFraming::unframe(
+            Some(&mut receiver),
+            &[1, 2, 3],
+            Some(FRAME_ENCRYPTED),
+            &framed
+        )
+        .is_err());
+
+        // Encryption is only advertised with a session secret.
+        let config = SessionFramingConfig {
+            encryption: true,
+            ..Default::default()
+        };
+        assert_eq!(config.capabilities(false), 0);
+        assert_eq!(config.capabilities(true), CAPABILITY_ENCRYPTION);
+        let config = SessionFramingConfig {
+            require_encryption: true,
+            ..Default::default()
+        };
+        assert_eq!(config.capabilities(true), CAPABILITY_ENCRYPTION);
+    }
+}
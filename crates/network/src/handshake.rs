@@ -27,6 +27,7 @@ use crate::{
 };
 use cfx_types::{Public, H256};
 use io::{IoContext, StreamToken};
+use keccak_hash::keccak;
 use log::{debug, error, trace};
 use mio::net::TcpStream;
 use priority_send_queue::SendQueuePriority;
@@ -67,6 +68,10 @@ pub struct Handshake {
     pub state: HandshakeState,
     /// nonce for verification
     nonce: H256,
+    /// nonce of the remote node, known once the auth or ack of auth is read
+    remote_nonce: Option<H256>,
+    /// Whether the handshake is initiated by us
+    originated: bool,
 }
 
 impl Handshake {
@@ -79,6 +84,8 @@ impl Handshake {
             connection: Connection::new(token, socket),
             state: HandshakeState::New,
             nonce: H256::random(),
+            remote_nonce: None,
+            originated: id.is_some(),
         }
     }
 
@@ -101,6 +108,27 @@ impl Handshake {
     /// Check if handshake is complete
     pub fn done(&self) -> bool { self.state == HandshakeState::StartSession }
 
+    /// Returns the secret shared by the two nodes once the handshake is
+    /// complete. It is derived from the nonces of both nodes, which are only
+    /// exchanged encrypted with the node keys, so none is available if the
+    /// cryptography is bypassed.
+    pub fn session_secret(&self) -> Option<H256> {
+        if !self.done() {
+            return None;
+        }
+        let remote_nonce = self.remote_nonce?;
+        let (initiator_nonce, recipient_nonce) = if self.originated {
+            (self.nonce, remote_nonce)
+        } else {
+            (remote_nonce, self.nonce)
+        };
+
+        let mut data = Vec::with_capacity(2 * H256::len_bytes());
+        data.extend_from_slice(initiator_nonce.as_bytes());
+        data.extend_from_slice(recipient_nonce.as_bytes());
+        Some(keccak(data))
+    }
+
     /// Readable IO handler. Drives the state change.
     pub fn readable<Message>(
         &mut self, io: &IoContext<Message>, host: &HostMetadata,
@@ -192,6 +220,7 @@ impl Handshake {
 
         let (remote_public, remote_nonce) = auth.split_at(NodeId::len_bytes());
         self.id.assign_from_slice(remote_public);
+        self.remote_nonce = Some(H256::from_slice(remote_nonce));
 
         self.write_ack_of_auth(io, remote_nonce)
     }
@@ -267,6 +296,7 @@ impl Handshake {
             debug!("failed to read ack of auth, nonce mismatch");
             return Err(Error::BadProtocol);
         }
+        self.remote_nonce = Some(H256::from_slice(remote_nonce));
 
         self.write_ack_of_ack(io, remote_nonce)
     }
@@ -310,6 +310,7 @@ impl NodeIpLimit {
 #[cfg(test)]
 mod tests {
     use super::{NodeDatabase, NodeId, NodeIpLimit, ValidateInsertResult};
+    use crate::ReputationConfig;
     use std::{net::IpAddr, str::FromStr};
 
     fn new_ip(ip: &'static str) -> IpAddr { IpAddr::from_str(ip).unwrap() }
@@ -376,7 +377,7 @@ mod tests {
     #[test]
     fn test_insert_duplicate_id_ip() {
         let mut limit = NodeIpLimit::new(2);
-        let db = NodeDatabase::new(None, 2);
+        let db = NodeDatabase::new(None, 2, ReputationConfig::default());
 
         // quota is enough
         let n = NodeId::random();
@@ -401,7 +402,7 @@ mod tests {
     #[test]
     fn test_insert_occupy_ip_new_node() {
         let mut limit = NodeIpLimit::new(2);
-        let db = NodeDatabase::new(None, 2);
+        let db = NodeDatabase::new(None, 2, ReputationConfig::default());
 
         // insert n1
         let n1 = NodeId::random();
@@ -434,7 +435,7 @@ mod tests {
     #[test]
     fn test_insert_occupy_ip_update_node() {
         let mut limit = NodeIpLimit::new(2);
-        let db = NodeDatabase::new(None, 2);
+        let db = NodeDatabase::new(None, 2, ReputationConfig::default());
 
         // insert n1 and n2
         let n1 = NodeId::random();
@@ -496,7 +497,7 @@ mod tests {
     #[test]
     fn test_select_evictee() {
         let limit = NodeIpLimit::new(2);
-        let db = NodeDatabase::new(None, 2);
+        let db = NodeDatabase::new(None, 2, ReputationConfig::default());
 
         // select from empty bucket
         assert_eq!(limit.select_evictee(&new_ip("127.0.0.1"), &db), None);
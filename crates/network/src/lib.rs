@@ -14,11 +14,13 @@ pub type PeerId = usize;
 mod connection;
 mod discovery;
 mod error;
+mod framing;
 mod handshake;
 mod ip;
 mod ip_utils;
 mod node_database;
 pub mod node_table;
+mod reputation;
 pub mod service;
 mod session;
 mod session_manager;
@@ -26,8 +28,10 @@ pub mod throttling;
 
 pub use crate::{
     error::{DisconnectReason, Error, ThrottlingReason},
+    framing::SessionFramingConfig,
     ip::SessionIpLimitConfig,
     node_table::Node,
+    reputation::{Misbehavior, PeerScore, ReputationConfig},
     service::NetworkService,
     session::SessionDetails,
 };
@@ -113,6 +117,11 @@ pub struct NetworkConfiguration {
     /// Maximum number of P2P nodes for subnet B (ip/16).
     pub subnet_quota: usize,
     pub session_ip_limit_config: SessionIpLimitConfig,
+    /// Compression and encryption of protocol packets with the peers
+    /// supporting them.
+    pub session_framing: SessionFramingConfig,
+    /// Reputation scoring of the peers by their misbehaviors.
+    pub reputation: ReputationConfig,
 
     pub discovery_config: DiscoveryConfiguration,
 }
@@ -150,6 +159,8 @@ impl NetworkConfiguration {
             test_mode: false,
             subnet_quota: 32,
             session_ip_limit_config: SessionIpLimitConfig::default(),
+            session_framing: SessionFramingConfig::default(),
+            reputation: ReputationConfig::default(),
             discovery_config,
         }
     }
@@ -286,6 +297,10 @@ pub trait NetworkContext {
         &self, node_id: &NodeId, op: Option<UpdateNodeOperation>, reason: &str,
     );
 
+    /// Lowers the reputation score of the peer, which is disconnected or
+    /// banned once its score drops to the thresholds.
+    fn note_misbehavior(&self, node_id: &NodeId, misbehavior: Misbehavior);
+
     /// Register a new IO timer. 'IoHandler::timeout' will be called with the
     /// token.
     fn register_timer(
@@ -5,14 +5,23 @@
 use crate::{
     ip::{NodeIpLimit, NodeTagIndex, ValidateInsertResult},
     node_table::{Node, NodeContact, NodeEntry, NodeId, NodeTable},
+    reputation::{
+        Misbehavior, PeerScore, ReputationAction, ReputationConfig,
+        ReputationTable,
+    },
     IpFilter,
 };
 use io::StreamToken;
-use std::{collections::HashSet, net::IpAddr, time::Duration};
+use std::{
+    collections::HashSet,
+    net::IpAddr,
+    time::{Duration, SystemTime},
+};
 
 const TRUSTED_NODES_FILE: &str = "trusted_nodes.json";
 const UNTRUSTED_NODES_FILE: &str = "untrusted_nodes.json";
 const BLACKLISTED_NODES_FILE: &str = "blacklisted_nodes.json";
+const PEER_SCORES_FILE: &str = "peer_scores.json";
 
 /// Node database maintains all P2P nodes in trusted and untrusted node tables,
 /// and supports to limit the number of nodes for the same IP address.
@@ -102,16 +111,34 @@ pub struct NodeDatabase {
     // 3. remove tag indices when demote a node
     // 4. remove tag indices when delete a trusted node
     trusted_node_tag_index: NodeTagIndex,
+
+    // Reputation scores of the nodes, and the timed bans by the scores or
+    // requested manually. A banned node is also in the blacklisted node table
+    // until the ban is lifted.
+    reputation: ReputationTable,
+    // Nodes moved to the blacklisted node table by a ban, rather than
+    // blacklisted for other reasons, so that only they are removed from the
+    // table once the ban expires. Not persisted, so after a restart the
+    // blacklisted lifetime applies to them.
+    blacklisted_by_ban: HashSet<NodeId>,
 }
 
 impl NodeDatabase {
-    pub fn new(path: Option<String>, subnet_quota: usize) -> Self {
+    pub fn new(
+        path: Option<String>, subnet_quota: usize,
+        reputation_config: ReputationConfig,
+    ) -> Self {
         let trusted_nodes = NodeTable::new(path.clone(), TRUSTED_NODES_FILE);
         let untrusted_nodes =
             NodeTable::new(path.clone(), UNTRUSTED_NODES_FILE);
         let ip_limit = NodeIpLimit::new(subnet_quota);
         let trusted_node_tag_index =
             NodeTagIndex::new_with_node_table(&trusted_nodes);
+        let reputation = ReputationTable::new(
+            reputation_config,
+            path.clone(),
+            PEER_SCORES_FILE,
+        );
 
         let mut db = NodeDatabase {
             trusted_nodes,
@@ -120,6 +147,8 @@ impl NodeDatabase {
             blacklisted_lifetime: Duration::from_secs(7 * 24 * 3600),
             ip_limit,
             trusted_node_tag_index,
+            reputation,
+            blacklisted_by_ban: HashSet::new(),
         };
 
         db.init(false /* trusted */);
@@ -384,6 +413,8 @@ impl NodeDatabase {
         self.untrusted_nodes.clear_useless();
 
         self.blacklisted_nodes.save();
+
+        self.reputation.save();
     }
 
     /// Promote untrusted nodes to trusted with the given duration.
@@ -550,6 +581,20 @@ impl NodeDatabase {
 
     /// Set the specified node to blacklisted.
     pub fn set_blacklisted(&mut self, id: &NodeId) {
+        self.blacklisted_by_ban.remove(id);
+        self.move_to_blacklisted(id);
+    }
+
+    /// Set the specified node to blacklisted for a ban, unless it is already
+    /// blacklisted for other reasons.
+    fn set_blacklisted_by_ban(&mut self, id: &NodeId) {
+        if !self.blacklisted_nodes.contains(id) {
+            self.blacklisted_by_ban.insert(*id);
+        }
+        self.move_to_blacklisted(id);
+    }
+
+    fn move_to_blacklisted(&mut self, id: &NodeId) {
         // update the last failure time
         self.note_failure(
             id, true,  /* by_connection */
@@ -567,6 +612,19 @@ impl NodeDatabase {
     /// If blacklisted for a long time, it will be removed from blacklisted node
     /// table.
     pub fn evaluate_blacklisted(&mut self, id: &NodeId) -> bool {
+        if let Some(banned_until) = self.reputation.banned_until(id) {
+            if banned_until > SystemTime::now() {
+                return true;
+            }
+            // The ban has expired, lift it along with the blacklisting by the
+            // ban, but keep the node blacklisted for other reasons.
+            self.reputation.unban(id);
+            if self.blacklisted_by_ban.remove(id) {
+                self.blacklisted_nodes.remove_with_id(id);
+                return false;
+            }
+        }
+
         let node = match self.blacklisted_nodes.get_mut(id) {
             Some(node) => node,
             None => return false,
@@ -592,12 +650,49 @@ impl NodeDatabase {
 
         true
     }
+
+    /// Lowers the reputation score of the specified node, and bans it if its
+    /// score drops to the ban threshold.
+    pub fn note_misbehavior(
+        &mut self, id: &NodeId, misbehavior: Misbehavior,
+    ) -> ReputationAction {
+        let action = self.reputation.note_misbehavior(id, misbehavior);
+        if action == ReputationAction::Ban {
+            self.set_blacklisted_by_ban(id);
+        }
+        action
+    }
+
+    /// Ban the specified node for the given duration, or the configured ban
+    /// duration if not specified.
+    pub fn ban(&mut self, id: &NodeId, duration: Option<Duration>) {
+        let duration =
+            duration.unwrap_or(self.reputation.config().ban_duration);
+        self.reputation.ban(id, duration);
+        self.set_blacklisted_by_ban(id);
+    }
+
+    /// Lift the ban of the specified node, and remove it from the blacklisted
+    /// node table. Returns `true` if the node is banned or blacklisted.
+    pub fn unban(&mut self, id: &NodeId) -> bool {
+        let banned = self.reputation.unban(id);
+        self.blacklisted_by_ban.remove(id);
+        let blacklisted = self.blacklisted_nodes.remove_with_id(id).is_some();
+        banned || blacklisted
+    }
+
+    /// Returns the reputation scores of the nodes that misbehaved or are
+    /// banned.
+    pub fn peer_scores(&self) -> Vec<PeerScore> { self.reputation.scores() }
 }
 
 #[cfg(test)]
 mod tests {
     use super::NodeDatabase;
-    use crate::node_table::{NodeEndpoint, NodeEntry, NodeId};
+    use crate::{
+        node_table::{NodeEndpoint, NodeEntry, NodeId},
+        reputation::{Misbehavior, ReputationConfig},
+    };
     use std::{str::FromStr, time::Duration};
 
     fn new_entry(addr: &str) -> NodeEntry {
@@ -609,7 +704,7 @@ mod tests {
 
     #[test]
     fn test_insert_with_token_added() {
-        let mut db = NodeDatabase::new(None, 2);
+        let mut db = NodeDatabase::new(None, 2, ReputationConfig::default());
 
         // add a new node
         let entry = new_entry("127.0.0.1:999");
@@ -627,7 +722,7 @@ mod tests {
 
     #[test]
     fn test_insert_with_token_updated_trusted() {
-        let mut db = NodeDatabase::new(None, 2);
+        let mut db = NodeDatabase::new(None, 2, ReputationConfig::default());
 
         // add trusted node, whose token is None
         let entry = new_entry("127.0.0.1:999");
@@ -651,7 +746,7 @@ mod tests {
 
     #[test]
     fn test_insert_with_token_updated_untrusted() {
-        let mut db = NodeDatabase::new(None, 2);
+        let mut db = NodeDatabase::new(None, 2, ReputationConfig::default());
 
         let entry = new_entry("127.0.0.1:999");
         db.insert_with_token(entry.clone(), 5);
@@ -669,7 +764,7 @@ mod tests {
 
     #[test]
     fn test_insert_with_token_updated_new_ip() {
-        let mut db = NodeDatabase::new(None, 2);
+        let mut db = NodeDatabase::new(None, 2, ReputationConfig::default());
 
         let entry1 = new_entry("127.0.0.1:999");
         db.insert_with_token(entry1.clone(), 5);
@@ -685,7 +780,7 @@ mod tests {
 
     #[test]
     fn test_insert_with_token_updated_ip_exists() {
-        let mut db = NodeDatabase::new(None, 2);
+        let mut db = NodeDatabase::new(None, 2, ReputationConfig::default());
 
         // add node1
         let entry1 = new_entry("127.0.0.1:999");
@@ -711,7 +806,7 @@ mod tests {
 
     #[test]
     fn test_demote() {
-        let mut db = NodeDatabase::new(None, 2);
+        let mut db = NodeDatabase::new(None, 2, ReputationConfig::default());
 
         // add a trusted node
         let entry = new_entry("127.0.0.1:999");
@@ -726,7 +821,7 @@ mod tests {
 
     #[test]
     fn test_remove() {
-        let mut db = NodeDatabase::new(None, 2);
+        let mut db = NodeDatabase::new(None, 2, ReputationConfig::default());
 
         // add trusted node
         let entry1 = new_entry("127.0.0.1:999");
@@ -752,7 +847,7 @@ mod tests {
 
     #[test]
     fn test_blacklisted() {
-        let mut db = NodeDatabase::new(None, 2);
+        let mut db = NodeDatabase::new(None, 2, ReputationConfig::default());
 
         let n = new_entry("127.0.0.1:999");
         db.insert_trusted(n.clone());
@@ -767,7 +862,7 @@ mod tests {
 
     #[test]
     fn test_blacklisted_lifetime() {
-        let mut db = NodeDatabase::new(None, 2);
+        let mut db = NodeDatabase::new(None, 2, ReputationConfig::default());
 
         let n = new_entry("127.0.0.1:999");
         db.insert_trusted(n.clone());
@@ -779,4 +874,66 @@ mod tests {
         assert_eq!(db.evaluate_blacklisted(&n.id), false);
         assert_eq!(db.get(&n.id, false), None);
     }
+
+    #[test]
+    fn test_ban_by_reputation() {
+        let mut db = NodeDatabase::new(None, 2, ReputationConfig::default());
+
+        let n = new_entry("127.0.0.1:999");
+        db.insert_trusted(n.clone());
+        db.note_misbehavior(&n.id, Misbehavior::InvalidBlock);
+        assert_eq!(db.evaluate_blacklisted(&n.id), false);
+
+        db.note_misbehavior(&n.id, Misbehavior::InvalidBlock);
+        db.note_misbehavior(&n.id, Misbehavior::InvalidBlock);
+        assert_eq!(db.evaluate_blacklisted(&n.id), true);
+        assert_eq!(db.get(&n.id, false), None);
+        assert_eq!(db.peer_scores()[0].id, n.id);
+
+        // banned nodes are refused to insert
+        db.insert_trusted(n.clone());
+        assert_eq!(db.get(&n.id, false), None);
+
+        assert_eq!(db.unban(&n.id), true);
+        assert_eq!(db.evaluate_blacklisted(&n.id), false);
+        db.insert_trusted(n.clone());
+        assert_eq!(db.get(&n.id, true).unwrap().id, n.id);
+    }
+
+    #[test]
+    fn test_ban_expired() {
+        let mut db = NodeDatabase::new(None, 2, ReputationConfig::default());
+
+        let n = new_entry("127.0.0.1:999");
+        db.insert_trusted(n.clone());
+        db.ban(&n.id, Some(Duration::from_millis(1)));
+        assert_eq!(db.get(&n.id, false), None);
+
+        std::thread::sleep(Duration::from_millis(2));
+        assert_eq!(db.evaluate_blacklisted(&n.id), false);
+        assert_eq!(db.unban(&n.id), false);
+    }
+
+    #[test]
+    fn test_ban_expired_keeps_blacklisted() {
+        let mut db = NodeDatabase::new(None, 2, ReputationConfig::default());
+
+        let n = new_entry("127.0.0.1:999");
+        db.insert_trusted(n.clone());
+        db.set_blacklisted(&n.id);
+        db.ban(&n.id, Some(Duration::from_millis(1)));
+
+        // the node is still blacklisted for the reason before the ban
+        std::thread::sleep(Duration::from_millis(2));
+        assert_eq!(db.evaluate_blacklisted(&n.id), true);
+        assert_eq!(db.unban(&n.id), true);
+        assert_eq!(db.evaluate_blacklisted(&n.id), false);
+
+        // blacklisted for another reason during the ban
+        db.insert_trusted(n.clone());
+        db.ban(&n.id, Some(Duration::from_millis(1)));
+        db.set_blacklisted(&n.id);
+        std::thread::sleep(Duration::from_millis(2));
+        assert_eq!(db.evaluate_blacklisted(&n.id), true);
+    }
 }
@@ -0,0 +1,416 @@
+// Copyright 2026 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use crate::node_table::NodeId;
+use log::{debug, warn};
+use serde::Deserialize;
+use serde_derive::Serialize;
+use std::{
+    collections::HashMap,
+    fs,
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Misbehaviors reported by the protocol handlers, each of which lowers the
+/// reputation score of the peer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Misbehavior {
+    /// A request to the peer timed out. It may be caused by the slowness of
+    /// the local node, so the peer is disconnected but never banned for it.
+    RequestTimeout,
+    /// The peer sent a message that is not expected, e.g. an unsolicited
+    /// response.
+    UnexpectedMessage,
+    /// The peer sent a malformed message, or a response that failed the
+    /// verification.
+    InvalidMessage,
+    /// The peer sent a block that failed the verification.
+    InvalidBlock,
+    /// The peer sent transactions that are invalid regardless of the state,
+    /// or too many transactions while the node catches up.
+    SpamTransactions,
+}
+
+impl Misbehavior {
+    fn penalty(&self) -> f64 {
+        match self {
+            Misbehavior::RequestTimeout => 5.0,
+            Misbehavior::UnexpectedMessage => 10.0,
+            Misbehavior::InvalidMessage => 25.0,
+            Misbehavior::InvalidBlock => 50.0,
+            Misbehavior::SpamTransactions => 10.0,
+        }
+    }
+
+    /// Whether the misbehavior is surely caused by the peer, so that it can
+    /// get the peer banned.
+    fn is_bannable(&self) -> bool {
+        !matches!(self, Misbehavior::RequestTimeout)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReputationConfig {
+    /// Duration for a score to decay to half of it.
+    pub half_life: Duration,
+    /// The peers whose score drops to this are disconnected.
+    pub disconnect_threshold: f64,
+    /// The peers whose score drops to this are banned.
+    pub ban_threshold: f64,
+    /// Duration of the bans by reputation, and of the bans requested without
+    /// an explicit duration.
+    pub ban_duration: Duration,
+}
+
+impl Default for ReputationConfig {
+    fn default() -> Self {
+        ReputationConfig {
+            half_life: Duration::from_secs(1800),
+            disconnect_threshold: -50.0,
+            ban_threshold: -100.0,
+            ban_duration: Duration::from_secs(3600),
+        }
+    }
+}
+
+/// What to do with a peer after its score is lowered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReputationAction {
+    None,
+    Disconnect,
+    Ban,
+}
+
+/// Reputation score of a peer, as persisted and reported by RPC.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerScore {
+    pub id: NodeId,
+    /// Score decayed to `updated_at`. It starts from 0 and only goes down
+    /// with misbehaviors.
+    pub score: f64,
+    /// UNIX timestamp in seconds.
+    pub updated_at: u64,
+    /// UNIX timestamp in seconds.
+    pub banned_until: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ScoreEntry {
+    score: f64,
+    updated_at: SystemTime,
+    banned_until: Option<SystemTime>,
+}
+
+/// Reputation scores of the peers, backed by a disk file.
+///
+/// Scores decay exponentially towards 0, so occasional misbehaviors of
+/// honest peers, e.g. timeouts due to network issues, are forgiven over time,
+/// while the peers that keep misbehaving are disconnected and then banned.
+pub struct ReputationTable {
+    config: ReputationConfig,
+    scores: HashMap<NodeId, ScoreEntry>,
+    path: Option<PathBuf>,
+}
+
+impl ReputationTable {
+    pub fn new(
+        config: ReputationConfig, dir: Option<String>, filename: &str,
+    ) -> Self {
+        let path = dir.map(|dir| {
+            let mut buf = PathBuf::from(dir);
+            buf.push(filename);
+            buf
+        });
+
+        let mut table = ReputationTable {
+            config,
+            scores: HashMap::new(),
+            path,
+        };
+        table.load_from_file();
+        table
+    }
+
+    pub fn config(&self) -> &ReputationConfig { &self.config }
+
+    fn decayed(&self, entry: &ScoreEntry, now: SystemTime) -> f64 {
+        let elapsed = now
+            .duration_since(entry.updated_at)
+            .unwrap_or_default()
+            .as_secs_f64();
+        entry.score * 0.5f64.powf(elapsed / self.config.half_life.as_secs_f64())
+    }
+
+    /// Returns the current score of the peer.
+    pub fn score(&self, id: &NodeId) -> f64 {
+        self.scores
+            .get(id)
+            .map_or(0.0, |entry| self.decayed(entry, SystemTime::now()))
+    }
+
+    /// Lowers the score of the peer for the misbehavior, and bans the peer
+    /// if its score drops to the ban threshold.
+    pub fn note_misbehavior(
+        &mut self, id: &NodeId, misbehavior: Misbehavior,
+    ) -> ReputationAction {
+        let now = SystemTime::now();
+        let current = self.score(id);
+        let mut score = current - misbehavior.penalty();
+        if !misbehavior.is_bannable() {
+            // Never lowers the score below the disconnect threshold.
+            score = score.max(current.min(self.config.disconnect_threshold));
+        }
+        let banned_until = self.banned_until(id);
+        self.scores.insert(
+            *id,
+            ScoreEntry {
+                score,
+                updated_at: now,
+                banned_until,
+            },
+        );
+        debug!(
+            "Peer {:?} misbehaved {:?}, reputation score = {}",
+            id, misbehavior, score
+        );
+
+        if score <= self.config.ban_threshold {
+            self.ban(id, self.config.ban_duration);
+            ReputationAction::Ban
+        } else if score <= self.config.disconnect_threshold {
+            ReputationAction::Disconnect
+        } else {
+            ReputationAction::None
+        }
+    }
+
+    /// Bans the peer for the given duration, or extends its ban.
+    pub fn ban(&mut self, id: &NodeId, duration: Duration) {
+        let now = SystemTime::now();
+        let score = self.score(id);
+        let until = now + duration;
+        let entry = self.scores.entry(*id).or_insert(ScoreEntry {
+            score,
+            updated_at: now,
+            banned_until: None,
+        });
+        entry.banned_until = Some(match entry.banned_until {
+            Some(banned_until) if banned_until > until => banned_until,
+            _ => until,
+        });
+    }
+
+    /// Lifts the ban of the peer, and returns `true` if it is banned.
+    pub fn unban(&mut self, id: &NodeId) -> bool {
+        match self.scores.get_mut(id) {
+            Some(entry) => entry.banned_until.take().is_some(),
+            None => false,
+        }
+    }
+
+    /// Returns the end of the ban of the peer, which may have passed.
+    pub fn banned_until(&self, id: &NodeId) -> Option<SystemTime> {
+        self.scores.get(id)?.banned_until
+    }
+
+    /// Returns the current scores of the peers, sorted from the lowest.
+    pub fn scores(&self) -> Vec<PeerScore> {
+        let now = SystemTime::now();
+        let mut scores: Vec<PeerScore> = self
+            .scores
+            .iter()
+            .map(|(id, entry)| PeerScore {
+                id: *id,
+                score: self.decayed(entry, now),
+                updated_at: unix_secs(now),
+                banned_until: entry.banned_until.map(unix_secs),
+            })
+            .collect();
+        scores.sort_by(|a, b| a.score.total_cmp(&b.score));
+        scores
+    }
+
+    /// Drops the peers that are not banned and whose score has decayed to
+    /// almost 0, which are the same as the peers never misbehaved.
+    fn prune(&mut self) {
+        let now = SystemTime::now();
+        let expired: Vec<NodeId> = self
+            .scores
+            .iter()
+            .filter(|(_, entry)| {
+                entry.banned_until.map_or(true, |until| until <= now)
+                    && self.decayed(entry, now) > -1.0
+            })
+            .map(|(id, _)| *id)
+            .collect();
+        for id in expired {
+            self.scores.remove(&id);
+        }
+    }
+
+    fn load_from_file(&mut self) {
+        let path = match self.path {
+            Some(ref path) => path,
+            None => return,
+        };
+
+        let file = match fs::File::open(path) {
+            Ok(file) => file,
+            Err(e) => {
+                debug!("reputation file not found: {:?}", e);
+                return;
+            }
+        };
+        match serde_json::from_reader::<_, Vec<PeerScore>>(file) {
+            Ok(scores) => {
+                for score in scores {
+                    self.scores.insert(
+                        score.id,
+                        ScoreEntry {
+                            score: score.score,
+                            updated_at: UNIX_EPOCH
+                                + Duration::from_secs(score.updated_at),
+                            banned_until: score.banned_until.map(|until| {
+                                UNIX_EPOCH + Duration::from_secs(until)
+                            }),
+                        },
+                    );
+                }
+            }
+            Err(e) => {
+                warn!("Error reading reputation file: {:?}", e);
+            }
+        }
+    }
+
+    /// Save the reputation file.
+    pub fn save(&mut self) {
+        self.prune();
+
+        let path = match self.path {
+            Some(ref path) => path,
+            None => return,
+        };
+
+        if let Some(dir) = path.parent() {
+            if let Err(e) = fs::create_dir_all(dir) {
+                warn!("Error creating reputation directory: {:?}", e);
+                return;
+            }
+        }
+
+        let scores: Vec<PeerScore> = self
+            .scores
+            .iter()
+            .map(|(id, entry)| PeerScore {
+                id: *id,
+                score: entry.score,
+                updated_at: unix_secs(entry.updated_at),
+                banned_until: entry.banned_until.map(unix_secs),
+            })
+            .collect();
+        match fs::File::create(path) {
+            Ok(file) => {
+                if let Err(e) = serde_json::to_writer_pretty(file, &scores) {
+                    warn!("Error writing reputation file: {:?}", e);
+                }
+            }
+            Err(e) => {
+                warn!("Error creating reputation file: {:?}", e);
+            }
+        }
+    }
+}
+
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_table() -> ReputationTable {
+        ReputationTable::new(ReputationConfig::default(), None, "")
+    }
+
+    #[test]
+    fn test_misbehavior_actions() {
+        let mut table = new_table();
+        let id = NodeId::random();
+
+        assert_eq!(
+            table.note_misbehavior(&id, Misbehavior::InvalidMessage),
+            ReputationAction::None
+        );
+        assert_eq!(
+            table.note_misbehavior(&id, Misbehavior::InvalidBlock),
+            ReputationAction::Disconnect
+        );
+        assert!(table.banned_until(&id).is_none());
+        assert_eq!(
+            table.note_misbehavior(&id, Misbehavior::InvalidBlock),
+            ReputationAction::Ban
+        );
+        assert!(table.banned_until(&id).unwrap() > SystemTime::now());
+        assert_eq!(table.scores()[0].id, id);
+
+        assert!(table.unban(&id));
+        assert!(!table.unban(&id));
+        assert!(table.score(&id) < -99.0);
+    }
+
+    #[test]
+    fn test_timeouts_not_banned() {
+        let mut table = new_table();
+        let id = NodeId::random();
+
+        assert_eq!(
+            table.note_misbehavior(&id, Misbehavior::RequestTimeout),
+            ReputationAction::None
+        );
+        for _ in 0..100 {
+            table.note_misbehavior(&id, Misbehavior::RequestTimeout);
+        }
+        assert_eq!(
+            table.note_misbehavior(&id, Misbehavior::RequestTimeout),
+            ReputationAction::Disconnect
+        );
+        assert!(table.banned_until(&id).is_none());
+        assert!(table.score(&id) >= -50.0);
+
+        // other misbehaviors still get the peer banned
+        table.note_misbehavior(&id, Misbehavior::InvalidBlock);
+        assert_eq!(
+            table.note_misbehavior(&id, Misbehavior::InvalidBlock),
+            ReputationAction::Ban
+        );
+        table.note_misbehavior(&id, Misbehavior::RequestTimeout);
+        assert!(table.score(&id) < -149.0);
+    }
+
+    #[test]
+    fn test_score_decay() {
+        let mut table = ReputationTable::new(
+            ReputationConfig {
+                half_life: Duration::from_millis(10),
+                ..Default::default()
+            },
+            None,
+            "",
+        );
+        let id = NodeId::random();
+        table.note_misbehavior(&id, Misbehavior::InvalidBlock);
+        assert!(table.score(&id) < -25.0);
+
+        std::thread::sleep(Duration::from_millis(100));
+        assert!(table.score(&id) > -1.0);
+        table.prune();
+        assert!(table.scores().is_empty());
+    }
+}
@@ -51,6 +51,7 @@ use crate::{
     node_database::NodeDatabase,
     node_table::*,
     parse_msg_id_leb128_2_bytes_at_most,
+    reputation::{Misbehavior, PeerScore, ReputationAction},
     session::{self, Session, SessionData, SessionDetails},
     session_manager::SessionManager,
     Error, HandlerWorkType, IpFilter, NatType, NetworkConfiguration,
@@ -390,6 +391,35 @@ impl NetworkService {
         true
     }
 
+    pub fn peer_scores(&self) -> Option<Vec<PeerScore>> {
+        let inner = self.inner.as_ref()?;
+        let scores = inner.node_db.read().peer_scores();
+        Some(scores)
+    }
+
+    /// Ban the node for the given duration, or the configured ban duration if
+    /// not specified, and disconnect it if connected.
+    pub fn ban_node(&self, id: &NodeId, duration: Option<Duration>) -> bool {
+        if self.inner.is_none() || self.io_service.is_none() {
+            return false;
+        }
+        let inner = self.inner.as_ref().unwrap();
+        inner.node_db.write().ban(id, duration);
+        let io = IoContext::new(self.io_service.as_ref().unwrap().channel(), 0);
+        inner.kill_connection(
+            id, &io, true, None, "banned", // reason
+        );
+        true
+    }
+
+    /// Lift the ban of the node. Returns `false` if the node is not banned.
+    pub fn unban_node(&self, id: &NodeId) -> bool {
+        match self.inner {
+            Some(ref inner) => inner.node_db.write().unban(id),
+            None => false,
+        }
+    }
+
     pub fn save_node_db(&self) {
         if let Some(inner) = &self.inner {
             inner.node_db.write().save();
@@ -631,6 +661,7 @@ impl NetworkServiceInner {
             node_db: RwLock::new(NodeDatabase::new(
                 nodes_path,
                 config.subnet_quota,
+                config.reputation.clone(),
             )),
             reserved_nodes: RwLock::new(HashSet::new()),
             dropped_nodes: RwLock::new(HashSet::new()),
@@ -2034,6 +2065,21 @@ impl<'a> NetworkContextTrait for NetworkContext<'a> {
             .kill_connection(node_id, self.io, true, op, reason);
     }
 
+    fn note_misbehavior(&self, node_id: &NodeId, misbehavior: Misbehavior) {
+        let action = self
+            .network_service
+            .node_db
+            .write()
+            .note_misbehavior(node_id, misbehavior);
+        let reason = match action {
+            ReputationAction::None => return,
+            ReputationAction::Disconnect => "low reputation",
+            ReputationAction::Ban => "banned by reputation",
+        };
+        self.network_service
+            .kill_connection(node_id, self.io, true, None, reason);
+    }
+
     fn register_timer(
         &self, token: TimerToken, delay: Duration,
     ) -> Result<(), Error> {
@@ -4,6 +4,7 @@
 
 use crate::{
     connection::{Connection, ConnectionDetails, SendQueueStatus, WriteStatus},
+    framing::{self, SessionFraming},
     handshake::Handshake,
     node_table::{NodeEndpoint, NodeEntry, NodeId},
     parse_msg_id_leb128_2_bytes_at_most,
@@ -12,11 +13,14 @@ use crate::{
     UpdateNodeOperation, PROTOCOL_ID_SIZE,
 };
 use bytes::Bytes;
+use cfx_types::H256;
 use cfx_util_macros::bail;
 use diem_crypto::{bls::BLS_PUBLIC_KEY_LENGTH, ValidCryptoMaterial};
 use diem_types::validator_config::{ConsensusPublicKey, ConsensusVRFPublicKey};
 use io::{IoContext, StreamToken};
+use lazy_static::lazy_static;
 use log::{debug, trace};
+use metrics::{Counter, CounterUsize, MetricFamily};
 use mio::{net::TcpStream, Registry, Token};
 use priority_send_queue::SendQueuePriority;
 use rlp::{Rlp, RlpStream};
@@ -28,9 +32,17 @@ use std::{
     fmt,
     net::SocketAddr,
     str,
+    sync::Arc,
     time::{Duration, Instant},
 };
 
+lazy_static! {
+    static ref PEER_READ_BYTES: Arc<MetricFamily<dyn Counter<usize>>> =
+        CounterUsize::register_family("network_peer_read_bytes", &["peer"]);
+    static ref PEER_WRITE_BYTES: Arc<MetricFamily<dyn Counter<usize>>> =
+        CounterUsize::register_family("network_peer_write_bytes", &["peer"]);
+}
+
 /// Peer session over TCP connection, including outgoing and incoming sessions.
 ///
 /// When a session created, 2 peers handshake with each other to exchange the
@@ -38,9 +50,12 @@ use std::{
 /// packet to exchange the supported protocols. Then, session is ready to send
 /// and receive protocol packets.
 ///
-/// Conflux do not use AES based encrypted connection to send protocol packets.
-/// This is because that Conflux has high TPS, and the encryption/decryption
-/// workloads are very heavy (about 20% CPU time in 3000 TPS).
+/// By default, Conflux do not use AES based encrypted connection to send
+/// protocol packets. This is because that Conflux has high TPS, and the
+/// encryption/decryption workloads are very heavy (about 20% CPU time in 3000
+/// TPS). Instead, compression and encryption of the protocol packets can be
+/// enabled per session if both peers advertise them in the Hello packet, see
+/// `framing` for details.
 pub struct Session {
     /// Session information
     pub metadata: SessionMetadata,
@@ -59,6 +74,17 @@ pub struct Session {
     last_read: Instant,
     last_write: (Instant, WriteStatus),
     pos_public_key: Option<(ConsensusPublicKey, ConsensusVRFPublicKey)>,
+
+    /// Secret shared with the remote peer, established by the handshake.
+    session_secret: Option<H256>,
+    /// Framing capabilities advertised to the remote peer.
+    capabilities: u8,
+    /// Framing of protocol packets negotiated with the remote peer.
+    framing: Option<SessionFraming>,
+
+    // traffic counters of the remote peer, looked up once per session
+    read_bytes: Arc<dyn Counter<usize>>,
+    write_bytes: Arc<dyn Counter<usize>>,
 }
 
 /// Session state.
@@ -121,6 +147,7 @@ impl Session {
         let mut handshake = Handshake::new(token, id, socket);
         handshake.start(io, &host.metadata)?;
 
+        let peer = address.to_string();
         Ok(Session {
             metadata: SessionMetadata {
                 id: id.cloned(),
@@ -136,6 +163,11 @@ impl Session {
             last_read: Instant::now(),
             last_write: (Instant::now(), WriteStatus::Complete),
             pos_public_key,
+            session_secret: None,
+            capabilities: 0,
+            framing: None,
+            read_bytes: PEER_READ_BYTES.with_label_values(&[&peer]),
+            write_bytes: PEER_WRITE_BYTES.with_label_values(&[&peer]),
         })
     }
 
@@ -210,7 +242,7 @@ impl Session {
     /// Complete the handshake process:
     /// 1. For incoming session, check if the remote peer is blacklisted.
     /// 2. Change the session state to `State::Session`.
-    /// 3. Send Hello packet to remote peer.
+    /// 3. Send Hello packet to remote peer with the framing capabilities.
     fn complete_handshake<Message>(
         &mut self, io: &IoContext<Message>, host: &NetworkServiceInner,
     ) -> Result<(), Error>
@@ -232,6 +264,12 @@ impl Session {
             self.metadata.id = Some(id);
         }
 
+        self.session_secret = wrapper.get().session_secret();
+        self.capabilities = host
+            .config
+            .session_framing
+            .capabilities(self.session_secret.is_some());
+
         // write HELLO packet to remote peer
         self.state = State::Session(wrapper.take().connection);
         self.write_hello(io, host)?;
@@ -291,6 +329,7 @@ impl Session {
     fn read_packet(
         &mut self, data: Bytes, host: &NetworkServiceInner,
     ) -> Result<SessionDataWithDisconnectInfo, Error> {
+        self.read_bytes.inc(data.len());
         let packet = SessionPacket::parse(data)?;
 
         // For protocol packet, the Hello packet should already been received.
@@ -320,6 +359,43 @@ impl Session {
                     });
                 }
 
+                // Both peers frame the protocol packets with the
+                // capabilities advertised by each other. The framing is
+                // negotiated only once, as renegotiating would reset the
+                // packet counter under the same keys.
+                if self.had_hello.is_none() {
+                    let remote_capabilities =
+                        framing::find_capabilities(&packet.extensions);
+                    self.framing = SessionFraming::negotiate(
+                        &host.config.session_framing,
+                        self.capabilities,
+                        remote_capabilities,
+                        self.session_secret.take().as_ref(),
+                        self.metadata.originated,
+                    );
+                    debug!(
+                        "Negotiated framing capabilities {:#x} in session {:?}",
+                        self.capabilities & remote_capabilities,
+                        self
+                    );
+                    if host.config.session_framing.require_encryption
+                        && !self
+                            .framing
+                            .as_ref()
+                            .map_or(false, SessionFraming::is_encrypted)
+                    {
+                        debug!(
+                            "Encryption not negotiated in session {:?}",
+                            self
+                        );
+                        return Err(self.send_disconnect(
+                            DisconnectReason::Custom(
+                                "encryption required".into(),
+                            ),
+                        ));
+                    }
+                }
+
                 // Handle Hello packet to exchange protocols
                 let rlp = Rlp::new(&packet.data);
                 let pos_public_key = self.read_hello(&rlp, host)?;
@@ -337,15 +413,21 @@ impl Session {
                 );
                 Err(Error::Disconnect(reason))
             }
-            PACKET_USER => Ok(SessionDataWithDisconnectInfo {
-                session_data: SessionData::Message {
-                    data: packet.data.to_vec(),
-                    protocol: packet
-                        .protocol
-                        .expect("protocol should available for USER packet"),
-                },
-                token_to_disconnect: None,
-            }),
+            PACKET_USER => {
+                let protocol = packet
+                    .protocol
+                    .expect("protocol should available for USER packet");
+                let data = SessionFraming::unframe(
+                    self.framing.as_mut(),
+                    &protocol,
+                    framing::find_framing(&packet.extensions),
+                    &packet.data,
+                )?;
+                Ok(SessionDataWithDisconnectInfo {
+                    session_data: SessionData::Message { data, protocol },
+                    token_to_disconnect: None,
+                })
+            }
             _ => {
                 debug!(
                     "read packet UNKNOWN, packet_id = {:?}, session = {:?}",
@@ -544,8 +626,13 @@ impl Session {
     /// Assemble a packet with specified protocol id, packet id and data.
     /// Return concrete error if session is expired or the protocol id is
     /// invalid.
+    ///
+    /// The data of protocol packets is framed as negotiated, and the Hello
+    /// packet carries the framing capabilities if any. `priority` is the
+    /// queue the packet is sent with, or `None` if it is written immediately.
     fn prepare_packet(
-        &self, protocol: Option<ProtocolId>, packet_id: u8, data: Vec<u8>,
+        &mut self, protocol: Option<ProtocolId>, packet_id: u8,
+        mut data: Vec<u8>, priority: Option<SendQueuePriority>,
     ) -> Result<Vec<u8>, Error> {
         if protocol.is_some() && self.had_hello.is_none() {
             debug!(
@@ -563,12 +650,30 @@ impl Session {
             return Err(Error::Expired);
         }
 
-        Ok(SessionPacket::assemble(
+        let mut extensions = Vec::new();
+        match (packet_id, protocol, self.framing.as_mut()) {
+            (PACKET_HELLO, _, _) if self.capabilities != 0 => {
+                extensions
+                    .push(framing::capabilities_extension(self.capabilities));
+            }
+            (PACKET_USER, Some(protocol), Some(framing)) => {
+                let (framed, extension) =
+                    framing.frame(&protocol, priority, data)?;
+                data = framed;
+                extensions.push(extension);
+            }
+            _ => {}
+        }
+
+        let packet = SessionPacket::assemble(
             packet_id,
             self.metadata.peer_header_version,
             protocol,
+            extensions,
             data,
-        ))
+        );
+        self.write_bytes.inc(packet.len());
+        Ok(packet)
     }
 
     #[inline]
@@ -614,7 +719,8 @@ impl Session {
             min_proto_version,
             &data,
         )?;
-        let packet = self.prepare_packet(protocol, packet_id, data)?;
+        let packet =
+            self.prepare_packet(protocol, packet_id, data, Some(priority))?;
         self.connection_mut().send(io, packet, priority)
     }
 
@@ -628,7 +734,7 @@ impl Session {
             min_proto_version,
             &data,
         )?;
-        let packet = self.prepare_packet(protocol, packet_id, data)?;
+        let packet = self.prepare_packet(protocol, packet_id, data, None)?;
         self.connection_mut().write_raw_data(packet)
     }
 
@@ -725,6 +831,14 @@ impl Session {
     }
 }
 
+impl Drop for Session {
+    fn drop(&mut self) {
+        let peer = self.address.to_string();
+        PEER_READ_BYTES.remove_label_values(&[&peer]);
+        PEER_WRITE_BYTES.remove_label_values(&[&peer]);
+    }
+}
+
 impl fmt::Debug for Session {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "Session {{ token: {}, id: {:?}, originated: {}, address: {:?}, had_hello: {}, expired: {} }}",
@@ -808,18 +922,28 @@ struct SessionPacket {
 }
 
 impl SessionPacket {
-    // data + Option<protocol> + protocol_flag + packet_id
+    // data + extensions + Option<protocol> + protocol_flag + packet_id
     fn assemble(
         id: u8, header_version: u8, protocol: Option<ProtocolId>,
-        mut data: Vec<u8>,
+        extensions: Vec<Vec<u8>>, mut data: Vec<u8>,
     ) -> Vec<u8> {
+        // Extensions are parsed from the end, so they are appended in reverse
+        // order to be parsed in the given order.
+        let has_extension = !extensions.is_empty() as u8;
+        for (i, extension) in extensions.iter().rev().enumerate() {
+            assert!(extension.len() < 0x80, "extension is too long");
+            data.extend_from_slice(extension);
+            data.push(((extension.len() as u8) << 1) + (i != 0) as u8);
+        }
+
         let mut protocol_flag = 0;
         if let Some(protocol) = protocol {
             data.extend_from_slice(&protocol);
             protocol_flag = 1;
         }
 
-        let header_byte = (header_version << 1) + protocol_flag;
+        let header_byte =
+            (has_extension << 4) + (header_version << 1) + protocol_flag;
         data.push(header_byte);
         data.push(id);
 
@@ -941,17 +1065,56 @@ mod tests {
 
     #[test]
     fn test_packet_assemble() {
-        let packet =
-            SessionPacket::assemble(5, PACKET_HEADER_VERSION, None, vec![1, 3]);
+        let packet = SessionPacket::assemble(
+            5,
+            PACKET_HEADER_VERSION,
+            None,
+            vec![],
+            vec![1, 3],
+        );
         assert_eq!(packet, vec![1, 3, 0, 5]);
 
         let packet = SessionPacket::assemble(
             6,
             PACKET_HEADER_VERSION,
             Some([8; 3]),
+            vec![],
             vec![2, 4],
         );
         assert_eq!(packet, vec![2, 4, 8, 8, 8, 1, 6]);
+
+        let packet = SessionPacket::assemble(
+            6,
+            PACKET_HEADER_VERSION,
+            Some([8; 3]),
+            vec![vec![7], vec![5, 5]],
+            vec![2, 4],
+        );
+        assert_eq!(packet, vec![2, 4, 5, 5, 4, 7, 3, 8, 8, 8, 0x11, 6]);
+    }
+
+    #[test]
+    fn test_packet_extensions() {
+        let extensions = vec![
+            framing::capabilities_extension(framing::CAPABILITY_COMPRESSION),
+            vec![],
+            vec![0xff; 3],
+        ];
+        let packet = SessionPacket::assemble(
+            PACKET_USER,
+            PACKET_HEADER_VERSION,
+            Some([3; 3]),
+            extensions.clone(),
+            vec![1, 9],
+        );
+        let packet = SessionPacket::parse(packet.into()).unwrap();
+        assert_eq!(packet.data, Bytes::from(vec![1, 9]));
+        assert_eq!(packet.extensions, extensions);
+        assert_eq!(
+            framing::find_capabilities(&packet.extensions),
+            framing::CAPABILITY_COMPRESSION
+        );
+        assert_eq!(framing::find_framing(&packet.extensions), None);
     }
 
     #[test]
@@ -13,6 +13,8 @@ use std::path::PathBuf;
 #[serde(default, deny_unknown_fields)]
 pub struct SafetyRulesConfig {
     pub backend: OnDiskStorageConfig,
+    pub service: SafetyRulesService,
+    pub network_timeout_ms: u64,
     pub enable_cached_safety_data: bool,
 }
 
@@ -20,6 +22,8 @@ impl Default for SafetyRulesConfig {
     fn default() -> Self {
         Self {
             backend: OnDiskStorageConfig::default(),
+            service: SafetyRulesService::Local,
+            network_timeout_ms: 30_000,
             enable_cached_safety_data: true,
         }
     }
@@ -27,6 +31,52 @@ impl Default for SafetyRulesConfig {
 
 impl SafetyRulesConfig {
     pub fn set_data_dir(&mut self, data_dir: PathBuf) {
+        if let SafetyRulesService::Process(remote_service) = &mut self.service {
+            remote_service.set_data_dir(data_dir.clone());
+        }
         self.backend.set_data_dir(data_dir);
     }
 }
+
+/// Defines how safety rules should be executed
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum SafetyRulesService {
+    /// This runs safety rules in the same thread as event processor
+    Local,
+    /// This is the production, separate service approach. The PoS keys and
+    /// the safety data are kept by the `safety-rules` process, and the node
+    /// sends all the signing requests to it without loading the keys.
+    Process(RemoteService),
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RemoteService {
+    // Unix socket served by the `safety-rules` process, relative to the data
+    // directory if it is a relative path
+    pub socket_path: PathBuf,
+    #[serde(skip)]
+    data_dir: PathBuf,
+}
+
+impl RemoteService {
+    pub fn new(socket_path: PathBuf) -> Self {
+        Self {
+            socket_path,
+            data_dir: PathBuf::new(),
+        }
+    }
+
+    pub fn socket_path(&self) -> PathBuf {
+        if self.socket_path.is_relative() {
+            self.data_dir.join(&self.socket_path)
+        } else {
+            self.socket_path.clone()
+        }
+    }
+
+    pub fn set_data_dir(&mut self, data_dir: PathBuf) {
+        self.data_dir = data_dir;
+    }
+}
@@ -9,7 +9,9 @@ use consensus_types::block::block_test_utils;
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use diem_secure_storage::OnDiskStorage;
 use diem_types::validator_signer::ValidatorSigner;
-use safety_rules::{test_utils, PersistentSafetyStorage, SafetyRules};
+use safety_rules::{
+    test_utils, PersistentSafetyStorage, SafetyRules, TSafetyRules,
+};
 use tempfile::TempDir;
 
 /// Execute an in order series of blocks (0 <- 1 <- 2 <- 3 and commit 0 and
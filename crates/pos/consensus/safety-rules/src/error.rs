@@ -25,6 +25,8 @@ pub enum Error {
     InternalError(String),
     #[error("Invalid proposal: {0}")]
     InvalidProposal(String),
+    #[error("Invalid transaction: {0}")]
+    InvalidTransaction(String),
     #[error("Invalid QC: {0}")]
     InvalidQuorumCertificate(String),
     #[error("{0} is not set, SafetyRules is not initialized")]
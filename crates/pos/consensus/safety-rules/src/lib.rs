@@ -14,12 +14,26 @@ mod consensus_state;
 mod error;
 mod logging;
 mod persistent_safety_storage;
+#[cfg(unix)]
+mod remote_service;
 mod safety_rules;
+mod serializer;
+mod t_safety_rules;
 
+#[cfg(unix)]
+pub use crate::remote_service::{
+    bind_remote_service, run_remote_service, RemoteClient,
+};
 pub use crate::{
-    consensus_state::ConsensusState, error::Error,
+    consensus_state::ConsensusState,
+    error::Error,
     persistent_safety_storage::PersistentSafetyStorage,
     safety_rules::SafetyRules,
+    serializer::{
+        SafetyRulesInput, SerializerClient, SerializerService,
+        TSerializerClient,
+    },
+    t_safety_rules::TSafetyRules,
 };
 
 /// Create a SafetyRules instance.
@@ -58,6 +72,64 @@ pub fn create_safety_rules(
     )
 }
 
+/// Create the SafetyRules used by consensus according to
+/// `SafetyRulesConfig::service`.
+///
+/// The keys loaded by the node are only used by the local SafetyRules. With
+/// `SafetyRulesService::Process` the node must not load the keys, and all the
+/// signing requests are sent to the `safety-rules` process, which signs them
+/// if they pass its rules.
+pub fn create_safety_rules_client(
+    config: &diem_config::config::SafetyRulesConfig,
+    keys: Option<(
+        diem_types::validator_config::ConsensusPrivateKey,
+        diem_types::validator_config::ConsensusVRFPrivateKey,
+    )>,
+    export_consensus_key: bool,
+) -> Result<Box<dyn TSafetyRules + Send + Sync>, Error> {
+    use diem_config::config::SafetyRulesService;
+    use diem_crypto::PrivateKey;
+    use diem_types::account_address::from_consensus_public_key;
+
+    match (&config.service, keys) {
+        (
+            SafetyRulesService::Local,
+            Some((consensus_private_key, vrf_private_key)),
+        ) => {
+            let author = from_consensus_public_key(
+                &consensus_private_key.public_key(),
+                &vrf_private_key.public_key(),
+            );
+            Ok(Box::new(create_safety_rules(
+                config,
+                author,
+                consensus_private_key,
+                Some(vrf_private_key),
+                export_consensus_key,
+            )))
+        }
+        (SafetyRulesService::Local, None) => Err(Error::ValidatorKeyNotFound(
+            "the local SafetyRules needs the PoS keys of the node".into(),
+        )),
+        (SafetyRulesService::Process(_), Some(_)) => Err(Error::InternalError(
+            "the PoS keys must be kept by the SafetyRules process, not \
+                 loaded by the node"
+                .into(),
+        )),
+        #[cfg(unix)]
+        (SafetyRulesService::Process(remote_service), None) => Ok(Box::new(
+            SerializerClient::new_client(Box::new(RemoteClient::new(
+                remote_service.socket_path(),
+                config.network_timeout_ms,
+            ))),
+        )),
+        #[cfg(not(unix))]
+        (SafetyRulesService::Process(_), None) => Err(Error::InternalError(
+            "the SafetyRules process is only supported on unix".into(),
+        )),
+    }
+}
+
 #[cfg(any(test, feature = "fuzzing"))]
 pub mod fuzzing_utils;
 
@@ -41,6 +41,7 @@ impl<'a> SafetyLogSchema<'a> {
 #[derive(Clone, Copy, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub enum LogEntry {
+    ComputeVrfProof,
     ConsensusState,
     ConstructAndSignVote,
     Epoch,
@@ -50,6 +51,7 @@ pub enum LogEntry {
     PreferredRound,
     SignProposal,
     SignTimeout,
+    SignTransaction,
     State,
 }
 
@@ -0,0 +1,94 @@
+// Copyright 2021 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+//! The reference SafetyRules service, which keeps the consensus key and the
+//! safety data of a PoS node in a separate process.
+//!
+//! Usage: `safety-rules <pos_config.yaml> <pos_key>`
+//!
+//! The config is the PoS config of the node, whose `consensus.safety_rules`
+//! gives the storage of the safety data and, with the `process` service, the
+//! Unix socket to listen on. The socket is only accessible to the user running
+//! this process. The encryption password of the key file is read from
+//! `CFX_POS_KEY_ENCRYPTION_PASSWORD`.
+
+#![forbid(unsafe_code)]
+
+use diem_config::config::{NodeConfig, SafetyRulesService};
+use diem_crypto::{key_file::load_pri_key, PrivateKey};
+use diem_types::{
+    account_address::from_consensus_public_key,
+    validator_config::{ConsensusPrivateKey, ConsensusVRFPrivateKey},
+};
+use std::{env, process, time::Duration};
+
+const PASSWORD_ENV: &str = "CFX_POS_KEY_ENCRYPTION_PASSWORD";
+
+#[cfg(unix)]
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() != 3 {
+        eprintln!("Usage: {} <pos_config.yaml> <pos_key>", args[0]);
+        process::exit(1);
+    }
+
+    let mut config = NodeConfig::load(&args[1]).unwrap_or_else(|e| {
+        eprintln!("Failed to load config {}: {:?}", args[1], e);
+        process::exit(1);
+    });
+    config.set_data_dir(config.data_dir().to_path_buf());
+    let sr_config = &config.consensus.safety_rules;
+    let socket_path = match &sr_config.service {
+        SafetyRulesService::Process(remote_service) => {
+            remote_service.socket_path()
+        }
+        SafetyRulesService::Local => {
+            eprintln!("consensus.safety_rules.service is not `process`");
+            process::exit(1);
+        }
+    };
+
+    let password = env::var(PASSWORD_ENV).unwrap_or_default();
+    let (consensus_private_key, vrf_private_key): (
+        ConsensusPrivateKey,
+        ConsensusVRFPrivateKey,
+    ) = load_pri_key(&args[2], &password).unwrap_or_else(|e| {
+        eprintln!("Failed to load PoS key {}: {}", args[2], e);
+        process::exit(1);
+    });
+    let author = from_consensus_public_key(
+        &consensus_private_key.public_key(),
+        &vrf_private_key.public_key(),
+    );
+
+    let _logger = diem_logger::Logger::new()
+        .level(config.logger.level)
+        .read_env()
+        .build();
+
+    let safety_rules = safety_rules::create_safety_rules(
+        sr_config,
+        author,
+        consensus_private_key,
+        Some(vrf_private_key),
+        false,
+    );
+    let listener = safety_rules::bind_remote_service(&socket_path)
+        .unwrap_or_else(|e| {
+            eprintln!("Failed to listen on {:?}: {}", socket_path, e);
+            process::exit(1);
+        });
+    println!("SafetyRules of {:?} listening on {:?}", author, socket_path);
+    safety_rules::run_remote_service(
+        safety_rules,
+        listener,
+        Duration::from_millis(sr_config.network_timeout_ms),
+    );
+}
+
+#[cfg(not(unix))]
+fn main() {
+    eprintln!("The SafetyRules service is only supported on unix");
+    process::exit(1);
+}
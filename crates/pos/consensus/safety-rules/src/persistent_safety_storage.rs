@@ -15,8 +15,11 @@ use diem_global_constants::{
 };
 use diem_logger::prelude::*;
 use diem_secure_storage::{CryptoStorage, KVStorage, OnDiskStorage};
-use diem_types::validator_config::{
-    ConsensusPrivateKey, ConsensusPublicKey, ConsensusSignature,
+use diem_types::{
+    transaction::{RawTransaction, SignedTransaction},
+    validator_config::{
+        ConsensusPrivateKey, ConsensusPublicKey, ConsensusSignature,
+    },
 };
 use serde::Serialize;
 use std::{convert::TryFrom, fs};
@@ -122,6 +125,19 @@ impl PersistentSafetyStorage {
         Ok(self.internal_store.get(OWNER_ACCOUNT).map(|v| v.value)?)
     }
 
+    pub fn consensus_public_key(&self) -> ConsensusPublicKey {
+        self.private_key.public_key()
+    }
+
+    pub fn sign_transaction(
+        &self, raw_tx: RawTransaction,
+    ) -> Result<SignedTransaction, Error> {
+        raw_tx
+            .sign(&self.private_key)
+            .map(|tx| tx.into_inner())
+            .map_err(|e| Error::InternalError(e.to_string()))
+    }
+
     pub fn consensus_key_for_version(
         &self, version: ConsensusPublicKey,
     ) -> Result<ConsensusPrivateKey, Error> {
@@ -0,0 +1,193 @@
+// Copyright 2021 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+//! SafetyRules served over a Unix domain socket. Each message is a bcs
+//! encoded `SafetyRulesInput` or result, prefixed by its length as a little
+//! endian `u32`.
+//!
+//! The socket is only accessible to the user running the service, which is
+//! the authentication of the node.
+
+use crate::{
+    serializer::{SafetyRulesInput, SerializerService, TSerializerClient},
+    Error, SafetyRules,
+};
+use diem_logger::prelude::*;
+use std::{
+    convert::TryFrom,
+    fs::{self, Permissions},
+    io::{self, Read, Write},
+    os::unix::{
+        fs::{FileTypeExt, PermissionsExt},
+        net::{UnixListener, UnixStream},
+    },
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// The largest message accepted from the connection, which is far more than
+/// a request or a response with the largest block.
+const MAX_MESSAGE_SIZE: usize = 64 * 1024 * 1024;
+
+/// The permissions of the socket, which only allow the owner to connect.
+const SOCKET_PERMISSIONS: u32 = 0o600;
+
+/// Binds the socket of the service at the given path, only accessible to the
+/// current user.
+pub fn bind_remote_service(path: &Path) -> io::Result<UnixListener> {
+    // The socket file left by a previous run prevents the binding, but any
+    // other file at the path is not removed.
+    if let Ok(metadata) = fs::symlink_metadata(path) {
+        if !metadata.file_type().is_socket() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{:?} exists and is not a socket", path),
+            ));
+        }
+        fs::remove_file(path)?;
+    }
+
+    let listener = UnixListener::bind(path)?;
+    fs::set_permissions(path, Permissions::from_mode(SOCKET_PERMISSIONS))?;
+    Ok(listener)
+}
+
+/// Serves the SafetyRules with the connections accepted by the listener.
+///
+/// The connections are served one at a time, so the requests are applied to
+/// the safety data in the order they are received and a node cannot get two
+/// conflicting signatures by racing two connections. A connection idle for
+/// longer than `timeout` is closed, so it cannot keep the others waiting.
+pub fn run_remote_service(
+    safety_rules: SafetyRules, listener: UnixListener, timeout: Duration,
+) {
+    let mut serializer_service = SerializerService::new(safety_rules);
+
+    for stream in listener.incoming() {
+        let mut stream = match stream.and_then(|stream| {
+            stream.set_read_timeout(Some(timeout))?;
+            stream.set_write_timeout(Some(timeout))?;
+            Ok(stream)
+        }) {
+            Ok(stream) => stream,
+            Err(e) => {
+                diem_warn!("Failed to accept SafetyRules connection: {:?}", e);
+                continue;
+            }
+        };
+        diem_info!("SafetyRules connection accepted");
+        if let Err(e) = serve_connection(&mut serializer_service, &mut stream) {
+            diem_warn!("SafetyRules connection closed: {:?}", e);
+        }
+    }
+}
+
+fn serve_connection(
+    serializer_service: &mut SerializerService, stream: &mut UnixStream,
+) -> io::Result<()> {
+    loop {
+        let request = match read_message(stream) {
+            Ok(request) => request,
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+            Err(e) => return Err(e),
+        };
+        let response = serializer_service
+            .handle_message(request)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        write_message(stream, &response)?;
+    }
+}
+
+/// Sends the requests to a SafetyRules served by `run_remote_service`.
+pub struct RemoteClient {
+    socket_path: PathBuf,
+    timeout: Duration,
+    stream: Option<UnixStream>,
+}
+
+impl RemoteClient {
+    pub fn new(socket_path: PathBuf, timeout_ms: u64) -> Self {
+        Self {
+            socket_path,
+            timeout: Duration::from_millis(timeout_ms),
+            stream: None,
+        }
+    }
+
+    fn process_one_message(
+        &mut self, input_message: &[u8],
+    ) -> io::Result<Vec<u8>> {
+        let stream = match self.stream.take() {
+            Some(stream) => stream,
+            None => {
+                let stream = UnixStream::connect(&self.socket_path)?;
+                stream.set_read_timeout(Some(self.timeout))?;
+                stream.set_write_timeout(Some(self.timeout))?;
+                stream
+            }
+        };
+        let stream = self.stream.insert(stream);
+        write_message(stream, input_message)?;
+        read_message(stream)
+    }
+}
+
+impl TSerializerClient for RemoteClient {
+    fn request(&mut self, input: SafetyRulesInput) -> Result<Vec<u8>, Error> {
+        let input_message = bcs::to_bytes(&input)?;
+        let reused = self.stream.is_some();
+        let mut result = self.process_one_message(&input_message);
+        if result.is_err() {
+            // The response of a failed request may still arrive, so the
+            // connection is not reused.
+            self.stream = None;
+            // The service closes idle connections, so a request failed on a
+            // reused connection is sent again on a new one. The service
+            // checks every request, so sending one twice cannot get two
+            // conflicting signatures.
+            if reused {
+                result = self.process_one_message(&input_message);
+                if result.is_err() {
+                    self.stream = None;
+                }
+            }
+        }
+        result.map_err(|e| {
+            Error::InternalError(format!(
+                "SafetyRules service {:?} unavailable: {}",
+                self.socket_path, e
+            ))
+        })
+    }
+}
+
+fn read_message(stream: &mut UnixStream) -> io::Result<Vec<u8>> {
+    let mut len = [0u8; 4];
+    stream.read_exact(&mut len)?;
+    let len = u32::from_le_bytes(len) as usize;
+    if len > MAX_MESSAGE_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("message of {} bytes is too large", len),
+        ));
+    }
+    let mut message = vec![0u8; len];
+    stream.read_exact(&mut message)?;
+    Ok(message)
+}
+
+fn write_message(stream: &mut UnixStream, message: &[u8]) -> io::Result<()> {
+    let len = u32::try_from(message.len())
+        .ok()
+        .filter(|len| *len as usize <= MAX_MESSAGE_SIZE)
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("message of {} bytes is too large", message.len()),
+            )
+        })?;
+    stream.write_all(&len.to_le_bytes())?;
+    stream.write_all(message)?;
+    stream.flush()
+}
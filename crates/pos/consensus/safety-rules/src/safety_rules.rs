@@ -11,6 +11,7 @@ use crate::{
     error::Error,
     logging::{LogEntry, LogEvent, SafetyLogSchema},
     persistent_safety_storage::PersistentSafetyStorage,
+    t_safety_rules::TSafetyRules,
 };
 use consensus_types::{
     block::Block,
@@ -26,7 +27,7 @@ use consensus_types::{
 use diem_crypto::{
     hash::{CryptoHash, HashValue},
     traits::Signature,
-    PrivateKey,
+    PrivateKey, VRFPrivateKey,
 };
 use diem_logger::prelude::*;
 use diem_types::{
@@ -34,7 +35,11 @@ use diem_types::{
     block_info::BlockInfo,
     epoch_state::EpochState,
     ledger_info::LedgerInfo,
-    validator_config::{ConsensusSignature, ConsensusVRFPrivateKey},
+    transaction::{RawTransaction, SignedTransaction},
+    validator_config::{
+        ConsensusPublicKey, ConsensusSignature, ConsensusVRFPrivateKey,
+        ConsensusVRFProof, ConsensusVRFPublicKey,
+    },
 };
 use log::error;
 use serde::Serialize;
@@ -87,6 +92,12 @@ impl SafetyRules {
         signer.sign(message, &self.persistent_storage)
     }
 
+    fn vrf_private_key(&self) -> Result<&ConsensusVRFPrivateKey, Error> {
+        self.vrf_private_key
+            .as_ref()
+            .ok_or_else(|| Error::NotInitialized("vrf_private_key".into()))
+    }
+
     fn signer(&self) -> Result<&ConfigurableValidatorSigner, Error> {
         self.validator_signer
             .as_ref()
@@ -473,7 +484,50 @@ impl SafetyRules {
         Ok(signature)
     }
 
-    pub fn start_voting(&mut self, initialize: bool) -> Result<(), Error> {
+    fn guarded_sign_transaction(
+        &mut self, raw_tx: RawTransaction,
+    ) -> Result<SignedTransaction, Error> {
+        let author = self.persistent_storage.author()?;
+        if raw_tx.sender() != author {
+            return Err(Error::InvalidTransaction(format!(
+                "sender {} is not the author {}",
+                raw_tx.sender(),
+                author
+            )));
+        }
+        self.persistent_storage.sign_transaction(raw_tx)
+    }
+}
+
+impl TSafetyRules for SafetyRules {
+    fn public_keys(
+        &mut self,
+    ) -> Result<(ConsensusPublicKey, ConsensusVRFPublicKey), Error> {
+        Ok((
+            self.persistent_storage.consensus_public_key(),
+            self.vrf_private_key()?.public_key(),
+        ))
+    }
+
+    fn sign_transaction(
+        &mut self, raw_tx: RawTransaction,
+    ) -> Result<SignedTransaction, Error> {
+        let cb = || self.guarded_sign_transaction(raw_tx);
+        run_and_log(cb, |log| log, LogEntry::SignTransaction)
+    }
+
+    fn compute_vrf_proof(
+        &mut self, seed: &[u8],
+    ) -> Result<ConsensusVRFProof, Error> {
+        let cb = || {
+            self.vrf_private_key()?
+                .compute(seed)
+                .map_err(|e| Error::InternalError(e.to_string()))
+        };
+        run_and_log(cb, |log| log, LogEntry::ComputeVrfProof)
+    }
+
+    fn start_voting(&mut self, initialize: bool) -> Result<(), Error> {
         if initialize {
             // If the node starts voting with its local safety data,
             // `SafetyRule` just remains the same.
@@ -484,24 +538,22 @@ impl SafetyRules {
         }
     }
 
-    pub fn stop_voting(&mut self) -> Result<(), Error> {
+    fn stop_voting(&mut self) -> Result<(), Error> {
         self.persistent_storage
             .save_to_suffix(SAFETY_STORAGE_SAVE_SUFFIX)
     }
 
-    pub fn consensus_state(&mut self) -> Result<ConsensusState, Error> {
+    fn consensus_state(&mut self) -> Result<ConsensusState, Error> {
         let cb = || self.guarded_consensus_state();
         run_and_log(cb, |log| log, LogEntry::ConsensusState)
     }
 
-    pub fn initialize(
-        &mut self, epoch_state: &EpochState,
-    ) -> Result<(), Error> {
+    fn initialize(&mut self, epoch_state: &EpochState) -> Result<(), Error> {
         let cb = || self.guarded_initialize(epoch_state);
         run_and_log(cb, |log| log, LogEntry::Initialize)
     }
 
-    pub fn construct_and_sign_vote(
+    fn construct_and_sign_vote(
         &mut self, maybe_signed_vote_proposal: &MaybeSignedVoteProposal,
     ) -> Result<Vote, Error> {
         let round = maybe_signed_vote_proposal.vote_proposal.block().round();
@@ -510,15 +562,13 @@ impl SafetyRules {
         run_and_log(cb, |log| log.round(round), LogEntry::ConstructAndSignVote)
     }
 
-    pub fn sign_proposal(
-        &mut self, block_data: BlockData,
-    ) -> Result<Block, Error> {
+    fn sign_proposal(&mut self, block_data: BlockData) -> Result<Block, Error> {
         let round = block_data.round();
         let cb = || self.guarded_sign_proposal(block_data);
         run_and_log(cb, |log| log.round(round), LogEntry::SignProposal)
     }
 
-    pub fn sign_timeout(
+    fn sign_timeout(
         &mut self, timeout: &Timeout,
     ) -> Result<ConsensusSignature, Error> {
         let cb = || self.guarded_sign_timeout(timeout);
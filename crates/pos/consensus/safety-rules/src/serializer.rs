@@ -0,0 +1,206 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+// Copyright 2021 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use crate::{ConsensusState, Error, SafetyRules, TSafetyRules};
+use consensus_types::{
+    block::Block, block_data::BlockData, timeout::Timeout, vote::Vote,
+    vote_proposal::MaybeSignedVoteProposal,
+};
+use diem_types::{
+    epoch_state::EpochState,
+    transaction::{RawTransaction, SignedTransaction},
+    validator_config::{
+        ConsensusPublicKey, ConsensusSignature, ConsensusVRFProof,
+        ConsensusVRFPublicKey,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+/// The requests of `TSafetyRules` encoded to be sent to a SafetyRules service.
+///
+/// `start_voting` and `stop_voting` are not sent: they move the safety data
+/// of the service, and restoring an old copy would allow it to vote twice in
+/// the same round.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum SafetyRulesInput {
+    ConsensusState,
+    Initialize(Box<EpochState>),
+    ConstructAndSignVote(Box<MaybeSignedVoteProposal>),
+    SignProposal(Box<BlockData>),
+    SignTimeout(Box<Timeout>),
+    PublicKeys,
+    SignTransaction(Box<RawTransaction>),
+    ComputeVrfProof(Vec<u8>),
+}
+
+/// Decodes the requests, applies them to the wrapped SafetyRules and encodes
+/// the results.
+pub struct SerializerService {
+    internal: SafetyRules,
+}
+
+impl SerializerService {
+    pub fn new(safety_rules: SafetyRules) -> Self {
+        Self {
+            internal: safety_rules,
+        }
+    }
+
+    pub fn handle_message(
+        &mut self, input_message: Vec<u8>,
+    ) -> Result<Vec<u8>, Error> {
+        let input: SafetyRulesInput = bcs::from_bytes(&input_message)?;
+
+        let output = match input {
+            SafetyRulesInput::ConsensusState => {
+                bcs::to_bytes(&self.internal.consensus_state())
+            }
+            SafetyRulesInput::Initialize(li) => {
+                bcs::to_bytes(&self.internal.initialize(&li))
+            }
+            SafetyRulesInput::ConstructAndSignVote(vote_proposal) => {
+                bcs::to_bytes(
+                    &self.internal.construct_and_sign_vote(&vote_proposal),
+                )
+            }
+            SafetyRulesInput::SignProposal(block_data) => {
+                bcs::to_bytes(&self.internal.sign_proposal(*block_data))
+            }
+            SafetyRulesInput::SignTimeout(timeout) => {
+                bcs::to_bytes(&self.internal.sign_timeout(&timeout))
+            }
+            SafetyRulesInput::PublicKeys => {
+                bcs::to_bytes(&self.internal.public_keys())
+            }
+            SafetyRulesInput::SignTransaction(raw_tx) => {
+                bcs::to_bytes(&self.internal.sign_transaction(*raw_tx))
+            }
+            SafetyRulesInput::ComputeVrfProof(seed) => {
+                bcs::to_bytes(&self.internal.compute_vrf_proof(&seed))
+            }
+        };
+
+        Ok(output?)
+    }
+}
+
+/// Implements `TSafetyRules` by sending the encoded requests through a
+/// `TSerializerClient`.
+pub struct SerializerClient {
+    service: Box<dyn TSerializerClient + Send + Sync>,
+}
+
+impl SerializerClient {
+    pub fn new(serializer_service: SerializerService) -> Self {
+        Self::new_client(Box::new(LocalService { serializer_service }))
+    }
+
+    pub fn new_client(
+        service: Box<dyn TSerializerClient + Send + Sync>,
+    ) -> Self {
+        Self { service }
+    }
+
+    fn request(&mut self, input: SafetyRulesInput) -> Result<Vec<u8>, Error> {
+        self.service.request(input)
+    }
+}
+
+impl TSafetyRules for SerializerClient {
+    fn consensus_state(&mut self) -> Result<ConsensusState, Error> {
+        let response = self.request(SafetyRulesInput::ConsensusState)?;
+        bcs::from_bytes(&response)?
+    }
+
+    fn initialize(&mut self, epoch_state: &EpochState) -> Result<(), Error> {
+        let response = self.request(SafetyRulesInput::Initialize(Box::new(
+            epoch_state.clone(),
+        )))?;
+        bcs::from_bytes(&response)?
+    }
+
+    fn construct_and_sign_vote(
+        &mut self, maybe_signed_vote_proposal: &MaybeSignedVoteProposal,
+    ) -> Result<Vote, Error> {
+        let response = self.request(SafetyRulesInput::ConstructAndSignVote(
+            Box::new(maybe_signed_vote_proposal.clone()),
+        ))?;
+        bcs::from_bytes(&response)?
+    }
+
+    fn sign_proposal(&mut self, block_data: BlockData) -> Result<Block, Error> {
+        let response =
+            self.request(SafetyRulesInput::SignProposal(Box::new(block_data)))?;
+        bcs::from_bytes(&response)?
+    }
+
+    fn sign_timeout(
+        &mut self, timeout: &Timeout,
+    ) -> Result<ConsensusSignature, Error> {
+        let response = self.request(SafetyRulesInput::SignTimeout(
+            Box::new(timeout.clone()),
+        ))?;
+        bcs::from_bytes(&response)?
+    }
+
+    fn public_keys(
+        &mut self,
+    ) -> Result<(ConsensusPublicKey, ConsensusVRFPublicKey), Error> {
+        let response = self.request(SafetyRulesInput::PublicKeys)?;
+        bcs::from_bytes(&response)?
+    }
+
+    fn sign_transaction(
+        &mut self, raw_tx: RawTransaction,
+    ) -> Result<SignedTransaction, Error> {
+        let response =
+            self.request(SafetyRulesInput::SignTransaction(Box::new(raw_tx)))?;
+        bcs::from_bytes(&response)?
+    }
+
+    fn compute_vrf_proof(
+        &mut self, seed: &[u8],
+    ) -> Result<ConsensusVRFProof, Error> {
+        let response =
+            self.request(SafetyRulesInput::ComputeVrfProof(seed.to_vec()))?;
+        bcs::from_bytes(&response)?
+    }
+
+    /// The service keeps voting with its own safety data, a saved copy cannot
+    /// be restored.
+    fn start_voting(&mut self, initialize: bool) -> Result<(), Error> {
+        if initialize {
+            Ok(())
+        } else {
+            Err(Error::InternalError(
+                "the safety data of a SafetyRules service cannot be restored"
+                    .into(),
+            ))
+        }
+    }
+
+    /// The safety data stays with the service, so there is nothing to save.
+    fn stop_voting(&mut self) -> Result<(), Error> { Ok(()) }
+}
+
+/// The transport of the encoded requests to a `SerializerService`.
+pub trait TSerializerClient {
+    fn request(&mut self, input: SafetyRulesInput) -> Result<Vec<u8>, Error>;
+}
+
+/// Serves the requests with a `SerializerService` in the same process, which
+/// exercises the encoding of the remote service without a connection.
+struct LocalService {
+    serializer_service: SerializerService,
+}
+
+impl TSerializerClient for LocalService {
+    fn request(&mut self, input: SafetyRulesInput) -> Result<Vec<u8>, Error> {
+        let input_message = bcs::to_bytes(&input)?;
+        self.serializer_service.handle_message(input_message)
+    }
+}
@@ -0,0 +1,75 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+// Copyright 2021 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use crate::{ConsensusState, Error};
+use consensus_types::{
+    block::Block, block_data::BlockData, timeout::Timeout, vote::Vote,
+    vote_proposal::MaybeSignedVoteProposal,
+};
+use diem_types::{
+    epoch_state::EpochState,
+    transaction::{RawTransaction, SignedTransaction},
+    validator_config::{
+        ConsensusPublicKey, ConsensusSignature, ConsensusVRFProof,
+        ConsensusVRFPublicKey,
+    },
+};
+
+/// Interface for SafetyRules
+pub trait TSafetyRules {
+    /// Provides the internal state of SafetyRules for monitoring / debugging
+    /// purposes. This does not include sensitive data like private keys.
+    fn consensus_state(&mut self) -> Result<ConsensusState, Error>;
+
+    /// Initialize SafetyRules using an Epoch ending LedgerInfo, this should
+    /// map to what was provided in consensus_state. It will be used to
+    /// initialize the ValidatorSet.
+    fn initialize(&mut self, epoch_state: &EpochState) -> Result<(), Error>;
+
+    /// Attempts to vote for a given proposal following the voting rules.
+    fn construct_and_sign_vote(
+        &mut self, maybe_signed_vote_proposal: &MaybeSignedVoteProposal,
+    ) -> Result<Vote, Error>;
+
+    /// As the holder of the private key, SafetyRules also signs proposals or
+    /// blocks. A Block is a signed BlockData along with some additional
+    /// metadata.
+    fn sign_proposal(&mut self, block_data: BlockData) -> Result<Block, Error>;
+
+    /// As the holder of the private key, SafetyRules also signs what is
+    /// effectively a timeout message. This returns the signature for that
+    /// timeout message.
+    fn sign_timeout(
+        &mut self, timeout: &Timeout,
+    ) -> Result<ConsensusSignature, Error>;
+
+    /// Returns the consensus and VRF public keys of the held private keys,
+    /// which give the author of the node.
+    fn public_keys(
+        &mut self,
+    ) -> Result<(ConsensusPublicKey, ConsensusVRFPublicKey), Error>;
+
+    /// As the holder of the private key, SafetyRules also signs the PoS
+    /// transactions sent by the node, like pivot decisions and elections.
+    /// Only the transactions sent by the author are signed.
+    fn sign_transaction(
+        &mut self, raw_tx: RawTransaction,
+    ) -> Result<SignedTransaction, Error>;
+
+    /// As the holder of the VRF private key, SafetyRules computes the VRF
+    /// proofs used by the proposer election and the committee election.
+    fn compute_vrf_proof(
+        &mut self, seed: &[u8],
+    ) -> Result<ConsensusVRFProof, Error>;
+
+    /// Starts voting with the local safety data if `initialize` is true, or
+    /// with the safety data saved by the last `stop_voting` otherwise.
+    fn start_voting(&mut self, initialize: bool) -> Result<(), Error>;
+
+    /// Saves the safety data so the voting can be resumed later.
+    fn stop_voting(&mut self) -> Result<(), Error>;
+}
@@ -6,5 +6,8 @@
 // See http://www.gnu.org/licenses/
 
 mod local;
+#[cfg(unix)]
+mod remote;
 mod safety_rules;
+mod serializer;
 mod suite;
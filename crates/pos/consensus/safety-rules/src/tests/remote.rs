@@ -0,0 +1,174 @@
+// Copyright 2021 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use crate::{
+    bind_remote_service, run_remote_service, test_utils, tests::suite, Error,
+    RemoteClient, SafetyRules, SerializerClient, TSafetyRules,
+};
+use diem_crypto::{PrivateKey, Uniform, VRFPrivateKey};
+use diem_types::{
+    block_info::PivotBlockDecision,
+    chain_id::ChainId,
+    transaction::{authenticator::TransactionAuthenticator, RawTransaction},
+    validator_config::ConsensusVRFPrivateKey,
+    validator_signer::ValidatorSigner,
+};
+use rand::{rngs::StdRng, SeedableRng};
+use std::{
+    fs, os::unix::fs::PermissionsExt, path::PathBuf, thread, time::Duration,
+};
+use tempfile::TempDir;
+
+#[test]
+fn test() { suite::run_test_suite(&safety_rules()); }
+
+#[test]
+fn test_unavailable_service() {
+    // Nothing serves the socket.
+    let dir = TempDir::new().unwrap();
+    let mut safety_rules = SerializerClient::new_client(Box::new(
+        RemoteClient::new(dir.path().join("safety_rules.sock"), 1_000),
+    ));
+    assert!(matches!(
+        safety_rules.consensus_state(),
+        Err(Error::InternalError(_))
+    ));
+}
+
+#[test]
+fn test_socket_permissions() {
+    let dir = TempDir::new().unwrap();
+    let socket_path = dir.path().join("safety_rules.sock");
+    // A socket left by a previous run is replaced.
+    drop(bind_remote_service(&socket_path).unwrap());
+    let _listener = bind_remote_service(&socket_path).unwrap();
+    let mode = fs::metadata(&socket_path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+
+    // Any other file is kept.
+    let file_path = dir.path().join("file");
+    fs::write(&file_path, b"data").unwrap();
+    assert!(bind_remote_service(&file_path).is_err());
+    assert_eq!(fs::read(&file_path).unwrap(), b"data");
+}
+
+#[test]
+fn test_keys_and_transactions() {
+    let signer = ValidatorSigner::from_int(0);
+    let vrf_private_key =
+        ConsensusVRFPrivateKey::generate(&mut StdRng::from_seed([1; 32]));
+    let (dir, storage) = test_utils::test_storage(&signer);
+    let safety_rules = SafetyRules::new(
+        storage,
+        false,
+        Some(vrf_private_key.clone()),
+        signer.author(),
+    );
+    let mut client = serve(&dir, safety_rules, Duration::from_secs(10));
+
+    assert_eq!(
+        client.public_keys().unwrap(),
+        (signer.public_key(), vrf_private_key.public_key())
+    );
+
+    let seed = b"seed";
+    assert_eq!(
+        client.compute_vrf_proof(seed).unwrap(),
+        vrf_private_key.compute(seed).unwrap()
+    );
+
+    let pivot_decision = PivotBlockDecision {
+        height: 1,
+        block_hash: Default::default(),
+    };
+    let signed_tx = client
+        .sign_transaction(RawTransaction::new_pivot_decision(
+            signer.author(),
+            pivot_decision.clone(),
+            ChainId::default(),
+        ))
+        .unwrap();
+    assert_eq!(signed_tx.sender(), signer.author());
+    assert!(matches!(
+        signed_tx.authenticator(),
+        TransactionAuthenticator::BLS { public_key, .. }
+            if public_key == signer.public_key()
+    ));
+    signed_tx.verify_signature().unwrap();
+
+    // Only the transactions of the author are signed.
+    let other = ValidatorSigner::from_int(1);
+    assert!(matches!(
+        client.sign_transaction(RawTransaction::new_pivot_decision(
+            other.author(),
+            pivot_decision,
+            ChainId::default(),
+        )),
+        Err(Error::InvalidTransaction(_))
+    ));
+}
+
+#[test]
+fn test_voting_data_not_moved() {
+    let signer = ValidatorSigner::from_int(0);
+    let (dir, storage) = test_utils::test_storage(&signer);
+    let safety_rules = SafetyRules::new(storage, false, None, signer.author());
+    let mut client = serve(&dir, safety_rules, Duration::from_secs(10));
+
+    // Stopping keeps the safety data in the service, and it cannot be
+    // replaced by a saved copy.
+    client.stop_voting().unwrap();
+    assert!(client.start_voting(false).is_err());
+    client.start_voting(true).unwrap();
+    client.consensus_state().unwrap();
+}
+
+#[test]
+fn test_idle_connection() {
+    let signer = ValidatorSigner::from_int(0);
+    let (dir, storage) = test_utils::test_storage(&signer);
+    let safety_rules = SafetyRules::new(storage, false, None, signer.author());
+    let mut idle = serve(&dir, safety_rules, Duration::from_millis(100));
+    idle.consensus_state().unwrap();
+
+    // The idle connection is closed by the service, so another one is
+    // served.
+    let mut other = SerializerClient::new_client(Box::new(RemoteClient::new(
+        socket_path(&dir),
+        10_000,
+    )));
+    other.consensus_state().unwrap();
+    drop(other);
+
+    // The closed connection is replaced.
+    thread::sleep(Duration::from_millis(300));
+    idle.consensus_state().unwrap();
+}
+
+fn socket_path(dir: &TempDir) -> PathBuf {
+    dir.path().join("safety_rules.sock")
+}
+
+fn serve(
+    dir: &TempDir, safety_rules: SafetyRules, timeout: Duration,
+) -> SerializerClient {
+    let socket_path = socket_path(dir);
+    let listener = bind_remote_service(&socket_path).unwrap();
+    thread::spawn(move || run_remote_service(safety_rules, listener, timeout));
+    SerializerClient::new_client(Box::new(RemoteClient::new(
+        socket_path,
+        10_000,
+    )))
+}
+
+fn safety_rules() -> suite::Callback {
+    Box::new(move || {
+        let signer = ValidatorSigner::from_int(0);
+        let (dir, storage) = test_utils::test_storage(&signer);
+        let safety_rules =
+            SafetyRules::new(storage, false, None, Default::default());
+        let safety_rules = serve(&dir, safety_rules, Duration::from_secs(10));
+        (dir, Box::new(safety_rules), signer, None)
+    })
+}
@@ -25,6 +25,6 @@ fn safety_rules(export_consensus_key: bool) -> suite::Callback {
             None,
             Default::default(),
         );
-        (dir, safety_rules, signer, None)
+        (dir, Box::new(safety_rules), signer, None)
     })
 }
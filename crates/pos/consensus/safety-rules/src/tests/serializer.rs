@@ -0,0 +1,34 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+// Copyright 2021 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use crate::{
+    test_utils, tests::suite, SafetyRules, SerializerClient, SerializerService,
+};
+use diem_types::validator_signer::ValidatorSigner;
+
+#[test]
+fn test() {
+    for export_consensus_key in &[false, true] {
+        suite::run_test_suite(&safety_rules(*export_consensus_key));
+    }
+}
+
+fn safety_rules(export_consensus_key: bool) -> suite::Callback {
+    Box::new(move || {
+        let signer = ValidatorSigner::from_int(0);
+        let (dir, storage) = test_utils::test_storage(&signer);
+        let safety_rules = SafetyRules::new(
+            storage,
+            export_consensus_key,
+            None,
+            Default::default(),
+        );
+        let serializer_service = SerializerService::new(safety_rules);
+        let safety_rules = SerializerClient::new(serializer_service);
+        (dir, Box::new(safety_rules), signer, None)
+    })
+}
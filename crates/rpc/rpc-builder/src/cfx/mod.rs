@@ -1,19 +1,23 @@
 mod module;
 
+use crate::ipc::IpcServerConfig;
 pub use crate::{
     error::*, id_provider::SubscriptionIdProvider, RpcServerHandle,
 };
-use cfx_rpc_middlewares::{maybe_cors_layer, Logger, Metrics, Throttle};
+use cfx_rpc_middlewares::{
+    maybe_cors_layer, Auth, CredentialLayer, Logger, Metrics, RpcAuth, Throttle,
+};
 pub use module::{CfxRpcModule, RpcModuleSelection};
 
 use blockgen::BlockGeneratorTestApi;
 use cfx_rpc_cfx_api::{
     CfxDebugRpcServer, CfxFilterRpcServer, CfxRpcServer, DebugRpcServer,
-    PosRpcServer, PubSubApiServer, TestRpcServer, TraceServer, TxPoolServer,
+    NetRpcServer, PosRpcServer, PubSubApiServer, TestRpcServer, TraceServer,
+    TxPoolServer,
 };
 use cfx_rpc_cfx_impl::{
-    CfxFilterHandler, CfxHandler, DebugHandler, PosHandler, PubSubHandler,
-    TestHandler, TraceHandler, TxPoolHandler,
+    CfxFilterHandler, CfxHandler, DebugHandler, NetHandler, PosHandler,
+    PubSubHandler, TestHandler, TraceHandler, TxPoolHandler,
 };
 use cfx_rpc_cfx_types::RpcImplConfiguration;
 use cfx_tasks::TaskExecutor;
@@ -36,6 +40,7 @@ use parking_lot::{Condvar, Mutex};
 use std::{
     collections::{HashMap, HashSet},
     net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+    path::PathBuf,
     sync::Arc,
 };
 use txgen::{DirectTransactionGenerator, TransactionGenerator};
@@ -96,7 +101,8 @@ impl RpcModuleBuilder {
         let mut modules = TransportRpcModules::default();
 
         if !module_config.is_empty() {
-            let TransportRpcModuleConfig { http, ws } = module_config.clone();
+            let TransportRpcModuleConfig { http, ws, ipc } =
+                module_config.clone();
 
             let Self {
                 rpc_impl_config,
@@ -135,6 +141,7 @@ impl RpcModuleBuilder {
             modules.config = module_config;
             modules.http = registry.maybe_module(http.as_ref());
             modules.ws = registry.maybe_module(ws.as_ref());
+            modules.ipc = registry.maybe_module(ipc.as_ref());
         }
 
         modules
@@ -238,6 +245,11 @@ impl RpcRegistryInner {
                                 ),
                             ))
                             .expect("No conflicts");
+                        methods
+                            .merge(NetRpcServer::into_rpc(NetHandler::new(
+                                self.network.clone(),
+                            )))
+                            .expect("No conflicts");
                         methods.into()
                     }
                     CfxRpcModule::Pos => {
@@ -335,6 +347,8 @@ pub struct RpcServerConfig {
     ws_server_config: Option<ServerConfigBuilder>,
     ws_cors_domains: Option<String>,
     ws_addr: Option<SocketAddr>,
+    ipc_server_config: Option<IpcServerConfig>,
+    auth: Option<Arc<RpcAuth>>,
 }
 
 impl Default for RpcServerConfig {
@@ -346,6 +360,8 @@ impl Default for RpcServerConfig {
             ws_server_config: None,
             ws_cors_domains: None,
             ws_addr: None,
+            ipc_server_config: None,
+            auth: None,
         }
     }
 }
@@ -386,6 +402,16 @@ impl RpcServerConfig {
         self
     }
 
+    pub fn with_ipc(mut self, config: IpcServerConfig) -> Self {
+        self.ipc_server_config = Some(config);
+        self
+    }
+
+    pub fn with_auth(mut self, auth: Option<Arc<RpcAuth>>) -> Self {
+        self.auth = auth;
+        self
+    }
+
     pub const fn with_http_address(mut self, addr: SocketAddr) -> Self {
         self.http_addr = Some(addr);
         self
@@ -406,25 +432,37 @@ impl RpcServerConfig {
             self.ws_server_config =
                 Some(ws.set_id_provider(id_provider.clone()));
         }
+        if let Some(ipc) = self.ipc_server_config.take() {
+            self.ipc_server_config =
+                Some(ipc.with_id_provider(id_provider.clone()));
+        }
 
         self
     }
 
     pub const fn has_server(&self) -> bool {
-        self.http_server_config.is_some() || self.ws_server_config.is_some()
+        self.http_server_config.is_some()
+            || self.ws_server_config.is_some()
+            || self.ipc_server_config.is_some()
     }
 
     pub const fn http_address(&self) -> Option<SocketAddr> { self.http_addr }
 
     pub const fn ws_address(&self) -> Option<SocketAddr> { self.ws_addr }
 
+    pub fn ipc_endpoint(&self) -> Option<&PathBuf> {
+        self.ipc_server_config.as_ref().map(IpcServerConfig::path)
+    }
+
     pub async fn start(
         self, modules: &TransportRpcModules,
         throttling_conf_file: Option<String>, throttling_section: &str,
         enable_metrics: bool,
     ) -> Result<RpcServerHandle, RpcError<CfxRpcModule>> {
         let throttling_section = throttling_section.to_string();
+        let auth = self.auth.clone();
         let rpc_middleware = RpcServiceBuilder::new()
+            .layer_fn(move |s| Auth::new(auth.clone(), s))
             .layer_fn(move |s| {
                 Throttle::new(
                     throttling_conf_file.as_ref().map(|s| s.as_str()),
@@ -435,6 +473,21 @@ impl RpcServerConfig {
             .layer_fn(move |s| Metrics::new(s, enable_metrics))
             .layer_fn(|s| Logger::new(s));
 
+        let (ipc_endpoint, ipc) =
+            match (self.ipc_server_config, modules.ipc.clone()) {
+                (Some(config), Some(module)) => {
+                    let endpoint = config.path().clone();
+                    let handle = config.start(module).map_err(|e| {
+                        RpcError::Custom(format!(
+                            "Failed to start IPC server at {:?}: {}",
+                            endpoint, e
+                        ))
+                    })?;
+                    (Some(endpoint), Some(handle))
+                }
+                _ => (None, None),
+            };
+
         let http_socket_addr = self.http_addr.unwrap_or(SocketAddr::V4(
             SocketAddrV4::new(Ipv4Addr::LOCALHOST, DEFAULT_HTTP_PORT),
         ));
@@ -473,7 +526,8 @@ impl RpcServerConfig {
                 let server = ServerBuilder::new()
                     .set_http_middleware(
                         tower::ServiceBuilder::new()
-                            .option_layer(maybe_cors_layer(cors)?),
+                            .option_layer(maybe_cors_layer(cors)?)
+                            .layer(CredentialLayer),
                     )
                     .set_rpc_middleware(rpc_middleware)
                     .set_config(config.build())
@@ -500,6 +554,8 @@ impl RpcServerConfig {
                         ws_local_addr: Some(addr),
                         http: Some(handle.clone()),
                         ws: Some(handle),
+                        ipc_endpoint: ipc_endpoint.clone(),
+                        ipc: ipc.clone(),
                     });
                 }
 
@@ -514,14 +570,20 @@ impl RpcServerConfig {
             ws_local_addr: None,
             http: None,
             ws: None,
+            ipc_endpoint,
+            ipc,
         };
 
         if let Some(config) = self.ws_server_config {
             let server = ServerBuilder::new()
                 .set_config(config.ws_only().build())
-                .set_http_middleware(tower::ServiceBuilder::new().option_layer(
-                    maybe_cors_layer(self.ws_cors_domains.clone())?,
-                ))
+                .set_http_middleware(
+                    tower::ServiceBuilder::new()
+                        .option_layer(maybe_cors_layer(
+                            self.ws_cors_domains.clone(),
+                        )?)
+                        .layer(CredentialLayer),
+                )
                 .set_rpc_middleware(rpc_middleware.clone())
                 .build(ws_socket_addr)
                 .await
@@ -545,9 +607,13 @@ impl RpcServerConfig {
         if let Some(config) = self.http_server_config {
             let server = ServerBuilder::new()
                 .set_config(config.http_only().build())
-                .set_http_middleware(tower::ServiceBuilder::new().option_layer(
-                    maybe_cors_layer(self.http_cors_domains.clone())?,
-                ))
+                .set_http_middleware(
+                    tower::ServiceBuilder::new()
+                        .option_layer(maybe_cors_layer(
+                            self.http_cors_domains.clone(),
+                        )?)
+                        .layer(CredentialLayer),
+                )
                 .set_rpc_middleware(rpc_middleware)
                 .build(http_socket_addr)
                 .await
@@ -577,6 +643,7 @@ impl RpcServerConfig {
 pub struct TransportRpcModuleConfig {
     pub http: Option<RpcModuleSelection>,
     pub ws: Option<RpcModuleSelection>,
+    pub ipc: Option<RpcModuleSelection>,
 }
 
 impl TransportRpcModuleConfig {
@@ -588,6 +655,10 @@ impl TransportRpcModuleConfig {
         Self::default().with_ws(ws)
     }
 
+    pub fn set_ipc(ipc: impl Into<RpcModuleSelection>) -> Self {
+        Self::default().with_ipc(ipc)
+    }
+
     pub fn with_http(mut self, http: impl Into<RpcModuleSelection>) -> Self {
         self.http = Some(http.into());
         self
@@ -598,14 +669,23 @@ impl TransportRpcModuleConfig {
         self
     }
 
+    pub fn with_ipc(mut self, ipc: impl Into<RpcModuleSelection>) -> Self {
+        self.ipc = Some(ipc.into());
+        self
+    }
+
     pub fn http_mut(&mut self) -> &mut Option<RpcModuleSelection> {
         &mut self.http
     }
 
     pub fn ws_mut(&mut self) -> &mut Option<RpcModuleSelection> { &mut self.ws }
 
+    pub fn ipc_mut(&mut self) -> &mut Option<RpcModuleSelection> {
+        &mut self.ipc
+    }
+
     pub const fn is_empty(&self) -> bool {
-        self.http.is_none() && self.ws.is_none()
+        self.http.is_none() && self.ws.is_none() && self.ipc.is_none()
     }
 
     pub const fn http(&self) -> Option<&RpcModuleSelection> {
@@ -614,6 +694,8 @@ impl TransportRpcModuleConfig {
 
     pub const fn ws(&self) -> Option<&RpcModuleSelection> { self.ws.as_ref() }
 
+    pub const fn ipc(&self) -> Option<&RpcModuleSelection> { self.ipc.as_ref() }
+
     fn ensure_ws_http_identical(
         &self,
     ) -> Result<(), WsHttpSamePortError<CfxRpcModule>> {
@@ -658,6 +740,7 @@ pub struct TransportRpcModules<Context = ()> {
     pub config: TransportRpcModuleConfig,
     pub http: Option<RpcModule<Context>>,
     pub ws: Option<RpcModule<Context>>,
+    pub ipc: Option<RpcModule<Context>>,
 }
 
 impl TransportRpcModules {
@@ -683,12 +766,22 @@ impl TransportRpcModules {
         Ok(false)
     }
 
+    pub fn merge_ipc(
+        &mut self, other: impl Into<Methods>,
+    ) -> Result<bool, RegisterMethodError> {
+        if let Some(ref mut ipc) = self.ipc {
+            return ipc.merge(other.into()).map(|_| true);
+        }
+        Ok(false)
+    }
+
     pub fn merge_configured(
         &mut self, other: impl Into<Methods>,
     ) -> Result<(), RegisterMethodError> {
         let other = other.into();
         self.merge_http(other.clone())?;
         self.merge_ws(other.clone())?;
+        self.merge_ipc(other.clone())?;
         Ok(())
     }
 
@@ -708,12 +801,21 @@ impl TransportRpcModules {
         }
     }
 
+    pub fn remove_ipc_method(&mut self, method_name: &'static str) -> bool {
+        if let Some(ipc_module) = &mut self.ipc {
+            ipc_module.remove_method(method_name).is_some()
+        } else {
+            false
+        }
+    }
+
     pub fn remove_method_from_configured(
         &mut self, method_name: &'static str,
     ) -> bool {
         let http_removed = self.remove_http_method(method_name);
         let ws_removed = self.remove_ws_method(method_name);
+        let ipc_removed = self.remove_ipc_method(method_name);
 
-        http_removed || ws_removed
+        http_removed || ws_removed || ipc_removed
     }
 }
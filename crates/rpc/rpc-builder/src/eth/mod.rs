@@ -29,20 +29,28 @@
 #![allow(unused)]
 mod module;
 
+use crate::ipc::IpcServerConfig;
 pub use crate::{
     error::*, id_provider::SubscriptionIdProvider, RpcServerHandle,
 };
-use cfx_rpc_middlewares::{maybe_cors_layer, Logger, Metrics, Throttle};
+use blockgen::RemoteWork;
+use cfx_rpc_middlewares::{
+    maybe_cors_layer, Auth, CredentialLayer, Logger, Metrics, RpcAuth, Throttle,
+};
 pub use module::{EthRpcModule, RpcModuleSelection};
 
 use cfx_rpc_cfx_types::RpcImplConfiguration;
 use cfx_rpc_eth_api::*;
-use cfx_rpc_eth_impl::{helpers::ChainInfo, *};
+use cfx_rpc_eth_impl::{
+    helpers::{ChainInfo, PendingBlockProvider},
+    *,
+};
 use cfx_tasks::TaskExecutor;
 use cfxcore::{
     Notifications, SharedConsensusGraph, SharedSynchronizationService,
     SharedTransactionPool,
 };
+use cfxcore_accounts::AccountProvider;
 pub use jsonrpsee::server::ServerBuilder;
 use jsonrpsee::{
     core::RegisterMethodError,
@@ -52,9 +60,11 @@ use jsonrpsee::{
     },
     Methods, RpcModule,
 };
+use log::warn;
 use std::{
     collections::HashMap,
     net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+    path::PathBuf,
     sync::Arc,
 };
 
@@ -72,6 +82,8 @@ pub struct RpcModuleBuilder {
     tx_pool: SharedTransactionPool,
     executor: TaskExecutor,
     notifications: Arc<Notifications>,
+    accounts: Arc<AccountProvider>,
+    remote_work: Option<Arc<RemoteWork>>,
 }
 
 impl RpcModuleBuilder {
@@ -79,6 +91,7 @@ impl RpcModuleBuilder {
         config: RpcImplConfiguration, consensus: SharedConsensusGraph,
         sync: SharedSynchronizationService, tx_pool: SharedTransactionPool,
         executor: TaskExecutor, notifications: Arc<Notifications>,
+        accounts: Arc<AccountProvider>, remote_work: Option<Arc<RemoteWork>>,
     ) -> Self {
         Self {
             config,
@@ -87,6 +100,8 @@ impl RpcModuleBuilder {
             tx_pool,
             executor,
             notifications,
+            accounts,
+            remote_work,
         }
     }
 
@@ -99,7 +114,8 @@ impl RpcModuleBuilder {
         let mut modules = TransportRpcModules::default();
 
         if !module_config.is_empty() {
-            let TransportRpcModuleConfig { http, ws } = module_config.clone();
+            let TransportRpcModuleConfig { http, ws, ipc } =
+                module_config.clone();
 
             let Self {
                 config,
@@ -108,6 +124,8 @@ impl RpcModuleBuilder {
                 tx_pool,
                 executor,
                 notifications,
+                accounts,
+                remote_work,
             } = self;
 
             let mut registry = RpcRegistryInner::new(
@@ -117,11 +135,14 @@ impl RpcModuleBuilder {
                 tx_pool,
                 executor,
                 notifications,
+                accounts,
+                remote_work,
             );
 
             modules.config = module_config;
-            modules.http = registry.maybe_module(http.as_ref());
-            modules.ws = registry.maybe_module(ws.as_ref());
+            modules.http = registry.maybe_module(http.as_ref(), false);
+            modules.ws = registry.maybe_module(ws.as_ref(), false);
+            modules.ipc = registry.maybe_module(ipc.as_ref(), true);
         }
 
         modules
@@ -138,6 +159,9 @@ pub struct RpcRegistryInner {
     modules: HashMap<EthRpcModule, Methods>,
     executor: TaskExecutor,
     notifications: Arc<Notifications>,
+    accounts: Arc<AccountProvider>,
+    remote_work: Option<Arc<RemoteWork>>,
+    pending_block: PendingBlockProvider,
 }
 
 impl RpcRegistryInner {
@@ -145,7 +169,10 @@ impl RpcRegistryInner {
         config: RpcImplConfiguration, consensus: SharedConsensusGraph,
         sync: SharedSynchronizationService, tx_pool: SharedTransactionPool,
         executor: TaskExecutor, notifications: Arc<Notifications>,
+        accounts: Arc<AccountProvider>, remote_work: Option<Arc<RemoteWork>>,
     ) -> Self {
+        let pending_block =
+            PendingBlockProvider::new(consensus.clone(), tx_pool.clone());
         Self {
             consensus,
             config,
@@ -154,6 +181,9 @@ impl RpcRegistryInner {
             modules: Default::default(),
             executor,
             notifications,
+            accounts,
+            remote_work,
+            pending_block,
         }
     }
 
@@ -203,16 +233,20 @@ impl RpcRegistryInner {
 
     /// Helper function to create a [`RpcModule`] if it's not `None`
     fn maybe_module(
-        &mut self, config: Option<&RpcModuleSelection>,
+        &mut self, config: Option<&RpcModuleSelection>, is_local: bool,
     ) -> Option<RpcModule<()>> {
-        config.map(|config| self.module_for(config))
+        config.map(|config| self.module_for(config, is_local))
     }
 
     /// Populates a new [`RpcModule`] based on the selected [`EthRpcModule`]s in
-    /// the given [`RpcModuleSelection`]
-    pub fn module_for(&mut self, config: &RpcModuleSelection) -> RpcModule<()> {
+    /// the given [`RpcModuleSelection`]. The local only modules are skipped
+    /// unless the module is served on a local transport (IPC).
+    pub fn module_for(
+        &mut self, config: &RpcModuleSelection, is_local: bool,
+    ) -> RpcModule<()> {
         let mut module = RpcModule::new(());
-        let all_methods = self.eth_methods(config.iter_selection());
+        let all_methods =
+            self.eth_methods(served_modules(config, is_local).into_iter());
         for methods in all_methods {
             module.merge(methods).expect("No conflicts");
         }
@@ -245,6 +279,8 @@ impl RpcRegistryInner {
                             self.sync.clone(),
                             self.tx_pool.clone(),
                             self.executor.clone(),
+                            self.remote_work.clone(),
+                            self.pending_block.clone(),
                         )
                         .into_rpc();
                         if self.config.poll_lifetime_in_seconds.is_some() {
@@ -255,6 +291,7 @@ impl RpcRegistryInner {
                                 self.executor.clone(),
                                 self.config.poll_lifetime_in_seconds.unwrap(),
                                 self.config.get_logs_filter_max_limit,
+                                self.pending_block.clone(),
                             )
                             .into_rpc();
                             module.merge(filter_module).expect("No conflicts");
@@ -284,6 +321,8 @@ impl RpcRegistryInner {
                             self.sync.clone(),
                             self.tx_pool.clone(),
                             self.executor.clone(),
+                            self.remote_work.clone(),
+                            self.pending_block.clone(),
                         );
                         ParityApi::new(eth_api).into_rpc().into()
                     }
@@ -297,6 +336,12 @@ impl RpcRegistryInner {
                     )
                     .into_rpc()
                     .into(),
+                    EthRpcModule::Personal => PersonalApi::new(
+                        Box::new(ChainInfo::new(self.consensus.clone())),
+                        self.accounts.clone(),
+                    )
+                    .into_rpc()
+                    .into(),
                 })
                 .clone()
         };
@@ -309,6 +354,23 @@ impl RpcRegistryInner {
     }
 }
 
+/// Returns the modules of `config` to serve on a transport. The local only
+/// modules are only served on local transports.
+fn served_modules(
+    config: &RpcModuleSelection, is_local: bool,
+) -> Vec<EthRpcModule> {
+    config
+        .iter_selection()
+        .filter(|module| {
+            let served = is_local || !module.is_local_only();
+            if !served && matches!(config, RpcModuleSelection::Selection(_)) {
+                warn!("The {} eSpace RPC is only served on IPC", module);
+            }
+            served
+        })
+        .collect()
+}
+
 /// A builder type for configuring and launching the servers that will handle
 /// RPC requests.
 ///
@@ -335,6 +397,10 @@ pub struct RpcServerConfig {
     ws_cors_domains: Option<String>,
     /// Address where to bind the ws server to
     ws_addr: Option<SocketAddr>,
+    /// Configs for JSON-RPC IPC server
+    ipc_server_config: Option<IpcServerConfig>,
+    /// Authentication of the callers, which is disabled if it is `None`
+    auth: Option<Arc<RpcAuth>>,
 }
 
 impl Default for RpcServerConfig {
@@ -346,6 +412,8 @@ impl Default for RpcServerConfig {
             ws_server_config: None,
             ws_cors_domains: None,
             ws_addr: None,
+            ipc_server_config: None,
+            auth: None,
             // rpc_middleware: RpcServiceBuilder::new(),
         }
     }
@@ -404,6 +472,18 @@ impl RpcServerConfig {
         self
     }
 
+    /// Configures the ipc server
+    pub fn with_ipc(mut self, config: IpcServerConfig) -> Self {
+        self.ipc_server_config = Some(config);
+        self
+    }
+
+    /// Configure the authentication of the callers for http _and_ ws
+    pub fn with_auth(mut self, auth: Option<Arc<RpcAuth>>) -> Self {
+        self.auth = auth;
+        self
+    }
+
     /// Configures the [`SocketAddr`] of the http server
     ///
     /// Default is [`Ipv4Addr::LOCALHOST`] and
@@ -433,6 +513,10 @@ impl RpcServerConfig {
             self.ws_server_config =
                 Some(ws.set_id_provider(id_provider.clone()));
         }
+        if let Some(ipc) = self.ipc_server_config {
+            self.ipc_server_config =
+                Some(ipc.with_id_provider(id_provider.clone()));
+        }
 
         self
     }
@@ -442,7 +526,9 @@ impl RpcServerConfig {
     /// If no server is configured, no server will be launched on
     /// [`RpcServerConfig::start`].
     pub const fn has_server(&self) -> bool {
-        self.http_server_config.is_some() || self.ws_server_config.is_some()
+        self.http_server_config.is_some()
+            || self.ws_server_config.is_some()
+            || self.ipc_server_config.is_some()
     }
 
     /// Returns the [`SocketAddr`] of the http server
@@ -451,6 +537,11 @@ impl RpcServerConfig {
     /// Returns the [`SocketAddr`] of the ws server
     pub const fn ws_address(&self) -> Option<SocketAddr> { self.ws_addr }
 
+    /// Returns the path of the ipc socket
+    pub fn ipc_endpoint(&self) -> Option<&PathBuf> {
+        self.ipc_server_config.as_ref().map(IpcServerConfig::path)
+    }
+
     // Builds and starts the configured server(s): http, ws, ipc.
     //
     // If both http and ws are on the same port, they are combined into one
@@ -461,7 +552,9 @@ impl RpcServerConfig {
         self, modules: &TransportRpcModules,
         throttling_conf_file: Option<String>, enable_metrics: bool,
     ) -> Result<RpcServerHandle, RpcError> {
+        let auth = self.auth.clone();
         let rpc_middleware = RpcServiceBuilder::new()
+            .layer_fn(move |s| Auth::new(auth.clone(), s))
             .layer_fn(move |s| {
                 Throttle::new(
                     throttling_conf_file.as_ref().map(|s| s.as_str()),
@@ -472,6 +565,21 @@ impl RpcServerConfig {
             .layer_fn(move |s| Metrics::new(s, enable_metrics))
             .layer_fn(|s| Logger::new(s));
 
+        let (ipc_endpoint, ipc) =
+            match (self.ipc_server_config, modules.ipc.clone()) {
+                (Some(config), Some(module)) => {
+                    let endpoint = config.path().clone();
+                    let handle = config.start(module).map_err(|e| {
+                        RpcError::Custom(format!(
+                            "Failed to start IPC server at {:?}: {}",
+                            endpoint, e
+                        ))
+                    })?;
+                    (Some(endpoint), Some(handle))
+                }
+                _ => (None, None),
+            };
+
         let http_socket_addr = self.http_addr.unwrap_or(SocketAddr::V4(
             SocketAddrV4::new(Ipv4Addr::LOCALHOST, DEFAULT_HTTP_PORT),
         ));
@@ -513,7 +621,8 @@ impl RpcServerConfig {
                 let server = ServerBuilder::new()
                     .set_http_middleware(
                         tower::ServiceBuilder::new()
-                            .option_layer(maybe_cors_layer(cors)?),
+                            .option_layer(maybe_cors_layer(cors)?)
+                            .layer(CredentialLayer),
                     )
                     .set_rpc_middleware(rpc_middleware)
                     .set_config(config.build())
@@ -543,6 +652,8 @@ impl RpcServerConfig {
                         ws_local_addr: Some(addr),
                         http: http_handle,
                         ws: ws_handle,
+                        ipc_endpoint: ipc_endpoint.clone(),
+                        ipc: ipc.clone(),
                     });
                 }
 
@@ -557,13 +668,19 @@ impl RpcServerConfig {
             ws_local_addr: None,
             http: None,
             ws: None,
+            ipc_endpoint,
+            ipc,
         };
         if let Some(config) = self.ws_server_config {
             let server = ServerBuilder::new()
                 .set_config(config.ws_only().build())
-                .set_http_middleware(tower::ServiceBuilder::new().option_layer(
-                    maybe_cors_layer(self.ws_cors_domains.clone())?,
-                ))
+                .set_http_middleware(
+                    tower::ServiceBuilder::new()
+                        .option_layer(maybe_cors_layer(
+                            self.ws_cors_domains.clone(),
+                        )?)
+                        .layer(CredentialLayer),
+                )
                 .set_rpc_middleware(rpc_middleware.clone())
                 .build(ws_socket_addr)
                 .await
@@ -588,9 +705,13 @@ impl RpcServerConfig {
         if let Some(config) = self.http_server_config {
             let server = ServerBuilder::new()
                 .set_config(config.http_only().build())
-                .set_http_middleware(tower::ServiceBuilder::new().option_layer(
-                    maybe_cors_layer(self.http_cors_domains.clone())?,
-                ))
+                .set_http_middleware(
+                    tower::ServiceBuilder::new()
+                        .option_layer(maybe_cors_layer(
+                            self.http_cors_domains.clone(),
+                        )?)
+                        .layer(CredentialLayer),
+                )
                 .set_rpc_middleware(rpc_middleware)
                 .build(http_socket_addr)
                 .await
@@ -625,6 +746,8 @@ pub struct TransportRpcModuleConfig {
     http: Option<RpcModuleSelection>,
     /// ws module configuration
     ws: Option<RpcModuleSelection>,
+    /// ipc module configuration
+    ipc: Option<RpcModuleSelection>,
 }
 
 impl TransportRpcModuleConfig {
@@ -638,6 +761,11 @@ impl TransportRpcModuleConfig {
         Self::default().with_ws(ws)
     }
 
+    /// Creates a new config with only ipc set
+    pub fn set_ipc(ipc: impl Into<RpcModuleSelection>) -> Self {
+        Self::default().with_ipc(ipc)
+    }
+
     /// Sets the [`RpcModuleSelection`] for the http transport.
     pub fn with_http(mut self, http: impl Into<RpcModuleSelection>) -> Self {
         self.http = Some(http.into());
@@ -650,6 +778,12 @@ impl TransportRpcModuleConfig {
         self
     }
 
+    /// Sets the [`RpcModuleSelection`] for the ipc transport.
+    pub fn with_ipc(mut self, ipc: impl Into<RpcModuleSelection>) -> Self {
+        self.ipc = Some(ipc.into());
+        self
+    }
+
     /// Get a mutable reference to the
     pub fn http_mut(&mut self) -> &mut Option<RpcModuleSelection> {
         &mut self.http
@@ -658,9 +792,14 @@ impl TransportRpcModuleConfig {
     /// Get a mutable reference to the
     pub fn ws_mut(&mut self) -> &mut Option<RpcModuleSelection> { &mut self.ws }
 
+    /// Get a mutable reference to the
+    pub fn ipc_mut(&mut self) -> &mut Option<RpcModuleSelection> {
+        &mut self.ipc
+    }
+
     /// Returns true if no transports are configured
     pub const fn is_empty(&self) -> bool {
-        self.http.is_none() && self.ws.is_none()
+        self.http.is_none() && self.ws.is_none() && self.ipc.is_none()
     }
 
     /// Returns the [`RpcModuleSelection`] for the http transport
@@ -671,6 +810,9 @@ impl TransportRpcModuleConfig {
     /// Returns the [`RpcModuleSelection`] for the ws transport
     pub const fn ws(&self) -> Option<&RpcModuleSelection> { self.ws.as_ref() }
 
+    /// Returns the [`RpcModuleSelection`] for the ipc transport
+    pub const fn ipc(&self) -> Option<&RpcModuleSelection> { self.ipc.as_ref() }
+
     /// Ensures that both http and ws are configured and that they are
     /// configured to use the same port.
     fn ensure_ws_http_identical(&self) -> Result<(), WsHttpSamePortError> {
@@ -713,11 +855,13 @@ impl TransportRpcModuleConfig {
 #[derive(Debug, Clone, Default)]
 pub struct TransportRpcModules<Context = ()> {
     /// The original config
-    config: TransportRpcModuleConfig,
+    pub config: TransportRpcModuleConfig,
     /// rpcs module for http
-    http: Option<RpcModule<Context>>,
+    pub http: Option<RpcModule<Context>>,
     /// rpcs module for ws
-    ws: Option<RpcModule<Context>>,
+    pub ws: Option<RpcModule<Context>>,
+    /// rpcs module for ipc
+    pub ipc: Option<RpcModule<Context>>,
 }
 
 // === impl TransportRpcModules ===
@@ -757,6 +901,20 @@ impl TransportRpcModules {
         Ok(false)
     }
 
+    /// Merge the given [Methods] in the configured ipc methods.
+    ///
+    /// Fails if any of the methods in other is present already.
+    ///
+    /// Returns [Ok(false)] if no ipc transport is configured.
+    pub fn merge_ipc(
+        &mut self, other: impl Into<Methods>,
+    ) -> Result<bool, RegisterMethodError> {
+        if let Some(ref mut ipc) = self.ipc {
+            return ipc.merge(other.into()).map(|_| true);
+        }
+        Ok(false)
+    }
+
     /// Merge the given [Methods] in all configured methods.
     ///
     /// Fails if any of the methods in other is present already.
@@ -766,6 +924,7 @@ impl TransportRpcModules {
         let other = other.into();
         self.merge_http(other.clone())?;
         self.merge_ws(other.clone())?;
+        self.merge_ipc(other.clone())?;
         Ok(())
     }
 
@@ -799,6 +958,21 @@ impl TransportRpcModules {
         }
     }
 
+    /// Removes the method with the given name from the configured ipc methods.
+    ///
+    /// Returns `true` if the method was found and removed, `false` otherwise.
+    ///
+    /// Be aware that a subscription consist of two methods, `subscribe` and
+    /// `unsubscribe` and it's the caller responsibility to remove both
+    /// `subscribe` and `unsubscribe` methods for subscriptions.
+    pub fn remove_ipc_method(&mut self, method_name: &'static str) -> bool {
+        if let Some(ipc_module) = &mut self.ipc {
+            ipc_module.remove_method(method_name).is_some()
+        } else {
+            false
+        }
+    }
+
     /// Removes the method with the given name from all configured transports.
     ///
     /// Returns `true` if the method was found and removed, `false` otherwise.
@@ -807,7 +981,121 @@ impl TransportRpcModules {
     ) -> bool {
         let http_removed = self.remove_http_method(method_name);
         let ws_removed = self.remove_ws_method(method_name);
+        let ipc_removed = self.remove_ipc_method(method_name);
+
+        http_removed || ws_removed || ipc_removed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cfx_rpc_cfx_types::traits::ChainMetaProvider;
+    use jsonrpsee::types::ErrorCode;
+    use serde_json::{json, Value};
+
+    struct TestChain;
+
+    impl ChainMetaProvider for TestChain {
+        fn chain_id(&self) -> u32 { 71 }
+    }
+
+    /// Assembles the module served on a transport the same way as
+    /// `RpcRegistryInner::module_for`, with only the personal namespace
+    /// implemented.
+    fn transport_module(
+        config: &RpcModuleSelection, is_local: bool,
+        accounts: &Arc<AccountProvider>,
+    ) -> RpcModule<()> {
+        let mut module = RpcModule::new(());
+        for namespace in served_modules(config, is_local) {
+            if namespace == EthRpcModule::Personal {
+                let personal =
+                    PersonalApi::new(Box::new(TestChain), accounts.clone());
+                module.merge(personal.into_rpc()).expect("No conflicts");
+            }
+        }
+        module
+    }
+
+    async fn sign_typed_data(
+        module: &RpcModule<()>, address: impl serde::Serialize,
+    ) -> Value {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "personal_signTypedData",
+            "params": [address, {
+                "types": {
+                    "EIP712Domain": [
+                        { "name": "name", "type": "string" },
+                        { "name": "chainId", "type": "uint256" }
+                    ],
+                    "Permit": [
+                        { "name": "spender", "type": "address" },
+                        { "name": "value", "type": "uint256" }
+                    ]
+                },
+                "primaryType": "Permit",
+                "domain": { "name": "Token", "chainId": 71 },
+                "message": {
+                    "spender": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB",
+                    "value": 1
+                }
+            }],
+        });
+        let (response, _) = module
+            .raw_json_request(&request.to_string(), 1)
+            .await
+            .unwrap();
+        serde_json::from_str(&response).unwrap()
+    }
+
+    #[test]
+    fn test_local_only_modules() {
+        assert!(EthRpcModule::Personal.is_local_only());
+        assert_eq!(
+            "personal".parse::<EthRpcModule>(),
+            Ok(EthRpcModule::Personal)
+        );
+
+        let selections = [
+            RpcModuleSelection::All,
+            RpcModuleSelection::Evm,
+            RpcModuleSelection::from([
+                EthRpcModule::Eth,
+                EthRpcModule::Personal,
+            ]),
+        ];
+        for selection in &selections {
+            let public = served_modules(selection, false);
+            assert!(!public.contains(&EthRpcModule::Personal));
+            assert!(public.contains(&EthRpcModule::Eth));
+        }
+        assert!(served_modules(&RpcModuleSelection::All, true)
+            .contains(&EthRpcModule::Personal));
+    }
 
-        http_removed || ws_removed
+    #[tokio::test]
+    async fn test_sign_typed_data_not_served_publicly() {
+        let accounts = Arc::new(AccountProvider::transient_provider());
+        let address = accounts.new_account(&"".to_string().into()).unwrap();
+        accounts
+            .unlock_account_permanently(address, "".to_string().into())
+            .unwrap();
+
+        // Requesting the personal namespace on HTTP or WS has no effect.
+        let public =
+            transport_module(&RpcModuleSelection::All, false, &accounts);
+        let response = sign_typed_data(&public, address).await;
+        assert_eq!(response["error"]["code"], ErrorCode::MethodNotFound.code());
+
+        // The unlocked account signs on IPC.
+        let ipc = transport_module(&RpcModuleSelection::All, true, &accounts);
+        let response = sign_typed_data(&ipc, address).await;
+        let signature = response["result"].as_str().unwrap();
+        // 65 bytes, with `v` being 27 or 28
+        assert_eq!(signature.len(), 2 + 65 * 2);
+        assert!(signature.ends_with("1b") || signature.ends_with("1c"));
     }
 }
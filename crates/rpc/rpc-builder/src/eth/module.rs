@@ -276,6 +276,8 @@ pub enum EthRpcModule {
     Parity,
     /// pubsub
     PubSub,
+    /// `personal_` module, only served on IPC
+    Personal,
 }
 
 impl EthRpcModule {
@@ -303,6 +305,10 @@ impl EthRpcModule {
     /// Returns the string representation of the module.
     #[inline]
     pub fn as_str(&self) -> &'static str { self.into() }
+
+    /// Returns true if the module uses the local accounts of the node, so
+    /// that it is never served on the public HTTP and WS transports.
+    pub const fn is_local_only(&self) -> bool { matches!(self, Self::Personal) }
 }
 
 impl FromStr for EthRpcModule {
@@ -320,6 +326,7 @@ impl FromStr for EthRpcModule {
             "rpc" => Self::Rpc,
             "parity" => Self::Parity,
             "pubsub" | "ethpubsub" => Self::PubSub,
+            "personal" => Self::Personal,
             _ => return Err(ParseError::VariantNotFound),
         })
     }
@@ -0,0 +1,679 @@
+//! JSON-RPC over Unix domain sockets in the format of the geth IPC: the
+//! requests and the responses are JSON values written one after another on
+//! the stream.
+//!
+//! The IPC endpoint is only reachable by the local users allowed by the
+//! permissions of the socket file, so the RPC middlewares of the public
+//! servers (authentication, throttling) are not applied.
+
+#![cfg_attr(not(unix), allow(dead_code, unused_imports))]
+
+use crate::id_provider::SubscriptionIdProvider;
+use jsonrpsee::{
+    core::{
+        server::{
+            helpers::{prepare_error, MethodSink},
+            BatchResponseBuilder, BoundedSubscriptions, ConnectionId,
+            MethodCallback, MethodResponse, SubscriptionState,
+        },
+        traits::IdProvider,
+    },
+    server::{stop_channel, ServerHandle, StopHandle},
+    types::{
+        error::reject_too_many_subscriptions, ErrorCode, ErrorObject, Id,
+        Notification, Params, Request,
+    },
+    Methods,
+};
+use log::{debug, warn};
+use serde_json::value::RawValue;
+use std::{io, path::PathBuf, sync::Arc};
+use tokio::sync::{mpsc, oneshot};
+
+/// The file mode of the socket, which only allows the owner to connect.
+pub const DEFAULT_IPC_PERMISSIONS: u32 = 0o600;
+
+const READ_BUFFER_SIZE: usize = 8192;
+
+#[derive(Debug, Clone)]
+pub struct IpcServerConfig {
+    /// Path of the socket file
+    path: PathBuf,
+    /// File mode of the socket file
+    permissions: u32,
+    /// Maximum size of a request, in bytes
+    max_request_body_size: u32,
+    /// Maximum size of a response, in bytes
+    max_response_body_size: u32,
+    /// Maximum number of the subscriptions of each connection
+    max_subscriptions_per_connection: u32,
+    /// Number of the messages buffered for each connection
+    message_buffer_capacity: u32,
+    /// Generates the subscription ids
+    id_provider: Arc<dyn IdProvider>,
+}
+
+impl IpcServerConfig {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            permissions: DEFAULT_IPC_PERMISSIONS,
+            max_request_body_size: 10 * 1024 * 1024,
+            max_response_body_size: 10 * 1024 * 1024,
+            max_subscriptions_per_connection: 1024,
+            message_buffer_capacity: 1024,
+            id_provider: Arc::new(SubscriptionIdProvider::default()),
+        }
+    }
+
+    /// Configures the file mode of the socket file, e.g. `0o660`
+    pub const fn with_permissions(mut self, permissions: u32) -> Self {
+        self.permissions = permissions;
+        self
+    }
+
+    pub const fn with_max_request_body_size(mut self, size: u32) -> Self {
+        self.max_request_body_size = size;
+        self
+    }
+
+    pub const fn with_max_response_body_size(mut self, size: u32) -> Self {
+        self.max_response_body_size = size;
+        self
+    }
+
+    pub const fn with_max_subscriptions_per_connection(
+        mut self, max: u32,
+    ) -> Self {
+        self.max_subscriptions_per_connection = max;
+        self
+    }
+
+    pub const fn with_message_buffer_capacity(mut self, capacity: u32) -> Self {
+        self.message_buffer_capacity = capacity;
+        self
+    }
+
+    /// Configures the generator of the subscription ids, which is
+    /// [`SubscriptionIdProvider`] by default.
+    pub fn with_id_provider(
+        mut self, id_provider: impl IdProvider + 'static,
+    ) -> Self {
+        self.id_provider = Arc::new(id_provider);
+        self
+    }
+
+    pub fn path(&self) -> &PathBuf { &self.path }
+
+    /// Binds the socket and serves the methods until the returned handle is
+    /// stopped.
+    #[cfg(unix)]
+    pub fn start(
+        self, methods: impl Into<Methods>,
+    ) -> io::Result<ServerHandle> {
+        use std::{
+            fs::{self, Permissions},
+            os::unix::fs::{FileTypeExt, PermissionsExt},
+        };
+        use tokio::net::UnixListener;
+
+        // The socket file left by a previous run prevents the binding, but
+        // any other file at the path is not removed.
+        if let Ok(metadata) = fs::symlink_metadata(&self.path) {
+            if !metadata.file_type().is_socket() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    format!("{:?} exists and is not a socket", self.path),
+                ));
+            }
+            fs::remove_file(&self.path)?;
+        }
+
+        let listener = UnixListener::bind(&self.path)?;
+        fs::set_permissions(
+            &self.path,
+            Permissions::from_mode(self.permissions),
+        )?;
+
+        let (stop_handle, server_handle) = stop_channel();
+        tokio::spawn(accept_connections(
+            listener,
+            methods.into(),
+            self,
+            stop_handle,
+        ));
+        Ok(server_handle)
+    }
+
+    #[cfg(not(unix))]
+    pub fn start(
+        self, _methods: impl Into<Methods>,
+    ) -> io::Result<ServerHandle> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "IPC is only supported on unix",
+        ))
+    }
+}
+
+#[cfg(unix)]
+async fn accept_connections(
+    listener: tokio::net::UnixListener, methods: Methods,
+    config: IpcServerConfig, stop_handle: StopHandle,
+) {
+    let mut next_conn_id = 0;
+    loop {
+        let stream = tokio::select! {
+            res = listener.accept() => match res {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    warn!("Failed to accept IPC connection: {:?}", e);
+                    continue;
+                }
+            },
+            _ = stop_handle.clone().shutdown() => break,
+        };
+
+        let service = IpcService {
+            methods: methods.clone(),
+            conn_id: ConnectionId(next_conn_id),
+            max_response_body_size: config.max_response_body_size,
+            bounded_subscriptions: BoundedSubscriptions::new(
+                config.max_subscriptions_per_connection,
+            ),
+            id_provider: config.id_provider.clone(),
+        };
+        next_conn_id = next_conn_id.wrapping_add(1);
+        tokio::spawn(serve_connection(
+            stream,
+            service,
+            config.max_request_body_size as usize,
+            config.message_buffer_capacity as usize,
+            stop_handle.clone(),
+        ));
+    }
+
+    if let Err(e) = std::fs::remove_file(&config.path) {
+        warn!("Failed to remove IPC socket {:?}: {:?}", config.path, e);
+    }
+}
+
+#[cfg(unix)]
+async fn serve_connection(
+    stream: tokio::net::UnixStream, service: IpcService,
+    max_request_body_size: usize, buffer_capacity: usize,
+    stop_handle: StopHandle,
+) {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let (mut reader, mut writer) = stream.into_split();
+    let (tx, mut rx) = mpsc::channel::<Box<RawValue>>(buffer_capacity);
+    let sink = MethodSink::new_with_limit(tx, service.max_response_body_size);
+    let (close_tx, mut close_rx) = oneshot::channel::<()>();
+
+    // The responses and the subscription notifications are written by one
+    // task, so the messages are not interleaved. Once the channel is closed
+    // or the client is gone, the subscriptions of the connection see their
+    // sink closed and end.
+    let writer_task = tokio::spawn(async move {
+        let mut closing = false;
+        loop {
+            let message = tokio::select! {
+                message = rx.recv() => message,
+                _ = &mut close_rx, if !closing => {
+                    // Write the messages already queued.
+                    closing = true;
+                    rx.close();
+                    continue;
+                }
+            };
+            let Some(message) = message else {
+                break;
+            };
+            if writer.write_all(message.get().as_bytes()).await.is_err()
+                || writer.write_all(b"\n").await.is_err()
+            {
+                break;
+            }
+        }
+    });
+
+    // Each pending call holds a sender, so the connection is closed once
+    // they are all completed.
+    let (pending_calls, mut pending_calls_completed) = mpsc::channel::<()>(1);
+    let mut splitter = JsonSplitter::default();
+    let mut chunk = vec![0u8; READ_BUFFER_SIZE];
+    loop {
+        let n = tokio::select! {
+            res = reader.read(&mut chunk) => match res {
+                Ok(0) => break,
+                Ok(n) => n,
+                Err(e) => {
+                    debug!("IPC connection read error: {:?}", e);
+                    break;
+                }
+            },
+            _ = stop_handle.clone().shutdown() => break,
+            _ = sink.closed() => break,
+        };
+        splitter.extend(&chunk[..n]);
+
+        let malformed = loop {
+            match splitter.next_value() {
+                Ok(Some(message)) => {
+                    let service = service.clone();
+                    let sink = sink.clone();
+                    let pending_calls = pending_calls.clone();
+                    tokio::spawn(async move {
+                        handle_message(&service, &message, &sink).await;
+                        drop(pending_calls);
+                    });
+                }
+                Ok(None) => break false,
+                Err(()) => break true,
+            }
+        };
+
+        if malformed {
+            // The stream cannot be split after a malformed value.
+            let _ = sink
+                .send_error(Id::Null, ErrorCode::ParseError.into())
+                .await;
+            break;
+        }
+        if splitter.pending_len() > max_request_body_size {
+            let _ = sink
+                .send_error(Id::Null, ErrorCode::OversizedRequest.into())
+                .await;
+            break;
+        }
+    }
+
+    drop(pending_calls);
+    let _ = pending_calls_completed.recv().await;
+    let _ = close_tx.send(());
+    let _ = writer_task.await;
+}
+
+/// Splits the JSON values out of a stream, scanning each byte once.
+///
+/// The values must be objects or arrays, i.e. a request or a batch, and can
+/// be separated by whitespaces.
+#[derive(Default)]
+struct JsonSplitter {
+    buf: Vec<u8>,
+    /// Offset of the value being scanned.
+    start: usize,
+    /// Offset of the first byte not scanned yet.
+    scanned: usize,
+    /// Nesting depth at the scanned offset.
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+}
+
+impl JsonSplitter {
+    fn extend(&mut self, data: &[u8]) {
+        // Drop the values returned so far.
+        self.buf.drain(..self.start);
+        self.scanned -= self.start;
+        self.start = 0;
+        self.buf.extend_from_slice(data);
+    }
+
+    /// Returns the next complete value, `Ok(None)` if more data is needed,
+    /// or an error if the stream is malformed.
+    fn next_value(&mut self) -> Result<Option<Vec<u8>>, ()> {
+        while self.scanned < self.buf.len() {
+            let byte = self.buf[self.scanned];
+            self.scanned += 1;
+            if self.in_string {
+                match byte {
+                    _ if self.escaped => self.escaped = false,
+                    b'\\' => self.escaped = true,
+                    b'"' => self.in_string = false,
+                    _ => {}
+                }
+                continue;
+            }
+            match byte {
+                b'{' | b'[' => self.depth += 1,
+                b'}' | b']' if self.depth > 0 => {
+                    self.depth -= 1;
+                    if self.depth == 0 {
+                        let value = self.buf[self.start..self.scanned].to_vec();
+                        self.start = self.scanned;
+                        return Ok(Some(value));
+                    }
+                }
+                b'"' if self.depth > 0 => self.in_string = true,
+                _ if self.depth > 0 => {}
+                _ if byte.is_ascii_whitespace() => {}
+                _ => return Err(()),
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the size of the incomplete value.
+    fn pending_len(&self) -> usize { self.buf.len() - self.start }
+}
+
+/// Calls the methods for a connection, the same as the RPC service of the
+/// jsonrpsee servers.
+#[derive(Clone)]
+struct IpcService {
+    methods: Methods,
+    conn_id: ConnectionId,
+    max_response_body_size: u32,
+    bounded_subscriptions: BoundedSubscriptions,
+    id_provider: Arc<dyn IdProvider>,
+}
+
+impl IpcService {
+    async fn call(
+        &self, req: Request<'_>, sink: &MethodSink,
+    ) -> MethodResponse {
+        let max_response_body_size = self.max_response_body_size as usize;
+        let Request {
+            id,
+            method,
+            params,
+            extensions,
+            ..
+        } = req;
+        let params = Params::new(params.as_ref().map(|p| p.get()));
+
+        let Some((_, callback)) = self.methods.method_with_name(&method) else {
+            return MethodResponse::error(
+                id,
+                ErrorObject::from(ErrorCode::MethodNotFound),
+            );
+        };
+        match callback {
+            MethodCallback::Sync(callback) => {
+                callback(id, params, max_response_body_size, extensions)
+            }
+            MethodCallback::Async(callback) => {
+                callback(
+                    id.into_owned(),
+                    params.into_owned(),
+                    self.conn_id,
+                    max_response_body_size,
+                    extensions,
+                )
+                .await
+            }
+            MethodCallback::Subscription(callback) => {
+                let Some(subscription_permit) =
+                    self.bounded_subscriptions.acquire()
+                else {
+                    return MethodResponse::error(
+                        id,
+                        reject_too_many_subscriptions(
+                            self.bounded_subscriptions.max(),
+                        ),
+                    );
+                };
+                let state = SubscriptionState {
+                    conn_id: self.conn_id,
+                    id_provider: &*self.id_provider,
+                    subscription_permit,
+                };
+                callback(id, params, sink.clone(), state, extensions).await
+            }
+            MethodCallback::Unsubscription(callback) => callback(
+                id,
+                params,
+                self.conn_id,
+                max_response_body_size,
+                extensions,
+            ),
+        }
+    }
+}
+
+/// Handles a single call or a batch of calls and sends the response. The
+/// responses of the subscriptions are sent by the subscriptions themselves.
+async fn handle_message(
+    service: &IpcService, message: &[u8], sink: &MethodSink,
+) {
+    let message = message.trim_ascii_start();
+    let response = if message.starts_with(b"[") {
+        handle_batch(service, message, sink).await
+    } else if let Ok(req) = serde_json::from_slice::<Request>(message) {
+        Some(service.call(req, sink).await)
+    } else if serde_json::from_slice::<Notification<Option<&RawValue>>>(message)
+        .is_ok()
+    {
+        // The notifications are ignored, the same as the other servers.
+        None
+    } else {
+        let (id, code) = prepare_error(message);
+        Some(MethodResponse::error(id, ErrorObject::from(code)))
+    };
+
+    let Some(response) = response else {
+        return;
+    };
+    if response.is_method_call() || response.is_batch() {
+        let is_success = response.is_success();
+        let (json, on_close, _) = response.into_parts();
+        if sink.send(json).await.is_err() {
+            return;
+        }
+        if let Some(on_close) = on_close {
+            on_close.notify(is_success);
+        }
+    }
+}
+
+/// Returns the response of a batch, or `None` if all the calls are
+/// notifications.
+async fn handle_batch(
+    service: &IpcService, message: &[u8], sink: &MethodSink,
+) -> Option<MethodResponse> {
+    let Ok(calls) = serde_json::from_slice::<Vec<&RawValue>>(message) else {
+        return Some(MethodResponse::error(
+            Id::Null,
+            ErrorObject::from(ErrorCode::ParseError),
+        ));
+    };
+    if calls.is_empty() {
+        return Some(MethodResponse::error(
+            Id::Null,
+            ErrorObject::from(ErrorCode::InvalidRequest),
+        ));
+    }
+
+    let mut responses = BatchResponseBuilder::new_with_limit(
+        service.max_response_body_size as usize,
+    );
+    let mut got_notification = false;
+    for call in calls {
+        let response =
+            if let Ok(req) = serde_json::from_str::<Request>(call.get()) {
+                service.call(req, sink).await
+            } else if serde_json::from_str::<Notification<Option<&RawValue>>>(
+                call.get(),
+            )
+            .is_ok()
+            {
+                got_notification = true;
+                continue;
+            } else {
+                let (id, code) = prepare_error(call.get().as_bytes());
+                MethodResponse::error(id, ErrorObject::from(code))
+            };
+        if let Err(too_large) = responses.append(response) {
+            return Some(too_large);
+        }
+    }
+
+    if responses.is_empty() && got_notification {
+        None
+    } else {
+        Some(MethodResponse::from_batch(responses.finish()))
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use jsonrpsee::{
+        core::{server::SubscriptionMessage, RpcResult, SubscriptionResult},
+        RpcModule,
+    };
+    use serde_json::{json, Value};
+    use std::path::Path;
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+        net::{unix::OwnedReadHalf, UnixStream},
+    };
+
+    fn test_module() -> RpcModule<()> {
+        let mut module = RpcModule::new(());
+        module
+            .register_method("test_hello", |_, _, _| RpcResult::Ok("hello"))
+            .unwrap();
+        module
+            .register_subscription(
+                "test_subscribe",
+                "test_subscription",
+                "test_unsubscribe",
+                |_, pending, _, _| async move {
+                    let sink = pending.accept().await?;
+                    let message = serde_json::value::to_raw_value(&"hi")?;
+                    sink.send(SubscriptionMessage::from(message)).await?;
+                    sink.closed().await;
+                    SubscriptionResult::Ok(())
+                },
+            )
+            .unwrap();
+        module
+    }
+
+    struct Client {
+        reader: BufReader<OwnedReadHalf>,
+        writer: tokio::net::unix::OwnedWriteHalf,
+    }
+
+    impl Client {
+        async fn connect(path: &Path) -> Self {
+            let (reader, writer) =
+                UnixStream::connect(path).await.unwrap().into_split();
+            Client {
+                reader: BufReader::new(reader),
+                writer,
+            }
+        }
+
+        async fn send(&mut self, data: &str) {
+            self.writer.write_all(data.as_bytes()).await.unwrap();
+        }
+
+        async fn receive(&mut self) -> Value {
+            let mut line = String::new();
+            self.reader.read_line(&mut line).await.unwrap();
+            serde_json::from_str(&line).unwrap()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.ipc");
+        let handle = IpcServerConfig::new(&path).start(test_module()).unwrap();
+        let mut client = Client::connect(&path).await;
+
+        // A single call, split across two writes.
+        client.send(r#"{"jsonrpc":"2.0","id":1,"#).await;
+        client.send(r#""method":"test_hello"}"#).await;
+        assert_eq!(
+            client.receive().await,
+            json!({"jsonrpc": "2.0", "id": 1, "result": "hello"})
+        );
+
+        // A batch, with a notification and an unknown method.
+        client
+            .send(
+                r#"[{"jsonrpc":"2.0","id":2,"method":"test_hello"},
+                    {"jsonrpc":"2.0","method":"test_hello"},
+                    {"jsonrpc":"2.0","id":3,"method":"test_unknown"}]"#,
+            )
+            .await;
+        let response = client.receive().await;
+        assert_eq!(response[0]["id"], 2);
+        assert_eq!(response[0]["result"], "hello");
+        assert_eq!(response[1]["id"], 3);
+        assert_eq!(
+            response[1]["error"]["code"],
+            ErrorCode::MethodNotFound.code()
+        );
+
+        // No response to a batch of notifications, and two calls in one
+        // write.
+        client
+            .send(
+                r#"[{"jsonrpc":"2.0","method":"test_hello"}]
+                {"jsonrpc":"2.0","id":4,"method":"test_subscribe"}"#,
+            )
+            .await;
+        let response = client.receive().await;
+        assert_eq!(response["id"], 4);
+        let subscription = response["result"].as_str().unwrap().to_string();
+        assert!(subscription.starts_with("0x"));
+        assert_eq!(
+            client.receive().await,
+            json!({
+                "jsonrpc": "2.0",
+                "method": "test_subscription",
+                "params": {"subscription": subscription, "result": "hi"},
+            })
+        );
+
+        // The subscription can't be removed by another connection.
+        let mut other = Client::connect(&path).await;
+        let unsubscribe = json!({
+            "jsonrpc": "2.0",
+            "id": 5,
+            "method": "test_unsubscribe",
+            "params": [subscription],
+        })
+        .to_string();
+        other.send(&unsubscribe).await;
+        assert_eq!(other.receive().await["result"], false);
+        client.send(&unsubscribe).await;
+        assert_eq!(client.receive().await["result"], true);
+
+        // A malformed stream is rejected and the connection is closed.
+        client.send("x").await;
+        assert_eq!(
+            client.receive().await["error"]["code"],
+            ErrorCode::ParseError.code()
+        );
+        let mut line = String::new();
+        assert_eq!(client.reader.read_line(&mut line).await.unwrap(), 0);
+
+        handle.stop().unwrap();
+        handle.stopped().await;
+    }
+
+    #[test]
+    fn test_json_splitter() {
+        let mut splitter = JsonSplitter::default();
+        splitter.extend(br#" {"a":"}\"{"} [1,"#);
+        assert_eq!(
+            splitter.next_value().unwrap().unwrap(),
+            br#" {"a":"}\"{"}"#
+        );
+        assert_eq!(splitter.next_value(), Ok(None));
+        assert_eq!(splitter.pending_len(), 4);
+        splitter.extend(b"[2]]\n");
+        assert_eq!(splitter.next_value().unwrap().unwrap(), b" [1,[2]]");
+        assert_eq!(splitter.next_value(), Ok(None));
+
+        splitter.extend(b"1");
+        assert_eq!(splitter.next_value(), Err(()));
+    }
+}
@@ -2,6 +2,7 @@ mod cfx;
 mod error;
 mod eth;
 mod id_provider;
+mod ipc;
 
 pub use cfx::{
     CfxRpcModule, RpcModuleBuilder as CfxRpcModuleBuilder,
@@ -12,11 +13,12 @@ pub use cfx::{
     TransportRpcModules as CfxTransportRpcModules,
 };
 pub use eth::{
-    RpcModuleBuilder, RpcModuleSelection, RpcServerConfig,
-    TransportRpcModuleConfig,
+    EthRpcModule, RpcModuleBuilder, RpcModuleSelection, RpcServerConfig,
+    TransportRpcModuleConfig, TransportRpcModules,
 };
+pub use ipc::{IpcServerConfig, DEFAULT_IPC_PERMISSIONS};
 
-use std::net::SocketAddr;
+use std::{net::SocketAddr, path::PathBuf};
 
 use jsonrpsee::server::{AlreadyStoppedError, ServerHandle};
 
@@ -27,6 +29,8 @@ pub struct RpcServerHandle {
     pub ws_local_addr: Option<SocketAddr>,
     pub http: Option<ServerHandle>,
     pub ws: Option<ServerHandle>,
+    pub ipc_endpoint: Option<PathBuf>,
+    pub ipc: Option<ServerHandle>,
 }
 
 impl RpcServerHandle {
@@ -38,6 +42,10 @@ impl RpcServerHandle {
         self.ws_local_addr
     }
 
+    pub const fn ipc_endpoint(&self) -> Option<&PathBuf> {
+        self.ipc_endpoint.as_ref()
+    }
+
     pub fn stop(self) -> Result<(), AlreadyStoppedError> {
         if let Some(handle) = self.http {
             handle.stop()?
@@ -47,6 +55,10 @@ impl RpcServerHandle {
             handle.stop()?
         }
 
+        if let Some(handle) = self.ipc {
+            handle.stop()?
+        }
+
         Ok(())
     }
 
@@ -5,6 +5,7 @@
 use cfx_rpc_cfx_types::{
     AccountPendingInfo, AccountPendingTransactions, BlockHashOrEpochNumber,
     Bytes as RpcBytes, Receipt as RpcReceipt, RpcAddress, TransactionRequest,
+    TypedData,
 };
 use cfx_types::{H256, H520, U128, U256, U64};
 use jsonrpsee::{core::RpcResult as JsonRpcResult, proc_macros::rpc};
@@ -39,6 +40,12 @@ pub trait CfxDebugRpc {
         &self, data: RpcBytes, address: RpcAddress, password: Option<String>,
     ) -> JsonRpcResult<H520>;
 
+    /// Signs CIP-23 typed structured data.
+    #[method(name = "signTypedData")]
+    fn sign_typed_data(
+        &self, data: TypedData, address: RpcAddress, password: Option<String>,
+    ) -> JsonRpcResult<H520>;
+
     #[method(name = "signTransaction")]
     fn sign_transaction(
         &self, tx: TransactionRequest, password: Option<String>,
@@ -9,7 +9,7 @@ use cfx_rpc_cfx_types::{
     EstimateGasAndCollateralResponse, Log as RpcLog, PoSEconomics,
     Receipt as RpcReceipt, RewardInfo as RpcRewardInfo, RpcAddress,
     SponsorInfo, Status as RpcStatus, StorageCollateralInfo, TokenSupplyInfo,
-    Transaction, TransactionRequest, VoteParamsInfo,
+    Transaction, TransactionRequest, VoteParamsInfo, Work,
 };
 use cfx_rpc_primitives::U64 as HexU64;
 use cfx_types::{H256, U256, U64};
@@ -293,6 +293,18 @@ pub trait CfxRpc {
         &self, epoch_number: Option<EpochNumber>,
     ) -> JsonRpcResult<VoteParamsInfo>;
 
+    /// Returns the work package of the block being mined:
+    /// `[pow_hash, seed_hash, boundary, block_height]`.
+    #[method(name = "getWork")]
+    async fn get_work(&self) -> JsonRpcResult<Work>;
+
+    /// Submits a proof-of-work solution for a work package returned by
+    /// `cfx_getWork`. Solutions for replaced block templates are rejected.
+    #[method(name = "submitWork")]
+    async fn submit_work(
+        &self, nonce: U256, pow_hash: H256,
+    ) -> JsonRpcResult<bool>;
+
     //        /// Returns transaction at given block hash and index.
     //        #[method(name = "getTransactionByBlockHashAndIndex")]
     //        fn transaction_by_block_hash_and_index(&self, H256, Index) ->
@@ -1,5 +1,6 @@
 mod cfx;
 mod debug;
+mod net;
 mod pos;
 mod pubsub;
 mod test;
@@ -8,6 +9,7 @@ mod txpool;
 
 pub use cfx::*;
 pub use debug::*;
+pub use net::*;
 pub use pos::*;
 pub use pubsub::*;
 pub use test::*;
@@ -0,0 +1,26 @@
+// Copyright 2026 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use jsonrpsee::{core::RpcResult as JsonRpcResult, proc_macros::rpc};
+use network::{node_table::NodeId, PeerScore};
+
+/// Management of the peers by their reputation scores.
+#[rpc(server, namespace = "net")]
+pub trait NetRpc {
+    /// Returns the reputation scores of the peers that misbehaved or are
+    /// banned, sorted from the lowest.
+    #[method(name = "peerScores")]
+    fn peer_scores(&self) -> JsonRpcResult<Vec<PeerScore>>;
+
+    /// Bans the peer for `duration` seconds, or the configured ban duration
+    /// if not specified, and disconnects it.
+    #[method(name = "ban")]
+    fn ban(
+        &self, node_id: NodeId, duration: Option<u64>,
+    ) -> JsonRpcResult<bool>;
+
+    /// Lifts the ban of the peer. Returns `false` if the peer is not banned.
+    #[method(name = "unban")]
+    fn unban(&self, node_id: NodeId) -> JsonRpcResult<bool>;
+}
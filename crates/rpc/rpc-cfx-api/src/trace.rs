@@ -2,6 +2,9 @@
 // Conflux is free software and distributed under GNU General Public License.
 // See http://www.gnu.org/licenses/
 
+use alloy_rpc_types_trace::geth::{
+    GethDebugTracingOptions, GethTrace, TraceResult,
+};
 use cfx_rpc_cfx_types::{
     EpochNumber, LocalizedBlockTrace, LocalizedTrace, TraceFilter,
 };
@@ -34,4 +37,25 @@ pub trait Trace {
     #[method(name = "epoch")]
     fn epoch_traces(&self, epoch: EpochNumber)
         -> RpcResult<Option<EpochTrace>>;
+
+    /// Re-executes the transaction with a geth tracer, e.g. a javascript
+    /// tracer.
+    #[method(name = "gethTransaction")]
+    fn geth_transaction_trace(
+        &self, tx_hash: H256, opts: Option<GethDebugTracingOptions>,
+    ) -> RpcResult<GethTrace>;
+
+    /// Re-executes the Core Space transactions of the given block with a geth
+    /// tracer.
+    #[method(name = "gethBlock")]
+    fn geth_block_traces(
+        &self, block_hash: H256, opts: Option<GethDebugTracingOptions>,
+    ) -> RpcResult<Vec<TraceResult>>;
+
+    /// Re-executes the Core Space transactions of the given epoch with a geth
+    /// tracer.
+    #[method(name = "gethEpoch")]
+    fn geth_epoch_traces(
+        &self, epoch: EpochNumber, opts: Option<GethDebugTracingOptions>,
+    ) -> RpcResult<Vec<TraceResult>>;
 }
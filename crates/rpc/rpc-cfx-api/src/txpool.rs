@@ -46,4 +46,9 @@ pub trait TxPool {
         &self, address: RpcAddress, maybe_start_nonce: Option<U256>,
         maybe_limit: Option<U64>,
     ) -> JsonRpcResult<AccountPendingTransactions>;
+
+    /// Rewrite the transaction journal with the transactions in the pool and
+    /// return the number of journaled transactions
+    #[method(name = "rotateJournal")]
+    fn txpool_rotate_journal(&self) -> JsonRpcResult<U64>;
 }
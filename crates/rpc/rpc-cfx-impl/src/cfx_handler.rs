@@ -36,7 +36,8 @@ use cfx_rpc_cfx_types::{
     EstimateGasAndCollateralResponse, Log as RpcLog, PoSEconomics,
     RewardInfo as RpcRewardInfo, RpcAddress, RpcImplConfiguration, SponsorInfo,
     Status as RpcStatus, StorageCollateralInfo, TokenSupplyInfo,
-    Transaction as RpcTransaction, TransactionRequest, VoteParamsInfo,
+    Transaction as RpcTransaction, TransactionRequest, TypedData,
+    VoteParamsInfo, Work,
 };
 use cfx_rpc_eth_types::FeeHistory;
 use cfx_rpc_primitives::U64 as HexU64;
@@ -89,8 +90,9 @@ use rustc_hex::ToHex;
 use storage_interface::DBReaderForPoW;
 
 use crate::{
-    eth_data_hash, helpers::build_block,
-    pos_handler::convert_to_pos_epoch_reward,
+    eth_data_hash, get_remote_work, helpers::build_block,
+    pos_handler::convert_to_pos_epoch_reward, sign_cip23_typed_data,
+    submit_remote_work,
 };
 
 fn into_rpc_err<E>(e: E) -> ErrorObjectOwned
@@ -416,7 +418,7 @@ impl CfxHandler {
             bail!(request_rejected_in_catch_up_mode(None));
         }
         let (signed_trans, failed_trans) =
-            self.tx_pool.insert_new_transactions(vec![tx]);
+            self.tx_pool.insert_local_transactions(vec![tx]);
 
         match (signed_trans.len(), failed_trans.len()) {
             (0, 0) => {
@@ -496,75 +498,11 @@ impl CfxHandler {
     ) -> CoreResult<EstimateGasAndCollateralResponse> {
         let (execution_outcome, estimation) =
             self.exec_transaction(request, epoch)?;
-        match execution_outcome {
-            ExecutionOutcome::NotExecutedDrop(TxDropError::OldNonce(
-                expected,
-                got,
-            )) => bail!(call_execution_error(
-                "Can not estimate: transaction can not be executed".into(),
-                format! {"nonce is too old expected {:?} got {:?}", expected, got}
-            )),
-            ExecutionOutcome::NotExecutedDrop(
-                TxDropError::InvalidRecipientAddress(recipient),
-            ) => bail!(call_execution_error(
-                "Can not estimate: transaction can not be executed".into(),
-                format! {"invalid recipient address {:?}", recipient}
-            )),
-            ExecutionOutcome::NotExecutedDrop(TxDropError::SenderWithCode(
-                address,
-            )) => bail!(call_execution_error(
-                "Can not estimate: transaction sender has code".into(),
-                format! {"transaction sender has code {:?}", address}
-            )),
-            ExecutionOutcome::NotExecutedToReconsiderPacking(e) => {
-                bail!(call_execution_error(
-                    "Can not estimate: transaction can not be executed".into(),
-                    format! {"{:?}", e}
-                ))
-            }
-            ExecutionOutcome::NotExecutedDrop(
-                TxDropError::NotEnoughGasLimit { expected, got },
-            ) => bail!(call_execution_error(
-                "Can not estimate: transaction can not be executed".into(),
-                format! {"not enough gas limit with respected to tx size: expected {:?} got {:?}", expected, got}
-            )),
-            ExecutionOutcome::ExecutionErrorBumpNonce(
-                ExecutionError::VmError(VmError::Reverted),
-                executed,
-            ) => {
-                let (revert_error, innermost_error, errors) =
-                    decode_error(&executed, |addr| {
-                        RpcAddress::try_from_h160(
-                            addr.clone(),
-                            self.network_type,
-                        )
-                        .unwrap()
-                        .base32_address
-                    });
-                bail!(call_execution_error(
-                    format!("Estimation isn't accurate: transaction is reverted{}{}",
-                        revert_error, innermost_error),
-                    errors.join("\n"),
-                ))
-            }
-            ExecutionOutcome::ExecutionErrorBumpNonce(e, _) => {
-                bail!(call_execution_error(
-                    format! {"Can not estimate: transaction execution failed, \
-                    all gas will be charged (execution error: {:?})", e}
-                    .into(),
-                    format! {"{:?}", e}
-                ))
-            }
-            ExecutionOutcome::Finished(_) => {}
-        };
-        let storage_collateralized =
-            U64::from(estimation.estimated_storage_limit);
-        let estimated_gas_used = estimation.estimated_gas_limit;
-        Ok(EstimateGasAndCollateralResponse {
-            gas_limit: estimated_gas_used,
-            gas_used: estimated_gas_used,
-            storage_collateralized,
-        })
+        estimate_gas_and_collateral_response(
+            execution_outcome,
+            estimation,
+            self.network_type,
+        )
     }
 
     fn check_response_size<T: serde::Serialize>(
@@ -1089,53 +1027,7 @@ impl CfxRpcServer for CfxHandler {
         );
         let (execution_outcome, _estimation) =
             self.exec_transaction(tx, epoch).map_err(into_rpc_err)?;
-        match execution_outcome {
-            ExecutionOutcome::NotExecutedDrop(TxDropError::OldNonce(
-                expected,
-                got,
-            )) => Err(call_execution_error(
-                "Transaction can not be executed".into(),
-                format! {"nonce is too old expected {:?} got {:?}", expected, got},
-            )),
-            ExecutionOutcome::NotExecutedDrop(
-                TxDropError::InvalidRecipientAddress(recipient),
-            ) => Err(call_execution_error(
-                "Transaction can not be executed".into(),
-                format! {"invalid recipient address {:?}", recipient},
-            )),
-            ExecutionOutcome::NotExecutedDrop(
-                TxDropError::NotEnoughGasLimit { expected, got },
-            ) => Err(call_execution_error(
-                "Transaction can not be executed".into(),
-                format! {"not enough gas limit with respected to tx size: expected {:?} got {:?}", expected, got},
-            )),
-            ExecutionOutcome::NotExecutedDrop(TxDropError::SenderWithCode(
-                address,
-            )) => Err(call_execution_error(
-                "Transaction can not be executed".into(),
-                format! {"tx sender has contract code: {:?}", address},
-            )),
-            ExecutionOutcome::NotExecutedToReconsiderPacking(e) => {
-                Err(call_execution_error(
-                    "Transaction can not be executed".into(),
-                    format! {"{:?}", e},
-                ))
-            }
-            ExecutionOutcome::ExecutionErrorBumpNonce(
-                ExecutionError::VmError(VmError::Reverted),
-                executed,
-            ) => Err(call_execution_error(
-                "Transaction reverted".into(),
-                format!("0x{}", executed.output.to_hex::<String>()),
-            )),
-            ExecutionOutcome::ExecutionErrorBumpNonce(e, _) => {
-                Err(call_execution_error(
-                    "Transaction execution failed".into(),
-                    format! {"{:?}", e},
-                ))
-            }
-            ExecutionOutcome::Finished(executed) => Ok(executed.output.into()),
-        }
+        call_response(execution_outcome)
     }
 
     async fn get_logs(
@@ -1870,6 +1762,156 @@ impl CfxRpcServer for CfxHandler {
             base_fee_share_prop,
         })
     }
+
+    async fn get_work(&self) -> RpcResult<Work> {
+        info!("RPC Request: cfx_getWork()");
+        get_remote_work(Some(&self.block_gen.remote_work()))
+    }
+
+    async fn submit_work(
+        &self, nonce: U256, pow_hash: H256,
+    ) -> RpcResult<bool> {
+        info!(
+            "RPC Request: cfx_submitWork nonce={:?} pow_hash={:?}",
+            nonce, pow_hash
+        );
+        submit_remote_work(
+            Some(&self.block_gen.remote_work()),
+            pow_hash,
+            nonce,
+        )?;
+        Ok(true)
+    }
+}
+
+/// Converts the outcome of the execution into the response of `cfx_call`.
+pub(crate) fn call_response(
+    execution_outcome: ExecutionOutcome,
+) -> RpcResult<Bytes> {
+    match execution_outcome {
+        ExecutionOutcome::NotExecutedDrop(TxDropError::OldNonce(
+            expected,
+            got,
+        )) => Err(call_execution_error(
+            "Transaction can not be executed".into(),
+            format! {"nonce is too old expected {:?} got {:?}", expected, got},
+        )),
+        ExecutionOutcome::NotExecutedDrop(
+            TxDropError::InvalidRecipientAddress(recipient),
+        ) => Err(call_execution_error(
+            "Transaction can not be executed".into(),
+            format! {"invalid recipient address {:?}", recipient},
+        )),
+        ExecutionOutcome::NotExecutedDrop(TxDropError::NotEnoughGasLimit {
+            expected,
+            got,
+        }) => Err(call_execution_error(
+            "Transaction can not be executed".into(),
+            format! {"not enough gas limit with respected to tx size: expected {:?} got {:?}", expected, got},
+        )),
+        ExecutionOutcome::NotExecutedDrop(TxDropError::SenderWithCode(
+            address,
+        )) => Err(call_execution_error(
+            "Transaction can not be executed".into(),
+            format! {"tx sender has contract code: {:?}", address},
+        )),
+        ExecutionOutcome::NotExecutedToReconsiderPacking(e) => {
+            Err(call_execution_error(
+                "Transaction can not be executed".into(),
+                format! {"{:?}", e},
+            ))
+        }
+        ExecutionOutcome::ExecutionErrorBumpNonce(
+            ExecutionError::VmError(VmError::Reverted),
+            executed,
+        ) => Err(call_execution_error(
+            "Transaction reverted".into(),
+            format!("0x{}", executed.output.to_hex::<String>()),
+        )),
+        ExecutionOutcome::ExecutionErrorBumpNonce(e, _) => {
+            Err(call_execution_error(
+                "Transaction execution failed".into(),
+                format! {"{:?}", e},
+            ))
+        }
+        ExecutionOutcome::Finished(executed) => Ok(executed.output.into()),
+    }
+}
+
+/// Converts the outcome of the execution into the response of
+/// `cfx_estimateGasAndCollateral`.
+pub(crate) fn estimate_gas_and_collateral_response(
+    execution_outcome: ExecutionOutcome, estimation: EstimateExt,
+    network: Network,
+) -> CoreResult<EstimateGasAndCollateralResponse> {
+    match execution_outcome {
+        ExecutionOutcome::NotExecutedDrop(TxDropError::OldNonce(
+            expected,
+            got,
+        )) => bail!(call_execution_error(
+            "Can not estimate: transaction can not be executed".into(),
+            format! {"nonce is too old expected {:?} got {:?}", expected, got}
+        )),
+        ExecutionOutcome::NotExecutedDrop(
+            TxDropError::InvalidRecipientAddress(recipient),
+        ) => bail!(call_execution_error(
+            "Can not estimate: transaction can not be executed".into(),
+            format! {"invalid recipient address {:?}", recipient}
+        )),
+        ExecutionOutcome::NotExecutedDrop(TxDropError::SenderWithCode(
+            address,
+        )) => bail!(call_execution_error(
+            "Can not estimate: transaction sender has code".into(),
+            format! {"transaction sender has code {:?}", address}
+        )),
+        ExecutionOutcome::NotExecutedToReconsiderPacking(e) => {
+            bail!(call_execution_error(
+                "Can not estimate: transaction can not be executed".into(),
+                format! {"{:?}", e}
+            ))
+        }
+        ExecutionOutcome::NotExecutedDrop(TxDropError::NotEnoughGasLimit {
+            expected,
+            got,
+        }) => bail!(call_execution_error(
+            "Can not estimate: transaction can not be executed".into(),
+            format! {"not enough gas limit with respected to tx size: expected {:?} got {:?}", expected, got}
+        )),
+        ExecutionOutcome::ExecutionErrorBumpNonce(
+            ExecutionError::VmError(VmError::Reverted),
+            executed,
+        ) => {
+            let (revert_error, innermost_error, errors) =
+                decode_error(&executed, |addr| {
+                    RpcAddress::try_from_h160(addr.clone(), network)
+                        .unwrap()
+                        .base32_address
+                });
+            bail!(call_execution_error(
+                format!(
+                    "Estimation isn't accurate: transaction is reverted{}{}",
+                    revert_error, innermost_error
+                ),
+                errors.join("\n"),
+            ))
+        }
+        ExecutionOutcome::ExecutionErrorBumpNonce(e, _) => {
+            bail!(call_execution_error(
+                format! {"Can not estimate: transaction execution failed, \
+                all gas will be charged (execution error: {:?})", e}
+                .into(),
+                format! {"{:?}", e}
+            ))
+        }
+        ExecutionOutcome::Finished(_) => {}
+    };
+    let storage_collateralized = U64::from(estimation.estimated_storage_limit);
+    let estimated_gas_used = estimation.estimated_gas_limit;
+    Ok(EstimateGasAndCollateralResponse {
+        gas_limit: estimated_gas_used,
+        gas_used: estimated_gas_used,
+        storage_collateralized,
+    })
 }
 
 /// Check balance against transaction helper
@@ -2051,6 +2093,19 @@ impl CfxDebugRpcServer for CfxHandler {
         Ok(H520(signature.into()))
     }
 
+    fn sign_typed_data(
+        &self, data: TypedData, address: RpcAddress, password: Option<String>,
+    ) -> RpcResult<H520> {
+        self.check_address_network(address.network)?;
+        sign_cip23_typed_data(
+            &self.accounts,
+            &data,
+            address.into(),
+            password,
+            self.consensus.best_chain_id().in_native_space(),
+        )
+    }
+
     fn sign_transaction(
         &self, tx: TransactionRequest, password: Option<String>,
     ) -> RpcResult<String> {
@@ -23,7 +23,7 @@ use cfx_rpc_cfx_types::{
     AccountPendingInfo, AccountPendingTransactions, Block as RpcBlock,
     BlockHashOrEpochNumber, Bytes, CfxFeeHistory, EpochNumber, RpcAddress,
     Status as RpcStatus, Transaction as RpcTransaction,
-    TxPoolPendingNonceRange, TxPoolStatus, TxWithPoolInfo,
+    TxPoolPendingNonceRange, TxPoolStatus, TxWithPoolInfo, TypedData,
 };
 use cfx_rpc_eth_types::FeeHistory;
 use cfx_rpc_primitives::U64 as HexU64;
@@ -65,6 +65,7 @@ use crate::{
     eth_data_hash, hash_value_to_h256,
     helpers::{build_block, MAX_FEE_HISTORY_CACHE_BLOCK_COUNT},
     pos_handler::convert_to_pos_epoch_reward,
+    sign_cip23_typed_data,
 };
 
 fn into_rpc_err<E>(e: E) -> ErrorObjectOwned
@@ -717,12 +718,16 @@ impl CommonRpcImpl {
     pub fn txpool_status(&self) -> RpcResult<TxPoolStatus> {
         let (ready_len, deferred_len, received_len, unexecuted_len) =
             self.tx_pool.stats();
+        let (local_pending, local_queued) =
+            self.tx_pool.total_local_pending_and_queued(None);
 
         Ok(TxPoolStatus {
             deferred: U64::from(deferred_len),
             ready: U64::from(ready_len),
             received: U64::from(received_len),
             unexecuted: U64::from(unexecuted_len),
+            local_pending: U64::from(local_pending),
+            local_queued: U64::from(local_queued),
         })
     }
 
@@ -837,6 +842,20 @@ impl CommonRpcImpl {
         Ok(H520(signature.into()))
     }
 
+    pub fn sign_typed_data(
+        &self, data: TypedData, address: RpcAddress, password: Option<String>,
+    ) -> RpcResult<H520> {
+        self.check_address_network(address.network)
+            .map_err(into_rpc_err)?;
+        sign_cip23_typed_data(
+            &self.accounts,
+            &data,
+            address.into(),
+            password,
+            self.consensus.best_chain_id().in_native_space(),
+        )
+    }
+
     pub fn save_node_db(&self) -> RpcResult<()> {
         self.network.save_node_db();
         Ok(())
@@ -1169,16 +1188,22 @@ impl CommonRpcImpl {
         &self, voting_power: U64, version: Option<u8>,
     ) -> RpcResult<(Bytes, AccountAddress)> {
         let legacy = version.map_or(false, |x| x == 0);
+        let (bls_key, vrf_key) =
+            self.pos_handler.config().pos_keys.as_ref().ok_or_else(|| {
+                internal_error_with_data(
+                    "The PoS keys are kept by the SafetyRules process",
+                )
+            })?;
         let tx = register_transaction(
-            self.pos_handler.config().bls_key.private_key(),
-            self.pos_handler.config().vrf_key.public_key(),
+            bls_key.private_key(),
+            vrf_key.public_key(),
             voting_power.as_u64(),
             0,
             legacy,
         );
         let identifier = from_consensus_public_key(
-            &self.pos_handler.config().bls_key.public_key(),
-            &self.pos_handler.config().vrf_key.public_key(),
+            &bls_key.public_key(),
+            &vrf_key.public_key(),
         );
         Ok((tx.data.into(), identifier))
     }
@@ -3,6 +3,7 @@ mod epoch_queue;
 pub mod poll_filter;
 pub mod poll_manager;
 pub mod subscribers;
+mod txpool_event;
 
 pub use block_provider::{build_block, build_header};
 pub use epoch_queue::EpochQueue;
@@ -10,5 +11,6 @@ pub use poll_filter::{
     limit_logs, PollFilter, SyncPollFilter, MAX_BLOCK_HISTORY_SIZE,
 };
 pub use poll_manager::PollManager;
+pub use txpool_event::rpc_txpool_event;
 
 pub const MAX_FEE_HISTORY_CACHE_BLOCK_COUNT: u64 = 1024;
@@ -0,0 +1,29 @@
+use cfx_rpc_cfx_types::{
+    TxPoolEvent as RpcTxPoolEvent, TxPoolEventKind as RpcTxPoolEventKind,
+};
+use cfxcore::transaction_pool::TxPoolEvent;
+
+/// Converts a transaction pool event to its RPC representation, with the
+/// sender formatted as `from`.
+pub fn rpc_txpool_event<A>(event: &TxPoolEvent, from: A) -> RpcTxPoolEvent<A> {
+    let (kind, replaced_by, reason) = match event {
+        TxPoolEvent::Added(_) => (RpcTxPoolEventKind::Added, None, None),
+        TxPoolEvent::Replaced { new, .. } => {
+            (RpcTxPoolEventKind::Replaced, Some(new.hash()), None)
+        }
+        TxPoolEvent::Dropped { reason, .. } => {
+            (RpcTxPoolEventKind::Dropped, None, Some(reason.to_string()))
+        }
+        TxPoolEvent::Packed(_) => (RpcTxPoolEventKind::Packed, None, None),
+        TxPoolEvent::Ready(_) => (RpcTxPoolEventKind::Ready, None, None),
+    };
+    let tx = event.transaction();
+    RpcTxPoolEvent {
+        kind,
+        hash: tx.hash(),
+        from,
+        nonce: *tx.nonce(),
+        replaced_by,
+        reason,
+    }
+}
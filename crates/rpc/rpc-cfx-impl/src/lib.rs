@@ -4,6 +4,7 @@ pub mod common;
 mod debug_handler;
 pub mod helpers;
 pub mod light;
+mod net_handler;
 mod pos_handler;
 mod pubsub;
 mod test_handler;
@@ -13,6 +14,7 @@ mod txpool_handler;
 pub use cfx_filter_handler::{CfxFilterHandler, UnfinalizedEpochs};
 pub use cfx_handler::{check_balance_against_transaction, CfxHandler};
 pub use debug_handler::DebugHandler;
+pub use net_handler::NetHandler;
 pub use pos_handler::{
     convert_to_pos_epoch_reward, hash_value_to_h256, PosHandler,
 };
@@ -21,8 +23,18 @@ pub use test_handler::TestHandler;
 pub use trace_handler::TraceHandler;
 pub use txpool_handler::TxPoolHandler;
 
-use cfx_types::H256;
+use blockgen::{RemoteWork, WorkError};
+use cfx_rpc_cfx_types::{TypedData, Work};
+use cfx_rpc_utils::error::jsonrpsee_error_helpers::{
+    cannot_submit_work, internal_error, invalid_params, no_work,
+};
+use cfx_types::{Address, BigEndianHash, H256, H520, U256};
+use cfxcore::pow::seed_hash;
+use cfxcore_accounts::{AccountProvider, SignError, TypedDataKind};
+use cfxkey::Password;
+use jsonrpsee::types::ErrorObjectOwned;
 use keccak_hash::keccak;
+use log::warn;
 
 /// Returns a eth_sign-compatible hash of data to sign.
 pub fn eth_data_hash(mut data: Vec<u8>) -> H256 {
@@ -31,3 +43,70 @@ pub fn eth_data_hash(mut data: Vec<u8>) -> H256 {
     message_data.append(&mut data);
     keccak(message_data)
 }
+
+/// Signs CIP-23 typed data with a local account. The domain must be bound to
+/// the Core Space chain `chain_id`.
+pub fn sign_cip23_typed_data(
+    accounts: &AccountProvider, data: &TypedData, address: Address,
+    password: Option<String>, chain_id: u32,
+) -> Result<H520, ErrorObjectOwned> {
+    match data.kind() {
+        Ok(TypedDataKind::Cip23) => {}
+        _ => {
+            return Err(invalid_params(
+                "data",
+                Some("expect CIP23Domain typed data"),
+            ))
+        }
+    }
+    match data.chain_id() {
+        Ok(Some(id)) if id == U256::from(chain_id) => {}
+        _ => {
+            return Err(invalid_params(
+                "data",
+                Some(format!("domain chainId must be {}", chain_id)),
+            ))
+        }
+    }
+
+    let password = password.map(Password::from);
+    match accounts.sign_typed_data(address, password, data) {
+        Ok(signature) => Ok(H520(signature.into())),
+        Err(SignError::InvalidTypedData(e)) => {
+            Err(invalid_params("data", Some(format!("{}", e))))
+        }
+        Err(err) => {
+            warn!("Unable to sign the typed data. With error {:?}", err);
+            Err(internal_error())
+        }
+    }
+}
+
+/// Returns the work package of the mining session for the remote miners.
+pub fn get_remote_work(
+    remote_work: Option<&RemoteWork>,
+) -> Result<Work, ErrorObjectOwned> {
+    let remote_work =
+        remote_work.ok_or_else(|| no_work("The node does not mine".into()))?;
+    let problem = remote_work.get_work().map_err(|e| no_work(e.to_string()))?;
+    Ok(Work {
+        pow_hash: problem.block_hash,
+        seed_hash: seed_hash(problem.block_height),
+        boundary: H256::from_uint(&problem.boundary),
+        block_height: problem.block_height.into(),
+    })
+}
+
+/// Submits the solution of a remote miner to the mining session.
+pub fn submit_remote_work(
+    remote_work: Option<&RemoteWork>, pow_hash: H256, nonce: U256,
+) -> Result<(), ErrorObjectOwned> {
+    let remote_work =
+        remote_work.ok_or_else(|| no_work("The node does not mine".into()))?;
+    remote_work
+        .submit_work(pow_hash, nonce)
+        .map_err(|e| match e {
+            WorkError::NoWork => no_work(e.to_string()),
+            _ => cannot_submit_work(e.to_string()),
+        })
+}
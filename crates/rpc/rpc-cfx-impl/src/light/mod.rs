@@ -5,12 +5,14 @@
 use std::{collections::BTreeMap, net::SocketAddr, sync::Arc};
 
 use cfx_addr::Network;
+use cfx_execute_helper::estimation::{EstimateExt, EstimateRequest};
+use cfx_executor::executive::ExecutionOutcome;
 use cfx_parameters::rpc::GAS_PRICE_DEFAULT_VALUE;
 use cfx_rpc_cfx_api::{
     CfxDebugRpcServer, CfxRpcServer, DebugRpcServer, TestRpcServer,
 };
 use cfx_rpc_cfx_types::{
-    address::check_rpc_address_network,
+    address::{check_rpc_address_network, check_two_rpc_address_network_match},
     pos::{Block as PosBlock, PoSEpochReward},
     receipt::Receipt as RpcReceipt,
     Account as RpcAccount, AccountPendingInfo, AccountPendingTransactions,
@@ -18,10 +20,10 @@ use cfx_rpc_cfx_types::{
     Bytes as RpcBytes, CfxFeeHistory, CfxRpcLogFilter,
     CheckBalanceAgainstTransactionResponse, ConsensusGraphStates, EpochNumber,
     EstimateGasAndCollateralResponse, Log as RpcLog, PoSEconomics,
-    RewardInfo as RpcRewardInfo, RpcAddress, SponsorInfo, StatOnGasLoad,
-    Status as RpcStatus, StorageCollateralInfo, SyncGraphStates,
+    RewardInfo as RpcRewardInfo, RpcAddress, RpcImplConfiguration, SponsorInfo,
+    StatOnGasLoad, Status as RpcStatus, StorageCollateralInfo, SyncGraphStates,
     TokenSupplyInfo, Transaction as RpcTransaction, TransactionRequest,
-    VoteParamsInfo,
+    TypedData, VoteParamsInfo, Work,
 };
 use cfx_rpc_eth_types::{FeeHistory, WrapTransaction};
 use cfx_rpc_primitives::U64 as HexU64;
@@ -52,14 +54,18 @@ use network::{
     throttling, PeerInfo, SessionDetails, UpdateNodeOperation,
 };
 use primitives::{
-    Account, DepositInfo, StorageRoot, TransactionWithSignature, VoteStakeInfo,
+    Account, DepositInfo, EpochNumber as PrimitiveEpochNumber, StorageRoot,
+    TransactionWithSignature, VoteStakeInfo,
 };
 use rlp::Encodable;
 
 use crate::{
+    cfx_handler::{call_response, estimate_gas_and_collateral_response},
     check_balance_against_transaction,
     common::CommonRpcImpl,
+    get_remote_work,
     helpers::{build_block, MAX_FEE_HISTORY_CACHE_BLOCK_COUNT},
+    submit_remote_work,
 };
 
 fn into_rpc_err<E>(e: E) -> ErrorObjectOwned
@@ -72,6 +78,8 @@ fn not_supported() -> ErrorObjectOwned {
 }
 
 pub struct RpcImpl {
+    config: RpcImplConfiguration,
+
     // account provider used for signing transactions
     accounts: Arc<AccountProvider>,
 
@@ -87,10 +95,12 @@ pub struct RpcImpl {
 
 impl RpcImpl {
     pub fn new(
-        light: Arc<LightQueryService>, accounts: Arc<AccountProvider>,
-        consensus: SharedConsensusGraph, data_man: Arc<BlockDataManager>,
+        config: RpcImplConfiguration, light: Arc<LightQueryService>,
+        accounts: Arc<AccountProvider>, consensus: SharedConsensusGraph,
+        data_man: Arc<BlockDataManager>,
     ) -> Self {
         RpcImpl {
+            config,
             accounts,
             consensus,
             data_man,
@@ -950,6 +960,99 @@ impl RpcImpl {
         ))
     }
 
+    /// Executes the call on top of the state of `epoch`, see
+    /// `QueryService::call_virtual`.
+    async fn exec_transaction(
+        &self, request: TransactionRequest, epoch: EpochNumber,
+    ) -> RpcResult<(ExecutionOutcome, EstimateExt)> {
+        let rpc_request_network = invalid_params_check(
+            "request",
+            check_two_rpc_address_network_match(
+                request.from.as_ref(),
+                request.to.as_ref(),
+            ),
+        )?;
+        invalid_params_check(
+            "request",
+            check_rpc_address_network(
+                rpc_request_network,
+                self.light.get_network_type(),
+            ),
+        )?;
+
+        let estimate_request = EstimateRequest {
+            has_sender: request.from.is_some(),
+            has_gas_limit: request.gas.is_some(),
+            has_gas_price: request.has_gas_price(),
+            has_nonce: request.nonce.is_some(),
+            has_storage_limit: request.storage_limit.is_some(),
+            collect_access_list: false,
+        };
+
+        let epoch_height = invalid_params_check(
+            "epoch",
+            self.light.get_height_from_epoch_number(epoch.into()),
+        )?;
+        let chain_id = self
+            .consensus
+            .config()
+            .chain_id
+            .read()
+            .get_chain_id(epoch_height);
+        let signed_tx = request.sign_call(
+            epoch_height,
+            chain_id.in_native_space(),
+            self.config.max_estimation_gas_limit,
+        )?;
+
+        self.light
+            .call_virtual(
+                signed_tx,
+                PrimitiveEpochNumber::Number(epoch_height),
+                estimate_request,
+                Default::default(),
+            )
+            .await
+            .map_err(into_rpc_err)
+    }
+
+    async fn call(
+        &self, request: TransactionRequest,
+        block_hash_or_epoch_number: Option<BlockHashOrEpochNumber>,
+    ) -> RpcResult<Bytes> {
+        info!(
+            "RPC Request: cfx_call request={:?} epoch={:?}",
+            request, block_hash_or_epoch_number
+        );
+
+        let epoch = Self::get_epoch_number_with_pivot_check(
+            self.consensus.clone(),
+            block_hash_or_epoch_number,
+        )?;
+        let (execution_outcome, _estimation) =
+            self.exec_transaction(request, epoch).await?;
+        call_response(execution_outcome)
+    }
+
+    async fn estimate_gas_and_collateral(
+        &self, request: TransactionRequest, epoch: Option<EpochNumber>,
+    ) -> RpcResult<EstimateGasAndCollateralResponse> {
+        info!(
+            "RPC Request: cfx_estimateGasAndCollateral request={:?} epoch={:?}",
+            request, epoch
+        );
+
+        let epoch = epoch.unwrap_or(EpochNumber::LatestState);
+        let (execution_outcome, estimation) =
+            self.exec_transaction(request, epoch).await?;
+        estimate_gas_and_collateral_response(
+            execution_outcome,
+            estimation,
+            *self.light.get_network_type(),
+        )
+        .map_err(into_rpc_err)
+    }
+
     async fn fee_history(
         &self, mut block_count: HexU64, newest_block: EpochNumber,
         reward_percentiles: Option<Vec<f64>>,
@@ -1210,10 +1313,10 @@ impl CfxRpcServer for LightCfxHandler {
 
     /// Call contract, returning the output data.
     async fn call(
-        &self, _tx: TransactionRequest,
-        _block_hash_or_epoch_number: Option<BlockHashOrEpochNumber>,
+        &self, tx: TransactionRequest,
+        block_hash_or_epoch_number: Option<BlockHashOrEpochNumber>,
     ) -> RpcResult<Bytes> {
-        return Err(not_supported());
+        self.rpc_impl.call(tx, block_hash_or_epoch_number).await
     }
 
     /// Returns logs matching the filter provided.
@@ -1232,9 +1335,11 @@ impl CfxRpcServer for LightCfxHandler {
 
     /// Return estimated gas and collateral usage.
     async fn estimate_gas_and_collateral(
-        &self, _request: TransactionRequest, _epoch_number: Option<EpochNumber>,
+        &self, request: TransactionRequest, epoch_number: Option<EpochNumber>,
     ) -> RpcResult<EstimateGasAndCollateralResponse> {
-        return Err(not_supported());
+        self.rpc_impl
+            .estimate_gas_and_collateral(request, epoch_number)
+            .await
     }
 
     async fn fee_history(
@@ -1364,6 +1469,15 @@ impl CfxRpcServer for LightCfxHandler {
         return Err(not_supported());
     }
 
+    async fn get_work(&self) -> RpcResult<Work> { get_remote_work(None) }
+
+    async fn submit_work(
+        &self, nonce: U256, pow_hash: H256,
+    ) -> RpcResult<bool> {
+        submit_remote_work(None, pow_hash, nonce)?;
+        Ok(true)
+    }
+
     /// Returns block with given block number.
     async fn block_by_block_number(
         &self, _block_number: U64, _include_txs: bool,
@@ -1408,6 +1522,12 @@ impl CfxDebugRpcServer for LightCfxHandler {
         self.common_impl.sign(data, address, password)
     }
 
+    fn sign_typed_data(
+        &self, data: TypedData, address: RpcAddress, password: Option<String>,
+    ) -> RpcResult<H520> {
+        self.common_impl.sign_typed_data(data, address, password)
+    }
+
     /// Get transaction pending info by account address
     async fn account_pending_info(
         &self, address: RpcAddress,
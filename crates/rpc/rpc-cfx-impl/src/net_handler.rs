@@ -0,0 +1,32 @@
+// Copyright 2026 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use cfx_rpc_cfx_api::NetRpcServer;
+use jsonrpsee::core::RpcResult;
+use network::{node_table::NodeId, NetworkService, PeerScore};
+use std::{sync::Arc, time::Duration};
+
+pub struct NetHandler {
+    network: Arc<NetworkService>,
+}
+
+impl NetHandler {
+    pub fn new(network: Arc<NetworkService>) -> Self { NetHandler { network } }
+}
+
+impl NetRpcServer for NetHandler {
+    fn peer_scores(&self) -> RpcResult<Vec<PeerScore>> {
+        Ok(self.network.peer_scores().unwrap_or_default())
+    }
+
+    fn ban(&self, node_id: NodeId, duration: Option<u64>) -> RpcResult<bool> {
+        Ok(self
+            .network
+            .ban_node(&node_id, duration.map(Duration::from_secs)))
+    }
+
+    fn unban(&self, node_id: NodeId) -> RpcResult<bool> {
+        Ok(self.network.unban_node(&node_id))
+    }
+}
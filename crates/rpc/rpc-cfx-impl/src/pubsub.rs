@@ -1,5 +1,6 @@
 use crate::helpers::{
-    block_provider::build_header, subscribers::pipe_from_stream, EpochQueue,
+    block_provider::build_header, rpc_txpool_event,
+    subscribers::pipe_from_stream, EpochQueue,
 };
 use cfx_addr::Network;
 use cfx_parameters::{
@@ -9,12 +10,13 @@ use cfx_parameters::{
 use cfx_rpc_cfx_api::PubSubApiServer;
 use cfx_rpc_cfx_types::{
     pubsub::{self, Kind, Params, SubscriptionEpoch},
-    Header, Log,
+    Header, Log, RpcAddress, Transaction,
 };
 use cfx_tasks::TaskExecutor;
 use cfx_types::{Space, H256};
 use cfxcore::{
-    channel::Channel, BlockDataManager, Notifications, SharedConsensusGraph,
+    channel::Channel, transaction_pool::TxPoolEvent, BlockDataManager,
+    Notifications, SharedConsensusGraph,
 };
 use futures::{future::join_all, StreamExt};
 use jsonrpsee::{
@@ -43,6 +45,8 @@ pub struct PubSubHandler {
     log_loop_started: Arc<RwLock<HashMap<LogFilter, bool>>>,
     log_senders:
         Arc<RwLock<HashMap<LogFilter, broadcast::Sender<pubsub::Result>>>>,
+    txpool_loop_started: Arc<RwLock<bool>>,
+    txpool_event_sender: Arc<broadcast::Sender<TxPoolEvent>>,
 }
 
 impl PubSubHandler {
@@ -51,6 +55,8 @@ impl PubSubHandler {
         consensus: SharedConsensusGraph, network: Network,
     ) -> Self {
         let (head_sender, _) = broadcast::channel(BROADCAST_CHANNEL_SIZE);
+        let (txpool_event_sender, _) =
+            broadcast::channel(BROADCAST_CHANNEL_SIZE);
         let handler = Arc::new(ChainNotificationHandler {
             consensus: consensus.clone(),
             data_man: consensus.data_manager().clone(),
@@ -75,6 +81,8 @@ impl PubSubHandler {
             head_sender: Arc::new(head_sender),
             log_loop_started: Arc::new(RwLock::new(HashMap::new())),
             log_senders,
+            txpool_loop_started: Arc::new(RwLock::new(false)),
+            txpool_event_sender: Arc::new(txpool_event_sender),
         }
     }
 
@@ -120,6 +128,13 @@ impl PubSubHandler {
             })
     }
 
+    fn new_txpool_events_stream(&self) -> impl Stream<Item = TxPoolEvent> {
+        let receiver = self.txpool_event_sender.subscribe();
+        BroadcastStream::new(receiver)
+            .filter(|item| futures::future::ready(item.is_ok()))
+            .map(|item| item.expect("should not be an error"))
+    }
+
     fn new_logs_stream(
         &self, filter: LogFilter,
     ) -> impl Stream<Item = pubsub::Result> {
@@ -203,6 +218,36 @@ impl PubSubHandler {
         self.executor.spawn(fut);
     }
 
+    fn start_txpool_events_loop(&self) {
+        let mut loop_started = self.txpool_loop_started.write();
+        if *loop_started {
+            return;
+        }
+        *loop_started = true;
+
+        debug!("async start_txpool_events_loop");
+        let mut receiver = self.notifications.txpool_events.subscribe();
+        let txpool_event_sender = self.txpool_event_sender.clone();
+        let txpool_loop_started = self.txpool_loop_started.clone();
+
+        let fut = async move {
+            while let Some(event) = receiver.recv().await {
+                if event.transaction().space() != Space::Native {
+                    continue;
+                }
+                let send_res = txpool_event_sender.send(event);
+                if send_res.is_err() {
+                    // stop the loop
+                    let mut loop_started = txpool_loop_started.write();
+                    *loop_started = false;
+                    return;
+                }
+            }
+        };
+
+        self.executor.spawn(fut);
+    }
+
     fn start_epoch_loop(&self, epoch: SubscriptionEpoch) {
         let epochs_ordered = self.notifications.epochs_ordered.clone();
         match epoch {
@@ -320,6 +365,68 @@ impl PubSubApiServer for PubSubHandler {
             (Kind::Logs, _) => {
                 return Err("Expected filter parameter.".into());
             }
+            (Kind::NewPendingTransactions, params) => {
+                let full_transactions = match params {
+                    None => false,
+                    Some(Params::FullTransactions(full)) => full,
+                    Some(_) => {
+                        return Err(
+                            "Expected fullTransactions parameter.".into()
+                        );
+                    }
+                };
+                let sink = pending.accept().await?;
+                let network = self.handler.network;
+                let stream =
+                    self.new_txpool_events_stream().filter_map(move |event| {
+                        let result =
+                            event.new_pending_transaction().and_then(|tx| {
+                                if full_transactions {
+                                    Transaction::from_signed(tx, None, network)
+                                        .ok()
+                                        .map(|tx| {
+                                            pubsub::Result::FullTransaction(
+                                                Box::new(tx),
+                                            )
+                                        })
+                                } else {
+                                    Some(pubsub::Result::TransactionHash(
+                                        tx.hash(),
+                                    ))
+                                }
+                            });
+                        futures::future::ready(result)
+                    });
+                self.executor.spawn(async move {
+                    let _ = pipe_from_stream(sink, stream).await;
+                });
+                self.start_txpool_events_loop();
+            }
+            (Kind::TxPoolEvents, None) => {
+                let sink = pending.accept().await?;
+                let network = self.handler.network;
+                let stream =
+                    self.new_txpool_events_stream().filter_map(move |event| {
+                        let result = RpcAddress::try_from_h160(
+                            event.transaction().sender().address,
+                            network,
+                        )
+                        .ok()
+                        .map(|from| {
+                            pubsub::Result::TxPoolEvent(rpc_txpool_event(
+                                &event, from,
+                            ))
+                        });
+                        futures::future::ready(result)
+                    });
+                self.executor.spawn(async move {
+                    let _ = pipe_from_stream(sink, stream).await;
+                });
+                self.start_txpool_events_loop();
+            }
+            (Kind::TxPoolEvents, _) => {
+                return Err("Expected no parameters.".into());
+            }
             _ => {
                 return Err("Unsupported subscription kind.".into());
             }
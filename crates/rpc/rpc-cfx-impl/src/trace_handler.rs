@@ -2,6 +2,9 @@
 // Conflux is free software and distributed under GNU General Public License.
 // See http://www.gnu.org/licenses/
 
+use alloy_rpc_types_trace::geth::{
+    GethDebugTracingOptions, GethTrace, TraceResult,
+};
 use cfx_addr::Network;
 use cfx_execute_helper::exec_tracer::TraceFilter as PrimitiveTraceFilter;
 use cfx_rpc_cfx_api::TraceServer;
@@ -16,6 +19,7 @@ use cfxcore::{
     BlockDataManager, ConsensusGraph, SharedConsensusGraph,
 };
 use cfxcore_errors::ProviderBlockError;
+use geth_tracer::{check_tracing_options, to_alloy_h256, GethTraceWithHash};
 use jsonrpsee::core::RpcResult;
 use log::warn;
 use primitives::EpochNumber;
@@ -215,6 +219,84 @@ impl TraceHandler {
 
         Ok(Some(EpochTrace::new(cfx_traces, eth_traces)))
     }
+
+    pub fn geth_transaction_trace_impl(
+        &self, tx_hash: H256, opts: Option<GethDebugTracingOptions>,
+    ) -> CoreResult<GethTrace> {
+        let opts = opts.unwrap_or_default();
+        check_tracing_options(&opts)?;
+
+        let tx_index = self
+            .data_man
+            .transaction_index_by_hash(&tx_hash, false /* update_cache */)
+            .ok_or("invalid tx hash")?;
+        let epoch_num = self
+            .consensus
+            .get_block_epoch_number(&tx_index.block_hash)
+            .ok_or("invalid tx hash")?;
+
+        let trace = self
+            .consensus_graph()
+            .collect_epoch_geth_trace(epoch_num, Some(tx_hash), opts)?
+            .into_iter()
+            .find(|val| val.tx_hash == tx_hash)
+            .ok_or("trace generation failed")?;
+        Ok(trace.trace?)
+    }
+
+    pub fn geth_block_traces_impl(
+        &self, block_hash: H256, opts: Option<GethDebugTracingOptions>,
+    ) -> CoreResult<Vec<TraceResult>> {
+        let opts = opts.unwrap_or_default();
+        check_tracing_options(&opts)?;
+
+        let block = self
+            .data_man
+            .block_by_hash(&block_hash, false /* update_cache */)
+            .ok_or("invalid block hash")?;
+        let epoch_num = self
+            .consensus
+            .get_block_epoch_number_with_pivot_check(&block_hash, false)?;
+
+        let traces = self
+            .consensus_graph()
+            .collect_epoch_geth_trace(epoch_num, None, opts)?
+            .into_iter()
+            .filter(|val| {
+                block.transactions.iter().any(|tx| tx.hash() == val.tx_hash)
+            });
+        Ok(native_geth_traces(traces))
+    }
+
+    pub fn geth_epoch_traces_impl(
+        &self, epoch: EpochNumber, opts: Option<GethDebugTracingOptions>,
+    ) -> CoreResult<Vec<TraceResult>> {
+        let opts = opts.unwrap_or_default();
+        check_tracing_options(&opts)?;
+
+        let epoch_num = self.consensus.get_height_from_epoch_number(epoch)?;
+        let traces = self
+            .consensus_graph()
+            .collect_epoch_geth_trace(epoch_num, None, opts)?;
+        Ok(native_geth_traces(traces))
+    }
+}
+
+/// Returns the traces of the Core Space transactions.
+fn native_geth_traces(
+    traces: impl IntoIterator<Item = GethTraceWithHash>,
+) -> Vec<TraceResult> {
+    traces
+        .into_iter()
+        .filter(|val| val.space == Space::Native)
+        .map(|val| {
+            let tx_hash = Some(to_alloy_h256(val.tx_hash));
+            match val.trace {
+                Ok(result) => TraceResult::Success { result, tx_hash },
+                Err(error) => TraceResult::Error { error, tx_hash },
+            }
+        })
+        .collect()
 }
 
 impl TraceServer for TraceHandler {
@@ -244,6 +326,27 @@ impl TraceServer for TraceHandler {
         self.epoch_trace_impl(epoch.into_primitive())
             .map_err(Into::into)
     }
+
+    fn geth_transaction_trace(
+        &self, tx_hash: H256, opts: Option<GethDebugTracingOptions>,
+    ) -> RpcResult<GethTrace> {
+        self.geth_transaction_trace_impl(tx_hash, opts)
+            .map_err(Into::into)
+    }
+
+    fn geth_block_traces(
+        &self, block_hash: H256, opts: Option<GethDebugTracingOptions>,
+    ) -> RpcResult<Vec<TraceResult>> {
+        self.geth_block_traces_impl(block_hash, opts)
+            .map_err(Into::into)
+    }
+
+    fn geth_epoch_traces(
+        &self, epoch: RpcEpochNumber, opts: Option<GethDebugTracingOptions>,
+    ) -> RpcResult<Vec<TraceResult>> {
+        self.geth_epoch_traces_impl(epoch.into_primitive(), opts)
+            .map_err(Into::into)
+    }
 }
 
 impl TraceHandler {
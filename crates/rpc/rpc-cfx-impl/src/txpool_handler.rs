@@ -50,11 +50,15 @@ impl TxPoolServer for TxPoolHandler {
     fn txpool_status(&self) -> RpcResult<TxPoolStatus> {
         let (ready_len, deferred_len, received_len, unexecuted_len) =
             self.tx_pool.stats();
+        let (local_pending, local_queued) =
+            self.tx_pool.total_local_pending_and_queued(None);
         Ok(TxPoolStatus {
             deferred: U64::from(deferred_len),
             ready: U64::from(ready_len),
             received: U64::from(received_len),
             unexecuted: U64::from(unexecuted_len),
+            local_pending: U64::from(local_pending),
+            local_queued: U64::from(local_queued),
         })
     }
 
@@ -201,4 +205,12 @@ impl TxPoolServer for TxPoolHandler {
             pending_count: pending_count.into(),
         })
     }
+
+    fn txpool_rotate_journal(&self) -> RpcResult<U64> {
+        let journaled = self
+            .tx_pool
+            .rotate_journal()
+            .map_err(internal_error_with_data)?;
+        Ok(U64::from(journaled))
+    }
 }
@@ -34,6 +34,7 @@ pub mod transaction_request;
 mod transaction_status;
 pub mod tx_pool;
 pub mod vote_params_info;
+mod work;
 
 pub use account::Account;
 pub use address::RpcAddress;
@@ -64,7 +65,9 @@ pub use transaction_request::*;
 pub use transaction_status::{PendingReason, TransactionStatus};
 pub use tx_pool::*;
 pub use vote_params_info::VoteParamsInfo;
+pub use work::Work;
 
 pub use cfx_rpc_primitives::Bytes;
+pub use cfxcore_accounts::TypedData;
 
 pub use subscriber_id::SubId;
@@ -3,6 +3,7 @@ use cfx_types::{Bloom, U256};
 use primitives::{BlockHeader, Receipt, SignedTransaction};
 use std::sync::Arc;
 
+#[derive(Clone)]
 pub struct PhantomBlock {
     pub pivot_header: BlockHeader,
     pub transactions: Vec<Arc<SignedTransaction>>,
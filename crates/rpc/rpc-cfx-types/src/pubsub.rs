@@ -20,14 +20,16 @@
 
 //! Pub-Sub types.
 
-use crate::{CfxRpcLogFilter, Header, Log};
+use crate::{
+    CfxRpcLogFilter, Header, Log, RpcAddress, Transaction, TxPoolEvent,
+};
 use cfx_types::{H256, U256};
 use serde::{de::Error, Deserialize, Deserializer, Serialize};
 use serde_json::{from_value, Value};
 
 /// Subscription result.
 #[allow(dead_code)]
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 #[serde(untagged, rename_all = "camelCase")]
 // NOTE: rename_all does not apply to enum member fields
 // see: https://github.com/serde-rs/serde/issues/1061
@@ -41,6 +43,12 @@ pub enum Result {
     /// Transaction hash
     TransactionHash(H256),
 
+    /// Full transaction
+    FullTransaction(Box<Transaction>),
+
+    /// Transaction pool event
+    TxPoolEvent(TxPoolEvent<RpcAddress>),
+
     /// Epoch
     #[serde(rename_all = "camelCase")]
     Epoch {
@@ -68,6 +76,8 @@ pub enum Kind {
     Syncing,
     /// Epoch
     Epochs,
+    /// Transaction pool events subscription.
+    TxPoolEvents,
 }
 
 /// Subscription epoch.
@@ -90,6 +100,14 @@ pub enum Params {
     Logs(CfxRpcLogFilter),
     /// Epoch parameters.
     Epochs(SubscriptionEpoch),
+    /// Whether to publish the full pending transactions.
+    FullTransactions(bool),
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+struct PendingTransactionsParams {
+    full_transactions: bool,
 }
 
 impl Default for Params {
@@ -107,6 +125,14 @@ impl<'a> Deserialize<'a> for Params {
             return Ok(Params::None);
         }
 
+        // try to interpret as pending transactions parameters
+        if let Some(full_transactions) = v.as_bool() {
+            return Ok(Params::FullTransactions(full_transactions));
+        }
+        if let Ok(params) = from_value::<PendingTransactionsParams>(v.clone()) {
+            return Ok(Params::FullTransactions(params.full_transactions));
+        }
+
         // try to interpret as a log filter
         if let Ok(v) = from_value(v.clone()).map(Params::Logs) {
             return Ok(v);
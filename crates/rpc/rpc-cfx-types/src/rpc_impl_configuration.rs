@@ -3,6 +3,8 @@ use cfx_types::U256;
 #[derive(Clone, Default, Debug)]
 pub struct RpcImplConfiguration {
     pub get_logs_filter_max_limit: Option<usize>,
+    /// The maximum number of addresses in one `eth_getMultipleAccounts` call.
+    pub get_multiple_accounts_max_limit: usize,
     /// If it's `true`, `DEFERRED_STATE_EPOCH_COUNT` blocks are generated after
     /// receiving a new tx through RPC calling to pack and execute this
     /// transaction.
@@ -37,10 +37,43 @@ pub struct AccountPendingTransactions {
     pub pending_count: U64,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TxPoolEventKind {
+    Added,
+    Replaced,
+    Dropped,
+    Packed,
+    Ready,
+}
+
+/// A change of a transaction in the transaction pool, published to the
+/// `txPoolEvents` subscriptions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxPoolEvent<A> {
+    #[serde(rename = "type")]
+    pub kind: TxPoolEventKind,
+    pub hash: H256,
+    pub from: A,
+    pub nonce: U256,
+    /// The hash of the new transaction of a `replaced` event.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replaced_by: Option<H256>,
+    /// The reason of a `dropped` event.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
 #[derive(Default, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
 pub struct TxPoolStatus {
     pub deferred: U64,
     pub ready: U64,
     pub received: U64,
     pub unexecuted: U64,
+    /// The ready transactions from the local accounts.
+    pub local_pending: U64,
+    /// The unexecuted but not ready transactions from the local accounts.
+    pub local_queued: U64,
 }
@@ -0,0 +1,52 @@
+// Copyright 2019 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use cfx_types::{H256, U64};
+use serde::{Serialize, Serializer};
+
+/// The work package of `getWork`, serialized as an array for compatibility
+/// with the Ethereum miners: `[pow_hash, seed_hash, boundary, block_height]`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Work {
+    /// The hash of the block header without the nonce
+    pub pow_hash: H256,
+    /// The seed hash of the octopus dataset
+    pub seed_hash: H256,
+    /// The boundary the hash of the solution must not exceed
+    pub boundary: H256,
+    /// The height of the block
+    pub block_height: U64,
+}
+
+impl Serialize for Work {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer {
+        (
+            self.pow_hash,
+            self.seed_hash,
+            self.boundary,
+            self.block_height,
+        )
+            .serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize_work() {
+        let work = Work {
+            pow_hash: H256::from_low_u64_be(1),
+            seed_hash: H256::zero(),
+            boundary: H256::from_low_u64_be(0xff),
+            block_height: 16.into(),
+        };
+        assert_eq!(
+            serde_json::to_string(&work).unwrap(),
+            r#"["0x0000000000000000000000000000000000000000000000000000000000000001","0x0000000000000000000000000000000000000000000000000000000000000000","0x00000000000000000000000000000000000000000000000000000000000000ff","0x10"]"#
+        );
+    }
+}
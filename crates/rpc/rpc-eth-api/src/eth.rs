@@ -1,8 +1,9 @@
 use cfx_rpc_eth_types::{
-    AccessListResult, AccountPendingTransactions, Block, BlockId,
-    BlockOverrides, Bundle, EthCallResponse, EthRpcLogFilter as Filter,
-    FeeHistory, Header, Log, Receipt, RpcStateOverride, SimulatePayload,
-    SimulatedBlock, StateContext, SyncStatus, Transaction, TransactionRequest,
+    AccessListResult, Account, AccountPendingTransactions, Block, BlockId,
+    BlockOverrides, Bundle, EIP1186AccountProofResponse, EthCallResponse,
+    EthRpcLogFilter as Filter, FeeHistory, Header, Log, Receipt,
+    RpcStateOverride, SimulatePayload, SimulatedBlock, StateContext,
+    SyncStatus, Transaction, TransactionRequest, Work,
 };
 use cfx_rpc_primitives::{Bytes, Index};
 use cfx_types::{Address, H256, H64, U256, U64};
@@ -248,12 +249,17 @@ pub trait EthApi {
 
     /// Returns the account details by specifying an address and a block
     /// number/tag
-    // #[method(name = "getAccount")]
-    // async fn get_account(
-    //     &self,
-    //     address: Address,
-    //     block: BlockId,
-    // ) -> RpcResult<Option<reth_rpc_types::Account>>;
+    #[method(name = "getAccount")]
+    async fn get_account(
+        &self, address: Address, block_number: Option<BlockId>,
+    ) -> RpcResult<Option<Account>>;
+
+    /// Returns the account details of multiple addresses at the same block
+    /// number/tag. All accounts are read from the same state.
+    #[method(name = "getMultipleAccounts")]
+    async fn get_multiple_accounts(
+        &self, addresses: Vec<Address>, block_number: Option<BlockId>,
+    ) -> RpcResult<Vec<Option<Account>>>;
 
     /// Introduced in EIP-1559, returns suggestion for the priority for dynamic
     /// fee transactions.
@@ -289,8 +295,8 @@ pub trait EthApi {
 
     /// Returns the hash of the current block, the seedHash, and the boundary
     /// condition to be met (“target”)
-    // #[method(name = "getWork")]
-    // async fn get_work(&self) -> RpcResult<Work>;
+    #[method(name = "getWork")]
+    async fn get_work(&self) -> RpcResult<Work>;
 
     /// Used for submitting mining hashrate.
     ///
@@ -342,17 +348,14 @@ pub trait EthApi {
     // async fn sign_typed_data(&self, address: Address, data: TypedData) ->
     // RpcResult<Bytes>;
 
-    // Returns the account and storage values of the specified account including
-    // the Merkle-proof. This call can be used to verify that the data you
-    // are pulling from is not tampered with.
-
-    // #[method(name = "getProof")]
-    // async fn get_proof(
-    //     &self,
-    //     address: Address,
-    //     keys: Vec<JsonStorageKey>,
-    //     block_number: Option<BlockId>,
-    // ) -> RpcResult<EIP1186AccountProofResponse>;
+    /// Returns the account and storage values of the specified account
+    /// including the Merkle-proof. This call can be used to verify that the
+    /// data you are pulling from is not tampered with.
+    #[method(name = "getProof")]
+    async fn get_proof(
+        &self, address: Address, keys: Vec<JsonStorageKey>,
+        block_number: Option<BlockId>,
+    ) -> RpcResult<EIP1186AccountProofResponse>;
 
     /// Returns logs matching given filter object.
     #[method(name = "getLogs")]
@@ -3,6 +3,7 @@ mod eth;
 mod filter;
 mod net;
 mod parity;
+mod personal;
 mod pubsub;
 mod rpc;
 mod trace;
@@ -14,6 +15,7 @@ pub use eth::EthApiServer;
 pub use filter::EthFilterApiServer;
 pub use net::NetApiServer;
 pub use parity::ParityApiServer;
+pub use personal::PersonalApiServer;
 pub use pubsub::EthPubSubApiServer;
 pub use rpc::RpcApiServer;
 pub use trace::TraceApiServer;
@@ -0,0 +1,15 @@
+use cfx_rpc_eth_types::TypedData;
+use cfx_rpc_primitives::Bytes;
+use cfx_types::Address;
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+
+/// Rpc interface of the local accounts of the node. It is only served on IPC.
+#[rpc(server, namespace = "personal")]
+pub trait PersonalApi {
+    /// Signs data via [EIP-712](https://github.com/ethereum/EIPs/blob/master/EIPS/eip-712.md)
+    /// with an unlocked local account.
+    #[method(name = "signTypedData")]
+    async fn sign_typed_data(
+        &self, address: Address, data: TypedData,
+    ) -> RpcResult<Bytes>;
+}
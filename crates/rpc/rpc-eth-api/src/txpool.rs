@@ -1,7 +1,7 @@
 use cfx_rpc_eth_types::{
     TxpoolContent, TxpoolContentFrom, TxpoolInspect, TxpoolStatus,
 };
-use cfx_types::Address;
+use cfx_types::{Address, U64};
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 
 /// Txpool rpc interface.
@@ -40,4 +40,9 @@ pub trait TxPoolApi {
     /// See [here](https://geth.ethereum.org/docs/rpc/ns-txpool#txpool_content) for more details
     #[method(name = "content")]
     async fn txpool_content(&self) -> RpcResult<TxpoolContent>;
+
+    /// Rewrites the transaction journal with the transactions currently in
+    /// the pool, and returns the number of journaled transactions.
+    #[method(name = "rotateJournal")]
+    async fn txpool_rotate_journal(&self) -> RpcResult<U64>;
 }
@@ -1,18 +1,24 @@
 use crate::helpers::{
-    FeeHistoryCache, TxExecutor, MAX_FEE_HISTORY_CACHE_BLOCK_COUNT,
+    build_account_proof, build_simulated_block, call_many_responses,
+    check_multiple_accounts_limit, read_accounts, FeeHistoryCache,
+    PendingBlockProvider, TxExecutor, MAX_FEE_HISTORY_CACHE_BLOCK_COUNT,
+    MAX_PROOF_STORAGE_KEYS,
 };
 use async_trait::async_trait;
+use blockgen::RemoteWork;
 use cfx_parameters::rpc::GAS_PRICE_DEFAULT_VALUE;
+use cfx_rpc_cfx_impl::{get_remote_work, submit_remote_work};
 use cfx_rpc_cfx_types::{
     traits::BlockProvider, PhantomBlock, RpcImplConfiguration,
 };
 use cfx_rpc_eth_api::EthApiServer;
 use cfx_rpc_eth_types::{
-    AccessListResult, AccountPendingTransactions, Block, BlockId,
-    BlockOverrides, Bundle, EthCallResponse, EthRpcLogFilter,
-    EthRpcLogFilter as Filter, FeeHistory, Header, Log, LogData, Receipt,
-    RpcStateOverride, SimulatePayload, SimulatedBlock, StateContext, SyncInfo,
-    SyncStatus, Transaction, TransactionRequest,
+    AccessListResult, Account, AccountPendingTransactions, Block, BlockId,
+    BlockOverrides, Bundle, EIP1186AccountProofResponse, EthCallResponse,
+    EthRpcLogFilter, EthRpcLogFilter as Filter, FeeHistory, Header, Log,
+    LogData, Receipt, RpcStateOverride, SimBlock, SimulatePayload,
+    SimulatedBlock, StateContext, SyncInfo, SyncStatus, Transaction,
+    TransactionRequest, Work, MAX_SIMULATE_BLOCKS,
 };
 use cfx_rpc_primitives::{Bytes, Index, U64 as HexU64};
 use cfx_rpc_utils::{
@@ -33,6 +39,7 @@ use cfx_types::{
 };
 use cfx_util_macros::bail;
 use cfxcore::{
+    block_data_manager::BlockDataManager,
     errors::{Error as CoreError, Result as CoreResult},
     ConsensusGraph, SharedConsensusGraph, SharedSynchronizationService,
     SharedTransactionPool,
@@ -41,9 +48,10 @@ use cfxcore_errors::ProviderBlockError;
 use jsonrpsee::{core::RpcResult, types::ErrorObjectOwned as RpcError};
 use primitives::{
     filter::LogFilter, receipt::EVM_SPACE_SUCCESS, Action, EpochNumber,
-    StorageKey, StorageValue, TransactionStatus, TransactionWithSignature,
+    SignedTransaction, StateRoot, StorageKey, StorageValue, TransactionStatus,
+    TransactionWithSignature,
 };
-use std::future::Future;
+use std::{future::Future, sync::Arc};
 
 type BlockNumber = BlockId;
 type BlockNumberOrTag = BlockId;
@@ -60,13 +68,16 @@ pub struct EthApi {
     fee_history_cache: FeeHistoryCache,
     tx_executor: TxExecutor,
     task_executor: TaskExecutor,
+    remote_work: Option<Arc<RemoteWork>>,
+    pending_block: PendingBlockProvider,
 }
 
 impl EthApi {
     pub fn new(
         config: RpcImplConfiguration, consensus: SharedConsensusGraph,
         sync: SharedSynchronizationService, tx_pool: SharedTransactionPool,
-        executor: TaskExecutor,
+        executor: TaskExecutor, remote_work: Option<Arc<RemoteWork>>,
+        pending_block: PendingBlockProvider,
     ) -> Self {
         let cloned_consensus = consensus.clone();
         let max_estimation_gas_limit = config.max_estimation_gas_limit;
@@ -79,8 +90,11 @@ impl EthApi {
             tx_executor: TxExecutor::new(
                 cloned_consensus,
                 max_estimation_gas_limit,
-            ),
+            )
+            .with_pending_block(pending_block.clone()),
             task_executor: executor,
+            remote_work,
+            pending_block,
         }
     }
 
@@ -128,7 +142,7 @@ impl EthApi {
             bail!(request_rejected_in_catch_up_mode(None));
         }
         let (signed_trans, failed_trans) =
-            self.tx_pool.insert_new_transactions(vec![tx]);
+            self.tx_pool.insert_local_transactions(vec![tx]);
         if signed_trans.len() + failed_trans.len() > 1 {
             // This should never happen
             Ok(H256::zero().into())
@@ -148,7 +162,7 @@ impl EthApi {
     }
 
     pub fn construct_rpc_receipt(
-        &self, b: &PhantomBlock, idx: usize, prior_log_index: &mut usize,
+        b: &PhantomBlock, idx: usize, prior_log_index: &mut usize,
     ) -> CoreResult<Receipt> {
         if b.transactions.len() != b.receipts.len() {
             return Err(internal_error_with_data(
@@ -303,7 +317,7 @@ impl EthApi {
         let mut prior_log_index = 0;
 
         for idx in 0..b.receipts.len() {
-            block_receipts.push(self.construct_rpc_receipt(
+            block_receipts.push(Self::construct_rpc_receipt(
                 &b,
                 idx,
                 &mut prior_log_index,
@@ -343,6 +357,51 @@ impl EthApi {
         }
     }
 
+    pub fn block_raw_tx_by_index(
+        phantom_block: Option<PhantomBlock>, idx: usize,
+    ) -> Option<Bytes> {
+        phantom_block?
+            .transactions
+            .get(idx)
+            .map(|tx| Self::raw_transaction(tx))
+    }
+
+    /// The EIP-2718 encoding of a transaction, the same as the input of
+    /// `eth_sendRawTransaction`.
+    pub fn raw_transaction(tx: &SignedTransaction) -> Bytes {
+        Bytes::new(rlp::encode(&tx.transaction.transaction).to_vec())
+    }
+
+    /// Decode the input of `eth_sendRawTransaction`.
+    pub fn decode_raw_transaction(
+        raw: &[u8],
+    ) -> Result<TransactionWithSignature, EthApiError> {
+        let tx = match TransactionWithSignature::from_raw(raw) {
+            Ok(tx) => tx,
+            Err(_) => bail!(EthApiError::FailedToDecodeSignedTransaction),
+        };
+
+        if tx.space() != Space::Ethereum {
+            bail!(EthApiError::Other(
+                "Incorrect transaction space".to_string()
+            ));
+        }
+
+        if tx.recover_public().is_err() {
+            bail!(EthApiError::InvalidTransactionSignature);
+        }
+
+        Ok(tx)
+    }
+
+    pub fn header_by_number(
+        &self, block_num: BlockNumber,
+    ) -> CoreResult<Option<Header>> {
+        let phantom_block = self.phantom_block_by_number(block_num)?;
+
+        Ok(phantom_block.map(|pb| Header::from_phantom(&pb)))
+    }
+
     pub fn sync_status(&self) -> SyncStatus {
         if self.sync.catch_up_mode() {
             SyncStatus::Info(SyncInfo {
@@ -396,6 +455,9 @@ impl EthApi {
     pub fn user_balance(
         &self, address: H160, num: Option<BlockNumber>,
     ) -> CoreResult<U256> {
+        if num == Some(BlockNumber::Pending) {
+            return self.pending_block.pending_block()?.balance(&address);
+        }
         let epoch_num =
             self.convert_block_number_to_epoch_number(num.unwrap_or_default())?;
         let state_db = self
@@ -411,6 +473,13 @@ impl EthApi {
     pub fn storage_at(
         &self, address: H160, position: U256, block_num: Option<BlockNumber>,
     ) -> CoreResult<H256> {
+        if block_num == Some(BlockNumber::Pending) {
+            let value = self
+                .pending_block
+                .pending_block()?
+                .storage_at(&address, &H256::from_uint(&position))?;
+            return Ok(H256::from_uint(&value));
+        }
         let epoch_num = self.convert_block_number_to_epoch_number(
             block_num.unwrap_or_default(),
         )?;
@@ -435,6 +504,90 @@ impl EthApi {
         )
     }
 
+    fn state_root_at_height(&self, height: u64) -> CoreResult<StateRoot> {
+        let pivot = self
+            .consensus_graph()
+            .get_hash_from_epoch_number(EpochNumber::Number(height))?;
+        let commitment = self
+            .consensus
+            .data_manager()
+            .get_epoch_execution_commitment_with_db(&pivot)
+            .ok_or_else(|| {
+                format!("Execution commitment not found, height={}", height)
+            })?;
+        Ok(commitment.state_root_with_aux_info.state_root.clone())
+    }
+
+    pub fn get_proof(
+        &self, address: H160, keys: Vec<JsonStorageKey>,
+        block_num: Option<BlockNumber>,
+    ) -> CoreResult<EIP1186AccountProofResponse> {
+        if keys.len() > MAX_PROOF_STORAGE_KEYS {
+            bail!(invalid_params(
+                "keys",
+                Some(format!(
+                    "Too many storage keys, max limitation is {}",
+                    MAX_PROOF_STORAGE_KEYS
+                ))
+            ));
+        }
+
+        let epoch_num = self.convert_block_number_to_epoch_number(
+            block_num.unwrap_or_default(),
+        )?;
+        let height = self
+            .consensus_graph()
+            .get_height_from_epoch_number(epoch_num)
+            .map_err(|e| invalid_params_rpc_err(e, None::<()>))?;
+
+        let state = self.consensus.get_storage_state_by_epoch_number(
+            EpochNumber::Number(height),
+            "block_number",
+        )?;
+
+        // The intermediate delta MPT is keyed with the padding derived from
+        // the state root one snapshot period earlier.
+        let snapshot_epoch_count =
+            self.consensus.data_manager().get_snapshot_epoch_count() as u64;
+        let prev_snapshot_state_root = match height {
+            h if h <= snapshot_epoch_count => None,
+            h => Some(self.state_root_at_height(h - snapshot_epoch_count)?),
+        };
+
+        build_account_proof(
+            &state,
+            address,
+            keys,
+            height,
+            prev_snapshot_state_root,
+        )
+    }
+
+    /// Read the accounts of `addresses` from the same state.
+    pub fn get_accounts(
+        &self, addresses: &[H160], block_num: Option<BlockNumber>,
+    ) -> CoreResult<Vec<Option<Account>>> {
+        let epoch_num = self.convert_block_number_to_epoch_number(
+            block_num.unwrap_or_default(),
+        )?;
+        let state = self
+            .consensus
+            .get_storage_state_by_epoch_number(epoch_num, "block_number")?;
+
+        read_accounts(&state, addresses)
+    }
+
+    pub fn get_multiple_accounts(
+        &self, addresses: Vec<H160>, block_num: Option<BlockNumber>,
+    ) -> CoreResult<Vec<Option<Account>>> {
+        check_multiple_accounts_limit(
+            addresses.len(),
+            self.config.get_multiple_accounts_max_limit,
+        )?;
+
+        self.get_accounts(&addresses, block_num)
+    }
+
     pub fn phantom_block_by_hash(
         &self, hash: H256,
     ) -> CoreResult<Option<PhantomBlock>> {
@@ -447,6 +600,10 @@ impl EthApi {
     pub fn phantom_block_by_number(
         &self, block_num: BlockNumber,
     ) -> CoreResult<Option<PhantomBlock>> {
+        if block_num == BlockNumber::Pending {
+            let pending_block = self.pending_block.pending_block()?;
+            return Ok(Some(pending_block.block.clone()));
+        }
         let phantom_block = {
             // keep read lock to ensure consistent view
             let _inner = self.consensus_graph().inner.read();
@@ -577,6 +734,10 @@ impl EthApi {
     pub fn code_at(
         &self, address: H160, block_num: Option<BlockNumber>,
     ) -> CoreResult<Bytes> {
+        if block_num == Some(BlockNumber::Pending) {
+            let code = self.pending_block.pending_block()?.code(&address)?;
+            return Ok(Bytes::new(code));
+        }
         let epoch_num = self.convert_block_number_to_epoch_number(
             block_num.unwrap_or_default(),
         )?;
@@ -713,21 +874,23 @@ impl EthApi {
         Ok(fee_history)
     }
 
-    pub fn transaction_by_hash(
+    /// Returns the phantom block containing the transaction and the index of
+    /// the transaction in it, or `None` if the transaction is not executed.
+    fn phantom_block_by_tx_hash(
         &self, hash: H256,
-    ) -> CoreResult<Option<Transaction>> {
+    ) -> CoreResult<Option<(PhantomBlock, usize)>> {
         let tx_index = match self
             .consensus
             .data_manager()
             .transaction_index_by_hash(&hash, false /* update_cache */)
         {
-            None => return Ok(self.get_tx_from_txpool(hash)),
+            None => return Ok(None),
             Some(tx_index) => tx_index,
         };
 
         let epoch_num =
             match self.consensus.get_block_epoch_number(&tx_index.block_hash) {
-                None => return Ok(self.get_tx_from_txpool(hash)),
+                None => return Ok(None),
                 Some(n) => n,
             };
 
@@ -741,31 +904,105 @@ impl EthApi {
             .map_err(|e| invalid_params_rpc_err(e, None::<()>))?;
 
         let phantom_block = match maybe_block {
-            None => return Ok(self.get_tx_from_txpool(hash)),
+            None => return Ok(None),
             Some(b) => b,
         };
 
-        for (idx, tx) in phantom_block.transactions.iter().enumerate() {
-            if tx.hash() == hash {
-                let tx = Self::block_tx_by_index(Some(phantom_block), idx);
-                if let Some(tx_ref) = &tx {
-                    if tx_ref.status
-                        == Some(
-                            TransactionStatus::Skipped
-                                .in_space(Space::Ethereum)
-                                .into(),
-                        )
-                    {
-                        // A skipped transaction is not available to clients if
-                        // accessed by its hash.
-                        return Ok(None);
-                    }
-                }
-                return Ok(tx);
+        let idx = phantom_block
+            .transactions
+            .iter()
+            .position(|tx| tx.hash() == hash);
+        Ok(idx.map(|idx| (phantom_block, idx)))
+    }
+
+    pub fn transaction_by_hash(
+        &self, hash: H256,
+    ) -> CoreResult<Option<Transaction>> {
+        let (phantom_block, idx) = match self.phantom_block_by_tx_hash(hash)? {
+            None => return Ok(self.get_tx_from_txpool(hash)),
+            Some(v) => v,
+        };
+
+        let tx = Self::block_tx_by_index(Some(phantom_block), idx);
+        if let Some(tx_ref) = &tx {
+            if tx_ref.status
+                == Some(
+                    TransactionStatus::Skipped.in_space(Space::Ethereum).into(),
+                )
+            {
+                // A skipped transaction is not available to clients if
+                // accessed by its hash.
+                return Ok(None);
             }
         }
+        Ok(tx)
+    }
 
-        Ok(self.get_tx_from_txpool(hash))
+    pub fn raw_transaction_by_hash(
+        &self, hash: H256,
+    ) -> CoreResult<Option<Bytes>> {
+        let (phantom_block, idx) = match self.phantom_block_by_tx_hash(hash)? {
+            None => {
+                return Ok(self
+                    .tx_pool
+                    .get_transaction(&hash)
+                    .filter(|tx| tx.space() == Space::Ethereum)
+                    .map(|tx| Self::raw_transaction(&tx)));
+            }
+            Some(v) => v,
+        };
+
+        Ok(Self::block_raw_tx_by_hash_index(phantom_block, idx))
+    }
+
+    /// The raw transaction at `idx` of the phantom block found by the
+    /// transaction hash.
+    pub fn block_raw_tx_by_hash_index(
+        phantom_block: PhantomBlock, idx: usize,
+    ) -> Option<Bytes> {
+        if phantom_block.receipts.get(idx)?.outcome_status
+            == TransactionStatus::Skipped
+        {
+            // A skipped transaction is not available to clients if accessed
+            // by its hash.
+            return None;
+        }
+        Self::block_raw_tx_by_index(Some(phantom_block), idx)
+    }
+
+    /// Returns the executed transaction with the given sender and nonce, found
+    /// by the sender and nonce index of the data manager.
+    pub fn transaction_by_sender_and_nonce(
+        &self, sender: Address, nonce: U256,
+    ) -> CoreResult<Option<Transaction>> {
+        let hash = match Self::tx_hash_by_sender_and_nonce(
+            self.consensus.data_manager(),
+            &sender,
+            &nonce,
+        )? {
+            None => return Ok(None),
+            Some(hash) => hash,
+        };
+        // The index is not cleared when the transaction is reverted to the
+        // pool by a pivot chain reorg, so the pending transactions are
+        // filtered out.
+        Ok(self
+            .transaction_by_hash(hash)?
+            .filter(|tx| tx.block_hash.is_some()))
+    }
+
+    /// Returns the hash of the executed transaction with the given sender and
+    /// nonce, or an error if the index is disabled.
+    pub fn tx_hash_by_sender_and_nonce(
+        data_man: &BlockDataManager, sender: &Address, nonce: &U256,
+    ) -> CoreResult<Option<H256>> {
+        if !data_man.tx_sender_nonce_index_enabled() {
+            bail!(invalid_params_rpc_err(
+                "the sender and nonce index is disabled, set `persist_tx_sender_nonce_index` to enable it",
+                None::<()>,
+            ));
+        }
+        Ok(data_man.tx_hash_by_sender_nonce(sender, nonce))
     }
 
     pub fn transaction_receipt(
@@ -809,7 +1046,7 @@ impl EthApi {
 
         for (idx, tx) in phantom_block.transactions.iter().enumerate() {
             if tx.hash() == tx_hash {
-                let receipt = self.construct_rpc_receipt(
+                let receipt = Self::construct_rpc_receipt(
                     &phantom_block,
                     idx,
                     &mut prior_log_index,
@@ -835,25 +1072,46 @@ impl EthApi {
     }
 
     pub fn logs(&self, filter: EthRpcLogFilter) -> CoreResult<Vec<Log>> {
+        let pending_block = if filter.to_block == Some(BlockNumber::Pending) {
+            Some(self.pending_block.pending_block()?)
+        } else {
+            None
+        };
+        // The executed epochs are not queried if the range starts from the
+        // pending block.
+        let only_pending = match (&pending_block, filter.from_block) {
+            (Some(_), Some(BlockNumber::Pending)) => true,
+            (Some(block), Some(BlockNumber::Num(from))) => {
+                from >= block.number()
+            }
+            _ => false,
+        };
         let filter: LogFilter = filter.into_primitive(self)?;
 
-        let logs = self
-            .consensus_graph()
-            .logs(filter)
-            .map_err(|err| CoreError::from(err))?;
+        let logs = if only_pending {
+            vec![]
+        } else {
+            self.consensus_graph()
+                .logs(filter.clone())
+                .map_err(|err| CoreError::from(err))?
+        };
+        let pending_logs = pending_block
+            .map_or(vec![], |pending_block| pending_block.logs(&filter));
 
         // If the results does not fit into `max_limit`, report an error
         if let Some(max_limit) = self.config.get_logs_filter_max_limit {
-            if logs.len() > max_limit {
+            if logs.len() + pending_logs.len() > max_limit {
                 bail!(invalid_params("filter", Some(format!("This query results in too many logs, max limitation is {}, please use a smaller block range", max_limit))));
             }
         }
 
-        Ok(logs
+        let mut logs = logs
             .iter()
             .cloned()
             .map(|l| Log::try_from_localized(l, self, false))
-            .collect::<Result<_, _>>()?)
+            .collect::<Result<Vec<_>, _>>()?;
+        logs.extend(pending_logs);
+        Ok(logs)
     }
 
     pub fn max_priority_fee_per_gas(&self) -> CoreResult<U256> {
@@ -1046,8 +1304,7 @@ impl EthApiServer for EthApi {
     async fn raw_transaction_by_hash(
         &self, hash: H256,
     ) -> RpcResult<Option<Bytes>> {
-        let _ = hash;
-        Err(internal_error_with_data("Not implemented"))
+        self.raw_transaction_by_hash(hash).map_err(|err| err.into())
     }
 
     /// Returns the information about a transaction requested by transaction
@@ -1063,8 +1320,9 @@ impl EthApiServer for EthApi {
     async fn raw_transaction_by_block_hash_and_index(
         &self, hash: H256, index: Index,
     ) -> RpcResult<Option<Bytes>> {
-        let _ = (hash, index);
-        Err(internal_error_with_data("Not implemented"))
+        let phantom_block = self.phantom_block_by_hash(hash)?;
+
+        Ok(EthApi::block_raw_tx_by_index(phantom_block, index.value()))
     }
 
     /// Returns information about a transaction by block hash and transaction
@@ -1082,8 +1340,9 @@ impl EthApiServer for EthApi {
     async fn raw_transaction_by_block_number_and_index(
         &self, number: BlockNumberOrTag, index: Index,
     ) -> RpcResult<Option<Bytes>> {
-        let _ = (number, index);
-        Err(internal_error_with_data("Not implemented"))
+        let phantom_block = self.phantom_block_by_number(number)?;
+
+        Ok(EthApi::block_raw_tx_by_index(phantom_block, index.value()))
     }
 
     /// Returns information about a transaction by block number and transaction
@@ -1100,8 +1359,8 @@ impl EthApiServer for EthApi {
     async fn transaction_by_sender_and_nonce(
         &self, address: Address, nonce: U64,
     ) -> RpcResult<Option<Transaction>> {
-        let _ = (address, nonce);
-        Err(internal_error_with_data("Not implemented"))
+        self.transaction_by_sender_and_nonce(address, nonce.as_u64().into())
+            .map_err(|err| err.into())
     }
 
     /// Returns the receipt of a transaction by transaction hash.
@@ -1149,14 +1408,16 @@ impl EthApiServer for EthApi {
     async fn header_by_number(
         &self, hash: BlockNumberOrTag,
     ) -> RpcResult<Option<Header>> {
-        let _ = hash;
-        Err(internal_error_with_data("Not implemented"))
+        self.header_by_number(hash).map_err(|err| err.into())
     }
 
     /// Returns the block's header at given hash.
     async fn header_by_hash(&self, hash: H256) -> RpcResult<Option<Header>> {
-        let _ = hash;
-        Err(internal_error_with_data("Not implemented"))
+        self.header_by_number(BlockNumber::Hash {
+            hash,
+            require_canonical: None,
+        })
+        .map_err(|err| err.into())
     }
 
     /// `eth_simulateV1` executes an arbitrary number of transactions on top of
@@ -1165,9 +1426,44 @@ impl EthApiServer for EthApi {
     async fn simulate_v1(
         &self, opts: SimulatePayload, block_number: Option<BlockId>,
     ) -> RpcResult<Vec<SimulatedBlock>> {
-        let _ = block_number;
-        let _ = opts;
-        Err(internal_error_with_data("Not implemented"))
+        let SimulatePayload {
+            block_state_calls,
+            trace_transfers,
+            validation,
+            return_full_transactions,
+        } = opts;
+        if block_state_calls.len() as u64 > MAX_SIMULATE_BLOCKS {
+            return Err(invalid_params_rpc_err(
+                format!(
+                    "too many blocks, at most {} blocks can be simulated",
+                    MAX_SIMULATE_BLOCKS
+                ),
+                None::<()>,
+            ));
+        }
+
+        let outcomes = self.tx_executor.simulate_transactions(
+            block_state_calls,
+            block_number,
+            validation,
+        )?;
+
+        let mut parent_hash = match outcomes.first() {
+            Some(outcome) => outcome.env.last_hash,
+            None => return Ok(vec![]),
+        };
+        let mut blocks = Vec::with_capacity(outcomes.len());
+        for outcome in outcomes {
+            let block = build_simulated_block(
+                outcome,
+                parent_hash,
+                trace_transfers,
+                return_full_transactions,
+            )?;
+            parent_hash = block.inner.header.hash;
+            blocks.push(block);
+        }
+        Ok(blocks)
     }
 
     /// Executes a new message call immediately without creating a transaction
@@ -1194,10 +1490,31 @@ impl EthApiServer for EthApi {
         &self, bundle: Bundle, state_context: Option<StateContext>,
         state_override: Option<RpcStateOverride>,
     ) -> RpcResult<Vec<EthCallResponse>> {
-        let _ = bundle;
-        let _ = state_context;
-        let _ = state_override;
-        Err(internal_error_with_data("Not implemented"))
+        let StateContext {
+            block_number,
+            transaction_index,
+        } = state_context.unwrap_or_default();
+        if transaction_index.is_some_and(|index| index.is_index()) {
+            return Err(invalid_params_rpc_err(
+                "transactionIndex is not supported, the calls are executed on top of the whole block",
+                None::<()>,
+            ));
+        }
+
+        let block = SimBlock {
+            block_overrides: bundle.block_override,
+            state_overrides: state_override,
+            calls: bundle.transactions,
+        };
+        let mut outcomes = self.tx_executor.simulate_transactions(
+            vec![block],
+            block_number,
+            false,
+        )?;
+
+        Ok(call_many_responses(
+            outcomes.pop().expect("one block is simulated"),
+        ))
     }
 
     /// Generates an access list for a transaction.
@@ -1260,11 +1577,21 @@ impl EthApiServer for EthApi {
 
     /// Returns the account details by specifying an address and a block
     /// number/tag
-    // async fn get_account(
-    //     &self,
-    //     address: Address,
-    //     block: BlockId,
-    // ) -> RpcResult<Option<reth_rpc_types::Account>>;
+    async fn get_account(
+        &self, address: Address, block_number: Option<BlockId>,
+    ) -> RpcResult<Option<Account>> {
+        let mut accounts = self.get_accounts(&[address], block_number)?;
+        Ok(accounts.pop().flatten())
+    }
+
+    /// Returns the account details of multiple addresses at the same block
+    /// number/tag. All accounts are read from the same state.
+    async fn get_multiple_accounts(
+        &self, addresses: Vec<Address>, block_number: Option<BlockId>,
+    ) -> RpcResult<Vec<Option<Account>>> {
+        self.get_multiple_accounts(addresses, block_number)
+            .map_err(|err| err.into())
+    }
 
     /// Introduced in EIP-1559, returns suggestion for the priority for dynamic
     /// fee transactions.
@@ -1297,14 +1624,21 @@ impl EthApiServer for EthApi {
     }
 
     /// Returns whether the client is actively mining new blocks.
-    async fn is_mining(&self) -> RpcResult<bool> { Ok(false) }
+    async fn is_mining(&self) -> RpcResult<bool> {
+        Ok(self
+            .remote_work
+            .as_ref()
+            .is_some_and(|work| work.get_work().is_ok()))
+    }
 
     /// Returns the number of hashes per second that the node is mining with.
     async fn hashrate(&self) -> RpcResult<U256> { Ok(U256::zero()) }
 
     /// Returns the hash of the current block, the seedHash, and the boundary
     /// condition to be met (“target”)
-    // async fn get_work(&self) -> RpcResult<Work>;
+    async fn get_work(&self) -> RpcResult<Work> {
+        get_remote_work(self.remote_work.as_deref())
+    }
 
     /// Used for submitting mining hashrate.
     ///
@@ -1323,8 +1657,11 @@ impl EthApiServer for EthApi {
     async fn submit_work(
         &self, nonce: H64, pow_hash: H256, mix_digest: H256,
     ) -> RpcResult<bool> {
-        let _ = (nonce, pow_hash, mix_digest);
-        Ok(false)
+        // The octopus PoW has no mix digest.
+        let _ = mix_digest;
+        let nonce = U256::from_big_endian(nonce.as_bytes());
+        submit_remote_work(self.remote_work.as_deref(), pow_hash, nonce)?;
+        Ok(true)
     }
 
     /// Sends transaction; will block waiting for signer to return the
@@ -1338,24 +1675,7 @@ impl EthApiServer for EthApi {
 
     /// Sends signed transaction, returning its hash.
     async fn send_raw_transaction(&self, bytes: Bytes) -> RpcResult<H256> {
-        let tx = if let Ok(tx) =
-            TransactionWithSignature::from_raw(&bytes.into_vec())
-        {
-            tx
-        } else {
-            bail!(EthApiError::FailedToDecodeSignedTransaction)
-        };
-
-        if tx.space() != Space::Ethereum {
-            bail!(EthApiError::Other(
-                "Incorrect transaction space".to_string()
-            ));
-        }
-
-        if tx.recover_public().is_err() {
-            bail!(EthApiError::InvalidTransactionSignature);
-        }
-
+        let tx = Self::decode_raw_transaction(&bytes.into_vec())?;
         let r = self.send_transaction_with_signature(tx)?;
         Ok(r)
     }
@@ -1381,6 +1701,14 @@ impl EthApiServer for EthApi {
         Err(internal_error_with_data("Not implemented"))
     }
 
+    async fn get_proof(
+        &self, address: Address, keys: Vec<JsonStorageKey>,
+        block_number: Option<BlockId>,
+    ) -> RpcResult<EIP1186AccountProofResponse> {
+        self.get_proof(address, keys, block_number)
+            .map_err(|err| err.into())
+    }
+
     async fn logs(&self, filter: Filter) -> RpcResult<Vec<Log>> {
         self.logs(filter).map_err(|err| err.into())
     }
@@ -1397,3 +1725,219 @@ impl EthApiServer for EthApi {
         .map_err(|err| err.into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::EthApi;
+    use cfx_executor::machine::VmFactory;
+    use cfx_rpc_cfx_types::PhantomBlock;
+    use cfx_rpc_utils::error::errors::EthApiError;
+    use cfx_types::{Address, Bloom, H256, U256};
+    use cfxcore::{
+        block_data_manager::{
+            BlockDataManager, DataManagerConfiguration, DbType,
+        },
+        pow::PowComputer,
+        sync::utils::initialize_data_manager_with_config,
+    };
+    use cfxkey::{Generator, KeyPair, Random};
+    use jsonrpsee::types::{
+        error::INVALID_PARAMS_CODE, ErrorObjectOwned as RpcError,
+    };
+    use primitives::{
+        transaction::{
+            native_transaction::NativeTransaction, AccessListItem,
+            Eip1559Transaction, Eip155Transaction, Eip2930Transaction,
+            EthereumTransaction,
+        },
+        Action, BlockHeaderBuilder, Receipt, SignedTransaction, Transaction,
+        TransactionStatus,
+    };
+    use std::{path::Path, sync::Arc, time::Duration};
+
+    fn eth_txs(sender: &KeyPair) -> Vec<Arc<SignedTransaction>> {
+        let to = Action::Call(Address::repeat_byte(0x11));
+        let access_list = vec![AccessListItem {
+            address: Address::repeat_byte(0x22),
+            storage_keys: vec![H256::repeat_byte(0x33)],
+        }];
+        let txs: Vec<Transaction> = vec![
+            Eip155Transaction {
+                nonce: 0.into(),
+                gas_price: 1.into(),
+                gas: 21_000.into(),
+                action: to.clone(),
+                value: 1.into(),
+                chain_id: Some(71),
+                data: vec![],
+            }
+            .into(),
+            Transaction::Ethereum(EthereumTransaction::Eip2930(
+                Eip2930Transaction {
+                    chain_id: 71,
+                    nonce: 1.into(),
+                    gas_price: 1.into(),
+                    gas: 30_000.into(),
+                    action: to.clone(),
+                    value: 2.into(),
+                    data: vec![0xab],
+                    access_list: access_list.clone(),
+                },
+            )),
+            Transaction::Ethereum(EthereumTransaction::Eip1559(
+                Eip1559Transaction {
+                    chain_id: 71,
+                    nonce: 2.into(),
+                    max_priority_fee_per_gas: 1.into(),
+                    max_fee_per_gas: 2.into(),
+                    gas: 30_000.into(),
+                    action: to,
+                    value: 3.into(),
+                    data: vec![0xcd],
+                    access_list,
+                },
+            )),
+        ];
+        txs.into_iter()
+            .map(|tx| Arc::new(tx.sign(sender.secret())))
+            .collect()
+    }
+
+    #[test]
+    fn test_raw_transaction_round_trip() {
+        let sender = Random.generate().unwrap();
+        let txs = eth_txs(&sender);
+
+        // legacy transactions are RLP lists, typed ones start with the type.
+        let expected_first_bytes = [None, Some(0x01), Some(0x02)];
+        for (tx, first_byte) in txs.iter().zip(expected_first_bytes) {
+            let raw = EthApi::raw_transaction(tx);
+            match first_byte {
+                None => assert!(raw.0[0] >= 0xc0),
+                Some(b) => assert_eq!(raw.0[0], b),
+            }
+
+            let decoded = EthApi::decode_raw_transaction(&raw.0).unwrap();
+            assert_eq!(decoded, tx.transaction);
+            assert_eq!(decoded.hash(), tx.hash());
+            assert_eq!(decoded.recover_public().unwrap(), tx.public.unwrap());
+        }
+
+        let native: Transaction = NativeTransaction {
+            nonce: 0.into(),
+            gas_price: 1.into(),
+            gas: 21_000.into(),
+            action: Action::Call(Address::repeat_byte(0x11)),
+            value: 1.into(),
+            storage_limit: 0,
+            epoch_height: 0,
+            chain_id: 1,
+            data: vec![],
+        }
+        .into();
+        let native = native.sign(sender.secret());
+        assert!(matches!(
+            EthApi::decode_raw_transaction(&EthApi::raw_transaction(&native).0),
+            Err(EthApiError::Other(_))
+        ));
+        assert!(matches!(
+            EthApi::decode_raw_transaction(&[0xc0]),
+            Err(EthApiError::FailedToDecodeSignedTransaction)
+        ));
+    }
+
+    #[test]
+    fn test_skipped_raw_transaction_by_hash() {
+        let txs = eth_txs(&Random.generate().unwrap());
+        let receipt = |outcome_status| Receipt {
+            outcome_status,
+            ..Default::default()
+        };
+        let phantom_block = PhantomBlock {
+            pivot_header: BlockHeaderBuilder::new().build(),
+            transactions: txs.clone(),
+            receipts: vec![
+                receipt(TransactionStatus::Success),
+                receipt(TransactionStatus::Skipped),
+                receipt(TransactionStatus::Failure),
+            ],
+            errors: vec![String::new(); 3],
+            bloom: Bloom::zero(),
+            traces: vec![],
+            total_gas_limit: U256::zero(),
+        };
+
+        for (idx, available) in [(0, true), (1, false), (2, true)] {
+            assert_eq!(
+                EthApi::block_raw_tx_by_hash_index(phantom_block.clone(), idx),
+                available.then(|| EthApi::raw_transaction(&txs[idx]))
+            );
+            // The block still includes the skipped transaction.
+            assert_eq!(
+                EthApi::block_raw_tx_by_index(Some(phantom_block.clone()), idx),
+                Some(EthApi::raw_transaction(&txs[idx]))
+            );
+        }
+        assert_eq!(EthApi::block_raw_tx_by_hash_index(phantom_block, 3), None);
+    }
+
+    fn new_data_manager(
+        db_dir: &Path, persist_tx_sender_nonce_index: bool,
+    ) -> Arc<BlockDataManager> {
+        let mut config = DataManagerConfiguration::new(
+            true,  /* persist_tx_index */
+            false, /* persist_block_number_index */
+            Duration::from_millis(300_000),
+            DbType::Rocksdb,
+        );
+        config.persist_tx_sender_nonce_index = persist_tx_sender_nonce_index;
+        initialize_data_manager_with_config(
+            db_dir.to_str().unwrap(),
+            config,
+            Arc::new(PowComputer::new(true)),
+            VmFactory::new(1024 * 32),
+        )
+        .0
+    }
+
+    #[test]
+    fn test_tx_hash_by_sender_and_nonce() {
+        let tx = eth_txs(&Random.generate().unwrap()).remove(0);
+
+        let db_dir = tempfile::tempdir().unwrap();
+        let data_man = new_data_manager(db_dir.path(), false);
+        let err: RpcError = EthApi::tx_hash_by_sender_and_nonce(
+            &data_man,
+            &tx.sender,
+            &0.into(),
+        )
+        .unwrap_err()
+        .into();
+        assert_eq!(err.code(), INVALID_PARAMS_CODE);
+        assert!(err.message().contains("persist_tx_sender_nonce_index"));
+        drop(data_man);
+
+        let db_dir = tempfile::tempdir().unwrap();
+        let data_man = new_data_manager(db_dir.path(), true);
+        data_man
+            .insert_tx_hash_by_sender_nonce(&tx, TransactionStatus::Success);
+        assert_eq!(
+            EthApi::tx_hash_by_sender_and_nonce(
+                &data_man,
+                &tx.sender,
+                &0.into()
+            )
+            .unwrap(),
+            Some(tx.hash())
+        );
+        assert_eq!(
+            EthApi::tx_hash_by_sender_and_nonce(
+                &data_man,
+                &tx.sender,
+                &1.into()
+            )
+            .unwrap(),
+            None
+        );
+    }
+}
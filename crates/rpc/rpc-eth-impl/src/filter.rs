@@ -4,6 +4,7 @@ use crate::{
         poll_filter::{
             limit_logs, PollFilter, SyncPollFilter, MAX_BLOCK_HISTORY_SIZE,
         },
+        PendingBlockProvider,
     },
     traits::Filterable,
 };
@@ -31,6 +32,7 @@ impl EthFilterApi {
         consensus: SharedConsensusGraph, tx_pool: SharedTransactionPool,
         epochs_ordered: Arc<Channel<(u64, Vec<H256>)>>, executor: TaskExecutor,
         poll_lifetime: u32, logs_filter_max_limit: Option<usize>,
+        pending_block: PendingBlockProvider,
     ) -> EthFilterApi {
         let eth_filter = EthFilterHelper::new(
             consensus,
@@ -39,6 +41,7 @@ impl EthFilterApi {
             executor,
             poll_lifetime,
             logs_filter_max_limit,
+            pending_block,
         );
         EthFilterApi { inner: eth_filter }
     }
@@ -49,12 +52,7 @@ impl EthFilterApiServer for EthFilterApi {
     async fn new_filter(&self, filter: Filter) -> RpcResult<FilterId> {
         let mut polls = self.inner.polls().lock();
         let epoch_number = self.inner.best_executed_epoch_number();
-
-        if filter.to_block == Some(BlockId::Pending) {
-            bail!(invalid_request_msg(
-                "Filter logs from pending blocks is not supported"
-            ))
-        }
+        let include_pending = filter.to_block == Some(BlockId::Pending);
 
         let filter: LogFilter = self.inner.into_primitive_filter(filter)?;
 
@@ -65,7 +63,8 @@ impl EthFilterApiServer for EthFilterApi {
                 epoch_number - 1
             },
             filter,
-            include_pending: false,
+            include_pending,
+            last_pending_block: None,
             previous_logs: VecDeque::with_capacity(MAX_BLOCK_HISTORY_SIZE),
             recent_reported_epochs: VecDeque::with_capacity(
                 MAX_BLOCK_HISTORY_SIZE,
@@ -164,7 +163,8 @@ impl EthFilterApiServer for EthFilterApi {
                 ref mut recent_reported_epochs,
                 ref mut previous_logs,
                 ref filter,
-                include_pending: _,
+                include_pending,
+                ref mut last_pending_block,
             } => {
                 let (reorg_len, epochs) =
                     self.inner.epochs_since_last_request(
@@ -212,6 +212,16 @@ impl EthFilterApiServer for EthFilterApi {
                     previous_logs.push_front(log);
                 }
 
+                // logs from the pending block, once for each pending block
+                if include_pending {
+                    let (pending_hash, mut pending_logs) =
+                        self.inner.pending_logs(&filter)?;
+                    if *last_pending_block != Some(pending_hash) {
+                        *last_pending_block = Some(pending_hash);
+                        logs.append(&mut pending_logs);
+                    }
+                }
+
                 Ok(FilterChanges::Logs(limit_logs(
                     logs,
                     self.inner.get_logs_filter_max_limit(),
@@ -240,10 +250,11 @@ impl EthFilterApiServer for EthFilterApi {
         };
 
         // retrieve logs
-        Ok(limit_logs(
-            self.inner.logs(filter)?,
-            self.inner.get_logs_filter_max_limit(),
-        ))
+        let mut logs = self.inner.logs(filter.clone())?;
+        if include_pending {
+            logs.append(&mut self.inner.pending_logs(&filter)?.1);
+        }
+        Ok(limit_logs(logs, self.inner.get_logs_filter_max_limit()))
     }
 
     async fn uninstall_filter(&self, id: FilterId) -> RpcResult<bool> {
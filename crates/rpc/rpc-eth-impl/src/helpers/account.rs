@@ -0,0 +1,115 @@
+use super::storage_root_hash;
+use cfx_rpc_eth_types::Account;
+use cfx_rpc_utils::error::jsonrpsee_error_helpers::invalid_params;
+use cfx_storage::{
+    state::{StateDbGetOriginalMethods, StateTrait},
+    StorageState,
+};
+use cfx_types::{AddressSpaceUtil, H160};
+use cfx_util_macros::bail;
+use cfxcore::errors::{Error as CoreError, Result as CoreResult};
+use primitives::{account::EthereumAccount, StorageKey};
+
+/// Check the number of addresses requested by `eth_getMultipleAccounts`.
+pub fn check_multiple_accounts_limit(
+    count: usize, max_limit: usize,
+) -> CoreResult<()> {
+    if count > max_limit {
+        bail!(invalid_params(
+            "addresses",
+            Some(format!(
+                "Too many addresses, max limitation is {}",
+                max_limit
+            ))
+        ));
+    }
+    Ok(())
+}
+
+/// Read the eSpace accounts of `addresses` from `state`, in the same order.
+/// A missing account is `None`.
+pub fn read_accounts(
+    state: &StorageState, addresses: &[H160],
+) -> CoreResult<Vec<Option<Account>>> {
+    addresses
+        .iter()
+        .map(|address| {
+            let key = StorageKey::new_account_key(address).with_evm_space();
+            let account =
+                match state.get(key).map_err(|err| CoreError::from(err))? {
+                    Some(raw) => rlp::decode::<EthereumAccount>(&raw)?,
+                    None => return Ok(None),
+                };
+            let storage_root = state
+                .get_original_storage_root(&address.with_evm_space())
+                .map_err(|err| CoreError::from(err))?;
+
+            Ok(Some(Account {
+                balance: account.balance,
+                nonce: account.nonce,
+                code_hash: account.code_hash,
+                storage_root: storage_root_hash(&storage_root),
+            }))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_multiple_accounts_limit, read_accounts};
+    use crate::helpers::{
+        build_account_proof,
+        proof::tests::{make_state, ACCOUNT, OTHER_ACCOUNT},
+    };
+    use cfx_types::{Address, H160};
+    use jsonrpsee::types::{error::INVALID_PARAMS_CODE, ErrorObjectOwned};
+    use keccak_hash::KECCAK_EMPTY;
+
+    #[test]
+    fn test_read_accounts() {
+        let test_state = make_state();
+        let missing = Address::repeat_byte(0x56);
+
+        let accounts = read_accounts(&test_state.state, &[ACCOUNT]).unwrap();
+        let account = accounts[0].as_ref().unwrap();
+        assert_eq!(account.balance, 5.into());
+        assert_eq!(account.nonce, 1.into());
+        assert_eq!(account.code_hash, KECCAK_EMPTY);
+
+        // The storage root is the `storageHash` of `eth_getProof`.
+        let proof = build_account_proof(
+            &test_state.state,
+            ACCOUNT,
+            vec![],
+            3,
+            Some(test_state.prev_snapshot_state_root.clone()),
+        )
+        .unwrap();
+        assert_eq!(account.storage_root, proof.storage_hash);
+
+        assert_eq!(
+            read_accounts(&test_state.state, &[missing]).unwrap(),
+            vec![None]
+        );
+
+        // The order of the batch is preserved, duplicates included.
+        let batch: Vec<H160> = vec![missing, OTHER_ACCOUNT, ACCOUNT, missing];
+        let accounts = read_accounts(&test_state.state, &batch).unwrap();
+        assert_eq!(accounts.len(), 4);
+        assert_eq!(accounts[0], None);
+        assert_eq!(accounts[1].as_ref().unwrap().balance, 7.into());
+        assert_eq!(accounts[2].as_ref(), Some(account));
+        assert_eq!(accounts[3], None);
+    }
+
+    #[test]
+    fn test_multiple_accounts_limit() {
+        assert!(check_multiple_accounts_limit(0, 2).is_ok());
+        assert!(check_multiple_accounts_limit(2, 2).is_ok());
+
+        let err: ErrorObjectOwned =
+            check_multiple_accounts_limit(3, 2).unwrap_err().into();
+        assert_eq!(err.code(), INVALID_PARAMS_CODE);
+        assert_eq!(err.message(), "Invalid parameters: addresses");
+    }
+}
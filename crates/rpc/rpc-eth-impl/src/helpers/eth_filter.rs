@@ -5,7 +5,10 @@ use std::{
 };
 
 use crate::{
-    helpers::{poll_filter::SyncPollFilter, poll_manager::PollManager},
+    helpers::{
+        poll_filter::SyncPollFilter, poll_manager::PollManager,
+        PendingBlockProvider,
+    },
     traits::Filterable,
 };
 use cfx_rpc_cfx_types::traits::BlockProvider;
@@ -32,6 +35,7 @@ pub struct EthFilterHelper {
     polls: Mutex<PollManager<SyncPollFilter<Log>>>,
     unfinalized_epochs: Arc<RwLock<UnfinalizedEpochs>>,
     logs_filter_max_limit: Option<usize>,
+    pending_block: PendingBlockProvider,
 }
 
 pub struct UnfinalizedEpochs {
@@ -54,6 +58,7 @@ impl EthFilterHelper {
         consensus: SharedConsensusGraph, tx_pool: SharedTransactionPool,
         epochs_ordered: Arc<Channel<(u64, Vec<H256>)>>, executor: TaskExecutor,
         poll_lifetime: u32, logs_filter_max_limit: Option<usize>,
+        pending_block: PendingBlockProvider,
     ) -> Self {
         let filter_client = EthFilterHelper {
             consensus,
@@ -61,6 +66,7 @@ impl EthFilterHelper {
             polls: Mutex::new(PollManager::new(poll_lifetime)),
             unfinalized_epochs: Default::default(),
             logs_filter_max_limit,
+            pending_block,
         };
 
         // start loop to receive epochs, to avoid re-org during filter query
@@ -215,6 +221,14 @@ impl Filterable for EthFilterHelper {
             .map_err(RpcError::from)?)
     }
 
+    fn pending_logs(&self, filter: &LogFilter) -> RpcResult<(H256, Vec<Log>)> {
+        let pending_block = self.pending_block.pending_block()?;
+        Ok((
+            pending_block.block.pivot_header.hash(),
+            pending_block.logs(filter),
+        ))
+    }
+
     fn logs_for_epoch(
         &self, filter: &LogFilter, epoch: (u64, Vec<H256>), removed: bool,
     ) -> RpcResult<Vec<Log>> {
@@ -1,13 +1,24 @@
+mod account;
 mod chain_info;
 pub mod eth_filter;
 mod fee_history_cache;
+mod pending_block;
 pub mod poll_filter;
 pub mod poll_manager;
+mod proof;
+mod simulate;
 mod tx_executor;
 
+pub use account::{check_multiple_accounts_limit, read_accounts};
 pub use cfx_rpc_cfx_impl::helpers::EpochQueue;
 pub use chain_info::ChainInfo;
 pub use fee_history_cache::{
     FeeHistoryCache, MAX_FEE_HISTORY_CACHE_BLOCK_COUNT,
 };
+pub use pending_block::{PendingBlock, PendingBlockProvider};
+pub use proof::{
+    build_account_proof, encode_trie_proofs, storage_root_hash,
+    verify_account_proof, MAX_PROOF_STORAGE_KEYS,
+};
+pub use simulate::{build_simulated_block, call_many_responses};
 pub use tx_executor::TxExecutor;
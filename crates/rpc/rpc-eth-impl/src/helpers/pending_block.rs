@@ -0,0 +1,367 @@
+//! The speculative pending block of the eSpace RPCs. It is assembled from the
+//! ready transactions in the pool, executed on top of the latest state and
+//! rebuilt once a newer epoch is executed, or at a bounded rate to pick up the
+//! changes of the pool.
+
+use cfx_executor::state::{SavedState, State};
+use cfx_parameters::block::{
+    MAX_BLOCK_SIZE_IN_BYTES, MAX_TRANSACTION_COUNT_PER_BLOCK,
+};
+use cfx_rpc_cfx_types::PhantomBlock;
+use cfx_rpc_eth_types::{BlockOverrides, Log};
+use cfx_types::{Address, AddressSpaceUtil, Bloom, Space, H256, U256};
+use cfx_util_macros::bail;
+use cfx_vm_types::Env;
+use cfxcore::{
+    errors::Result as CoreResult, verification::compute_transaction_root,
+    SharedConsensusGraph, SharedTransactionPool,
+};
+use log::debug;
+use parking_lot::Mutex;
+use primitives::{filter::LogFilter, BlockHeaderBuilder, EpochNumber};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// The minimum interval between two builds of the pending block on the same
+/// parent. The pool version changes with nearly every inserted transaction,
+/// so the changes of the pool are picked up at most once per interval.
+const PENDING_BLOCK_MIN_REBUILD_INTERVAL: Duration = Duration::from_secs(1);
+
+/// What a pending block is built from.
+#[derive(Clone, Copy, Debug)]
+struct BuildSource {
+    parent_hash: H256,
+    /// The version of the transaction pool the block is packed from.
+    pool_version: u64,
+    built_at: Instant,
+}
+
+impl BuildSource {
+    /// Whether a block built from this source is outdated at `now`: a newer
+    /// epoch has been executed, or the pool has changed and the block is old
+    /// enough to be rebuilt.
+    fn is_outdated(
+        &self, parent_hash: &H256, pool_version: u64, now: Instant,
+    ) -> bool {
+        self.parent_hash != *parent_hash
+            || (self.pool_version != pool_version
+                && now.saturating_duration_since(self.built_at)
+                    >= PENDING_BLOCK_MIN_REBUILD_INTERVAL)
+    }
+}
+
+/// A pending block and the changes made by its transactions.
+pub struct PendingBlock {
+    pub block: PhantomBlock,
+    env: Env,
+    saved_state: SavedState,
+    source: BuildSource,
+    consensus: SharedConsensusGraph,
+}
+
+impl PendingBlock {
+    /// The pivot block of the epoch the pending block is built on.
+    pub fn parent_hash(&self) -> H256 { self.env.last_hash }
+
+    /// The number of the pending block, i.e. the next epoch number.
+    pub fn number(&self) -> u64 { self.block.pivot_header.height() }
+
+    pub fn balance(&self, address: &Address) -> CoreResult<U256> {
+        Ok(self.state()?.balance(&address.with_evm_space())?)
+    }
+
+    pub fn nonce(&self, address: &Address) -> CoreResult<U256> {
+        Ok(self.state()?.nonce(&address.with_evm_space())?)
+    }
+
+    pub fn code(&self, address: &Address) -> CoreResult<Vec<u8>> {
+        Ok(self
+            .state()?
+            .code(&address.with_evm_space())?
+            .map_or(vec![], |code| (*code).clone()))
+    }
+
+    pub fn storage_at(
+        &self, address: &Address, position: &H256,
+    ) -> CoreResult<U256> {
+        Ok(self
+            .state()?
+            .storage_at(&address.with_evm_space(), position.as_bytes())?)
+    }
+
+    /// Runs `f` with a copy of the state and the environment of the pending
+    /// block.
+    pub fn with_state<R>(
+        &self, f: impl FnOnce(&mut State, &Env) -> CoreResult<R>,
+    ) -> CoreResult<R> {
+        f(&mut self.state()?, &self.env)
+    }
+
+    /// Returns the logs in the pending block matching `filter`, if the
+    /// pending block is in the range of `filter`.
+    pub fn logs(&self, filter: &LogFilter) -> Vec<Log> {
+        if !in_filter_range(filter, self.number()) {
+            return vec![];
+        }
+        Log::from_phantom_block(&self.block, filter)
+    }
+
+    fn state(&self) -> CoreResult<State> {
+        self.consensus
+            .pending_block_state(&self.env.last_hash, &self.saved_state)
+    }
+}
+
+/// Whether the block numbered `number` following the latest executed epoch
+/// is in the range of `filter`. The upper bound is not checked, as the
+/// pending logs are only requested by the filters up to the pending block.
+fn in_filter_range(filter: &LogFilter, number: u64) -> bool {
+    match filter {
+        LogFilter::EpochLogFilter {
+            from_epoch: EpochNumber::Number(from_epoch),
+            ..
+        } => *from_epoch <= number,
+        LogFilter::EpochLogFilter { .. } => true,
+        // The pending block has neither a known hash nor a block number.
+        LogFilter::BlockHashLogFilter { .. }
+        | LogFilter::BlockNumberLogFilter { .. } => false,
+    }
+}
+
+/// Provides the pending block, shared by the eSpace RPC modules.
+#[derive(Clone)]
+pub struct PendingBlockProvider {
+    consensus: SharedConsensusGraph,
+    tx_pool: SharedTransactionPool,
+    pending_block: Arc<Mutex<Option<Arc<PendingBlock>>>>,
+    /// Held while a pending block is built, so concurrent callers wait for
+    /// the same block instead of building it again.
+    build_lock: Arc<Mutex<()>>,
+}
+
+impl PendingBlockProvider {
+    pub fn new(
+        consensus: SharedConsensusGraph, tx_pool: SharedTransactionPool,
+    ) -> Self {
+        PendingBlockProvider {
+            consensus,
+            tx_pool,
+            pending_block: Default::default(),
+            build_lock: Default::default(),
+        }
+    }
+
+    /// Returns the pending block on top of the latest executed epoch. The
+    /// block is rebuilt if a newer epoch has been executed, or if the
+    /// transaction pool has changed and the block was built at least
+    /// `PENDING_BLOCK_MIN_REBUILD_INTERVAL` ago.
+    pub fn pending_block(&self) -> CoreResult<Arc<PendingBlock>> {
+        let epoch_number = self.consensus.best_executed_state_epoch_number();
+        let parent_hash = match self
+            .consensus
+            .get_block_hashes_by_epoch(EpochNumber::Number(epoch_number))?
+            .last()
+        {
+            Some(hash) => *hash,
+            None => bail!("Inconsistent state: empty epoch"),
+        };
+        if let Some(block) = self.cached(&parent_hash) {
+            return Ok(block);
+        }
+
+        let _build_lock = self.build_lock.lock();
+        // The block may have been built while waiting for the lock.
+        if let Some(block) = self.cached(&parent_hash) {
+            return Ok(block);
+        }
+        let block = Arc::new(self.build(epoch_number, parent_hash)?);
+        debug!(
+            "Pending block rebuilt, parent={:?} transactions={}",
+            parent_hash,
+            block.block.transactions.len()
+        );
+        *self.pending_block.lock() = Some(block.clone());
+        Ok(block)
+    }
+
+    fn cached(&self, parent_hash: &H256) -> Option<Arc<PendingBlock>> {
+        let pool_version = self.tx_pool.version();
+        self.pending_block
+            .lock()
+            .as_ref()
+            .filter(|block| {
+                !block.source.is_outdated(
+                    parent_hash,
+                    pool_version,
+                    Instant::now(),
+                )
+            })
+            .cloned()
+    }
+
+    fn build(
+        &self, epoch_number: u64, parent_hash: H256,
+    ) -> CoreResult<PendingBlock> {
+        let parent = match self
+            .consensus
+            .data_manager()
+            .block_header_by_hash(&parent_hash)
+        {
+            Some(header) => header,
+            None => bail!("Parent block {:?} not found", parent_hash),
+        };
+        // Read before packing, so the changes made while packing are picked
+        // up by the next build.
+        let source = BuildSource {
+            parent_hash,
+            pool_version: self.tx_pool.version(),
+            built_at: Instant::now(),
+        };
+        let machine = self.tx_pool.machine();
+        let parent_base_price = parent
+            .base_price()
+            .unwrap_or_else(|| machine.params().init_base_price());
+
+        let (transactions, base_price) = self.tx_pool.pack_transactions_1559(
+            MAX_TRANSACTION_COUNT_PER_BLOCK,
+            *parent.gas_limit(),
+            parent_base_price,
+            MAX_BLOCK_SIZE_IN_BYTES,
+            epoch_number,
+            self.consensus.best_info().best_block_number,
+        );
+        let transactions = transactions
+            .into_iter()
+            .filter(|tx| tx.space() == Space::Ethereum)
+            .collect();
+
+        let execution = self.consensus.execute_pending_block(
+            EpochNumber::Number(epoch_number),
+            transactions,
+            BlockOverrides {
+                base_fee: Some(base_price[Space::Ethereum]),
+                ..Default::default()
+            },
+        )?;
+
+        let header = BlockHeaderBuilder::new()
+            .with_parent_hash(parent_hash)
+            .with_height(execution.env.epoch_height)
+            .with_timestamp(execution.env.timestamp)
+            .with_author(execution.env.author)
+            .with_transactions_root(compute_transaction_root(
+                &execution.transactions,
+            ))
+            .with_gas_limit(*parent.gas_limit())
+            .with_base_price(Some(base_price))
+            .build();
+        let bloom = execution.receipts.iter().fold(
+            Bloom::zero(),
+            |mut bloom, receipt| {
+                bloom.accrue_bloom(&receipt.log_bloom);
+                bloom
+            },
+        );
+        let total_gas_limit = header.espace_gas_limit(true);
+
+        Ok(PendingBlock {
+            block: PhantomBlock {
+                pivot_header: header,
+                transactions: execution.transactions,
+                receipts: execution.receipts,
+                errors: execution.errors,
+                bloom,
+                traces: vec![],
+                total_gas_limit,
+            },
+            env: execution.env,
+            saved_state: execution.saved_state,
+            source,
+            consensus: self.consensus.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        in_filter_range, BuildSource, PENDING_BLOCK_MIN_REBUILD_INTERVAL,
+    };
+    use cfx_types::H256;
+    use primitives::{
+        filter::{LogFilter, LogFilterParams},
+        EpochNumber,
+    };
+    use std::time::{Duration, Instant};
+
+    fn epoch_filter(from_epoch: EpochNumber) -> LogFilter {
+        LogFilter::EpochLogFilter {
+            from_epoch,
+            to_epoch: EpochNumber::LatestState,
+            params: LogFilterParams::default(),
+        }
+    }
+
+    #[test]
+    fn test_in_filter_range() {
+        assert!(in_filter_range(&epoch_filter(EpochNumber::Number(9)), 10));
+        assert!(in_filter_range(&epoch_filter(EpochNumber::Number(10)), 10));
+        assert!(!in_filter_range(&epoch_filter(EpochNumber::Number(11)), 10));
+        // `latest` and the other tags are before the pending block.
+        assert!(in_filter_range(&epoch_filter(EpochNumber::LatestState), 10));
+        assert!(in_filter_range(&epoch_filter(EpochNumber::Earliest), 10));
+
+        let block_hash_filter = LogFilter::BlockHashLogFilter {
+            block_hashes: vec![H256::zero()],
+            params: LogFilterParams::default(),
+        };
+        assert!(!in_filter_range(&block_hash_filter, 10));
+    }
+
+    #[test]
+    fn test_rebuild_rate_with_changing_pool() {
+        let start = Instant::now();
+        let parent_hash = H256::repeat_byte(1);
+        let mut source = BuildSource {
+            parent_hash,
+            pool_version: 0,
+            built_at: start,
+        };
+
+        // A transaction is inserted every 10ms, and the pending block is
+        // requested after each insertion.
+        let step = Duration::from_millis(10);
+        let mut builds = 0;
+        for i in 1..=500u32 {
+            let now = start + step * i;
+            let pool_version = i as u64;
+            if source.is_outdated(&parent_hash, pool_version, now) {
+                assert!(
+                    now - source.built_at >= PENDING_BLOCK_MIN_REBUILD_INTERVAL
+                );
+                source = BuildSource {
+                    parent_hash,
+                    pool_version,
+                    built_at: now,
+                };
+                builds += 1;
+            }
+        }
+        let max_builds = (step * 500).as_millis()
+            / PENDING_BLOCK_MIN_REBUILD_INTERVAL.as_millis();
+        assert!(builds > 0 && builds as u128 <= max_builds);
+
+        // An unchanged pool never rebuilds the block on the same parent.
+        let later = source.built_at + PENDING_BLOCK_MIN_REBUILD_INTERVAL * 10;
+        assert!(!source.is_outdated(&parent_hash, source.pool_version, later));
+
+        // A new parent rebuilds the block at once.
+        assert!(source.is_outdated(
+            &H256::repeat_byte(2),
+            source.pool_version,
+            source.built_at
+        ));
+    }
+}
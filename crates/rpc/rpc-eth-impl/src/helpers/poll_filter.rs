@@ -63,6 +63,8 @@ pub enum PollFilter<T> {
         previous_logs: VecDeque<Vec<T>>,
         filter: LogFilter,
         include_pending: bool,
+        /// The pending block whose logs have been reported.
+        last_pending_block: Option<H256>,
     },
 }
 
@@ -0,0 +1,588 @@
+use cfx_rpc_eth_types::{EIP1186AccountProofResponse, EIP1186StorageProof};
+use cfx_rpc_primitives::Bytes;
+use cfx_storage::{
+    state::{StateDbGetOriginalMethods, StateTrait},
+    NodeMerkleProof, StateProof, StorageState, TrieProof,
+};
+use cfx_types::{Address, AddressSpaceUtil, BigEndianHash, H256, U256};
+use cfxcore::errors::{Error as CoreError, Result as CoreResult};
+use keccak_hash::{keccak, KECCAK_EMPTY};
+use primitives::{
+    account::EthereumAccount, DeltaMptKeyPadding, StateRoot, StorageKey,
+    StorageKeyWithSpace, StorageRoot, StorageValue,
+};
+
+/// The maximum number of storage keys that can be proved in one
+/// `eth_getProof` call.
+pub const MAX_PROOF_STORAGE_KEYS: usize = 1024;
+
+/// Encode the proofs of the three MPTs as `[delta, intermediate, snapshot]`.
+pub fn encode_trie_proofs(
+    delta: Option<TrieProof>, intermediate: Option<TrieProof>,
+    snapshot: Option<TrieProof>,
+) -> Vec<Bytes> {
+    [delta, intermediate, snapshot]
+        .into_iter()
+        .map(|maybe_proof| {
+            Bytes::new(
+                maybe_proof.map_or_else(Vec::new, |p| rlp::encode(&p).to_vec()),
+            )
+        })
+        .collect()
+}
+
+fn decode_trie_proofs(
+    proofs: &[Bytes],
+) -> Result<(Option<TrieProof>, Option<TrieProof>, Option<TrieProof>), String> {
+    if proofs.len() != 3 {
+        return Err(format!(
+            "Expect 3 trie proofs (delta, intermediate, snapshot), got {}",
+            proofs.len()
+        ));
+    }
+
+    let decode = |raw: &Bytes| -> Result<Option<TrieProof>, String> {
+        if raw.0.is_empty() {
+            return Ok(None);
+        }
+        rlp::decode(&raw.0)
+            .map(Some)
+            .map_err(|e| format!("Invalid trie proof: {:?}", e))
+    };
+
+    Ok((
+        decode(&proofs[0])?,
+        decode(&proofs[1])?,
+        decode(&proofs[2])?,
+    ))
+}
+
+fn decode_state_proof(proofs: &[Bytes]) -> Result<StateProof, String> {
+    let (delta, intermediate, snapshot) = decode_trie_proofs(proofs)?;
+    let mut proof = StateProof::default();
+    proof
+        .with_delta(delta)
+        .with_intermediate(intermediate)
+        .with_snapshot(snapshot);
+    Ok(proof)
+}
+
+fn decode_node_merkle_proof(
+    proofs: &[Bytes],
+) -> Result<NodeMerkleProof, String> {
+    let (delta, intermediate, snapshot) = decode_trie_proofs(proofs)?;
+    let mut proof = NodeMerkleProof::default();
+    proof
+        .with_delta(delta)
+        .with_intermediate(intermediate)
+        .with_snapshot(snapshot);
+    Ok(proof)
+}
+
+/// Build the `eth_getProof` response of `address` and the storage slots
+/// `keys` from `state`, the state after executing `block_number`.
+/// `prev_snapshot_state_root` is the state root one snapshot period earlier,
+/// if the block is not in the first snapshot period.
+pub fn build_account_proof(
+    state: &StorageState, address: Address, keys: Vec<U256>, block_number: u64,
+    prev_snapshot_state_root: Option<StateRoot>,
+) -> CoreResult<EIP1186AccountProofResponse> {
+    let state_root = state
+        .get_state_root()
+        .map_err(|err| CoreError::from(err))?
+        .state_root;
+
+    let account_key = StorageKey::new_account_key(&address).with_evm_space();
+    let (maybe_account, account_proof) = state
+        .get_original_raw_with_proof(account_key)
+        .map_err(|err| CoreError::from(err))?;
+    let account = match maybe_account {
+        Some(raw) => rlp::decode::<EthereumAccount>(&raw)?,
+        None => EthereumAccount {
+            balance: U256::zero(),
+            nonce: U256::zero(),
+            code_hash: KECCAK_EMPTY,
+        },
+    };
+
+    let (storage_root, storage_root_proof) = state
+        .get_original_storage_root_with_proof(&address.with_evm_space())
+        .map_err(|err| CoreError::from(err))?;
+
+    let mut storage_proof = Vec::with_capacity(keys.len());
+    for position in keys {
+        let position: H256 = H256::from_uint(&position);
+        let key = StorageKey::new_storage_key(&address, position.as_ref())
+            .with_evm_space();
+        let (maybe_value, proof) = state
+            .get_original_raw_with_proof(key)
+            .map_err(|err| CoreError::from(err))?;
+        let value = match maybe_value {
+            Some(raw) => rlp::decode::<StorageValue>(&raw)?.value,
+            None => U256::zero(),
+        };
+        storage_proof.push(EIP1186StorageProof {
+            key: position,
+            value,
+            proof: encode_trie_proofs(
+                proof.delta_proof,
+                proof.intermediate_proof,
+                proof.snapshot_proof,
+            ),
+        });
+    }
+
+    Ok(EIP1186AccountProofResponse {
+        address,
+        balance: account.balance,
+        code_hash: account.code_hash,
+        nonce: account.nonce,
+        storage_hash: storage_root_hash(&storage_root),
+        account_proof: encode_trie_proofs(
+            account_proof.delta_proof,
+            account_proof.intermediate_proof,
+            account_proof.snapshot_proof,
+        ),
+        storage_proof,
+        storage_root: Bytes::new(rlp::encode(&storage_root).to_vec()),
+        storage_root_proof: encode_trie_proofs(
+            storage_root_proof.delta_proof,
+            storage_root_proof.intermediate_proof,
+            storage_root_proof.snapshot_proof,
+        ),
+        block_number: block_number.into(),
+        state_root,
+        prev_snapshot_state_root,
+    })
+}
+
+/// `storageHash` reported for a storage root triplet.
+pub fn storage_root_hash(storage_root: &StorageRoot) -> H256 {
+    keccak(rlp::encode(storage_root))
+}
+
+fn verify_entry(
+    proof: &StateProof, key: &Vec<u8>, value: Option<&[u8]>,
+    state_root: &StateRoot,
+    maybe_intermediate_padding: &Option<DeltaMptKeyPadding>,
+) -> bool {
+    proof.is_valid_kv(
+        key,
+        value,
+        state_root.clone(),
+        maybe_intermediate_padding.clone(),
+    )
+}
+
+/// Verify an `eth_getProof` response against the `stateRoot` field (i.e.
+/// the deferred state root) of the header that commits the state of
+/// `proof.block_number`, which is the header at height
+/// `proof.block_number + DEFERRED_STATE_EPOCH_COUNT`.
+///
+/// The previous snapshot state root carried in the response derives the key
+/// padding of the intermediate MPT, so it must be checked against
+/// `prev_snapshot_deferred_state_root`, the deferred state root of the block
+/// one snapshot period earlier. Both are absent in the first snapshot period.
+pub fn verify_account_proof(
+    proof: &EIP1186AccountProofResponse, deferred_state_root: &H256,
+    prev_snapshot_deferred_state_root: Option<&H256>,
+) -> Result<(), String> {
+    let state_root = &proof.state_root;
+    if state_root.compute_state_root_hash() != *deferred_state_root {
+        return Err("State root does not match the deferred state root".into());
+    }
+
+    match (
+        &proof.prev_snapshot_state_root,
+        prev_snapshot_deferred_state_root,
+    ) {
+        (Some(root), Some(expected))
+            if root.compute_state_root_hash() != *expected =>
+        {
+            return Err("Previous snapshot state root mismatch".into());
+        }
+        (None, Some(_)) => {
+            return Err("Previous snapshot state root is missing".into());
+        }
+        (Some(_), None) => {
+            return Err(
+                "Previous snapshot state root cannot be verified without \
+                 the expected root"
+                    .into(),
+            );
+        }
+        _ => {}
+    }
+
+    let maybe_intermediate_padding =
+        proof.prev_snapshot_state_root.as_ref().map(|root| {
+            StorageKeyWithSpace::delta_mpt_padding(
+                &root.snapshot_root,
+                &root.intermediate_delta_root,
+            )
+        });
+
+    let address: &Address = &proof.address;
+
+    // account
+    let account_key = StorageKey::new_account_key(address)
+        .with_evm_space()
+        .to_key_bytes();
+    let is_empty_account = proof.balance.is_zero()
+        && proof.nonce.is_zero()
+        && proof.code_hash == KECCAK_EMPTY;
+    let account = rlp::encode(&EthereumAccount {
+        balance: proof.balance,
+        nonce: proof.nonce,
+        code_hash: proof.code_hash,
+    })
+    .to_vec();
+    let account_proof = decode_state_proof(&proof.account_proof)?;
+    // An empty account may also be proved by its non-existence.
+    if !verify_entry(
+        &account_proof,
+        &account_key,
+        Some(&account),
+        state_root,
+        &maybe_intermediate_padding,
+    ) && !(is_empty_account
+        && verify_entry(
+            &account_proof,
+            &account_key,
+            None,
+            state_root,
+            &maybe_intermediate_padding,
+        ))
+    {
+        return Err("Invalid account proof".into());
+    }
+
+    // storage root
+    let storage_root: StorageRoot = rlp::decode(&proof.storage_root.0)
+        .map_err(|e| format!("Invalid storage root: {:?}", e))?;
+    if storage_root_hash(&storage_root) != proof.storage_hash {
+        return Err("Storage hash does not match the storage root".into());
+    }
+    let storage_root_key = StorageKey::new_storage_root_key(address)
+        .with_evm_space()
+        .to_key_bytes();
+    if !decode_node_merkle_proof(&proof.storage_root_proof)?.is_valid(
+        &storage_root_key,
+        &storage_root,
+        state_root.clone(),
+        maybe_intermediate_padding.clone(),
+    ) {
+        return Err("Invalid storage root proof".into());
+    }
+
+    // storage entries
+    for EIP1186StorageProof {
+        key,
+        value,
+        proof: entry_proof,
+    } in &proof.storage_proof
+    {
+        let storage_key = StorageKey::new_storage_key(address, key.as_ref())
+            .with_evm_space()
+            .to_key_bytes();
+        // Zero-valued slots are deleted from the state.
+        let encoded = (!value.is_zero()).then(|| {
+            rlp::encode(&StorageValue {
+                value: *value,
+                owner: None,
+            })
+            .to_vec()
+        });
+        if !verify_entry(
+            &decode_state_proof(entry_proof)?,
+            &storage_key,
+            encoded.as_deref(),
+            state_root,
+            &maybe_intermediate_padding,
+        ) {
+            return Err(format!("Invalid storage proof for key {:?}", key));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+    use cfx_storage::{
+        state_manager::StateManagerTrait,
+        tests::{
+            new_state_manager_for_unit_test_with_snapshot_epoch_count,
+            FakeStateManager,
+        },
+        StateIndex,
+    };
+    use primitives::MERKLE_NULL_NODE;
+
+    pub(crate) const ACCOUNT: Address = Address::repeat_byte(0x12);
+    pub(crate) const OTHER_ACCOUNT: Address = Address::repeat_byte(0x34);
+
+    /// The state of the fourth epoch, with one epoch per snapshot period, so
+    /// that the snapshot, intermediate and delta MPTs are all non-empty.
+    pub(crate) struct TestState {
+        // The state manager must outlive the state.
+        _manager: FakeStateManager,
+        pub(crate) state: StorageState,
+        pub(crate) prev_snapshot_state_root: StateRoot,
+    }
+
+    fn set_account(
+        state: &mut dyn StateTrait, address: &Address, balance: u64, nonce: u64,
+    ) {
+        let account = EthereumAccount {
+            balance: balance.into(),
+            nonce: nonce.into(),
+            code_hash: KECCAK_EMPTY,
+        };
+        state
+            .set(
+                StorageKey::new_account_key(address).with_evm_space(),
+                rlp::encode(&account).to_vec().into(),
+            )
+            .unwrap();
+    }
+
+    fn set_slot(
+        state: &mut dyn StateTrait, address: &Address, slot: u64, value: u64,
+    ) {
+        let position = H256::from_low_u64_be(slot);
+        let value = StorageValue {
+            value: value.into(),
+            owner: None,
+        };
+        state
+            .set(
+                StorageKey::new_storage_key(address, position.as_ref())
+                    .with_evm_space(),
+                rlp::encode(&value).to_vec().into(),
+            )
+            .unwrap();
+    }
+
+    pub(crate) fn make_state() -> TestState {
+        let snapshot_epoch_count = 1;
+        let manager = new_state_manager_for_unit_test_with_snapshot_epoch_count(
+            snapshot_epoch_count,
+        );
+
+        let mut state = manager.get_state_for_genesis_write();
+        set_account(&mut *state, &ACCOUNT, 1, 0);
+        set_slot(&mut *state, &ACCOUNT, 1, 10);
+        set_slot(&mut *state, &ACCOUNT, 2, 20);
+        let mut epoch_id = H256::from_low_u64_be(1);
+        let mut root = state.compute_state_root().unwrap();
+        state.commit(epoch_id).unwrap();
+
+        let epochs: [&dyn Fn(&mut dyn StateTrait); 2] = [
+            &|state| {
+                set_slot(state, &ACCOUNT, 2, 21);
+                set_slot(state, &ACCOUNT, 3, 30);
+                set_account(state, &OTHER_ACCOUNT, 7, 0);
+            },
+            &|state| {
+                set_account(state, &ACCOUNT, 5, 1);
+                set_slot(state, &ACCOUNT, 4, 40);
+            },
+        ];
+        let mut height = 1;
+        for update in epochs {
+            let mut state = manager
+                .get_state_for_next_epoch_inner(
+                    StateIndex::new_for_next_epoch(
+                        &epoch_id,
+                        &root,
+                        height,
+                        snapshot_epoch_count,
+                    ),
+                    true,
+                    false,
+                )
+                .unwrap()
+                .unwrap();
+            update(&mut state);
+            epoch_id = H256::from_low_u64_be(height + 1);
+            root = state.compute_state_root().unwrap();
+            state.commit(epoch_id).unwrap();
+            height += 1;
+        }
+
+        let mut state = manager
+            .get_state_for_next_epoch_inner(
+                StateIndex::new_for_next_epoch(
+                    &epoch_id,
+                    &root,
+                    height,
+                    snapshot_epoch_count,
+                ),
+                true,
+                false,
+            )
+            .unwrap()
+            .unwrap();
+        set_slot(&mut state, &ACCOUNT, 5, 50);
+        state.compute_state_root().unwrap();
+
+        TestState {
+            _manager: manager,
+            state,
+            prev_snapshot_state_root: root.state_root,
+        }
+    }
+
+    fn make_proof(test_state: &TestState) -> EIP1186AccountProofResponse {
+        build_account_proof(
+            &test_state.state,
+            ACCOUNT,
+            (1..=6).map(U256::from).collect(),
+            3,
+            Some(test_state.prev_snapshot_state_root.clone()),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_verify_account_proof() {
+        let test_state = make_state();
+        let proof = make_proof(&test_state);
+        let state_root = &proof.state_root;
+
+        // all three tries are involved
+        assert_ne!(state_root.snapshot_root, MERKLE_NULL_NODE);
+        assert_ne!(state_root.intermediate_delta_root, MERKLE_NULL_NODE);
+        assert_ne!(state_root.delta_root, MERKLE_NULL_NODE);
+
+        assert_eq!(proof.balance, 5.into());
+        assert_eq!(proof.nonce, 1.into());
+        let values: Vec<_> =
+            proof.storage_proof.iter().map(|p| p.value).collect();
+        let expected: Vec<U256> = [10, 21, 30, 40, 50, 0]
+            .into_iter()
+            .map(U256::from)
+            .collect();
+        assert_eq!(values, expected);
+
+        let deferred_state_root = state_root.compute_state_root_hash();
+        let prev_root = test_state
+            .prev_snapshot_state_root
+            .compute_state_root_hash();
+        verify_account_proof(&proof, &deferred_state_root, Some(&prev_root))
+            .unwrap();
+
+        // A missing account is proved by its non-existence.
+        let missing = build_account_proof(
+            &test_state.state,
+            Address::repeat_byte(0x56),
+            vec![U256::one()],
+            3,
+            Some(test_state.prev_snapshot_state_root.clone()),
+        )
+        .unwrap();
+        assert_eq!(missing.code_hash, KECCAK_EMPTY);
+        verify_account_proof(&missing, &deferred_state_root, Some(&prev_root))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_reject_tampered_proof() {
+        let test_state = make_state();
+        let proof = make_proof(&test_state);
+        let deferred_state_root = proof.state_root.compute_state_root_hash();
+        let prev_root = test_state
+            .prev_snapshot_state_root
+            .compute_state_root_hash();
+        let verify = |proof: &EIP1186AccountProofResponse| {
+            verify_account_proof(proof, &deferred_state_root, Some(&prev_root))
+        };
+
+        let mut tampered = proof.clone();
+        tampered.balance += U256::one();
+        assert_eq!(verify(&tampered), Err("Invalid account proof".into()));
+
+        for i in 0..proof.storage_proof.len() {
+            let mut tampered = proof.clone();
+            tampered.storage_proof[i].value += U256::one();
+            assert!(verify(&tampered)
+                .unwrap_err()
+                .starts_with("Invalid storage proof"));
+        }
+
+        // A slot of the older tries can't be proved with its stale value.
+        let mut stale = proof.clone();
+        stale.storage_proof[1].value = 20.into();
+        assert!(verify(&stale).is_err());
+
+        let mut tampered = proof.clone();
+        tampered.storage_hash = H256::zero();
+        assert!(verify(&tampered).is_err());
+    }
+
+    #[test]
+    fn test_reject_forged_prev_snapshot_state_root() {
+        let test_state = make_state();
+        let proof = make_proof(&test_state);
+        let deferred_state_root = proof.state_root.compute_state_root_hash();
+        let prev_root = test_state
+            .prev_snapshot_state_root
+            .compute_state_root_hash();
+
+        let mut forged = proof.clone();
+        let forged_root = forged.prev_snapshot_state_root.as_mut().unwrap();
+        forged_root.intermediate_delta_root = H256::repeat_byte(0xab);
+
+        assert_eq!(
+            verify_account_proof(
+                &forged,
+                &deferred_state_root,
+                Some(&prev_root)
+            ),
+            Err("Previous snapshot state root mismatch".into())
+        );
+
+        // The root carried in the response can't be trusted on its own.
+        assert!(
+            verify_account_proof(&forged, &deferred_state_root, None).is_err()
+        );
+        assert!(
+            verify_account_proof(&proof, &deferred_state_root, None).is_err()
+        );
+
+        let mut removed = proof.clone();
+        removed.prev_snapshot_state_root = None;
+        assert!(verify_account_proof(
+            &removed,
+            &deferred_state_root,
+            Some(&prev_root)
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_reject_mismatched_state_root() {
+        let proof = EIP1186AccountProofResponse::default();
+        let expected = proof.state_root.compute_state_root_hash();
+
+        assert!(verify_account_proof(&proof, &H256::zero(), None).is_err());
+
+        // The default response carries no trie proofs at all.
+        let err = verify_account_proof(&proof, &expected, None).unwrap_err();
+        assert!(err.contains("Expect 3 trie proofs"));
+    }
+
+    #[test]
+    fn test_trie_proofs_round_trip() {
+        let encoded = encode_trie_proofs(None, None, None);
+        assert_eq!(encoded.len(), 3);
+        assert!(encoded.iter().all(|p| p.0.is_empty()));
+        assert_eq!(
+            decode_state_proof(&encoded).unwrap(),
+            StateProof::default()
+        );
+        assert!(decode_state_proof(&encoded[..2]).is_err());
+    }
+}
@@ -0,0 +1,546 @@
+//! Conversions of the outcomes of the simulated blocks into the responses of
+//! `eth_simulateV1` and `eth_callMany`.
+
+use crate::helpers::TxExecutor;
+use cfx_execute_helper::exec_tracer::{Action, ExecTrace, ExecTraceKey};
+use cfx_executor::executive::{ExecutionError, ExecutionOutcome};
+use cfx_rpc_eth_types::{
+    Block, BlockTransactions, EthCallResponse, Header, Log, LogData,
+    SimCallResult, SimulateError, SimulatedBlock, Transaction,
+};
+use cfx_rpc_utils::error::{
+    error_codes::CALL_EXECUTION_ERROR,
+    jsonrpsee_error_helpers::geth_call_execution_error,
+};
+use cfx_types::{Address, Space, H256, U256};
+use cfx_util_macros::bail;
+use cfx_vm_types::{CallType, Error as VmError};
+use cfxcore::{consensus::SimulatedBlockOutcome, errors::Result as CoreResult};
+use jsonrpsee::types::ErrorObjectOwned as RpcError;
+use keccak_hash::{keccak, KECCAK_EMPTY_LIST_RLP, KECCAK_NULL_RLP};
+use primitives::{
+    log_entry::build_bloom, receipt::EVM_SPACE_SUCCESS, LogEntry,
+};
+use rlp::RlpStream;
+use rustc_hex::ToHex;
+use solidity_abi::string_revert_reason_decode;
+
+/// The pseudo address the native token transfers are logged from when
+/// `traceTransfers` is enabled, the same as geth.
+const TRANSFER_LOG_ADDRESS: Address = Address::repeat_byte(0xee);
+
+/// Converts the outcomes of the calls in an `eth_callMany` bundle.
+pub fn call_many_responses(
+    outcome: SimulatedBlockOutcome,
+) -> Vec<EthCallResponse> {
+    outcome
+        .transactions
+        .into_iter()
+        .map(|(_, outcome)| {
+            match TxExecutor::parse_execution_outcome(outcome) {
+                Ok(executed) => EthCallResponse {
+                    value: Some(executed.output.into()),
+                    error: None,
+                },
+                Err(e) => EthCallResponse {
+                    value: None,
+                    error: Some(RpcError::from(e).message().to_string()),
+                },
+            }
+        })
+        .collect()
+}
+
+/// Builds the block returned by `eth_simulateV1` from a simulated block. The
+/// hash of the block is derived from its parent, its number, its timestamp
+/// and its transactions, as the simulated blocks are never sealed.
+pub fn build_simulated_block(
+    outcome: SimulatedBlockOutcome, parent_hash: H256, trace_transfers: bool,
+    full_transactions: bool,
+) -> CoreResult<SimulatedBlock> {
+    let env = outcome.env;
+    let number = env.epoch_height;
+
+    let mut stream = RlpStream::new_list(4);
+    stream.append(&parent_hash);
+    stream.append(&number);
+    stream.append(&env.timestamp);
+    stream.append_list(
+        &outcome
+            .transactions
+            .iter()
+            .map(|(tx, _)| tx.hash())
+            .collect::<Vec<_>>(),
+    );
+    let block_hash = keccak(stream.out());
+
+    let mut calls = Vec::with_capacity(outcome.transactions.len());
+    let mut hashes = Vec::with_capacity(outcome.transactions.len());
+    let mut full_txs = vec![];
+    let mut block_logs = vec![];
+    let mut gas_used = U256::zero();
+    let mut size = 0;
+    for (index, (tx, outcome)) in outcome.transactions.into_iter().enumerate() {
+        let (executed, error) = match outcome {
+            ExecutionOutcome::Finished(executed) => (executed, None),
+            ExecutionOutcome::ExecutionErrorBumpNonce(
+                ExecutionError::VmError(e),
+                executed,
+            ) => {
+                let error = match e {
+                    VmError::Reverted => {
+                        let e = geth_call_execution_error(
+                            format!(
+                                "execution reverted: revert: {}",
+                                string_revert_reason_decode(&executed.output)
+                            ),
+                            format!("0x{}", executed.output.to_hex::<String>()),
+                        );
+                        SimulateError {
+                            code: e.code(),
+                            message: e.message().to_string(),
+                        }
+                    }
+                    e => SimulateError {
+                        code: CALL_EXECUTION_ERROR as i32,
+                        message: e.to_string(),
+                    },
+                };
+                (executed, Some(error))
+            }
+            // The calls which can not be executed fail the whole request.
+            outcome @ (ExecutionOutcome::NotExecutedDrop(_)
+            | ExecutionOutcome::NotExecutedToReconsiderPacking(_)
+            | ExecutionOutcome::ExecutionErrorBumpNonce(
+                ExecutionError::NotEnoughCash { .. }
+                | ExecutionError::NonceOverflow(_),
+                _,
+            )) => match TxExecutor::parse_execution_outcome(outcome) {
+                Err(e) => return Err(e),
+                Ok(_) => bail!("Inconsistent state: the call is not executed"),
+            },
+        };
+        let success = error.is_none();
+
+        let mut logs = if trace_transfers {
+            transfer_logs(executed.ext_result.get::<ExecTraceKey>())
+        } else {
+            vec![]
+        };
+        logs.extend(executed.logs);
+
+        let log_base = block_logs.len();
+        let rpc_logs = logs
+            .iter()
+            .enumerate()
+            .map(|(log_index, log)| Log {
+                inner: LogData {
+                    address: log.address,
+                    topics: log.topics.clone(),
+                    data: log.data.clone().into(),
+                },
+                block_hash,
+                block_number: number.into(),
+                transaction_hash: tx.hash(),
+                transaction_index: index.into(),
+                log_index: Some((log_base + log_index).into()),
+                transaction_log_index: Some(log_index.into()),
+                block_timestamp: Some(env.timestamp.into()),
+                removed: false,
+            })
+            .collect();
+        block_logs.extend(logs);
+
+        gas_used += executed.gas_used;
+        size += tx.rlp_size();
+        calls.push(SimCallResult {
+            return_data: executed.output.into(),
+            logs: rpc_logs,
+            gas_used: executed.gas_used.as_u64().into(),
+            status: (success as u64).into(),
+            error,
+        });
+
+        if full_transactions {
+            let contract_address =
+                match Transaction::deployed_contract_address(&tx) {
+                    Some(address) if success => Some(address),
+                    _ => None,
+                };
+            let status = if success { EVM_SPACE_SUCCESS } else { 0 };
+            full_txs.push(Transaction::from_signed(
+                &tx,
+                (Some(block_hash), Some(number.into()), Some(index.into())),
+                (Some(status.into()), contract_address),
+            ));
+        }
+        hashes.push(tx.hash());
+    }
+
+    let transactions = if full_transactions {
+        BlockTransactions::Full(full_txs)
+    } else {
+        BlockTransactions::Hashes(hashes)
+    };
+    let (transactions_root, receipts_root) = if calls.is_empty() {
+        (KECCAK_NULL_RLP, KECCAK_NULL_RLP)
+    } else {
+        (H256::zero(), H256::zero())
+    };
+
+    let header = Header {
+        hash: block_hash,
+        parent_hash,
+        uncles_hash: KECCAK_EMPTY_LIST_RLP,
+        author: env.author,
+        miner: env.author,
+        state_root: H256::zero(),
+        transactions_root,
+        receipts_root,
+        number: number.into(),
+        gas_used,
+        gas_limit: env.gas_limit,
+        espace_gas_limit: env.gas_limit,
+        extra_data: Default::default(),
+        logs_bloom: build_bloom(&block_logs),
+        timestamp: env.timestamp.into(),
+        difficulty: env.difficulty,
+        total_difficulty: U256::zero(),
+        base_fee_per_gas: Some(env.base_gas_price[Space::Ethereum]),
+        size: size.into(),
+        nonce: Default::default(),
+        mix_hash: H256::zero(),
+    };
+
+    Ok(SimulatedBlock {
+        inner: Block {
+            header,
+            transactions,
+            uncles: vec![],
+        },
+        calls,
+    })
+}
+
+/// Logs the native token transfers of a transaction as ERC-20 `Transfer`
+/// events emitted by [`TRANSFER_LOG_ADDRESS`], in the order the transfers
+/// happen. The transfers of the reverted frames are skipped.
+fn transfer_logs(traces: Option<&Vec<ExecTrace>>) -> Vec<LogEntry> {
+    let Some(traces) = traces else {
+        return vec![];
+    };
+
+    let mut logs = vec![];
+    // The positions of the logs of the pending creations, whose recipient
+    // is only known when the creation returns.
+    let mut pending_creates = vec![];
+    for trace in traces.iter().filter(|trace| trace.valid) {
+        match &trace.action {
+            Action::Call(call)
+                if call.call_type == CallType::Call
+                    && !call.value.is_zero() =>
+            {
+                logs.push(transfer_log(call.from, call.to, call.value));
+            }
+            Action::Create(create) => {
+                pending_creates.push((!create.value.is_zero()).then(|| {
+                    logs.push(transfer_log(
+                        create.from,
+                        Address::zero(),
+                        create.value,
+                    ));
+                    logs.len() - 1
+                }));
+            }
+            Action::CreateResult(result) => {
+                if let Some(Some(position)) = pending_creates.pop() {
+                    logs[position].topics[2] = address_topic(result.addr);
+                }
+            }
+            Action::SelfDestruct(selfdestruct)
+                if !selfdestruct.balance.is_zero() =>
+            {
+                logs.push(transfer_log(
+                    selfdestruct.address,
+                    selfdestruct.refund_address,
+                    selfdestruct.balance,
+                ));
+            }
+            _ => {}
+        }
+    }
+    logs
+}
+
+fn transfer_log(from: Address, to: Address, value: U256) -> LogEntry {
+    LogEntry {
+        address: TRANSFER_LOG_ADDRESS,
+        topics: vec![
+            keccak("Transfer(address,address,uint256)"),
+            address_topic(from),
+            address_topic(to),
+        ],
+        data: value.to_big_endian().to_vec(),
+        space: Space::Ethereum,
+    }
+}
+
+fn address_topic(address: Address) -> H256 { H256::from(address) }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cfx_execute_helper::exec_tracer::{
+        Call, CallResult, Create, CreateResult, Outcome,
+    };
+    use cfx_executor::executive::{
+        executed::make_ext_result, Executed, TxDropError,
+    };
+    use cfx_rpc_eth_types::{SimBlock, TransactionRequest, MAX_SIMULATE_CALLS};
+    use cfx_vm_types::{CreateType, Env};
+    use primitives::SignedTransaction;
+
+    fn sim_call(nonce: u64, gas: Option<u64>) -> TransactionRequest {
+        TransactionRequest {
+            from: Some(Address::repeat_byte(1)),
+            to: Some(Address::repeat_byte(2)),
+            nonce: Some(nonce.into()),
+            gas: gas.map(Into::into),
+            ..Default::default()
+        }
+    }
+
+    fn signed(nonce: u64) -> SignedTransaction {
+        TxExecutor::sign_call_request_with_chain_id(
+            sim_call(nonce, Some(21000)),
+            1,
+            None,
+            false,
+        )
+        .unwrap()
+        .0
+    }
+
+    fn executed(output: Vec<u8>, logs: Vec<LogEntry>) -> Executed {
+        Executed {
+            base_gas: 21000,
+            gas_used: 21000.into(),
+            fee: U256::zero(),
+            burnt_fee: None,
+            gas_charged: 21000.into(),
+            gas_sponsor_paid: false,
+            logs,
+            storage_sponsor_paid: false,
+            storage_collateralized: vec![],
+            storage_released: vec![],
+            contracts_created: vec![],
+            output,
+            ext_result: make_ext_result(()),
+        }
+    }
+
+    fn block_outcome(
+        transactions: Vec<(SignedTransaction, ExecutionOutcome)>,
+    ) -> SimulatedBlockOutcome {
+        SimulatedBlockOutcome {
+            env: Env {
+                epoch_height: 11,
+                timestamp: 100,
+                ..Default::default()
+            },
+            transactions,
+        }
+    }
+
+    fn call(from: Address, to: Address, value: u64) -> Action {
+        Action::Call(Call {
+            space: Space::Ethereum,
+            from,
+            to,
+            value: value.into(),
+            gas: 21000.into(),
+            input: vec![],
+            call_type: CallType::Call,
+        })
+    }
+
+    fn call_result(outcome: Outcome) -> Action {
+        Action::CallResult(CallResult {
+            outcome,
+            gas_left: U256::zero(),
+            return_data: vec![],
+        })
+    }
+
+    fn trace(action: Action, valid: bool) -> ExecTrace {
+        ExecTrace { action, valid }
+    }
+
+    #[test]
+    fn test_transfer_logs() {
+        let (a, b, c) = (
+            Address::repeat_byte(1),
+            Address::repeat_byte(2),
+            Address::repeat_byte(3),
+        );
+        let traces = vec![
+            trace(call(a, b, 100), true),
+            // A reverted sub-call does not transfer.
+            trace(call(b, c, 10), false),
+            trace(call_result(Outcome::Reverted), false),
+            trace(
+                Action::Create(Create {
+                    space: Space::Ethereum,
+                    from: b,
+                    value: 20.into(),
+                    gas: 21000.into(),
+                    init: vec![],
+                    create_type: CreateType::CREATE,
+                }),
+                true,
+            ),
+            trace(
+                Action::CreateResult(CreateResult {
+                    outcome: Outcome::Success,
+                    addr: c,
+                    gas_left: U256::zero(),
+                    return_data: vec![],
+                }),
+                true,
+            ),
+            trace(call_result(Outcome::Success), true),
+        ];
+
+        let logs = transfer_logs(Some(&traces));
+        assert_eq!(logs.len(), 2);
+        assert!(logs.iter().all(|log| log.address == TRANSFER_LOG_ADDRESS));
+        assert_eq!(logs[0].topics[1..], [address_topic(a), address_topic(b)]);
+        assert_eq!(U256::from_big_endian(&logs[0].data), 100.into());
+        assert_eq!(logs[1].topics[1..], [address_topic(b), address_topic(c)]);
+        assert_eq!(U256::from_big_endian(&logs[1].data), 20.into());
+
+        assert!(transfer_logs(None).is_empty());
+    }
+
+    #[test]
+    fn test_build_simulated_block() {
+        let log = LogEntry {
+            address: Address::repeat_byte(3),
+            topics: vec![H256::repeat_byte(4)],
+            data: vec![5],
+            space: Space::Ethereum,
+        };
+        let outcome = block_outcome(vec![
+            (
+                signed(0),
+                ExecutionOutcome::Finished(executed(
+                    vec![1],
+                    vec![log.clone(), log],
+                )),
+            ),
+            (
+                signed(1),
+                ExecutionOutcome::ExecutionErrorBumpNonce(
+                    ExecutionError::VmError(VmError::Reverted),
+                    executed(vec![], vec![]),
+                ),
+            ),
+        ]);
+        let parent_hash = H256::repeat_byte(9);
+        let block =
+            build_simulated_block(outcome, parent_hash, false, false).unwrap();
+
+        let header = &block.inner.header;
+        assert_eq!(header.parent_hash, parent_hash);
+        assert_eq!(header.number, 11.into());
+        assert_eq!(header.timestamp, 100.into());
+        assert_eq!(header.gas_used, 42000.into());
+
+        assert_eq!(block.calls.len(), 2);
+        assert_eq!(block.calls[0].status, 1.into());
+        assert!(block.calls[0].error.is_none());
+        assert_eq!(block.calls[0].logs.len(), 2);
+        assert_eq!(block.calls[0].logs[1].log_index, Some(1.into()));
+        assert_eq!(block.calls[0].logs[1].block_hash, header.hash);
+        assert_eq!(block.calls[1].status, 0.into());
+        assert!(block.calls[1].error.is_some());
+    }
+
+    #[test]
+    fn test_not_executed_call() {
+        // A call which can not be executed fails the request instead of
+        // the call.
+        let outcome = block_outcome(vec![(
+            signed(0),
+            ExecutionOutcome::NotExecutedDrop(TxDropError::OldNonce(
+                1.into(),
+                0.into(),
+            )),
+        )]);
+        assert!(
+            build_simulated_block(outcome, H256::zero(), false, false).is_err()
+        );
+
+        let outcome = block_outcome(vec![(
+            signed(0),
+            ExecutionOutcome::ExecutionErrorBumpNonce(
+                ExecutionError::NonceOverflow(Address::repeat_byte(1)),
+                executed(vec![], vec![]),
+            ),
+        )]);
+        assert!(
+            build_simulated_block(outcome, H256::zero(), false, false).is_err()
+        );
+    }
+
+    #[test]
+    fn test_simulate_calls_cap() {
+        let block = |calls| SimBlock {
+            calls,
+            ..Default::default()
+        };
+
+        // The calls are counted across the blocks.
+        let blocks = vec![
+            block(vec![sim_call(0, Some(1)); MAX_SIMULATE_CALLS / 2]),
+            block(vec![sim_call(0, Some(1)); MAX_SIMULATE_CALLS / 2]),
+        ];
+        assert!(
+            TxExecutor::sign_simulate_blocks(blocks.clone(), 1, None).is_ok()
+        );
+        let mut too_many = blocks;
+        too_many.push(block(vec![sim_call(0, Some(1))]));
+        assert!(TxExecutor::sign_simulate_blocks(too_many, 1, None).is_err());
+    }
+
+    #[test]
+    fn test_simulate_gas_cap() {
+        let max_gas = Some(U256::from(100_000));
+        let blocks = vec![
+            SimBlock {
+                calls: vec![sim_call(0, Some(30_000)), sim_call(1, None)],
+                ..Default::default()
+            },
+            SimBlock {
+                calls: vec![sim_call(2, Some(30_000))],
+                ..Default::default()
+            },
+        ];
+        // The call without a gas limit takes the gas left, so nothing is
+        // left for the next block.
+        assert!(TxExecutor::sign_simulate_blocks(blocks.clone(), 1, max_gas)
+            .is_err());
+
+        let mut blocks = blocks;
+        blocks[0].calls[1].gas = Some(40_000.into());
+        let signed =
+            TxExecutor::sign_simulate_blocks(blocks.clone(), 1, max_gas)
+                .unwrap();
+        assert_eq!(signed.len(), 2);
+        assert_eq!(*signed[0].transactions[1].0.gas(), 40_000.into());
+
+        // The gas limits add up to more than the cap.
+        blocks[1].calls[0].gas = Some(30_001.into());
+        assert!(TxExecutor::sign_simulate_blocks(blocks, 1, max_gas).is_err());
+    }
+}
@@ -1,10 +1,12 @@
+use crate::helpers::PendingBlockProvider;
 use cfx_execute_helper::estimation::{EstimateExt, EstimateRequest};
 use cfx_executor::executive::{
     Executed, ExecutionError, ExecutionOutcome, ToRepackError, TxDropError,
 };
 use cfx_rpc_eth_types::{
     AccountOverride, BlockId, BlockOverrides, Error, EvmOverrides,
-    RpcStateOverride, TransactionRequest,
+    RpcStateOverride, SimBlock, StateOverride, TransactionRequest,
+    DEFAULT_ETH_GAS_CALL_REQUEST, MAX_SIMULATE_CALLS,
 };
 use cfx_rpc_utils::error::{
     errors::{EthApiError, RpcInvalidTransactionError},
@@ -16,11 +18,12 @@ use cfx_types::U256;
 use cfx_util_macros::bail;
 use cfx_vm_types::Error as VmError;
 use cfxcore::{
+    consensus::{SimulateBlock, SimulatedBlockOutcome},
     errors::{Error as CoreError, Result as CoreResult},
     ConsensusGraph, SharedConsensusGraph,
 };
 use jsonrpsee::types::ErrorObjectOwned as RpcError;
-use primitives::EpochNumber;
+use primitives::{EpochNumber, SignedTransaction};
 use rustc_hex::ToHex;
 use solidity_abi::string_revert_reason_decode;
 use std::collections::HashMap;
@@ -29,6 +32,9 @@ use std::collections::HashMap;
 pub struct TxExecutor {
     consensus: SharedConsensusGraph,
     max_estimation_gas_limit: Option<U256>,
+    /// The calls against the `pending` tag are executed on the pending block
+    /// if set, otherwise on the latest state.
+    pending_block: Option<PendingBlockProvider>,
 }
 
 impl TxExecutor {
@@ -38,9 +44,17 @@ impl TxExecutor {
         Self {
             consensus,
             max_estimation_gas_limit,
+            pending_block: None,
         }
     }
 
+    pub fn with_pending_block(
+        mut self, pending_block: PendingBlockProvider,
+    ) -> Self {
+        self.pending_block = Some(pending_block);
+        self
+    }
+
     pub fn consensus_graph(&self) -> &ConsensusGraph { &self.consensus }
 
     pub fn convert_block_number_to_epoch_number(
@@ -70,7 +84,7 @@ impl TxExecutor {
     }
 
     pub fn do_exec_transaction(
-        &self, mut request: TransactionRequest,
+        &self, request: TransactionRequest,
         block_number_or_hash: Option<BlockId>,
         state_overrides: Option<RpcStateOverride>,
         block_overrides: Option<Box<BlockOverrides>>,
@@ -78,6 +92,150 @@ impl TxExecutor {
     ) -> CoreResult<(ExecutionOutcome, EstimateExt)> {
         let consensus_graph = self.consensus_graph();
 
+        let state_overrides = Self::convert_state_overrides(state_overrides)?;
+        let evm_overrides = EvmOverrides::new(state_overrides, block_overrides);
+
+        if let (Some(BlockId::Pending), Some(pending_block)) =
+            (&block_number_or_hash, &self.pending_block)
+        {
+            let (signed_tx, estimate_request) =
+                self.sign_call_request(request, collect_access_list)?;
+            return pending_block.pending_block()?.with_state(|state, env| {
+                consensus_graph.call_virtual_on_state(
+                    state,
+                    env,
+                    &signed_tx,
+                    estimate_request,
+                    evm_overrides,
+                )
+            });
+        }
+
+        let epoch = self.convert_block_number_to_epoch_number(
+            block_number_or_hash.unwrap_or_default(),
+        )?;
+
+        let (signed_tx, estimate_request) =
+            self.sign_call_request(request, collect_access_list)?;
+
+        consensus_graph.call_virtual(
+            &signed_tx,
+            epoch,
+            estimate_request,
+            evm_overrides,
+        )
+    }
+
+    /// Executes the blocks of calls one after another, sharing the state,
+    /// so each call sees the effects of the calls before it.
+    pub fn simulate_transactions(
+        &self, blocks: Vec<SimBlock>, block_number_or_hash: Option<BlockId>,
+        validation: bool,
+    ) -> CoreResult<Vec<SimulatedBlockOutcome>> {
+        let chain_id = self.consensus.best_chain_id();
+        let simulate_blocks = Self::sign_simulate_blocks(
+            blocks,
+            chain_id.in_evm_space(),
+            self.max_estimation_gas_limit,
+        )?;
+
+        let epoch = self.convert_block_number_to_epoch_number(
+            block_number_or_hash.unwrap_or_default(),
+        )?;
+
+        self.consensus_graph().simulate_virtual(
+            simulate_blocks,
+            epoch,
+            validation,
+        )
+    }
+
+    /// Signs the calls of the simulated blocks for the eSpace chain
+    /// `chain_id`. At most [`MAX_SIMULATE_CALLS`] calls are simulated, and
+    /// the gas cap `max_gas` of a single call is shared by all of them: the
+    /// calls without a gas limit take the gas left.
+    pub fn sign_simulate_blocks(
+        blocks: Vec<SimBlock>, chain_id: u32, max_gas: Option<U256>,
+    ) -> CoreResult<Vec<SimulateBlock>> {
+        let total_calls: usize = blocks.iter().map(|b| b.calls.len()).sum();
+        if total_calls > MAX_SIMULATE_CALLS {
+            bail!(invalid_input_rpc_err(format!(
+                "too many calls, at most {} calls can be simulated",
+                MAX_SIMULATE_CALLS
+            )));
+        }
+
+        let mut gas_left =
+            max_gas.unwrap_or(DEFAULT_ETH_GAS_CALL_REQUEST.into());
+        let mut simulate_blocks = Vec::with_capacity(blocks.len());
+        for block in blocks {
+            let mut transactions = Vec::with_capacity(block.calls.len());
+            for request in block.calls {
+                if gas_left.is_zero() {
+                    bail!(invalid_input_rpc_err(format!(
+                        "the calls exceed the gas cap {}",
+                        max_gas.unwrap_or(DEFAULT_ETH_GAS_CALL_REQUEST.into())
+                    )));
+                }
+                let (tx, request) = Self::sign_call_request_with_chain_id(
+                    request,
+                    chain_id,
+                    Some(gas_left),
+                    false,
+                )?;
+                gas_left -= *tx.gas();
+                transactions.push((tx, request));
+            }
+            let state_overrides =
+                Self::convert_state_overrides(block.state_overrides)?;
+            simulate_blocks.push(SimulateBlock {
+                transactions,
+                evm_overrides: EvmOverrides::new(
+                    state_overrides,
+                    block.block_overrides.map(Box::new),
+                ),
+            });
+        }
+        Ok(simulate_blocks)
+    }
+
+    pub fn convert_state_overrides(
+        state_overrides: Option<RpcStateOverride>,
+    ) -> CoreResult<Option<StateOverride>> {
+        let Some(states) = state_overrides else {
+            return Ok(None);
+        };
+        let mut state_overrides = HashMap::new();
+        for (address, rpc_account_override) in states {
+            let account_override = AccountOverride::try_from(
+                rpc_account_override,
+            )
+            .map_err(|err| {
+                CoreError::InvalidParam(err.into(), Default::default())
+            })?;
+            state_overrides.insert(address, account_override);
+        }
+        Ok(Some(state_overrides))
+    }
+
+    fn sign_call_request(
+        &self, request: TransactionRequest, collect_access_list: bool,
+    ) -> CoreResult<(SignedTransaction, EstimateRequest)> {
+        let chain_id = self.consensus.best_chain_id();
+        Self::sign_call_request_with_chain_id(
+            request,
+            chain_id.in_evm_space(),
+            self.max_estimation_gas_limit,
+            collect_access_list,
+        )
+    }
+
+    /// Signs the call request for the eSpace chain `chain_id`, with the gas
+    /// limit capped by `max_gas`.
+    pub fn sign_call_request_with_chain_id(
+        mut request: TransactionRequest, chain_id: u32, max_gas: Option<U256>,
+        collect_access_list: bool,
+    ) -> CoreResult<(SignedTransaction, EstimateRequest)> {
         if request.gas_price.is_some()
             && request.max_priority_fee_per_gas.is_some()
         {
@@ -100,30 +258,6 @@ impl TxExecutor {
             }
         }
 
-        let state_overrides = match state_overrides {
-            Some(states) => {
-                let mut state_overrides = HashMap::new();
-                for (address, rpc_account_override) in states {
-                    let account_override =
-                        AccountOverride::try_from(rpc_account_override)
-                            .map_err(|err| {
-                                CoreError::InvalidParam(
-                                    err.into(),
-                                    Default::default(),
-                                )
-                            })?;
-                    state_overrides.insert(address, account_override);
-                }
-                Some(state_overrides)
-            }
-            None => None,
-        };
-        let evm_overrides = EvmOverrides::new(state_overrides, block_overrides);
-
-        let epoch = self.convert_block_number_to_epoch_number(
-            block_number_or_hash.unwrap_or_default(),
-        )?;
-
         // if gas_price and gas is zero, it is considered as not set
         request.unset_zero_gas_and_price();
 
@@ -136,17 +270,9 @@ impl TxExecutor {
             collect_access_list,
         };
 
-        let chain_id = self.consensus.best_chain_id();
-
-        let max_gas = self.max_estimation_gas_limit;
-        let signed_tx = request.sign_call(chain_id.in_evm_space(), max_gas)?;
+        let signed_tx = request.sign_call(chain_id, max_gas)?;
 
-        consensus_graph.call_virtual(
-            &signed_tx,
-            epoch,
-            estimate_request,
-            evm_overrides,
-        )
+        Ok((signed_tx, estimate_request))
     }
 
     pub fn parse_execution_outcome(
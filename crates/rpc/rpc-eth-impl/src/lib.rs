@@ -2,8 +2,10 @@ mod debug;
 mod eth;
 mod filter;
 pub mod helpers;
+mod light;
 mod net;
 mod parity;
+mod personal;
 mod pubsub;
 mod rpc;
 mod trace;
@@ -14,8 +16,10 @@ mod web3;
 pub use debug::DebugApi;
 pub use eth::EthApi;
 pub use filter::EthFilterApi;
+pub use light::LightEthApi;
 pub use net::NetApi;
 pub use parity::ParityApi;
+pub use personal::PersonalApi;
 pub use pubsub::PubSubApi;
 pub use rpc::RPCApi;
 pub use trace::TraceApi;
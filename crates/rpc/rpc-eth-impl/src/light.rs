@@ -0,0 +1,850 @@
+//! eSpace RPCs served by light nodes. Only the methods that can be answered
+//! with data verified by the light protocol are supported: state entries,
+//! transactions, receipts and logs are retrieved from full node peers and
+//! checked against the proofs before they are returned. Calls are executed
+//! on top of the state entries retrieved on demand.
+
+use crate::{helpers::TxExecutor, EthApi};
+use async_trait::async_trait;
+use cfx_execute_helper::estimation::EstimateExt;
+use cfx_executor::executive::ExecutionOutcome;
+use cfx_parameters::rpc::GAS_PRICE_DEFAULT_VALUE;
+use cfx_rpc_cfx_types::{
+    traits::BlockProvider, PhantomBlock, RpcImplConfiguration,
+};
+use cfx_rpc_eth_api::EthApiServer;
+use cfx_rpc_eth_types::{
+    AccessListResult, Account, AccountPendingTransactions, Block, BlockId,
+    BlockOverrides, Bundle, EIP1186AccountProofResponse, EthCallResponse,
+    EthRpcLogFilter as Filter, EvmOverrides, FeeHistory, Header, Log, Receipt,
+    RpcStateOverride, SimulatePayload, SimulatedBlock, StateContext,
+    SyncStatus, Transaction, TransactionRequest, Work,
+};
+use cfx_rpc_primitives::{Bytes, Index};
+use cfx_rpc_utils::error::{
+    errors::EthApiError,
+    jsonrpsee_error_helpers::{
+        invalid_params, invalid_params_check, invalid_params_msg,
+        unimplemented, unknown_block,
+    },
+};
+use cfx_types::{
+    Address, AddressSpaceUtil, BigEndianHash, Space, H160, H256, H64, U256, U64,
+};
+use cfx_util_macros::bail;
+use cfxcore::{
+    light_protocol::Error as LightError, LightQueryService,
+    SharedConsensusGraph,
+};
+use jsonrpsee::{core::RpcResult, types::ErrorObjectOwned as RpcError};
+use log::{debug, info};
+use primitives::{filter::FilterError, EpochNumber, TransactionWithSignature};
+use std::sync::Arc;
+
+type BlockNumberOrTag = BlockId;
+
+type JsonStorageKey = U256;
+
+fn into_rpc_err<E>(e: E) -> RpcError
+where cfxcore::errors::Error: From<E> {
+    RpcError::from(cfxcore::errors::Error::from(e))
+}
+
+fn not_supported() -> RpcError {
+    unimplemented(Some("Light nodes only serve the verifiable eth_* methods: state, calls, transactions, receipts and logs".into()))
+}
+
+pub struct LightEthApi {
+    config: RpcImplConfiguration,
+
+    // consensus graph
+    consensus: SharedConsensusGraph,
+
+    // helper API for retrieving verified information from peers
+    light: Arc<LightQueryService>,
+}
+
+impl LightEthApi {
+    pub fn new(
+        config: RpcImplConfiguration, consensus: SharedConsensusGraph,
+        light: Arc<LightQueryService>,
+    ) -> Self {
+        LightEthApi {
+            config,
+            consensus,
+            light,
+        }
+    }
+
+    /// Returns the epoch of the pivot block `hash`. Blocks in eSpace
+    /// correspond to epochs, so non-pivot blocks are unknown.
+    fn pivot_epoch_number(&self, hash: &H256) -> Option<u64> {
+        let epoch = self.consensus.get_block_epoch_number(hash)?;
+        let pivot = self
+            .consensus
+            .get_block_hashes_by_epoch(EpochNumber::Number(epoch))
+            .ok()?
+            .last()
+            .cloned();
+
+        (pivot == Some(*hash)).then_some(epoch)
+    }
+
+    fn epoch_number(
+        &self, block_number: Option<BlockId>,
+    ) -> RpcResult<EpochNumber> {
+        match block_number.unwrap_or_default() {
+            BlockId::Hash { hash, .. } => self
+                .pivot_epoch_number(&hash)
+                .map(EpochNumber::Number)
+                .ok_or_else(unknown_block),
+            // note: light nodes have no transaction pool, so the pending
+            // state is the latest verifiable state
+            block_number => block_number.try_into().map_err(RpcError::from),
+        }
+    }
+
+    pub fn chain_id(&self) -> RpcResult<u32> {
+        invalid_params_check(
+            "epoch",
+            self.light
+                .get_latest_verifiable_chain_id()
+                .map(|id| id.in_evm_space()),
+        )
+    }
+
+    pub fn block_number(&self) -> RpcResult<U256> {
+        invalid_params_check(
+            "epoch",
+            self.light
+                .get_latest_verifiable_epoch_number()
+                .map(|height| height.into()),
+        )
+    }
+
+    pub async fn gas_price(&self) -> RpcResult<U256> {
+        info!("RPC Request: eth_gasPrice");
+
+        let best_header = self
+            .consensus
+            .data_manager()
+            .block_header_by_hash(&self.consensus.best_block_hash());
+
+        if let Some(base_price) = best_header.and_then(|h| h.base_price()) {
+            return Ok(base_price[Space::Ethereum]);
+        }
+
+        Ok(self
+            .light
+            .gas_price()
+            .await
+            .map_err(|e| invalid_params_msg(&e.to_string()))?
+            .unwrap_or(GAS_PRICE_DEFAULT_VALUE.into()))
+    }
+
+    pub async fn balance(
+        &self, address: H160, block_number: Option<BlockId>,
+    ) -> RpcResult<U256> {
+        info!(
+            "RPC Request: eth_getBalance address={:?} block_number={:?}",
+            address, block_number
+        );
+
+        let epoch = self.epoch_number(block_number)?;
+
+        let account = invalid_params_check(
+            "address",
+            self.light
+                .get_account_with_space(epoch, address.with_evm_space())
+                .await,
+        )?;
+
+        Ok(account.map(|account| account.balance).unwrap_or_default())
+    }
+
+    pub async fn nonce(
+        &self, address: H160, block_number: Option<BlockId>,
+    ) -> RpcResult<U256> {
+        info!(
+            "RPC Request: eth_getTransactionCount address={:?} block_number={:?}",
+            address, block_number
+        );
+
+        let epoch = self.epoch_number(block_number)?;
+
+        let account = invalid_params_check(
+            "address",
+            self.light
+                .get_account_with_space(epoch, address.with_evm_space())
+                .await,
+        )?;
+
+        Ok(account.map(|account| account.nonce).unwrap_or_default())
+    }
+
+    pub async fn code(
+        &self, address: H160, block_number: Option<BlockId>,
+    ) -> RpcResult<Bytes> {
+        info!(
+            "RPC Request: eth_getCode address={:?} block_number={:?}",
+            address, block_number
+        );
+
+        let epoch = self.epoch_number(block_number)?;
+
+        Ok(Bytes::new(
+            invalid_params_check(
+                "address",
+                self.light
+                    .get_code_with_space(epoch, address.with_evm_space())
+                    .await,
+            )?
+            .unwrap_or_default(),
+        ))
+    }
+
+    pub async fn storage_at(
+        &self, address: H160, position: U256, block_number: Option<BlockId>,
+    ) -> RpcResult<H256> {
+        info!(
+            "RPC Request: eth_getStorageAt address={:?} position={:?} block_number={:?}",
+            address, position, block_number
+        );
+
+        let epoch = self.epoch_number(block_number)?;
+
+        Ok(invalid_params_check(
+            "address",
+            self.light
+                .get_storage_with_space(
+                    epoch,
+                    address.with_evm_space(),
+                    H256::from_uint(&position),
+                )
+                .await,
+        )?
+        .unwrap_or_default())
+    }
+
+    async fn phantom_block_by_number(
+        &self, block_number: BlockId,
+    ) -> RpcResult<Option<PhantomBlock>> {
+        let epoch = match block_number {
+            BlockId::Hash { hash, .. } => {
+                match self.pivot_epoch_number(&hash) {
+                    None => return Ok(None),
+                    Some(epoch) => EpochNumber::Number(epoch),
+                }
+            }
+            block_number => self.epoch_number(Some(block_number))?,
+        };
+
+        let height = match self.light.get_height_from_epoch_number(epoch) {
+            Ok(height) => height,
+            // the block exists, but it cannot be verified yet
+            Err(FilterError::UnableToVerify { .. }) => return Ok(None),
+            Err(e) => return Err(invalid_params_msg(&e.to_string())),
+        };
+
+        Ok(Some(
+            self.light
+                .get_phantom_block(height)
+                .await
+                .map_err(into_rpc_err)?,
+        ))
+    }
+
+    /// Returns the phantom block containing transaction `hash` and the index
+    /// of the transaction in it.
+    async fn phantom_block_by_tx_hash(
+        &self, hash: H256,
+    ) -> RpcResult<Option<(PhantomBlock, usize)>> {
+        // return `null` on timeout, as peers cannot provide non-existence
+        // proofs for transactions
+        let tx_info = match self.light.get_tx_info(hash).await {
+            Ok(t) => t,
+            Err(LightError::Timeout(_)) => return Ok(None),
+            Err(e) => return Err(invalid_params_msg(&e.to_string())),
+        };
+
+        if tx_info.tx.space() != Space::Ethereum {
+            return Ok(None);
+        }
+
+        let epoch = match tx_info.maybe_epoch {
+            None => return Ok(None),
+            Some(epoch) => epoch,
+        };
+
+        let phantom_block =
+            match self.phantom_block_by_number(BlockId::Num(epoch)).await? {
+                None => return Ok(None),
+                Some(pb) => pb,
+            };
+
+        let idx = phantom_block
+            .transactions
+            .iter()
+            .position(|tx| tx.hash() == hash);
+        Ok(idx.map(|idx| (phantom_block, idx)))
+    }
+
+    pub async fn block_by_number(
+        &self, block_number: BlockId, include_txs: bool,
+    ) -> RpcResult<Option<Block>> {
+        info!(
+            "RPC Request: eth_getBlockByNumber block_number={:?} include_txs={:?}",
+            block_number, include_txs
+        );
+
+        Ok(self
+            .phantom_block_by_number(block_number)
+            .await?
+            .map(|pb| Block::from_phantom(&pb, include_txs)))
+    }
+
+    pub async fn header_by_number(
+        &self, block_number: BlockId,
+    ) -> RpcResult<Option<Header>> {
+        Ok(self
+            .phantom_block_by_number(block_number)
+            .await?
+            .map(|pb| Header::from_phantom(&pb)))
+    }
+
+    pub async fn block_receipts(
+        &self, block_number: BlockId,
+    ) -> RpcResult<Option<Vec<Receipt>>> {
+        info!(
+            "RPC Request: eth_getBlockReceipts block_number={:?}",
+            block_number
+        );
+
+        let pb = match self.phantom_block_by_number(block_number).await? {
+            None => return Ok(None),
+            Some(pb) => pb,
+        };
+
+        let mut prior_log_index = 0;
+        let receipts = (0..pb.receipts.len())
+            .map(|idx| {
+                EthApi::construct_rpc_receipt(&pb, idx, &mut prior_log_index)
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(RpcError::from)?;
+
+        Ok(Some(receipts))
+    }
+
+    pub async fn transaction_by_hash(
+        &self, hash: H256,
+    ) -> RpcResult<Option<Transaction>> {
+        info!("RPC Request: eth_getTransactionByHash hash={:?}", hash);
+
+        Ok(self
+            .phantom_block_by_tx_hash(hash)
+            .await?
+            .and_then(|(pb, idx)| EthApi::block_tx_by_index(Some(pb), idx)))
+    }
+
+    pub async fn raw_transaction_by_hash(
+        &self, hash: H256,
+    ) -> RpcResult<Option<Bytes>> {
+        info!("RPC Request: eth_getRawTransactionByHash hash={:?}", hash);
+
+        Ok(self
+            .phantom_block_by_tx_hash(hash)
+            .await?
+            .and_then(|(pb, idx)| EthApi::block_raw_tx_by_index(Some(pb), idx)))
+    }
+
+    pub async fn transaction_receipt(
+        &self, hash: H256,
+    ) -> RpcResult<Option<Receipt>> {
+        info!("RPC Request: eth_getTransactionReceipt hash={:?}", hash);
+
+        let (pb, idx) = match self.phantom_block_by_tx_hash(hash).await? {
+            None => return Ok(None),
+            Some(v) => v,
+        };
+
+        let mut prior_log_index =
+            pb.receipts[..idx].iter().map(|r| r.logs.len()).sum();
+
+        Ok(Some(
+            EthApi::construct_rpc_receipt(&pb, idx, &mut prior_log_index)
+                .map_err(RpcError::from)?,
+        ))
+    }
+
+    pub async fn logs(&self, filter: Filter) -> RpcResult<Vec<Log>> {
+        info!("RPC Request: eth_getLogs filter={:?}", filter);
+
+        let filter = filter.into_primitive(self).map_err(RpcError::from)?;
+
+        let phantom_blocks = self
+            .light
+            .get_phantom_blocks_for_logs(
+                filter.clone(),
+                self.config.get_logs_filter_max_limit,
+            )
+            .await
+            .map_err(|e| invalid_params_msg(&e.to_string()))?;
+
+        let logs: Vec<_> = phantom_blocks
+            .iter()
+            .flat_map(|pb| Log::from_phantom_block(pb, &filter))
+            .collect();
+
+        // If the results does not fit into `max_limit`, report an error
+        if let Some(max_limit) = self.config.get_logs_filter_max_limit {
+            if logs.len() > max_limit {
+                bail!(invalid_params("filter", Some(format!("This query results in too many logs, max limitation is {}, please use a smaller block range", max_limit))));
+            }
+        }
+
+        debug!("Collected matching logs = {:?}", logs);
+        Ok(logs)
+    }
+
+    /// Executes the call on top of the state of `block_number`, see
+    /// `QueryService::call_virtual`.
+    async fn exec_transaction(
+        &self, request: TransactionRequest, block_number: Option<BlockId>,
+        state_overrides: Option<RpcStateOverride>,
+        block_overrides: Option<Box<BlockOverrides>>,
+        collect_access_list: bool,
+    ) -> RpcResult<(ExecutionOutcome, EstimateExt)> {
+        let epoch = self.epoch_number(block_number)?;
+
+        let state_overrides =
+            TxExecutor::convert_state_overrides(state_overrides)?;
+        let (signed_tx, estimate_request) =
+            TxExecutor::sign_call_request_with_chain_id(
+                request,
+                self.chain_id()?,
+                self.config.max_estimation_gas_limit,
+                collect_access_list,
+            )?;
+
+        Ok(self
+            .light
+            .call_virtual(
+                signed_tx,
+                epoch,
+                estimate_request,
+                EvmOverrides::new(state_overrides, block_overrides),
+            )
+            .await?)
+    }
+
+    pub async fn call(
+        &self, request: TransactionRequest, block_number: Option<BlockId>,
+        state_overrides: Option<RpcStateOverride>,
+        block_overrides: Option<Box<BlockOverrides>>,
+    ) -> RpcResult<Bytes> {
+        info!(
+            "RPC Request: eth_call request={:?} block_number={:?}",
+            request, block_number
+        );
+
+        let (outcome, _) = self
+            .exec_transaction(
+                request,
+                block_number,
+                state_overrides,
+                block_overrides,
+                false,
+            )
+            .await?;
+        let executed = TxExecutor::parse_execution_outcome(outcome)?;
+
+        Ok(executed.output.into())
+    }
+
+    pub async fn estimate_gas(
+        &self, request: TransactionRequest, block_number: Option<BlockId>,
+        state_overrides: Option<RpcStateOverride>,
+    ) -> RpcResult<U256> {
+        info!(
+            "RPC Request: eth_estimateGas request={:?} block_number={:?}",
+            request, block_number
+        );
+
+        let (outcome, estimation) = self
+            .exec_transaction(
+                request,
+                block_number,
+                state_overrides,
+                None,
+                false,
+            )
+            .await?;
+        TxExecutor::parse_execution_outcome(outcome)?;
+
+        Ok(estimation.estimated_gas_limit)
+    }
+
+    pub async fn create_access_list(
+        &self, request: TransactionRequest, block_number: Option<BlockId>,
+        state_overrides: Option<RpcStateOverride>,
+    ) -> RpcResult<AccessListResult> {
+        info!(
+            "RPC Request: eth_createAccessList request={:?} block_number={:?}",
+            request, block_number
+        );
+
+        let (outcome, estimation) = self
+            .exec_transaction(
+                request,
+                block_number,
+                state_overrides,
+                None,
+                true,
+            )
+            .await?;
+        let error = TxExecutor::parse_execution_outcome(outcome)
+            .map_err(|err| err.to_string())
+            .err();
+
+        Ok(AccessListResult {
+            access_list: estimation.access_list,
+            gas_used: estimation.estimated_gas_limit,
+            error,
+        })
+    }
+
+    pub fn send_raw_transaction(&self, raw: Bytes) -> RpcResult<H256> {
+        info!("RPC Request: eth_sendRawTransaction bytes={:?}", raw);
+
+        let raw: Vec<u8> = raw.into_vec();
+
+        // decode tx so that we have its hash
+        // this way we also avoid spamming peers with invalid txs
+        let tx = match TransactionWithSignature::from_raw(&raw) {
+            Ok(tx) => tx,
+            Err(_) => bail!(EthApiError::FailedToDecodeSignedTransaction),
+        };
+
+        if tx.space() != Space::Ethereum {
+            bail!(EthApiError::Other(
+                "Incorrect transaction space".to_string()
+            ));
+        }
+
+        if tx.recover_public().is_err() {
+            bail!(EthApiError::InvalidTransactionSignature);
+        }
+
+        match /* success = */ self.light.send_raw_tx(raw) {
+            true => Ok(tx.hash()),
+            false => Err(into_rpc_err(LightError::InternalError(
+                "Unable to relay tx".into(),
+            ))),
+        }
+    }
+}
+
+impl BlockProvider for &LightEthApi {
+    fn get_block_epoch_number(&self, hash: &H256) -> Option<u64> {
+        self.consensus.get_block_epoch_number(hash)
+    }
+
+    fn get_block_hashes_by_epoch(
+        &self, epoch_number: EpochNumber,
+    ) -> Result<Vec<H256>, String> {
+        self.consensus
+            .get_block_hashes_by_epoch(epoch_number)
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[async_trait]
+impl EthApiServer for LightEthApi {
+    async fn protocol_version(&self) -> RpcResult<U64> { Ok(U64::from(65)) }
+
+    fn syncing(&self) -> RpcResult<SyncStatus> { Ok(SyncStatus::None) }
+
+    async fn author(&self) -> RpcResult<Address> { Ok(H160::zero()) }
+
+    fn accounts(&self) -> RpcResult<Vec<Address>> { Ok(vec![]) }
+
+    fn block_number(&self) -> RpcResult<U256> { self.block_number() }
+
+    async fn chain_id(&self) -> RpcResult<Option<U64>> {
+        Ok(Some(self.chain_id()?.into()))
+    }
+
+    async fn block_by_hash(
+        &self, hash: H256, full: bool,
+    ) -> RpcResult<Option<Block>> {
+        self.block_by_number(
+            BlockId::Hash {
+                hash,
+                require_canonical: None,
+            },
+            full,
+        )
+        .await
+    }
+
+    async fn block_by_number(
+        &self, number: BlockNumberOrTag, full: bool,
+    ) -> RpcResult<Option<Block>> {
+        self.block_by_number(number, full).await
+    }
+
+    async fn block_transaction_count_by_hash(
+        &self, _hash: H256,
+    ) -> RpcResult<Option<U256>> {
+        Err(not_supported())
+    }
+
+    async fn block_transaction_count_by_number(
+        &self, _number: BlockNumberOrTag,
+    ) -> RpcResult<Option<U256>> {
+        Err(not_supported())
+    }
+
+    async fn block_uncles_count_by_hash(
+        &self, _hash: H256,
+    ) -> RpcResult<Option<U256>> {
+        Ok(Some(U256::zero()))
+    }
+
+    async fn block_uncles_count_by_number(
+        &self, _number: BlockNumberOrTag,
+    ) -> RpcResult<Option<U256>> {
+        Ok(Some(U256::zero()))
+    }
+
+    async fn block_receipts(
+        &self, block_id: BlockId,
+    ) -> RpcResult<Option<Vec<Receipt>>> {
+        self.block_receipts(block_id).await
+    }
+
+    async fn uncle_by_block_hash_and_index(
+        &self, _hash: H256, _index: Index,
+    ) -> RpcResult<Option<Block>> {
+        Ok(None)
+    }
+
+    async fn uncle_by_block_number_and_index(
+        &self, _number: BlockNumberOrTag, _index: Index,
+    ) -> RpcResult<Option<Block>> {
+        Ok(None)
+    }
+
+    async fn raw_transaction_by_hash(
+        &self, hash: H256,
+    ) -> RpcResult<Option<Bytes>> {
+        self.raw_transaction_by_hash(hash).await
+    }
+
+    async fn transaction_by_hash(
+        &self, hash: H256,
+    ) -> RpcResult<Option<Transaction>> {
+        self.transaction_by_hash(hash).await
+    }
+
+    async fn raw_transaction_by_block_hash_and_index(
+        &self, _hash: H256, _index: Index,
+    ) -> RpcResult<Option<Bytes>> {
+        Err(not_supported())
+    }
+
+    async fn transaction_by_block_hash_and_index(
+        &self, _hash: H256, _index: Index,
+    ) -> RpcResult<Option<Transaction>> {
+        Err(not_supported())
+    }
+
+    async fn raw_transaction_by_block_number_and_index(
+        &self, _number: BlockNumberOrTag, _index: Index,
+    ) -> RpcResult<Option<Bytes>> {
+        Err(not_supported())
+    }
+
+    async fn transaction_by_block_number_and_index(
+        &self, _number: BlockNumberOrTag, _index: Index,
+    ) -> RpcResult<Option<Transaction>> {
+        Err(not_supported())
+    }
+
+    async fn transaction_by_sender_and_nonce(
+        &self, _address: Address, _nonce: U64,
+    ) -> RpcResult<Option<Transaction>> {
+        Err(not_supported())
+    }
+
+    async fn transaction_receipt(
+        &self, hash: H256,
+    ) -> RpcResult<Option<Receipt>> {
+        self.transaction_receipt(hash).await
+    }
+
+    async fn balance(
+        &self, address: Address, block_number: Option<BlockId>,
+    ) -> RpcResult<U256> {
+        self.balance(address, block_number).await
+    }
+
+    async fn storage_at(
+        &self, address: Address, index: JsonStorageKey,
+        block_number: Option<BlockId>,
+    ) -> RpcResult<H256> {
+        self.storage_at(address, index, block_number).await
+    }
+
+    async fn transaction_count(
+        &self, address: Address, block_number: Option<BlockId>,
+    ) -> RpcResult<U256> {
+        self.nonce(address, block_number).await
+    }
+
+    async fn get_code(
+        &self, address: Address, block_number: Option<BlockId>,
+    ) -> RpcResult<Bytes> {
+        self.code(address, block_number).await
+    }
+
+    async fn header_by_number(
+        &self, block_number: BlockNumberOrTag,
+    ) -> RpcResult<Option<Header>> {
+        self.header_by_number(block_number).await
+    }
+
+    async fn header_by_hash(&self, hash: H256) -> RpcResult<Option<Header>> {
+        self.header_by_number(BlockId::Hash {
+            hash,
+            require_canonical: None,
+        })
+        .await
+    }
+
+    async fn simulate_v1(
+        &self, _opts: SimulatePayload, _block_number: Option<BlockId>,
+    ) -> RpcResult<Vec<SimulatedBlock>> {
+        Err(not_supported())
+    }
+
+    async fn call(
+        &self, request: TransactionRequest, block_number: Option<BlockId>,
+        state_overrides: Option<RpcStateOverride>,
+        block_overrides: Option<Box<BlockOverrides>>,
+    ) -> RpcResult<Bytes> {
+        self.call(request, block_number, state_overrides, block_overrides)
+            .await
+    }
+
+    async fn call_many(
+        &self, _bundle: Bundle, _state_context: Option<StateContext>,
+        _state_override: Option<RpcStateOverride>,
+    ) -> RpcResult<Vec<EthCallResponse>> {
+        Err(not_supported())
+    }
+
+    async fn create_access_list(
+        &self, request: TransactionRequest, block_number: Option<BlockId>,
+        state_override: Option<RpcStateOverride>,
+    ) -> RpcResult<AccessListResult> {
+        self.create_access_list(request, block_number, state_override)
+            .await
+    }
+
+    async fn estimate_gas(
+        &self, request: TransactionRequest, block_number: Option<BlockId>,
+        state_override: Option<RpcStateOverride>,
+    ) -> RpcResult<U256> {
+        self.estimate_gas(request, block_number, state_override)
+            .await
+    }
+
+    async fn gas_price(&self) -> RpcResult<U256> { self.gas_price().await }
+
+    async fn get_account(
+        &self, _address: Address, _block_number: Option<BlockId>,
+    ) -> RpcResult<Option<Account>> {
+        Err(not_supported())
+    }
+
+    async fn get_multiple_accounts(
+        &self, _addresses: Vec<Address>, _block_number: Option<BlockId>,
+    ) -> RpcResult<Vec<Option<Account>>> {
+        Err(not_supported())
+    }
+
+    async fn max_priority_fee_per_gas(&self) -> RpcResult<U256> {
+        Err(not_supported())
+    }
+
+    async fn fee_history(
+        &self, _block_count: U64, _newest_block: BlockNumberOrTag,
+        _reward_percentiles: Option<Vec<f64>>,
+    ) -> RpcResult<FeeHistory> {
+        Err(not_supported())
+    }
+
+    async fn is_mining(&self) -> RpcResult<bool> { Ok(false) }
+
+    async fn hashrate(&self) -> RpcResult<U256> { Ok(U256::zero()) }
+
+    async fn get_work(&self) -> RpcResult<Work> { Err(not_supported()) }
+
+    async fn submit_hashrate(
+        &self, _hashrate: U256, _id: H256,
+    ) -> RpcResult<bool> {
+        Ok(false)
+    }
+
+    async fn submit_work(
+        &self, _nonce: H64, _pow_hash: H256, _mix_digest: H256,
+    ) -> RpcResult<bool> {
+        Err(not_supported())
+    }
+
+    async fn send_transaction(
+        &self, _request: TransactionRequest,
+    ) -> RpcResult<H256> {
+        Err(not_supported())
+    }
+
+    async fn send_raw_transaction(&self, bytes: Bytes) -> RpcResult<H256> {
+        self.send_raw_transaction(bytes)
+    }
+
+    async fn submit_transaction(&self, raw: Bytes) -> RpcResult<H256> {
+        self.send_raw_transaction(raw)
+    }
+
+    async fn sign(
+        &self, _address: Address, _message: Bytes,
+    ) -> RpcResult<Bytes> {
+        Err(not_supported())
+    }
+
+    async fn sign_transaction(
+        &self, _transaction: TransactionRequest,
+    ) -> RpcResult<Bytes> {
+        Err(not_supported())
+    }
+
+    async fn get_proof(
+        &self, _address: Address, _keys: Vec<JsonStorageKey>,
+        _block_number: Option<BlockId>,
+    ) -> RpcResult<EIP1186AccountProofResponse> {
+        Err(not_supported())
+    }
+
+    async fn logs(&self, filter: Filter) -> RpcResult<Vec<Log>> {
+        self.logs(filter).await
+    }
+
+    async fn account_pending_transactions(
+        &self, _address: Address, _maybe_start_nonce: Option<U256>,
+        _maybe_limit: Option<U64>,
+    ) -> RpcResult<AccountPendingTransactions> {
+        Err(not_supported())
+    }
+}
@@ -0,0 +1,82 @@
+use async_trait::async_trait;
+use cfx_rpc_cfx_types::traits::ChainMetaProvider;
+use cfx_rpc_eth_api::PersonalApiServer;
+use cfx_rpc_eth_types::TypedData;
+use cfx_rpc_primitives::Bytes;
+use cfx_rpc_utils::error::jsonrpsee_error_helpers::{
+    internal_error_with_data, invalid_params,
+};
+use cfx_types::{Address, U256};
+use cfxcore_accounts::{AccountProvider, SignError, TypedDataKind};
+use jsonrpsee::core::RpcResult;
+use std::sync::Arc;
+
+/// Signs with the local accounts of the node. It must not be served on the
+/// public transports, see `EthRpcModule::is_local_only`.
+pub struct PersonalApi {
+    chain_meta: Box<dyn ChainMetaProvider + Send + Sync>,
+    accounts: Arc<AccountProvider>,
+}
+
+impl PersonalApi {
+    pub fn new(
+        chain_meta: Box<dyn ChainMetaProvider + Send + Sync>,
+        accounts: Arc<AccountProvider>,
+    ) -> Self {
+        Self {
+            chain_meta,
+            accounts,
+        }
+    }
+
+    /// Signs EIP-712 typed data with an unlocked local account. The domain
+    /// must not be bound to another chain.
+    pub fn sign_typed_data(
+        &self, address: Address, data: TypedData,
+    ) -> RpcResult<Bytes> {
+        if data.kind().ok() != Some(TypedDataKind::Eip712) {
+            return Err(invalid_params(
+                "data",
+                Some("expect EIP712Domain typed data"),
+            ));
+        }
+        let chain_id = self.chain_meta.chain_id();
+        match data.chain_id() {
+            Ok(None) => {}
+            Ok(Some(id)) if id == U256::from(chain_id) => {}
+            _ => {
+                return Err(invalid_params(
+                    "data",
+                    Some(format!("domain chainId must be {}", chain_id)),
+                ))
+            }
+        }
+
+        let signature =
+            match self.accounts.sign_typed_data(address, None, &data) {
+                Ok(signature) => signature,
+                Err(SignError::InvalidTypedData(e)) => {
+                    return Err(invalid_params("data", Some(format!("{}", e))))
+                }
+                Err(e) => {
+                    return Err(internal_error_with_data(format!(
+                        "Unable to sign the typed data: {}",
+                        e
+                    )))
+                }
+            };
+        // Ethereum signatures carry the recovery id as `v = 27 + recid`.
+        let mut raw: [u8; 65] = signature.into();
+        raw[64] += 27;
+        Ok(Bytes::new(raw.to_vec()))
+    }
+}
+
+#[async_trait]
+impl PersonalApiServer for PersonalApi {
+    async fn sign_typed_data(
+        &self, address: Address, data: TypedData,
+    ) -> RpcResult<Bytes> {
+        self.sign_typed_data(address, data)
+    }
+}
@@ -3,17 +3,20 @@ use cfx_parameters::{
     consensus::DEFERRED_STATE_EPOCH_COUNT,
     consensus_internal::REWARD_EPOCH_COUNT,
 };
-use cfx_rpc_cfx_impl::helpers::subscribers::pipe_from_stream;
+use cfx_rpc_cfx_impl::helpers::{
+    rpc_txpool_event, subscribers::pipe_from_stream,
+};
 use cfx_rpc_cfx_types::{traits::BlockProvider, PhantomBlock};
 use cfx_rpc_eth_api::EthPubSubApiServer;
 use cfx_rpc_eth_types::{
     eth_pubsub::{Kind as SubscriptionKind, Params, Result as PubSubResult},
-    Header, Log,
+    Header, Log, Transaction,
 };
 use cfx_tasks::TaskExecutor;
 use cfx_types::{Space, H256};
 use cfxcore::{
-    BlockDataManager, ConsensusGraph, Notifications, SharedConsensusGraph,
+    transaction_pool::TxPoolEvent, BlockDataManager, ConsensusGraph,
+    Notifications, SharedConsensusGraph,
 };
 use futures::StreamExt;
 use jsonrpsee::{core::SubscriptionResult, PendingSubscriptionSink};
@@ -42,6 +45,8 @@ pub struct PubSubApi {
     head_sender: Arc<broadcast::Sender<Header>>,
     log_loop_started: Arc<RwLock<HashMap<LogFilter, bool>>>,
     log_senders: Arc<RwLock<HashMap<LogFilter, broadcast::Sender<Log>>>>,
+    txpool_loop_started: Arc<RwLock<bool>>,
+    txpool_event_sender: Arc<broadcast::Sender<TxPoolEvent>>,
 }
 
 impl PubSubApi {
@@ -50,6 +55,8 @@ impl PubSubApi {
         executor: TaskExecutor,
     ) -> PubSubApi {
         let (head_sender, _) = broadcast::channel(BROADCAST_CHANNEL_SIZE);
+        let (txpool_event_sender, _) =
+            broadcast::channel(BROADCAST_CHANNEL_SIZE);
         let log_senders = Arc::new(RwLock::new(HashMap::new()));
         let chain_data_provider =
             Arc::new(ChainDataProvider::new(consensus.clone()));
@@ -62,6 +69,8 @@ impl PubSubApi {
             log_senders,
             chain_data_provider,
             log_loop_started: Arc::new(RwLock::new(HashMap::new())),
+            txpool_loop_started: Arc::new(RwLock::new(false)),
+            txpool_event_sender: Arc::new(txpool_event_sender),
         }
     }
 
@@ -79,6 +88,13 @@ impl PubSubApi {
             .map(|item| item.expect("should not be an error"))
     }
 
+    fn new_txpool_events_stream(&self) -> impl Stream<Item = TxPoolEvent> {
+        let receiver = self.txpool_event_sender.subscribe();
+        BroadcastStream::new(receiver)
+            .filter(|item| futures::future::ready(item.is_ok()))
+            .map(|item| item.expect("should not be an error"))
+    }
+
     fn new_logs_stream(&self, filter: LogFilter) -> impl Stream<Item = Log> {
         let receiver;
         let senders = self.log_senders.read();
@@ -155,6 +171,37 @@ impl PubSubApi {
         self.executor.spawn(fut);
     }
 
+    fn start_txpool_events_loop(&self) {
+        let mut loop_started = self.txpool_loop_started.write();
+        if *loop_started {
+            return;
+        }
+        *loop_started = true;
+
+        debug!("async start_txpool_events_loop");
+
+        let mut receiver = self.notifications.txpool_events.subscribe();
+        let txpool_event_sender = self.txpool_event_sender.clone();
+        let txpool_loop_started = self.txpool_loop_started.clone();
+
+        let fut = async move {
+            while let Some(event) = receiver.recv().await {
+                if event.transaction().space() != Space::Ethereum {
+                    continue;
+                }
+                let send_res = txpool_event_sender.send(event);
+                if send_res.is_err() {
+                    // stop the loop
+                    let mut loop_started = txpool_loop_started.write();
+                    *loop_started = false;
+                    return;
+                }
+            }
+        };
+
+        self.executor.spawn(fut);
+    }
+
     fn start_logs_loop(&self, filter: LogFilter) {
         let mut loop_started = self.log_loop_started.write();
         if loop_started.contains_key(&filter) {
@@ -309,6 +356,60 @@ impl EthPubSubApiServer for PubSubApi {
                 self.start_logs_loop(filter);
                 Ok(())
             }
+            (SubscriptionKind::NewPendingTransactions, params) => {
+                let full_transactions = match params {
+                    None => false,
+                    Some(Params::FullTransactions(full)) => full,
+                    Some(_) => {
+                        return Err("Expected fullTransactions parameter".into())
+                    }
+                };
+
+                let sink = pending.accept().await?;
+                let stream =
+                    self.new_txpool_events_stream().filter_map(move |event| {
+                        let result =
+                            event.new_pending_transaction().map(|tx| {
+                                if full_transactions {
+                                    PubSubResult::FullTransaction(Box::new(
+                                        Transaction::from_signed(
+                                            tx,
+                                            (None, None, None),
+                                            (None, None),
+                                        ),
+                                    ))
+                                } else {
+                                    PubSubResult::TransactionHash(tx.hash())
+                                }
+                            });
+                        futures::future::ready(result)
+                    });
+                self.executor.spawn(async {
+                    let _ = pipe_from_stream(sink, stream).await;
+                });
+
+                // start the txpool event loop
+                self.start_txpool_events_loop();
+                Ok(())
+            }
+            (SubscriptionKind::TxPoolEvents, None) => {
+                let sink = pending.accept().await?;
+                let stream = self.new_txpool_events_stream().map(|event| {
+                    let from = event.transaction().sender().address;
+                    PubSubResult::TxPoolEvent(rpc_txpool_event(&event, from))
+                });
+                self.executor.spawn(async {
+                    let _ = pipe_from_stream(sink, stream).await;
+                });
+
+                // start the txpool event loop
+                self.start_txpool_events_loop();
+                Ok(())
+            }
+            (SubscriptionKind::TxPoolEvents, _) => {
+                // reject
+                Err("Params should be empty".into())
+            }
             (_, _) => {
                 // reject
                 Err("Not supported".into())
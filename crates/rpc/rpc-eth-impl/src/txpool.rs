@@ -4,6 +4,7 @@ use cfx_rpc_eth_types::{
     Transaction, TxpoolContent, TxpoolContentFrom, TxpoolInspect,
     TxpoolInspectSummary, TxpoolStatus,
 };
+use cfx_rpc_utils::error::jsonrpsee_error_helpers::internal_error_with_data;
 use cfx_types::{Address, AddressWithSpace, Space, U256, U64};
 use cfxcore::SharedTransactionPool;
 use jsonrpsee::core::RpcResult;
@@ -32,9 +33,14 @@ impl TxPoolApiServer for TxPoolApi {
     async fn txpool_status(&self) -> RpcResult<TxpoolStatus> {
         let pending = self.tx_pool.total_pending(Some(Space::Ethereum));
         let queued = self.tx_pool.total_queued(Some(Space::Ethereum));
+        let (local_pending, local_queued) = self
+            .tx_pool
+            .total_local_pending_and_queued(Some(Space::Ethereum));
         Ok(TxpoolStatus {
             pending: U64::from(pending),
             queued: U64::from(queued),
+            local_pending: U64::from(local_pending),
+            local_queued: U64::from(local_queued),
         })
     }
 
@@ -87,4 +93,12 @@ impl TxPoolApiServer for TxPoolApi {
         let queued = queued.into_iter().map(converter).collect();
         Ok(TxpoolContent { pending, queued })
     }
+
+    async fn txpool_rotate_journal(&self) -> RpcResult<U64> {
+        let journaled = self
+            .tx_pool
+            .rotate_journal()
+            .map_err(internal_error_with_data)?;
+        Ok(U64::from(journaled))
+    }
 }
@@ -0,0 +1,17 @@
+use cfx_types::{H256, U256};
+use serde::{Deserialize, Serialize};
+
+/// Account information returned by `eth_getAccount`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Account {
+    /// The balance of the account.
+    pub balance: U256,
+    /// The nonce of the account.
+    pub nonce: U256,
+    /// The code hash of the account.
+    pub code_hash: H256,
+    /// `keccak256` of the RLP encoded storage root triplet, the same value
+    /// as `storageHash` returned by `eth_getProof`.
+    pub storage_root: H256,
+}
@@ -1,5 +1,4 @@
-use crate::{BlockId, BlockOverrides, TransactionRequest};
-use cfx_bytes::Bytes;
+use crate::{BlockId, BlockOverrides, Bytes, TransactionRequest};
 
 /// Bundle of transactions
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
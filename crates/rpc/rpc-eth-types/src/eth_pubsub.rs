@@ -4,14 +4,14 @@
 
 //! Pub-Sub types.
 
-use super::{EthRpcLogFilter, Header, Log};
-use cfx_types::H256;
+use super::{EthRpcLogFilter, Header, Log, Transaction, TxPoolEvent};
+use cfx_types::{Address, H256};
 use serde::{de::Error, Deserialize, Deserializer, Serialize};
 use serde_json::{from_value, Value};
 
 /// Subscription result.
 #[allow(dead_code)]
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 #[serde(untagged, rename_all = "camelCase")]
 // NOTE: rename_all does not apply to enum member fields
 // see: https://github.com/serde-rs/serde/issues/1061
@@ -24,6 +24,12 @@ pub enum Result {
 
     /// Transaction hash
     TransactionHash(H256),
+
+    /// Full transaction
+    FullTransaction(Box<Transaction>),
+
+    /// Transaction pool event
+    TxPoolEvent(TxPoolEvent<Address>),
 }
 
 /// Subscription kind.
@@ -39,6 +45,8 @@ pub enum Kind {
     NewPendingTransactions,
     /// Node syncing status subscription.
     Syncing,
+    /// Transaction pool events subscription.
+    TxPoolEvents,
 }
 
 /// Subscription kind.
@@ -48,6 +56,14 @@ pub enum Params {
     None,
     /// Log parameters.
     Logs(EthRpcLogFilter),
+    /// Whether to publish the full pending transactions.
+    FullTransactions(bool),
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+struct PendingTransactionsParams {
+    full_transactions: bool,
 }
 
 impl Default for Params {
@@ -65,6 +81,14 @@ impl<'a> Deserialize<'a> for Params {
             return Ok(Params::None);
         }
 
+        // try to interpret as pending transactions parameters
+        if let Some(full_transactions) = v.as_bool() {
+            return Ok(Params::FullTransactions(full_transactions));
+        }
+        if let Ok(params) = from_value::<PendingTransactionsParams>(v.clone()) {
+            return Ok(Params::FullTransactions(params.full_transactions));
+        }
+
         // try to interpret as a log filter
         from_value(v.clone()).map(Params::Logs).map_err(|e| {
             D::Error::custom(format!("Invalid Pub-Sub parameters: {}", e))
@@ -1,4 +1,5 @@
 mod access_list;
+mod account;
 mod authorization;
 mod block;
 mod block_number;
@@ -9,6 +10,7 @@ pub mod eth_pubsub;
 mod fee_history;
 mod filter;
 mod log;
+mod proof;
 mod receipt;
 mod simulate;
 mod state;
@@ -21,17 +23,20 @@ mod transaction_request;
 mod tx_pool;
 
 pub use access_list::*;
+pub use account::Account;
 pub use authorization::{Authorization, SignedAuthorization};
-pub use block::{Block, BlockOverrides, Header};
+pub use block::{Block, BlockOverrides, BlockTransactions, Header};
 pub use block_number::BlockId;
 pub use block_properties::BlockProperties;
 pub use call::*;
+pub use cfx_rpc_cfx_types::{TxPoolEvent, TxPoolEventKind, TypedData, Work};
 pub use cfx_rpc_primitives::{Bytes, Index, U64};
 pub use errors::Error;
 pub use eth_pubsub::*;
 pub use fee_history::FeeHistory;
 pub use filter::*;
 pub use log::*;
+pub use proof::{EIP1186AccountProofResponse, EIP1186StorageProof};
 pub use receipt::Receipt;
 pub use simulate::*;
 pub use state::{
@@ -19,9 +19,10 @@
 // along with OpenEthereum.  If not, see <http://www.gnu.org/licenses/>.
 
 use crate::{Bytes, Error};
-use cfx_rpc_cfx_types::traits::BlockProvider;
+use cfx_rpc_cfx_types::{traits::BlockProvider, PhantomBlock};
 use cfx_types::{H160, H256, U256, U64};
 use primitives::{
+    filter::LogFilterParams,
     log_entry::{LocalizedLogEntry, LogEntry},
     EpochNumber,
 };
@@ -97,6 +98,41 @@ impl Log {
         })
     }
 
+    /// Returns the logs in the phantom block `pb` matching `params`.
+    pub fn from_phantom_block(
+        pb: &PhantomBlock, params: &LogFilterParams,
+    ) -> Vec<Log> {
+        let header = &pb.pivot_header;
+        let block_hash = header.hash();
+        let mut logs = vec![];
+        let mut log_index = 0;
+        for (idx, (tx, receipt)) in
+            pb.transactions.iter().zip(&pb.receipts).enumerate()
+        {
+            for (tx_log_index, log) in receipt.logs.iter().enumerate() {
+                if params.matches(log) {
+                    logs.push(Log {
+                        inner: LogData {
+                            address: log.address,
+                            topics: log.topics.clone(),
+                            data: log.data.clone().into(),
+                        },
+                        block_hash,
+                        block_number: header.height().into(),
+                        transaction_hash: tx.hash(),
+                        transaction_index: idx.into(),
+                        block_timestamp: Some(header.timestamp().into()),
+                        log_index: Some(log_index.into()),
+                        transaction_log_index: Some(tx_log_index.into()),
+                        removed: false,
+                    });
+                }
+                log_index += 1;
+            }
+        }
+        logs
+    }
+
     pub fn try_from(_e: LogEntry) -> Result<Log, String> {
         unimplemented!();
         // Ok(Log {
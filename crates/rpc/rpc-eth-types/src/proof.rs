@@ -0,0 +1,58 @@
+use cfx_rpc_primitives::Bytes;
+use cfx_types::{Address, H256, U256, U64};
+use primitives::StateRoot;
+use serde::{Deserialize, Serialize};
+
+/// Response of `eth_getProof`, modelled after
+/// [EIP-1186](https://eips.ethereum.org/EIPS/eip-1186).
+///
+/// Conflux keeps its state in three MPTs (snapshot, intermediate delta and
+/// delta), so every proof field is a list of exactly three RLP encoded
+/// `TrieProof`s in the order `[delta, intermediate, snapshot]`. An empty
+/// entry means that the corresponding trie is empty and no proof is needed.
+///
+/// The state root is not committed by the block it was executed in, but by
+/// its deferred block (`blockNumber + DEFERRED_STATE_EPOCH_COUNT`), whose
+/// header field `stateRoot` must equal the hash of `stateRoot` below.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EIP1186AccountProofResponse {
+    /// The address of the account.
+    pub address: Address,
+    /// The balance of the account.
+    pub balance: U256,
+    /// The code hash of the account.
+    pub code_hash: H256,
+    /// The nonce of the account.
+    pub nonce: U256,
+    /// `keccak256` of the RLP encoded storage root triplet.
+    pub storage_hash: H256,
+    /// Merkle proof of the account entry.
+    pub account_proof: Vec<Bytes>,
+    /// Merkle proofs of the requested storage entries.
+    pub storage_proof: Vec<EIP1186StorageProof>,
+    /// RLP encoded storage root triplet of the account.
+    pub storage_root: Bytes,
+    /// Merkle proof of the storage root triplet.
+    pub storage_root_proof: Vec<Bytes>,
+    /// The block number at which the state is read.
+    pub block_number: U64,
+    /// The state root after executing `blockNumber`.
+    pub state_root: StateRoot,
+    /// The state root one snapshot period before `blockNumber`, needed to
+    /// derive the key padding of the intermediate delta MPT.
+    pub prev_snapshot_state_root: Option<StateRoot>,
+}
+
+/// Storage entry with its merkle proof.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EIP1186StorageProof {
+    /// The requested storage key.
+    pub key: H256,
+    /// The storage value.
+    pub value: U256,
+    /// Merkle proof of the storage entry, see
+    /// [`EIP1186AccountProofResponse`] for the layout.
+    pub proof: Vec<Bytes>,
+}
@@ -1,13 +1,15 @@
 use crate::{
-    Block, BlockOverrides, Log, RpcAccountOverride as StateOverride,
-    TransactionRequest,
+    Block, BlockOverrides, Bytes, Log, RpcStateOverride, TransactionRequest,
 };
-use cfx_bytes::Bytes;
 use cfx_types::U64;
 
 /// The maximum number of blocks that can be simulated in a single request,
 pub const MAX_SIMULATE_BLOCKS: u64 = 256;
 
+/// The maximum number of calls that can be simulated in a single request,
+/// across all the blocks.
+pub const MAX_SIMULATE_CALLS: usize = 1000;
+
 /// Represents a batch of calls to be simulated sequentially within a block.
 /// This struct includes block and state overrides as well as the transaction
 /// requests to be executed.
@@ -26,7 +28,7 @@ pub struct SimBlock {
         feature = "serde",
         serde(default, skip_serializing_if = "Option::is_none")
     )]
-    pub state_overrides: Option<StateOverride>,
+    pub state_overrides: Option<RpcStateOverride>,
     /// A vector of transactions to be simulated.
     #[cfg_attr(feature = "serde", serde(default))]
     pub calls: Vec<TransactionRequest>,
@@ -34,7 +36,7 @@ pub struct SimBlock {
 
 impl SimBlock {
     /// Enables state overrides
-    pub fn with_state_overrides(mut self, overrides: StateOverride) -> Self {
+    pub fn with_state_overrides(mut self, overrides: RpcStateOverride) -> Self {
         self.state_overrides = Some(overrides);
         self
     }
@@ -16,9 +16,18 @@ pub struct AccountPendingTransactions {
 }
 
 #[derive(Default, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
 pub struct TxpoolStatus {
     pub pending: U64,
     pub queued: U64,
+    /// The pending transactions from the local accounts, included in
+    /// `pending`.
+    #[serde(default)]
+    pub local_pending: U64,
+    /// The queued transactions from the local accounts, included in
+    /// `queued`.
+    #[serde(default)]
+    pub local_queued: U64,
 }
 
 /// Transaction summary as found in the Txpool Inspection property.
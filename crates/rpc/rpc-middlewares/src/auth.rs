@@ -0,0 +1,729 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use cfx_rpc_utils::error::jsonrpsee_error_helpers::{
+    request_rejected_too_many_request_error,
+    request_rejected_unauthorized_error,
+};
+use futures::FutureExt;
+use hmac::{Hmac, Mac};
+use http::{header::AUTHORIZATION, HeaderMap, Request as HttpRequest};
+use jsonrpsee::{
+    core::{
+        middleware::{BatchEntry, BatchEntryErr, ResponseFuture},
+        RpcResult,
+    },
+    server::{
+        middleware::rpc::{Batch, Notification, RpcServiceT},
+        MethodResponse,
+    },
+    types::Request,
+};
+use lazy_static::lazy_static;
+use log::debug;
+use metrics::{
+    register_timer_family, Counter, CounterUsize, MetricFamily, ScopeTimer,
+    Timer,
+};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashMap,
+    fmt,
+    future::Future,
+    str::FromStr,
+    sync::Arc,
+    task::{Context, Poll},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use throttling::token_bucket::{
+    ThrottleResult, TokenBucket, TokenBucketManager,
+};
+use tower::{Layer, Service};
+
+lazy_static! {
+    static ref RPC_CLIENT_REQUEST_TIMERS: Arc<MetricFamily<dyn Timer>> =
+        register_timer_family("rpc_client_request", &["client"]);
+    static ref RPC_CLIENT_REJECTED: Arc<MetricFamily<dyn Counter<usize>>> =
+        CounterUsize::register_family("rpc_client_rejected", &["client"]);
+    static ref RPC_CLIENT_THROTTLED: Arc<MetricFamily<dyn Counter<usize>>> =
+        CounterUsize::register_family("rpc_client_throttled", &["client"]);
+}
+
+/// Label of the requests without a valid credential.
+const UNKNOWN_CLIENT: &str = "unknown";
+
+/// Name of the token bucket shared by all the methods of a client.
+const ALL_METHODS_BUCKET: &str = "*";
+
+/// Header of the static API keys, as an alternative of
+/// `Authorization: Bearer <key>`.
+const API_KEY_HEADER: &str = "x-api-key";
+
+/// The credential carried by the HTTP request (or the WebSocket handshake),
+/// which is passed to [`Auth`] in the request extensions.
+#[derive(Clone, Debug)]
+pub enum Credential {
+    ApiKey(String),
+    Jwt(String),
+}
+
+impl Credential {
+    fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        if let Some(bearer) = headers
+            .get(AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+        {
+            let token = bearer.trim().to_string();
+            return Some(
+                if token.matches('.').count() == 2 {
+                    Credential::Jwt(token)
+                } else {
+                    Credential::ApiKey(token)
+                },
+            );
+        }
+
+        headers
+            .get(API_KEY_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .map(|key| Credential::ApiKey(key.trim().to_string()))
+    }
+}
+
+#[derive(Deserialize)]
+struct RawAuthConfig {
+    jwt_secret_file: Option<String>,
+    #[serde(default)]
+    clients: HashMap<String, RawClientConfig>,
+}
+
+#[derive(Deserialize)]
+struct RawClientConfig {
+    api_key: Option<String>,
+    allowed_methods: Option<Vec<String>>,
+    #[serde(default)]
+    throttle: HashMap<String, String>,
+}
+
+struct Client {
+    id: String,
+    /// Method names or prefixes ending with `*`; all the methods are allowed
+    /// if it is `None`.
+    allowed_methods: Option<Vec<String>>,
+    buckets: TokenBucketManager,
+}
+
+impl Client {
+    fn is_allowed(&self, method: &str) -> bool {
+        let Some(allowed) = &self.allowed_methods else {
+            return true;
+        };
+        allowed
+            .iter()
+            .any(|pattern| match pattern.strip_suffix('*') {
+                Some(prefix) => method.starts_with(prefix),
+                None => method == pattern,
+            })
+    }
+
+    fn throttle(&self, method: &str) -> RpcResult<()> {
+        for name in [ALL_METHODS_BUCKET, method] {
+            let Some(bucket) = self.buckets.get(name) else {
+                continue;
+            };
+            let result = bucket.lock().throttle_default();
+            let details = match result {
+                ThrottleResult::Success => continue,
+                ThrottleResult::Throttled(wait_time) => {
+                    format!("throttled in {:?}", wait_time)
+                }
+                ThrottleResult::AlreadyThrottled => {
+                    "already throttled, please try again later".into()
+                }
+            };
+            debug!("RPC {} of client {} throttled", method, self.id);
+            RPC_CLIENT_THROTTLED.with_label_values(&[&self.id]).inc(1);
+            return Err(request_rejected_too_many_request_error(Some(details)));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+struct JwtHeader {
+    alg: String,
+}
+
+#[derive(Deserialize)]
+struct JwtClaims {
+    sub: String,
+    exp: Option<u64>,
+    nbf: Option<u64>,
+}
+
+/// Authenticates the RPC callers with the static API keys or the HS256 JWTs,
+/// and applies the method allow-list and the token buckets of each caller.
+///
+/// The configuration is a TOML file like:
+///
+/// ```toml
+/// # Hex encoded secret shared with the JWT issuer. The `sub` claim of a JWT
+/// # is the id of the client.
+/// jwt_secret_file = "jwt.hex"
+///
+/// [clients.partner]
+/// api_key = "some-random-key"
+/// # All the methods are allowed if absent.
+/// allowed_methods = ["eth_*", "cfx_getStatus"]
+///
+/// # Token buckets in the format of the throttling configuration. The `*`
+/// # bucket is shared by all the methods.
+/// [clients.partner.throttle]
+/// "*" = "100,100,50,1,50"
+/// eth_call = "20,20,10,1,10"
+/// ```
+pub struct RpcAuth {
+    jwt_secret: Option<Vec<u8>>,
+    /// SHA-256 hashes of the API keys to the client ids.
+    api_keys: HashMap<[u8; 32], String>,
+    clients: HashMap<String, Client>,
+}
+
+impl RpcAuth {
+    pub fn load(file: &str) -> Result<Self, String> {
+        let content = std::fs::read_to_string(file)
+            .map_err(|e| format!("failed to read rpc auth file: {:?}", e))?;
+        let raw: RawAuthConfig = toml::from_str(&content)
+            .map_err(|e| format!("failed to parse rpc auth file: {:?}", e))?;
+
+        let jwt_secret = match raw.jwt_secret_file {
+            Some(path) => {
+                let hex_secret =
+                    std::fs::read_to_string(&path).map_err(|e| {
+                        format!("failed to read jwt secret file: {:?}", e)
+                    })?;
+                let secret =
+                    hex::decode(hex_secret.trim().trim_start_matches("0x"))
+                        .map_err(|e| format!("invalid jwt secret: {:?}", e))?;
+                if secret.len() < 32 {
+                    return Err("jwt secret must be at least 32 bytes".into());
+                }
+                Some(secret)
+            }
+            None => None,
+        };
+
+        let mut api_keys = HashMap::new();
+        let mut clients = HashMap::new();
+        for (id, raw_client) in raw.clients {
+            if id == UNKNOWN_CLIENT {
+                return Err(format!("client id {:?} is reserved", id));
+            }
+            if let Some(key) = raw_client.api_key {
+                if api_keys.insert(key_hash(&key), id.clone()).is_some() {
+                    return Err(format!("duplicate api key of client {}", id));
+                }
+            }
+
+            let mut buckets = TokenBucketManager::default();
+            for (name, bucket) in raw_client.throttle {
+                let bucket = TokenBucket::from_str(&bucket).map_err(|e| {
+                    format!("invalid token bucket {} of {}: {}", name, id, e)
+                })?;
+                buckets.register(name, bucket);
+            }
+
+            let client = Client {
+                id: id.clone(),
+                allowed_methods: raw_client.allowed_methods,
+                buckets,
+            };
+            clients.insert(id, client);
+        }
+
+        Ok(Self {
+            jwt_secret,
+            api_keys,
+            clients,
+        })
+    }
+
+    /// Returns the id of the client if the request is allowed.
+    fn check(
+        &self, credential: Option<&Credential>, method: &str,
+    ) -> RpcResult<&str> {
+        let client = match self.authenticate(credential) {
+            Ok(client) => client,
+            Err(reason) => {
+                debug!("RPC {} unauthorized: {}", method, reason);
+                RPC_CLIENT_REJECTED
+                    .with_label_values(&[UNKNOWN_CLIENT])
+                    .inc(1);
+                return Err(request_rejected_unauthorized_error(reason));
+            }
+        };
+
+        if !client.is_allowed(method) {
+            RPC_CLIENT_REJECTED.with_label_values(&[&client.id]).inc(1);
+            return Err(request_rejected_unauthorized_error(format!(
+                "method {} is not allowed",
+                method
+            )));
+        }
+        client.throttle(method)?;
+
+        Ok(&client.id)
+    }
+
+    fn authenticate(
+        &self, credential: Option<&Credential>,
+    ) -> Result<&Client, String> {
+        let id = match credential {
+            None => return Err("missing credential".into()),
+            Some(Credential::ApiKey(key)) => self
+                .api_keys
+                .get(&key_hash(key))
+                .cloned()
+                .ok_or_else(|| "invalid api key".to_string())?,
+            Some(Credential::Jwt(token)) => self.verify_jwt(token)?,
+        };
+        self.clients
+            .get(&id)
+            .ok_or_else(|| format!("unknown client {}", id))
+    }
+
+    /// Verifies the HS256 JWT and returns its subject.
+    fn verify_jwt(&self, token: &str) -> Result<String, String> {
+        let secret = self
+            .jwt_secret
+            .as_ref()
+            .ok_or_else(|| "jwt is not enabled".to_string())?;
+
+        let mut parts = token.split('.');
+        let (Some(header), Some(claims), Some(signature), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err("malformed jwt".into());
+        };
+
+        let decode = |part: &str| {
+            URL_SAFE_NO_PAD
+                .decode(part)
+                .map_err(|_| "malformed jwt".to_string())
+        };
+        let jwt_header: JwtHeader = serde_json::from_slice(&decode(header)?)
+            .map_err(|_| "malformed jwt header".to_string())?;
+        if jwt_header.alg != "HS256" {
+            return Err(format!(
+                "unsupported jwt algorithm {}",
+                jwt_header.alg
+            ));
+        }
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret)
+            .expect("hmac accepts keys of any size");
+        mac.update(header.as_bytes());
+        mac.update(b".");
+        mac.update(claims.as_bytes());
+        mac.verify_slice(&decode(signature)?)
+            .map_err(|_| "invalid jwt signature".to_string())?;
+
+        let claims: JwtClaims = serde_json::from_slice(&decode(claims)?)
+            .map_err(|_| "malformed jwt claims".to_string())?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time after unix epoch")
+            .as_secs();
+        if claims.exp.map_or(false, |exp| exp <= now) {
+            return Err("jwt expired".into());
+        }
+        if claims.nbf.map_or(false, |nbf| nbf > now) {
+            return Err("jwt not yet valid".into());
+        }
+
+        Ok(claims.sub)
+    }
+}
+
+impl fmt::Debug for RpcAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The secrets are not printed.
+        f.debug_struct("RpcAuth")
+            .field("jwt_enabled", &self.jwt_secret.is_some())
+            .field("clients", &self.clients.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+fn key_hash(key: &str) -> [u8; 32] { Sha256::digest(key.as_bytes()).into() }
+
+/// HTTP middleware that passes the credential in the headers to [`Auth`].
+#[derive(Clone, Default)]
+pub struct CredentialLayer;
+
+impl<S> Layer<S> for CredentialLayer {
+    type Service = CredentialService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service { CredentialService { inner } }
+}
+
+#[derive(Clone)]
+pub struct CredentialService<S> {
+    inner: S,
+}
+
+impl<S, B> Service<HttpRequest<B>> for CredentialService<S>
+where S: Service<HttpRequest<B>>
+{
+    type Error = S::Error;
+    type Future = S::Future;
+    type Response = S::Response;
+
+    fn poll_ready(
+        &mut self, cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: HttpRequest<B>) -> Self::Future {
+        // Do not trust a credential inserted by the client in other ways.
+        req.extensions_mut().remove::<Credential>();
+        if let Some(credential) = Credential::from_headers(req.headers()) {
+            req.extensions_mut().insert(credential);
+        }
+        self.inner.call(req)
+    }
+}
+
+/// RPC middleware that rejects the requests of the unauthenticated callers,
+/// and the requests exceeding the quotas of the callers.
+#[derive(Clone)]
+pub struct Auth<S> {
+    service: S,
+    auth: Option<Arc<RpcAuth>>,
+}
+
+impl<S> Auth<S> {
+    pub fn new(auth: Option<Arc<RpcAuth>>, service: S) -> Self {
+        Auth { service, auth }
+    }
+
+    /// Returns the id of the client, or `None` if the authentication is not
+    /// enabled.
+    fn before(&self, req: &Request) -> RpcResult<Option<String>> {
+        let Some(auth) = &self.auth else {
+            return Ok(None);
+        };
+        let credential = req.extensions().get::<Credential>();
+        auth.check(credential, req.method_name())
+            .map(|id| Some(id.to_string()))
+    }
+}
+
+impl<S> RpcServiceT for Auth<S>
+where S: RpcServiceT<
+            MethodResponse = MethodResponse,
+            BatchResponse = MethodResponse,
+            NotificationResponse = MethodResponse,
+        > + Send
+        + Sync
+        + Clone
+        + 'static
+{
+    type BatchResponse = S::BatchResponse;
+    type MethodResponse = S::MethodResponse;
+    type NotificationResponse = S::NotificationResponse;
+
+    fn call<'a>(
+        &self, req: Request<'a>,
+    ) -> impl Future<Output = Self::MethodResponse> + Send + 'a {
+        match self.before(&req) {
+            Ok(client) => {
+                let timer = client.map(|client| {
+                    ScopeTimer::time_scope(
+                        RPC_CLIENT_REQUEST_TIMERS.with_label_values(&[&client]),
+                    )
+                });
+                let service = self.service.clone();
+                Box::pin(async move {
+                    let resp = service.call(req).await;
+                    drop(timer);
+                    resp
+                })
+                .boxed()
+            }
+            Err(e) => {
+                debug!("auth interceptor: method `{}` rejected", req.method);
+                Box::pin(async move { MethodResponse::error(req.id, e) })
+                    .boxed()
+            }
+        }
+    }
+
+    fn batch<'a>(
+        &self, mut batch: Batch<'a>,
+    ) -> impl Future<Output = Self::BatchResponse> + Send + 'a {
+        for entry in batch.iter_mut() {
+            let result = match entry {
+                Ok(BatchEntry::Call(req)) => {
+                    self.before(req).map_err(|e| (req.id.clone(), e))
+                }
+                Ok(BatchEntry::Notification(_)) => continue,
+                Err(_) => continue,
+            };
+
+            if let Err((id, e)) = result {
+                // This will create a new error response for batch and replace
+                // the method call
+                *entry = Err(BatchEntryErr::new(id, e));
+            }
+        }
+
+        self.service.batch(batch)
+    }
+
+    fn notification<'a>(
+        &self, n: Notification<'a>,
+    ) -> impl Future<Output = Self::NotificationResponse> + Send + 'a {
+        let Some(auth) = &self.auth else {
+            return ResponseFuture::future(self.service.notification(n));
+        };
+        let credential = n.extensions().get::<Credential>();
+        match auth.check(credential, n.method_name()) {
+            Ok(_) => ResponseFuture::future(self.service.notification(n)),
+            // Notifications are not expected to return a response so just
+            // drop the rejected ones.
+            Err(_e) => ResponseFuture::ready(MethodResponse::notification()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpsee::types::{Id, ResponsePayload};
+
+    const SECRET: [u8; 32] = [7; 32];
+
+    fn now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    }
+
+    /// Client `a` has an API key and a method allow-list, `b` and `c` share
+    /// the same quota of 2 requests.
+    fn test_auth() -> RpcAuth {
+        let mut api_keys = HashMap::new();
+        let mut clients = HashMap::new();
+        for (id, allowed_methods, throttle) in [
+            (
+                "a",
+                Some(vec!["eth_*".into(), "cfx_getStatus".into()]),
+                None,
+            ),
+            ("b", None, Some("2,2,0,1,0")),
+            ("c", None, Some("2,2,0,1,0")),
+        ] {
+            api_keys.insert(key_hash(&format!("key-{}", id)), id.to_string());
+            let mut buckets = TokenBucketManager::default();
+            if let Some(bucket) = throttle {
+                buckets.register(
+                    ALL_METHODS_BUCKET.into(),
+                    TokenBucket::from_str(bucket).unwrap(),
+                );
+            }
+            let client = Client {
+                id: id.to_string(),
+                allowed_methods,
+                buckets,
+            };
+            clients.insert(id.to_string(), client);
+        }
+        RpcAuth {
+            jwt_secret: Some(SECRET.to_vec()),
+            api_keys,
+            clients,
+        }
+    }
+
+    fn jwt(alg: &str, claims: serde_json::Value, secret: &[u8]) -> String {
+        let header = URL_SAFE_NO_PAD
+            .encode(serde_json::json!({"alg": alg, "typ": "JWT"}).to_string());
+        let claims = URL_SAFE_NO_PAD.encode(claims.to_string());
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret).unwrap();
+        mac.update(format!("{}.{}", header, claims).as_bytes());
+        let signature =
+            URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes().as_slice());
+        format!("{}.{}.{}", header, claims, signature)
+    }
+
+    fn check_jwt(auth: &RpcAuth, token: String) -> RpcResult<String> {
+        auth.check(Some(&Credential::Jwt(token)), "eth_call")
+            .map(str::to_string)
+    }
+
+    #[test]
+    fn test_jwt() {
+        let auth = test_auth();
+        let claims = serde_json::json!({"sub": "a", "exp": now() + 60});
+        assert_eq!(
+            check_jwt(&auth, jwt("HS256", claims.clone(), &SECRET)).unwrap(),
+            "a"
+        );
+
+        assert_eq!(
+            auth.verify_jwt(&jwt("HS256", claims.clone(), &[8; 32])),
+            Err("invalid jwt signature".into())
+        );
+        assert_eq!(
+            auth.verify_jwt(&jwt("none", claims.clone(), &SECRET)),
+            Err("unsupported jwt algorithm none".into())
+        );
+        assert_eq!(
+            auth.verify_jwt(&jwt(
+                "HS256",
+                serde_json::json!({"sub": "a", "exp": now() - 1}),
+                &SECRET
+            )),
+            Err("jwt expired".into())
+        );
+        assert_eq!(
+            auth.verify_jwt(&jwt(
+                "HS256",
+                serde_json::json!({"sub": "a", "nbf": now() + 60}),
+                &SECRET
+            )),
+            Err("jwt not yet valid".into())
+        );
+
+        // A valid token of an unknown client.
+        let token = jwt("HS256", serde_json::json!({"sub": "d"}), &SECRET);
+        assert_eq!(auth.verify_jwt(&token), Ok("d".into()));
+        assert!(check_jwt(&auth, token).is_err());
+    }
+
+    #[test]
+    fn test_api_key() {
+        let auth = test_auth();
+        let check = |headers: &[(&'static str, &str)]| {
+            let mut header_map = HeaderMap::new();
+            for (name, value) in headers {
+                header_map.insert(*name, value.parse().unwrap());
+            }
+            let credential = Credential::from_headers(&header_map);
+            auth.check(credential.as_ref(), "eth_call")
+                .map(str::to_string)
+        };
+
+        assert_eq!(check(&[(API_KEY_HEADER, "key-a")]).unwrap(), "a");
+        assert_eq!(check(&[("authorization", "Bearer key-a")]).unwrap(), "a");
+        assert!(check(&[(API_KEY_HEADER, "key-d")]).is_err());
+        assert!(check(&[("authorization", "Basic key-a")]).is_err());
+        assert!(check(&[]).is_err());
+    }
+
+    #[test]
+    fn test_allowed_methods() {
+        let auth = test_auth();
+        let credential = Credential::ApiKey("key-a".into());
+        let check = |method| auth.check(Some(&credential), method).is_ok();
+
+        assert!(check("eth_call"));
+        assert!(check("eth_getBalance"));
+        assert!(check("cfx_getStatus"));
+        assert!(!check("cfx_getStatusOf"));
+        assert!(!check("cfx_call"));
+        assert!(!check("debug_traceTransaction"));
+    }
+
+    #[test]
+    fn test_throttle_per_client() {
+        let auth = test_auth();
+        let b = Credential::ApiKey("key-b".into());
+        let c = Credential::ApiKey("key-c".into());
+
+        assert!(auth.check(Some(&b), "eth_call").is_ok());
+        assert!(auth.check(Some(&b), "cfx_call").is_ok());
+        let err = auth.check(Some(&b), "eth_call").unwrap_err();
+        assert_eq!(
+            err.code(),
+            request_rejected_too_many_request_error(None).code()
+        );
+
+        // The quota of `c` is not used by `b`.
+        assert!(auth.check(Some(&c), "eth_call").is_ok());
+        assert!(auth.check(Some(&c), "eth_call").is_ok());
+        assert!(auth.check(Some(&c), "eth_call").is_err());
+    }
+
+    /// Responds to a batch with whether each entry is passed as a request.
+    #[derive(Clone)]
+    struct BatchEntries;
+
+    impl RpcServiceT for BatchEntries {
+        type BatchResponse = MethodResponse;
+        type MethodResponse = MethodResponse;
+        type NotificationResponse = MethodResponse;
+
+        fn call<'a>(
+            &self, req: Request<'a>,
+        ) -> impl Future<Output = Self::MethodResponse> + Send + 'a {
+            let resp = MethodResponse::response(
+                req.id,
+                ResponsePayload::success(true),
+                usize::MAX,
+            );
+            async move { resp }
+        }
+
+        fn batch<'a>(
+            &self, batch: Batch<'a>,
+        ) -> impl Future<Output = Self::BatchResponse> + Send + 'a {
+            let entries: Vec<bool> =
+                batch.iter().map(|entry| entry.is_ok()).collect();
+            let resp = MethodResponse::response(
+                Id::Null,
+                ResponsePayload::success(entries),
+                usize::MAX,
+            );
+            async move { resp }
+        }
+
+        fn notification<'a>(
+            &self, _n: Notification<'a>,
+        ) -> impl Future<Output = Self::NotificationResponse> + Send + 'a
+        {
+            async move { MethodResponse::notification() }
+        }
+    }
+
+    #[test]
+    fn test_batch_entries_rejected() {
+        let service = Auth::new(Some(Arc::new(test_auth())), BatchEntries);
+        let request =
+            |id: u64, method: &str, credential: Option<Credential>| {
+                let mut req =
+                    Request::owned(method.to_string(), None, Id::Number(id));
+                if let Some(credential) = credential {
+                    req.extensions_mut().insert(credential);
+                }
+                Ok(BatchEntry::Call(req))
+            };
+        let batch = Batch::from(vec![
+            request(1, "eth_call", Some(Credential::ApiKey("key-a".into()))),
+            request(2, "cfx_call", Some(Credential::ApiKey("key-a".into()))),
+            request(3, "eth_call", None),
+            request(4, "eth_call", Some(Credential::ApiKey("key-b".into()))),
+        ]);
+
+        let resp = futures::executor::block_on(service.batch(batch));
+        let resp: serde_json::Value =
+            serde_json::from_str(resp.to_json().get()).unwrap();
+        assert_eq!(
+            resp["result"],
+            serde_json::json!([true, false, false, true])
+        );
+    }
+}
@@ -1,8 +1,10 @@
+mod auth;
 mod cors;
 mod log;
 mod metrics;
 mod throttle;
 
+pub use auth::{Auth, Credential, CredentialLayer, CredentialService, RpcAuth};
 pub use cors::{create_cors_layer, maybe_cors_layer, CorsDomainError};
 pub use log::Logger;
 pub use metrics::Metrics;
@@ -9,13 +9,18 @@ use jsonrpsee::{
 };
 use lazy_static::lazy_static;
 use log::debug;
-use metrics::{register_timer_with_group, ScopeTimer, Timer};
+use metrics::{
+    register_timer_family, register_timer_with_group, MetricFamily, ScopeTimer,
+    Timer,
+};
 use parking_lot::Mutex;
 use std::{collections::HashMap, future::Future, sync::Arc};
 
 lazy_static! {
     static ref METRICS_INTERCEPTOR_TIMERS: Mutex<HashMap<String, Arc<dyn Timer>>> =
         Default::default();
+    static ref RPC_REQUEST_TIMERS: Arc<MetricFamily<dyn Timer>> =
+        register_timer_family("async_rpc_request", &["method"]);
 }
 #[derive(Clone)]
 pub struct Metrics<S> {
@@ -68,10 +73,15 @@ where S: RpcServiceT<MethodResponse = MethodResponse>
                     .lock()
                     .get(req.method_name())
                     .map(|timer| timer.clone());
+                let request_timer =
+                    RPC_REQUEST_TIMERS.with_label_values(&[req.method_name()]);
                 let setup = lazy(move |_| {
-                    Ok(maybe_timer
-                        .as_ref()
-                        .map(|timer| ScopeTimer::time_scope(timer.clone())))
+                    Ok((
+                        maybe_timer
+                            .as_ref()
+                            .map(|timer| ScopeTimer::time_scope(timer.clone())),
+                        ScopeTimer::time_scope(request_timer),
+                    ))
                 });
 
                 let service = self.service.clone();
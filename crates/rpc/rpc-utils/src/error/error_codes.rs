@@ -67,6 +67,9 @@ pub const REQUEST_REJECTED_TOO_MANY_REQUESTS: i64 = -32072;
 /// This is mostly an application error but it's generic enough to define it
 /// here.
 pub const REQUEST_REJECTED_LIMIT_DATA: i64 = -32041;
+/// When the caller is not authenticated, or it is not allowed to call the
+/// method.
+pub const REQUEST_REJECTED_UNAUTHORIZED: i64 = -32079;
 
 /* Conflux node status related error codes
  *
@@ -109,6 +109,30 @@ pub fn request_rejected_too_many_request_error(
     )
 }
 
+pub fn request_rejected_unauthorized_error(
+    details: String,
+) -> ErrorObjectOwned {
+    ErrorObjectOwned::owned(
+        codes::REQUEST_REJECTED_UNAUTHORIZED as i32,
+        "Request rejected: unauthorized.",
+        Some(details),
+    )
+}
+
+/// The node does not have mining work for the remote miners.
+pub fn no_work(details: String) -> ErrorObjectOwned {
+    ErrorObjectOwned::owned(
+        codes::INCAPABLE as i32,
+        "No mining work available.",
+        Some(details),
+    )
+}
+
+/// The solution submitted by a remote miner is rejected.
+pub fn cannot_submit_work(details: String) -> ErrorObjectOwned {
+    invalid_params_rpc_err("Cannot submit work.", Some(details))
+}
+
 pub fn pivot_assumption_failed(expected: H256, got: H256) -> ErrorObjectOwned {
     ErrorObjectOwned::owned(
         codes::CONFLUX_PIVOT_CHAIN_UNSTABLE as i32,
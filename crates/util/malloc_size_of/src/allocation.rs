@@ -0,0 +1,110 @@
+//! Per-thread accounting of the heap allocations.
+
+use std::{
+    alloc::{GlobalAlloc, Layout},
+    cell::Cell,
+    marker::PhantomData,
+};
+
+thread_local! {
+    /// The bytes allocated by the current thread in the innermost
+    /// [`AllocationScope`], net of the deallocations.
+    static ALLOCATED: Cell<Option<isize>> = const { Cell::new(None) };
+}
+
+/// Adds to the allocation count of the current thread, if it is in an
+/// [`AllocationScope`].
+#[inline]
+fn count(bytes: isize) {
+    // Doesn't allocate, the thread local has a const initializer and no
+    // destructor.
+    let _ = ALLOCATED.try_with(|allocated| {
+        if let Some(total) = allocated.get() {
+            allocated.set(Some(total.wrapping_add(bytes)));
+        }
+    });
+}
+
+/// Wraps an allocator to count the allocations of the threads in an
+/// [`AllocationScope`].
+pub struct CountingAllocator<A>(A);
+
+impl<A> CountingAllocator<A> {
+    pub const fn new(inner: A) -> Self { Self(inner) }
+}
+
+unsafe impl<A: GlobalAlloc> GlobalAlloc for CountingAllocator<A> {
+    #[inline]
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.0.alloc(layout);
+        if !ptr.is_null() {
+            count(layout.size() as isize);
+        }
+        ptr
+    }
+
+    #[inline]
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.0.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            count(layout.size() as isize);
+        }
+        ptr
+    }
+
+    #[inline]
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.0.dealloc(ptr, layout);
+        count(-(layout.size() as isize));
+    }
+
+    #[inline]
+    unsafe fn realloc(
+        &self, ptr: *mut u8, layout: Layout, new_size: usize,
+    ) -> *mut u8 {
+        let new_ptr = self.0.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            count(new_size as isize - layout.size() as isize);
+        }
+        new_ptr
+    }
+}
+
+/// Counts the bytes allocated by the current thread while the scope is alive,
+/// net of the deallocations.
+///
+/// Only the allocations of a [`CountingAllocator`] are counted, so the count
+/// stays zero if it is not the global allocator. The count of a nested scope
+/// is added to the outer one when it is dropped.
+pub struct AllocationScope {
+    outer: Option<isize>,
+    // The count is kept in a thread local.
+    _not_send: PhantomData<*const ()>,
+}
+
+impl AllocationScope {
+    pub fn new() -> Self {
+        Self {
+            outer: ALLOCATED.with(|allocated| allocated.replace(Some(0))),
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Returns the bytes allocated since the scope was created.
+    pub fn allocated(&self) -> isize {
+        ALLOCATED.with(|allocated| allocated.get().unwrap_or_default())
+    }
+}
+
+impl Default for AllocationScope {
+    fn default() -> Self { Self::new() }
+}
+
+impl Drop for AllocationScope {
+    fn drop(&mut self) {
+        let inner = self.allocated();
+        ALLOCATED.with(|allocated| {
+            allocated.set(self.outer.map(|outer| outer.wrapping_add(inner)))
+        });
+    }
+}
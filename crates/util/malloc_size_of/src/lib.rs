@@ -10,6 +10,9 @@
 
 //! A reduced fork of Firefox's malloc_size_of crate, for bundling with
 //! WebRender.
+
+pub mod allocation;
+
 use cfg_if::cfg_if;
 use cfx_types::{
     AddressWithSpace, AllChainID, Space, SpaceMap, H160, H256, H512, U256, U512,
@@ -3,6 +3,8 @@
 //! We provide support for jemalloc and snmalloc on unix systems, and prefer
 //! jemalloc if both are enabled.
 
+use malloc_size_of::allocation::CountingAllocator;
+
 // We provide jemalloc allocator support, alongside snmalloc. If both features
 // are enabled, jemalloc is prioritized.
 cfg_if::cfg_if! {
@@ -37,8 +39,11 @@ cfg_if::cfg_if! {
     }
 }
 
-/// Custom allocator.
-pub type Allocator = AllocatorWrapper;
+/// Custom allocator, which also counts the allocations of the threads in an
+/// [`AllocationScope`](malloc_size_of::allocation::AllocationScope).
+pub type Allocator = CountingAllocator<AllocatorWrapper>;
 
 /// Creates a new [custom allocator][Allocator].
-pub const fn new_allocator() -> Allocator { new_allocator_wrapper() }
+pub const fn new_allocator() -> Allocator {
+    CountingAllocator::new(new_allocator_wrapper())
+}
@@ -3,6 +3,7 @@
 // See http://www.gnu.org/licenses/
 
 use crate::{
+    family::{register_family, MetricFamily},
     metrics::{is_enabled, Metric, ORDER},
     registry::{DEFAULT_GROUPING_REGISTRY, DEFAULT_REGISTRY},
 };
@@ -55,6 +56,26 @@ macro_rules! construct_counter {
 
                 counter
             }
+
+            /// Registers a family of counters labelled by `label_names`.
+            pub fn register_family(
+                name: &str, label_names: &[&str],
+            ) -> Arc<MetricFamily<dyn Counter<$data_type>>> {
+                let enabled = is_enabled();
+                register_family(name, label_names, enabled, move || {
+                    if !enabled {
+                        return (
+                            Arc::new(NoopCounter) as Arc<dyn Counter<_>>,
+                            None,
+                        );
+                    }
+                    let counter = Arc::new($name::default());
+                    (
+                        counter.clone() as Arc<dyn Counter<_>>,
+                        Some(counter as Arc<dyn Metric>),
+                    )
+                })
+            }
         }
 
         impl Counter<$data_type> for $name {
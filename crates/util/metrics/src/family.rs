@@ -0,0 +1,107 @@
+// Copyright 2019 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use crate::{metrics::Metric, registry::DEFAULT_REGISTRY};
+use parking_lot::RwLock;
+use std::{collections::BTreeMap, sync::Arc};
+
+type NewMetric<T> = dyn Fn() -> (Arc<T>, Option<Arc<dyn Metric>>) + Send + Sync;
+
+/// A family of metrics sharing one name, each identified by the values of the
+/// labels, e.g. the request timers labelled by RPC method or the traffic
+/// counters labelled by peer.
+pub struct MetricFamily<T: ?Sized> {
+    label_names: Vec<String>,
+    metrics: RwLock<BTreeMap<Vec<String>, (Arc<T>, Arc<dyn Metric>)>>,
+    // Returns the metric to update and the metric to report, which is None if
+    // the metrics are disabled.
+    new_metric: Box<NewMetric<T>>,
+}
+
+impl<T: ?Sized> MetricFamily<T> {
+    fn new(
+        label_names: &[&str],
+        new_metric: impl Fn() -> (Arc<T>, Option<Arc<dyn Metric>>)
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        MetricFamily {
+            label_names: label_names.iter().map(|l| l.to_string()).collect(),
+            metrics: Default::default(),
+            new_metric: Box::new(new_metric),
+        }
+    }
+
+    /// Returns the metric of the label values, which is created on first
+    /// use. The values must be given in the order of the label names.
+    pub fn with_label_values(&self, values: &[&str]) -> Arc<T> {
+        assert_eq!(
+            values.len(),
+            self.label_names.len(),
+            "Label values {:?} do not match label names {:?}",
+            values,
+            self.label_names
+        );
+        let key: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+        if let Some((metric, _)) = self.metrics.read().get(&key) {
+            return metric.clone();
+        }
+
+        let mut metrics = self.metrics.write();
+        if let Some((metric, _)) = metrics.get(&key) {
+            return metric.clone();
+        }
+        let (metric, reported) = (self.new_metric)();
+        if let Some(reported) = reported {
+            metrics.insert(key, (metric.clone(), reported));
+        }
+        metric
+    }
+
+    /// Stops reporting the metric of the label values, e.g. when a peer
+    /// disconnects.
+    pub fn remove_label_values(&self, values: &[&str]) {
+        let key: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+        self.metrics.write().remove(&key);
+    }
+}
+
+/// The type-erased view of a [`MetricFamily`] kept in the registry.
+pub trait Family: Send + Sync {
+    fn label_names(&self) -> &[String];
+
+    /// Returns the label values and the metric of each member.
+    fn get_all(&self) -> Vec<(Vec<String>, Arc<dyn Metric>)>;
+}
+
+impl<T: ?Sized + Send + Sync> Family for MetricFamily<T> {
+    fn label_names(&self) -> &[String] { &self.label_names }
+
+    fn get_all(&self) -> Vec<(Vec<String>, Arc<dyn Metric>)> {
+        self.metrics
+            .read()
+            .iter()
+            .map(|(values, (_, metric))| (values.clone(), metric.clone()))
+            .collect()
+    }
+}
+
+/// Creates a family and registers it to the default registry if the metrics
+/// are enabled.
+pub(crate) fn register_family<T: ?Sized + Send + Sync + 'static>(
+    name: &str, label_names: &[&str], enabled: bool,
+    new_metric: impl Fn() -> (Arc<T>, Option<Arc<dyn Metric>>)
+        + Send
+        + Sync
+        + 'static,
+) -> Arc<MetricFamily<T>> {
+    let family = Arc::new(MetricFamily::new(label_names, new_metric));
+    if enabled {
+        DEFAULT_REGISTRY
+            .write()
+            .register_family(name.into(), family.clone());
+    }
+    family
+}
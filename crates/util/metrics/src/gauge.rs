@@ -3,6 +3,7 @@
 // See http://www.gnu.org/licenses/
 
 use crate::{
+    family::{register_family, MetricFamily},
     metrics::{is_enabled, Metric, ORDER},
     registry::{DEFAULT_GROUPING_REGISTRY, DEFAULT_REGISTRY},
 };
@@ -54,6 +55,26 @@ macro_rules! construct_gauge {
 
                 gauge
             }
+
+            /// Registers a family of gauges labelled by `label_names`.
+            pub fn register_family(
+                name: &str, label_names: &[&str],
+            ) -> Arc<MetricFamily<dyn Gauge<$data_type>>> {
+                let enabled = is_enabled();
+                register_family(name, label_names, enabled, move || {
+                    if !enabled {
+                        return (
+                            Arc::new(NoopGauge) as Arc<dyn Gauge<_>>,
+                            None,
+                        );
+                    }
+                    let gauge = Arc::new($name::default());
+                    (
+                        gauge.clone() as Arc<dyn Gauge<_>>,
+                        Some(gauge as Arc<dyn Metric>),
+                    )
+                })
+            }
         }
 
         impl Gauge<$data_type> for $name {
@@ -3,6 +3,7 @@
 // See http://www.gnu.org/licenses/
 
 use crate::{
+    family::{register_family, MetricFamily},
     metrics::{is_enabled, Metric},
     registry::{DEFAULT_GROUPING_REGISTRY, DEFAULT_REGISTRY},
 };
@@ -26,6 +27,8 @@ pub trait Histogram: Send + Sync {
     fn sum(&self) -> u64 { 0 }
     fn update(&self, _v: u64) {}
     fn variance(&self) -> f64 { 0.0 }
+    /// Returns the values in the sample, in ascending order.
+    fn values(&self) -> Vec<u64> { Vec::new() }
     fn update_since(&self, start_time: Instant) {
         self.update(
             Instant::now()
@@ -35,6 +38,7 @@ pub trait Histogram: Send + Sync {
     }
 }
 
+#[derive(Clone, Copy)]
 pub enum Sample {
     Uniform,
     ExpDecay(f64),
@@ -100,6 +104,38 @@ impl Sample {
             }
         }
     }
+
+    /// Registers a family of histograms labelled by `label_names`.
+    pub fn register_family(
+        &self, name: &str, label_names: &[&str], reservoir_size: usize,
+    ) -> Arc<MetricFamily<dyn Histogram>> {
+        assert!(reservoir_size > 0);
+
+        let enabled = is_enabled();
+        let sample = *self;
+        register_family(name, label_names, enabled, move || {
+            if !enabled {
+                return (Arc::new(NoopHistogram) as Arc<dyn Histogram>, None);
+            }
+            match sample {
+                Sample::Uniform => {
+                    let sample = Arc::new(UniformSample::new(reservoir_size));
+                    (
+                        sample.clone() as Arc<dyn Histogram>,
+                        Some(sample as Arc<dyn Metric>),
+                    )
+                }
+                Sample::ExpDecay(alpha) => {
+                    let sample =
+                        Arc::new(ExpDecaySample::new(alpha, reservoir_size));
+                    (
+                        sample.clone() as Arc<dyn Histogram>,
+                        Some(sample as Arc<dyn Metric>),
+                    )
+                }
+            }
+        })
+    }
 }
 
 struct NoopHistogram;
@@ -133,6 +169,12 @@ impl Histogram for Snapshot {
     fn sum(&self) -> u64 { self.values.iter().sum() }
 
     fn variance(&self) -> f64 { sample_variance(&self.values) }
+
+    fn values(&self) -> Vec<u64> {
+        let mut values = self.values.clone();
+        values.sort();
+        values
+    }
 }
 
 fn sample_percentile(sorted_values: &[u64], p: f64) -> u64 {
@@ -240,14 +282,14 @@ struct ExpDecaySampleData {
 /// Decay Model for Streaming Systems".
 ///
 /// <http://dimacs.rutgers.edu/~graham/pubs/papers/fwddecay.pdf>
-struct ExpDecaySample {
+pub(crate) struct ExpDecaySample {
     alpha: f64,
     reservoir_size: usize,
     data: RwLock<ExpDecaySampleData>,
 }
 
 impl ExpDecaySample {
-    fn new(alpha: f64, reservoir_size: usize) -> Self {
+    pub(crate) fn new(alpha: f64, reservoir_size: usize) -> Self {
         let now = Instant::now();
         ExpDecaySample {
             alpha,
@@ -4,6 +4,7 @@
 
 mod counter;
 mod ewma;
+mod family;
 mod gauge;
 mod histogram;
 mod lock;
@@ -14,10 +15,12 @@ mod registry;
 mod report;
 mod report_influxdb;
 mod report_prometheus;
+mod report_prometheus_push;
 mod timer;
 
 pub use self::{
     counter::{Counter, CounterUsize},
+    family::{Family, MetricFamily},
     gauge::{Gauge, GaugeUsize},
     histogram::{Histogram, Sample},
     lock::{Lock, MutexExtensions, RwLockExtensions},
@@ -27,6 +30,11 @@ pub use self::{
     registry::{
         GroupingRegistry, Registry, DEFAULT_GROUPING_REGISTRY, DEFAULT_REGISTRY,
     },
-    report::{report_async, Reportable, Reporter},
-    timer::{register_timer, register_timer_with_group, ScopeTimer, Timer},
+    report::{report_async, report_on_stop, Reportable, Reporter},
+    report_prometheus::{ExpositionFormat, PrometheusReporter},
+    report_prometheus_push::PrometheusPushReporter,
+    timer::{
+        register_timer, register_timer_family, register_timer_with_group,
+        ScopeTimer, Timer, TimerHistogram,
+    },
 };
@@ -3,9 +3,12 @@
 // See http://www.gnu.org/licenses/
 
 use crate::{
-    report::{report_async, FileReporter, Reportable},
+    report::{
+        report_async, report_on_stop, report_stopped, FileReporter, Reportable,
+    },
     report_influxdb::{InfluxdbReportable, InfluxdbReporter},
     report_prometheus::{PrometheusReportable, PrometheusReporter},
+    report_prometheus_push::PrometheusPushReporter,
 };
 use cfx_tasks::TaskExecutor;
 use duration_str::deserialize_duration;
@@ -25,8 +28,12 @@ pub fn is_enabled() -> bool { ENABLED.load(ORDER) }
 
 pub fn enable() { ENABLED.store(true, ORDER); }
 
-/// Signal all metrics reporter threads to stop.
-pub fn stop() { STOPPED.store(true, ORDER); }
+/// Signal all metrics reporter threads to stop, and run the reporters that
+/// report on stop.
+pub fn stop() {
+    STOPPED.store(true, ORDER);
+    report_stopped();
+}
 
 pub fn is_stopped() -> bool { STOPPED.load(ORDER) }
 
@@ -52,6 +59,9 @@ pub struct MetricsConfiguration {
     pub influxdb_report_password: Option<String>,
     pub influxdb_report_node: Option<String>,
     pub prometheus_listen_addr: Option<String>,
+    pub prometheus_push_gateway: Option<String>,
+    pub prometheus_push_job: String,
+    pub prometheus_push_instance: Option<String>,
 }
 
 impl Default for MetricsConfiguration {
@@ -66,6 +76,9 @@ impl Default for MetricsConfiguration {
             influxdb_report_password: None,
             influxdb_report_node: None,
             prometheus_listen_addr: None,
+            prometheus_push_gateway: None,
+            prometheus_push_job: "conflux".into(),
+            prometheus_push_instance: None,
         }
     }
 }
@@ -131,4 +144,26 @@ pub fn initialize(config: MetricsConfiguration, executor: TaskExecutor) {
             }
         }
     }
+
+    // prometheus push gateway reporter
+    if let Some(gateway) = config.prometheus_push_gateway {
+        let new_reporter = || {
+            PrometheusPushReporter::new(
+                &gateway,
+                &config.prometheus_push_job,
+                config.prometheus_push_instance.as_deref(),
+            )
+        };
+        match new_reporter().and_then(|r| Ok((r, new_reporter()?))) {
+            Ok((reporter, stop_reporter)) => {
+                info!("Pushing metrics to Prometheus push gateway {}", gateway);
+                report_async(reporter, config.report_interval);
+                // Short-lived nodes may exit before the first push.
+                report_on_stop(stop_reporter);
+            }
+            Err(e) => {
+                error!("Failed to initialize PrometheusPushReporter: {}", e);
+            }
+        }
+    }
 }
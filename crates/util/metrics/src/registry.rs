@@ -2,7 +2,7 @@
 // Conflux is free software and distributed under GNU General Public License.
 // See http://www.gnu.org/licenses/
 
-use crate::metrics::Metric;
+use crate::{family::Family, metrics::Metric};
 use lazy_static::lazy_static;
 use parking_lot::RwLock;
 use std::{collections::HashMap, sync::Arc};
@@ -17,17 +17,36 @@ lazy_static! {
 #[derive(Default)]
 pub struct Registry {
     metrics: HashMap<String, Arc<dyn Metric>>,
+    families: HashMap<String, Arc<dyn Family>>,
 }
 
 impl Registry {
     pub fn register(&mut self, name: String, metric: Arc<dyn Metric>) {
         assert!(!self.metrics.contains_key(&name));
+        assert!(!self.families.contains_key(&name));
         self.metrics.insert(name, metric);
     }
 
+    pub fn register_family(&mut self, name: String, family: Arc<dyn Family>) {
+        assert!(
+            !self.metrics.contains_key(&name)
+                && !self.families.contains_key(&name),
+            "Metric name {:?} already exists",
+            &name
+        );
+        self.families.insert(name, family);
+    }
+
     pub fn get_all(&self) -> &HashMap<String, Arc<dyn Metric>> { &self.metrics }
 
-    pub fn clear(&mut self) { self.metrics.clear(); }
+    pub fn get_families(&self) -> &HashMap<String, Arc<dyn Family>> {
+        &self.families
+    }
+
+    pub fn clear(&mut self) {
+        self.metrics.clear();
+        self.families.clear();
+    }
 }
 
 #[derive(Default)]
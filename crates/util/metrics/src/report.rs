@@ -9,8 +9,10 @@ use crate::{
     meter::{Meter, StandardMeter},
     metrics::{is_enabled, is_stopped},
     registry::{DEFAULT_GROUPING_REGISTRY, DEFAULT_REGISTRY},
+    timer::TimerHistogram,
 };
 use lazy_static::lazy_static;
+use parking_lot::Mutex;
 use rand::Rng;
 use std::{
     fs::OpenOptions,
@@ -25,6 +27,8 @@ lazy_static! {
         GaugeUsize::register("metrics_report_time");
     static ref REPORT_FAILURE_COUNTER: Arc<dyn Counter<usize>> =
         CounterUsize::register("metrics_report_failures");
+    static ref STOP_REPORTERS: Mutex<Vec<Box<dyn Reporter>>> =
+        Default::default();
 }
 
 pub trait Reporter: Send {
@@ -61,6 +65,33 @@ pub fn report_async<R: 'static + Reporter>(reporter: R, interval: Duration) {
     });
 }
 
+/// Reports once more when the metrics are stopped, so that the metrics of a
+/// node that exits between two reports are not lost.
+pub fn report_on_stop<R: 'static + Reporter>(reporter: R) {
+    if !is_enabled() {
+        return;
+    }
+    STOP_REPORTERS.lock().push(Box::new(reporter));
+}
+
+pub(crate) fn report_stopped() {
+    let reporters = std::mem::take(&mut *STOP_REPORTERS.lock());
+    if reporters.is_empty() {
+        return;
+    }
+
+    // The reporters may block on their own runtime, which is not allowed on
+    // the thread of another runtime.
+    let handle = thread::spawn(move || {
+        for reporter in reporters {
+            if let Err(e) = reporter.report() {
+                eprintln!("Failed to report metrics on stop: {}", e);
+            }
+        }
+    });
+    let _ = handle.join();
+}
+
 pub struct FileReporter {
     file_path: String,
 }
@@ -113,6 +144,29 @@ impl Reporter for FileReporter {
             .map_err(|e| format!("failed to write file, {:?}", e))?;
         }
 
+        for (name, family) in DEFAULT_REGISTRY.read().get_families() {
+            for (values, metric) in family.get_all() {
+                let labels: Vec<String> = family
+                    .label_names()
+                    .iter()
+                    .zip(values.iter())
+                    .map(|(k, v)| format!("{}=\"{}\"", k, v))
+                    .collect();
+                file.write(
+                    format!(
+                        "{}, {}{{{}}}, {}, {}\n",
+                        now.as_millis(),
+                        name,
+                        labels.join(","),
+                        metric.get_type(),
+                        metric.get_value()
+                    )
+                    .as_bytes(),
+                )
+                .map_err(|e| format!("failed to write file, {:?}", e))?;
+            }
+        }
+
         Ok(true)
     }
 }
@@ -207,3 +261,11 @@ impl<T: Histogram> Reportable for T {
         )
     }
 }
+
+impl Reportable for TimerHistogram {
+    fn get_value(&self) -> String { self.sample.get_value() }
+
+    fn get_value_with_group(&self, name: &str) -> String {
+        self.sample.get_value_with_group(name)
+    }
+}
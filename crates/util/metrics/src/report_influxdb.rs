@@ -9,6 +9,7 @@ use crate::{
     meter::{Meter, StandardMeter},
     registry::{DEFAULT_GROUPING_REGISTRY, DEFAULT_REGISTRY},
     report::Reporter,
+    timer::TimerHistogram,
 };
 use influx_db_client::{
     reqwest::ClientBuilder as HttpClientBuilder, Client, Point, Points,
@@ -102,6 +103,23 @@ impl Reporter for InfluxdbReporter {
             points = points.push(point);
         }
 
+        // Each member of a family is a point tagged with its label values.
+        for (name, family) in DEFAULT_REGISTRY.read().get_families() {
+            for (values, metric) in family.get_all() {
+                let mut point = Point::new(name);
+                point = metric.add_field(point, None);
+
+                for (k, v) in family.label_names().iter().zip(values) {
+                    point = point.add_tag(k.clone(), Value::String(v));
+                }
+                for (k, v) in &self.tags {
+                    point = point.add_tag(k.clone(), Value::String(v.clone()));
+                }
+
+                points = points.push(point);
+            }
+        }
+
         if let Err(e) = self.runtime.block_on(self.client.write_points(
             points,
             Some(Precision::Milliseconds),
@@ -216,3 +234,9 @@ impl<T: Histogram> InfluxdbReportable for T {
             )
     }
 }
+
+impl InfluxdbReportable for TimerHistogram {
+    fn add_field(&self, point: Point, prefix: Option<&String>) -> Point {
+        self.sample.add_field(point, prefix)
+    }
+}
@@ -9,11 +9,13 @@ use crate::{
     meter::{Meter, StandardMeter},
     metrics::is_enabled,
     registry::{DEFAULT_GROUPING_REGISTRY, DEFAULT_REGISTRY},
+    timer::TimerHistogram,
 };
 use hyper_util::rt::TokioIo;
 use lazy_static::lazy_static;
 use log::{error, info};
 use std::{
+    collections::HashMap,
     convert::Infallible,
     fmt::{self, Write},
     net::SocketAddr,
@@ -22,7 +24,10 @@ use std::{
 use tokio::net::TcpListener;
 
 use cfx_tasks::TaskExecutor;
-use hyper::{server::conn::http1, service::service_fn, Response, StatusCode};
+use hyper::{
+    header::ACCEPT, server::conn::http1, service::service_fn, Response,
+    StatusCode,
+};
 pub struct PrometheusReporter {
     listen_addr: SocketAddr,
     executor: TaskExecutor,
@@ -32,6 +37,112 @@ lazy_static! {
     static ref PREVIOUS_METRICS_SIZE: AtomicUsize = AtomicUsize::new(24576); // default size 24KB
 }
 
+/// Upper bounds of the histogram buckets in the OpenMetrics output, in the
+/// 1-2-5 series.
+const HISTOGRAM_BUCKETS: [f64; 28] = [
+    1.0, 2.0, 5.0, 1e1, 2e1, 5e1, 1e2, 2e2, 5e2, 1e3, 2e3, 5e3, 1e4, 2e4, 5e4,
+    1e5, 2e5, 5e5, 1e6, 2e6, 5e6, 1e7, 2e7, 5e7, 1e8, 2e8, 5e8, 1e9,
+];
+
+/// Upper bounds of the timer buckets in the OpenMetrics output, in seconds.
+const TIMER_BUCKETS: [f64; 14] = [
+    0.00001, 0.0001, 0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5,
+    5.0, 10.0,
+];
+
+/// The text formats in which the metrics are exposed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExpositionFormat {
+    /// The Prometheus text format 0.0.4
+    Prometheus,
+    /// The OpenMetrics text format 1.0.0
+    OpenMetrics,
+}
+
+impl ExpositionFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExpositionFormat::Prometheus => "text/plain; version=0.0.4",
+            ExpositionFormat::OpenMetrics => {
+                "application/openmetrics-text; version=1.0.0; charset=utf-8"
+            }
+        }
+    }
+
+    /// Picks the format requested by the `Accept` header of a scrape.
+    pub fn from_accept_header(accept: Option<&str>) -> Self {
+        match accept {
+            Some(accept) if accept.contains("application/openmetrics-text") => {
+                ExpositionFormat::OpenMetrics
+            }
+            _ => ExpositionFormat::Prometheus,
+        }
+    }
+}
+
+/// A metric family in the exposition, with the samples of all members.
+pub struct PrometheusFamily {
+    pub name: String,
+    pub metric_type: &'static str,
+    pub help: String,
+    pub samples: Vec<PrometheusSample>,
+}
+
+pub struct PrometheusSample {
+    /// Appended to the family name, e.g. `_bucket`
+    pub suffix: &'static str,
+    pub labels: Vec<(String, String)>,
+    pub value: String,
+}
+
+impl PrometheusFamily {
+    fn new(name: &str, metric_type: &'static str, help: &str) -> Self {
+        PrometheusFamily {
+            name: name.into(),
+            metric_type,
+            help: help.into(),
+            samples: vec![],
+        }
+    }
+
+    fn with_sample<T: ToString>(
+        mut self, suffix: &'static str, labels: Vec<(String, String)>, value: T,
+    ) -> Self {
+        self.samples.push(PrometheusSample {
+            suffix,
+            labels,
+            value: value.to_string(),
+        });
+        self
+    }
+
+    pub fn write(&self, buffer: &mut dyn Write) -> fmt::Result {
+        writeln!(buffer, "# HELP {} {}", self.name, escape(&self.help, false))?;
+        writeln!(buffer, "# TYPE {} {}", self.name, self.metric_type)?;
+        for sample in &self.samples {
+            write!(buffer, "{}{}", self.name, sample.suffix)?;
+            if !sample.labels.is_empty() {
+                let labels: Vec<String> = sample
+                    .labels
+                    .iter()
+                    .map(|(k, v)| format!("{}=\"{}\"", k, escape(v, true)))
+                    .collect();
+                write!(buffer, "{{{}}}", labels.join(","))?;
+            }
+            writeln!(buffer, " {}", sample.value)?;
+        }
+        Ok(())
+    }
+}
+
+fn escape(text: &str, quote: bool) -> String {
+    let mut escaped = text.replace('\\', "\\\\").replace('\n', "\\n");
+    if quote {
+        escaped = escaped.replace('"', "\\\"");
+    }
+    escaped
+}
+
 impl PrometheusReporter {
     pub fn new(
         listen_addr: &str, executor: TaskExecutor,
@@ -96,8 +207,11 @@ impl PrometheusReporter {
 
                 let io = TokioIo::new(stream);
 
-                let service = service_fn(|_req| async {
-                    let metrics_data = match PrometheusReporter::collect_metrics() {
+                let service = service_fn(|req| async move {
+                    let format = ExpositionFormat::from_accept_header(
+                        req.headers().get(ACCEPT).and_then(|v| v.to_str().ok()),
+                    );
+                    let metrics_data = match PrometheusReporter::collect_metrics_with_format(format) {
                         Ok(data) => data,
                         Err(e) => {
                             error!("Failed to collect metrics: {}", e);
@@ -108,10 +222,7 @@ impl PrometheusReporter {
                     };
                     let response = Response::builder()
                         .status(StatusCode::OK)
-                        .header(
-                            "content-type",
-                            "text/plain; version=0.0.4",
-                        )
+                        .header("content-type", format.content_type())
                         .body(metrics_data)
                         .unwrap_or_else(|e| {
                             error!("Failed to create response: {}", e);
@@ -134,6 +245,12 @@ impl PrometheusReporter {
     }
 
     pub fn collect_metrics() -> Result<String, fmt::Error> {
+        Self::collect_metrics_with_format(ExpositionFormat::Prometheus)
+    }
+
+    pub fn collect_metrics_with_format(
+        format: ExpositionFormat,
+    ) -> Result<String, fmt::Error> {
         let capacity = PREVIOUS_METRICS_SIZE.load(Ordering::Relaxed);
         // Increase the buffer size by 25%
         let mut buffer = String::with_capacity(capacity + capacity / 4);
@@ -141,134 +258,267 @@ impl PrometheusReporter {
         let registry = DEFAULT_REGISTRY.read();
 
         for (name, metric) in registry.get_all() {
-            metric.write_prometheus(name, None, &mut buffer)?;
+            for family in metric.prometheus_families(name, format) {
+                family.write(&mut buffer)?;
+            }
         }
 
         let grouping_registry = DEFAULT_GROUPING_REGISTRY.read();
         for (group_name, metrics) in grouping_registry.get_all() {
             for (metric_name, metric) in metrics {
-                metric.write_prometheus(
-                    metric_name,
-                    Some(group_name),
-                    &mut buffer,
-                )?;
+                let full_name = format!("{}_{}", group_name, metric_name);
+                for family in metric.prometheus_families(&full_name, format) {
+                    family.write(&mut buffer)?;
+                }
             }
         }
 
+        // The members of a labelled family are merged into the same exposed
+        // families, with the labels added to each sample.
+        for (name, family) in registry.get_families() {
+            let mut merged: Vec<PrometheusFamily> = vec![];
+            let mut index: HashMap<String, usize> = HashMap::new();
+            for (values, metric) in family.get_all() {
+                let labels: Vec<(String, String)> = family
+                    .label_names()
+                    .iter()
+                    .cloned()
+                    .zip(values.into_iter())
+                    .collect();
+                for mut exposed in metric.prometheus_families(name, format) {
+                    for sample in exposed.samples.iter_mut() {
+                        let mut sample_labels = labels.clone();
+                        sample_labels.append(&mut sample.labels);
+                        sample.labels = sample_labels;
+                    }
+                    match index.get(&exposed.name) {
+                        Some(i) => {
+                            merged[*i].samples.append(&mut exposed.samples)
+                        }
+                        None => {
+                            index.insert(exposed.name.clone(), merged.len());
+                            merged.push(exposed);
+                        }
+                    }
+                }
+            }
+            for exposed in merged {
+                exposed.write(&mut buffer)?;
+            }
+        }
+
+        if format == ExpositionFormat::OpenMetrics {
+            writeln!(buffer, "# EOF")?;
+        }
+
         PREVIOUS_METRICS_SIZE.store(buffer.len(), Ordering::Relaxed);
         Ok(buffer)
     }
 }
+
 pub trait PrometheusReportable {
-    fn write_prometheus(
-        &self, name: &str, group: Option<&str>, buffer: &mut dyn Write,
-    ) -> std::fmt::Result;
-}
-impl PrometheusReportable for CounterUsize {
+    /// Returns the exposed families of the metric. A metric may be exposed as
+    /// several families, e.g. the count and the rates of a meter.
+    fn prometheus_families(
+        &self, name: &str, format: ExpositionFormat,
+    ) -> Vec<PrometheusFamily>;
+
     fn write_prometheus(
         &self, name: &str, group: Option<&str>, buffer: &mut dyn Write,
     ) -> std::fmt::Result {
         let full_name = group
             .map_or_else(|| name.to_string(), |g| format!("{}_{}", g, name));
-        writeln!(buffer, "# HELP {} {}", full_name, full_name)?;
-        writeln!(buffer, "# TYPE {} counter", full_name)?;
-        writeln!(buffer, "{} {}", full_name, self.count())?;
+        for family in
+            self.prometheus_families(&full_name, ExpositionFormat::Prometheus)
+        {
+            family.write(buffer)?;
+        }
         Ok(())
     }
 }
-impl PrometheusReportable for GaugeUsize {
-    fn write_prometheus(
-        &self, name: &str, group: Option<&str>, buffer: &mut dyn Write,
-    ) -> std::fmt::Result {
-        let full_name = group
-            .map_or_else(|| name.to_string(), |g| format!("{}_{}", g, name));
 
-        writeln!(buffer, "# HELP {} {}", full_name, full_name)?;
-        writeln!(buffer, "# TYPE {} gauge", full_name)?;
-        writeln!(buffer, "{} {}", full_name, self.value())?;
-        Ok(())
+// An OpenMetrics counter is named without the `_total` suffix, which is added
+// to its sample.
+fn counter_family<T: ToString>(
+    name: &str, help: &str, format: ExpositionFormat, value: T,
+) -> PrometheusFamily {
+    match format {
+        ExpositionFormat::Prometheus => PrometheusFamily::new(
+            name, "counter", help,
+        )
+        .with_sample("", vec![], value),
+        ExpositionFormat::OpenMetrics => PrometheusFamily::new(
+            name.strip_suffix("_total").unwrap_or(name),
+            "counter",
+            help,
+        )
+        .with_sample("_total", vec![], value),
+    }
+}
+
+impl PrometheusReportable for CounterUsize {
+    fn prometheus_families(
+        &self, name: &str, format: ExpositionFormat,
+    ) -> Vec<PrometheusFamily> {
+        vec![counter_family(name, name, format, self.count())]
     }
 }
+
+impl PrometheusReportable for GaugeUsize {
+    fn prometheus_families(
+        &self, name: &str, _format: ExpositionFormat,
+    ) -> Vec<PrometheusFamily> {
+        vec![PrometheusFamily::new(name, "gauge", name).with_sample(
+            "",
+            vec![],
+            self.value(),
+        )]
+    }
+}
+
 impl PrometheusReportable for StandardMeter {
-    fn write_prometheus(
-        &self, name: &str, group: Option<&str>, buffer: &mut dyn Write,
-    ) -> std::fmt::Result {
-        let base_name = group
-            .map_or_else(|| name.to_string(), |g| format!("{}_{}", g, name));
+    fn prometheus_families(
+        &self, name: &str, format: ExpositionFormat,
+    ) -> Vec<PrometheusFamily> {
         let snapshot = self.snapshot();
-        // count (counter)
-        let count_name = format!("{}_total", base_name);
-        writeln!(buffer, "# HELP {} Total number of events.", count_name)?;
-        writeln!(buffer, "# TYPE {} counter", count_name)?;
-        writeln!(buffer, "{} {}", count_name, snapshot.count())?;
-        // rates (gauge)
-        let m1_name = format!("{}_m1_rate", base_name);
-
-        writeln!(
-            buffer,
-            "# HELP {} One-minute exponentially-weighted moving average rate.",
-            m1_name
-        )?;
-        writeln!(buffer, "# TYPE {} gauge", m1_name)?;
-        writeln!(buffer, "{} {}", m1_name, snapshot.rate1())?;
-        let m5_name = format!("{}_m5_rate", base_name);
-        writeln!(
-            buffer,
-            "# HELP {} Five-minute exponentially-weighted moving average rate.",
-            m5_name
-        )?;
-        writeln!(buffer, "# TYPE {} gauge", m5_name)?;
-        writeln!(buffer, "{} {}", m5_name, snapshot.rate5())?;
-        let m15_name = format!("{}_m15_rate", base_name);
-        writeln!(buffer, "# HELP {} Fifteen-minute exponentially-weighted moving average rate.", m15_name)?;
-        writeln!(buffer, "# TYPE {} gauge", m15_name)?;
-        writeln!(buffer, "{} {}", m15_name, snapshot.rate15())?;
-        let mean_name = format!("{}_mean_rate", base_name);
-        writeln!(
-            buffer,
-            "# HELP {} Mean rate since the meter was created.",
-            mean_name
-        )?;
-        writeln!(buffer, "# TYPE {} gauge", mean_name)?;
-        writeln!(buffer, "{} {}", mean_name, snapshot.rate_mean())?;
-        let m0_name = format!("{}_m0_rate", base_name);
-        writeln!(
-            buffer,
-            "# HELP {} Raw rate over the last tick window.",
-            m0_name
-        )?;
-        writeln!(buffer, "# TYPE {} gauge", m0_name)?;
-        writeln!(buffer, "{} {}", m0_name, snapshot.rate_m0())?;
-        Ok(())
+        let rate = |suffix: &str, help: &str, value: f64| {
+            PrometheusFamily::new(
+                &format!("{}_{}", name, suffix),
+                "gauge",
+                help,
+            )
+            .with_sample("", vec![], value)
+        };
+        vec![
+            counter_family(
+                &format!("{}_total", name),
+                "Total number of events.",
+                format,
+                snapshot.count(),
+            ),
+            rate(
+                "m1_rate",
+                "One-minute exponentially-weighted moving average rate.",
+                snapshot.rate1(),
+            ),
+            rate(
+                "m5_rate",
+                "Five-minute exponentially-weighted moving average rate.",
+                snapshot.rate5(),
+            ),
+            rate(
+                "m15_rate",
+                "Fifteen-minute exponentially-weighted moving average rate.",
+                snapshot.rate15(),
+            ),
+            rate(
+                "mean_rate",
+                "Mean rate since the meter was created.",
+                snapshot.rate_mean(),
+            ),
+            rate(
+                "m0_rate",
+                "Raw rate over the last tick window.",
+                snapshot.rate_m0(),
+            ),
+        ]
     }
 }
-impl<T: Histogram> PrometheusReportable for T {
-    fn write_prometheus(
-        &self, name: &str, group: Option<&str>, buffer: &mut dyn Write,
-    ) -> std::fmt::Result {
-        let base_name = group
-            .map_or_else(|| name.to_string(), |g| format!("{}_{}", g, name));
-        let snapshot = self.snapshot();
-
-        writeln!(buffer, "# HELP {} {}", base_name, base_name)?;
-        writeln!(buffer, "# TYPE {} summary", base_name)?;
 
-        writeln!(buffer, "{}_count {}", base_name, snapshot.count())?;
-        writeln!(buffer, "{}_sum {}", base_name, snapshot.sum())?;
+fn summary_family(name: &str, snapshot: &dyn Histogram) -> PrometheusFamily {
+    let mut family = PrometheusFamily::new(name, "summary", name)
+        .with_sample("_count", vec![], snapshot.count())
+        .with_sample("_sum", vec![], snapshot.sum())
+        .with_sample("_min", vec![], snapshot.min())
+        .with_sample("_max", vec![], snapshot.max())
+        .with_sample("_mean", vec![], snapshot.mean())
+        .with_sample("_stddev", vec![], snapshot.stddev())
+        .with_sample("_variance", vec![], snapshot.variance());
+
+    let quantiles = [0.5, 0.75, 0.9, 0.95, 0.99, 0.999];
+    for q in quantiles.iter() {
+        family = family.with_sample(
+            "",
+            vec![("quantile".into(), q.to_string())],
+            snapshot.percentile(*q),
+        );
+    }
+    family
+}
 
-        writeln!(buffer, "{}_min {}", base_name, snapshot.min())?;
-        writeln!(buffer, "{}_max {}", base_name, snapshot.max())?;
-        writeln!(buffer, "{}_mean {}", base_name, snapshot.mean())?;
-        writeln!(buffer, "{}_stddev {}", base_name, snapshot.stddev())?;
-        writeln!(buffer, "{}_variance {}", base_name, snapshot.variance())?;
+// The buckets are estimated from the values in the sample, scaled to the
+// count of all the observed values.
+fn histogram_family(
+    name: &str, help: &str, snapshot: &dyn Histogram, buckets: &[f64],
+    scale: f64,
+) -> PrometheusFamily {
+    let values = snapshot.values();
+    let count = snapshot.count();
+    let ratio = if values.is_empty() {
+        0.0
+    } else {
+        count as f64 / values.len() as f64
+    };
+
+    let mut family = PrometheusFamily::new(name, "histogram", help);
+    let mut observed = 0;
+    for bound in buckets {
+        while observed < values.len()
+            && values[observed] as f64 * scale <= *bound
+        {
+            observed += 1;
+        }
+        family = family.with_sample(
+            "_bucket",
+            vec![("le".into(), bound.to_string())],
+            (observed as f64 * ratio).round() as u64,
+        );
+    }
+    let sum: f64 =
+        values.iter().map(|v| *v as f64 * scale).sum::<f64>() * ratio;
+    family
+        .with_sample("_bucket", vec![("le".into(), "+Inf".into())], count)
+        .with_sample("_count", vec![], count)
+        .with_sample("_sum", vec![], sum)
+}
 
-        let quantiles = [0.5, 0.75, 0.9, 0.95, 0.99, 0.999];
-        for q in quantiles.iter() {
-            let value = snapshot.percentile(*q);
-            writeln!(buffer, "{}{{quantile=\"{}\"}} {}", base_name, q, value)?;
+impl<T: Histogram> PrometheusReportable for T {
+    fn prometheus_families(
+        &self, name: &str, format: ExpositionFormat,
+    ) -> Vec<PrometheusFamily> {
+        let snapshot = self.snapshot();
+        match format {
+            ExpositionFormat::Prometheus => {
+                vec![summary_family(name, snapshot.as_ref())]
+            }
+            ExpositionFormat::OpenMetrics => vec![histogram_family(
+                name,
+                "Distribution of the sampled values.",
+                snapshot.as_ref(),
+                &HISTOGRAM_BUCKETS,
+                1.0,
+            )],
         }
+    }
+}
 
-        Ok(())
+impl PrometheusReportable for TimerHistogram {
+    fn prometheus_families(
+        &self, name: &str, format: ExpositionFormat,
+    ) -> Vec<PrometheusFamily> {
+        let snapshot = self.sample().snapshot();
+        match format {
+            ExpositionFormat::Prometheus => {
+                vec![summary_family(name, snapshot.as_ref())]
+            }
+            ExpositionFormat::OpenMetrics => vec![histogram_family(
+                name,
+                "Duration of the timed events in seconds.",
+                snapshot.as_ref(),
+                &TIMER_BUCKETS,
+                1e-9,
+            )],
+        }
     }
 }
 
@@ -547,4 +797,77 @@ mod tests {
             buffer.contains("node_test_request_histogram{quantile=\"0.999\"}")
         );
     }
+
+    #[test]
+    fn test_openmetrics_counter() {
+        let counter = CounterUsize::default();
+        counter.inc(3);
+
+        let mut buffer = String::new();
+        for family in counter
+            .prometheus_families("test_counter", ExpositionFormat::OpenMetrics)
+        {
+            family.write(&mut buffer).unwrap();
+        }
+        assert!(buffer.contains("# TYPE test_counter counter"));
+        assert!(buffer.contains("test_counter_total 3"));
+    }
+
+    #[test]
+    fn test_openmetrics_histogram() {
+        let histogram = crate::histogram::UniformSample::new(99);
+        histogram.update(1);
+        histogram.update(2);
+        histogram.update(10);
+        histogram.update(100);
+        histogram.update(1000);
+
+        let mut buffer = String::new();
+        for family in histogram.prometheus_families(
+            "test_histogram",
+            ExpositionFormat::OpenMetrics,
+        ) {
+            family.write(&mut buffer).unwrap();
+        }
+        assert!(buffer.contains("# TYPE test_histogram histogram"));
+        assert!(buffer.contains("test_histogram_bucket{le=\"1\"} 1"));
+        assert!(buffer.contains("test_histogram_bucket{le=\"10\"} 3"));
+        assert!(buffer.contains("test_histogram_bucket{le=\"500\"} 4"));
+        assert!(buffer.contains("test_histogram_bucket{le=\"+Inf\"} 5"));
+        assert!(buffer.contains("test_histogram_count 5"));
+        assert!(buffer.contains("test_histogram_sum 1113"));
+    }
+
+    #[test]
+    fn test_family_prometheus() {
+        reset_registries();
+
+        let family =
+            CounterUsize::register_family("test_family_requests", &["method"]);
+        family.with_label_values(&["cfx_call"]).inc(2);
+        family.with_label_values(&["cfx_epochNumber"]).inc(5);
+
+        let body = PrometheusReporter::collect_metrics().unwrap();
+        assert_eq!(
+            body.matches("# TYPE test_family_requests counter").count(),
+            1
+        );
+        assert!(body.contains("test_family_requests{method=\"cfx_call\"} 2"));
+        assert!(
+            body.contains("test_family_requests{method=\"cfx_epochNumber\"} 5")
+        );
+
+        let body = PrometheusReporter::collect_metrics_with_format(
+            ExpositionFormat::OpenMetrics,
+        )
+        .unwrap();
+        assert!(
+            body.contains("test_family_requests_total{method=\"cfx_call\"} 2")
+        );
+        assert!(body.ends_with("# EOF\n"));
+
+        family.remove_label_values(&["cfx_call"]);
+        let body = PrometheusReporter::collect_metrics().unwrap();
+        assert!(!body.contains("cfx_call"));
+    }
 }
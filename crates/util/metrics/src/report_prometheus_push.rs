@@ -0,0 +1,142 @@
+// Copyright 2019 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use crate::{
+    report::Reporter,
+    report_prometheus::{ExpositionFormat, PrometheusReporter},
+};
+use base64::{engine::general_purpose::URL_SAFE, Engine};
+use log::debug;
+use reqwest::{header::CONTENT_TYPE, Client, Url};
+use std::time::Duration;
+use tokio::runtime::{Builder, Runtime};
+
+const PUSH_TIMEOUT_SECONDS: u64 = 30;
+
+/// Pushes the metrics to a Prometheus push gateway, for nodes that cannot be
+/// scraped, e.g. short-lived test nodes or nodes behind a NAT.
+pub struct PrometheusPushReporter {
+    runtime: Runtime,
+    client: Client,
+    url: Url,
+}
+
+impl PrometheusPushReporter {
+    /// The metrics are grouped by `job` and `instance` on the gateway, and
+    /// each push replaces the metrics previously pushed to the same group.
+    pub fn new(
+        gateway: &str, job: &str, instance: Option<&str>,
+    ) -> Result<Self, String> {
+        let url = push_url(gateway, job, instance)?;
+
+        let client = Client::builder()
+            .timeout(Duration::from_secs(PUSH_TIMEOUT_SECONDS))
+            .build()
+            .map_err(|e| format!("failed to build http client: {}", e))?;
+        let runtime = Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| format!("failed to build runtime: {}", e))?;
+
+        Ok(PrometheusPushReporter {
+            runtime,
+            client,
+            url,
+        })
+    }
+}
+
+impl Reporter for PrometheusPushReporter {
+    fn report(&self) -> Result<bool, String> {
+        let format = ExpositionFormat::Prometheus;
+        let body = PrometheusReporter::collect_metrics_with_format(format)
+            .map_err(|e| format!("failed to collect metrics: {:?}", e))?;
+
+        let result = self.runtime.block_on(
+            self.client
+                .put(self.url.clone())
+                .header(CONTENT_TYPE, format.content_type())
+                .body(body)
+                .send(),
+        );
+
+        match result.and_then(|response| response.error_for_status()) {
+            Ok(_) => Ok(true),
+            Err(e) => {
+                debug!("failed to push metrics to {}, {:?}", self.url, e);
+                Ok(false)
+            }
+        }
+    }
+}
+
+/// Builds the url of the grouping key `job` and `instance` on the gateway.
+fn push_url(
+    gateway: &str, job: &str, instance: Option<&str>,
+) -> Result<Url, String> {
+    let mut url = Url::parse(gateway)
+        .map_err(|e| format!("invalid push gateway url {}: {}", gateway, e))?;
+    {
+        let mut segments = url.path_segments_mut().map_err(|_| {
+            format!("invalid push gateway url {}: not a base", gateway)
+        })?;
+        segments.pop_if_empty().push("metrics");
+        for (name, value) in Some(("job", job))
+            .into_iter()
+            .chain(instance.map(|i| ("instance", i)))
+        {
+            let (name, value) = grouping_label(name, value);
+            segments.push(&name).push(&value);
+        }
+    }
+    Ok(url)
+}
+
+/// Returns the path segments of a grouping label. The values which cannot be
+/// a path segment, i.e. the empty ones and those containing `/`, are encoded
+/// in base64 as the push gateway requires. Other values are percent-encoded
+/// when pushed to the url.
+fn grouping_label(name: &str, value: &str) -> (String, String) {
+    if value.is_empty() {
+        (format!("{}@base64", name), "=".into())
+    } else if value.contains('/') {
+        (format!("{}@base64", name), URL_SAFE.encode(value))
+    } else {
+        (name.into(), value.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::push_url;
+
+    #[test]
+    fn test_push_url() {
+        assert_eq!(
+            push_url("http://gateway:9091/", "conflux", None)
+                .unwrap()
+                .as_str(),
+            "http://gateway:9091/metrics/job/conflux"
+        );
+        assert_eq!(
+            push_url("http://gateway:9091/prefix", "conflux", Some("node 1"))
+                .unwrap()
+                .as_str(),
+            "http://gateway:9091/prefix/metrics/job/conflux/instance/node%201"
+        );
+        assert_eq!(
+            push_url("http://gateway:9091", "a/b", Some(""))
+                .unwrap()
+                .as_str(),
+            "http://gateway:9091/metrics/job@base64/YS9i/instance@base64/="
+        );
+        assert_eq!(
+            push_url("http://gateway:9091", "a?b#c", None)
+                .unwrap()
+                .as_str(),
+            "http://gateway:9091/metrics/job/a%3Fb%23c"
+        );
+        assert!(push_url("gateway:9091", "conflux", None).is_err());
+    }
+}
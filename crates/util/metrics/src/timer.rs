@@ -3,9 +3,11 @@
 // See http://www.gnu.org/licenses/
 
 use crate::{
-    histogram::{Histogram, Sample},
+    family::{register_family, MetricFamily},
+    histogram::{ExpDecaySample, Histogram},
     meter::{register_meter_with_group, Meter},
-    metrics::is_enabled,
+    metrics::{is_enabled, Metric},
+    registry::DEFAULT_GROUPING_REGISTRY,
 };
 use std::{
     sync::Arc,
@@ -26,10 +28,15 @@ fn register_timer_exp_decay(
     if !is_enabled() {
         Arc::new(NoopTimer)
     } else {
+        let histogram = Arc::new(TimerHistogram::new());
+        DEFAULT_GROUPING_REGISTRY.write().register(
+            group.into(),
+            time_name.into(),
+            histogram.clone(),
+        );
         Arc::new(StandardTimer {
             meter: register_meter_with_group(group, counter_name),
-            histogram: Sample::ExpDecay(0.015)
-                .register_with_group(group, time_name, 1024),
+            histogram,
         })
     }
 }
@@ -44,19 +51,63 @@ pub fn register_timer_with_group(group: &str, name: &str) -> Arc<dyn Timer> {
     register_timer_exp_decay(group, counter_name.as_str(), time_name.as_str())
 }
 
+/// Registers a family of timers labelled by `label_names`. Unlike the timers
+/// registered by [`register_timer`], the members have no meter, as the count
+/// of the histogram tells the number of events.
+pub fn register_timer_family(
+    name: &str, label_names: &[&str],
+) -> Arc<MetricFamily<dyn Timer>> {
+    let enabled = is_enabled();
+    register_family(name, label_names, enabled, move || {
+        if !enabled {
+            return (Arc::new(NoopTimer) as Arc<dyn Timer>, None);
+        }
+        let histogram = Arc::new(TimerHistogram::new());
+        (
+            histogram.clone() as Arc<dyn Timer>,
+            Some(histogram as Arc<dyn Metric>),
+        )
+    })
+}
+
 struct NoopTimer;
 impl Timer for NoopTimer {}
 
 struct StandardTimer {
     meter: Arc<dyn Meter>,
-    histogram: Arc<dyn Histogram>,
+    histogram: Arc<TimerHistogram>,
 }
 
 impl Timer for StandardTimer {
     fn update(&self, d: Duration) {
         self.meter.mark(1);
-        self.histogram.update(d.as_nanos() as u64);
+        self.histogram.update(d);
+    }
+}
+
+/// The histogram of the durations measured by a timer, in nanoseconds. It is
+/// reported the same way as other histograms, except that the OpenMetrics
+/// output is converted to seconds.
+pub struct TimerHistogram {
+    pub(crate) sample: ExpDecaySample,
+}
+
+impl TimerHistogram {
+    fn new() -> Self {
+        TimerHistogram {
+            sample: ExpDecaySample::new(0.015, 1024),
+        }
     }
+
+    pub fn sample(&self) -> &dyn Histogram { &self.sample }
+}
+
+impl Timer for TimerHistogram {
+    fn update(&self, d: Duration) { self.sample.update(d.as_nanos() as u64); }
+}
+
+impl Metric for TimerHistogram {
+    fn get_type(&self) -> &str { "Timer" }
 }
 
 pub struct ScopeTimer {
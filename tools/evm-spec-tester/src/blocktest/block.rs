@@ -0,0 +1,432 @@
+use cfx_rpc_eth_types::Bytes;
+use cfx_types::{Address, U256, U64};
+use eest_types::{BlockHeader, SpecId};
+use keccak_hash::{keccak, KECCAK_EMPTY_LIST_RLP};
+use primitives::TransactionWithSignature;
+use rlp::{Decodable, DecoderError, Rlp};
+use std::cmp::max;
+
+const MAXIMUM_EXTRA_DATA_SIZE: usize = 32;
+const MIN_GAS_LIMIT: u64 = 5000;
+const GAS_LIMIT_BOUND_DIVISOR: u64 = 1024;
+const ELASTICITY_MULTIPLIER: u64 = 2;
+const BASE_FEE_MAX_CHANGE_DENOMINATOR: u64 = 8;
+
+/// A block decoded from the RLP bytes in the fixture.
+pub struct DecodedBlock {
+    pub header: BlockHeader,
+    pub transactions: Vec<TransactionWithSignature>,
+    pub uncle_count: usize,
+    pub withdrawals: Option<Vec<Withdrawal>>,
+}
+
+/// EIP-4895 withdrawal, only the fields changing the state are kept.
+pub struct Withdrawal {
+    pub address: Address,
+    /// Amount in Gwei.
+    pub amount: u64,
+}
+
+pub fn decode_block(raw: &[u8]) -> Result<DecodedBlock, DecoderError> {
+    let rlp = Rlp::new(raw);
+    let item_count = rlp.item_count()?;
+    if item_count != 3 && item_count != 4 {
+        return Err(DecoderError::RlpIncorrectListLen);
+    }
+
+    let header = decode_header(&rlp.at(0)?)?;
+    let transactions = rlp.list_at(1)?;
+    let uncle_count = rlp.at(2)?.item_count()?;
+    let withdrawals = if item_count == 4 {
+        let mut withdrawals = vec![];
+        for item in rlp.at(3)?.iter() {
+            if item.item_count()? != 4 {
+                return Err(DecoderError::RlpIncorrectListLen);
+            }
+            withdrawals.push(Withdrawal {
+                address: item.val_at(2)?,
+                amount: item.val_at(3)?,
+            });
+        }
+        Some(withdrawals)
+    } else {
+        None
+    };
+
+    Ok(DecodedBlock {
+        header,
+        transactions,
+        uncle_count,
+        withdrawals,
+    })
+}
+
+fn decode_header(rlp: &Rlp) -> Result<BlockHeader, DecoderError> {
+    if rlp.item_count()? < 15 {
+        return Err(DecoderError::RlpIncorrectListLen);
+    }
+
+    // The nonce is a fixed length byte array instead of a number.
+    let nonce: Vec<u8> = rlp.val_at(14)?;
+    if nonce.len() != 8 {
+        return Err(DecoderError::RlpInvalidLength);
+    }
+
+    Ok(BlockHeader {
+        parent_hash: rlp.val_at(0)?,
+        uncle_hash: rlp.val_at(1)?,
+        coinbase: rlp.val_at(2)?,
+        state_root: rlp.val_at(3)?,
+        transactions_trie: rlp.val_at(4)?,
+        receipt_trie: rlp.val_at(5)?,
+        bloom: rlp.val_at(6)?,
+        difficulty: rlp.val_at(7)?,
+        number: rlp.val_at(8)?,
+        gas_limit: rlp.val_at(9)?,
+        gas_used: rlp.val_at(10)?,
+        timestamp: rlp.val_at(11)?,
+        extra_data: Bytes::new(rlp.val_at(12)?),
+        mix_hash: rlp.val_at(13)?,
+        nonce: U64::from_big_endian(&nonce),
+        base_fee_per_gas: optional_at(rlp, 15)?,
+        withdrawals_root: optional_at(rlp, 16)?,
+        blob_gas_used: optional_at(rlp, 17)?,
+        excess_blob_gas: optional_at(rlp, 18)?,
+        parent_beacon_block_root: optional_at(rlp, 19)?,
+        requests_hash: optional_at(rlp, 20)?,
+        hash: keccak(rlp.as_raw()),
+    })
+}
+
+fn optional_at<T: Decodable>(
+    rlp: &Rlp, index: usize,
+) -> Result<Option<T>, DecoderError> {
+    if rlp.item_count()? > index {
+        Ok(Some(rlp.val_at(index)?))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Checks the header against its parent before executing the block. The
+/// receipts, transactions and withdrawals roots are not checked.
+pub fn validate_header(
+    header: &BlockHeader, parent: &BlockHeader, uncle_count: usize,
+    has_withdrawals: bool, spec_id: SpecId,
+) -> Result<(), String> {
+    if header.parent_hash != parent.hash {
+        return Err(format!(
+            "parent hash mismatch: got {:?}, expected {:?}",
+            header.parent_hash, parent.hash
+        ));
+    }
+    if header.number != parent.number + 1 {
+        return Err(format!("invalid number {}", header.number));
+    }
+    if header.timestamp <= parent.timestamp {
+        return Err(format!(
+            "timestamp {} not greater than parent {}",
+            header.timestamp, parent.timestamp
+        ));
+    }
+    if header.extra_data.0.len() > MAXIMUM_EXTRA_DATA_SIZE {
+        return Err(format!(
+            "extra data too long: {}",
+            header.extra_data.0.len()
+        ));
+    }
+
+    // Fields deprecated by the merge
+    if !header.difficulty.is_zero() {
+        return Err(format!("non-zero difficulty {}", header.difficulty));
+    }
+    if !header.nonce.is_zero() {
+        return Err(format!("non-zero nonce {}", header.nonce));
+    }
+    if header.uncle_hash != KECCAK_EMPTY_LIST_RLP || uncle_count != 0 {
+        return Err("uncles are not allowed".into());
+    }
+
+    let bound = parent.gas_limit / GAS_LIMIT_BOUND_DIVISOR;
+    let diff = if header.gas_limit > parent.gas_limit {
+        header.gas_limit - parent.gas_limit
+    } else {
+        parent.gas_limit - header.gas_limit
+    };
+    if diff >= bound || header.gas_limit < MIN_GAS_LIMIT.into() {
+        return Err(format!("invalid gas limit {}", header.gas_limit));
+    }
+    if header.gas_used > header.gas_limit {
+        return Err(format!(
+            "gas used {} exceeds gas limit {}",
+            header.gas_used, header.gas_limit
+        ));
+    }
+
+    let expected_base_fee = calc_next_base_fee(parent);
+    if header.base_fee_per_gas != Some(expected_base_fee) {
+        return Err(format!(
+            "invalid base fee: got {:?}, expected {}",
+            header.base_fee_per_gas, expected_base_fee
+        ));
+    }
+
+    let shanghai = spec_id >= SpecId::SHANGHAI;
+    if header.withdrawals_root.is_some() != shanghai
+        || has_withdrawals != shanghai
+    {
+        return Err("unexpected presence of withdrawals".into());
+    }
+    let cancun = spec_id >= SpecId::CANCUN;
+    if header.blob_gas_used.is_some() != cancun
+        || header.excess_blob_gas.is_some() != cancun
+        || header.parent_beacon_block_root.is_some() != cancun
+    {
+        return Err("unexpected presence of blob gas fields".into());
+    }
+    if header.requests_hash.is_some() != (spec_id >= SpecId::PRAGUE) {
+        return Err("unexpected presence of requests hash".into());
+    }
+
+    Ok(())
+}
+
+/// EIP-1559 base fee of the child block.
+fn calc_next_base_fee(parent: &BlockHeader) -> U256 {
+    let base_fee = parent.base_fee_per_gas.unwrap_or_default();
+    let gas_target = parent.gas_limit / ELASTICITY_MULTIPLIER;
+    if gas_target.is_zero() || parent.gas_used == gas_target {
+        base_fee
+    } else if parent.gas_used > gas_target {
+        let delta = base_fee * (parent.gas_used - gas_target)
+            / gas_target
+            / BASE_FEE_MAX_CHANGE_DENOMINATOR;
+        base_fee + max(delta, U256::one())
+    } else {
+        let delta = base_fee * (gas_target - parent.gas_used)
+            / gas_target
+            / BASE_FEE_MAX_CHANGE_DENOMINATOR;
+        base_fee - delta
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cfx_types::{Bloom, H256};
+    use rlp::RlpStream;
+
+    const GWEI: u64 = 1_000_000_000;
+
+    fn parent_header() -> BlockHeader {
+        BlockHeader {
+            parent_hash: H256::zero(),
+            uncle_hash: KECCAK_EMPTY_LIST_RLP,
+            coinbase: Address::zero(),
+            state_root: H256::zero(),
+            transactions_trie: H256::zero(),
+            receipt_trie: H256::zero(),
+            bloom: Bloom::zero(),
+            difficulty: U256::zero(),
+            number: U256::one(),
+            gas_limit: U256::from(30_000_000),
+            gas_used: U256::from(15_000_000),
+            timestamp: U256::from(1000),
+            extra_data: Bytes::new(vec![]),
+            mix_hash: H256::zero(),
+            nonce: U64::zero(),
+            base_fee_per_gas: Some(U256::from(GWEI)),
+            withdrawals_root: Some(H256::zero()),
+            blob_gas_used: None,
+            excess_blob_gas: None,
+            parent_beacon_block_root: None,
+            requests_hash: None,
+            hash: H256::repeat_byte(1),
+        }
+    }
+
+    /// A valid child of `parent_header` in Shanghai, whose parent uses
+    /// exactly the gas target so that the base fee is unchanged.
+    fn child_header() -> BlockHeader {
+        BlockHeader {
+            parent_hash: H256::repeat_byte(1),
+            number: U256::from(2),
+            timestamp: U256::from(1012),
+            gas_used: U256::zero(),
+            hash: H256::repeat_byte(2),
+            ..parent_header()
+        }
+    }
+
+    fn validate(header: &BlockHeader) -> Result<(), String> {
+        validate_header(header, &parent_header(), 0, true, SpecId::SHANGHAI)
+    }
+
+    fn encode_header(header: &BlockHeader, nonce: &[u8]) -> Vec<u8> {
+        let mut s = RlpStream::new_list(17);
+        s.append(&header.parent_hash)
+            .append(&header.uncle_hash)
+            .append(&header.coinbase)
+            .append(&header.state_root)
+            .append(&header.transactions_trie)
+            .append(&header.receipt_trie)
+            .append(&header.bloom)
+            .append(&header.difficulty)
+            .append(&header.number)
+            .append(&header.gas_limit)
+            .append(&header.gas_used)
+            .append(&header.timestamp)
+            .append(&header.extra_data.0)
+            .append(&header.mix_hash)
+            .append(&nonce.to_vec())
+            .append(&header.base_fee_per_gas.unwrap())
+            .append(&header.withdrawals_root.unwrap());
+        s.out().to_vec()
+    }
+
+    fn encode_block(header_rlp: &[u8], withdrawal: Option<Address>) -> Vec<u8> {
+        let mut s = RlpStream::new_list(3 + withdrawal.is_some() as usize);
+        s.append_raw(header_rlp, 1);
+        s.begin_list(0);
+        s.begin_list(0);
+        if let Some(address) = withdrawal {
+            s.begin_list(1);
+            s.begin_list(4)
+                .append(&0u64)
+                .append(&0u64)
+                .append(&address)
+                .append(&GWEI);
+        }
+        s.out().to_vec()
+    }
+
+    #[test]
+    fn test_decode_block() {
+        let header_rlp = encode_header(&child_header(), &[0; 8]);
+        let address = Address::repeat_byte(3);
+        let block =
+            decode_block(&encode_block(&header_rlp, Some(address))).unwrap();
+
+        assert_eq!(block.header.hash, keccak(&header_rlp));
+        assert_eq!(block.header.number, U256::from(2));
+        assert_eq!(block.header.base_fee_per_gas, Some(U256::from(GWEI)));
+        assert_eq!(block.header.withdrawals_root, Some(H256::zero()));
+        assert_eq!(block.header.blob_gas_used, None);
+        assert!(block.transactions.is_empty());
+        assert_eq!(block.uncle_count, 0);
+        let withdrawals = block.withdrawals.unwrap();
+        assert_eq!(withdrawals.len(), 1);
+        assert_eq!(withdrawals[0].address, address);
+        assert_eq!(withdrawals[0].amount, GWEI);
+
+        let block = decode_block(&encode_block(&header_rlp, None)).unwrap();
+        assert!(block.withdrawals.is_none());
+
+        // the nonce must be 8 bytes
+        let header_rlp = encode_header(&child_header(), &[0; 7]);
+        assert!(decode_block(&encode_block(&header_rlp, None)).is_err());
+
+        // a block is a list of 3 or 4 items
+        let mut s = RlpStream::new_list(2);
+        s.append_raw(&encode_header(&child_header(), &[0; 8]), 1);
+        s.begin_list(0);
+        assert!(decode_block(&s.out()).is_err());
+    }
+
+    #[test]
+    fn test_validate_header() {
+        assert_eq!(validate(&child_header()), Ok(()));
+
+        let header = BlockHeader {
+            parent_hash: H256::repeat_byte(9),
+            ..child_header()
+        };
+        assert!(validate(&header).is_err());
+
+        let header = BlockHeader {
+            number: U256::from(3),
+            ..child_header()
+        };
+        assert!(validate(&header).is_err());
+
+        let header = BlockHeader {
+            timestamp: U256::from(1000),
+            ..child_header()
+        };
+        assert!(validate(&header).is_err());
+
+        let header = BlockHeader {
+            difficulty: U256::one(),
+            ..child_header()
+        };
+        assert!(validate(&header).is_err());
+
+        let header = BlockHeader {
+            extra_data: Bytes::new(vec![0; MAXIMUM_EXTRA_DATA_SIZE + 1]),
+            ..child_header()
+        };
+        assert!(validate(&header).is_err());
+
+        // the gas limit may change by less than 1/1024 of the parent
+        let header = BlockHeader {
+            gas_limit: U256::from(30_000_000 - 29_295),
+            ..child_header()
+        };
+        assert_eq!(validate(&header), Ok(()));
+        let header = BlockHeader {
+            gas_limit: U256::from(30_000_000 + 29_296),
+            ..child_header()
+        };
+        assert!(validate(&header).is_err());
+
+        let header = BlockHeader {
+            base_fee_per_gas: Some(U256::from(GWEI + 1)),
+            ..child_header()
+        };
+        assert!(validate(&header).is_err());
+
+        assert!(validate_header(
+            &child_header(),
+            &parent_header(),
+            1, /* uncle_count */
+            true,
+            SpecId::SHANGHAI
+        )
+        .is_err());
+        assert!(validate_header(
+            &child_header(),
+            &parent_header(),
+            0,
+            false, /* has_withdrawals */
+            SpecId::SHANGHAI
+        )
+        .is_err());
+        // the blob gas fields are required since Cancun
+        assert!(validate_header(
+            &child_header(),
+            &parent_header(),
+            0,
+            true,
+            SpecId::CANCUN
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_calc_next_base_fee() {
+        let base_fee = U256::from(GWEI);
+        assert_eq!(calc_next_base_fee(&parent_header()), base_fee);
+
+        let parent = BlockHeader {
+            gas_used: U256::from(30_000_000),
+            ..parent_header()
+        };
+        assert_eq!(calc_next_base_fee(&parent), base_fee * 9 / 8);
+
+        let parent = BlockHeader {
+            gas_used: U256::zero(),
+            ..parent_header()
+        };
+        assert_eq!(calc_next_base_fee(&parent), base_fee * 7 / 8);
+    }
+}
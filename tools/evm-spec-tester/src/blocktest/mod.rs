@@ -1,9 +1,21 @@
-use crate::util::{find_all_json_tests, make_configuration};
+mod block;
+mod unit_tester;
+
+use crate::{
+    statetest::TestError,
+    util::{contains_meta_dir, find_all_json_tests, make_configuration},
+};
 use cfx_config::Configuration;
 use clap::Args;
-use std::{path::PathBuf, sync::Arc};
+use eest_types::BlockchainTestSuite;
+use itertools::Itertools;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use unit_tester::UnitTester;
 
-/// ethereum statetest doc: https://eest.ethereum.org/main/consuming_tests/state_test/
+/// ethereum blockchain test doc: https://eest.ethereum.org/main/consuming_tests/blockchain_test/
 #[derive(Args, Debug)]
 pub struct BlockchainTestCmd {
     /// Paths to blockchain test files or directories
@@ -17,10 +29,27 @@ pub struct BlockchainTestCmd {
     /// Only run tests matching this string
     #[arg(short, long, value_name = "Matches")]
     pub(super) matches: Option<String>,
+
+    /// Write the summary and the failures in JSON format to this file
+    #[arg(long, value_name = "PATH")]
+    pub(super) report: Option<PathBuf>,
+}
+
+/// Counters of a test run.
+#[derive(Default)]
+struct Summary {
+    skipped_suite: usize,
+    load_err_suite: usize,
+    success_units: usize,
+    skipped_units: usize,
+    total_blocks: usize,
+    errors: Vec<TestError>,
 }
 
 impl BlockchainTestCmd {
+    /// Runs `blocktest` command.
     pub fn run(&self) -> bool {
+        let mut summary = Summary::default();
         for path in &self.paths {
             if !path.exists() {
                 panic!("Path not exists: {:?}", path);
@@ -33,24 +62,140 @@ impl BlockchainTestCmd {
                 continue;
             }
 
-            if let Err(_) = self.run_file_tests(test_files, path) {
-                warn!("Failed to run tests in directory: {:?}", path);
-                continue;
+            self.run_file_tests(test_files, path, &mut summary);
+        }
+
+        let success = summary.load_err_suite == 0 && summary.errors.is_empty();
+        if let Some(report) = &self.report {
+            if let Err(e) = write_report(report, &summary) {
+                warn!("Failed to write report to {:?}: {}", report, e);
             }
         }
+        print_summary(summary);
 
-        true
+        success
     }
 
     fn run_file_tests(
-        &self, test_files: Vec<PathBuf>, path: &PathBuf,
-    ) -> Result<(), String> {
+        &self, test_files: Vec<PathBuf>, path: &PathBuf, summary: &mut Summary,
+    ) {
         info!(
             "Running {} TestSuites in {}",
             test_files.len(),
             path.display()
         );
 
-        Ok(())
+        for path in test_files {
+            if contains_meta_dir(&path) {
+                summary.skipped_suite += 1;
+                continue;
+            }
+
+            let tester = match SuiteTester::load(&path, self.config.clone()) {
+                Ok(tester) => tester,
+                Err(err_msg) => {
+                    warn!(
+                        "TestSuite load failed. path: {:?}, error: {}",
+                        path, err_msg
+                    );
+                    summary.load_err_suite += 1;
+                    continue;
+                }
+            };
+            tester.run(self.matches.as_deref(), summary);
+        }
+    }
+}
+
+fn print_summary(summary: Summary) {
+    let error_units = summary.errors.len();
+
+    for (path, units) in
+        &summary.errors.into_iter().chunk_by(|err| err.path.clone())
+    {
+        println!("\nPath {path} fails:");
+        for TestError { name, kind, .. } in units {
+            println!("\t{name}: {kind}");
+        }
+    }
+
+    println!("\n\nSkipped TestSuites: {}", summary.skipped_suite);
+    println!("Load Failed TestSuites: {}", summary.load_err_suite);
+    println!("Success Units: {}", summary.success_units);
+    println!("Skipped Units: {}", summary.skipped_units);
+    println!("Error Units  : {}", error_units);
+    println!("Total Blocks: {}", summary.total_blocks);
+}
+
+fn write_report(path: &Path, summary: &Summary) -> Result<(), String> {
+    let failures: Vec<_> = summary
+        .errors
+        .iter()
+        .map(|err| {
+            serde_json::json!({
+                "path": err.path,
+                "name": err.name,
+                "error": err.kind.to_string(),
+            })
+        })
+        .collect();
+    let report = serde_json::json!({
+        "skippedSuites": summary.skipped_suite,
+        "loadFailedSuites": summary.load_err_suite,
+        "successUnits": summary.success_units,
+        "skippedUnits": summary.skipped_units,
+        "errorUnits": summary.errors.len(),
+        "totalBlocks": summary.total_blocks,
+        "failures": failures,
+    });
+    let content =
+        serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?;
+    std::fs::write(path, content).map_err(|e| e.to_string())
+}
+
+struct SuiteTester {
+    path: String,
+    suite: BlockchainTestSuite,
+    config: Arc<Configuration>,
+}
+
+impl SuiteTester {
+    pub fn load(
+        path: &PathBuf, config: Arc<Configuration>,
+    ) -> Result<Self, String> {
+        let s = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+        let suite: BlockchainTestSuite =
+            serde_json::from_str(&s).map_err(|e| e.to_string())?;
+
+        let path = path.to_string_lossy().into_owned();
+        Ok(Self {
+            path,
+            suite,
+            config,
+        })
+    }
+
+    fn run(self, matches: Option<&str>, summary: &mut Summary) {
+        if matches.is_some() {
+            trace!("Running TestSuite: {}", self.path);
+        } else {
+            debug!("Running TestSuite: {}", self.path);
+        }
+
+        for (name, unit) in self.suite.0 {
+            let unit_tester =
+                UnitTester::new(&self.path, name, unit, self.config.clone());
+            match unit_tester.run(matches) {
+                Ok(cnt) => {
+                    summary.total_blocks += cnt;
+                    if cnt > 0 {
+                        summary.success_units += 1;
+                    } else {
+                        summary.skipped_units += 1;
+                    }
+                }
+                Err(e) => summary.errors.push(e),
+            }
+        }
     }
 }
@@ -0,0 +1,459 @@
+use super::block::{decode_block, validate_header, DecodedBlock};
+use crate::{
+    state_dump::espace_state_root,
+    statetest::{
+        unit_tester::{
+            post_transact::{
+                check_post_state, distribute_tx_fee_to_miner,
+                match_fail_reason, TestOutcome,
+            },
+            pre_transact::{
+                calc_blob_gasprice, check_tx_common, make_machine_verify_conf,
+                make_state, make_transact_options, set_beacon_root_storage,
+            },
+        },
+        StateMismatch, TestError, TestErrorKind,
+    },
+};
+use cfx_config::Configuration;
+use cfx_execute_helper::observer::Observer;
+use cfx_executor::{
+    executive::{
+        ExecutionError, ExecutionOutcome, ExecutiveContext, TransactOptions,
+    },
+    machine::Machine,
+    state::State,
+};
+use cfx_statedb::StateDb;
+use cfx_types::{
+    h256_to_u256_be, AddressSpaceUtil, AllChainID, Space, SpaceMap, H256, U256,
+};
+use cfx_vm_types::Env;
+use cfxcore::verification::VerificationConfig;
+use eest_types::{
+    BlockHeader, BlockchainTestUnit, SpecId, SpecName, TestBlock,
+};
+use primitives::{transaction::TransactionError, SignedTransaction};
+use std::{collections::BTreeMap, sync::Arc};
+
+const GWEI: u64 = 1_000_000_000;
+
+/// Why a block is rejected.
+#[derive(Debug)]
+enum Rejection {
+    /// The block is malformed or its header is invalid.
+    Block(String),
+    /// A transaction fails the consensus check.
+    Consensus(TransactionError),
+    /// A transaction is not executed, or it is not allowed to be packed in
+    /// Ethereum.
+    Execution(ExecutionOutcome),
+    /// The transaction cannot be decoded or its signature is invalid.
+    Transaction(String),
+    GasAllowanceExceeded,
+    GasUsedMismatch {
+        got: U256,
+        expected: U256,
+    },
+    StateRootMismatch {
+        got: H256,
+        expected: H256,
+    },
+}
+
+impl Rejection {
+    fn matches(&self, expect_exception: &str) -> bool {
+        expect_exception.split("|").any(|reason| match self {
+            Rejection::Block(_)
+            | Rejection::GasUsedMismatch { .. }
+            | Rejection::StateRootMismatch { .. } => {
+                reason.starts_with("BlockException.")
+            }
+            Rejection::Consensus(e) => {
+                match_fail_reason(reason, TestOutcome::Consensus(e))
+            }
+            Rejection::Execution(outcome) => {
+                match_fail_reason(reason, TestOutcome::Execution(outcome))
+            }
+            Rejection::Transaction(_) => {
+                reason.starts_with("TransactionException.")
+            }
+            Rejection::GasAllowanceExceeded => {
+                reason == "TransactionException.GAS_ALLOWANCE_EXCEEDED"
+            }
+        })
+    }
+
+    /// Converts the rejection of a valid block to the test error.
+    fn into_error_kind(self, number: u64) -> TestErrorKind {
+        match self {
+            Rejection::GasUsedMismatch { got, expected } => {
+                StateMismatch::GasMismatch { got, expected }.into()
+            }
+            Rejection::StateRootMismatch { got, expected } => {
+                StateMismatch::StateRootMismatch { got, expected }.into()
+            }
+            rejection => TestErrorKind::BlockRejected {
+                number,
+                reason: format!("{:?}", rejection),
+            },
+        }
+    }
+}
+
+pub struct UnitTester {
+    path: String,
+    name: String,
+    unit: BlockchainTestUnit,
+    config: Arc<Configuration>,
+}
+
+impl UnitTester {
+    pub fn new(
+        path: &String, name: String, unit: BlockchainTestUnit,
+        config: Arc<Configuration>,
+    ) -> Self {
+        UnitTester {
+            path: path.clone(),
+            name,
+            unit,
+            config,
+        }
+    }
+
+    fn err(&self, kind: TestErrorKind) -> TestError {
+        TestError {
+            name: self.name.clone(),
+            path: self.path.clone(),
+            kind,
+        }
+    }
+
+    /// Runs the unit and returns the number of the executed blocks, which is
+    /// 0 if the unit is skipped.
+    pub fn run(&self, matches: Option<&str>) -> Result<usize, TestError> {
+        if !matches.map_or(true, |pat| {
+            format!("{}::{}", &self.path, &self.name).contains(pat)
+        }) {
+            return Ok(0);
+        }
+
+        if matches.is_some() {
+            info!("Running TestUnit: {}", self.name);
+        } else {
+            trace!("Running TestUnit: {}", self.name);
+        }
+
+        if self.is_unsupported() {
+            return Ok(0);
+        }
+
+        let spec = &self.unit.network;
+        let spec_id = spec.to_spec_id();
+        let (machine, verification) =
+            make_machine_verify_conf(self.config.clone(), spec);
+
+        // setup the genesis state
+        let genesis = &self.unit.genesis_block_header;
+        let state = make_state(&self.unit.pre);
+        state.commit(genesis.hash, None).expect("db error");
+        let got = espace_state_root(&genesis.hash);
+        if got != genesis.state_root {
+            return Err(self.err(
+                StateMismatch::StateRootMismatch {
+                    got,
+                    expected: genesis.state_root,
+                }
+                .into(),
+            ));
+        }
+
+        // the last valid block
+        let mut last_header: Option<BlockHeader> = None;
+        let mut block_cnt = 0;
+        for test_block in &self.unit.blocks {
+            let parent = last_header.as_ref().unwrap_or(genesis);
+            let (rlp, expect_exception) = match test_block {
+                TestBlock::Block(block) => (&block.rlp, None),
+                TestBlock::InvalidBlock(block) => {
+                    (&block.rlp, Some(&block.expect_exception))
+                }
+            };
+            block_cnt += 1;
+
+            let result = decode_block(&rlp.0)
+                .map_err(|e| Rejection::Block(format!("rlp: {:?}", e)))
+                .and_then(|block| {
+                    self.execute_block(
+                        block,
+                        parent,
+                        &machine,
+                        &verification,
+                        spec_id,
+                    )
+                });
+
+            match (result, expect_exception) {
+                (Ok(header), None) => {
+                    last_header = Some(header);
+                }
+                (Err(rejection), None) => {
+                    let number = parent.number.as_u64() + 1;
+                    return Err(self.err(rejection.into_error_kind(number)));
+                }
+                (Ok(_), Some(fail_reason)) => {
+                    return Err(self.err(TestErrorKind::ShouldFail {
+                        fail_reason: fail_reason.clone(),
+                    }));
+                }
+                (Err(rejection), Some(fail_reason)) => {
+                    if !rejection.matches(fail_reason) {
+                        return Err(self.err(
+                            TestErrorKind::InconsistentBlockException {
+                                expected: fail_reason.clone(),
+                                actual: format!("{:?}", rejection),
+                            },
+                        ));
+                    }
+                }
+            }
+        }
+
+        let parent = last_header.as_ref().unwrap_or(genesis);
+        if let Some(expected) = self.unit.last_block_hash {
+            if parent.hash != expected {
+                return Err(self.err(
+                    StateMismatch::LastBlockHashMismatch {
+                        got: parent.hash,
+                        expected,
+                    }
+                    .into(),
+                ));
+            }
+        }
+
+        let state =
+            State::new(StateDb::new_for_unit_test_with_epoch(&parent.hash))
+                .expect("db error");
+        check_post_state(&state, &self.unit.post_state)
+            .map_err(|kind| self.err(kind))?;
+
+        Ok(block_cnt)
+    }
+
+    /// The blob transactions (EIP-4844) and the execution layer requests
+    /// (EIP-7685) are not supported by Conflux, and the transition forks are
+    /// not supported by the tester.
+    fn is_unsupported(&self) -> bool {
+        let spec = &self.unit.network;
+        if *spec == SpecName::Unknown {
+            return true;
+        }
+        let spec_id = spec.to_spec_id();
+        if spec_id < SpecId::MERGE || spec_id > SpecId::OSAKA {
+            return true;
+        }
+
+        self.unit.blocks.iter().any(|test_block| match test_block {
+            TestBlock::Block(block) => {
+                block.transactions.iter().any(|tx| tx.tx_type.as_u64() == 3)
+            }
+            TestBlock::InvalidBlock(block) => {
+                ["BLOB", "REQUESTS", "SYSTEM_CONTRACT", "DEPOSIT"]
+                    .iter()
+                    .any(|pat| block.expect_exception.contains(pat))
+                    || block.rlp_decoded.as_ref().map_or(false, |block| {
+                        block
+                            .transactions
+                            .iter()
+                            .any(|tx| tx.tx_type.as_u64() == 3)
+                    })
+            }
+        })
+    }
+
+    /// Executes the block on the state of its parent, and commits the state
+    /// to the block hash.
+    fn execute_block(
+        &self, block: DecodedBlock, parent: &BlockHeader, machine: &Machine,
+        verification: &VerificationConfig, spec_id: SpecId,
+    ) -> Result<BlockHeader, Rejection> {
+        let DecodedBlock {
+            header,
+            transactions,
+            uncle_count,
+            withdrawals,
+        } = block;
+        validate_header(
+            &header,
+            parent,
+            uncle_count,
+            withdrawals.is_some(),
+            spec_id,
+        )
+        .map_err(Rejection::Block)?;
+
+        let mut state =
+            State::new(StateDb::new_for_unit_test_with_epoch(&parent.hash))
+                .expect("db error");
+        let mut env = self.make_block_env(machine, &header);
+        let spec = machine.spec(env.number, env.epoch_height);
+
+        // system operations at the beginning of the block
+        if spec.eip2935 {
+            state
+                .set_eip2935_storage(env.number - 1, parent.hash)
+                .expect("db error");
+        }
+        if let Some(beacon_root) = header.parent_beacon_block_root {
+            set_beacon_root_storage(&mut state, env.timestamp, beacon_root);
+        }
+
+        for transaction in transactions {
+            let public = transaction
+                .recover_public()
+                .map_err(|e| Rejection::Transaction(format!("{:?}", e)))?;
+            let tx = SignedTransaction::new(public, transaction);
+            if tx.space() != Space::Ethereum {
+                return Err(Rejection::Transaction(
+                    "not an ethereum transaction".into(),
+                ));
+            }
+
+            if env.accumulated_gas_used + *tx.gas_limit() > env.gas_limit {
+                return Err(Rejection::GasAllowanceExceeded);
+            }
+
+            env.transaction_hash = tx.hash();
+            check_tx_common(machine, &env, &tx, verification)
+                .map_err(Rejection::Consensus)?;
+
+            let options = make_transact_options(true, false, None, None);
+            let outcome = transact(machine, &env, &mut state, &tx, options);
+            let executed = match outcome {
+                // Conflux bumps the nonce of these transactions, but they are
+                // invalid in Ethereum.
+                ExecutionOutcome::ExecutionErrorBumpNonce(
+                    ExecutionError::NotEnoughCash { .. }
+                    | ExecutionError::NonceOverflow(_),
+                    _,
+                )
+                | ExecutionOutcome::NotExecutedDrop(_)
+                | ExecutionOutcome::NotExecutedToReconsiderPacking(_) => {
+                    return Err(Rejection::Execution(outcome));
+                }
+                outcome => outcome.try_into_executed().expect("executed"),
+            };
+
+            env.accumulated_gas_used += executed.gas_used;
+            distribute_tx_fee_to_miner(&mut state, &executed, &env.author);
+        }
+
+        for withdrawal in withdrawals.unwrap_or_default() {
+            state
+                .add_balance(
+                    &withdrawal.address.with_evm_space(),
+                    &(U256::from(withdrawal.amount) * GWEI),
+                )
+                .expect("db error");
+        }
+
+        if env.accumulated_gas_used != header.gas_used {
+            return Err(Rejection::GasUsedMismatch {
+                got: env.accumulated_gas_used,
+                expected: header.gas_used,
+            });
+        }
+
+        state.commit(header.hash, None).expect("db error");
+        let got = espace_state_root(&header.hash);
+        if got != header.state_root {
+            return Err(Rejection::StateRootMismatch {
+                got,
+                expected: header.state_root,
+            });
+        }
+
+        Ok(header)
+    }
+
+    fn make_block_env(&self, machine: &Machine, header: &BlockHeader) -> Env {
+        let config_chain_id: AllChainID =
+            machine.params().chain_id.read().get_chain_id(0);
+        let mut chain_id = BTreeMap::new();
+        chain_id.insert(Space::Native, config_chain_id.in_native_space());
+        chain_id.insert(Space::Ethereum, self.unit.config.chainid as u32);
+
+        let base_gas_price = header
+            .base_fee_per_gas
+            .map(|v| SpaceMap::new(v, v))
+            .unwrap_or_default();
+
+        let blob_gas = header.excess_blob_gas.unwrap_or_default().as_u64();
+
+        Env {
+            chain_id,
+            number: header.number.as_u64(),
+            author: header.coinbase,
+            timestamp: header.timestamp.as_u64(),
+            // After ETH2.0, the DIFFICULTY opcode is changed to PREVRANDAO
+            difficulty: h256_to_u256_be(header.mix_hash),
+            gas_limit: header.gas_limit,
+            last_hash: header.parent_hash,
+            accumulated_gas_used: U256::zero(),
+            base_gas_price,
+            burnt_gas_price: base_gas_price, /* to align with ethereum, all
+                                              * base gas price is burnt */
+            epoch_height: header.number.as_u64(), // set to current number
+            transaction_epoch_bound: 100000,      /* set to default
+                                                   * epoch bound */
+            blob_gas_fee: calc_blob_gasprice(blob_gas),
+            ..Default::default()
+        }
+    }
+}
+
+fn transact(
+    machine: &Machine, env: &Env, state: &mut State,
+    transaction: &SignedTransaction, options: TransactOptions<Observer>,
+) -> ExecutionOutcome {
+    let spec = machine.spec(env.number, env.epoch_height);
+
+    let evm = ExecutiveContext::new(state, env, machine, &spec);
+    let outcome = evm.transact(transaction, options).expect("db error");
+    state.update_state_post_tx_execution(false);
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rejection;
+
+    #[test]
+    fn test_rejection_matches() {
+        let rejection = Rejection::Block("invalid gas limit".into());
+        assert!(rejection.matches("BlockException.INVALID_GASLIMIT"));
+        assert!(!rejection.matches("TransactionException.NONCE_MISMATCH"));
+        assert!(rejection.matches(
+            "TransactionException.NONCE_MISMATCH|BlockException.INVALID_GASLIMIT"
+        ));
+
+        let rejection = Rejection::Transaction("invalid signature".into());
+        assert!(rejection.matches("TransactionException.TYPE_NOT_SUPPORTED"));
+        assert!(!rejection.matches("BlockException.RLP_STRUCTURES_ENCODING"));
+
+        let rejection = Rejection::GasAllowanceExceeded;
+        assert!(
+            rejection.matches("TransactionException.GAS_ALLOWANCE_EXCEEDED")
+        );
+        assert!(
+            !rejection.matches("TransactionException.INTRINSIC_GAS_TOO_LOW")
+        );
+
+        let rejection = Rejection::GasUsedMismatch {
+            got: 1.into(),
+            expected: 2.into(),
+        };
+        assert!(rejection.matches("BlockException.INCORRECT_BLOCK_FORMAT"));
+    }
+}
@@ -1,4 +1,6 @@
-use crate::{blocktest::BlockchainTestCmd, statetest::command::StateTestCmd};
+use crate::{
+    blocktest::BlockchainTestCmd, statetest::command::StateTestCmd, t8n::T8nCmd,
+};
 use clap::{Parser, Subcommand};
 use clap_verbosity_flag::{InfoLevel, Verbosity};
 
@@ -24,6 +26,9 @@ pub enum Commands {
     Statetest(StateTestCmd),
     /// Execute blockchain tests of ethereum execution spec tests
     Blocktest(BlockchainTestCmd),
+    /// Run the state transition tool (t8n) to fill ethereum execution spec
+    /// tests
+    T8n(T8nCmd),
 }
 
 impl MainCmd {
@@ -31,6 +36,7 @@ impl MainCmd {
         match self.command {
             Commands::Statetest(cmd) => cmd.run(),
             Commands::Blocktest(cmd) => cmd.run(),
+            Commands::T8n(cmd) => cmd.run(),
         }
     }
 }
@@ -0,0 +1,171 @@
+use alloy_primitives::{Address as AlloyAddress, B256, U256 as AlloyU256};
+use alloy_trie::{
+    root::{state_root_unhashed, storage_root_unhashed},
+    TrieAccount,
+};
+use cfx_executor::state::State;
+use cfx_statedb::StateDb;
+use cfx_types::{Address, AddressSpaceUtil, Space, H256, U256};
+use keccak_hash::KECCAK_EMPTY;
+use primitives::{
+    SkipInputCheck, StorageKey, StorageKeyWithSpace, StorageValue,
+};
+use std::collections::BTreeMap;
+
+/// An eSpace account read out of the state.
+pub struct AccountDump {
+    pub nonce: U256,
+    pub balance: U256,
+    pub code: Vec<u8>,
+    pub code_hash: H256,
+    /// Non-zero storage slots.
+    pub storage: BTreeMap<H256, U256>,
+}
+
+/// Reads out the eSpace accounts in the state committed to `epoch_id`. The
+/// empty accounts are left out as EIP-161 requires.
+pub fn dump_espace_accounts(epoch_id: &H256) -> BTreeMap<Address, AccountDump> {
+    let mut db = StateDb::new_for_unit_test_with_epoch(epoch_id);
+    let kvs = db
+        .read_all(StorageKey::EmptyKey.with_native_space(), None)
+        .expect("db error");
+
+    let mut storage: BTreeMap<Address, BTreeMap<H256, U256>> = BTreeMap::new();
+    for (key, value) in kvs {
+        let key = StorageKeyWithSpace::from_key_bytes::<SkipInputCheck>(&key);
+        if key.space != Space::Ethereum {
+            continue;
+        }
+        match key.key {
+            StorageKey::AccountKey(address_bytes) => {
+                storage
+                    .entry(Address::from_slice(address_bytes))
+                    .or_default();
+            }
+            StorageKey::StorageKey {
+                address_bytes,
+                storage_key,
+            } => {
+                let value: StorageValue =
+                    rlp::decode(&value).expect("valid storage value");
+                if value.value.is_zero() {
+                    continue;
+                }
+                storage
+                    .entry(Address::from_slice(address_bytes))
+                    .or_default()
+                    .insert(H256::from_slice(storage_key), value.value);
+            }
+            _ => {}
+        }
+    }
+
+    let state = State::new(db).expect("db error");
+    storage
+        .into_iter()
+        .filter_map(|(address, storage)| {
+            let address_with_space = address.with_evm_space();
+            let nonce = state.nonce(&address_with_space).expect("db error");
+            let balance = state.balance(&address_with_space).expect("db error");
+            let code_hash =
+                state.code_hash(&address_with_space).expect("db error");
+            if nonce.is_zero() && balance.is_zero() && code_hash == KECCAK_EMPTY
+            {
+                return None;
+            }
+
+            let code = state
+                .code(&address_with_space)
+                .expect("db error")
+                .map_or_else(Vec::new, |code| code.as_ref().clone());
+            let account = AccountDump {
+                nonce,
+                balance,
+                code,
+                code_hash,
+                storage,
+            };
+            Some((address, account))
+        })
+        .collect()
+}
+
+/// Computes the Ethereum state root of the accounts.
+///
+/// The state of Conflux is not organized as the Ethereum state trie, so the
+/// accounts are put into a new trie.
+pub fn state_root(accounts: &BTreeMap<Address, AccountDump>) -> H256 {
+    let accounts = accounts.iter().map(|(address, account)| {
+        let storage = account
+            .storage
+            .iter()
+            .map(|(key, value)| (B256::from(key.0), to_alloy(*value)));
+        let account = TrieAccount {
+            nonce: account.nonce.as_u64(),
+            balance: to_alloy(account.balance),
+            storage_root: storage_root_unhashed(storage),
+            code_hash: B256::from(account.code_hash.0),
+        };
+        (AlloyAddress::from(address.0), account)
+    });
+
+    H256::from(state_root_unhashed(accounts).0)
+}
+
+/// Computes the Ethereum state root of the eSpace accounts in the state
+/// committed to `epoch_id`.
+pub fn espace_state_root(epoch_id: &H256) -> H256 {
+    state_root(&dump_espace_accounts(epoch_id))
+}
+
+pub fn to_alloy(value: U256) -> AlloyU256 {
+    AlloyU256::from_be_bytes(value.to_big_endian())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::statetest::unit_tester::pre_transact::make_state;
+    use alloy_trie::EMPTY_ROOT_HASH;
+    use cfx_rpc_eth_types::Bytes;
+    use eest_types::AccountInfo;
+    use std::collections::HashMap;
+
+    fn account(balance: u64, storage: &[(u64, u64)]) -> AccountInfo {
+        AccountInfo {
+            balance: balance.into(),
+            code: Bytes::new(vec![]),
+            nonce: 0,
+            storage: storage
+                .iter()
+                .map(|(k, v)| (U256::from(*k), U256::from(*v)))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_dump_espace_accounts() {
+        let funded = Address::repeat_byte(1);
+        let mut pre = HashMap::new();
+        pre.insert(Address::repeat_byte(2), account(0, &[]));
+        pre.insert(funded, account(10, &[(1, 0), (2, 5)]));
+        let epoch_id = H256::repeat_byte(0xd1);
+        make_state(&pre).commit(epoch_id, None).expect("db error");
+
+        // the empty account and the zero slot are left out
+        let accounts = dump_espace_accounts(&epoch_id);
+        assert_eq!(accounts.len(), 1);
+        let dump = &accounts[&funded];
+        assert_eq!(dump.balance, U256::from(10));
+        assert_eq!(dump.code_hash, KECCAK_EMPTY);
+        assert_eq!(dump.storage.len(), 1);
+        assert_eq!(dump.storage[&H256::from_low_u64_be(2)], U256::from(5));
+        assert_ne!(espace_state_root(&epoch_id), H256::from(EMPTY_ROOT_HASH.0));
+
+        let mut pre = HashMap::new();
+        pre.insert(Address::repeat_byte(2), account(0, &[]));
+        let epoch_id = H256::repeat_byte(0xd2);
+        make_state(&pre).commit(epoch_id, None).expect("db error");
+        assert_eq!(espace_state_root(&epoch_id), H256::from(EMPTY_ROOT_HASH.0));
+    }
+}
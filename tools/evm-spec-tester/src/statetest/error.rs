@@ -57,6 +57,12 @@ pub enum TestErrorKind {
     InvalidPath,
     #[error("no JSON test files found in path")]
     NoJsonFiles,
+    #[error("valid block {number} rejected: {reason}")]
+    BlockRejected { number: u64, reason: String },
+    #[error(
+        "inconsistent block exception: expect: {expected}, actual: {actual}"
+    )]
+    InconsistentBlockException { expected: String, actual: String },
     #[error("internal error: {0}")]
     Internal(String),
 }
@@ -68,6 +74,8 @@ pub enum StateMismatch {
     LogsRootMismatch { got: H256, expected: H256 },
     #[error("state root mismatch: got {got}, expected {expected}")]
     StateRootMismatch { got: H256, expected: H256 },
+    #[error("last block hash mismatch: got {got}, expected {expected}")]
+    LastBlockHashMismatch { got: H256, expected: H256 },
     #[error(
         "balance mismatch: address {address}, got {got}, expected {expected}"
     )]
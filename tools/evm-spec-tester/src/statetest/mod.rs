@@ -1,9 +1,9 @@
 pub(crate) mod command;
 mod error;
-mod unit_tester;
+pub(crate) mod unit_tester;
 mod utils;
 
-pub use error::TestError;
+pub use error::{StateMismatch, TestError, TestErrorKind};
 
 use cfx_config::Configuration;
 use eest_types::StateTestSuite;
@@ -1,5 +1,5 @@
-mod post_transact;
-mod pre_transact;
+pub(crate) mod post_transact;
+pub(crate) mod pre_transact;
 
 use self::post_transact::is_unsupport_reason;
 
@@ -15,6 +15,7 @@ use cfx_executor::{
     machine::Machine,
     state::State,
 };
+use cfx_types::H256;
 use cfx_vm_types::Env;
 use cfxcore::verification::VerificationConfig;
 use eest_types::{SpecId, SpecName, StateTest, StateTestUnit};
@@ -137,6 +138,12 @@ impl UnitTester {
                 tx_gas_limit: tx.gas_limit().as_u64(),
                 block_height: env.epoch_height,
                 block_number: env.number,
+                // A state test only provides the environment of the block.
+                block_hash: H256::zero(),
+                tx_hash: tx.hash(),
+                tx_index: 0,
+                gas_price: u64::try_from(*tx.gas_price()).unwrap_or(u64::MAX),
+                block_timestamp: env.timestamp,
             })
         } else {
             None
@@ -153,21 +160,19 @@ impl UnitTester {
             self.transact(&machine, &env, &mut state, &tx, transact_options);
 
         if trace {
-            if let Some(geth_traces) = geth_traces(&outcome) {
-                match geth_traces {
-                    GethTrace::Default(v) => {
-                        for log in &v.struct_logs {
-                            trace!(
-                                "{}",
-                                serde_json::to_string(log)
-                                    .expect("struct log must be serializable")
-                            );
-                        }
+            match geth_traces(&outcome) {
+                Some(Ok(GethTrace::Default(v))) => {
+                    for log in &v.struct_logs {
+                        trace!(
+                            "{}",
+                            serde_json::to_string(log)
+                                .expect("struct log must be serializable")
+                        );
                     }
-                    _ => {}
                 }
-            } else {
-                trace!("No geth traces found");
+                Some(Ok(_)) => {}
+                Some(Err(e)) => trace!("Failed to collect geth traces: {}", e),
+                None => trace!("No geth traces found"),
             }
         }
 
@@ -80,7 +80,7 @@ pub fn process_consensus_check_fail(
     }
 }
 
-fn match_fail_reason(reason: &str, outcome: TestOutcome<'_>) -> bool {
+pub fn match_fail_reason(reason: &str, outcome: TestOutcome<'_>) -> bool {
     reason
         .split("|")
         .any(|reason| match_fail_single_reason(reason, outcome))
@@ -156,6 +156,34 @@ fn match_fail_single_reason(reason: &str, outcome: TestOutcome<'_>) -> bool {
 pub fn check_execution_outcome(
     tx: &SignedTransaction, state: &State, unit: &StateTestUnit,
     expected_state: &HashMap<Address, AccountInfo>, gas_used: U256,
+) -> Result<(), TestErrorKind> {
+    // log the gas usage
+    let sender = tx.sender();
+    if let Some(account_info) = expected_state.get(&sender.address) {
+        let expected_balance = account_info.balance;
+        let got_balance = state.balance(&sender).unwrap_or_default();
+        if got_balance != expected_balance && tx.value().is_zero() {
+            let before_balance = unit
+                .pre
+                .get(&sender.address)
+                .map(|v| v.balance)
+                .unwrap_or_default();
+            let expected_gas_used =
+                (before_balance - expected_balance) / tx.gas_price();
+            if expected_gas_used != gas_used {
+                bail!(StateMismatch::GasMismatch {
+                    got: gas_used,
+                    expected: expected_gas_used,
+                });
+            }
+        }
+    }
+
+    check_post_state(state, expected_state)
+}
+
+pub fn check_post_state(
+    state: &State, expected_state: &HashMap<Address, AccountInfo>,
 ) -> Result<(), TestErrorKind> {
     for (&addr, account_info) in expected_state {
         let user_addr = addr.with_evm_space();
@@ -164,20 +192,6 @@ pub fn check_execution_outcome(
         let expected_balance = account_info.balance;
         let got_balance = state.balance(&user_addr).unwrap_or_default();
         if got_balance != expected_balance {
-            // log the gas usage
-            if user_addr == tx.sender() && tx.value().is_zero() {
-                let before_balance =
-                    unit.pre.get(&addr).map(|v| v.balance).unwrap_or_default();
-                let expected_gas_used =
-                    (before_balance - expected_balance) / tx.gas_price();
-                if expected_gas_used != gas_used {
-                    bail!(StateMismatch::GasMismatch {
-                        got: gas_used,
-                        expected: expected_gas_used,
-                    });
-                }
-            }
-
             bail!(StateMismatch::BalanceMismatch {
                 address: user_addr.address,
                 got: got_balance,
@@ -1,5 +1,5 @@
 use super::super::error::TestErrorKind;
-use crate::util::set_cips_according_to_spec;
+use crate::util::set_transitions_according_to_spec;
 use alloy_rpc_types_trace::geth::{
     GethDebugTracingOptions, GethDefaultTracingOptions,
 };
@@ -9,14 +9,15 @@ use cfx_executor::{
     executive::{ChargeCollateral, TransactOptions, TransactSettings},
     machine::{Machine, VmFactory},
     state::State,
+    substate::Substate,
 };
 use cfx_rpc_eth_types::{
     AccountOverride, AccountStateOverrideMode, StateOverride,
 };
 use cfx_statedb::StateDb;
 use cfx_types::{
-    h256_to_u256_be, u256_to_h256_be, AllChainID, Space, SpaceMap, H256, U256,
-    U64,
+    h256_to_u256_be, u256_to_h256_be, AddressWithSpace, AllChainID, Space,
+    SpaceMap, H160, H256, U256, U64,
 };
 use cfx_vm_types::Env;
 use cfxcore::verification::{VerificationConfig, VerifyTxMode};
@@ -26,6 +27,7 @@ use eest_types::{
     TransactionParts, TransactionType, TxPartIndices,
 };
 use geth_tracer::TxExecContext;
+use hex_literal::hex;
 use primitives::{
     transaction::{
         Action, AuthorizationListItem, Eip1559Transaction, Eip155Transaction,
@@ -40,19 +42,23 @@ use std::{
     u64,
 };
 
+/// EIP-4788 beacon roots contract
+const BEACON_ROOTS_ADDRESS: AddressWithSpace = AddressWithSpace {
+    address: H160(hex!("000F3df6D732807Ef1319fB7B8bB8522d0Beac02")),
+    space: Space::Ethereum,
+};
+const HISTORY_BUFFER_LENGTH: u64 = 8191;
+
 pub fn make_machine_verify_conf(
     raw_config: Arc<Configuration>, spec: &SpecName,
 ) -> (Arc<Machine>, VerificationConfig) {
-    let mut config = raw_config.as_ref().clone();
-    set_cips_according_to_spec(&mut config, spec);
+    let mut params = raw_config.common_params();
+    set_transitions_according_to_spec(&mut params, spec);
     let machine = {
         let vm_factory = VmFactory::new(1024 * 32);
-        Arc::new(Machine::new_with_builtin(
-            config.common_params(),
-            vm_factory,
-        ))
+        Arc::new(Machine::new_with_builtin(params, vm_factory))
     };
-    let verification = config.verification_config(machine.clone());
+    let verification = raw_config.verification_config(machine.clone());
     (machine, verification)
 }
 
@@ -268,7 +274,7 @@ pub fn make_block_env(
     }
 }
 
-fn calc_blob_gasprice(excess_blob_gas: u64) -> U256 {
+pub fn calc_blob_gasprice(excess_blob_gas: u64) -> U256 {
     fn fake_exponential(factor: u64, numerator: u64, denominator: u64) -> u128 {
         assert_ne!(denominator, 0, "attempt to divide by zero");
         let factor = factor as u128;
@@ -346,3 +352,27 @@ pub fn check_tx_common(
         verify_mode,
     )
 }
+
+/// Writes the parent beacon block root as the system call of EIP-4788 does.
+pub fn set_beacon_root_storage(state: &mut State, timestamp: u64, root: H256) {
+    if state.has_no_code(&BEACON_ROOTS_ADDRESS).expect("db error") {
+        return;
+    }
+
+    let timestamp_index = timestamp % HISTORY_BUFFER_LENGTH;
+    let root_index = timestamp_index + HISTORY_BUFFER_LENGTH;
+    for (index, value) in [
+        (timestamp_index, U256::from(timestamp)),
+        (root_index, h256_to_u256_be(root)),
+    ] {
+        state
+            .set_storage(
+                &BEACON_ROOTS_ADDRESS,
+                u256_to_h256_be(index.into()).0.to_vec(),
+                value,
+                Default::default(),
+                &mut Substate::new(),
+            )
+            .expect("db error");
+    }
+}
@@ -0,0 +1,247 @@
+use cfx_types::{u256_to_h256_be, Address, H256};
+use cfxkey::{Secret, Signature};
+use eest_types::{
+    AccountInfo, SignedAuthorization, T8nAccount, T8nEnv, T8nTransaction,
+};
+use primitives::{
+    transaction::{
+        eth_transaction::eip155_signature::{
+            extract_chain_id_from_legacy_v, extract_standard_v,
+        },
+        Action, AuthorizationListItem, Eip1559Transaction, Eip155Transaction,
+        Eip2930Transaction, Eip7702Transaction, EthereumTransaction,
+    },
+    Transaction, TransactionWithSignature,
+};
+use rlp::Rlp;
+use serde_json::Value;
+use std::{collections::HashMap, io::Read, path::Path};
+
+/// The input of a state transition.
+pub struct T8nInput {
+    pub alloc: HashMap<Address, T8nAccount>,
+    pub env: T8nEnv,
+    /// The transactions to apply, or the reason why each one cannot be
+    /// built.
+    pub txs: Vec<Result<TransactionWithSignature, String>>,
+}
+
+/// Reads the input files. If any of them is `stdin`, all the inputs are read
+/// from the standard input as one object with the `alloc`, `env` and `txs`
+/// (or `txsRlp`) fields.
+pub fn read_input(
+    alloc: &Path, env: &Path, txs: &Path, chain_id: u64,
+) -> Result<T8nInput, String> {
+    let is_stdin = |path: &Path| path == Path::new("stdin");
+
+    if is_stdin(alloc) || is_stdin(env) || is_stdin(txs) {
+        let mut content = String::new();
+        std::io::stdin()
+            .read_to_string(&mut content)
+            .map_err(|e| format!("read stdin: {}", e))?;
+        let mut value: Value = serde_json::from_str(&content)
+            .map_err(|e| format!("parse stdin: {}", e))?;
+        let mut take = |key: &str| value.get_mut(key).map(Value::take);
+
+        let alloc = take("alloc").ok_or("missing alloc in stdin")?;
+        let env = take("env").ok_or("missing env in stdin")?;
+        let txs = match (take("txs"), take("txsRlp")) {
+            (_, Some(Value::String(raw))) => decode_txs_rlp(&raw)?,
+            (Some(txs), _) => build_txs(txs, chain_id)?,
+            (None, _) => vec![],
+        };
+
+        return Ok(T8nInput {
+            alloc: serde_json::from_value(alloc)
+                .map_err(|e| format!("parse alloc: {}", e))?,
+            env: serde_json::from_value(env)
+                .map_err(|e| format!("parse env: {}", e))?,
+            txs,
+        });
+    }
+
+    let txs_value: Value = read_json(txs)?;
+    let txs = if txs.extension() == Some("rlp".as_ref()) {
+        let Value::String(raw) = txs_value else {
+            return Err("rlp input must be a hex string".into());
+        };
+        decode_txs_rlp(&raw)?
+    } else {
+        build_txs(txs_value, chain_id)?
+    };
+
+    Ok(T8nInput {
+        alloc: read_json(alloc)?,
+        env: read_json(env)?,
+        txs,
+    })
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T, String> {
+    let s = std::fs::read_to_string(path)
+        .map_err(|e| format!("read {:?}: {}", path, e))?;
+    serde_json::from_str(&s).map_err(|e| format!("parse {:?}: {}", path, e))
+}
+
+pub fn to_account_info(account: T8nAccount) -> AccountInfo {
+    AccountInfo {
+        balance: account.balance,
+        code: account.code,
+        nonce: account.nonce.as_u64(),
+        storage: account.storage,
+    }
+}
+
+fn decode_txs_rlp(
+    raw: &str,
+) -> Result<Vec<Result<TransactionWithSignature, String>>, String> {
+    let bytes = hex::decode(raw.trim_start_matches("0x"))
+        .map_err(|e| format!("decode txs rlp: {}", e))?;
+    let rlp = Rlp::new(&bytes);
+    let txs = rlp
+        .iter()
+        .map(|item| item.as_val().map_err(|e| format!("rlp: {:?}", e)))
+        .collect();
+    Ok(txs)
+}
+
+fn build_txs(
+    txs: Value, chain_id: u64,
+) -> Result<Vec<Result<TransactionWithSignature, String>>, String> {
+    let txs: Vec<T8nTransaction> =
+        serde_json::from_value(txs).map_err(|e| format!("parse txs: {}", e))?;
+    Ok(txs.into_iter().map(|tx| build_tx(tx, chain_id)).collect())
+}
+
+/// Builds the transaction, and signs it if the secret key is given.
+fn build_tx(
+    tx: T8nTransaction, chain_id: u64,
+) -> Result<TransactionWithSignature, String> {
+    let tx_type = tx.tx_type.as_u64();
+    let action = match tx.to {
+        Some(to) => Action::Call(to),
+        None => Action::Create,
+    };
+    let nonce = tx.nonce;
+    let gas = tx.gas;
+    let value = tx.value;
+    let data = tx.input.0;
+    let gas_price = tx.gas_price.unwrap_or_default();
+    let max_fee_per_gas = tx.max_fee_per_gas.unwrap_or_default();
+    let max_priority_fee_per_gas =
+        tx.max_priority_fee_per_gas.unwrap_or_default();
+    let access_list = tx.access_list.unwrap_or_default();
+    let typed_chain_id = tx.chain_id.map_or(chain_id, |id| id.as_u64()) as u32;
+
+    // The chain id of a legacy transaction comes from `v` if it is signed,
+    // or from the `protected` flag if it is to be signed here.
+    let legacy_v = tx.v.map(|v| v.as_u64());
+    let legacy_chain_id = match (tx.secret_key, legacy_v) {
+        (Some(_), _) if !tx.protected.unwrap_or(true) => None,
+        (Some(_), _) => Some(typed_chain_id),
+        (None, Some(v)) => {
+            extract_chain_id_from_legacy_v(v).map(|id| id as u32)
+        }
+        (None, None) => None,
+    };
+
+    let unsigned = match tx_type {
+        0 => EthereumTransaction::Eip155(Eip155Transaction {
+            nonce,
+            gas_price,
+            gas,
+            action,
+            value,
+            data,
+            chain_id: legacy_chain_id,
+        }),
+        1 => EthereumTransaction::Eip2930(Eip2930Transaction {
+            nonce,
+            gas_price,
+            gas,
+            action,
+            value,
+            data,
+            chain_id: typed_chain_id,
+            access_list,
+        }),
+        2 => EthereumTransaction::Eip1559(Eip1559Transaction {
+            nonce,
+            max_priority_fee_per_gas,
+            max_fee_per_gas,
+            gas,
+            action,
+            value,
+            data,
+            chain_id: typed_chain_id,
+            access_list,
+        }),
+        3 => {
+            // conflux does not support EIP-4844
+            return Err("blob transaction is not supported".into());
+        }
+        4 => {
+            let Action::Call(destination) = action else {
+                return Err("set code transaction without destination".into());
+            };
+            let authorization_list = tx
+                .authorization_list
+                .unwrap_or_default()
+                .into_iter()
+                .map(|v| {
+                    let auth = SignedAuthorization::from(v);
+                    AuthorizationListItem {
+                        address: auth.inner().address,
+                        nonce: auth.inner().nonce,
+                        chain_id: auth.inner().chain_id,
+                        y_parity: auth.y_parity(),
+                        r: auth.r(),
+                        s: auth.s(),
+                    }
+                })
+                .collect();
+            EthereumTransaction::Eip7702(Eip7702Transaction {
+                nonce,
+                max_priority_fee_per_gas,
+                max_fee_per_gas,
+                gas,
+                destination,
+                value,
+                data,
+                chain_id: typed_chain_id,
+                access_list,
+                authorization_list,
+            })
+        }
+        _ => return Err(format!("unknown transaction type {}", tx_type)),
+    };
+    let unsigned = Transaction::Ethereum(unsigned);
+
+    if let Some(secret) = tx.secret_key {
+        return Ok(unsigned.sign(&Secret::from(secret)).transaction);
+    }
+
+    let (Some(v), Some(r), Some(s)) = (legacy_v, tx.r, tx.s) else {
+        return Err("missing signature".into());
+    };
+    let v = if tx_type == 0 {
+        extract_standard_v(v)
+    } else {
+        v as u8
+    };
+    let signature =
+        Signature::from_rsv(&u256_to_h256_be(r), &u256_to_h256_be(s), v);
+    Ok(unsigned.with_signature(signature))
+}
+
+/// Looks up the hash of the block `number` in the env, whose keys are in hex
+/// or decimal.
+pub fn block_hash(env: &T8nEnv, number: u64) -> Option<H256> {
+    env.block_hashes.iter().find_map(|(key, hash)| {
+        let parsed = match key.strip_prefix("0x") {
+            Some(hex) => u64::from_str_radix(hex, 16),
+            None => key.parse(),
+        };
+        (parsed == Ok(number)).then_some(*hash)
+    })
+}
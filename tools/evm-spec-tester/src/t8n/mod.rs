@@ -0,0 +1,194 @@
+mod input;
+mod output;
+mod transition;
+
+use crate::{
+    statetest::unit_tester::pre_transact::make_machine_verify_conf,
+    util::make_configuration,
+};
+use alloy_rpc_types_trace::geth::GethDefaultTracingOptions;
+use cfx_config::Configuration;
+use cfx_types::U256;
+use clap::Args;
+use eest_types::{SpecId, SpecName};
+use input::read_input;
+use output::ForkFields;
+use serde_json::{json, Value};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use transition::{TraceConfig, Transition};
+
+/// The state transition tool (t8n) in the interface of the geth `evm t8n`,
+/// which is driven by the execution spec tests to fill the fixtures.
+///
+/// doc: https://github.com/ethereum/go-ethereum/tree/master/cmd/evm
+#[derive(Args, Debug)]
+pub struct T8nCmd {
+    /// Path of the pre-state allocation, or `stdin`
+    #[arg(long = "input.alloc", default_value = "alloc.json")]
+    input_alloc: PathBuf,
+
+    /// Path of the block environment, or `stdin`
+    #[arg(long = "input.env", default_value = "env.json")]
+    input_env: PathBuf,
+
+    /// Path of the transactions in JSON, or in RLP if it ends with `.rlp`,
+    /// or `stdin`
+    #[arg(long = "input.txs", default_value = "txs.json")]
+    input_txs: PathBuf,
+
+    /// Where to write the post-state allocation, or `stdout`
+    #[arg(long = "output.alloc", default_value = "alloc.json")]
+    output_alloc: PathBuf,
+
+    /// Where to write the execution result, or `stdout`
+    #[arg(long = "output.result", default_value = "result.json")]
+    output_result: PathBuf,
+
+    /// Where to write the RLP of the included transactions, or `stdout`
+    #[arg(long = "output.body")]
+    output_body: Option<PathBuf>,
+
+    /// Directory of the output files and the traces
+    #[arg(long = "output.basedir")]
+    output_basedir: Option<PathBuf>,
+
+    /// Name of the fork, e.g. `Cancun`
+    #[arg(long = "state.fork", default_value = "Prague")]
+    fork: String,
+
+    /// Chain id of the eSpace
+    #[arg(long = "state.chainid", default_value_t = 1)]
+    chain_id: u64,
+
+    /// Block reward of the coinbase, which is not rewarded if it is negative
+    #[arg(
+        long = "state.reward",
+        default_value_t = 0,
+        allow_negative_numbers = true
+    )]
+    reward: i64,
+
+    /// Write the execution traces to `trace-{index}-{hash}.jsonl`
+    #[arg(long)]
+    trace: bool,
+
+    /// Enable the memory in the traces
+    #[arg(long = "trace.memory")]
+    trace_memory: bool,
+
+    /// Disable the stack in the traces
+    #[arg(long = "trace.nostack")]
+    trace_nostack: bool,
+
+    /// Enable the return data in the traces
+    #[arg(long = "trace.returndata")]
+    trace_returndata: bool,
+
+    /// Conflux client configuration
+    #[arg(short, long, value_parser = make_configuration, default_value = "", help = "Path to the configuration file")]
+    config: Arc<Configuration>,
+}
+
+impl T8nCmd {
+    /// Runs `t8n` command.
+    pub fn run(&self) -> bool {
+        match self.transition() {
+            Ok(()) => true,
+            Err(e) => {
+                error!("t8n failed: {}", e);
+                false
+            }
+        }
+    }
+
+    fn transition(&self) -> Result<(), String> {
+        let spec: SpecName = serde_json::from_value(json!(self.fork))
+            .map_err(|e| e.to_string())?;
+        let spec_id = spec.to_spec_id();
+        // Conflux only aligns with the forks after the merge, and the
+        // transition forks are not supported.
+        if spec == SpecName::Unknown
+            || spec_id < SpecId::MERGE
+            || spec_id > SpecId::OSAKA
+        {
+            return Err(format!("unsupported fork {}", self.fork));
+        }
+
+        let input = read_input(
+            &self.input_alloc,
+            &self.input_env,
+            &self.input_txs,
+            self.chain_id,
+        )?;
+        let fork = ForkFields {
+            difficulty: input.env.current_difficulty,
+            base_fee: input.env.current_base_fee,
+            excess_blob_gas: (spec_id >= SpecId::CANCUN)
+                .then(|| input.env.current_excess_blob_gas.unwrap_or_default()),
+            has_requests: spec_id >= SpecId::PRAGUE,
+        };
+
+        let (machine, verification) =
+            make_machine_verify_conf(self.config.clone(), &spec);
+        let trace = self.trace.then(|| {
+            let mut options = GethDefaultTracingOptions::default();
+            options.disable_storage = Some(true);
+            options.enable_memory = Some(self.trace_memory);
+            options.disable_stack = Some(self.trace_nostack);
+            options.enable_return_data = Some(self.trace_returndata);
+            TraceConfig {
+                basedir: self.output_basedir.clone().unwrap_or_default(),
+                options,
+            }
+        });
+        let transition = Transition {
+            machine,
+            verification: &verification,
+            chain_id: self.chain_id,
+            reward: (self.reward >= 0).then(|| U256::from(self.reward as u64)),
+            trace: trace.as_ref(),
+        };
+        let block = transition.apply(input);
+
+        let mut stdout = serde_json::Map::new();
+        let outputs = [
+            ("alloc", Some(&self.output_alloc), block.alloc_json()),
+            (
+                "result",
+                Some(&self.output_result),
+                block.result_json(&fork),
+            ),
+            ("body", self.output_body.as_ref(), block.body_json()),
+        ];
+        for (key, path, value) in outputs {
+            match path {
+                Some(path) if path == Path::new("stdout") => {
+                    stdout.insert(key.into(), value);
+                }
+                Some(path) => self.write_output(path, &value)?,
+                None => {}
+            }
+        }
+        if !stdout.is_empty() {
+            let content = serde_json::to_string_pretty(&Value::from(stdout))
+                .map_err(|e| e.to_string())?;
+            println!("{}", content);
+        }
+
+        Ok(())
+    }
+
+    fn write_output(&self, name: &Path, value: &Value) -> Result<(), String> {
+        let path = match &self.output_basedir {
+            Some(dir) => dir.join(name),
+            None => name.to_path_buf(),
+        };
+        let content =
+            serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+        std::fs::write(&path, content)
+            .map_err(|e| format!("write {:?}: {}", path, e))
+    }
+}
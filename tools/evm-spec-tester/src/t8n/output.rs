@@ -0,0 +1,232 @@
+use crate::state_dump::AccountDump;
+use alloy_primitives::B256;
+use alloy_trie::root::ordered_trie_root_with_encoder;
+use cfx_types::{Address, Bloom, H256, U256};
+use eest_types::Withdrawal;
+use keccak_hash::keccak;
+use primitives::{log_entry::LogEntry, SignedTransaction};
+use rlp::RlpStream;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+
+/// The receipt of an included transaction.
+pub struct T8nReceipt {
+    pub tx_type: u8,
+    pub tx_hash: H256,
+    pub tx_index: usize,
+    pub success: bool,
+    pub cumulative_gas_used: U256,
+    pub gas_used: U256,
+    pub effective_gas_price: U256,
+    pub contract_address: Option<Address>,
+    pub logs: Vec<LogEntry>,
+    pub bloom: Bloom,
+}
+
+impl T8nReceipt {
+    fn to_json(&self, first_log_index: usize) -> Value {
+        let logs: Vec<_> = self
+            .logs
+            .iter()
+            .enumerate()
+            .map(|(i, log)| {
+                json!({
+                    "address": log.address,
+                    "topics": log.topics,
+                    "data": format!("0x{}", hex::encode(&log.data)),
+                    "transactionHash": self.tx_hash,
+                    "transactionIndex": U256::from(self.tx_index),
+                    "blockHash": H256::zero(),
+                    "logIndex": U256::from(first_log_index + i),
+                    "removed": false,
+                })
+            })
+            .collect();
+
+        json!({
+            "type": format!("{:#x}", self.tx_type),
+            "root": "0x",
+            "status": if self.success { "0x1" } else { "0x0" },
+            "cumulativeGasUsed": self.cumulative_gas_used,
+            "logsBloom": self.bloom,
+            "logs": logs,
+            "transactionHash": self.tx_hash,
+            "contractAddress": self.contract_address.unwrap_or_default(),
+            "gasUsed": self.gas_used,
+            "effectiveGasPrice": self.effective_gas_price,
+            "blockHash": H256::zero(),
+            "transactionIndex": U256::from(self.tx_index),
+        })
+    }
+
+    /// Encodes the receipt as it is put in the receipt trie of Ethereum.
+    fn encode(&self, out: &mut Vec<u8>) {
+        if self.tx_type != 0 {
+            out.push(self.tx_type);
+        }
+        let mut s = RlpStream::new_list(4);
+        s.append(&(self.success as u8));
+        s.append(&self.cumulative_gas_used);
+        s.append(&self.bloom);
+        append_logs(&mut s, self.logs.iter());
+        out.extend_from_slice(&s.out());
+    }
+}
+
+/// A transaction that is not included in the block.
+pub struct Rejected {
+    pub index: usize,
+    pub error: String,
+}
+
+/// The block built by the transition.
+pub struct T8nBlock {
+    pub accounts: BTreeMap<Address, AccountDump>,
+    pub state_root: H256,
+    pub transactions: Vec<SignedTransaction>,
+    pub receipts: Vec<T8nReceipt>,
+    pub rejected: Vec<Rejected>,
+    pub gas_used: U256,
+    pub withdrawals: Option<Vec<Withdrawal>>,
+}
+
+/// Fields of the result that depend on the fork.
+pub struct ForkFields {
+    pub difficulty: Option<U256>,
+    pub base_fee: Option<U256>,
+    /// Excess blob gas, after Cancun.
+    pub excess_blob_gas: Option<U256>,
+    /// Whether the block carries the execution layer requests, after Prague.
+    pub has_requests: bool,
+}
+
+impl T8nBlock {
+    pub fn result_json(&self, fork: &ForkFields) -> Value {
+        let tx_root =
+            ordered_trie_root_with_encoder(&self.transactions, |tx, out| {
+                out.extend_from_slice(&rlp::encode(&tx.transaction.transaction))
+            });
+        let receipts_root =
+            ordered_trie_root_with_encoder(&self.receipts, |receipt, out| {
+                receipt.encode(out)
+            });
+
+        let mut s = RlpStream::new();
+        append_logs(
+            &mut s,
+            self.receipts.iter().flat_map(|receipt| &receipt.logs),
+        );
+        let logs_hash = keccak(s.out());
+
+        let mut logs_bloom = Bloom::default();
+        let mut log_index = 0;
+        let mut receipts = Vec::with_capacity(self.receipts.len());
+        for receipt in &self.receipts {
+            logs_bloom.accrue_bloom(&receipt.bloom);
+            receipts.push(receipt.to_json(log_index));
+            log_index += receipt.logs.len();
+        }
+
+        let rejected: Vec<_> = self
+            .rejected
+            .iter()
+            .map(|r| json!({ "index": r.index, "error": r.error }))
+            .collect();
+
+        let mut result = json!({
+            "stateRoot": self.state_root,
+            "txRoot": to_h256(tx_root),
+            "receiptsRoot": to_h256(receipts_root),
+            "logsHash": logs_hash,
+            "logsBloom": logs_bloom,
+            "receipts": receipts,
+            "currentDifficulty": fork.difficulty,
+            "gasUsed": self.gas_used,
+            "currentBaseFee": fork.base_fee,
+        });
+        let obj = result.as_object_mut().expect("object");
+        if !rejected.is_empty() {
+            obj.insert("rejected".into(), rejected.into());
+        }
+        if let Some(withdrawals) = &self.withdrawals {
+            let root = ordered_trie_root_with_encoder(withdrawals, |w, out| {
+                let mut s = RlpStream::new_list(4);
+                s.append(&w.index);
+                s.append(&w.validator_index);
+                s.append(&w.address);
+                s.append(&w.amount);
+                out.extend_from_slice(&s.out());
+            });
+            obj.insert("withdrawalsRoot".into(), json!(to_h256(root)));
+        }
+        if let Some(excess_blob_gas) = fork.excess_blob_gas {
+            // conflux does not support blob transactions
+            obj.insert("currentExcessBlobGas".into(), json!(excess_blob_gas));
+            obj.insert("blobGasUsed".into(), json!(U256::zero()));
+        }
+        if fork.has_requests {
+            // No request is generated, and the hash of no request is
+            // sha256("").
+            obj.insert("requests".into(), json!([]));
+            obj.insert("requestsHash".into(), json!(EMPTY_REQUESTS_HASH));
+        }
+        result
+    }
+
+    pub fn alloc_json(&self) -> Value {
+        let alloc: Map<String, Value> = self
+            .accounts
+            .iter()
+            .map(|(address, account)| {
+                let mut obj = Map::new();
+                obj.insert("balance".into(), json!(account.balance));
+                obj.insert("nonce".into(), json!(account.nonce));
+                if !account.code.is_empty() {
+                    obj.insert(
+                        "code".into(),
+                        json!(format!("0x{}", hex::encode(&account.code))),
+                    );
+                }
+                if !account.storage.is_empty() {
+                    let storage: Map<String, Value> = account
+                        .storage
+                        .iter()
+                        .map(|(k, v)| (format!("{:?}", k), json!(v)))
+                        .collect();
+                    obj.insert("storage".into(), storage.into());
+                }
+                (format!("{:?}", address), obj.into())
+            })
+            .collect();
+        alloc.into()
+    }
+
+    /// The RLP list of the included transactions in hex.
+    pub fn body_json(&self) -> Value {
+        let txs: Vec<_> =
+            self.transactions.iter().map(|tx| &tx.transaction).collect();
+        let mut s = RlpStream::new_list(txs.len());
+        for tx in txs {
+            s.append(tx);
+        }
+        json!(format!("0x{}", hex::encode(s.out())))
+    }
+}
+
+const EMPTY_REQUESTS_HASH: &str =
+    "0xe3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+fn append_logs<'a>(
+    s: &mut RlpStream, logs: impl Iterator<Item = &'a LogEntry>,
+) {
+    let logs: Vec<_> = logs.collect();
+    s.begin_list(logs.len());
+    for log in logs {
+        s.begin_list(3);
+        s.append(&log.address);
+        s.append_list(&log.topics);
+        s.append(&log.data);
+    }
+}
+
+fn to_h256(root: B256) -> H256 { H256::from(root.0) }
@@ -0,0 +1,348 @@
+use super::{
+    input::{block_hash, to_account_info, T8nInput},
+    output::{Rejected, T8nBlock, T8nReceipt},
+};
+use crate::{
+    state_dump::{dump_espace_accounts, state_root},
+    statetest::unit_tester::{
+        post_transact::distribute_tx_fee_to_miner,
+        pre_transact::{
+            calc_blob_gasprice, check_tx_common, make_state,
+            set_beacon_root_storage,
+        },
+    },
+};
+use alloy_rpc_types_trace::geth::{
+    GethDebugTracingOptions, GethDefaultTracingOptions, GethTrace,
+};
+use cfx_execute_helper::{observer::Observer, tx_outcome::geth_traces};
+use cfx_executor::{
+    executive::{
+        ChargeCollateral, ExecutionError, ExecutionOutcome, ExecutiveContext,
+        TransactOptions, TransactSettings,
+    },
+    machine::Machine,
+    state::State,
+};
+use cfx_types::{
+    contract_address::{cal_contract_address, CreateContractAddressType},
+    h256_to_u256_be, AddressSpaceUtil, AllChainID, Space, SpaceMap, H256, U256,
+};
+use cfx_vm_types::Env;
+use cfxcore::verification::VerificationConfig;
+use eest_types::T8nEnv;
+use geth_tracer::TxExecContext;
+use primitives::{
+    log_entry::build_bloom, transaction::Action, SignedTransaction,
+    TransactionWithSignature,
+};
+use std::{
+    collections::BTreeMap,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+const GWEI: u64 = 1_000_000_000;
+
+/// Where and how the execution traces are written.
+pub struct TraceConfig {
+    pub basedir: PathBuf,
+    pub options: GethDefaultTracingOptions,
+}
+
+/// Applies the transactions and the withdrawals on the pre-state.
+pub struct Transition<'a> {
+    pub machine: Arc<Machine>,
+    pub verification: &'a VerificationConfig,
+    pub chain_id: u64,
+    /// Block reward of the coinbase, which is not rewarded if it is `None`.
+    pub reward: Option<U256>,
+    pub trace: Option<&'a TraceConfig>,
+}
+
+impl Transition<'_> {
+    pub fn apply(&self, input: T8nInput) -> T8nBlock {
+        let T8nInput { alloc, env, txs } = input;
+        let alloc = alloc
+            .into_iter()
+            .map(|(address, account)| (address, to_account_info(account)))
+            .collect();
+        let mut state = make_state(&alloc);
+        let mut block_env = self.make_block_env(&env);
+        let spec = self.machine.spec(block_env.number, block_env.epoch_height);
+
+        // system operations at the beginning of the block
+        if spec.eip2935 && block_env.number > 0 {
+            state
+                .set_eip2935_storage(block_env.number - 1, block_env.last_hash)
+                .expect("db error");
+        }
+        if let Some(beacon_root) = env.parent_beacon_block_root {
+            set_beacon_root_storage(
+                &mut state,
+                block_env.timestamp,
+                beacon_root,
+            );
+        }
+
+        let mut transactions = vec![];
+        let mut receipts = vec![];
+        let mut rejected = vec![];
+        for (index, tx) in txs.into_iter().enumerate() {
+            match self.apply_tx(&mut block_env, &mut state, tx, receipts.len())
+            {
+                Ok((tx, receipt)) => {
+                    transactions.push(tx);
+                    receipts.push(receipt);
+                }
+                Err(error) => {
+                    debug!("Rejected transaction {}: {}", index, error);
+                    rejected.push(Rejected { index, error });
+                }
+            }
+        }
+
+        if let Some(reward) = self.reward {
+            state
+                .add_balance(&block_env.author.with_evm_space(), &reward)
+                .expect("db error");
+        }
+        for withdrawal in env.withdrawals.iter().flatten() {
+            state
+                .add_balance(
+                    &withdrawal.address.with_evm_space(),
+                    &(withdrawal.amount * GWEI),
+                )
+                .expect("db error");
+        }
+
+        // The post state is committed to a fake epoch to be read out.
+        let epoch_id = H256::repeat_byte(0xff);
+        state.commit(epoch_id, None).expect("db error");
+        let accounts = dump_espace_accounts(&epoch_id);
+
+        T8nBlock {
+            state_root: state_root(&accounts),
+            accounts,
+            transactions,
+            receipts,
+            rejected,
+            gas_used: block_env.accumulated_gas_used,
+            withdrawals: env.withdrawals,
+        }
+    }
+
+    /// Executes the transaction and returns its receipt, or the reason why it
+    /// is rejected.
+    fn apply_tx(
+        &self, env: &mut Env, state: &mut State,
+        tx: Result<TransactionWithSignature, String>, tx_index: usize,
+    ) -> Result<(SignedTransaction, T8nReceipt), String> {
+        let tx = tx?;
+        let public = tx
+            .recover_public()
+            .map_err(|e| format!("invalid signature: {:?}", e))?;
+        let tx = SignedTransaction::new(public, tx);
+        if tx.space() != Space::Ethereum {
+            return Err("not an ethereum transaction".into());
+        }
+        if env.accumulated_gas_used + *tx.gas_limit() > env.gas_limit {
+            return Err("gas limit reached".into());
+        }
+
+        env.transaction_hash = tx.hash();
+        check_tx_common(&self.machine, env, &tx, self.verification)
+            .map_err(|e| format!("{:?}", e))?;
+
+        // Conflux charges the sender and bumps its nonce even if it cannot
+        // afford the transaction, so such transactions are rejected before
+        // they touch the state.
+        let sender = tx.sender();
+        let balance = state.balance(&sender).expect("db error");
+        let cost = tx
+            .gas_limit()
+            .full_mul(*tx.gas_price())
+            .checked_add((*tx.value()).into());
+        if cost.map_or(true, |cost| cost > balance.into()) {
+            return Err(format!(
+                "insufficient funds for gas * price + value: address {:?} have {}",
+                sender.address, balance
+            ));
+        }
+        if state.nonce(&sender).expect("db error") == U256::from(u64::MAX) {
+            return Err("nonce has max value".into());
+        }
+
+        let outcome = self.transact(env, state, &tx, tx_index);
+        if let Some(trace) = self.trace {
+            if let Err(e) = write_trace(&trace.basedir, tx_index, &tx, &outcome)
+            {
+                warn!("Failed to write trace of {:?}: {}", tx.hash(), e);
+            }
+        }
+        let (success, executed) = match outcome {
+            ExecutionOutcome::Finished(executed) => (true, executed),
+            ExecutionOutcome::ExecutionErrorBumpNonce(
+                ExecutionError::NotEnoughCash { .. }
+                | ExecutionError::NonceOverflow(_),
+                _,
+            )
+            | ExecutionOutcome::NotExecutedDrop(_)
+            | ExecutionOutcome::NotExecutedToReconsiderPacking(_) => {
+                return Err(format!("{:?}", outcome));
+            }
+            ExecutionOutcome::ExecutionErrorBumpNonce(_, executed) => {
+                (false, executed)
+            }
+        };
+
+        env.accumulated_gas_used += executed.gas_used;
+        distribute_tx_fee_to_miner(state, &executed, &env.author);
+
+        let contract_address = match tx.action() {
+            Action::Create => Some(
+                cal_contract_address(
+                    CreateContractAddressType::FromSenderNonce,
+                    &sender.address,
+                    tx.nonce(),
+                    &[],
+                )
+                .0,
+            ),
+            Action::Call(_) => None,
+        };
+        let base_price = env.base_gas_price[Space::Ethereum];
+        let receipt = T8nReceipt {
+            tx_type: tx.type_id(),
+            tx_hash: tx.hash(),
+            tx_index,
+            success,
+            cumulative_gas_used: env.accumulated_gas_used,
+            gas_used: executed.gas_used,
+            effective_gas_price: tx.effective_gas_price(&base_price),
+            contract_address,
+            bloom: build_bloom(&executed.logs),
+            logs: executed.logs,
+        };
+        Ok((tx, receipt))
+    }
+
+    fn transact(
+        &self, env: &Env, state: &mut State, transaction: &SignedTransaction,
+        tx_index: usize,
+    ) -> ExecutionOutcome {
+        let spec = self.machine.spec(env.number, env.epoch_height);
+        let observer = match self.trace {
+            Some(trace) => {
+                let mut opts = GethDebugTracingOptions::default();
+                opts.config = trace.options.clone();
+                Observer::geth_tracer(
+                    TxExecContext {
+                        tx_gas_limit: transaction.gas_limit().as_u64(),
+                        block_height: env.epoch_height,
+                        block_number: env.number,
+                        // The block is not sealed while its transactions are
+                        // applied, so its hash is unknown.
+                        block_hash: H256::zero(),
+                        tx_hash: transaction.hash(),
+                        tx_index: tx_index as u64,
+                        gas_price: u64::try_from(*transaction.gas_price())
+                            .unwrap_or(u64::MAX),
+                        block_timestamp: env.timestamp,
+                    },
+                    self.machine.clone(),
+                    opts,
+                )
+            }
+            None => Observer::with_no_tracing(),
+        };
+        let options = TransactOptions {
+            observer,
+            settings: TransactSettings {
+                charge_collateral: ChargeCollateral::Normal,
+                charge_gas: true,
+                check_base_price: true,
+                check_epoch_bound: false,
+                forbid_eoa_with_code: true,
+            },
+        };
+
+        let evm = ExecutiveContext::new(state, env, &self.machine, &spec);
+        let outcome = evm.transact(transaction, options).expect("db error");
+        state.update_state_post_tx_execution(false);
+        outcome
+    }
+
+    fn make_block_env(&self, env: &T8nEnv) -> Env {
+        let config_chain_id: AllChainID =
+            self.machine.params().chain_id.read().get_chain_id(0);
+        let mut chain_id = BTreeMap::new();
+        chain_id.insert(Space::Native, config_chain_id.in_native_space());
+        chain_id.insert(Space::Ethereum, self.chain_id as u32);
+
+        let base_gas_price = env
+            .current_base_fee
+            .map(|v| SpaceMap::new(v, v))
+            .unwrap_or_default();
+
+        let number = env.current_number.as_u64();
+        let blob_gas = env.current_excess_blob_gas.unwrap_or_default().as_u64();
+
+        // After ETH2.0, the DIFFICULTY opcode is changed to PREVRANDAO
+        let difficulty = match env.current_random {
+            Some(random) => h256_to_u256_be(random),
+            None => env.current_difficulty.unwrap_or_default(),
+        };
+        let last_hash = number
+            .checked_sub(1)
+            .and_then(|parent| block_hash(env, parent))
+            .unwrap_or_default();
+
+        Env {
+            chain_id,
+            number,
+            author: env.current_coinbase,
+            timestamp: env.current_timestamp.as_u64(),
+            difficulty,
+            gas_limit: env.current_gas_limit,
+            last_hash,
+            accumulated_gas_used: U256::zero(),
+            base_gas_price,
+            burnt_gas_price: base_gas_price, /* to align with ethereum, all
+                                              * base gas price is burnt */
+            epoch_height: number, // set to current number
+            transaction_epoch_bound: 100000, /* set to default
+                                   * epoch bound */
+            blob_gas_fee: calc_blob_gasprice(blob_gas),
+            ..Default::default()
+        }
+    }
+}
+
+/// Writes the struct logs of the transaction to `trace-{index}-{hash}.jsonl`
+/// in the format of the geth `evm t8n --trace`.
+fn write_trace(
+    basedir: &Path, tx_index: usize, tx: &SignedTransaction,
+    outcome: &ExecutionOutcome,
+) -> Result<(), String> {
+    let frame = match geth_traces(outcome) {
+        Some(Ok(GethTrace::Default(frame))) => frame,
+        Some(Ok(_)) => return Err("unexpected trace type".into()),
+        Some(Err(e)) => return Err(e),
+        None => return Ok(()),
+    };
+
+    let path =
+        basedir.join(format!("trace-{}-{:?}.jsonl", tx_index, tx.hash()));
+    let mut file = std::fs::File::create(&path).map_err(|e| e.to_string())?;
+    for log in &frame.struct_logs {
+        let line = serde_json::to_string(log).map_err(|e| e.to_string())?;
+        writeln!(file, "{}", line).map_err(|e| e.to_string())?;
+    }
+    let summary = serde_json::json!({
+        "output": frame.return_value,
+        "gasUsed": format!("{:#x}", frame.gas),
+    });
+    writeln!(file, "{}", summary).map_err(|e| e.to_string())
+}
@@ -1,5 +1,6 @@
 use cfx_config::{Configuration, RawConfiguration};
-use eest_types::SpecName;
+use cfx_executor::spec::CommonParams;
+use eest_types::{SpecId, SpecName};
 use primitives::block_header::CIP112_TRANSITION_HEIGHT;
 use std::{
     path::{Path, PathBuf},
@@ -21,6 +22,12 @@ pub(crate) fn find_all_json_tests(path: &Path) -> Vec<PathBuf> {
     }
 }
 
+/// Check if the path matches `.meta/**`.
+pub(crate) fn contains_meta_dir(path: &Path) -> bool {
+    path.iter()
+        .any(|c| c.to_str().map_or(false, |s| s == ".meta"))
+}
+
 pub(crate) fn make_configuration(
     config_file: &str,
 ) -> Result<Arc<Configuration>, String> {
@@ -59,13 +66,39 @@ pub(crate) fn default_raw_configuration() -> RawConfiguration {
     config
 }
 
-pub(crate) fn set_cips_according_to_spec(
-    conf: &mut Configuration, spec: &SpecName,
+/// Sets the transitions of the Ethereum features according to the fork of the
+/// test. Conflux bundles these features in its own hardforks, so the heights
+/// from the configuration are overridden feature by feature.
+pub(crate) fn set_transitions_according_to_spec(
+    params: &mut CommonParams, spec: &SpecName,
 ) {
-    // set osaka opcode transition height according to spec
-    if spec >= &SpecName::Osaka {
-        conf.raw_conf.osaka_opcode_transition_height = Some(1);
-    } else {
-        conf.raw_conf.osaka_opcode_transition_height = Some(u64::MAX);
-    }
+    let spec_id = spec.to_spec_id();
+    let activate_from = |fork: SpecId| {
+        if spec_id >= fork {
+            1
+        } else {
+            u64::MAX
+        }
+    };
+
+    // London: EIP-3541 and EIP-3607, active in all the supported forks
+    params.transition_heights.cip150 = 1;
+    params.transition_heights.cip152 = 1;
+
+    // Cancun: EIP-1153, EIP-5656 and EIP-6780
+    params.transition_numbers.cancun_opcodes = activate_from(SpecId::CANCUN);
+    params.transition_heights.cip151 = activate_from(SpecId::CANCUN);
+    params.transition_heights.cip154 = activate_from(SpecId::CANCUN);
+
+    // Prague: EIP-7702, EIP-2537, EIP-2935 and EIP-7623
+    let prague = activate_from(SpecId::PRAGUE);
+    params.transition_heights.cip7702 = prague;
+    params.transition_heights.eip2537 = prague;
+    params.transition_heights.eip2935 = prague;
+    params.transition_heights.eip7623 = prague;
+
+    // Osaka: EIP-7939 and EIP-7951
+    let osaka = activate_from(SpecId::OSAKA);
+    params.transition_heights.cip166 = osaka;
+    params.transition_heights.cip167 = osaka;
 }